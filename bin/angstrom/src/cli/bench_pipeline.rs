@@ -0,0 +1,261 @@
+//! Capacity planning benchmark of the block building pipeline. Drives synthetic
+//! standing orders through the stateless validation checks, pool insertion,
+//! matching and bundle encoding on a single machine. Chain state is mocked:
+//! every order is taken to be funded and approved, and orders are unsigned,
+//! so signature recovery isn't part of the measured validation.
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH}
+};
+
+use alloy::primitives::{aliases::U40, Address, FixedBytes};
+use angstrom_types::{
+    consensus::{PreProposal, Proposal},
+    contract_payloads::angstrom::AngstromBundle,
+    matching::{
+        uniswap::{LiqRange, PoolSnapshot},
+        Ray, SqrtPriceX96
+    },
+    primitive::{PeerId, PoolId},
+    sol_bindings::{
+        grouped_orders::{AllOrders, GroupedUserOrder, GroupedVanillaOrder, StandingVariants},
+        rpc_orders::{ExactStandingOrder, OrderMeta}
+    }
+};
+use matching_engine::{
+    strategy::{MatchingStrategy, SimpleCheckpointStrategy},
+    MatchingManager
+};
+use order_pool::{order_storage::OrderStorage, PoolConfig};
+use pade::PadeEncode;
+use secp256k1::{
+    rand::{thread_rng, Rng},
+    SecretKey
+};
+use validation::order::{
+    stages::StaticChecksStage,
+    state::{account::StorageWithData, pools::UserOrderPoolInfo}
+};
+
+/// How far limit prices are spread around the center price of a pool.
+const PRICE_SPREAD: f64 = 0.05;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct BenchPipelineArgs {
+    /// number of blocks to build
+    #[clap(long, default_value = "20")]
+    pub blocks:          u64,
+    /// number of pools the orders are spread over
+    #[clap(long, default_value = "4")]
+    pub pools:           usize,
+    /// orders submitted per pool and block, half of them bids
+    #[clap(long, default_value = "500")]
+    pub orders_per_pool: usize
+}
+
+/// Latencies recorded for a single stage of the pipeline.
+#[derive(Debug, Default)]
+struct StageTimings {
+    samples: Vec<Duration>,
+    total:   Duration
+}
+
+impl StageTimings {
+    fn record(&mut self, elapsed: Duration) {
+        self.samples.push(elapsed);
+        self.total += elapsed;
+    }
+
+    fn percentile(sorted: &[Duration], p: f64) -> Duration {
+        if sorted.is_empty() {
+            return Duration::ZERO
+        }
+        let idx = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+        sorted[idx]
+    }
+
+    fn report(&mut self, stage: &str) {
+        self.samples.sort_unstable();
+        println!(
+            "{stage:<12} samples={:<8} total={:>10.2?} p50={:>10.2?} p90={:>10.2?} p99={:>10.2?} \
+             max={:>10.2?}",
+            self.samples.len(),
+            self.total,
+            Self::percentile(&self.samples, 0.50),
+            Self::percentile(&self.samples, 0.90),
+            Self::percentile(&self.samples, 0.99),
+            self.samples.last().copied().unwrap_or_default()
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+struct PipelineReport {
+    validation:   StageTimings,
+    insertion:    StageTimings,
+    matching:     StageTimings,
+    bundle:       StageTimings,
+    orders:       usize,
+    rejected:     usize,
+    bundle_bytes: usize
+}
+
+/// A synthetic pool along with the tokens and snapshot the bundle is built
+/// against.
+struct BenchPool {
+    id:       PoolId,
+    token0:   Address,
+    token1:   Address,
+    snapshot: PoolSnapshot
+}
+
+pub fn run(args: BenchPipelineArgs) -> eyre::Result<()> {
+    let pools = (0..args.pools)
+        .map(|_| bench_pool())
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let bundle_pools = pools
+        .iter()
+        .enumerate()
+        .map(|(store_index, pool)| {
+            (pool.id, (pool.token0, pool.token1, pool.snapshot.clone(), store_index as u16))
+        })
+        .collect::<HashMap<_, _>>();
+
+    // the pre-proposals and proposals are never broadcast, so any key will do
+    let sk = SecretKey::new(&mut thread_rng());
+    let static_checks = StaticChecksStage::default();
+    let mut report = PipelineReport::default();
+
+    for block in 1..=args.blocks {
+        let orders = pools
+            .iter()
+            .flat_map(|pool| generate_orders(pool, block, args.orders_per_pool))
+            .collect::<Vec<_>>();
+        report.orders += orders.len();
+
+        let storage = OrderStorage::new(&PoolConfig {
+            ids: pools.iter().map(|pool| pool.id).collect(),
+            ..Default::default()
+        });
+        for (order, pool_info) in orders {
+            let start = Instant::now();
+            let validated = static_checks
+                .check(&AllOrders::from(order.clone()))
+                .map(|_| {
+                    order.into_order_storage_with_data(block, true, true, true, pool_info, vec![])
+                });
+            report.validation.record(start.elapsed());
+
+            let Ok(validated) = validated else {
+                report.rejected += 1;
+                continue
+            };
+
+            let start = Instant::now();
+            let inserted = storage.add_new_limit_order(
+                validated.try_map_inner(|order| Ok(GroupedUserOrder::Vanilla(order)))?
+            );
+            report.insertion.record(start.elapsed());
+            if inserted.is_err() {
+                report.rejected += 1;
+            }
+        }
+
+        let start = Instant::now();
+        let book = storage.get_all_orders();
+        let preproposal =
+            PreProposal::generate_pre_proposal(block, PeerId::default(), book.limit, vec![], &sk);
+        let solutions =
+            MatchingManager::build_books(std::slice::from_ref(&preproposal), Default::default())
+                .iter()
+                .filter_map(|book| SimpleCheckpointStrategy::run(book).map(|s| s.solution(None)))
+                .collect::<Vec<_>>();
+        report.matching.record(start.elapsed());
+
+        let start = Instant::now();
+        let proposal = Proposal::generate_proposal(
+            block,
+            PeerId::default(),
+            vec![preproposal],
+            solutions,
+            &sk
+        );
+        let encoded = AngstromBundle::from_proposal(&proposal, &bundle_pools, Default::default())?
+            .pade_encode();
+        report.bundle.record(start.elapsed());
+        report.bundle_bytes += encoded.len();
+    }
+
+    let elapsed = report.validation.total
+        + report.insertion.total
+        + report.matching.total
+        + report.bundle.total;
+    println!(
+        "blocks={} orders={} rejected={} bundle_bytes={} orders_per_sec={:.0}",
+        args.blocks,
+        report.orders,
+        report.rejected,
+        report.bundle_bytes,
+        report.orders as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    report.validation.report("validation");
+    report.insertion.report("insertion");
+    report.matching.report("matching");
+    report.bundle.report("bundle");
+
+    Ok(())
+}
+
+fn bench_pool() -> eyre::Result<BenchPool> {
+    let mut rng = thread_rng();
+    let (a, b): (Address, Address) = (rng.gen::<[u8; 20]>().into(), rng.gen::<[u8; 20]>().into());
+    let (token0, token1) = if a < b { (a, b) } else { (b, a) };
+    let snapshot = PoolSnapshot::new(
+        vec![LiqRange::new(-6000, 6000, 1_000_000_000_000_000_000)?],
+        SqrtPriceX96::at_tick(0)?
+    )?;
+
+    Ok(BenchPool { id: FixedBytes::from(rng.gen::<[u8; 32]>()), token0, token1, snapshot })
+}
+
+/// Standing orders of distinct users, with prices spread around `1` so that
+/// the books cross.
+fn generate_orders(
+    pool: &BenchPool,
+    block: u64,
+    count: usize
+) -> Vec<(GroupedVanillaOrder, UserOrderPoolInfo)> {
+    let mut rng = thread_rng();
+    let deadline = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 60 * 60;
+
+    (0..count)
+        .map(|i| {
+            let is_bid = i % 2 == 0;
+            let (asset_in, asset_out) =
+                if is_bid { (pool.token1, pool.token0) } else { (pool.token0, pool.token1) };
+            let price = 1.0 + rng.gen_range(-PRICE_SPREAD..PRICE_SPREAD);
+            let order = ExactStandingOrder {
+                exactIn: true,
+                amount: rng.gen_range(1_000_000..1_000_000_000),
+                minPrice: Ray::from(price).into(),
+                assetIn: asset_in,
+                assetOut: asset_out,
+                nonce: block,
+                deadline: U40::from(deadline),
+                meta: OrderMeta {
+                    isEcdsa:   true,
+                    from:      rng.gen::<[u8; 20]>().into(),
+                    signature: Default::default()
+                },
+                ..Default::default()
+            };
+            let pool_info = UserOrderPoolInfo { token: asset_in, is_bid, pool_id: pool.id };
+
+            (GroupedVanillaOrder::Standing(StandingVariants::Exact(order)), pool_info)
+        })
+        .collect()
+}
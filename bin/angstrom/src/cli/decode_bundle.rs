@@ -0,0 +1,233 @@
+//! Decodes the calldata of `Angstrom::execute` back into the bundle and prints
+//! it, optionally checking that it is consistent with itself.
+use std::collections::HashMap;
+
+use alloy::{hex, sol_types::SolCall};
+use angstrom_types::{
+    contract_payloads::angstrom::{AngstromBundle, OrderQuantities},
+    sol_bindings::sol::AngstromContract
+};
+use pade::PadeDecode;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct DecodeBundleArgs {
+    /// hex calldata of `Angstrom::execute`
+    pub calldata: String,
+    /// checks the bundle is consistent with itself, failing if it isn't
+    #[clap(long)]
+    pub check:    bool
+}
+
+pub fn run(args: DecodeBundleArgs) -> eyre::Result<()> {
+    let bundle = decode(&args.calldata)?;
+    print_bundle(&bundle);
+
+    if args.check {
+        let issues = consistency_issues(&bundle);
+        if !issues.is_empty() {
+            println!("\ninconsistencies:");
+            issues.iter().for_each(|issue| println!("  {issue}"));
+            eyre::bail!("bundle has {} inconsistencies", issues.len())
+        }
+        println!("\nbundle is consistent");
+    }
+
+    Ok(())
+}
+
+fn decode(calldata: &str) -> eyre::Result<AngstromBundle> {
+    let calldata = hex::decode(calldata.trim())?;
+    let data = AngstromContract::executeCall::abi_decode(&calldata, true)?.data;
+
+    let mut buf = data.as_ref();
+    let bundle = AngstromBundle::pade_decode(&mut buf, None)
+        .map_err(|_| eyre::eyre!("calldata isn't a PADE encoded bundle"))?;
+    if !buf.is_empty() {
+        eyre::bail!("{} trailing bytes after the bundle", buf.len())
+    }
+
+    Ok(bundle)
+}
+
+fn print_bundle(bundle: &AngstromBundle) {
+    println!("bundle {}", bundle.bundle_hash());
+
+    println!("\nassets ({}):", bundle.assets.len());
+    for (i, asset) in bundle.assets.iter().enumerate() {
+        println!(
+            "  [{i}] {} borrow: {} save: {} settle: {}",
+            asset.addr, asset.borrow, asset.save, asset.settle
+        );
+    }
+
+    println!("\npairs ({}):", bundle.pairs.len());
+    for (i, pair) in bundle.pairs.iter().enumerate() {
+        println!(
+            "  [{i}] assets {}/{} store index: {} price 1/0: {}",
+            pair.index0, pair.index1, pair.store_index, pair.price_1over0
+        );
+    }
+
+    println!("\npool updates ({}):", bundle.pool_updates.len());
+    for update in &bundle.pool_updates {
+        println!(
+            "  pair {} zero for one: {} swap in: {} rewards: {:?}",
+            update.pair_index, update.zero_for_one, update.swap_in_quantity, update.rewards_update
+        );
+    }
+
+    println!("\ntop of block orders ({}):", bundle.top_of_block_orders.len());
+    for order in &bundle.top_of_block_orders {
+        println!(
+            "  {} asset {} -> {} in: {} out: {} internal: {} recipient: {:?}",
+            order.order_hash(),
+            order.asset_in_index,
+            order.asset_out_index,
+            order.quantity_in,
+            order.quantity_out,
+            order.use_internal,
+            order.recipient
+        );
+    }
+
+    println!("\nuser orders ({}):", bundle.user_orders.len());
+    for order in &bundle.user_orders {
+        println!(
+            "  {} pair {} a to b: {} exact in: {} min price: {} {:?} internal: {} standing: {:?}",
+            order.order_hash(),
+            order.pair_index,
+            order.a_to_b,
+            order.exact_in,
+            order.min_price,
+            order.order_quantities,
+            order.use_internal,
+            order.standing_validation
+        );
+    }
+}
+
+/// Everything in the bundle the contract would reject or that can't have come
+/// out of a balanced settlement. Swap outputs aren't part of the bundle, so
+/// the asset balances are only checked against the swap inputs.
+fn consistency_issues(bundle: &AngstromBundle) -> Vec<String> {
+    let mut issues = Vec::new();
+    let assets = bundle.assets.len();
+    let pairs = bundle.pairs.len();
+
+    if !bundle.assets.windows(2).all(|w| w[0].addr < w[1].addr) {
+        issues.push("assets aren't sorted by address without duplicates".to_string());
+    }
+    for (i, pair) in bundle.pairs.iter().enumerate() {
+        if pair.index0 as usize >= assets || pair.index1 as usize >= assets {
+            issues.push(format!("pair {i} refers to an asset out of range"));
+        }
+        if pair.index0 >= pair.index1 {
+            issues.push(format!("pair {i} assets aren't in ascending order"));
+        }
+    }
+
+    if !bundle
+        .pool_updates
+        .windows(2)
+        .all(|w| w[0].pair_index < w[1].pair_index)
+    {
+        issues.push("pool updates aren't sorted by pair without duplicates".to_string());
+    }
+    // what the swaps pay into the pools has to be settled with them
+    let mut swapped_in: HashMap<usize, u128> = HashMap::new();
+    for update in &bundle.pool_updates {
+        let Some(pair) = bundle.pairs.get(update.pair_index as usize) else {
+            issues.push(format!("pool update refers to pair {} out of range", update.pair_index));
+            continue
+        };
+        let asset_in = if update.zero_for_one { pair.index0 } else { pair.index1 };
+        let total = swapped_in.entry(asset_in as usize).or_default();
+        *total = total.saturating_add(update.swap_in_quantity);
+    }
+    for (i, asset) in bundle.assets.iter().enumerate() {
+        let swapped_in = swapped_in.get(&i).copied().unwrap_or_default();
+        if swapped_in > asset.settle {
+            issues.push(format!(
+                "asset {i} settles {} with the pools but the swaps pay in {swapped_in}",
+                asset.settle
+            ));
+        } else if asset.settle - swapped_in > asset.borrow {
+            issues.push(format!(
+                "asset {i} repays {} more than it borrows",
+                asset.settle - swapped_in - asset.borrow
+            ));
+        }
+    }
+
+    for order in &bundle.top_of_block_orders {
+        let hash = order.order_hash();
+        if order.asset_in_index as usize >= assets || order.asset_out_index as usize >= assets {
+            issues.push(format!("top of block order {hash} refers to an asset out of range"));
+        }
+        if order.asset_in_index == order.asset_out_index {
+            issues.push(format!("top of block order {hash} swaps an asset for itself"));
+        }
+    }
+
+    for order in &bundle.user_orders {
+        let hash = order.order_hash();
+        if order.pair_index as usize >= pairs {
+            issues.push(format!(
+                "user order {hash} refers to pair {} out of range",
+                order.pair_index
+            ));
+        }
+        if let OrderQuantities::Partial { min_quantity_in, max_quantity_in, filled_quantity } =
+            order.order_quantities
+        {
+            if min_quantity_in > max_quantity_in || filled_quantity > max_quantity_in {
+                issues.push(format!(
+                    "user order {hash} fills {filled_quantity} outside of \
+                     {min_quantity_in}..={max_quantity_in}"
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, U256};
+    use angstrom_types::contract_payloads::{rewards::PoolUpdate, Asset, Pair};
+
+    use super::*;
+
+    fn asset(byte: u8, borrow: u128, settle: u128) -> Asset {
+        Asset { addr: Address::with_last_byte(byte), borrow, save: 0, settle }
+    }
+
+    #[test]
+    fn decodes_and_checks_the_calldata() {
+        let mut bundle = AngstromBundle::new(
+            vec![asset(1, 0, 100), asset(2, 90, 0)],
+            vec![Pair {
+                index0:       0,
+                index1:       1,
+                store_index:  0,
+                price_1over0: U256::from(1)
+            }],
+            vec![PoolUpdate {
+                zero_for_one:     true,
+                pair_index:       0,
+                swap_in_quantity: 100,
+                rewards_update:   Default::default()
+            }],
+            vec![],
+            vec![]
+        );
+        let calldata = hex::encode_prefixed(bundle.execute_calldata());
+        assert_eq!(decode(&calldata).unwrap(), bundle);
+        assert!(consistency_issues(&bundle).is_empty());
+
+        bundle.pairs[0].index1 = 2;
+        bundle.pool_updates[0].swap_in_quantity = 150;
+        assert_eq!(consistency_issues(&bundle).len(), 2);
+    }
+}
@@ -0,0 +1,290 @@
+//! Deploys the angstrom contracts to a new environment: the Uniswap pool
+//! manager, Angstrom at a mined CREATE2 address carrying its hook flags, the
+//! pool gate and mock tokens. The configured pools are initialized and the
+//! resulting addresses are written to a manifest the node config can be built
+//! from.
+use std::{collections::HashSet, path::PathBuf, str::FromStr};
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::{
+        address,
+        aliases::{I24, U24},
+        keccak256, Address, Bytes, B256, U160, U256
+    },
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+    sol_types::SolValue
+};
+use angstrom_types::{
+    contract_bindings::{
+        angstrom::Angstrom, mintable_mock_erc_20::MintableMockERC20, pool_gate::PoolGate,
+        pool_manager::PoolManager
+    },
+    primitive::{PoolId, PoolKey}
+};
+use serde::{Deserialize, Serialize};
+
+pub mod angstrom;
+pub mod uniswap_flags;
+
+pub const DEFAULT_CREATE2_FACTORY: Address = address!("4e59b44847b379578588920cA78FbF26c0B4956C");
+
+/// Fee of every angstrom pool, matches `POOL_FEE` of the contract.
+const POOL_FEE: u32 = 0;
+
+/// Attempt to find a target address that includes the appropriate flags
+/// Returns the address found and the salt needed to pad the initcode to
+/// deploy to that address
+pub fn mine_address(flags: U160, mask: U160, initcode: &Bytes) -> (Address, U256) {
+    mine_address_with_factory(DEFAULT_CREATE2_FACTORY, flags, mask, initcode)
+}
+
+pub fn mine_address_with_factory(
+    factory: Address,
+    flags: U160,
+    mask: U160,
+    initcode: &Bytes
+) -> (Address, U256) {
+    let init_code_hash = keccak256(initcode);
+    let mut salt = U256::ZERO;
+    let mut counter: u128 = 0;
+    loop {
+        let target_address: Address = factory.create2(B256::from(salt), init_code_hash);
+        let u_address: U160 = target_address.into();
+        if (u_address & mask) == flags {
+            break;
+        }
+        salt += U256::from(1_u8);
+        counter += 1;
+        if counter > 100_000 {
+            panic!("We tried this too many times!")
+        }
+    }
+    let final_address = factory.create2(B256::from(salt), init_code_hash);
+    (final_address, salt)
+}
+
+/// An asset of a pool, either an address or `mock<i>` for the i-th deployed
+/// mock token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployAsset {
+    Address(Address),
+    Mock(usize)
+}
+
+impl DeployAsset {
+    fn resolve(&self, mock_tokens: &[Address]) -> eyre::Result<Address> {
+        match self {
+            Self::Address(address) => Ok(*address),
+            Self::Mock(i) => mock_tokens
+                .get(*i)
+                .copied()
+                .ok_or_else(|| eyre::eyre!("only {} mock tokens are deployed", mock_tokens.len()))
+        }
+    }
+}
+
+impl FromStr for DeployAsset {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("mock") {
+            Some(i) => Ok(Self::Mock(i.parse()?)),
+            None => Ok(Self::Address(s.parse()?))
+        }
+    }
+}
+
+/// A pool to configure and initialize, given as
+/// `<ASSET_A>:<ASSET_B>:<TICK_SPACING>:<FEE_E6>:<SQRT_PRICE_X96>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployPool {
+    pub asset_a:        DeployAsset,
+    pub asset_b:        DeployAsset,
+    pub tick_spacing:   u16,
+    pub fee_e6:         u32,
+    pub sqrt_price_x96: U160
+}
+
+impl FromStr for DeployPool {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [asset_a, asset_b, tick_spacing, fee_e6, sqrt_price_x96] =
+            s.split(':').collect::<Vec<_>>().try_into().map_err(|_| {
+                eyre::eyre!(
+                    "expected <ASSET_A>:<ASSET_B>:<TICK_SPACING>:<FEE_E6>:<SQRT_PRICE_X96>, got \
+                     {s}"
+                )
+            })?;
+
+        Ok(Self {
+            asset_a:        asset_a.parse()?,
+            asset_b:        asset_b.parse()?,
+            tick_spacing:   tick_spacing.parse()?,
+            fee_e6:         fee_e6.parse()?,
+            sqrt_price_x96: sqrt_price_x96.parse()?
+        })
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct DeployArgs {
+    #[clap(long, default_value = "http://localhost:8545")]
+    pub rpc_url:     String,
+    /// hex encoded key of the deployer, which becomes the controller of the
+    /// contract
+    #[clap(long)]
+    pub private_key: String,
+    /// receiver of the protocol fees, defaults to the deployer
+    #[clap(long)]
+    pub fee_master:  Option<Address>,
+    /// amount of mock tokens to deploy, pools refer to them as `mock<i>`
+    #[clap(long, default_value = "2")]
+    pub mock_tokens: usize,
+    /// the pools to configure and initialize, as
+    /// `<ASSET_A>:<ASSET_B>:<TICK_SPACING>:<FEE_E6>:<SQRT_PRICE_X96>`
+    #[clap(long = "pool")]
+    pub pools:       Vec<DeployPool>,
+    /// file the deployments manifest is written to
+    #[clap(long, default_value = "deployments.json")]
+    pub out:         PathBuf
+}
+
+/// A deployed pool. Has the same fields as the pools of the node config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployedPool {
+    pub token0:       Address,
+    pub token1:       Address,
+    pub pool_id:      PoolId,
+    pub store_index:  u16,
+    pub tick_spacing: u16
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentManifest {
+    pub chain_id:     u64,
+    pub controller:   Address,
+    pub pool_manager: Address,
+    pub pool_gate:    Address,
+    pub angstrom:     Address,
+    pub mock_tokens:  Vec<Address>,
+    pub pools:        Vec<DeployedPool>
+}
+
+pub fn run(args: DeployArgs) -> eyre::Result<()> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(deploy(args))
+}
+
+async fn deploy(args: DeployArgs) -> eyre::Result<()> {
+    let signer: PrivateKeySigner = args.private_key.parse()?;
+    let controller = signer.address();
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::new(signer))
+        .on_builtin(&args.rpc_url)
+        .await?;
+    let chain_id = provider.get_chain_id().await?;
+
+    let pool_manager = *PoolManager::deploy(&provider).await?.address();
+    println!("deployed pool manager at {pool_manager}");
+
+    let fee_master = args.fee_master.unwrap_or(controller);
+    let angstrom = angstrom::deploy_angstrom(&provider, pool_manager, controller, fee_master).await;
+    println!("deployed angstrom at {angstrom}");
+
+    let pool_gate = PoolGate::deploy(&provider, pool_manager).await?;
+    pool_gate.setHook(angstrom).send().await?.watch().await?;
+    println!("deployed pool gate at {}", pool_gate.address());
+
+    let mut mock_tokens = Vec::with_capacity(args.mock_tokens);
+    for _ in 0..args.mock_tokens {
+        let token = *MintableMockERC20::deploy(&provider).await?.address();
+        println!("deployed mock token at {token}");
+        mock_tokens.push(token);
+    }
+
+    let contract = Angstrom::new(angstrom, &provider);
+    let mut pairs = HashSet::new();
+    let mut pools = Vec::with_capacity(args.pools.len());
+    for (store_index, pool) in args.pools.iter().enumerate() {
+        let asset_a = pool.asset_a.resolve(&mock_tokens)?;
+        let asset_b = pool.asset_b.resolve(&mock_tokens)?;
+        let (token0, token1) =
+            if asset_a < asset_b { (asset_a, asset_b) } else { (asset_b, asset_a) };
+        // configuring a pair again replaces it in the store, which would shift the
+        // store indexes of the pools
+        if !pairs.insert((token0, token1)) {
+            return Err(eyre::eyre!("pool {token0}/{token1} is configured twice"))
+        }
+
+        contract
+            .configurePool(token0, token1, pool.tick_spacing, U24::from(pool.fee_e6))
+            .send()
+            .await?
+            .watch()
+            .await?;
+        contract
+            .initializePool(token0, token1, U256::from(store_index), pool.sqrt_price_x96)
+            .send()
+            .await?
+            .watch()
+            .await?;
+
+        let pool_key = PoolKey {
+            currency0:   token0,
+            currency1:   token1,
+            fee:         U24::from(POOL_FEE),
+            tickSpacing: I24::try_from(pool.tick_spacing)?,
+            hooks:       angstrom
+        };
+        let pool_id = keccak256(pool_key.abi_encode());
+        println!("initialized pool {pool_id} of {token0}/{token1}");
+
+        pools.push(DeployedPool {
+            token0,
+            token1,
+            pool_id,
+            store_index: store_index as u16,
+            tick_spacing: pool.tick_spacing
+        });
+    }
+
+    let manifest = DeploymentManifest {
+        chain_id,
+        controller,
+        pool_manager,
+        pool_gate: *pool_gate.address(),
+        angstrom,
+        mock_tokens,
+        pools
+    };
+    serde_json::to_writer_pretty(std::fs::File::create(&args.out)?, &manifest)?;
+    println!("wrote deployments manifest to {}", args.out.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pools() {
+        let pool: DeployPool = "mock0:0x0000000000000000000000000000000000000001:60:3000:\
+                                79228162514264337593543950336"
+            .parse()
+            .unwrap();
+        assert_eq!(pool.asset_a, DeployAsset::Mock(0));
+        assert_eq!(pool.asset_b, DeployAsset::Address(Address::with_last_byte(1)));
+        assert_eq!(pool.tick_spacing, 60);
+        assert_eq!(pool.sqrt_price_x96, U160::from(1) << 96);
+
+        assert!("mock0:mock1:60".parse::<DeployPool>().is_err());
+        assert!(DeployAsset::Mock(2).resolve(&[Address::ZERO]).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Offline reproduction of the block building pipeline. Loads recorded
+//! pre-proposals, rebuilds the pool snapshots at the given block, runs matching
+//! and bundle construction and simulates the resulting bundle.
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+
+use alloy::{
+    primitives::{Address, BlockNumber, FixedBytes},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{TransactionInput, TransactionRequest}
+};
+use angstrom_types::{
+    consensus::{PreProposal, Proposal},
+    contract_payloads::angstrom::AngstromBundle,
+    matching::uniswap::PoolSnapshot,
+    orders::PriceLevelPriority,
+    primitive::PeerId
+};
+use matching_engine::{cfmm::uniswap::pool::EnhancedUniswapV3Pool, MatchingManager};
+use secp256k1::{rand::thread_rng, SecretKey};
+
+/// A pool to rebuild the snapshot of, given as `<POOL_ID>=<UNISWAP_POOL>`
+#[derive(Debug, Clone)]
+pub struct DryRunPool {
+    pub pool_id: FixedBytes<32>,
+    pub address: Address
+}
+
+impl FromStr for DryRunPool {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pool_id, address) = s
+            .split_once('=')
+            .ok_or_else(|| eyre::eyre!("expected <POOL_ID>=<UNISWAP_POOL>, got {s}"))?;
+
+        Ok(Self { pool_id: pool_id.parse()?, address: address.parse()? })
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct DryRunArgs {
+    /// the block to rebuild the bundle for
+    #[clap(long)]
+    pub block:                BlockNumber,
+    /// json archive of the pre-proposals recorded for the block
+    #[clap(long)]
+    pub preproposals:         PathBuf,
+    /// the pools to rebuild snapshots for, as `<POOL_ID>=<UNISWAP_POOL>`
+    #[clap(long = "pool")]
+    pub pools:                Vec<DryRunPool>,
+    /// address of the angstrom contract the bundle is simulated against
+    #[clap(long)]
+    pub angstrom_address:     Address,
+    #[clap(long, default_value = "http://localhost:8545")]
+    pub rpc_url:              String,
+    #[clap(long, default_value = "400")]
+    pub ticks_per_side:       u16,
+    /// how orders at the same price are ranked, has to match the validators
+    #[clap(long, default_value = "volume")]
+    pub price_level_priority: PriceLevelPriority
+}
+
+pub fn run(args: DryRunArgs) -> eyre::Result<()> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(dry_run(args))
+}
+
+async fn dry_run(args: DryRunArgs) -> eyre::Result<()> {
+    let provider = Arc::new(ProviderBuilder::new().on_builtin(&args.rpc_url).await?);
+
+    let preproposals: Vec<PreProposal> =
+        serde_json::from_reader(std::fs::File::open(&args.preproposals)?)?;
+
+    let mut pools: HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)> = HashMap::new();
+    for (store_index, pool) in args.pools.iter().enumerate() {
+        let mut uniswap_pool = EnhancedUniswapV3Pool::new(pool.address, args.ticks_per_side);
+        uniswap_pool
+            .initialize(Some(args.block), provider.clone())
+            .await?;
+        let snapshot = uniswap_pool.fetch_pool_snapshot()?;
+
+        pools.insert(
+            pool.pool_id,
+            (uniswap_pool.token_a, uniswap_pool.token_b, snapshot, store_index as u16)
+        );
+    }
+
+    let solutions = MatchingManager::new(args.price_level_priority)
+        .build_proposal(preproposals.clone())
+        .await
+        .map_err(|e| eyre::eyre!(e))?;
+
+    // the proposal is never broadcast, so any key will do
+    let sk = SecretKey::new(&mut thread_rng());
+    let proposal =
+        Proposal::generate_proposal(args.block, PeerId::default(), preproposals, solutions, &sk);
+    #[cfg(not(feature = "cross-pool-netting"))]
+    let bundle = AngstromBundle::from_proposal(&proposal, &pools, args.price_level_priority)?;
+    #[cfg(feature = "cross-pool-netting")]
+    let bundle = {
+        let (bundle, settlements) = AngstromBundle::from_proposal_with_netting(
+            &proposal,
+            &pools,
+            args.price_level_priority
+        )?;
+        // every order moves one asset in and one out
+        let transfers = 2 * (bundle.user_orders.len() + bundle.top_of_block_orders.len());
+        println!("{transfers} user transfers, {} once netted across pairs", settlements.len());
+        bundle
+    };
+    println!("{bundle:#?}");
+
+    let tx = TransactionRequest::default()
+        .to(args.angstrom_address)
+        .input(TransactionInput::new(bundle.execute_calldata()));
+
+    match provider.call(&tx).block(args.block.into()).await {
+        Ok(res) => println!("simulation succeeded: {res}"),
+        Err(e) => println!("simulation failed: {e}")
+    }
+
+    Ok(())
+}
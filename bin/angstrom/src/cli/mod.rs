@@ -1,32 +1,76 @@
 //! CLI definition and entrypoint to executable
-use std::{collections::HashSet, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration
+};
 
 use alloy_primitives::Address;
-use angstrom_metrics::{initialize_prometheus_metrics, METRICS_ENABLED};
+use angstrom_metrics::{init_pool_labels, initialize_prometheus_metrics, METRICS_ENABLED};
 use angstrom_network::manager::StromConsensusEvent;
-use order_pool::{order_storage::OrderStorage, PoolConfig, PoolManagerUpdate};
+use order_pool::{
+    order_storage::OrderStorage, PoolConfig, SequencedUpdate,
+    ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT
+};
 use reth_node_builder::{FullNode, NodeHandle};
 use secp256k1::{PublicKey, Secp256k1, SecretKey};
 use tokio::sync::mpsc::{
     channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender
 };
 
+mod bench_pipeline;
+mod decode_bundle;
+pub mod deploy;
+mod dry_run;
 mod network_builder;
-use alloy::providers::{network::Ethereum, ProviderBuilder};
+mod screening;
+use alloy::{
+    providers::{network::Ethereum, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+    transports::http::reqwest::Url
+};
 use alloy_chains::Chain;
 use angstrom_eth::{
     handle::{Eth, EthCommand},
-    manager::EthDataCleanser
+    manager::{EthDataCleanser, DEFAULT_STALL_SLOTS}
 };
 use angstrom_network::{
+    leader_fast_path::DEFAULT_FAST_PATH_MIN_QUANTITY,
     pool_manager::{OrderCommand, PoolHandle},
-    NetworkBuilder as StromNetworkBuilder, NetworkOrderEvent, PoolManagerBuilder, StatusState,
-    VerificationSidecar
+    LeaderFastPath, NetworkBuilder as StromNetworkBuilder, NetworkOrderEvent, PeerStore,
+    PoolManagerBuilder, ReplicationRole, RoundLeader, StatusState, VerificationSidecar
+};
+use angstrom_rpc::{
+    api::{
+        AdminApiServer, ConsensusApiServer, DashboardApiServer, DeskApiServer, OrderApiServer,
+        QuotingApiServer
+    },
+    screening::{
+        AddressListScreening, AddressScreening, OrderScreener, ScreeningFailureMode,
+        DEFAULT_SCREENING_CACHE_SIZE, DEFAULT_SCREENING_CACHE_TTL
+    },
+    types::ApiKeyConfig,
+    AdminApi, ConsensusApi, DashboardApi, DeskApi, OrderApi, QuotesApi
+};
+use angstrom_types::{
+    consensus::BundleAttestation,
+    orders::PriceLevelPriority,
+    persistence::Persisted,
+    primitive::{PeerId, PoolId}
+};
+use clap::{Parser, Subcommand};
+use consensus::{
+    slot_timing::{SlotTiming, DEFAULT_SLOT_DURATION},
+    status::CurrentRound,
+    submission::{
+        BundleSubmitter, BundleSubmitterHandle, SubmissionRetry, SubmissionTarget,
+        DEFAULT_BUNDLE_RELAY, DEFAULT_SUBMISSION_ATTEMPTS
+    },
+    summary::RoundSummaries,
+    validator_registry::{ValidatorRegistry, DEFAULT_VALIDATOR_EPOCH_LENGTH},
+    AngstromValidator, ConsensusManager, ManagerNetworkDeps, Signer, DEFAULT_LEADER_SELECTION_DIR
 };
-use angstrom_rpc::{api::OrderApiServer, OrderApi};
-use angstrom_types::primitive::PeerId;
-use clap::Parser;
-use consensus::{AngstromValidator, ConsensusManager, ManagerNetworkDeps, Signer};
 use reth::{
     api::NodeAddOns,
     builder::{FullNodeComponents, Node},
@@ -37,16 +81,65 @@ use reth::{
 };
 use reth_cli_util::get_secret_key;
 use reth_metrics::common::mpsc::{UnboundedMeteredReceiver, UnboundedMeteredSender};
+use reth_network::Peers;
 use reth_network_peers::pk2id;
 use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
-use validation::init_validation;
+use validation::{
+    bundle::{RevmBundleSimulator, ValidatorBundlePools},
+    order::state::config::load_validation_config,
+    validator::{ValidationClient, ValidationRequest},
+    OrderValidatorBuilder, TOKEN_CONFIG_FILE
+};
+
+use crate::cli::{
+    bench_pipeline::BenchPipelineArgs, decode_bundle::DecodeBundleArgs, deploy::DeployArgs,
+    dry_run::DryRunArgs, network_builder::AngstromNetworkBuilder, screening::HttpScreening
+};
+
+/// Commands that run without a node.
+#[derive(Debug, Parser)]
+#[command(name = "angstrom")]
+struct ToolCli {
+    #[command(subcommand)]
+    command: ToolCommand
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum ToolCommand {
+    /// Rebuild and simulate the bundle for a block
+    DryRun(DryRunArgs),
+    /// Deploy the angstrom contracts to a new environment
+    Deploy(DeployArgs),
+    /// Benchmark the ingest to bundle pipeline
+    BenchPipeline(BenchPipelineArgs),
+    /// Decode and inspect the calldata of a bundle
+    DecodeBundle(DecodeBundleArgs)
+}
 
-use crate::cli::network_builder::AngstromNetworkBuilder;
+impl ToolCommand {
+    fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::DryRun(args) => dry_run::run(args),
+            Self::Deploy(args) => deploy::run(args),
+            Self::BenchPipeline(args) => bench_pipeline::run(args),
+            Self::DecodeBundle(args) => decode_bundle::run(args)
+        }
+    }
+}
 
 /// Convenience function for parsing CLI options, set up logging and run the
 /// chosen command.
 #[inline]
 pub fn run() -> eyre::Result<()> {
+    // the tool commands don't need a node, so they are handled before reth parses
+    // the args
+    if std::env::args()
+        .nth(1)
+        .is_some_and(|name| ToolCommand::has_subcommand(&name))
+    {
+        return ToolCli::parse().command.run()
+    }
+
     Cli::<EthereumChainSpecParser, AngstromConfig>::parse().run(|builder, args| async move {
         let executor = builder.task_executor().clone();
 
@@ -56,16 +149,35 @@ pub fn run() -> eyre::Result<()> {
         } else {
             METRICS_ENABLED.set(false).unwrap();
         }
+        init_pool_labels(load_pool_labels(&args.validation_config));
 
         let secret_key = get_secret_key(&args.secret_key_location)?;
 
-        let mut network = init_network_builder(secret_key)?;
+        let mut network = init_network_builder(secret_key, args.relay_only)?;
+        if let Some(path) = args.peer_store.clone() {
+            network = network.with_peer_store(path);
+        }
         let protocol_handle = network.build_protocol_handler();
         let channels = initialize_strom_handles();
 
         // for rpc
         let pool = channels.get_pool_handle();
         let executor_clone = executor.clone();
+        let desk_executor = executor.clone();
+        let consensus_executor = executor.clone();
+        let attestations = channels.attestation_tx.clone();
+        let round_summaries = channels.round_summaries.clone();
+        let current_round = channels.current_round.clone();
+        let validation_client = channels.get_validation_client();
+        let token_decimals = load_token_decimals(&args.validation_config);
+        let api_keys = args
+            .rpc_api_keys
+            .as_ref()
+            .map(|path| -> eyre::Result<Vec<ApiKeyConfig>> {
+                Ok(serde_json::from_reader(std::fs::File::open(path)?)?)
+            })
+            .transpose()?;
+        let screener = order_screener(&args)?;
         // let consensus = channels.get_consensus_handle();
         let NodeHandle { node, node_exit_future } = builder
             .with_types::<EthereumNode>()
@@ -76,45 +188,81 @@ pub fn run() -> eyre::Result<()> {
             )
             .with_add_ons::<EthereumAddOns>(Default::default())
             .extend_rpc_modules(move |rpc_context| {
-                let order_api = OrderApi::new(pool.clone(), executor_clone);
-                // let quotes_api = QuotesApi { pool: pool.clone() };
-                // let consensus_api = ConsensusApi { consensus: consensus.clone() };
+                let mut order_api = OrderApi::new(pool.clone(), executor_clone)
+                    .with_token_decimals(token_decimals)
+                    .with_validator(validation_client.clone());
+                if let Some(screener) = screener {
+                    order_api = order_api.with_screening(screener);
+                }
+                let quotes_api = QuotesApi::new(pool.clone(), validation_client.clone());
+                let consensus_api =
+                    ConsensusApi::new(attestations, current_round, consensus_executor);
                 rpc_context.modules.merge_configured(order_api.into_rpc())?;
-                // rpc_context
-                //     .modules
-                //     .merge_configured(quotes_api.into_rpc())?;
-                // rpc_context
-                //     .modules
-                //     .merge_configured(consensus_api.into_rpc())?;
+                rpc_context
+                    .modules
+                    .merge_configured(consensus_api.into_rpc())?;
+                let dashboard_api = DashboardApi::new(pool.clone(), round_summaries);
+                rpc_context
+                    .modules
+                    .merge_configured(dashboard_api.into_rpc())?;
+                // changes node configuration, so it is only served locally
+                let admin_api = AdminApi::new(validation_client);
+                rpc_context.modules.merge_ipc(admin_api.into_rpc())?;
+                if let Some(api_keys) = api_keys {
+                    let desk_api = DeskApi::new(pool.clone(), desk_executor, api_keys);
+                    rpc_context.modules.merge_configured(desk_api.into_rpc())?;
+                }
+                rpc_context
+                    .modules
+                    .merge_configured(quotes_api.into_rpc())?;
 
                 Ok(())
             })
             .launch()
             .await?;
 
-        initialize_strom_components(
-            Address::ZERO,
-            args,
-            secret_key,
-            channels,
-            network,
-            node,
-            &executor
-        )
-        .await;
+        initialize_strom_components(args, secret_key, channels, network, node, &executor).await?;
 
         node_exit_future.await
     })
 }
 
-pub fn init_network_builder(secret_key: SecretKey) -> eyre::Result<StromNetworkBuilder> {
+/// The decimals of the tokens we know about, used to normalize the prices
+/// returned over rpc.
+fn load_token_decimals(config_path: &Path) -> HashMap<Address, u8> {
+    load_validation_config(config_path)
+        .map(|config| {
+            config
+                .dust
+                .tokens
+                .into_iter()
+                .map(|token| (token.token, token.decimals))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `SYM0/SYM1` names of the pools we know about, used to label metrics and
+/// logs instead of the raw pool ids.
+fn load_pool_labels(config_path: &Path) -> HashMap<PoolId, String> {
+    load_validation_config(config_path)
+        .map(|config| config.pool_labels())
+        .unwrap_or_default()
+}
+
+pub fn init_network_builder(
+    secret_key: SecretKey,
+    relay_only: bool
+) -> eyre::Result<StromNetworkBuilder> {
     let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
 
     let state = StatusState {
-        version:   0,
-        chain:     Chain::mainnet().id(),
-        peer:      pk2id(&public_key),
-        timestamp: 0
+        version: 0,
+        chain: Chain::mainnet().id(),
+        peer: pk2id(&public_key),
+        timestamp: 0,
+        max_order_horizon: ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+        relay_only
     };
 
     let verification =
@@ -137,12 +285,20 @@ pub struct StromHandles {
     pub orderpool_tx: UnboundedSender<DefaultOrderCommand>,
     pub orderpool_rx: UnboundedReceiver<DefaultOrderCommand>,
 
-    pub pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>,
+    pub pool_manager_tx: tokio::sync::broadcast::Sender<SequencedUpdate>,
 
     // pub consensus_tx:    Sender<ConsensusCommand>,
     // pub consensus_rx:    Receiver<ConsensusCommand>,
     pub consensus_tx_op: UnboundedMeteredSender<StromConsensusEvent>,
-    pub consensus_rx_op: UnboundedMeteredReceiver<StromConsensusEvent>
+    pub consensus_rx_op: UnboundedMeteredReceiver<StromConsensusEvent>,
+
+    pub attestation_tx:  tokio::sync::broadcast::Sender<BundleAttestation>,
+    pub round_summaries: RoundSummaries,
+    pub round_leader:    RoundLeader,
+    pub current_round:   CurrentRound,
+
+    pub validator_tx: UnboundedSender<ValidationRequest>,
+    pub validator_rx: UnboundedReceiver<ValidationRequest>
 }
 
 impl StromHandles {
@@ -153,6 +309,10 @@ impl StromHandles {
         }
     }
 
+    pub fn get_validation_client(&self) -> ValidationClient {
+        ValidationClient(self.validator_tx.clone())
+    }
+
     // pub fn get_consensus_handle(&self) -> ConsensusHandle {
     //     ConsensusHandle { sender: self.consensus_tx.clone() }
     // }
@@ -166,6 +326,8 @@ pub fn initialize_strom_handles() -> StromHandles {
     let (orderpool_tx, orderpool_rx) = unbounded_channel();
     let (consensus_tx_op, consensus_rx_op) =
         reth_metrics::common::mpsc::metered_unbounded_channel("orderpool");
+    let (attestation_tx, _) = tokio::sync::broadcast::channel(100);
+    let (validator_tx, validator_rx) = unbounded_channel();
 
     StromHandles {
         eth_tx,
@@ -178,19 +340,25 @@ pub fn initialize_strom_handles() -> StromHandles {
         // consensus_tx,
         // consensus_rx,
         consensus_tx_op,
-        consensus_rx_op
+        consensus_rx_op,
+        attestation_tx,
+        round_summaries: RoundSummaries::new(),
+        round_leader: RoundLeader::new(),
+        current_round: CurrentRound::new(),
+        validator_tx,
+        validator_rx
     }
 }
 
 pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeAddOns<Node>>(
-    angstrom_address: Address,
     config: AngstromConfig,
     secret_key: SecretKey,
     handles: StromHandles,
     network_builder: StromNetworkBuilder,
     node: FullNode<Node, AddOns>,
     executor: &TaskExecutor
-) {
+) -> eyre::Result<()> {
+    let angstrom_address = config.angstrom_address;
     let eth_handle = EthDataCleanser::spawn(
         angstrom_address,
         node.provider.subscribe_to_canonical_state(),
@@ -198,30 +366,58 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         executor.clone(),
         handles.eth_tx,
         handles.eth_rx,
-        HashSet::new()
+        HashSet::new(),
+        config.canonical_stall_slots
     )
     .unwrap();
 
-    let network_handle = network_builder
-        .with_pool_manager(handles.pool_tx)
-        .with_consensus_manager(handles.consensus_tx_op)
-        .build_handle(executor.clone(), node.provider.clone());
+    // dial the peers we knew about before the restart instead of waiting for
+    // discovery to find them again
+    if let Some(path) = config.peer_store.as_ref().filter(|path| path.exists()) {
+        match PeerStore::read(path) {
+            Ok(store) => store
+                .reconnect_candidates()
+                .into_iter()
+                .for_each(|(peer_id, addr)| node.network.add_peer(peer_id, addr)),
+            Err(e) => eprintln!("failed to load the peer store - {:?}", e)
+        }
+    }
+
+    let mut network_builder = network_builder.with_pool_manager(handles.pool_tx);
+    if !config.relay_only {
+        network_builder = network_builder.with_consensus_manager(handles.consensus_tx_op);
+    }
+    let network_handle = network_builder.build_handle(executor.clone(), node.provider.clone());
     let block_height = node.provider.best_block_number().unwrap();
-    let validator = init_validation(
-        node.provider.clone(),
-        node.provider.subscribe_to_canonical_state(),
-        config.validation_cache_size
+
+    // I am sure there is a prettier way of doing this
+    let provider = Arc::new(
+        ProviderBuilder::<_, _, Ethereum>::default()
+            .on_builtin(node.rpc_server_handles.rpc.http_url().unwrap().as_str())
+            .await?
     );
+    let validator_builder = OrderValidatorBuilder::new(
+        node.provider.clone(),
+        node.provider.subscribe_to_canonical_state()
+    )
+    .with_config_path(&config.validation_config)
+    .with_cache_size(config.validation_cache_size)
+    .with_max_workers(config.validation_max_workers)
+    .with_request_channel(handles.validator_tx, handles.validator_rx);
+    // orders are checked by both the pool and the validator, which share the
+    // signers they recover
+    let signers = validator_builder.signer_cache();
+    let validator = validator_builder.build(provider.clone()).await?;
 
     // Create our pool config
-    let pool_config = PoolConfig::default();
+    let pool_config = PoolConfig { twap_enabled: config.enable_twap, ..Default::default() };
 
     // Create order storage based on that config
     let order_storage = Arc::new(OrderStorage::new(&pool_config));
 
     // Build our PoolManager using the PoolConfig and OrderStorage we've already
     // created
-    let _pool_handle = PoolManagerBuilder::new(
+    let mut pool_manager = PoolManagerBuilder::new(
         validator.clone(),
         Some(order_storage.clone()),
         network_handle.clone(),
@@ -229,30 +425,85 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         handles.pool_rx
     )
     .with_config(pool_config)
-    .build_with_channels(
+    .with_signer_cache(signers);
+    if let Some(standby) = config.standby_peer {
+        pool_manager = pool_manager.with_replication(ReplicationRole::Primary { standby });
+    } else if let Some(primary) = config.primary_peer {
+        pool_manager = pool_manager.with_replication(ReplicationRole::Standby { primary });
+    }
+    // relay only nodes don't follow the rounds, so they never know the leader
+    if !config.relay_only {
+        pool_manager = pool_manager.with_leader_fast_path(LeaderFastPath::new(
+            handles.round_leader.clone(),
+            config.fast_path_min_quantity
+        ));
+    }
+    let _pool_handle = pool_manager.build_with_channels(
         executor.clone(),
         handles.orderpool_tx,
         handles.orderpool_rx,
         handles.pool_manager_tx
     );
 
-    let signer = Signer::new(secret_key);
+    // relay only nodes validate, gossip and serve orders but never join a round
+    if config.relay_only {
+        return Ok(())
+    }
 
-    // TODO load the stakes from Eigen using node.provider
-    // list of PeerIds will be known upfront on the first version
-    let validators = vec![
-        AngstromValidator::new(PeerId::default(), 100),
-        AngstromValidator::new(PeerId::default(), 200),
-        AngstromValidator::new(PeerId::default(), 300),
-    ];
+    let signer = Signer::new(secret_key);
+    // the bundle is simulated as sent by us, as `execute` is only open to nodes
+    let node_signer = PrivateKeySigner::from_slice(&secret_key.secret_bytes())?;
+    let bundle_simulator =
+        RevmBundleSimulator::new(node.provider.clone(), angstrom_address, node_signer.address());
+    // bundles are built against the pools synced by the validator
+    let bundle_pools = ValidatorBundlePools::new(
+        validator.clone(),
+        &load_validation_config(&config.validation_config)?
+    );
 
-    // I am sure there is a prettier way of doing this
-    let provider = ProviderBuilder::<_, _, Ethereum>::default()
-        .on_builtin(node.rpc_server_handles.rpc.http_url().unwrap().as_str())
-        .await
-        .unwrap();
+    // nothing is sent on chain unless asked for
+    let bundle_submitter = config.submit_bundles.then(|| {
+        let target = if config.public_submission {
+            SubmissionTarget::Public
+        } else {
+            SubmissionTarget::from_relays(config.bundle_relays.clone())
+        };
+        let (submission_tx, submission_rx) = unbounded_channel();
+        let bundle_submitter =
+            BundleSubmitter::new(provider.clone(), node_signer, angstrom_address, target)
+                .with_retry(SubmissionRetry {
+                    attempts: config.submission_attempts,
+                    ..Default::default()
+                });
+        executor.spawn(Box::pin(bundle_submitter.run(submission_rx)));
+        BundleSubmitterHandle::new(submission_tx)
+    });
+
+    let mut validator_updates = None;
+    let validators = if let Some(address) = config.validator_registry {
+        let registry =
+            ValidatorRegistry::new(provider.clone(), address, config.validator_epoch_length);
+        let validators = registry
+            .validators(registry.active_set_block(block_height))
+            .await?;
+        let (tx, rx) = unbounded_channel();
+        executor.spawn(Box::pin(registry.refresh_on_epochs(
+            block_height,
+            node.provider.subscribe_to_canonical_state(),
+            tx
+        )));
+        validator_updates = Some(rx);
+        validators
+    } else {
+        // list of PeerIds will be known upfront on the first version
+        vec![
+            AngstromValidator::new(PeerId::default(), 100),
+            AngstromValidator::new(PeerId::default(), 200),
+            AngstromValidator::new(PeerId::default(), 300),
+        ]
+    };
 
-    let manager = ConsensusManager::new(
+    let mut manager = ConsensusManager::new(
         ManagerNetworkDeps::new(
             network_handle.clone(),
             node.provider.subscribe_to_canonical_state(),
@@ -262,27 +513,167 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         validators,
         order_storage.clone(),
         block_height,
-        Arc::new(provider)
+        provider,
+        &config.leader_selection_dir
     );
+    manager = manager
+        .with_attestations(handles.attestation_tx)
+        .with_round_summaries(handles.round_summaries)
+        .with_round_leader(handles.round_leader)
+        .with_current_round(handles.current_round)
+        .with_bundle_simulator(Arc::new(bundle_simulator))
+        .with_bundle_pools(Arc::new(bundle_pools))
+        .with_price_level_priority(config.price_level_priority);
+    if let Some(bundle_submitter) = bundle_submitter {
+        manager = manager.with_bundle_submitter(bundle_submitter);
+    }
+    if let Some(genesis_time) = config.beacon_genesis_time {
+        let slot_duration = Duration::from_secs(config.slot_duration_secs);
+        manager = manager.with_slot_timing(SlotTiming::new(genesis_time, slot_duration));
+    }
+    if let Some(validator_updates) = validator_updates {
+        manager = manager.with_validator_updates(validator_updates);
+    }
     let _consensus_handle = executor.spawn_critical("consensus", Box::pin(manager));
+
+    Ok(())
 }
 
 #[derive(Debug, Clone, Default, clap::Args)]
 pub struct AngstromConfig {
     #[clap(long)]
-    pub mev_guard:             bool,
+    pub mev_guard:              bool,
+    /// address of the angstrom contract, the bundles of our proposals are
+    /// simulated against and sent to it
+    #[clap(long)]
+    pub angstrom_address:       Address,
     #[clap(long)]
-    pub secret_key_location:   PathBuf,
+    pub secret_key_location:    PathBuf,
+    /// toml file of the pools and token slots to validate orders for, read
+    /// again when it changes or the node receives a SIGHUP
+    #[clap(long, default_value = TOKEN_CONFIG_FILE)]
+    pub validation_config:      PathBuf,
     // default is 100mb
     #[clap(long, default_value = "1000000")]
-    pub validation_cache_size: usize,
+    pub validation_cache_size:  usize,
+    /// enables autoscaling of the validation worker threads with the
+    /// available cores of the machine, bounded by the given max
+    #[clap(long)]
+    pub validation_max_workers: Option<usize>,
+    /// amount of slots without a new canonical block before the canonical
+    /// state stream is considered stalled and re-subscribed to
+    #[clap(long, default_value_t = DEFAULT_STALL_SLOTS)]
+    pub canonical_stall_slots:  u32,
+    /// json file of api keys that get access to the `angstrom_desk`
+    /// namespace, each with their own order quotas
+    #[clap(long)]
+    pub rpc_api_keys:           Option<PathBuf>,
+    /// file the peer table is persisted to, so that a restarted node
+    /// reconnects to known peers and keeps its bans
+    #[clap(long)]
+    pub peer_store:             Option<PathBuf>,
+    /// unix timestamp of the beacon chain genesis. When set, the consensus
+    /// phases end relative to the slot schedule instead of the block arrival
+    #[clap(long)]
+    pub beacon_genesis_time:    Option<u64>,
+    #[clap(long, default_value_t = DEFAULT_SLOT_DURATION.as_secs())]
+    pub slot_duration_secs:     u64,
+    /// enables the TWAP order slicing service
+    #[clap(long)]
+    pub enable_twap:            bool,
+    /// peer id of a warm standby, our validated orders and cancellations are
+    /// replicated to it
+    #[clap(long, conflicts_with = "primary_peer")]
+    pub standby_peer:           Option<PeerId>,
+    /// runs the node as the warm standby of the given peer, applying the
+    /// orders and cancellations it replicates
+    #[clap(long)]
+    pub primary_peer:           Option<PeerId>,
+    /// validates, gossips and serves orders without taking part in consensus,
+    /// for nodes that aren't validators
+    #[clap(long)]
+    pub relay_only:             bool,
+    /// smallest `quantityIn` of a top of block order for it to be sent
+    /// straight to the round leader when it arrives during bid aggregation
+    #[clap(long, default_value_t = DEFAULT_FAST_PATH_MIN_QUANTITY)]
+    pub fast_path_min_quantity: u128,
+    /// stake registry contract the validator set and voting powers are loaded
+    /// from, a fixed set is used when not given
+    #[clap(long)]
+    pub validator_registry:     Option<Address>,
+    /// blocks between two reloads of the validator set from the registry
+    #[clap(long, default_value_t = DEFAULT_VALIDATOR_EPOCH_LENGTH)]
+    pub validator_epoch_length: u64,
+    /// directory the leader selection state is kept in across restarts
+    #[clap(long, default_value = DEFAULT_LEADER_SELECTION_DIR)]
+    pub leader_selection_dir:   PathBuf,
+    /// how orders at the same price are ranked, `volume` or `arrival`. Every
+    /// validator has to run with the same one
+    #[clap(long, default_value = "volume")]
+    pub price_level_priority:   PriceLevelPriority,
+    /// sends the bundle of our proposals on chain when we lead a round, no
+    /// bundle is sent without it
+    #[clap(long)]
+    pub submit_bundles:         bool,
+    /// private relays the bundles of our proposals are sent to with
+    /// `eth_sendBundle`
+    #[clap(long, value_delimiter = ',', default_value = DEFAULT_BUNDLE_RELAY)]
+    pub bundle_relays:          Vec<Url>,
+    /// sends the bundles of our proposals to the public mempool instead of
+    /// the relays
+    #[clap(long, requires = "submit_bundles", conflicts_with = "bundle_relays")]
+    pub public_submission:      bool,
+    /// amount of times a bundle is sent to a target before giving up on it
+    #[clap(long, default_value_t = DEFAULT_SUBMISSION_ATTEMPTS)]
+    pub submission_attempts:    u32,
+    /// json array of addresses whose orders are turned away over rpc
+    #[clap(long, conflicts_with = "screening_url")]
+    pub screening_list:         Option<PathBuf>,
+    /// service the accounts of orders submitted over rpc are screened with,
+    /// asked with `GET <url>/<address>` for a `{"blocked": bool}` body
+    #[clap(long)]
+    pub screening_url:          Option<Url>,
+    /// rejects orders whose accounts can't be screened instead of letting
+    /// them through
+    #[clap(long)]
+    pub screening_fail_closed:  bool,
+    /// seconds the screening result of an address is reused for
+    #[clap(long, default_value_t = DEFAULT_SCREENING_CACHE_TTL.as_secs())]
+    pub screening_cache_secs:   u64,
+    /// most addresses whose screening result is kept
+    #[clap(long, default_value_t = DEFAULT_SCREENING_CACHE_SIZE)]
+    pub screening_cache_size:   usize,
     /// enables the metrics
     #[clap(long, default_value = "false", global = true)]
-    pub metrics:               bool,
+    pub metrics:                bool,
     /// spawns the prometheus metrics exporter at the specified port
     /// Default: 6969
     #[clap(long, default_value = "6969", global = true)]
-    pub metrics_port:          u16
+    pub metrics_port:           u16
+}
+
+/// The screener of the orders submitted over rpc, if screening is enabled.
+fn order_screener(config: &AngstromConfig) -> eyre::Result<Option<OrderScreener>> {
+    let screening: Arc<dyn AddressScreening> = if let Some(path) = &config.screening_list {
+        let addresses: Vec<Address> = serde_json::from_reader(std::fs::File::open(path)?)?;
+        Arc::new(AddressListScreening::new(addresses))
+    } else if let Some(url) = &config.screening_url {
+        Arc::new(HttpScreening::new(url.clone())?)
+    } else {
+        return Ok(None)
+    };
+    let failure_mode = if config.screening_fail_closed {
+        ScreeningFailureMode::Closed
+    } else {
+        ScreeningFailureMode::Open
+    };
+
+    Ok(Some(
+        OrderScreener::new(screening)
+            .with_failure_mode(failure_mode)
+            .with_cache_ttl(Duration::from_secs(config.screening_cache_secs))
+            .with_cache_size(config.screening_cache_size)
+    ))
 }
 
 async fn init_metrics(metrics_port: u16) {
@@ -290,3 +681,24 @@ async fn init_metrics(metrics_port: u16) {
         .await
         .inspect_err(|e| eprintln!("failed to start metrics endpoint - {:?}", e));
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn parses_tool_commands() {
+        ToolCli::command().debug_assert();
+        assert!(ToolCommand::has_subcommand("decode-bundle"));
+        assert!(!ToolCommand::has_subcommand("node"));
+
+        let cli =
+            ToolCli::try_parse_from(["angstrom", "decode-bundle", "0x00", "--check"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            ToolCommand::DecodeBundle(DecodeBundleArgs { check: true, .. })
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+//! Screening of order accounts through an external service.
+use std::time::Duration;
+
+use alloy::transports::http::reqwest::{self, Url};
+use alloy_primitives::Address;
+use angstrom_rpc::screening::{AddressScreening, ScreeningError, ScreeningFuture};
+
+/// How long the service has to answer before the address counts as unscreened,
+/// so that a hanging service doesn't hold up order submission.
+pub const SCREENING_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Asks `GET <url>/<address>` whether the address is blocked, expecting a json
+/// body of the form `{"blocked": bool}`.
+pub struct HttpScreening {
+    url:  Url,
+    http: reqwest::Client
+}
+
+impl HttpScreening {
+    pub fn new(url: Url) -> reqwest::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(SCREENING_REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self { url, http })
+    }
+}
+
+impl AddressScreening for HttpScreening {
+    fn is_blocked(&self, address: Address) -> ScreeningFuture<'_> {
+        Box::pin(async move {
+            let unavailable = |e: reqwest::Error| ScreeningError::Unavailable(e.to_string());
+            let url = format!("{}/{address}", self.url.as_str().trim_end_matches('/'));
+            let response = self
+                .http
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(unavailable)?;
+            let body = response.text().await.map_err(unavailable)?;
+            let body: serde_json::Value = serde_json::from_str(&body)
+                .map_err(|e| ScreeningError::Unavailable(e.to_string()))?;
+
+            body.get("blocked")
+                .and_then(serde_json::Value::as_bool)
+                .ok_or_else(|| ScreeningError::Unavailable(format!("unexpected response {body}")))
+        })
+    }
+}
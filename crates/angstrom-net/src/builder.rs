@@ -1,10 +1,10 @@
 //! Builder structs for messages.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use alloy::primitives::{Address, FixedBytes};
 use alloy_chains::Chain;
-use angstrom_types::primitive::PeerId;
+use angstrom_types::{persistence::Persisted, primitive::PeerId};
 use futures::FutureExt;
 use parking_lot::RwLock;
 use reth_metrics::common::mpsc::{MeteredPollSender, UnboundedMeteredSender};
@@ -15,8 +15,8 @@ use tokio_util::sync::PollSender;
 
 use crate::{
     manager::StromConsensusEvent, state::StromState, types::status::StatusState, NetworkOrderEvent,
-    Status, StromNetworkHandle, StromNetworkManager, StromProtocolHandler, StromSessionManager,
-    StromSessionMessage, Swarm, VerificationSidecar
+    PeerStore, Status, StromNetworkHandle, StromNetworkManager, StromProtocolHandler,
+    StromSessionManager, StromSessionMessage, Swarm, VerificationSidecar
 };
 
 pub struct NetworkBuilder {
@@ -25,7 +25,8 @@ pub struct NetworkBuilder {
     session_manager_rx:   Option<Receiver<StromSessionMessage>>,
 
     validator_set: Arc<RwLock<HashSet<Address>>>,
-    verification:  VerificationSidecar
+    verification:  VerificationSidecar,
+    peer_store:    Option<PathBuf>
 }
 
 impl NetworkBuilder {
@@ -36,7 +37,8 @@ impl NetworkBuilder {
             to_consensus_manager: None,
             session_manager_rx: None,
 
-            validator_set: Default::default()
+            validator_set: Default::default(),
+            peer_store: None
         }
     }
 
@@ -58,6 +60,13 @@ impl NetworkBuilder {
         self
     }
 
+    /// Loads the peer table from the given path on startup and keeps it
+    /// persisted there while running.
+    pub fn with_peer_store(mut self, path: PathBuf) -> Self {
+        self.peer_store = Some(path);
+        self
+    }
+
     pub fn build_protocol_handler(&mut self) -> StromProtocolHandler {
         let (session_manager_tx, session_manager_rx) = tokio::sync::mpsc::channel(100);
         let protocol = StromProtocolHandler::new(
@@ -78,12 +87,21 @@ impl NetworkBuilder {
         tp: TP,
         db: DB
     ) -> StromNetworkHandle {
-        let state = StromState::new(db, self.validator_set.clone());
+        let mut state = StromState::new(db, self.validator_set.clone());
+        if let Some(path) = self.peer_store.as_ref().filter(|path| path.exists()) {
+            match PeerStore::read(path) {
+                Ok(store) => state.peers_mut().load_peers(store.peers),
+                Err(e) => tracing::warn!(?e, ?path, "failed to load the peer table")
+            }
+        }
         let sessions = StromSessionManager::new(self.session_manager_rx.take().unwrap());
         let swarm = Swarm::new(sessions, state);
 
-        let network =
+        let mut network =
             StromNetworkManager::new(swarm, self.to_pool_manager, self.to_consensus_manager);
+        if let Some(path) = self.peer_store {
+            network = network.with_peer_store(path);
+        }
 
         let handle = network.get_handle();
         tp.spawn_critical("strom network", network.boxed());
@@ -121,6 +139,18 @@ impl StatusBuilder {
         self
     }
 
+    /// Sets the max order deadline horizon, in seconds.
+    pub fn max_order_horizon(mut self, max_order_horizon: u64) -> Self {
+        self.state.max_order_horizon = max_order_horizon;
+        self
+    }
+
+    /// Marks the node as relay only.
+    pub fn relay_only(mut self, relay_only: bool) -> Self {
+        self.state.relay_only = relay_only;
+        self
+    }
+
     /// Sets the chain id.
     pub fn chain(mut self, chain: Chain) -> Self {
         self.state.chain = chain.id();
@@ -44,3 +44,19 @@ impl From<alloy::rlp::Error> for StromStreamError {
         StromStreamError::InvalidMessageError
     }
 }
+
+impl From<StromStreamError> for angstrom_errors::NetworkError {
+    fn from(value: StromStreamError) -> Self {
+        match value {
+            StromStreamError::StromHandshakeError(e) => e.into(),
+            StromStreamError::MessageTooBig(_) => Self::MessageTooBig,
+            StromStreamError::InvalidMessageError => Self::InvalidMessage
+        }
+    }
+}
+
+impl From<StromHandshakeError> for angstrom_errors::NetworkError {
+    fn from(value: StromHandshakeError) -> Self {
+        Self::Handshake(value.to_string())
+    }
+}
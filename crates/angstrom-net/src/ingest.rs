@@ -0,0 +1,101 @@
+//! Stages that every network order event and order command passes through
+//! before the pool manager dispatches it to validation, so integrators can
+//! rate limit, screen, enrich or export ingested orders without touching the
+//! pool manager.
+use crate::{pool_manager::OrderCommand, NetworkOrderEvent};
+
+/// A stage of the ingest pipeline. Returning `None` drops the event or
+/// command. A dropped command closes its response channel, which the
+/// [`PoolHandle`](crate::pool_manager::PoolHandle) reports as a failure.
+pub trait IngestMiddleware: Send + 'static {
+    fn on_network_order_event(&mut self, event: NetworkOrderEvent) -> Option<NetworkOrderEvent> {
+        Some(event)
+    }
+
+    fn on_command(&mut self, command: OrderCommand) -> Option<OrderCommand> {
+        Some(command)
+    }
+}
+
+/// Runs the stages in the order they were added.
+#[derive(Default)]
+pub struct IngestPipeline {
+    stages: Vec<Box<dyn IngestMiddleware>>
+}
+
+impl IngestPipeline {
+    pub fn push(&mut self, stage: impl IngestMiddleware) {
+        self.stages.push(Box::new(stage));
+    }
+
+    pub fn on_network_order_event(
+        &mut self,
+        event: NetworkOrderEvent
+    ) -> Option<NetworkOrderEvent> {
+        self.stages
+            .iter_mut()
+            .try_fold(event, |event, stage| stage.on_network_order_event(event))
+    }
+
+    pub fn on_command(&mut self, command: OrderCommand) -> Option<OrderCommand> {
+        self.stages
+            .iter_mut()
+            .try_fold(command, |command, stage| stage.on_command(command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use alloy::primitives::B256;
+    use angstrom_types::primitive::PeerId;
+
+    use super::*;
+
+    /// Records the stages an event went through, dropping it at `drop_at`.
+    struct Stage {
+        id:      usize,
+        drop_at: Option<usize>,
+        seen:    Arc<Mutex<Vec<usize>>>
+    }
+
+    impl IngestMiddleware for Stage {
+        fn on_network_order_event(
+            &mut self,
+            event: NetworkOrderEvent
+        ) -> Option<NetworkOrderEvent> {
+            self.seen.lock().unwrap().push(self.id);
+            (self.drop_at != Some(self.id)).then_some(event)
+        }
+    }
+
+    fn event() -> NetworkOrderEvent {
+        NetworkOrderEvent::OrderAnnouncements {
+            peer_id:      PeerId::default(),
+            order_hashes: vec![B256::ZERO]
+        }
+    }
+
+    fn pipeline(drop_at: Option<usize>, seen: &Arc<Mutex<Vec<usize>>>) -> IngestPipeline {
+        let mut pipeline = IngestPipeline::default();
+        for id in 0..3 {
+            pipeline.push(Stage { id, drop_at, seen: seen.clone() });
+        }
+        pipeline
+    }
+
+    #[test]
+    fn runs_stages_in_order() {
+        let seen = Arc::default();
+        assert_eq!(pipeline(None, &seen).on_network_order_event(event()), Some(event()));
+        assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn stops_at_the_stage_that_drops() {
+        let seen = Arc::default();
+        assert_eq!(pipeline(Some(1), &seen).on_network_order_event(event()), None);
+        assert_eq!(*seen.lock().unwrap(), vec![0, 1]);
+    }
+}
@@ -0,0 +1,106 @@
+//! Fast path for top of block orders that arrive while the bids of a round
+//! are being aggregated. Gossip might not get them to the leader before it
+//! builds its proposal, so large ones are also sent to it directly.
+use std::sync::{Arc, RwLock};
+
+use alloy::primitives::BlockNumber;
+use angstrom_types::{primitive::PeerId, sol_bindings::grouped_orders::AllOrders};
+
+/// Smallest `quantityIn` of a top of block order for it to take the fast
+/// path.
+pub const DEFAULT_FAST_PATH_MIN_QUANTITY: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LeaderRound {
+    block_height: BlockNumber,
+    leader:       PeerId,
+    aggregating:  bool
+}
+
+/// The leader of the current round and whether its bids are being
+/// aggregated. Written by the consensus manager, read by the pool manager.
+#[derive(Debug, Clone, Default)]
+pub struct RoundLeader(Arc<RwLock<Option<LeaderRound>>>);
+
+impl RoundLeader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_round(&self, block_height: BlockNumber, leader: PeerId) {
+        *self.0.write().unwrap() = Some(LeaderRound { block_height, leader, aggregating: false });
+    }
+
+    pub fn set_aggregating(&self, block_height: BlockNumber, aggregating: bool) {
+        if let Some(round) = self
+            .0
+            .write()
+            .unwrap()
+            .as_mut()
+            .filter(|round| round.block_height == block_height)
+        {
+            round.aggregating = aggregating;
+        }
+    }
+
+    /// The leader of the round, if its bids are being aggregated.
+    pub fn aggregating_leader(&self) -> Option<PeerId> {
+        self.0
+            .read()
+            .unwrap()
+            .filter(|round| round.aggregating)
+            .map(|round| round.leader)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LeaderFastPath {
+    round:           RoundLeader,
+    min_quantity_in: u128
+}
+
+impl LeaderFastPath {
+    pub fn new(round: RoundLeader, min_quantity_in: u128) -> Self {
+        Self { round, min_quantity_in }
+    }
+
+    /// The leader the order should be sent to directly, if any.
+    pub(crate) fn leader_for(&self, order: &AllOrders) -> Option<PeerId> {
+        match order {
+            AllOrders::TOB(tob) if tob.quantityIn >= self.min_quantity_in => {
+                self.round.aggregating_leader()
+            }
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::rpc_orders::TopOfBlockOrder;
+
+    use super::*;
+
+    #[test]
+    fn forwards_large_tob_orders_while_aggregating() {
+        let round = RoundLeader::new();
+        let fast_path = LeaderFastPath::new(round.clone(), 100);
+        let leader = PeerId::repeat_byte(1);
+        let order = AllOrders::TOB(TopOfBlockOrder { quantityIn: 100, ..Default::default() });
+        let small = AllOrders::TOB(TopOfBlockOrder { quantityIn: 99, ..Default::default() });
+
+        round.start_round(1, leader);
+        assert_eq!(fast_path.leader_for(&order), None);
+
+        // a late update for the previous round is ignored
+        round.set_aggregating(0, true);
+        assert_eq!(fast_path.leader_for(&order), None);
+
+        round.set_aggregating(1, true);
+        assert_eq!(fast_path.leader_for(&order), Some(leader));
+        assert_eq!(fast_path.leader_for(&small), None);
+
+        round.set_aggregating(1, false);
+        assert_eq!(fast_path.leader_for(&order), None);
+    }
+}
@@ -11,8 +11,21 @@ pub mod state;
 pub mod manager;
 pub use manager::{StromNetworkEvent, StromNetworkManager};
 
+pub mod ingest;
+pub use ingest::IngestMiddleware;
+
 pub mod pool_manager;
-pub use pool_manager::PoolManagerBuilder;
+pub use pool_manager::{PoolManagerBuilder, ReplicationRole};
+
+pub mod propagation;
+pub use propagation::PropagationConfig;
+
+pub mod order_fetcher;
+
+pub mod replication;
+
+pub mod leader_fast_path;
+pub use leader_fast_path::{LeaderFastPath, RoundLeader};
 
 pub mod peers;
 pub use peers::*;
@@ -1,5 +1,6 @@
 use std::{
     future::Future,
+    path::PathBuf,
     pin::Pin,
     sync::{atomic::AtomicUsize, Arc},
     task::{Context, Poll}
@@ -9,20 +10,27 @@ use alloy::primitives::BlockNumber;
 use alloy_rpc_types::Block;
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
+    persistence::Persisted,
     primitive::PeerId,
     sol_bindings::ext::RawPoolOrder
 };
 use futures::StreamExt;
 use reth_eth_wire::DisconnectReason;
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::{
+    sync::mpsc::UnboundedSender,
+    time::{Duration, Interval}
+};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::error;
 
-use crate::{NetworkOrderEvent, StromMessage, StromNetworkHandleMsg, Swarm, SwarmEvent};
+use crate::{NetworkOrderEvent, PeerStore, StromMessage, StromNetworkHandleMsg, Swarm, SwarmEvent};
 #[allow(unused_imports)]
 use crate::{StromNetworkConfig, StromNetworkHandle, StromSessionManager};
 
+/// How often the peer table is written to the peer store.
+const PEER_STORE_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
 #[allow(dead_code)]
 pub struct StromNetworkManager<DB> {
     handle: StromNetworkHandle,
@@ -36,7 +44,9 @@ pub struct StromNetworkManager<DB> {
     /// This is updated via internal events and shared via `Arc` with the
     /// [`NetworkHandle`] Updated by the `NetworkWorker` and loaded by the
     /// `NetworkService`.
-    num_active_peers: Arc<AtomicUsize>
+    num_active_peers: Arc<AtomicUsize>,
+    /// Where the peer table is persisted, if anywhere
+    peer_store:       Option<(PathBuf, Interval)>
 }
 
 impl<DB: Unpin> StromNetworkManager<DB> {
@@ -58,7 +68,23 @@ impl<DB: Unpin> StromNetworkManager<DB> {
             from_handle_rx: rx.into(),
             to_pool_manager,
             to_consensus_manager,
-            event_listeners: Vec::new()
+            event_listeners: Vec::new(),
+            peer_store: None
+        }
+    }
+
+    /// Periodically persists the peer table to the given path, as well as on
+    /// shutdown.
+    pub fn with_peer_store(mut self, path: PathBuf) -> Self {
+        self.peer_store = Some((path, tokio::time::interval(PEER_STORE_PERSIST_INTERVAL)));
+        self
+    }
+
+    fn persist_peers(&self) {
+        let Some((path, _)) = self.peer_store.as_ref() else { return };
+        let store = PeerStore::new(self.swarm.state().peers().peer_records());
+        if let Err(e) = store.write(path) {
+            error!(?e, ?path, "failed to persist the peer table");
         }
     }
 
@@ -124,6 +150,7 @@ impl<DB: Unpin> StromNetworkManager<DB> {
 
                 // drop pending connections
 
+                self.persist_peers();
                 let _ = tx.send(());
             }
             StromNetworkHandleMsg::RemovePeer(peer_id) => {
@@ -173,6 +200,14 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                 _ => {}
             };
 
+            if self
+                .peer_store
+                .as_mut()
+                .is_some_and(|(_, interval)| interval.poll_tick(cx).is_ready())
+            {
+                self.persist_peers();
+            }
+
             if let Poll::Ready(Some(event)) = self.swarm.poll_next_unpin(cx) {
                 match event {
                     SwarmEvent::ValidMessage { peer_id, msg } => match msg {
@@ -191,6 +226,32 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                                 tx.send(NetworkOrderEvent::IncomingOrders { peer_id, orders: a });
                             });
                         }
+                        StromMessage::OrderRejected(order_hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                tx.send(NetworkOrderEvent::OrderRejections {
+                                    peer_id,
+                                    order_hashes
+                                });
+                            });
+                        }
+                        StromMessage::ReplicateOrders(updates) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                tx.send(NetworkOrderEvent::Replication { peer_id, updates });
+                            });
+                        }
+                        StromMessage::NewPooledOrderHashes(order_hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                tx.send(NetworkOrderEvent::OrderAnnouncements {
+                                    peer_id,
+                                    order_hashes
+                                });
+                            });
+                        }
+                        StromMessage::GetPooledOrders(order_hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                tx.send(NetworkOrderEvent::OrderRequests { peer_id, order_hashes });
+                            });
+                        }
                         _ => {}
                     },
                     SwarmEvent::Disconnected { peer_id } => {
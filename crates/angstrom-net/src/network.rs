@@ -1,5 +1,6 @@
 use std::sync::{atomic::AtomicUsize, Arc};
 
+use alloy::primitives::B256;
 use angstrom_types::{primitive::PeerId, sol_bindings::grouped_orders::AllOrders};
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_network::DisconnectReason;
@@ -9,7 +10,7 @@ use tokio::sync::{
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use crate::{ReputationChangeKind, StromMessage, StromNetworkEvent};
+use crate::{ReplicatedUpdate, ReputationChangeKind, StromMessage, StromNetworkEvent};
 
 //TODO:
 // 1) Implement the order pool manager
@@ -91,7 +92,30 @@ struct StromNetworkInner {
 /// All events related to orders emitted by the network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkOrderEvent {
-    IncomingOrders { peer_id: PeerId, orders: Vec<AllOrders> }
+    IncomingOrders {
+        peer_id: PeerId,
+        orders:  Vec<AllOrders>
+    },
+    /// The peer rejected orders we propagated to it
+    OrderRejections {
+        peer_id:      PeerId,
+        order_hashes: Vec<B256>
+    },
+    /// Order pool changes streamed to us by a primary node
+    Replication {
+        peer_id: PeerId,
+        updates: Vec<ReplicatedUpdate>
+    },
+    /// The peer announced new orders by their hashes
+    OrderAnnouncements {
+        peer_id:      PeerId,
+        order_hashes: Vec<B256>
+    },
+    /// The peer asks for the orders we announced to it
+    OrderRequests {
+        peer_id:      PeerId,
+        order_hashes: Vec<B256>
+    }
 }
 
 #[derive(Debug)]
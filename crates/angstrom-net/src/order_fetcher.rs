@@ -0,0 +1,192 @@
+//! Tracks the announced orders we requested from peers. A request that isn't
+//! answered in time is retried with another peer that announced the order,
+//! and requests never carry more than [`MAX_ORDER_HASHES_PER_REQUEST`] hashes.
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant}
+};
+
+use alloy::primitives::B256;
+use angstrom_types::primitive::PeerId;
+
+/// Most order hashes a single `GetPooledOrders` message may carry, larger
+/// requests are only answered up to it.
+pub const MAX_ORDER_HASHES_PER_REQUEST: usize = 256;
+
+/// How long a peer has to answer a request before we ask another peer.
+pub const ORDER_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most peers remembered per order to retry a request with.
+const MAX_FALLBACK_PEERS: usize = 4;
+
+#[derive(Debug)]
+struct InflightOrder {
+    peer:         PeerId,
+    requested_at: Instant,
+    /// other peers that announced the order
+    fallbacks:    Vec<PeerId>
+}
+
+#[derive(Debug)]
+pub struct OrderFetcher {
+    inflight:     HashMap<B256, InflightOrder>,
+    max_inflight: usize,
+    timeout:      Duration
+}
+
+impl OrderFetcher {
+    pub fn new(max_inflight: usize, timeout: Duration) -> Self {
+        Self { inflight: HashMap::new(), max_inflight, timeout }
+    }
+
+    /// The announced orders to request from `peer` now. Orders that are
+    /// already requested from another peer are remembered to retry with
+    /// `peer` instead.
+    pub fn on_announcement(
+        &mut self,
+        peer: PeerId,
+        order_hashes: impl IntoIterator<Item = B256>,
+        now: Instant
+    ) -> Vec<B256> {
+        order_hashes
+            .into_iter()
+            .filter(|hash| {
+                if let Some(inflight) = self.inflight.get_mut(hash) {
+                    if inflight.peer != peer
+                        && inflight.fallbacks.len() < MAX_FALLBACK_PEERS
+                        && !inflight.fallbacks.contains(&peer)
+                    {
+                        inflight.fallbacks.push(peer);
+                    }
+                    return false
+                }
+                if self.inflight.len() >= self.max_inflight {
+                    return false
+                }
+
+                self.inflight
+                    .insert(*hash, InflightOrder { peer, requested_at: now, fallbacks: vec![] });
+                true
+            })
+            .collect()
+    }
+
+    pub fn on_order_received(&mut self, order_hash: &B256) {
+        self.inflight.remove(order_hash);
+    }
+
+    /// Moves the requests of a disconnected peer to the other peers that
+    /// announced the orders, returning the orders to request from each.
+    pub fn on_peer_removed(&mut self, peer: PeerId, now: Instant) -> HashMap<PeerId, Vec<B256>> {
+        self.inflight
+            .values_mut()
+            .for_each(|inflight| inflight.fallbacks.retain(|fallback| *fallback != peer));
+        self.reassign(|inflight| inflight.peer == peer, now)
+    }
+
+    /// Moves the requests that weren't answered in time to the next peer that
+    /// announced the order, returning the orders to request from each. Orders
+    /// no other peer announced are given up on.
+    pub fn retry_timed_out(&mut self, now: Instant) -> HashMap<PeerId, Vec<B256>> {
+        let timeout = self.timeout;
+        self.reassign(
+            |inflight| now.saturating_duration_since(inflight.requested_at) >= timeout,
+            now
+        )
+    }
+
+    fn reassign(
+        &mut self,
+        mut stale: impl FnMut(&InflightOrder) -> bool,
+        now: Instant
+    ) -> HashMap<PeerId, Vec<B256>> {
+        let mut requests = HashMap::<PeerId, Vec<B256>>::new();
+        self.inflight.retain(|hash, inflight| {
+            if !stale(inflight) {
+                return true
+            }
+            if inflight.fallbacks.is_empty() {
+                return false
+            }
+
+            inflight.peer = inflight.fallbacks.remove(0);
+            inflight.requested_at = now;
+            requests.entry(inflight.peer).or_default().push(*hash);
+            true
+        });
+
+        requests
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetcher() -> OrderFetcher {
+        OrderFetcher::new(2, ORDER_REQUEST_TIMEOUT)
+    }
+
+    #[test]
+    fn requests_an_order_from_the_first_peer_announcing_it() {
+        let mut fetcher = fetcher();
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let order = B256::with_last_byte(1);
+        let now = Instant::now();
+
+        assert_eq!(fetcher.on_announcement(first, [order], now), vec![order]);
+        assert!(fetcher.on_announcement(second, [order], now).is_empty());
+
+        fetcher.on_order_received(&order);
+        assert!(fetcher
+            .retry_timed_out(now + ORDER_REQUEST_TIMEOUT)
+            .is_empty());
+    }
+
+    #[test]
+    fn retries_timed_out_requests_with_another_peer() {
+        let mut fetcher = fetcher();
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let order = B256::with_last_byte(1);
+        let now = Instant::now();
+
+        fetcher.on_announcement(first, [order], now);
+        fetcher.on_announcement(second, [order], now);
+
+        assert!(fetcher
+            .retry_timed_out(now + Duration::from_secs(1))
+            .is_empty());
+        let retried = now + ORDER_REQUEST_TIMEOUT;
+        assert_eq!(fetcher.retry_timed_out(retried), HashMap::from([(second, vec![order])]));
+
+        // nobody else announced it, so it's given up on
+        assert!(fetcher
+            .retry_timed_out(retried + ORDER_REQUEST_TIMEOUT)
+            .is_empty());
+        assert_eq!(fetcher.on_announcement(first, [order], retried), vec![order]);
+    }
+
+    #[test]
+    fn moves_requests_off_disconnected_peers() {
+        let mut fetcher = fetcher();
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let order = B256::with_last_byte(1);
+        let now = Instant::now();
+
+        fetcher.on_announcement(first, [order], now);
+        fetcher.on_announcement(second, [order], now);
+
+        assert_eq!(fetcher.on_peer_removed(first, now), HashMap::from([(second, vec![order])]));
+        assert!(fetcher.on_peer_removed(second, now).is_empty());
+        assert_eq!(fetcher.on_announcement(first, [order], now), vec![order]);
+    }
+
+    #[test]
+    fn bounds_the_inflight_requests() {
+        let mut fetcher = fetcher();
+        let peer = PeerId::random();
+        let orders = (1..=3).map(B256::with_last_byte).collect::<Vec<_>>();
+
+        assert_eq!(fetcher.on_announcement(peer, orders.clone(), Instant::now()), orders[..2]);
+    }
+}
@@ -1,8 +1,13 @@
-use std::collections::{hash_map::Entry, HashMap, VecDeque};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    net::SocketAddr,
+    time::{SystemTime, UNIX_EPOCH}
+};
 
 use reth_eth_wire::DisconnectReason;
 use reth_net_banlist::BanList;
 use reth_network_peers::{NodeRecord, PeerId};
+use serde::{Deserialize, Serialize};
 use tokio::{
     sync::{mpsc, mpsc::UnboundedSender, oneshot},
     time::{Duration, Instant, Interval}
@@ -11,7 +16,10 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::trace;
 
 pub use super::reputation::ReputationChangeWeights;
-use super::reputation::{is_banned_reputation, ReputationChangeKind, DEFAULT_REPUTATION};
+use super::{
+    reputation::{is_banned_reputation, ReputationChangeKind, DEFAULT_REPUTATION},
+    PeerRecord
+};
 
 /// Maintains the state of _all_ the peers known to the network.
 ///
@@ -64,6 +72,60 @@ impl PeersManager {
             .push_back(PeerAction::PeerRemoved(peer_id));
     }
 
+    /// Loads the peers of a previous run. Peers that were banned stay banned.
+    pub fn load_peers(&mut self, records: Vec<PeerRecord>) {
+        for record in records {
+            let mut peer = Peer::new(record.kind, false, false);
+            peer.reputation = record.reputation;
+            peer.last_seen = record.last_seen;
+            peer.addr = record.addr;
+            if peer.is_banned() {
+                self.ban_list.ban_peer(record.peer_id);
+            }
+            self.peers.insert(record.peer_id, peer);
+        }
+    }
+
+    /// The current peer table, to be persisted across restarts.
+    pub fn peer_records(&self) -> Vec<PeerRecord> {
+        self.peers
+            .iter()
+            .map(|(peer_id, peer)| PeerRecord {
+                peer_id:    *peer_id,
+                kind:       peer.kind,
+                reputation: peer.reputation,
+                last_seen:  peer.last_seen,
+                addr:       peer.addr
+            })
+            .collect()
+    }
+
+    pub fn on_session_established(&mut self, peer_id: PeerId, addr: SocketAddr) {
+        let peer = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| Peer::new(PeerKind::Basic, false, false));
+        peer.connected = true;
+        peer.addr = Some(addr);
+        peer.last_seen = Some(unix_now());
+
+        // the peer was banned in a previous run
+        if peer.is_banned() {
+            peer.connected = false;
+            self.queued_actions
+                .push_back(PeerAction::DisconnectBannedIncoming { peer_id });
+        }
+    }
+
+    pub fn on_session_closed(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if peer.connected {
+                peer.last_seen = Some(unix_now());
+            }
+            peer.connected = false;
+        }
+    }
+
     pub fn change_weight(&mut self, peer_id: PeerId, weight: ReputationChangeKind) {
         if let Some(outcome) = self
             .peers
@@ -120,7 +182,7 @@ pub(crate) enum PeerCommand {
 }
 
 /// Represents the kind of peer
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PeerKind {
     /// Basic peer kind.
     #[default]
@@ -143,7 +205,11 @@ pub struct Peer {
     /// If the peer is trusted
     trusted:    bool,
     /// if peer is connected
-    connected:  bool
+    connected:  bool,
+    /// unix timestamp of the last time we had a session with the peer
+    last_seen:  Option<u64>,
+    /// the last address we had a session with the peer on
+    addr:       Option<SocketAddr>
 }
 
 /// Outcomes when a reputation change is applied to a peer
@@ -162,7 +228,14 @@ enum ReputationChangeOutcome {
 
 impl Peer {
     fn new(kind: PeerKind, trusted: bool, connected: bool) -> Self {
-        Peer { reputation: DEFAULT_REPUTATION, kind, trusted, connected }
+        Peer {
+            reputation: DEFAULT_REPUTATION,
+            kind,
+            trusted,
+            connected,
+            last_seen: None,
+            addr: None
+        }
     }
 
     /// Resets the reputation of the peer to the default value. This always
@@ -217,6 +290,13 @@ impl Peer {
     }
 }
 
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 /// Actions the peer manager can trigger.
 #[derive(Debug)]
 pub enum PeerAction {
@@ -4,6 +4,8 @@ pub mod manager;
 mod reputation;
 pub use manager::*;
 pub use reputation::ReputationChangeKind;
+pub mod store;
+pub use store::{PeerRecord, PeerStore, PeerStoreError};
 
 /// Maximum number of available slots for outbound sessions.
 pub(crate) const DEFAULT_MAX_PEERS_OUTBOUND: usize = 100;
@@ -0,0 +1,110 @@
+//! On-disk copy of the peer table. Lets a restarted node reconnect to the
+//! peers it already knows and keep the bans of peers that misbehaved.
+
+use std::net::SocketAddr;
+
+use angstrom_types::persistence::{Persisted, PersistenceError};
+use reth_network_peers::PeerId;
+use serde::{Deserialize, Serialize};
+
+use super::{reputation::is_banned_reputation, PeerKind};
+
+/// The current version of the peer store format. Needs to be bumped whenever
+/// the layout of [`PeerRecord`] changes.
+pub const PEER_STORE_VERSION: u8 = 1;
+
+pub type PeerStoreError = PersistenceError;
+
+/// A single entry of the peer table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerRecord {
+    pub peer_id:    PeerId,
+    pub kind:       PeerKind,
+    pub reputation: i32,
+    /// unix timestamp of the last time we had a session with the peer
+    pub last_seen:  Option<u64>,
+    /// the last address we had a session with the peer on
+    pub addr:       Option<SocketAddr>
+}
+
+impl PeerRecord {
+    pub fn is_banned(&self) -> bool {
+        is_banned_reputation(self.reputation)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerStore {
+    pub peers: Vec<PeerRecord>
+}
+
+impl Persisted for PeerStore {
+    const VERSION: u8 = PEER_STORE_VERSION;
+}
+
+impl PeerStore {
+    pub fn new(peers: Vec<PeerRecord>) -> Self {
+        Self { peers }
+    }
+
+    /// The peers worth dialing on startup, trusted peers first and then the
+    /// most recently seen ones.
+    pub fn reconnect_candidates(&self) -> Vec<(PeerId, SocketAddr)> {
+        let mut candidates = self
+            .peers
+            .iter()
+            .filter(|peer| !peer.is_banned())
+            .filter_map(|peer| peer.addr.map(|addr| (peer, addr)))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(peer, _)| {
+            let trusted = matches!(peer.kind, PeerKind::Trusted | PeerKind::TrustedMevGuard);
+            (std::cmp::Reverse(trusted), std::cmp::Reverse(peer.last_seen))
+        });
+
+        candidates
+            .into_iter()
+            .map(|(peer, addr)| (peer.peer_id, addr))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::reputation::BANNED_REPUTATION;
+
+    fn record(byte: u8, kind: PeerKind, reputation: i32, last_seen: u64) -> PeerRecord {
+        PeerRecord {
+            peer_id: PeerId::with_last_byte(byte),
+            kind,
+            reputation,
+            last_seen: Some(last_seen),
+            addr: Some(SocketAddr::from(([127, 0, 0, 1], 30303 + byte as u16)))
+        }
+    }
+
+    #[test]
+    fn round_trips_and_orders_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peers.json");
+
+        let store = PeerStore::new(vec![
+            record(1, PeerKind::Basic, 0, 10),
+            record(2, PeerKind::Basic, 0, 20),
+            record(3, PeerKind::Trusted, 0, 5),
+            record(4, PeerKind::Basic, BANNED_REPUTATION - 1, 30),
+        ]);
+        store.write(&path).unwrap();
+
+        let read = PeerStore::read(&path).unwrap();
+        assert_eq!(read, store);
+        assert_eq!(
+            read.reconnect_candidates()
+                .into_iter()
+                .map(|(peer_id, _)| peer_id)
+                .collect::<Vec<_>>(),
+            vec![PeerId::with_last_byte(3), PeerId::with_last_byte(2), PeerId::with_last_byte(1)]
+        );
+    }
+}
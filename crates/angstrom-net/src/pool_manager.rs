@@ -6,47 +6,57 @@ use std::{
     num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll}
+    task::{Context, Poll},
+    time::Instant
 };
 
-use alloy::primitives::{Address, TxHash, B256};
+use alloy::primitives::{Address, BlockNumber, TxHash, B256};
+use angstrom_errors::{PoolError, ValidationError};
 use angstrom_eth::manager::EthEvent;
 use angstrom_types::{
     contract_bindings::pool_manager::PoolManager::{
         syncCall, PoolManagerCalls::updateDynamicLPFee
     },
     orders::{OrderOrigin, OrderSet},
-    primitive::{Order, PeerId},
+    primitive::{Order, PeerId, PoolId},
     sol_bindings::{
         grouped_orders::{
             AllOrders, FlashVariants, GroupedVanillaOrder, OrderWithStorageData, StandingVariants
         },
-        sol::TopOfBlockOrder,
+        rpc_orders::TopOfBlockOrder,
         RawPoolOrder
     }
 };
 use futures::{
     future::BoxFuture,
     poll,
-    stream::{BoxStream, FuturesUnordered},
+    stream::{BoxStream, FuturesOrdered, FuturesUnordered},
     Future, FutureExt, Stream, StreamExt
 };
 use order_pool::{
-    order_storage::OrderStorage, OrderIndexer, OrderPoolHandle, PoolConfig, PoolInnerEvent,
-    PoolManagerUpdate
+    audit::OrderTrailEntry,
+    order_storage::{OrderCursor, OrderFilter, OrderPage, OrderStorage},
+    surveillance::{BlockOrderActivity, OrderStatus, PoolActivity},
+    twap::{TwapError, TwapInstruction, TwapStatus},
+    BookSnapshot, OrderIndexer, OrderPoolHandle, PoolConfig, PoolInnerEvent, SequencedUpdate
 };
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_network::transactions::ValidationOutcome;
 use reth_tasks::TaskSpawner;
-use tokio::sync::{
-    broadcast,
-    broadcast::{Receiver, Sender},
-    mpsc,
-    mpsc::{error::SendError, unbounded_channel, UnboundedReceiver, UnboundedSender},
-    oneshot
+use tokio::{
+    sync::{
+        broadcast,
+        broadcast::{Receiver, Sender},
+        mpsc,
+        mpsc::{error::SendError, unbounded_channel, UnboundedReceiver, UnboundedSender},
+        oneshot
+    },
+    task::JoinHandle,
+    time::{Duration, Interval}
 };
 use tokio_stream::wrappers::{BroadcastStream, ReceiverStream, UnboundedReceiverStream};
 use validation::{
+    common::signer_cache::SignerCache,
     order::{
         self, order_validator::OrderValidator, OrderValidationRequest, OrderValidationResults,
         OrderValidatorHandle, ValidationFuture
@@ -55,25 +65,70 @@ use validation::{
 };
 
 use crate::{
-    LruCache, NetworkOrderEvent, ReputationChangeKind, StromMessage, StromNetworkEvent,
-    StromNetworkHandle
+    ingest::{IngestMiddleware, IngestPipeline},
+    leader_fast_path::LeaderFastPath,
+    order_fetcher::{OrderFetcher, MAX_ORDER_HASHES_PER_REQUEST, ORDER_REQUEST_TIMEOUT},
+    propagation::{PendingBatch, PropagationConfig, PropagationMetrics},
+    replication::MissedCancels,
+    LruCache, NetworkOrderEvent, ReplicatedUpdate, ReputationChangeKind, StromMessage,
+    StromNetworkEvent, StromNetworkHandle
 };
 
 /// Cache limit of transactions to keep track of for a single peer.
 const PEER_ORDER_CACHE_LIMIT: usize = 1024 * 10;
 
+/// Max updates per replication message, keeps replicating a large book well
+/// below the message size cap.
+const REPLICATION_BATCH_SIZE: usize = 1024;
+
+/// How often standing orders are checked for passed deadlines.
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Replicates the order pool to a second node, so that it can take over
+/// market making without having to rebuild its book first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationRole {
+    /// Streams validated orders and cancellations to the standby
+    Primary { standby: PeerId },
+    /// Applies the orders and cancellations streamed by the primary
+    Standby { primary: PeerId }
+}
+
 /// Api to interact with [`PoolManager`] task.
 #[derive(Debug, Clone)]
 pub struct PoolHandle {
     pub manager_tx:      UnboundedSender<OrderCommand>,
-    pub pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>
+    pub pool_manager_tx: tokio::sync::broadcast::Sender<SequencedUpdate>
 }
 
 #[derive(Debug)]
 pub enum OrderCommand {
     // new orders
     NewOrder(OrderOrigin, AllOrders, tokio::sync::oneshot::Sender<OrderValidationResults>),
-    CancelOrder(Address, B256, tokio::sync::oneshot::Sender<bool>)
+    NewGtcOrder(OrderOrigin, AllOrders, tokio::sync::oneshot::Sender<OrderValidationResults>),
+    NewGasCappedOrder {
+        origin:             OrderOrigin,
+        order:              AllOrders,
+        max_gas:            u128,
+        good_til_cancelled: bool,
+        validation_tx:      tokio::sync::oneshot::Sender<OrderValidationResults>
+    },
+    NewOrders(OrderOrigin, Vec<(AllOrders, tokio::sync::oneshot::Sender<OrderValidationResults>)>),
+    CancelOrder(Address, B256, tokio::sync::oneshot::Sender<bool>),
+    DisableAccount(Address, tokio::sync::oneshot::Sender<bool>),
+    EnableAccount(Address, tokio::sync::oneshot::Sender<bool>),
+    SubmitTwap(TwapInstruction, tokio::sync::oneshot::Sender<Result<B256, TwapError>>),
+    TwapStatus(B256, tokio::sync::oneshot::Sender<Option<TwapStatus>>),
+    PoolActivity(Option<PoolId>, tokio::sync::oneshot::Sender<Vec<PoolActivity>>),
+    BlockActivity(BlockNumber, tokio::sync::oneshot::Sender<Option<BlockOrderActivity>>),
+    OrderStatus(B256, tokio::sync::oneshot::Sender<Option<OrderStatus>>),
+    OrderTrail(B256, tokio::sync::oneshot::Sender<Vec<OrderTrailEntry>>),
+    BookSnapshot(PoolId, tokio::sync::oneshot::Sender<BookSnapshot>),
+    OrdersPage(OrderFilter, Option<OrderCursor>, usize, tokio::sync::oneshot::Sender<OrderPage>),
+    BestSearcherOrder(
+        PoolId,
+        tokio::sync::oneshot::Sender<Option<OrderWithStorageData<TopOfBlockOrder>>>
+    )
 }
 
 impl PoolHandle {
@@ -97,22 +152,170 @@ impl OrderPoolHandle for PoolHandle {
         self.send(OrderCommand::NewOrder(origin, order, tx)).is_ok();
         rx.map(|result| match result {
             Ok(OrderValidationResults::Valid(_)) => true,
-            Ok(OrderValidationResults::Invalid(_)) => false,
+            Ok(OrderValidationResults::Invalid(..)) => false,
+            Ok(OrderValidationResults::Rejected(..)) => false,
+            Ok(OrderValidationResults::SimFailed(..)) => false,
             Ok(OrderValidationResults::TransitionedToBlock) => false,
             Err(_) => false
         })
     }
 
-    fn subscribe_orders(&self) -> Receiver<PoolManagerUpdate> {
+    fn new_orders(
+        &self,
+        origin: OrderOrigin,
+        orders: Vec<AllOrders>
+    ) -> impl Future<Output = Vec<OrderValidationResults>> + Send {
+        let (orders, receivers): (Vec<_>, Vec<_>) = orders
+            .into_iter()
+            .map(|order| {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let hash = order.order_hash();
+                (
+                    (order, tx),
+                    rx.map(move |result| {
+                        result.unwrap_or_else(|_| {
+                            OrderValidationResults::Invalid(
+                                hash,
+                                ValidationError::Other("order pool stopped".to_string())
+                            )
+                        })
+                    })
+                )
+            })
+            .unzip();
+        self.send(OrderCommand::NewOrders(origin, orders));
+        futures::future::join_all(receivers)
+    }
+
+    fn new_gtc_order(
+        &self,
+        origin: OrderOrigin,
+        order: AllOrders
+    ) -> impl Future<Output = bool> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::NewGtcOrder(origin, order, tx))
+            .is_ok();
+        rx.map(|result| matches!(result, Ok(OrderValidationResults::Valid(_))))
+    }
+
+    fn new_gas_capped_order(
+        &self,
+        origin: OrderOrigin,
+        order: AllOrders,
+        max_gas: u128,
+        good_til_cancelled: bool
+    ) -> impl Future<Output = bool> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::NewGasCappedOrder {
+            origin,
+            order,
+            max_gas,
+            good_til_cancelled,
+            validation_tx: tx
+        })
+        .is_ok();
+        rx.map(|result| matches!(result, Ok(OrderValidationResults::Valid(_))))
+    }
+
+    fn subscribe_orders(&self) -> Receiver<SequencedUpdate> {
         self.pool_manager_tx.subscribe()
     }
 
+    fn book_snapshot(
+        &self,
+        pool_id: PoolId
+    ) -> impl Future<Output = Result<BookSnapshot, PoolError>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::BookSnapshot(pool_id, tx)).is_ok();
+        rx.map(|res| res.map_err(|_| PoolError::Unavailable))
+    }
+
+    fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> impl Future<Output = OrderPage> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::OrdersPage(filter, after, limit, tx))
+            .is_ok();
+        rx.map(|res| res.unwrap_or_default())
+    }
+
+    fn best_searcher_order(
+        &self,
+        pool_id: PoolId
+    ) -> impl Future<Output = Option<OrderWithStorageData<TopOfBlockOrder>>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::BestSearcherOrder(pool_id, tx))
+            .is_ok();
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn submit_twap(
+        &self,
+        instruction: TwapInstruction
+    ) -> impl Future<Output = Result<B256, TwapError>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::SubmitTwap(instruction, tx)).is_ok();
+        rx.map(|res| res.unwrap_or(Err(TwapError::Disabled)))
+    }
+
+    fn twap_status(&self, id: B256) -> impl Future<Output = Option<TwapStatus>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::TwapStatus(id, tx)).is_ok();
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn pool_activity(
+        &self,
+        pool_id: Option<PoolId>
+    ) -> impl Future<Output = Vec<PoolActivity>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::PoolActivity(pool_id, tx)).is_ok();
+        rx.map(|res| res.unwrap_or_default())
+    }
+
+    fn block_activity(
+        &self,
+        block_number: BlockNumber
+    ) -> impl Future<Output = Option<BlockOrderActivity>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::BlockActivity(block_number, tx))
+            .is_ok();
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn order_status(&self, order_hash: B256) -> impl Future<Output = Option<OrderStatus>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::OrderStatus(order_hash, tx)).is_ok();
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn order_trail(&self, order_hash: B256) -> impl Future<Output = Vec<OrderTrailEntry>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::OrderTrail(order_hash, tx)).is_ok();
+        rx.map(|res| res.unwrap_or_default())
+    }
+
     fn cancel_order(&self, from: Address, order_hash: B256) -> impl Future<Output = bool> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.send(OrderCommand::CancelOrder(from, order_hash, tx))
             .is_ok();
         rx.map(|res| res.unwrap_or(false))
     }
+
+    fn disable_account(&self, account: Address) -> impl Future<Output = bool> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::DisableAccount(account, tx)).is_ok();
+        rx.map(|res| res.unwrap_or(false))
+    }
+
+    fn enable_account(&self, account: Address) -> impl Future<Output = bool> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(OrderCommand::EnableAccount(account, tx)).is_ok();
+        rx.map(|res| res.unwrap_or(false))
+    }
 }
 
 pub struct PoolManagerBuilder<V>
@@ -125,7 +328,12 @@ where
     strom_network_events: UnboundedReceiverStream<StromNetworkEvent>,
     eth_network_events:   UnboundedReceiverStream<EthEvent>,
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
-    config:               PoolConfig
+    config:               PoolConfig,
+    replication:          Option<ReplicationRole>,
+    propagation:          PropagationConfig,
+    leader_fast_path:     Option<LeaderFastPath>,
+    ingest:               IngestPipeline,
+    signers:              SignerCache
 }
 
 impl<V> PoolManagerBuilder<V>
@@ -146,7 +354,12 @@ where
             network_handle,
             validator,
             order_storage,
-            config: Default::default()
+            config: Default::default(),
+            replication: None,
+            propagation: Default::default(),
+            leader_fast_path: None,
+            ingest: IngestPipeline::default(),
+            signers: SignerCache::default()
         }
     }
 
@@ -155,6 +368,39 @@ where
         self
     }
 
+    pub fn with_replication(mut self, role: ReplicationRole) -> Self {
+        self.replication = Some(role);
+        self
+    }
+
+    /// Overrides how long orders are batched per peer before they are
+    /// propagated, and how many go into a single message.
+    pub fn with_propagation(mut self, propagation: PropagationConfig) -> Self {
+        self.propagation = propagation;
+        self
+    }
+
+    /// Sends large top of block orders that arrive while the bids of a round
+    /// are aggregated straight to its leader, on top of gossiping them.
+    pub fn with_leader_fast_path(mut self, leader_fast_path: LeaderFastPath) -> Self {
+        self.leader_fast_path = Some(leader_fast_path);
+        self
+    }
+
+    /// Adds a stage that every network order event and order command passes
+    /// through before it is dispatched, after the stages added before it.
+    pub fn with_ingest_middleware(mut self, middleware: impl IngestMiddleware) -> Self {
+        self.ingest.push(middleware);
+        self
+    }
+
+    /// Shares the signer cache of the validator, see
+    /// [`OrderValidatorBuilder::signer_cache`](validation::OrderValidatorBuilder::signer_cache).
+    pub fn with_signer_cache(mut self, signers: SignerCache) -> Self {
+        self.signers = signers;
+        self
+    }
+
     pub fn with_storage(mut self, order_storage: Arc<OrderStorage>) -> Self {
         self.order_storage.insert(order_storage);
         self
@@ -165,7 +411,7 @@ where
         task_spawner: TP,
         tx: UnboundedSender<OrderCommand>,
         rx: UnboundedReceiver<OrderCommand>,
-        pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>
+        pool_manager_tx: tokio::sync::broadcast::Sender<SequencedUpdate>
     ) -> PoolHandle {
         let rx = UnboundedReceiverStream::new(rx);
         let order_storage = self
@@ -178,7 +424,11 @@ where
             order_storage.clone(),
             0,
             pool_manager_tx.clone()
-        );
+        )
+        .with_max_deadline_horizon(self.config.max_deadline_horizon)
+        .with_twap(self.config.twap_enabled)
+        .with_max_sim_failures(self.config.max_sim_failures)
+        .with_signer_cache(self.signers.clone());
 
         task_spawner.spawn_critical(
             "transaction manager",
@@ -188,8 +438,24 @@ where
                 order_events:         self.order_events,
                 peer_to_info:         HashMap::default(),
                 order_indexer:        inner,
+                signers:              self.signers,
+                recovering:           FuturesOrdered::new(),
                 network:              self.network_handle,
-                command_rx:           rx
+                command_rx:           rx,
+                replication:          self.replication,
+                replicated:           LruCache::new(
+                    NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
+                ),
+                missed_cancels:       MissedCancels::default(),
+                order_fetcher:        OrderFetcher::new(
+                    PEER_ORDER_CACHE_LIMIT,
+                    ORDER_REQUEST_TIMEOUT
+                ),
+                expiry_sweep:         tokio::time::interval(EXPIRY_SWEEP_INTERVAL),
+                propagation:          self.propagation,
+                propagation_metrics:  PropagationMetrics::default(),
+                leader_fast_path:     self.leader_fast_path,
+                ingest:               self.ingest
             })
         );
 
@@ -210,7 +476,11 @@ where
             order_storage.clone(),
             0,
             pool_manager_tx.clone()
-        );
+        )
+        .with_max_deadline_horizon(self.config.max_deadline_horizon)
+        .with_twap(self.config.twap_enabled)
+        .with_max_sim_failures(self.config.max_sim_failures)
+        .with_signer_cache(self.signers.clone());
 
         task_spawner.spawn_critical(
             "transaction manager",
@@ -220,8 +490,24 @@ where
                 order_events:         self.order_events,
                 peer_to_info:         HashMap::default(),
                 order_indexer:        inner,
+                signers:              self.signers,
+                recovering:           FuturesOrdered::new(),
                 network:              self.network_handle,
-                command_rx:           rx
+                command_rx:           rx,
+                replication:          self.replication,
+                replicated:           LruCache::new(
+                    NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()
+                ),
+                missed_cancels:       MissedCancels::default(),
+                order_fetcher:        OrderFetcher::new(
+                    PEER_ORDER_CACHE_LIMIT,
+                    ORDER_REQUEST_TIMEOUT
+                ),
+                expiry_sweep:         tokio::time::interval(EXPIRY_SWEEP_INTERVAL),
+                propagation:          self.propagation,
+                propagation_metrics:  PropagationMetrics::default(),
+                leader_fast_path:     self.leader_fast_path,
+                ingest:               self.ingest
             })
         );
 
@@ -235,6 +521,12 @@ where
 {
     /// access to validation and sorted storage of orders.
     order_indexer:        OrderIndexer<V>,
+    /// Shared with the order indexer, so the signers recovered here are cache
+    /// hits once the orders are admitted
+    signers:              SignerCache,
+    /// Orders from peers whose signers are being recovered on the blocking
+    /// pool, in the order they arrived
+    recovering:           FuturesOrdered<JoinHandle<(PeerId, Vec<AllOrders>)>>,
     /// Network access.
     network:              StromNetworkHandle,
     /// Subscriptions to all the strom-network related events.
@@ -249,7 +541,24 @@ where
     /// Incoming events from the ProtocolManager.
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
     /// All the connected peers.
-    peer_to_info:         HashMap<PeerId, StromPeer>
+    peer_to_info:         HashMap<PeerId, StromPeer>,
+    /// Whether we replicate our pool to a standby or are one ourselves.
+    replication:          Option<ReplicationRole>,
+    /// Orders we got from the primary, which already propagated them.
+    replicated:           LruCache<B256>,
+    /// Cancellations to replay once the standby reconnects
+    missed_cancels:       MissedCancels,
+    /// Announced orders we requested from a peer
+    order_fetcher:        OrderFetcher,
+    /// Evicts standing orders once their deadline passes
+    expiry_sweep:         Interval,
+    /// How orders are batched before they are propagated
+    propagation:          PropagationConfig,
+    propagation_metrics:  PropagationMetrics,
+    /// Sends late top of block orders straight to the round leader
+    leader_fast_path:     Option<LeaderFastPath>,
+    /// Stages incoming events and commands pass through before dispatch
+    ingest:               IngestPipeline
 }
 
 impl<V> PoolManager<V>
@@ -265,16 +574,27 @@ where
         _command_tx: UnboundedSender<OrderCommand>,
         command_rx: UnboundedReceiverStream<OrderCommand>,
         order_events: UnboundedMeteredReceiver<NetworkOrderEvent>,
-        pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>
+        pool_manager_tx: tokio::sync::broadcast::Sender<SequencedUpdate>
     ) -> Self {
         Self {
             strom_network_events,
             network,
+            signers: order_indexer.signer_cache().clone(),
+            recovering: FuturesOrdered::new(),
             order_indexer,
             peer_to_info: HashMap::new(),
             order_events,
             command_rx,
-            eth_network_events
+            eth_network_events,
+            replication: None,
+            replicated: LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()),
+            missed_cancels: MissedCancels::default(),
+            order_fetcher: OrderFetcher::new(PEER_ORDER_CACHE_LIMIT, ORDER_REQUEST_TIMEOUT),
+            expiry_sweep: tokio::time::interval(EXPIRY_SWEEP_INTERVAL),
+            propagation: Default::default(),
+            propagation_metrics: PropagationMetrics::default(),
+            leader_fast_path: None,
+            ingest: IngestPipeline::default()
         }
     }
 
@@ -283,10 +603,68 @@ where
             OrderCommand::NewOrder(origin, order, validation_response) => self
                 .order_indexer
                 .new_rpc_order(OrderOrigin::External, order, validation_response),
+            OrderCommand::NewGtcOrder(origin, order, validation_response) => self
+                .order_indexer
+                .new_gtc_order(OrderOrigin::External, order, validation_response),
+            OrderCommand::NewGasCappedOrder {
+                order,
+                max_gas,
+                good_til_cancelled,
+                validation_tx,
+                ..
+            } => self.order_indexer.new_gas_capped_order(
+                OrderOrigin::External,
+                order,
+                max_gas,
+                good_til_cancelled,
+                validation_tx
+            ),
+            OrderCommand::NewOrders(origin, orders) => self
+                .order_indexer
+                .new_rpc_orders(OrderOrigin::External, orders),
             OrderCommand::CancelOrder(from, order_hash, receiver) => {
                 let res = self.order_indexer.cancel_order(from, order_hash);
+                if res {
+                    self.replicate(vec![ReplicatedUpdate::CancelledOrder { from, order_hash }]);
+                }
                 receiver.send(res);
             }
+            OrderCommand::DisableAccount(account, receiver) => {
+                let res = self.order_indexer.disable_account(account);
+                receiver.send(res);
+            }
+            OrderCommand::EnableAccount(account, receiver) => {
+                let res = self.order_indexer.enable_account(account);
+                receiver.send(res);
+            }
+            OrderCommand::SubmitTwap(instruction, receiver) => {
+                let res = self.order_indexer.submit_twap(instruction);
+                receiver.send(res);
+            }
+            OrderCommand::TwapStatus(id, receiver) => {
+                receiver.send(self.order_indexer.twap_status(&id));
+            }
+            OrderCommand::PoolActivity(pool_id, receiver) => {
+                receiver.send(self.order_indexer.pool_activity(pool_id));
+            }
+            OrderCommand::BlockActivity(block_number, receiver) => {
+                receiver.send(self.order_indexer.block_activity(block_number));
+            }
+            OrderCommand::OrderStatus(order_hash, receiver) => {
+                receiver.send(self.order_indexer.order_status(&order_hash));
+            }
+            OrderCommand::OrderTrail(order_hash, receiver) => {
+                receiver.send(self.order_indexer.order_trail(&order_hash));
+            }
+            OrderCommand::BookSnapshot(pool_id, receiver) => {
+                receiver.send(self.order_indexer.book_snapshot(pool_id));
+            }
+            OrderCommand::OrdersPage(filter, after, limit, receiver) => {
+                receiver.send(self.order_indexer.orders_page(filter, after, limit));
+            }
+            OrderCommand::BestSearcherOrder(pool_id, receiver) => {
+                receiver.send(self.order_indexer.best_searcher_order(pool_id));
+            }
         }
     }
 
@@ -299,6 +677,9 @@ where
                     address_changeset
                 );
             }
+            EthEvent::ChainReorg { block_number, addresses } => {
+                self.order_indexer.chain_reorg(block_number, addresses);
+            }
             EthEvent::ReorgedOrders(orders) => {
                 self.order_indexer.reorg(orders);
             }
@@ -310,21 +691,106 @@ where
         }
     }
 
+    /// Admits orders from a peer once their signers are cached.
+    fn on_incoming_orders(&mut self, peer_id: PeerId, orders: Vec<AllOrders>) {
+        let rejected = orders
+            .into_iter()
+            .map(|order| {
+                self.order_fetcher.on_order_received(&order.order_hash());
+                self.peer_to_info
+                    .get_mut(&peer_id)
+                    .map(|peer| peer.orders.insert(order.order_hash()));
+
+                self.order_indexer
+                    .new_network_order(peer_id, OrderOrigin::External, order)
+            })
+            .collect::<Vec<_>>();
+        self.on_pool_events(rejected);
+    }
+
     fn on_network_order_event(&mut self, event: NetworkOrderEvent) {
         match event {
             NetworkOrderEvent::IncomingOrders { peer_id, orders } => {
                 tracing::debug!("recieved IncomingOrders from peer {:?}", peer_id);
-                orders.into_iter().for_each(|order| {
-                    self.peer_to_info
-                        .get_mut(&peer_id)
-                        .map(|peer| peer.orders.insert(order.order_hash()));
-
-                    self.order_indexer.new_network_order(
-                        peer_id,
-                        OrderOrigin::External,
-                        order.clone()
+                // ecrecover is too slow to run for a batch of orders on this task
+                let signers = self.signers.clone();
+                self.recovering
+                    .push_back(tokio::task::spawn_blocking(move || {
+                        orders.iter().for_each(|order| {
+                            signers.recover_signer(order);
+                        });
+                        (peer_id, orders)
+                    }));
+            }
+            NetworkOrderEvent::Replication { peer_id, updates } => {
+                if self.replication != Some(ReplicationRole::Standby { primary: peer_id }) {
+                    tracing::warn!(?peer_id, "ignoring order replication from a non primary peer");
+                    return
+                }
+
+                let events = updates
+                    .into_iter()
+                    .filter_map(|update| match update {
+                        ReplicatedUpdate::NewOrder(order) => {
+                            self.replicated.insert(order.order_hash());
+                            Some(self.order_indexer.new_replicated_order(order))
+                        }
+                        ReplicatedUpdate::CancelledOrder { from, order_hash } => {
+                            self.order_indexer.cancel_order(from, order_hash);
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                self.on_pool_events(events);
+            }
+            NetworkOrderEvent::OrderRejections { peer_id, order_hashes } => {
+                tracing::debug!(?peer_id, ?order_hashes, "peer rejected propagated orders");
+                // the peer already knows about these orders, so we treat them as seen to
+                // avoid forwarding them again
+                if let Some(peer) = self.peer_to_info.get_mut(&peer_id) {
+                    order_hashes.into_iter().for_each(|hash| {
+                        peer.orders.insert(hash);
+                    });
+                }
+            }
+            NetworkOrderEvent::OrderAnnouncements { peer_id, order_hashes } => {
+                if let Some(peer) = self.peer_to_info.get_mut(&peer_id) {
+                    peer.orders.extend(order_hashes.iter().copied());
+                }
+
+                // an order announced by several peers is only pulled from the first one,
+                // the others are asked if it doesn't answer in time
+                let unknown = order_hashes
+                    .into_iter()
+                    .filter(|hash| !self.order_indexer.is_known_order(hash));
+                let missing = self
+                    .order_fetcher
+                    .on_announcement(peer_id, unknown, Instant::now());
+                self.request_orders(peer_id, missing);
+            }
+            NetworkOrderEvent::OrderRequests { peer_id, mut order_hashes } => {
+                if order_hashes.len() > MAX_ORDER_HASHES_PER_REQUEST {
+                    tracing::debug!(
+                        ?peer_id,
+                        requested = order_hashes.len(),
+                        "peer requested too many orders at once, answering the first ones"
                     );
-                });
+                    order_hashes.truncate(MAX_ORDER_HASHES_PER_REQUEST);
+                }
+
+                let orders = self.order_indexer.pooled_orders(&order_hashes);
+                if let Some(peer) = self.peer_to_info.get_mut(&peer_id) {
+                    peer.orders
+                        .extend(orders.iter().map(|order| order.order_hash()));
+                }
+
+                self.propagation_metrics
+                    .orders_served
+                    .increment(orders.len() as u64);
+                for batch in orders.chunks(self.propagation.max_batch_size.max(1)) {
+                    self.network
+                        .send_message(peer_id, StromMessage::PropagatePooledOrders(batch.to_vec()));
+                }
             }
         }
     }
@@ -336,22 +802,45 @@ where
                 self.peer_to_info.insert(
                     peer_id,
                     StromPeer {
-                        orders: LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap())
+                        orders:  LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()),
+                        pending: PendingBatch::default()
                     }
                 );
+
+                // bring the standby up to date with our book and the cancellations it
+                // missed, from here on it gets every update as it happens
+                if self.replication == Some(ReplicationRole::Primary { standby: peer_id }) {
+                    let book = self.order_indexer.resting_orders(None);
+                    let cancels = self.missed_cancels.take();
+                    tracing::info!(
+                        ?peer_id,
+                        orders = book.len(),
+                        cancels = cancels.len(),
+                        "standby connected, replicating book"
+                    );
+                    self.replicate(
+                        book.into_iter()
+                            .map(ReplicatedUpdate::NewOrder)
+                            .chain(cancels)
+                            .collect()
+                    );
+                }
             }
             StromNetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peer_to_info.remove(&peer_id);
+                self.retry_requests_of(peer_id);
             }
             StromNetworkEvent::PeerRemoved(peer_id) => {
                 self.peer_to_info.remove(&peer_id);
+                self.retry_requests_of(peer_id);
             }
             StromNetworkEvent::PeerAdded(peer_id) => {
                 self.peer_to_info.insert(
                     peer_id,
                     StromPeer {
-                        orders: LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap())
+                        orders:  LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap()),
+                        pending: PendingBatch::default()
                     }
                 );
             }
@@ -372,27 +861,169 @@ where
                     });
                     None
                 }
+                PoolInnerEvent::RejectedOrder { order_hash, peer_id, penalize } => {
+                    if penalize {
+                        self.network.peer_reputation_change(
+                            peer_id,
+                            crate::ReputationChangeKind::InvalidOrder
+                        );
+                    }
+                    self.network
+                        .send_message(peer_id, StromMessage::OrderRejected(vec![order_hash]));
+                    None
+                }
                 PoolInnerEvent::None => None
             })
             .collect::<Vec<_>>();
 
+        self.replicate(
+            valid_orders
+                .iter()
+                .cloned()
+                .map(ReplicatedUpdate::NewOrder)
+                .collect()
+        );
+        self.forward_to_leader(&valid_orders);
         self.broadcast_orders_to_peers(valid_orders);
     }
 
+    /// Sends the top of block orders that take the fast path to the round
+    /// leader right away, the other peers get them with their batch.
+    fn forward_to_leader(&mut self, valid_orders: &[AllOrders]) {
+        let Some(fast_path) = &self.leader_fast_path else { return };
+        for order in valid_orders
+            .iter()
+            .filter(|order| !self.replicated.contains(&order.order_hash()))
+        {
+            let Some(leader) = fast_path.leader_for(order) else { continue };
+            let Some(peer) = self.peer_to_info.get_mut(&leader) else {
+                self.propagation_metrics
+                    .leader_fast_path_missed
+                    .increment(1);
+                continue
+            };
+            if !peer.orders.insert(order.order_hash()) {
+                continue
+            }
+
+            self.propagation_metrics.leader_fast_path.increment(1);
+            self.network
+                .send_message(leader, StromMessage::PropagatePooledOrders(vec![order.clone()]));
+        }
+    }
+
+    /// Requests the announced orders from the peer, in messages of at most
+    /// [`MAX_ORDER_HASHES_PER_REQUEST`] hashes.
+    fn request_orders(&self, peer_id: PeerId, order_hashes: Vec<B256>) {
+        if order_hashes.is_empty() {
+            return
+        }
+
+        self.propagation_metrics
+            .orders_requested
+            .increment(order_hashes.len() as u64);
+        for request in order_hashes.chunks(MAX_ORDER_HASHES_PER_REQUEST) {
+            self.network
+                .send_message(peer_id, StromMessage::GetPooledOrders(request.to_vec()));
+        }
+    }
+
+    /// Asks other peers for the orders the disconnected peer didn't send us.
+    fn retry_requests_of(&mut self, peer_id: PeerId) {
+        for (peer_id, order_hashes) in self.order_fetcher.on_peer_removed(peer_id, Instant::now()) {
+            self.request_orders(peer_id, order_hashes);
+        }
+    }
+
+    /// Asks other peers for the orders whose request timed out.
+    fn retry_timed_out_requests(&mut self) {
+        for (peer_id, order_hashes) in self.order_fetcher.retry_timed_out(Instant::now()) {
+            self.propagation_metrics
+                .order_requests_retried
+                .increment(order_hashes.len() as u64);
+            self.request_orders(peer_id, order_hashes);
+        }
+    }
+
+    /// Streams the updates to the standby if we are a primary, the
+    /// cancellations are kept for later while it is disconnected.
+    fn replicate(&mut self, updates: Vec<ReplicatedUpdate>) {
+        let Some(ReplicationRole::Primary { standby }) = self.replication else { return };
+        if !self.peer_to_info.contains_key(&standby) {
+            self.missed_cancels.on_missed(updates);
+            return
+        }
+
+        for batch in updates.chunks(REPLICATION_BATCH_SIZE) {
+            self.network
+                .send_message(standby, StromMessage::ReplicateOrders(batch.to_vec()));
+        }
+    }
+
+    /// Queues the orders for every peer that hasn't seen them yet. Peers
+    /// whose batch filled up get it right away, the others once their window
+    /// passes.
     fn broadcast_orders_to_peers(&mut self, valid_orders: Vec<AllOrders>) {
-        for order in valid_orders.iter() {
+        // the primary already propagated the orders it replicated to us
+        for order in valid_orders
+            .iter()
+            .filter(|order| !self.replicated.contains(&order.order_hash()))
+        {
+            let order_hash = order.order_hash();
             for (peer_id, info) in self.peer_to_info.iter_mut() {
-                let order_hash = order.order_hash();
-                if !info.orders.contains(&order_hash) {
-                    self.network.send_message(
+                if info.orders.contains(&order_hash) {
+                    continue
+                }
+                info.orders.insert(order_hash);
+                if let Some(batch) = info.pending.push(order.clone(), &self.propagation) {
+                    self.propagation_metrics.full_batches.increment(1);
+                    Self::send_batch(
+                        &self.network,
+                        &self.propagation,
+                        &self.propagation_metrics,
                         *peer_id,
-                        StromMessage::PropagatePooledOrders(vec![order.clone()])
+                        batch
                     );
-                    info.orders.insert(order_hash);
                 }
             }
         }
     }
+
+    /// Sends the batches whose window has passed.
+    fn flush_pending_batches(&mut self, cx: &mut Context<'_>) {
+        for (peer_id, info) in self.peer_to_info.iter_mut() {
+            if let Poll::Ready(batch) = info.pending.poll_flush(cx) {
+                Self::send_batch(
+                    &self.network,
+                    &self.propagation,
+                    &self.propagation_metrics,
+                    *peer_id,
+                    batch
+                );
+            }
+        }
+    }
+
+    fn send_batch(
+        network: &StromNetworkHandle,
+        config: &PropagationConfig,
+        metrics: &PropagationMetrics,
+        peer_id: PeerId,
+        batch: Vec<AllOrders>
+    ) {
+        metrics.messages_sent.increment(1);
+        metrics.orders_sent.increment(batch.len() as u64);
+        metrics.batch_size.record(batch.len() as f64);
+
+        let msg = if config.announce_hashes {
+            StromMessage::NewPooledOrderHashes(
+                batch.iter().map(|order| order.order_hash()).collect()
+            )
+        } else {
+            StromMessage::PropagatePooledOrders(batch)
+        };
+        network.send_message(peer_id, msg);
+    }
 }
 
 impl<V> Future for PoolManager<V>
@@ -417,12 +1048,29 @@ where
         // drain commands
         while let Poll::Ready(Some(cmd)) = this.command_rx.poll_next_unpin(cx) {
             tracing::debug!(?cmd, "that was a command");
-            this.on_command(cmd);
+            if let Some(cmd) = this.ingest.on_command(cmd) {
+                this.on_command(cmd);
+            }
         }
 
         // drain incoming transaction events
         while let Poll::Ready(Some(event)) = this.order_events.poll_next_unpin(cx) {
-            this.on_network_order_event(event);
+            if let Some(event) = this.ingest.on_network_order_event(event) {
+                this.on_network_order_event(event);
+            }
+        }
+
+        // admit the orders whose signers were recovered
+        while let Poll::Ready(Some(recovered)) = this.recovering.poll_next_unpin(cx) {
+            match recovered {
+                Ok((peer_id, orders)) => this.on_incoming_orders(peer_id, orders),
+                Err(e) => tracing::error!(%e, "recovering the signers of incoming orders failed")
+            }
+        }
+
+        while this.expiry_sweep.poll_tick(cx).is_ready() {
+            this.order_indexer.evict_expired_orders();
+            this.retry_timed_out_requests();
         }
 
         // poll underlying pool. This is the validation process that's being polled
@@ -430,6 +1078,8 @@ where
             this.on_pool_events(orders);
         }
 
+        this.flush_pending_batches(cx);
+
         Poll::Pending
     }
 }
@@ -447,6 +1097,31 @@ pub enum NetworkTransactionEvent {
 /// Tracks a single peer
 #[derive(Debug)]
 struct StromPeer {
-    /// Keeps track of transactions that we know the peer has seen.
-    orders: LruCache<B256>
+    /// Keeps track of the orders that we know the peer has seen, whether it
+    /// sent, announced or rejected them or we sent them to it. They are never
+    /// propagated to the peer again.
+    orders:  LruCache<B256>,
+    /// Orders waiting to be propagated to the peer.
+    pending: PendingBatch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn book_snapshot_fails_once_the_pool_stopped() {
+        let (manager_tx, manager_rx) = unbounded_channel();
+        let (pool_manager_tx, _) = broadcast::channel(1);
+        let handle = PoolHandle { manager_tx, pool_manager_tx };
+        drop(manager_rx);
+
+        assert_eq!(
+            handle
+                .book_snapshot(PoolId::with_last_byte(1))
+                .await
+                .unwrap_err(),
+            PoolError::Unavailable
+        );
+    }
 }
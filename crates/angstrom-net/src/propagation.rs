@@ -0,0 +1,137 @@
+//! Batching of the orders we gossip. Orders queue up per peer for a short
+//! window and go out as a single `PropagatePooledOrders` message, so that a
+//! burst of submissions doesn't turn into a message per order and peer.
+//! With `announce_hashes` set only the order hashes are sent, and peers pull
+//! the orders they are missing with `GetPooledOrders`.
+use std::{
+    pin::Pin,
+    task::{Context, Poll}
+};
+
+use angstrom_types::sol_bindings::grouped_orders::AllOrders;
+use futures::FutureExt;
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics
+};
+use tokio::time::{Duration, Sleep};
+
+/// How long an order waits for more orders to the same peer.
+pub const DEFAULT_BATCH_WINDOW: Duration = Duration::from_millis(50);
+
+/// Max orders per message, a full batch is sent without waiting out the
+/// window.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropagationConfig {
+    pub batch_window:    Duration,
+    pub max_batch_size:  usize,
+    /// announce order hashes instead of propagating the full orders
+    pub announce_hashes: bool
+}
+
+impl Default for PropagationConfig {
+    fn default() -> Self {
+        Self {
+            batch_window:    DEFAULT_BATCH_WINDOW,
+            max_batch_size:  DEFAULT_MAX_BATCH_SIZE,
+            announce_hashes: false
+        }
+    }
+}
+
+/// Orders queued for a single peer, flushed once the window of the oldest one
+/// passes or the batch is full.
+#[derive(Debug, Default)]
+pub(crate) struct PendingBatch {
+    orders:   Vec<AllOrders>,
+    flush_at: Option<Pin<Box<Sleep>>>
+}
+
+impl PendingBatch {
+    /// Queues the order, returning the batch if it is now full.
+    pub(crate) fn push(
+        &mut self,
+        order: AllOrders,
+        config: &PropagationConfig
+    ) -> Option<Vec<AllOrders>> {
+        self.orders.push(order);
+        if self.orders.len() >= config.max_batch_size.max(1) {
+            return Some(self.take())
+        }
+        if self.flush_at.is_none() {
+            self.flush_at = Some(Box::pin(tokio::time::sleep(config.batch_window)));
+        }
+
+        None
+    }
+
+    /// Resolves with the batch once its window has passed.
+    pub(crate) fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<Vec<AllOrders>> {
+        let Some(flush_at) = self.flush_at.as_mut() else { return Poll::Pending };
+        flush_at.poll_unpin(cx).map(|_| self.take())
+    }
+
+    fn take(&mut self) -> Vec<AllOrders> {
+        self.flush_at = None;
+        std::mem::take(&mut self.orders)
+    }
+}
+
+#[derive(Metrics)]
+#[metrics(scope = "strom.propagation")]
+pub(crate) struct PropagationMetrics {
+    /// Number of `PropagatePooledOrders` or `NewPooledOrderHashes` messages
+    /// sent
+    pub(crate) messages_sent:           Counter,
+    /// Number of orders sent or announced to peers, across all messages
+    pub(crate) orders_sent:             Counter,
+    /// Number of batches sent because they were full
+    pub(crate) full_batches:            Counter,
+    /// Number of orders per message
+    pub(crate) batch_size:              Histogram,
+    /// Number of announced orders we requested from peers
+    pub(crate) orders_requested:        Counter,
+    /// Number of orders sent in reply to `GetPooledOrders`
+    pub(crate) orders_served:           Counter,
+    /// Number of order requests retried with another peer after timing out
+    pub(crate) order_requests_retried:  Counter,
+    /// Number of top of block orders sent straight to the round leader
+    pub(crate) leader_fast_path:        Counter,
+    /// Number of top of block orders that qualified for the fast path while
+    /// we had no session with the leader
+    pub(crate) leader_fast_path_missed: Counter
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::poll_fn;
+
+    use super::*;
+
+    fn order() -> AllOrders {
+        AllOrders::TOB(Default::default())
+    }
+
+    #[tokio::test]
+    async fn flushes_when_full_or_after_the_window() {
+        let config = PropagationConfig {
+            batch_window: Duration::from_millis(10),
+            max_batch_size: 2,
+            ..Default::default()
+        };
+        let mut batch = PendingBatch::default();
+
+        assert!(batch.push(order(), &config).is_none());
+        assert_eq!(batch.push(order(), &config).map(|b| b.len()), Some(2));
+        assert!(batch
+            .poll_flush(&mut Context::from_waker(futures::task::noop_waker_ref()))
+            .is_pending());
+
+        assert!(batch.push(order(), &config).is_none());
+        let flushed = poll_fn(|cx| batch.poll_flush(cx)).await;
+        assert_eq!(flushed.len(), 1);
+        assert!(batch.orders.is_empty() && batch.flush_at.is_none());
+    }
+}
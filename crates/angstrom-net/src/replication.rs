@@ -0,0 +1,97 @@
+//! Cancellations the primary made while its standby was disconnected. The
+//! book replicated on reconnect only carries the orders still resting, so
+//! without replaying these the standby would keep the cancelled orders.
+use std::collections::VecDeque;
+
+use alloy::primitives::{Address, B256};
+
+use crate::ReplicatedUpdate;
+
+/// Most cancellations kept for a disconnected standby, the oldest are
+/// dropped past it.
+pub const MAX_MISSED_CANCELS: usize = 1024 * 16;
+
+#[derive(Debug)]
+pub struct MissedCancels {
+    cancels: VecDeque<(Address, B256)>,
+    limit:   usize
+}
+
+impl Default for MissedCancels {
+    fn default() -> Self {
+        Self::new(MAX_MISSED_CANCELS)
+    }
+}
+
+impl MissedCancels {
+    pub fn new(limit: usize) -> Self {
+        Self { cancels: VecDeque::new(), limit }
+    }
+
+    /// Keeps the cancellations among updates the standby didn't get, new
+    /// orders are left out as the standby gets the whole book on reconnect.
+    pub fn on_missed(&mut self, updates: impl IntoIterator<Item = ReplicatedUpdate>) {
+        for update in updates {
+            let ReplicatedUpdate::CancelledOrder { from, order_hash } = update else { continue };
+            if self.cancels.len() == self.limit {
+                tracing::warn!(?order_hash, "too many cancellations missed by the standby");
+                self.cancels.pop_front();
+            }
+            self.cancels.push_back((from, order_hash));
+        }
+    }
+
+    /// The cancellations to replay to the reconnected standby, oldest first.
+    pub fn take(&mut self) -> Vec<ReplicatedUpdate> {
+        self.cancels
+            .drain(..)
+            .map(|(from, order_hash)| ReplicatedUpdate::CancelledOrder { from, order_hash })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cancels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cancels.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256};
+    use angstrom_types::sol_bindings::{grouped_orders::AllOrders, rpc_orders::TopOfBlockOrder};
+
+    use super::MissedCancels;
+    use crate::ReplicatedUpdate;
+
+    fn cancel(n: u8) -> ReplicatedUpdate {
+        ReplicatedUpdate::CancelledOrder {
+            from:       Address::repeat_byte(n),
+            order_hash: B256::repeat_byte(n)
+        }
+    }
+
+    #[test]
+    fn replays_only_the_missed_cancellations_once() {
+        let mut missed = MissedCancels::default();
+        missed.on_missed([
+            cancel(1),
+            ReplicatedUpdate::NewOrder(AllOrders::TOB(TopOfBlockOrder::default())),
+            cancel(2)
+        ]);
+
+        assert_eq!(missed.take(), vec![cancel(1), cancel(2)]);
+        assert!(missed.take().is_empty());
+    }
+
+    #[test]
+    fn drops_the_oldest_cancellations_past_the_limit() {
+        let mut missed = MissedCancels::new(2);
+        missed.on_missed([cancel(1), cancel(2), cancel(3)]);
+
+        assert_eq!(missed.len(), 2);
+        assert_eq!(missed.take(), vec![cancel(2), cancel(3)]);
+    }
+}
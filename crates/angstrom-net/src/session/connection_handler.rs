@@ -92,6 +92,7 @@ impl ConnectionHandler for StromConnectionHandler {
         let handle = StromSessionHandle {
             direction,
             remote_id: peer_id,
+            remote_addr: self.socket_addr,
             established: Instant::now(),
             commands_to_session: tx
         };
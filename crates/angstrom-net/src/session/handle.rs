@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use angstrom_types::primitive::PeerId;
 use reth_network::Direction;
 use tokio::{sync::mpsc, time::Instant};
@@ -25,6 +27,8 @@ pub struct StromSessionHandle {
     pub(crate) direction:           Direction,
     /// The identifier of the remote peer
     pub(crate) remote_id:           PeerId,
+    /// The address of the remote peer
+    pub(crate) remote_addr:         SocketAddr,
     /// The timestamp when the session has been established.
     pub(crate) established:         Instant,
     /// Sender half of the command channel used send commands _to_ the spawned
@@ -101,9 +101,10 @@ impl StromSessionManager {
                     }
 
                     let event = SessionEvent::SessionEstablished {
-                        peer_id:   handle.remote_id,
-                        direction: handle.direction,
-                        timeout:   Arc::new(AtomicU64::new(40))
+                        peer_id:     handle.remote_id,
+                        remote_addr: handle.remote_addr,
+                        direction:   handle.direction,
+                        timeout:     Arc::new(AtomicU64::new(40))
                     };
                     self.active_sessions.insert(handle.remote_id, handle);
 
@@ -142,12 +143,14 @@ pub enum SessionEvent {
     /// This session is now able to exchange data.
     SessionEstablished {
         /// The remote node's public key
-        peer_id:   PeerId,
+        peer_id:     PeerId,
+        /// The remote node's socket address
+        remote_addr: SocketAddr,
         /// The direction of the session, either `Inbound` or `Outgoing`
-        direction: Direction,
+        direction:   Direction,
         /// The maximum time that the session waits for a response from the peer
         /// before timing out the connection
-        timeout:   Arc<AtomicU64>
+        timeout:     Arc<AtomicU64>
     },
     /// The peer was already connected with another session.
     AlreadyConnected {
@@ -81,6 +81,8 @@ pub struct StromSession {
     pub(crate) terminate_message: Option<(PollSender<StromSessionMessage>, StromSessionMessage)>,
     /// has a value until verification has been completed.
     pub verification_sidecar: VerificationSidecar,
+    /// whether the peer announced itself as relay only in its status
+    remote_relay_only: bool,
     /// has sent the handle to the receiver
     pending_handle: Option<StromSessionHandle>,
     /// buffer for pending messages
@@ -105,6 +107,7 @@ impl StromSession {
             to_session_manager,
             protocol_breach_request_timeout,
             terminate_message: None,
+            remote_relay_only: false,
             pending_handle: Some(handle),
             outbound_buffer: VecDeque::default()
         }
@@ -164,6 +167,11 @@ impl StromSession {
                     || Poll::Ready(None),
                     |msg| match msg {
                         SessionCommand::Disconnect { .. } => self.emit_disconnect(cx),
+                        SessionCommand::Message(msg) if self.skips_consensus(&msg) => {
+                            // make sure the commands queued behind it get polled
+                            cx.waker().wake_by_ref();
+                            Poll::Pending
+                        }
                         SessionCommand::Message(msg) => {
                             let msg = StromProtocolMessage {
                                 message_id: msg.message_id(),
@@ -186,6 +194,10 @@ impl StromSession {
         while let Poll::Ready(msg) = self.conn.poll_next_unpin(cx).map(|data| {
             data.map(|bytes| {
                 let msg = StromProtocolMessage::decode_message(&mut bytes.deref());
+                if msg.as_ref().is_ok_and(|m| self.skips_consensus(&m.message)) {
+                    tracing::trace!(peer = ?self.remote_peer_id, "dropping consensus message");
+                    return
+                }
 
                 let msg = msg
                     .map(|m| StromSessionMessage::ValidMessage {
@@ -235,6 +247,7 @@ impl StromSession {
                     msg.map_or(false, |msg| {
                         // first message has to be status
                         if let StromMessage::Status(status) = msg.message {
+                            self.remote_relay_only = status.state.relay_only;
                             self.verify_incoming_status(status)
                         } else {
                             false
@@ -259,12 +272,29 @@ impl StromSession {
         }
     }
 
+    /// Consensus messages are not exchanged when either side of the session is
+    /// relay only.
+    fn skips_consensus(&self, msg: &StromMessage) -> bool {
+        msg.is_consensus()
+            && (self.remote_relay_only || self.verification_sidecar.status.relay_only)
+    }
+
     fn verify_incoming_status(&self, status: Status) -> bool {
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis();
 
+        // peers need to agree on the order horizon, otherwise the books would diverge
+        if status.state.max_order_horizon != self.verification_sidecar.status.max_order_horizon {
+            tracing::debug!(
+                ours = self.verification_sidecar.status.max_order_horizon,
+                theirs = status.state.max_order_horizon,
+                "peer has a mismatched max order horizon"
+            );
+            return false
+        }
+
         let status_time = status.state.timestamp + STATUS_TIMESTAMP_TIMEOUT_MS;
         current_time <= status_time && status.verify() == Ok(self.remote_peer_id)
     }
@@ -25,6 +25,10 @@ impl<DB> StromState<DB> {
         Self { peers_manager: PeersManager::new(), db, validators, active_peers: HashSet::new() }
     }
 
+    pub fn peers(&self) -> &PeersManager {
+        &self.peers_manager
+    }
+
     pub fn peers_mut(&mut self) -> &mut PeersManager {
         &mut self.peers_manager
     }
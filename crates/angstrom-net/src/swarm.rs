@@ -5,6 +5,7 @@ use std::{
 
 use angstrom_types::primitive::PeerId;
 use futures::{Stream, StreamExt};
+use reth_eth_wire::DisconnectReason;
 
 use crate::{
     peers::PeersManager,
@@ -59,8 +60,14 @@ impl<DB: Unpin> Swarm<DB> {
             SessionEvent::ValidMessage { peer_id, message } => {
                 Some(SwarmEvent::ValidMessage { peer_id, msg: message.message })
             }
-            SessionEvent::Disconnected { peer_id } => Some(SwarmEvent::Disconnected { peer_id }),
-            SessionEvent::SessionEstablished { peer_id, direction, timeout } => {
+            SessionEvent::Disconnected { peer_id } => {
+                self.state.peers_mut().on_session_closed(peer_id);
+                Some(SwarmEvent::Disconnected { peer_id })
+            }
+            SessionEvent::SessionEstablished { peer_id, remote_addr, direction, timeout } => {
+                self.state
+                    .peers_mut()
+                    .on_session_established(peer_id, remote_addr);
                 Some(SwarmEvent::SessionEstablished { peer_id })
             }
             _ => None
@@ -68,7 +75,16 @@ impl<DB: Unpin> Swarm<DB> {
     }
 
     fn on_state_event(&mut self, action: StateEvent) -> Option<SwarmEvent> {
-        tracing::warn!(?action, "no impl");
+        match action {
+            StateEvent::Disconnect { peer_id, reason } => {
+                self.sessions.disconnect(peer_id, reason);
+            }
+            StateEvent::DisconnectBannedIncoming { peer_id } => {
+                self.sessions
+                    .disconnect(peer_id, Some(DisconnectReason::DisconnectRequested));
+            }
+            action => tracing::warn!(?action, "no impl")
+        }
         None
     }
 }
@@ -1,7 +1,10 @@
 #![allow(missing_docs)]
 use std::{fmt::Debug, sync::Arc};
 
-use alloy::rlp::{Buf, BufMut, Decodable, Encodable};
+use alloy::{
+    primitives::{Address, B256},
+    rlp::{Buf, BufMut, Decodable, Encodable}
+};
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
     sol_bindings::grouped_orders::AllOrders
@@ -19,18 +22,37 @@ use crate::Status;
 // https://github.com/ethereum/go-ethereum/blob/30602163d5d8321fbc68afdcbbaf2362b2641bde/eth/protocols/eth/protocol.go#L50
 pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
-const STROM_CAPABILITY: Capability = Capability::new_static("strom", 1);
-const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 5);
+/// Bumped whenever the wire format changes, so peers on different versions
+/// don't negotiate a session they can't decode.
+///
+/// - 2: `NewPooledOrderHashes` and `GetPooledOrders`
+/// - 3: `max_order_horizon` in the status handshake
+/// - 4: `ReplicateOrders`
+/// - 5: orders excluded from matching in proposals
+/// - 6: `OrderRejected`, which moved the ids of the messages after it up by
+///   one
+/// - 7: `relay_only` in the status handshake
+const STROM_CAPABILITY: Capability = Capability::new_static("strom", 7);
+const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 8);
 /// Represents message IDs for eth protocol messages.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StromMessageID {
-    Status     = 0,
+    Status               = 0,
     /// Consensus
-    PrePropose = 1,
-    Propose    = 2,
+    PrePropose           = 1,
+    Propose              = 2,
     /// Propagation messages that broadcast new orders to all peers
-    PropagatePooledOrders = 3
+    PropagatePooledOrders = 3,
+    /// Notice sent back to a peer that propagated orders which failed
+    /// validation deterministically
+    OrderRejected        = 4,
+    /// Order pool changes a primary node streams to its warm standby
+    ReplicateOrders      = 5,
+    /// Hashes of new orders, peers pull the bodies they are missing
+    NewPooledOrderHashes = 6,
+    /// Request for the bodies of announced orders
+    GetPooledOrders      = 7
 }
 
 impl Encodable for StromMessageID {
@@ -51,6 +73,10 @@ impl Decodable for StromMessageID {
             1 => StromMessageID::PrePropose,
             2 => StromMessageID::Propose,
             3 => StromMessageID::PropagatePooledOrders,
+            4 => StromMessageID::OrderRejected,
+            5 => StromMessageID::ReplicateOrders,
+            6 => StromMessageID::NewPooledOrderHashes,
+            7 => StromMessageID::GetPooledOrders,
             _ => return Err(alloy::rlp::Error::Custom("Invalid message ID"))
         };
         buf.advance(1);
@@ -114,7 +140,17 @@ pub enum StromMessage {
     Propose(Proposal),
 
     /// Propagation messages that broadcast new orders to all peers
-    PropagatePooledOrders(Vec<AllOrders>)
+    PropagatePooledOrders(Vec<AllOrders>),
+    /// Hashes of propagated orders that failed validation deterministically
+    /// (bad signature, expired) and so should not be forwarded again
+    OrderRejected(Vec<B256>),
+    /// Order pool changes a primary node streams to its warm standby
+    ReplicateOrders(Vec<ReplicatedUpdate>),
+    /// Hashes of new orders, announced instead of propagating the full orders
+    NewPooledOrderHashes(Vec<B256>),
+    /// Request for the announced orders we don't have yet, answered with
+    /// `PropagatePooledOrders`
+    GetPooledOrders(Vec<B256>)
 }
 impl StromMessage {
     /// Returns the message's ID.
@@ -123,9 +159,28 @@ impl StromMessage {
             StromMessage::Status(_) => StromMessageID::Status,
             StromMessage::PrePropose(_) => StromMessageID::PrePropose,
             StromMessage::Propose(_) => StromMessageID::Propose,
-            StromMessage::PropagatePooledOrders(_) => StromMessageID::PropagatePooledOrders
+            StromMessage::PropagatePooledOrders(_) => StromMessageID::PropagatePooledOrders,
+            StromMessage::OrderRejected(_) => StromMessageID::OrderRejected,
+            StromMessage::ReplicateOrders(_) => StromMessageID::ReplicateOrders,
+            StromMessage::NewPooledOrderHashes(_) => StromMessageID::NewPooledOrderHashes,
+            StromMessage::GetPooledOrders(_) => StromMessageID::GetPooledOrders
         }
     }
+
+    /// Whether the message is part of a consensus round, these are never
+    /// exchanged with relay only nodes.
+    pub fn is_consensus(&self) -> bool {
+        matches!(self, StromMessage::PrePropose(_) | StromMessage::Propose(_))
+    }
+}
+
+/// A change to the primary's order pool that the standby applies to its own.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplicatedUpdate {
+    /// An order that passed validation on the primary
+    NewOrder(AllOrders),
+    /// An order that was cancelled by its owner
+    CancelledOrder { from: Address, order_hash: B256 }
 }
 
 /// Represents broadcast messages of [`StromMessage`] with the same object that
@@ -155,3 +210,46 @@ impl StromBroadcastMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, B256},
+        rlp::Encodable
+    };
+
+    use super::{ReplicatedUpdate, StromMessage, StromMessageID, StromProtocolMessage};
+
+    #[test]
+    fn replication_message_round_trips() {
+        let message = StromMessage::ReplicateOrders(vec![ReplicatedUpdate::CancelledOrder {
+            from:       Address::with_last_byte(1),
+            order_hash: B256::with_last_byte(2)
+        }]);
+        let protocol_message = StromProtocolMessage { message_id: message.message_id(), message };
+
+        let mut buf = vec![];
+        protocol_message.encode(&mut buf);
+        let decoded = StromProtocolMessage::decode_message(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(decoded.message_id, StromMessageID::ReplicateOrders);
+        assert_eq!(decoded, protocol_message);
+    }
+
+    #[test]
+    fn order_announcements_round_trip() {
+        for message in [
+            StromMessage::NewPooledOrderHashes(vec![B256::with_last_byte(1)]),
+            StromMessage::GetPooledOrders(vec![B256::with_last_byte(1), B256::with_last_byte(2)])
+        ] {
+            let protocol_message =
+                StromProtocolMessage { message_id: message.message_id(), message };
+
+            let mut buf = vec![];
+            protocol_message.encode(&mut buf);
+            let decoded = StromProtocolMessage::decode_message(&mut buf.as_slice()).unwrap();
+
+            assert_eq!(decoded, protocol_message);
+        }
+    }
+}
@@ -8,6 +8,7 @@ use alloy::{
     rlp::{BufMut, BytesMut}
 };
 use angstrom_types::primitive::{PeerId, Signature};
+use order_pool::ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT;
 use serde::{Deserialize, Serialize};
 
 use crate::StatusBuilder;
@@ -38,7 +39,14 @@ impl Status {
 
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Status {{ version: {}, chain: {}}}", self.state.version, self.state.chain,)
+        write!(
+            f,
+            "Status {{ version: {}, chain: {}, max_order_horizon: {}, relay_only: {}}}",
+            self.state.version,
+            self.state.chain,
+            self.state.max_order_horizon,
+            self.state.relay_only
+        )
     }
 }
 
@@ -47,14 +55,21 @@ impl Debug for Status {
         if f.alternate() {
             write!(
                 f,
-                "Status {{\n\tversion: {:?},\n\tchain: {:?}}}",
-                self.state.version, self.state.chain,
+                "Status {{\n\tversion: {:?},\n\tchain: {:?},\n\tmax_order_horizon: \
+                 {:?},\n\trelay_only: {:?}}}",
+                self.state.version,
+                self.state.chain,
+                self.state.max_order_horizon,
+                self.state.relay_only
             )
         } else {
             write!(
                 f,
-                "Status {{ version: {:?}, chain: {:?}}}",
-                self.state.version, self.state.chain,
+                "Status {{ version: {:?}, chain: {:?}, max_order_horizon: {:?}, relay_only: {:?}}}",
+                self.state.version,
+                self.state.chain,
+                self.state.max_order_horizon,
+                self.state.relay_only
             )
         }
     }
@@ -68,17 +83,27 @@ pub struct StatusState {
     /// The chain id, as introduced in
     /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
     /// PROBLEM BINCODE
-    pub chain:     u64,
+    pub chain:             u64,
     /// The peer that a node is trying to establish a connection with
-    pub peer:      PeerId,
+    pub peer:              PeerId,
     /// The current timestamp. Used to make sure that the status message will
     /// expire
-    pub timestamp: u128
+    pub timestamp:         u128,
+    /// The maximum number of seconds into the future that an order deadline
+    /// can be set to. Both sides of the connection must agree on this value
+    pub max_order_horizon: u64,
+    /// set by nodes that only relay orders and don't take part in consensus,
+    /// they are never sent consensus messages
+    pub relay_only:        bool
 }
 
 impl StatusState {
     pub fn new(peer: PeerId) -> Self {
-        Self { peer, ..Default::default() }
+        Self {
+            peer,
+            max_order_horizon: ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+            ..Default::default()
+        }
     }
 
     pub fn with_peer(mut self, peer: PeerId) -> Self {
@@ -87,13 +112,16 @@ impl StatusState {
     }
 
     /// creates message for signing.
-    /// keccak256(version || peer || timestamp)
+    /// keccak256(version || chain || peer || timestamp || max_order_horizon ||
+    /// relay_only)
     pub fn to_message(&self) -> FixedBytes<32> {
-        let mut buf = BytesMut::with_capacity(113);
+        let mut buf = BytesMut::with_capacity(122);
         buf.put_u8(self.version);
         buf.put_u64(self.chain);
         buf.put(self.peer.0.as_ref());
         buf.put_u128(self.timestamp);
+        buf.put_u64(self.max_order_horizon);
+        buf.put_u8(self.relay_only as u8);
 
         keccak256(buf)
     }
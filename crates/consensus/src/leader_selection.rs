@@ -1,18 +1,27 @@
 use std::{
     cmp::Ordering,
-    collections::HashSet,
-    fs::File,
-    io::{self, Read, Write}
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf}
 };
 
 use alloy::primitives::BlockNumber;
-use angstrom_types::primitive::PeerId;
+use angstrom_types::{
+    persistence::{Migrations, Persisted, PersistenceError},
+    primitive::PeerId
+};
+
+/// Default directory the leader selection state is kept in.
+pub const DEFAULT_LEADER_SELECTION_DIR: &str = "./";
 
-const ROUND_ROBIN_CACHE: &str = "./";
+const STATE_FILE: &str = "state.json";
 
 // https://github.com/tendermint/tendermint/pull/2785#discussion_r235038971
 const PENALTY_FACTOR: f64 = 1.125;
 
+/// How many blocks of leader selection state are kept around to roll back to
+/// on a reorg.
+pub const MAX_REORG_DEPTH: usize = 64;
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AngstromValidator {
     peer_id:      PeerId,
@@ -24,6 +33,22 @@ impl AngstromValidator {
     pub fn new(name: PeerId, voting_power: u64) -> Self {
         AngstromValidator { peer_id: name, voting_power, priority: 0.0 }
     }
+
+    pub fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    pub fn voting_power(&self) -> u64 {
+        self.voting_power
+    }
+}
+
+/// The selection state right after the proposer of a block was chosen.
+#[derive(Clone, Debug)]
+struct SelectionCheckpoint {
+    validators:    HashSet<AngstromValidator>,
+    block_number:  BlockNumber,
+    last_proposer: Option<PeerId>
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -31,17 +56,52 @@ pub struct WeightedRoundRobin {
     validators:                HashSet<AngstromValidator>,
     new_joiner_penalty_factor: f64,
     block_number:              BlockNumber,
-    last_proposer:             Option<PeerId>
+    last_proposer:             Option<PeerId>,
+    #[serde(skip)]
+    checkpoints:               VecDeque<SelectionCheckpoint>,
+    #[serde(skip)]
+    state_file:                PathBuf
+}
+
+impl Persisted for WeightedRoundRobin {
+    const CHECKSUMMED: bool = true;
+    const VERSION: u8 = 2;
+
+    fn migrations() -> Migrations {
+        // the state was written without a version before, and without a
+        // checksum in version 1
+        Migrations::new()
+            .with_migration(0, Migrations::unchanged)
+            .with_migration(1, Migrations::unchanged)
+    }
 }
 
 impl WeightedRoundRobin {
-    pub fn new(validators: Vec<AngstromValidator>, block_number: BlockNumber) -> Self {
-        let file_path = format!("{}/state.json", ROUND_ROBIN_CACHE);
-        if let Ok(mut file) = File::open(file_path) {
-            let mut contents = String::new();
-            if file.read_to_string(&mut contents).is_ok() {
-                if let Ok(state) = serde_json::from_str(&contents) {
-                    return state;
+    /// Picks up the state left in `state_dir` by a previous run. A state that
+    /// can't be read, for instance because it is corrupt, is moved aside and
+    /// the selection starts over from `validators`.
+    pub fn new(
+        validators: Vec<AngstromValidator>,
+        block_number: BlockNumber,
+        state_dir: impl AsRef<Path>
+    ) -> Self {
+        let state_file = state_dir.as_ref().join(STATE_FILE);
+        match Self::read(&state_file) {
+            Ok(mut state) => {
+                state.state_file = state_file;
+                return state
+            }
+            Err(PersistenceError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                let corrupt = state_file.with_extension("json.corrupt");
+                tracing::warn!(
+                    %e,
+                    state_file = %state_file.display(),
+                    moved_to = %corrupt.display(),
+                    "leader selection state is unreadable, starting over from the validator set"
+                );
+                if let Err(e) = std::fs::rename(&state_file, &corrupt) {
+                    tracing::warn!(%e, "failed to move the unreadable leader selection state");
                 }
             }
         }
@@ -49,11 +109,14 @@ impl WeightedRoundRobin {
             validators: HashSet::from_iter(validators),
             new_joiner_penalty_factor: PENALTY_FACTOR,
             block_number,
-            last_proposer: None
+            last_proposer: None,
+            checkpoints: VecDeque::new(),
+            state_file
         }
     }
 
-    fn proposer_selection(&mut self) -> PeerId {
+    /// `None` when there is no validator to choose from.
+    fn proposer_selection(&mut self) -> Option<PeerId> {
         let total_voting_power: u64 = self.validators.iter().map(|v| v.voting_power).sum();
 
         let mut updated_validators = HashSet::new();
@@ -63,18 +126,13 @@ impl WeightedRoundRobin {
         }
         self.validators = updated_validators;
 
-        let mut proposer = self
-            .validators
-            .iter()
-            .max_by(Self::priority)
-            .unwrap()
-            .clone();
+        let mut proposer = self.validators.iter().max_by(Self::priority)?.clone();
         proposer.priority -= total_voting_power as f64;
         let proposer_name = proposer.peer_id;
 
         self.validators.replace(proposer);
 
-        proposer_name
+        Some(proposer_name)
     }
 
     fn priority(a: &&AngstromValidator, b: &&AngstromValidator) -> Ordering {
@@ -120,33 +178,75 @@ impl WeightedRoundRobin {
         }
     }
 
+    pub fn total_voting_power(&self) -> u64 {
+        self.validators.iter().map(|v| v.voting_power).sum()
+    }
+
+    pub fn validators(&self) -> Vec<AngstromValidator> {
+        self.validators.iter().cloned().collect()
+    }
+
     pub fn last_proposer(&self) -> Option<PeerId> {
         self.last_proposer
     }
 
     pub fn choose_proposer(&mut self, block_number: BlockNumber) -> Option<PeerId> {
-        // 1. this is not ideal, since on multi-block reorgs the same proposer will be
-        //    chosen for the length of the reorg
-        // 2. reverting the block number (self.block_number = block_number) is also not
-        //    ideal, since nodes who were offline
-        // will not have seen the reorg, thus would not have executed the extra rounds
-        // after this if statement
+        // reorgs roll the state back with `rollback_to` before the proposer of the new
+        // tip is chosen. only a reorg deeper than the kept checkpoints gets here, and
+        // it keeps the last proposer until the chain passes our height again
         if block_number <= self.block_number {
             return self.last_proposer;
         }
 
         let rounds_to_catchup = (block_number - self.block_number) as usize;
         let mut leader = None;
-        for _ in 0..rounds_to_catchup {
+        for round in 1..=rounds_to_catchup {
             self.center_priorities();
             self.scale_priorities();
-            leader = Some(self.proposer_selection());
-            self.last_proposer = leader
+            leader = self.proposer_selection();
+            self.last_proposer = leader;
+            self.checkpoint(self.block_number + round as u64);
         }
         self.block_number = block_number;
         leader
     }
 
+    fn checkpoint(&mut self, block_number: BlockNumber) {
+        if self.checkpoints.len() == MAX_REORG_DEPTH {
+            self.checkpoints.pop_front();
+        }
+        self.checkpoints.push_back(SelectionCheckpoint {
+            validators: self.validators.clone(),
+            block_number,
+            last_proposer: self.last_proposer
+        });
+    }
+
+    /// Rolls the selection state back to right after the proposer of the
+    /// given block was chosen, so that the blocks after it get their
+    /// proposers chosen again. Returns false if the block is older than the
+    /// kept checkpoints, in which case the state is left untouched.
+    pub fn rollback_to(&mut self, block_number: BlockNumber) -> bool {
+        if block_number >= self.block_number {
+            return true
+        }
+        let Some(position) = self
+            .checkpoints
+            .iter()
+            .rposition(|checkpoint| checkpoint.block_number == block_number)
+        else {
+            return false
+        };
+
+        self.checkpoints.truncate(position + 1);
+        let checkpoint = self.checkpoints[position].clone();
+        self.validators = checkpoint.validators;
+        self.block_number = checkpoint.block_number;
+        self.last_proposer = checkpoint.last_proposer;
+
+        true
+    }
+
     fn remove_validator(&mut self, peer_id: &PeerId) {
         let validator = AngstromValidator::new(*peer_id, 0);
         self.validators.remove(&validator);
@@ -159,18 +259,40 @@ impl WeightedRoundRobin {
         self.validators.insert(new_validator);
     }
 
-    pub fn save_state(&self) -> io::Result<()> {
-        let file_path = format!("{}/state.json", ROUND_ROBIN_CACHE);
-        let serialized = serde_json::to_string(self).unwrap();
-        let mut file = File::create(file_path)?;
-        file.write_all(serialized.as_bytes())?;
-        Ok(())
+    /// Replaces the validator set. Validators that stay keep their priority,
+    /// new ones join with the same penalty as [`Self::add_validator`].
+    pub fn set_validators(&mut self, validators: Vec<AngstromValidator>) {
+        let total_voting_power: u64 = validators.iter().map(|v| v.voting_power).sum();
+        let joiner_priority = -self.new_joiner_penalty_factor * total_voting_power as f64;
+        let mut previous = std::mem::take(&mut self.validators);
+        self.validators = validators
+            .into_iter()
+            .map(|mut validator| {
+                validator.priority = previous
+                    .take(&validator)
+                    .map_or(joiner_priority, |existing| existing.priority);
+                validator
+            })
+            .collect();
+    }
+
+    pub fn save_state(&self) -> Result<(), PersistenceError> {
+        if let Some(dir) = self.state_file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        self.write(&self.state_file)
     }
 }
 
 impl Drop for WeightedRoundRobin {
     fn drop(&mut self) {
-        self.save_state().unwrap();
+        if let Err(e) = self.save_state() {
+            tracing::error!(
+                %e,
+                state_file = %self.state_file.display(),
+                "failed to save the leader selection state"
+            );
+        }
     }
 }
 
@@ -194,9 +316,18 @@ mod tests {
 
     use super::*;
 
+    /// A directory of its own for every test, so they don't pick up each
+    /// other's state.
+    fn state_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("angstrom-leader-selection-{}", PeerId::random()))
+    }
+
     fn cleanup(vm: WeightedRoundRobin) {
+        let dir = vm.state_file.parent().map(Path::to_path_buf);
         drop(vm);
-        std::fs::remove_file(format!("{}/state.json", ROUND_ROBIN_CACHE)).unwrap_or(());
+        if let Some(dir) = dir {
+            std::fs::remove_dir_all(dir).unwrap_or(());
+        }
     }
 
     #[test]
@@ -211,7 +342,7 @@ mod tests {
             AngstromValidator::new(peers["Bob"].clone(), 200),
             AngstromValidator::new(peers["Charlie"].clone(), 300),
         ];
-        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default());
+        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default(), state_dir());
 
         fn simulate_rounds(algo: &mut WeightedRoundRobin, rounds: usize) -> HashMap<PeerId, usize> {
             let mut stats = HashMap::new();
@@ -253,7 +384,7 @@ mod tests {
             AngstromValidator::new(peers["Alice"].clone(), 100),
             AngstromValidator::new(peers["Bob"].clone(), 200),
         ];
-        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default());
+        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default(), state_dir());
 
         fn simulate_rounds(
             algo: &mut WeightedRoundRobin,
@@ -288,6 +419,86 @@ mod tests {
         cleanup(algo);
     }
 
+    #[test]
+    fn no_proposer_without_validators() {
+        let peer = PeerId::random();
+        let mut algo = WeightedRoundRobin::new(vec![], BlockNumber::default(), state_dir());
+        assert_eq!(algo.choose_proposer(1), None);
+        assert_eq!(algo.choose_proposer(2), None);
+
+        algo.set_validators(vec![AngstromValidator::new(peer, 100)]);
+        assert_eq!(algo.choose_proposer(3), Some(peer));
+
+        cleanup(algo);
+    }
+
+    #[test]
+    fn test_reorg_rollback() {
+        let validators = vec![
+            AngstromValidator::new(PeerId::random(), 100),
+            AngstromValidator::new(PeerId::random(), 200),
+            AngstromValidator::new(PeerId::random(), 300),
+        ];
+        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default(), state_dir());
+        let leaders = (1..=12)
+            .map(|i| algo.choose_proposer(i).unwrap())
+            .collect::<Vec<_>>();
+        // leaders[i - 1] is the leader of block i
+        let mut reference = WeightedRoundRobin {
+            validators:                algo.validators.clone(),
+            new_joiner_penalty_factor: PENALTY_FACTOR,
+            block_number:              algo.block_number,
+            last_proposer:             algo.last_proposer,
+            checkpoints:               algo.checkpoints.clone(),
+            state_file:                state_dir().join(STATE_FILE)
+        };
+
+        for depth in 1..=2u64 {
+            // the new tip replaces the last `depth` blocks
+            let tip = 12 - depth + 1;
+            assert!(algo.rollback_to(tip - 1));
+            assert_eq!(algo.choose_proposer(tip), Some(leaders[tip as usize - 1]));
+            // the chain moves on as if nothing happened
+            for block in tip + 1..=12 {
+                assert_eq!(algo.choose_proposer(block), Some(leaders[block as usize - 1]));
+            }
+        }
+        assert_eq!(algo.choose_proposer(13), reference.choose_proposer(13));
+
+        // too deep to roll back
+        assert!(!algo.rollback_to(0));
+
+        cleanup(reference);
+        cleanup(algo);
+    }
+
+    #[test]
+    fn test_set_validators() {
+        let (alice, bob, charlie) = (PeerId::random(), PeerId::random(), PeerId::random());
+        let validators = vec![AngstromValidator::new(alice, 100), AngstromValidator::new(bob, 200)];
+        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default(), state_dir());
+        algo.choose_proposer(1);
+
+        let priority = |algo: &WeightedRoundRobin, peer: PeerId| {
+            algo.validators
+                .iter()
+                .find(|v| v.peer_id == peer)
+                .map(|v| v.priority)
+        };
+        let alice_priority = priority(&algo, alice);
+        algo.set_validators(vec![
+            AngstromValidator::new(alice, 150),
+            AngstromValidator::new(charlie, 300),
+        ]);
+
+        assert_eq!(algo.total_voting_power(), 450);
+        assert_eq!(priority(&algo, alice), alice_priority);
+        assert_eq!(priority(&algo, bob), None);
+        assert_eq!(priority(&algo, charlie), Some(-PENALTY_FACTOR * 450.0));
+
+        cleanup(algo);
+    }
+
     #[test]
     fn test_save_load_state() {
         let peers = HashMap::from([
@@ -300,11 +511,12 @@ mod tests {
             AngstromValidator::new(peers["Bob"].clone(), 200),
             AngstromValidator::new(peers["Charlie"].clone(), 300),
         ];
-        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default());
+        let dir = state_dir();
+        let mut algo = WeightedRoundRobin::new(validators, BlockNumber::default(), &dir);
 
         algo.save_state().unwrap();
 
-        let mut loaded_algo = WeightedRoundRobin::new(vec![], BlockNumber::default());
+        let mut loaded_algo = WeightedRoundRobin::new(vec![], BlockNumber::default(), &dir);
 
         assert_eq!(algo.validators, loaded_algo.validators);
         assert_eq!(algo.new_joiner_penalty_factor, loaded_algo.new_joiner_penalty_factor);
@@ -313,4 +525,25 @@ mod tests {
         // important otherwise you'd be working with cached state
         cleanup(algo);
     }
+
+    #[test]
+    fn test_corrupt_state_starts_over() {
+        let dir = state_dir();
+        let validators = vec![AngstromValidator::new(PeerId::random(), 100)];
+        let algo = WeightedRoundRobin::new(validators.clone(), 5, &dir);
+        let state_file = algo.state_file.clone();
+        // saves the state
+        drop(algo);
+
+        let state = std::fs::read_to_string(&state_file).unwrap();
+        std::fs::write(&state_file, state.replace("\"block_number\":5", "\"block_number\":6"))
+            .unwrap();
+
+        let algo = WeightedRoundRobin::new(validators.clone(), 10, &dir);
+        assert_eq!(algo.block_number, 10);
+        assert_eq!(algo.validators(), validators);
+        assert!(state_file.with_extension("json.corrupt").exists());
+
+        cleanup(algo);
+    }
 }
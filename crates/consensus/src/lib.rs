@@ -2,15 +2,22 @@ mod leader_selection;
 mod manager;
 mod round;
 mod signer;
+mod simulation;
+pub mod slot_timing;
+pub mod status;
+pub mod submission;
+pub mod summary;
+pub mod validator_registry;
 
 use std::pin::Pin;
 
-use angstrom_types::consensus::{PreProposal, Proposal};
+use angstrom_types::consensus::{BundleAttestation, PreProposal, Proposal};
 use futures::Stream;
-pub use leader_selection::AngstromValidator;
+pub use leader_selection::{AngstromValidator, DEFAULT_LEADER_SELECTION_DIR};
 pub use manager::*;
 pub use round::ConsensusState;
 pub use signer::*;
+use tokio::sync::broadcast;
 
 #[derive(Debug, Clone)]
 pub enum ConsensusMessage {
@@ -33,3 +40,14 @@ pub trait ConsensusUpdater: Send + Sync + 'static {
     /// sends a new block to the consensus
     fn new_block(&self, block: ());
 }
+
+/// Gives access to the signed bundle attestations published by consensus
+pub trait AttestationSubscriptions: Send + Sync + 'static {
+    fn subscribe_attestations(&self) -> broadcast::Receiver<BundleAttestation>;
+}
+
+impl AttestationSubscriptions for broadcast::Sender<BundleAttestation> {
+    fn subscribe_attestations(&self) -> broadcast::Receiver<BundleAttestation> {
+        self.subscribe()
+    }
+}
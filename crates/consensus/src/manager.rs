@@ -3,10 +3,12 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     marker::PhantomData,
+    path::Path,
     pin::Pin,
     sync::{Arc, Mutex},
     task::{Context, Poll},
-    thread::current
+    thread::current,
+    time::Instant
 };
 
 use alloy::{
@@ -16,11 +18,13 @@ use alloy::{
     transports::Transport
 };
 use angstrom_metrics::ConsensusMetricsWrapper;
-use angstrom_network::{manager::StromConsensusEvent, Peer, StromMessage, StromNetworkHandle};
+use angstrom_network::{
+    manager::StromConsensusEvent, Peer, RoundLeader, StromMessage, StromNetworkHandle
+};
 use angstrom_types::{
-    consensus::{PreProposal, Proposal},
+    consensus::{BundleAttestation, PreProposal, Proposal},
     contract_payloads::angstrom::TopOfBlockOrder,
-    orders::PoolSolution,
+    orders::{PoolSolution, PriceLevelPriority},
     primitive::PeerId
 };
 use futures::{pin_mut, FutureExt, Stream, StreamExt};
@@ -29,18 +33,30 @@ use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_provider::{CanonStateNotification, CanonStateNotifications};
 use tokio::{
     select,
-    sync::mpsc::{channel, unbounded_channel, Receiver, Sender, UnboundedReceiver},
+    sync::{
+        broadcast,
+        mpsc::{channel, unbounded_channel, Receiver, Sender, UnboundedReceiver}
+    },
     task::{JoinHandle, JoinSet}
 };
 use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 use tracing::{error, warn};
+use validation::bundle::{BundlePoolSource, BundleSimulator};
 
 use crate::{
     leader_selection::WeightedRoundRobin,
     round::{BidAggregation, BidSubmission, ConsensusState, Finalization, RoundStateMachine},
+    slot_timing::SlotTiming,
+    status::{CurrentRound, RoundStatus},
+    submission::BundleSubmitterHandle,
+    summary::RoundSummaries,
+    validator_registry::ValidatorSetUpdate,
     AngstromValidator, ConsensusListener, ConsensusMessage, ConsensusUpdater, Signer
 };
 
+/// attestations are only kept around for subscribers that are lagging behind
+const ATTESTATION_CHANNEL_SIZE: usize = 100;
+
 pub struct ConsensusManager<P, TR, N> {
     current_height:         BlockNumber,
     leader_selection:       WeightedRoundRobin,
@@ -51,6 +67,23 @@ pub struct ConsensusManager<P, TR, N> {
 
     /// Track broadcasted messages to avoid rebroadcasting
     broadcasted_messages: HashSet<StromConsensusEvent>,
+    /// where the signed attestations of the bundles we see are published
+    attestations:         broadcast::Sender<BundleAttestation>,
+    round_summaries:      RoundSummaries,
+    /// where the leader of the current round is published for the order fast
+    /// path
+    round_leader:         RoundLeader,
+    current_round:        CurrentRound,
+    /// validator sets loaded from the stake registry, see
+    /// [`Self::with_validator_updates`]
+    validator_updates:    Option<UnboundedReceiver<ValidatorSetUpdate>>,
+    pending_validators:   Option<ValidatorSetUpdate>,
+    /// lands the bundles of our proposals on chain, see
+    /// [`Self::with_bundle_submitter`]
+    bundle_submitter:     Option<BundleSubmitterHandle>,
+    /// when the current phase of the round started
+    phase_started:        Instant,
+    metrics:              ConsensusMetricsWrapper,
     provider:             P,
     _phantom:             PhantomData<(TR, N)>
 }
@@ -83,42 +116,210 @@ where
         validators: Vec<AngstromValidator>,
         order_storage: Arc<OrderStorage>,
         current_height: BlockNumber,
-        provider: P
+        provider: P,
+        state_dir: impl AsRef<Path>
     ) -> Self {
         let ManagerNetworkDeps { network, canonical_block_stream, strom_consensus_event } = netdeps;
         let wrapped_broadcast_stream = BroadcastStream::new(canonical_block_stream);
-        let mut leader_selection = WeightedRoundRobin::new(validators.clone(), current_height);
-        let leader = leader_selection.choose_proposer(current_height).unwrap();
+        let mut leader_selection =
+            WeightedRoundRobin::new(validators.clone(), current_height, state_dir);
+        let metrics = ConsensusMetricsWrapper::new();
+        let leader = choose_round_leader(&mut leader_selection, current_height, &metrics);
+        // the voting powers need to match the ones used for leader selection, which
+        // might have been loaded from the cache
+        let validators = leader_selection.validators();
+        let round_summaries = RoundSummaries::new();
+        round_summaries.start_round(current_height, network.peer_count());
+        let round_leader = RoundLeader::new();
+        round_leader.start_round(current_height, leader);
+        let state_transition = RoundStateMachine::new(
+            current_height,
+            order_storage,
+            signer,
+            leader,
+            validators.clone(),
+            metrics.clone()
+        );
+        let current_round = CurrentRound::new();
+        current_round.set(Self::round_status(&state_transition));
         Self {
             strom_consensus_event,
             current_height,
             leader_selection,
-            state_transition: RoundStateMachine::new(
-                current_height,
-                order_storage,
-                signer,
-                leader,
-                validators.clone(),
-                ConsensusMetricsWrapper::new()
-            ),
+            state_transition,
             network,
             canonical_block_stream: wrapped_broadcast_stream,
             broadcasted_messages: HashSet::new(),
+            attestations: broadcast::channel(ATTESTATION_CHANNEL_SIZE).0,
+            round_summaries,
+            round_leader,
+            current_round,
+            validator_updates: None,
+            pending_validators: None,
+            bundle_submitter: None,
+            phase_started: Instant::now(),
+            metrics,
             provider,
             _phantom: PhantomData
         }
     }
 
+    /// Times the phases of every round against the beacon chain slots.
+    pub fn with_slot_timing(mut self, slot_timing: SlotTiming) -> Self {
+        self.state_transition = self.state_transition.with_slot_timing(slot_timing);
+        self
+    }
+
+    /// Simulates the bundle of our proposals before proposing them, leaving out
+    /// the orders that make it revert.
+    pub fn with_bundle_simulator(mut self, bundle_simulator: Arc<dyn BundleSimulator>) -> Self {
+        self.state_transition = self
+            .state_transition
+            .with_bundle_simulator(bundle_simulator);
+        self
+    }
+
+    /// Builds the bundles of proposals against the pools of the given source.
+    pub fn with_bundle_pools(mut self, bundle_pools: Arc<dyn BundlePoolSource>) -> Self {
+        self.state_transition = self.state_transition.with_bundle_pools(bundle_pools);
+        self
+    }
+
+    /// Ranks the orders at the same price by `priority` when matching and
+    /// laying out bundles.
+    pub fn with_price_level_priority(mut self, priority: PriceLevelPriority) -> Self {
+        self.state_transition = self.state_transition.with_price_level_priority(priority);
+        self
+    }
+
+    /// Submits the bundle of every proposal of ours to the submitter.
+    pub fn with_bundle_submitter(mut self, bundle_submitter: BundleSubmitterHandle) -> Self {
+        self.bundle_submitter = Some(bundle_submitter);
+        self
+    }
+
+    /// Publishes the bundle attestations on the given channel, so they can be
+    /// subscribed to before the manager is spawned.
+    pub fn with_attestations(mut self, attestations: broadcast::Sender<BundleAttestation>) -> Self {
+        self.attestations = attestations;
+        self
+    }
+
+    /// Records the summary of every round in the given history, so it can be
+    /// served before the manager is spawned.
+    pub fn with_round_summaries(mut self, round_summaries: RoundSummaries) -> Self {
+        round_summaries.start_round(self.current_height, self.network.peer_count());
+        self.round_summaries = round_summaries;
+        self
+    }
+
+    /// Publishes the leader of every round and whether its bids are being
+    /// aggregated to the given handle.
+    pub fn with_round_leader(mut self, round_leader: RoundLeader) -> Self {
+        round_leader.start_round(self.current_height, self.state_transition.round_leader());
+        self.round_leader = round_leader;
+        self
+    }
+
+    /// Publishes the state of the current round to the given handle.
+    pub fn with_current_round(mut self, current_round: CurrentRound) -> Self {
+        current_round.set(Self::round_status(&self.state_transition));
+        self.current_round = current_round;
+        self
+    }
+
+    /// Replaces the validator set with the ones received, each one from the
+    /// round of its activation block.
+    pub fn with_validator_updates(
+        mut self,
+        validator_updates: UnboundedReceiver<ValidatorSetUpdate>
+    ) -> Self {
+        self.validator_updates = Some(validator_updates);
+        self
+    }
+
+    fn round_status(state_transition: &RoundStateMachine) -> RoundStatus {
+        RoundStatus::new(
+            state_transition.current_state(),
+            state_transition.round_leader(),
+            state_transition.validators()
+        )
+    }
+
+    fn publish_round_status(&self) {
+        self.current_round
+            .set(Self::round_status(&self.state_transition));
+    }
+
+    pub fn subscribe_attestations(&self) -> broadcast::Receiver<BundleAttestation> {
+        self.attestations.subscribe()
+    }
+
+    /// Records the attestation in the audit log and sends it to the
+    /// subscribers.
+    fn publish_attestation(&self, attestation: BundleAttestation) {
+        tracing::info!(
+            target: "angstrom::audit",
+            block_height = attestation.block_height,
+            kind = ?attestation.kind,
+            proposer = %attestation.proposer,
+            bundle_hash = %attestation.bundle_hash,
+            order_count = attestation.order_count,
+            signature = ?attestation.signature,
+            "bundle attestation"
+        );
+        let _ = self.attestations.send(attestation);
+    }
+
     fn on_blockchain_state(&mut self, notification: CanonStateNotification) {
-        let new_block = notification.tip();
-        self.current_height = new_block.block.number;
-        let round_leader = self
-            .leader_selection
-            .choose_proposer(self.current_height)
-            .unwrap();
+        let new_height = notification.tip().block.number;
+        let is_reorg = matches!(notification, CanonStateNotification::Reorg { .. })
+            || new_height <= self.current_height;
+        if is_reorg {
+            // the new tip replaces every block from its height onwards, so leader
+            // selection has to pick the proposers of those blocks again
+            let rolled_back = new_height == 0 || self.leader_selection.rollback_to(new_height - 1);
+            warn!(
+                old_height = self.current_height,
+                new_height, rolled_back, "chain reorg, resetting round"
+            );
+        }
+        // only a round that got to finalization has it end with the new block
+        let elapsed = self.phase_started.elapsed().as_millis() as u64;
+        self.round_summaries.update(self.current_height, |round| {
+            if round.bid_aggregation_ms.is_some() {
+                round.finalization_ms = Some(elapsed);
+            }
+        });
+        self.phase_started = Instant::now();
+        self.round_summaries
+            .start_round(new_height, self.network.peer_count());
+
+        self.current_height = new_height;
+        let activates = self
+            .pending_validators
+            .as_ref()
+            .is_some_and(|update| update.activation_block <= new_height);
+        if let Some(update) = activates.then(|| self.pending_validators.take()).flatten() {
+            if update.activation_block < new_height {
+                warn!(
+                    activation_block = update.activation_block,
+                    "validator set loaded after its activation block"
+                );
+            }
+            tracing::info!(count = update.validators.len(), "updating the validator set");
+            self.leader_selection.set_validators(update.validators);
+            self.state_transition
+                .set_validators(self.leader_selection.validators());
+        }
+        let round_leader =
+            choose_round_leader(&mut self.leader_selection, self.current_height, &self.metrics);
         self.state_transition
             .reset_round(self.current_height, round_leader);
+        self.round_leader
+            .start_round(self.current_height, round_leader);
         self.broadcasted_messages.clear();
+        self.publish_round_status();
     }
 
     fn on_network_event(&mut self, event: StromConsensusEvent) {
@@ -154,29 +355,68 @@ where
                 self.network.broadcast_message(msg);
             }
         }
+        // pre-proposals are collected outside of the phase transitions
+        self.publish_round_status();
     }
 
     pub fn on_state_start(&mut self, new_stat: ConsensusState) {
+        let elapsed = self.phase_started.elapsed().as_millis() as u64;
+        self.phase_started = Instant::now();
+        self.round_summaries
+            .update(new_stat.block_height(), |round| match &new_stat {
+                ConsensusState::BidSubmission(_) => {}
+                ConsensusState::BidAggregation(_) => round.bid_submission_ms = Some(elapsed),
+                ConsensusState::Finalization(finalization) => {
+                    round.bid_aggregation_ms = Some(elapsed);
+                    round.bundle_hash = finalization
+                        .attestation
+                        .as_ref()
+                        .map(|attestation| attestation.bundle_hash);
+                }
+            });
+        // as the leader the late orders already are in our own pool
+        self.round_leader.set_aggregating(
+            new_stat.block_height(),
+            matches!(new_stat, ConsensusState::BidAggregation(_))
+                && !self.state_transition.i_am_leader()
+        );
+        self.publish_round_status();
+
         match new_stat {
             // means we transitioned from commit phase to bid submission.
             // nothing much to do here. we just wait sometime to accumulate orders
             ConsensusState::BidSubmission(BidSubmission { pre_proposals, .. }) => {}
             // means we transitioned from bid submission to aggregation, therefore we broadcast our
             // pre-proposal to the network
-            ConsensusState::BidAggregation(BidAggregation { pre_proposals, .. }) => {
-                self.network.broadcast_message(
-                    self.state_transition
-                        .my_pre_proposal(&pre_proposals)
-                        .unwrap()
-                );
-            }
+            ConsensusState::BidAggregation(BidAggregation {
+                block_height, pre_proposals, ..
+            }) => match self.state_transition.my_pre_proposal(&pre_proposals) {
+                Some(pre_proposal) => self.network.broadcast_message(pre_proposal),
+                None => {
+                    error!(block_height, "No pre-proposal of ours to broadcast");
+                    self.metrics.incr_missing_pre_proposals();
+                }
+            },
             // TODO: maybe trigger the round verification job after it has finished, if we are not a
             // leader
-            ConsensusState::Finalization(finalization) => {
-                // tell everyone what we sent out to Ethereum
-                if self.state_transition.i_am_leader() {
+            ConsensusState::Finalization(mut finalization) => {
+                if let Some(attestation) = finalization.attestation.take() {
+                    self.publish_attestation(attestation);
+                }
+                // tell everyone what we sent out to Ethereum, there is no proposal when
+                // ours failed to build or to simulate
+                if let Some(proposal) = finalization
+                    .proposal
+                    .filter(|_| self.state_transition.i_am_leader())
+                {
+                    // our proposal is only sent out along with its bundle
+                    if let (Some(submitter), Some(bundle)) =
+                        (self.bundle_submitter.as_ref(), finalization.bundle)
+                    {
+                        submitter.submit(proposal.block_height, bundle);
+                    }
                     self.network
-                        .broadcast_message(StromMessage::Propose(finalization.proposal.unwrap()))
+                        .broadcast_message(StromMessage::Propose(proposal))
                 }
             }
         }
@@ -191,6 +431,22 @@ where
     }
 }
 
+/// The leader of the round at `block_height`. Without validators to choose
+/// from nobody leads, and the round goes on without a proposal.
+fn choose_round_leader(
+    leader_selection: &mut WeightedRoundRobin,
+    block_height: BlockNumber,
+    metrics: &ConsensusMetricsWrapper
+) -> PeerId {
+    leader_selection
+        .choose_proposer(block_height)
+        .unwrap_or_else(|| {
+            error!(block_height, "No leader could be chosen, the round has no proposer");
+            metrics.incr_leaderless_rounds();
+            PeerId::default()
+        })
+}
+
 impl<P, TR, N> Future for ConsensusManager<P, TR, N>
 where
     P: Provider<TR, N> + Send + Sync + Unpin,
@@ -202,6 +458,14 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        if let Some(Poll::Ready(Some(validators))) = this
+            .validator_updates
+            .as_mut()
+            .map(|updates| updates.poll_recv(cx))
+        {
+            this.pending_validators = Some(validators);
+        }
+
         if let Poll::Ready(Some(msg)) = this.canonical_block_stream.poll_next_unpin(cx) {
             match msg {
                 Ok(notification) => this.on_blockchain_state(notification),
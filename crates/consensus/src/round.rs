@@ -5,16 +5,16 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll, Waker},
-    time::Duration
+    time::{Duration, SystemTime}
 };
 
 use alloy::primitives::BlockNumber;
 use angstrom_metrics::ConsensusMetricsWrapper;
 use angstrom_network::{manager::StromConsensusEvent, StromMessage};
 use angstrom_types::{
-    consensus::{PreProposal, Proposal},
+    consensus::{AttestationKind, BundleAttestation, PreProposal, Proposal},
     contract_payloads::angstrom::AngstromBundle,
-    orders::{OrderSet, PoolSolution},
+    orders::{OrderSet, PoolSolution, PriceLevelPriority},
     primitive::PeerId,
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
@@ -28,14 +28,46 @@ use matching_engine::MatchingManager;
 use order_pool::order_storage::OrderStorage;
 use serde::{Deserialize, Serialize};
 use tokio::time;
+use validation::bundle::{BundlePoolSource, BundlePools, BundleSimulator};
 
-use crate::{AngstromValidator, Signer};
+use crate::{simulation::simulated_proposal, slot_timing::SlotTiming, AngstromValidator, Signer};
 
-async fn build_proposal(pre_proposals: Vec<PreProposal>) -> Result<Vec<PoolSolution>, String> {
-    let matcher = MatchingManager {};
+async fn build_proposal(
+    pre_proposals: Vec<PreProposal>,
+    priority: PriceLevelPriority
+) -> Result<Vec<PoolSolution>, String> {
+    let matcher = MatchingManager::new(priority);
     matcher.build_proposal(pre_proposals).await
 }
 
+/// The bundle the proposal settles as on chain, which its attestations refer
+/// to by hash.
+pub(crate) fn proposal_bundle(
+    proposal: &Proposal,
+    pools: &BundlePools,
+    priority: PriceLevelPriority
+) -> eyre::Result<AngstromBundle> {
+    AngstromBundle::from_proposal(proposal, pools, priority)
+}
+
+/// Snapshots the pools the solutions settle in. Solutions can only be left out
+/// of the bundle when there are none.
+async fn solution_pools(
+    source: Option<&Arc<dyn BundlePoolSource>>,
+    solutions: &[PoolSolution]
+) -> eyre::Result<BundlePools> {
+    let pool_ids = solutions
+        .iter()
+        .map(|solution| solution.id)
+        .collect::<Vec<_>>();
+    if pool_ids.is_empty() {
+        return Ok(BundlePools::default())
+    }
+    let source = source.ok_or_else(|| eyre::eyre!("no pools to build the bundle against"))?;
+
+    source.bundle_pools(pool_ids).await
+}
+
 const INITIAL_STATE_DURATION: Duration = Duration::from_secs(3);
 
 pub struct RoundStateMachine {
@@ -45,8 +77,21 @@ pub struct RoundStateMachine {
     validators:             Vec<AngstromValidator>,
     order_storage:          Arc<OrderStorage>,
     initial_state_duration: Duration,
+    /// when set, phases end relative to the slot schedule instead of the
+    /// arrival of the block
+    slot_timing:            Option<SlotTiming>,
+    submission_deadline:    Option<SystemTime>,
+    /// when set, the bundle of our proposals is simulated before we propose
+    bundle_simulator:       Option<Arc<dyn BundleSimulator>>,
+    /// the pools bundles are built against, see [`Self::with_bundle_pools`]
+    bundle_pools:           Option<Arc<dyn BundlePoolSource>>,
+    /// how the orders at the same price are ranked, the same on every node
+    price_level_priority:   PriceLevelPriority,
     metrics:                ConsensusMetricsWrapper,
     transition_future:      Option<BoxFuture<'static, ConsensusState>>,
+    /// height of the last round we built a proposal for, as we must never sign
+    /// two proposals for the same height
+    proposed_height:        Option<BlockNumber>,
     initial_state_timer:    Option<Pin<Box<time::Sleep>>>,
     waker:                  Option<Waker>
 }
@@ -66,10 +111,16 @@ impl RoundStateMachine {
             round_leader,
             validators,
             initial_state_duration: INITIAL_STATE_DURATION,
+            slot_timing: None,
+            submission_deadline: None,
+            bundle_simulator: None,
+            bundle_pools: None,
+            price_level_priority: PriceLevelPriority::default(),
             order_storage,
             signer,
             metrics,
             transition_future: None,
+            proposed_height: None,
             initial_state_timer: Some(timer),
 
             waker: None /* provider,
@@ -77,10 +128,64 @@ impl RoundStateMachine {
         }
     }
 
+    /// Ends the bid submission of every round so that the bundle can be
+    /// submitted before the deadline of the next slot.
+    pub fn with_slot_timing(mut self, slot_timing: SlotTiming) -> Self {
+        self.slot_timing = Some(slot_timing);
+        self.initial_state_timer = Some(Box::pin(time::sleep(self.bid_submission_duration())));
+        self
+    }
+
+    /// Simulates the bundle of every proposal we build, leaving out the orders
+    /// that make it revert.
+    pub fn with_bundle_simulator(mut self, bundle_simulator: Arc<dyn BundleSimulator>) -> Self {
+        self.bundle_simulator = Some(bundle_simulator);
+        self
+    }
+
+    /// Builds the bundles of proposals against the given pools. Without them
+    /// only proposals that settle nothing have a bundle.
+    pub fn with_bundle_pools(mut self, bundle_pools: Arc<dyn BundlePoolSource>) -> Self {
+        self.bundle_pools = Some(bundle_pools);
+        self
+    }
+
+    pub fn with_price_level_priority(mut self, priority: PriceLevelPriority) -> Self {
+        self.price_level_priority = priority;
+        self
+    }
+
+    /// Time left for bid submission of a round starting now.
+    fn bid_submission_duration(&mut self) -> Duration {
+        let Some(slot_timing) = &self.slot_timing else { return self.initial_state_duration };
+        let now = SystemTime::now();
+        self.submission_deadline = Some(slot_timing.submission_deadline(now));
+
+        slot_timing.bid_submission_duration(now)
+    }
+
     pub fn my_id(&self) -> PeerId {
         self.signer.my_id
     }
 
+    pub fn round_leader(&self) -> PeerId {
+        self.round_leader
+    }
+
+    pub fn current_state(&self) -> &ConsensusState {
+        &self.current_state
+    }
+
+    pub fn validators(&self) -> &[AngstromValidator] {
+        &self.validators
+    }
+
+    /// Takes effect for the quorums of the current round already, so it
+    /// should only be called as a round starts.
+    pub fn set_validators(&mut self, validators: Vec<AngstromValidator>) {
+        self.validators = validators;
+    }
+
     pub fn is_leader(&self, node: PeerId) -> bool {
         self.round_leader == node
     }
@@ -89,14 +194,47 @@ impl RoundStateMachine {
         self.is_leader(self.my_id())
     }
 
-    pub fn has_quorum(&self, voters: usize) -> bool {
-        voters >= (self.validators.len() * 2) / 3 + 1
+    /// The combined voting power of the whole validator set.
+    pub fn total_voting_power(&self) -> u64 {
+        self.validators.iter().map(|v| v.voting_power()).sum()
+    }
+
+    /// The voting power of a single peer, zero if they are not a validator.
+    pub fn voting_power_of(&self, peer: &PeerId) -> u64 {
+        self.validators
+            .iter()
+            .find(|v| v.peer_id() == *peer)
+            .map(|v| v.voting_power())
+            .unwrap_or_default()
+    }
+
+    /// The combined voting power of a set of peers, where each peer is only
+    /// counted once.
+    pub fn voting_power<'a>(&self, voters: impl IntoIterator<Item = &'a PeerId>) -> u64 {
+        voters
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|peer| self.voting_power_of(peer))
+            .sum()
+    }
+
+    /// The minimum voting power needed for a quorum, strictly more than 2/3 of
+    /// the total.
+    pub fn quorum_threshold(&self) -> u64 {
+        // stakes are configurable, so doubling the total could overflow
+        let total = self.total_voting_power();
+        total / 3 * 2 + (total % 3) * 2 / 3 + 1
+    }
+
+    pub fn has_quorum<'a>(&self, voters: impl IntoIterator<Item = &'a PeerId>) -> bool {
+        self.voting_power(voters) >= self.quorum_threshold()
     }
 
     pub fn reset_round(&mut self, block: BlockNumber, leader: PeerId) {
         self.round_leader = leader;
         self.current_state = Self::initial_state(block);
-        self.initial_state_timer = Some(Box::pin(time::sleep(self.initial_state_duration)));
+        self.initial_state_timer = Some(Box::pin(time::sleep(self.bid_submission_duration())));
         self.transition_future = None;
     }
 
@@ -122,12 +260,17 @@ impl RoundStateMachine {
         let i_am_leader = self.i_am_leader();
         match strom_msg {
             StromConsensusEvent::PreProposal(_, pre_proposal) => {
-                // we do not want to allow another node to push us to transition
-                if !matches!(self.current_state, ConsensusState::BidAggregation(_)) {
+                if !pre_proposal.is_valid() {
                     return None;
                 }
 
-                if !pre_proposal.is_valid() {
+                // keep early pre-proposals around for once we start aggregating, but we
+                // do not want to allow another node to push us to transition
+                if let ConsensusState::BidSubmission(state) = &mut self.current_state {
+                    state.pre_proposals.insert(pre_proposal);
+                    return None;
+                }
+                if !matches!(self.current_state, ConsensusState::BidAggregation(_)) {
                     return None;
                 }
 
@@ -145,9 +288,7 @@ impl RoundStateMachine {
                         .insert(merged_pre_proposal.clone());
                     let pre_proposals = self.current_state.pre_proposals();
 
-                    if self.have_quorum(self.all_searcher_orders(pre_proposals))
-                        && self.have_quorum(self.all_limit_orders(pre_proposals))
-                    {
+                    if self.pre_proposals_have_quorum(pre_proposals) {
                         // send the quorum pre_proposal to the leader
                         return Some((
                             Some(self.round_leader.clone()),
@@ -160,56 +301,75 @@ impl RoundStateMachine {
 
                 // Leader path
                 self.current_state.pre_proposals_mut().insert(pre_proposal);
-                let pre_proposals = self.current_state.pre_proposals();
-                let block_height = self.current_state.block_height();
-
-                if self.have_quorum(self.all_searcher_orders(pre_proposals))
-                    && self.have_quorum(self.all_limit_orders(pre_proposals))
-                {
-                    self.force_transition(ConsensusState::Finalization(Finalization {
-                        block_height,
-                        proposal: None,
-                        pre_proposals: pre_proposals.clone()
-                    }));
-                    return None;
-                }
+                self.try_propose();
             }
             StromConsensusEvent::Proposal(msg_sender, proposal) => {
                 let Proposal {
                     source: proposal_sender, block_height: proposal_block_height, ..
                 } = proposal;
 
+                // any peer can send us a proposal, only the one of the round leader counts
+                // and the leader builds its own
+                if i_am_leader || proposal_sender != self.round_leader {
+                    tracing::warn!(
+                        %msg_sender,
+                        %proposal_sender,
+                        block_height = proposal_block_height,
+                        state = self.current_state.name(),
+                        i_am_leader,
+                        "Ignoring proposal that isn't from the round leader or reached the leader"
+                    );
+                    self.metrics.incr_ignored_proposals();
+                    return None
+                }
+
                 let pre_proposals = self.current_state.pre_proposals();
-                if proposal.is_valid() && !i_am_leader {
+                // only commit to proposals that are built from a quorum of pre-proposals
+                let has_quorum = self.has_quorum(proposal.preproposals().iter().map(|p| &p.source));
+                if proposal.is_valid() && has_quorum {
                     self.force_transition(ConsensusState::Finalization(Finalization {
                         block_height:  proposal_block_height,
                         proposal:      Some(proposal),
-                        pre_proposals: pre_proposals.clone()
+                        pre_proposals: pre_proposals.clone(),
+                        attestation:   None,
+                        bundle:        None
                     }));
                 }
-
-                if i_am_leader {
-                    panic!(
-                        "Message sender: {}, Proposal block height: {}, Proposal source: {}, \
-                         Current state {}, something is terribly wrong",
-                        msg_sender,
-                        proposal_block_height,
-                        proposal_sender,
-                        self.current_state.name()
-                    );
-                }
             }
         }
 
         None
     }
 
+    /// Moves the leader to finalization once the pre-proposals reach a quorum.
+    /// Only the first quorum of a round is proposed on.
+    fn try_propose(&mut self) {
+        let block_height = self.current_state.block_height();
+        if self.proposed_height == Some(block_height) {
+            return
+        }
+
+        let pre_proposals = self.current_state.pre_proposals();
+        if self.pre_proposals_have_quorum(pre_proposals) {
+            let pre_proposals = pre_proposals.clone();
+            self.proposed_height = Some(block_height);
+            self.force_transition(ConsensusState::Finalization(Finalization {
+                block_height,
+                proposal: None,
+                pre_proposals,
+                attestation: None,
+                bundle: None
+            }));
+        }
+    }
+
     fn generate_bid_aggregation(
         &self,
         block_height: BlockNumber,
         pre_proposals: &HashSet<PreProposal>
     ) -> BidAggregation {
-        let OrderSet { limit, searcher } = self.order_storage.get_all_orders();
+        // the book bid with stays pinned for readers until the next block
+        let OrderSet { limit, searcher } = self.order_storage.pin_snapshot().orders.clone();
         let mut pre_proposals = pre_proposals.clone();
 
         let pre_proposal = Self::generate_our_merged_pre_proposal(
@@ -257,42 +417,74 @@ impl RoundStateMachine {
         )
     }
 
+    /// The pre-proposals have a quorum once their sources hold more than 2/3 of
+    /// the voting power and every order in them is backed by more than 2/3 of
+    /// the voting power.
+    fn pre_proposals_have_quorum(&self, pre_proposals: &HashSet<PreProposal>) -> bool {
+        let voting_power = self.voting_power(pre_proposals.iter().map(|p| &p.source));
+        self.metrics
+            .set_quorum_progress(voting_power, self.quorum_threshold());
+
+        voting_power >= self.quorum_threshold()
+            && self.have_quorum(self.all_searcher_orders(pre_proposals))
+            && self.have_quorum(self.all_limit_orders(pre_proposals))
+    }
+
     fn all_searcher_orders(
         &self,
         pre_proposals: &HashSet<PreProposal>
-    ) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
+    ) -> Vec<(PeerId, OrderWithStorageData<TopOfBlockOrder>)> {
         pre_proposals
             .iter()
-            .flat_map(|pre_proposal| pre_proposal.searcher.clone())
+            .flat_map(|pre_proposal| {
+                pre_proposal
+                    .searcher
+                    .iter()
+                    .map(|order| (pre_proposal.source, order.clone()))
+            })
             .collect()
     }
 
     fn all_limit_orders(
         &self,
         pre_proposals: &HashSet<PreProposal>
-    ) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
+    ) -> Vec<(PeerId, OrderWithStorageData<GroupedVanillaOrder>)> {
         pre_proposals
             .iter()
-            .flat_map(|pre_proposal| pre_proposal.limit.clone())
+            .flat_map(|pre_proposal| {
+                pre_proposal
+                    .limit
+                    .iter()
+                    .map(|order| (pre_proposal.source, order.clone()))
+            })
             .collect()
     }
 
-    fn have_quorum<T: Hash + Eq + Clone>(&self, orders: Vec<OrderWithStorageData<T>>) -> bool {
-        orders.len() == self.filter_quorum_orders(orders).len()
+    fn have_quorum<T: Hash + Eq + Clone>(
+        &self,
+        orders: Vec<(PeerId, OrderWithStorageData<T>)>
+    ) -> bool {
+        let unique_orders = orders
+            .iter()
+            .map(|(_, order)| order)
+            .collect::<HashSet<_>>()
+            .len();
+        unique_orders == self.filter_quorum_orders(orders).len()
     }
 
+    /// Returns the orders that are backed by a quorum of voting power.
     fn filter_quorum_orders<T: Hash + Eq + Clone>(
         &self,
-        input: Vec<OrderWithStorageData<T>>
+        input: Vec<(PeerId, OrderWithStorageData<T>)>
     ) -> Vec<OrderWithStorageData<T>> {
         input
             .into_iter()
-            .fold(HashMap::new(), |mut acc, order| {
-                *acc.entry(order).or_insert(0) += 1;
+            .fold(HashMap::new(), |mut acc: HashMap<_, HashSet<PeerId>>, (source, order)| {
+                acc.entry(order).or_default().insert(source);
                 acc
             })
             .into_iter()
-            .filter(|(_, count)| self.has_quorum(*count))
+            .filter(|(_, voters)| self.has_quorum(voters))
             .map(|(order, _)| order)
             .collect()
     }
@@ -300,6 +492,10 @@ impl RoundStateMachine {
     fn force_transition(&mut self, mut new_state: ConsensusState) {
         let signer = self.signer.clone();
         let metrics = self.metrics.clone();
+        let submission_deadline = self.submission_deadline;
+        let bundle_simulator = self.bundle_simulator.clone();
+        let bundle_pools = self.bundle_pools.clone();
+        let priority = self.price_level_priority;
         let pre_proposal_height = self.current_state.block_height();
         let pre_proposals: Vec<PreProposal> =
             self.current_state.pre_proposals().iter().cloned().collect();
@@ -307,13 +503,46 @@ impl RoundStateMachine {
         self.transition_future = Some(Box::pin(async move {
             if let ConsensusState::Finalization(finalization) = &mut new_state {
                 // someone already proposed and we are not a leader
-                if finalization.proposal.is_some() {
-                    // TODO: use this opportunity to trigger the proposal validation
+                if let Some(proposal) = finalization.proposal.as_ref() {
+                    let matcher = MatchingManager::new(priority);
+                    let (verification, timer) =
+                        async_time_fn(|| matcher.verify_proposal(proposal)).await;
+                    metrics.set_proposal_verification_time(pre_proposal_height, timer);
+
+                    let kind = if verification.is_ok() {
+                        AttestationKind::Verified
+                    } else {
+                        AttestationKind::Rejected
+                    };
+                    let bundle = solution_pools(bundle_pools.as_ref(), &proposal.solutions)
+                        .await
+                        .and_then(|pools| proposal_bundle(proposal, &pools, priority));
+                    match bundle {
+                        Ok(bundle) => {
+                            finalization.attestation =
+                                Some(signer.sign_attestation(kind, proposal, &bundle));
+                        }
+                        Err(err) => tracing::error!(
+                            error = %err,
+                            block_height = pre_proposal_height,
+                            "Failed to build the bundle of the proposal"
+                        )
+                    }
+
+                    if let Err(err) = verification {
+                        tracing::error!(
+                            error = %err,
+                            block_height = pre_proposal_height,
+                            source = %proposal.source,
+                            "Rejecting proposal that doesn't match our matching"
+                        );
+                        finalization.proposal = None;
+                    }
                     return new_state;
                 }
 
                 let (proposal_result, timer) = async_time_fn(|| async {
-                    match build_proposal(pre_proposals.clone()).await {
+                    match build_proposal(pre_proposals.clone(), priority).await {
                         Ok(solutions) => {
                             let proposal =
                                 signer.sign_proposal(pre_proposal_height, pre_proposals, solutions);
@@ -325,12 +554,72 @@ impl RoundStateMachine {
                 .await;
                 metrics.set_proposal_build_time(pre_proposal_height, timer);
 
+                let pools = match &proposal_result {
+                    Ok(proposal) => {
+                        match solution_pools(bundle_pools.as_ref(), &proposal.solutions).await {
+                            Ok(pools) => Arc::new(pools),
+                            Err(err) => {
+                                tracing::error!(
+                                    error = %err,
+                                    block_height = pre_proposal_height,
+                                    "Failed to load the pools of our proposal"
+                                );
+                                metrics.incr_bundle_build_failures();
+                                return new_state
+                            }
+                        }
+                    }
+                    Err(_) => Default::default()
+                };
+
+                let proposal_result = match (proposal_result, bundle_simulator) {
+                    (Ok(proposal), Some(simulator)) => {
+                        match simulated_proposal(
+                            simulator,
+                            pools.clone(),
+                            priority,
+                            &signer,
+                            proposal,
+                            &metrics
+                        )
+                        .await
+                        {
+                            Some(proposal) => Ok(proposal),
+                            None => return new_state
+                        }
+                    }
+                    (proposal_result, _) => proposal_result
+                };
+
                 match proposal_result {
                     Ok(proposal) => {
-                        finalization.proposal = Some(proposal.clone());
-                        // TODO: use the actual pools
-                        let pools = HashMap::new();
-                        let bundle = AngstromBundle::from_proposal(&proposal, &pools).unwrap();
+                        if submission_deadline.is_some_and(|deadline| SystemTime::now() > deadline)
+                        {
+                            tracing::warn!(
+                                block_height = pre_proposal_height,
+                                "Proposal was built after the submission deadline of the slot"
+                            );
+                        }
+                        // a proposal without a bundle can't be submitted, so it isn't sent out
+                        match proposal_bundle(&proposal, &pools, priority) {
+                            Ok(bundle) => {
+                                finalization.attestation = Some(signer.sign_attestation(
+                                    AttestationKind::Proposed,
+                                    &proposal,
+                                    &bundle
+                                ));
+                                finalization.proposal = Some(proposal.clone());
+                                finalization.bundle = Some(bundle);
+                            }
+                            Err(err) => {
+                                tracing::error!(
+                                    error = %err,
+                                    block_height = pre_proposal_height,
+                                    "Failed to build the bundle of our proposal"
+                                );
+                                metrics.incr_bundle_build_failures();
+                            }
+                        }
                     }
                     Err(err) => {
                         // Handle the error from build_proposal
@@ -360,15 +649,9 @@ impl Stream for RoundStateMachine {
 
         this.waker = Some(cx.waker().clone());
 
-        if let Some(future) = &mut this.transition_future {
-            return match future.as_mut().poll(cx) {
-                Poll::Ready(new_state) => Poll::Ready(Some(new_state)),
-                Poll::Pending => Poll::Pending
-            };
-        }
-
+        // a pending transition was forced by a message and takes precedence
         if let Some(timer) = &mut this.initial_state_timer {
-            if timer.as_mut().poll(cx).is_ready() {
+            if this.transition_future.is_none() && timer.as_mut().poll(cx).is_ready() {
                 if let ConsensusState::BidSubmission(BidSubmission {
                     block_height,
                     pre_proposals
@@ -378,12 +661,22 @@ impl Stream for RoundStateMachine {
                         this.generate_bid_aggregation(*block_height, pre_proposals);
                     this.transition_future =
                         Some(Box::pin(async { ConsensusState::BidAggregation(bid_aggregation) }));
-                    this.initial_state_timer = None;
                 }
+                this.initial_state_timer = None;
             }
         }
 
-        Poll::Pending
+        let Some(future) = &mut this.transition_future else { return Poll::Pending };
+        let Poll::Ready(new_state) = future.as_mut().poll(cx) else { return Poll::Pending };
+        this.transition_future = None;
+        this.current_state = new_state.clone();
+
+        // the early pre-proposals might already make up a quorum
+        if this.i_am_leader() && matches!(new_state, ConsensusState::BidAggregation(_)) {
+            this.try_propose();
+        }
+
+        Poll::Ready(Some(new_state))
     }
 }
 
@@ -404,7 +697,12 @@ pub struct BidAggregation {
 pub struct Finalization {
     pub block_height:  BlockNumber,
     pub pre_proposals: HashSet<PreProposal>,
-    pub proposal:      Option<Proposal>
+    pub proposal:      Option<Proposal>,
+    /// our signed take on the proposal, for the audit trail
+    pub attestation:   Option<BundleAttestation>,
+    /// the bundle of our own proposal, which is what gets submitted
+    #[serde(skip)]
+    pub bundle:        Option<AngstromBundle>
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -449,3 +747,464 @@ impl ConsensusState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+    use angstrom_types::{
+        matching::{
+            uniswap::{LiqRange, PoolSnapshot},
+            SqrtPriceX96
+        },
+        primitive::PoolId
+    };
+    use futures::{poll, StreamExt};
+    use proptest::prelude::*;
+    use reth_network_peers::pk2id;
+    use secp256k1::{Secp256k1, SecretKey};
+
+    use super::*;
+
+    const VALIDATORS: usize = 4;
+    const HEIGHT: BlockNumber = 10;
+
+    /// Something that can happen to a round before the network stabilizes.
+    /// Messages can arrive in any order, more than once or not at all.
+    #[derive(Debug, Clone)]
+    enum Event {
+        /// the bid submission phase times out
+        Timeout,
+        /// pre-proposal of the peer with the given index
+        PreProposal(usize),
+        /// proposal of the leader, built from all pre-proposals. It reaches the
+        /// leader as well, as peers can echo it back
+        Proposal,
+        /// proposal built from all pre-proposals but signed by the peer with
+        /// the given index, which isn't the leader
+        ForgedProposal(usize),
+        Poll
+    }
+
+    fn event() -> impl Strategy<Value = Event> {
+        prop_oneof![
+            Just(Event::Timeout),
+            (1..VALIDATORS).prop_map(Event::PreProposal),
+            Just(Event::Proposal),
+            (0..VALIDATORS).prop_map(Event::ForgedProposal),
+            Just(Event::Poll)
+        ]
+    }
+
+    struct Harness {
+        signers:  Vec<Signer>,
+        leader:   usize,
+        machine:  RoundStateMachine,
+        /// every state the machine transitioned to
+        observed: Vec<ConsensusState>
+    }
+
+    impl Harness {
+        /// We are the validator at index 0.
+        fn new(leader: usize) -> Self {
+            let secp = Secp256k1::new();
+            let signers = (0..VALIDATORS)
+                .map(|i| {
+                    let key = SecretKey::from_slice(&[i as u8 + 1; 32]).unwrap();
+                    Signer { my_id: pk2id(&key.public_key(&secp)), key }
+                })
+                .collect::<Vec<_>>();
+            let validators = signers
+                .iter()
+                .map(|signer| AngstromValidator::new(signer.my_id, 100))
+                .collect();
+            let machine = RoundStateMachine::new(
+                HEIGHT,
+                Arc::new(OrderStorage::default()),
+                signers[0].clone(),
+                signers[leader].my_id,
+                validators,
+                ConsensusMetricsWrapper::default()
+            );
+
+            Self { signers, leader, machine, observed: vec![] }
+        }
+
+        /// Gives the validator at each index the stake at the same index.
+        fn with_stakes(mut self, stakes: [u64; VALIDATORS]) -> Self {
+            let validators = self
+                .signers
+                .iter()
+                .zip(stakes)
+                .map(|(signer, stake)| AngstromValidator::new(signer.my_id, stake))
+                .collect();
+            self.machine.set_validators(validators);
+            self
+        }
+
+        fn peers(&self, indices: &[usize]) -> Vec<PeerId> {
+            indices.iter().map(|&i| self.signers[i].my_id).collect()
+        }
+
+        fn pre_proposal(&self, i: usize) -> PreProposal {
+            let signer = &self.signers[i];
+            PreProposal::generate_pre_proposal(HEIGHT, signer.my_id, vec![], vec![], &signer.key)
+        }
+
+        fn proposal(&self) -> Proposal {
+            self.proposal_from(self.leader)
+        }
+
+        /// A proposal built from all pre-proposals, signed by the peer with the
+        /// given index.
+        fn proposal_from(&self, i: usize) -> Proposal {
+            let pre_proposals = (0..VALIDATORS).map(|i| self.pre_proposal(i)).collect();
+            self.signers[i].sign_proposal(HEIGHT, pre_proposals, vec![])
+        }
+
+        fn finalized(&self) -> bool {
+            self.observed
+                .iter()
+                .any(|state| matches!(state, ConsensusState::Finalization(_)))
+        }
+
+        async fn apply(&mut self, event: Event) {
+            match event {
+                Event::Timeout => time::advance(INITIAL_STATE_DURATION).await,
+                Event::PreProposal(i) => {
+                    let msg = StromConsensusEvent::PreProposal(
+                        self.signers[i].my_id,
+                        self.pre_proposal(i)
+                    );
+                    self.machine.on_strom_message(msg);
+                }
+                Event::Proposal => {
+                    let msg = StromConsensusEvent::Proposal(
+                        self.signers[self.leader].my_id,
+                        self.proposal()
+                    );
+                    self.machine.on_strom_message(msg);
+                }
+                Event::ForgedProposal(i) if i == self.leader => {}
+                Event::ForgedProposal(i) => {
+                    let msg =
+                        StromConsensusEvent::Proposal(self.signers[i].my_id, self.proposal_from(i));
+                    self.machine.on_strom_message(msg);
+                }
+                Event::Poll => {}
+            }
+            self.drive().await;
+        }
+
+        async fn drive(&mut self) {
+            while let std::task::Poll::Ready(Some(state)) = poll!(self.machine.next()) {
+                self.observed.push(state);
+            }
+        }
+
+        fn check_safety(&self) {
+            let phase = |state: &ConsensusState| match state {
+                ConsensusState::BidSubmission(_) => 0,
+                ConsensusState::BidAggregation(_) => 1,
+                ConsensusState::Finalization(_) => 2
+            };
+            assert!(self
+                .observed
+                .iter()
+                .all(|state| state.block_height() == HEIGHT));
+            assert!(
+                self.observed
+                    .windows(2)
+                    .all(|w| phase(&w[0]) <= phase(&w[1])),
+                "round went backwards: {:?}",
+                self.observed.iter().map(|s| s.name()).collect::<Vec<_>>()
+            );
+
+            let signed = self
+                .observed
+                .iter()
+                .filter_map(|state| match state {
+                    ConsensusState::Finalization(f) => f.proposal.as_ref(),
+                    _ => None
+                })
+                .filter(|proposal| proposal.source == self.signers[0].my_id)
+                .count();
+            assert!(signed <= 1, "signed {signed} proposals for one height");
+            if self.leader != 0 {
+                assert_eq!(signed, 0);
+            }
+
+            // only ever finalize on the proposal of the leader
+            let leader = self.signers[self.leader].my_id;
+            for state in &self.observed {
+                if let ConsensusState::Finalization(Finalization { proposal: Some(p), .. }) = state
+                {
+                    assert_eq!(p.source, leader, "finalized on a proposal not from the leader");
+                }
+            }
+        }
+
+        /// Once the network is synchronous, every peer sends its pre-proposal
+        /// again and the leader its proposal, which has to finalize the round.
+        async fn check_liveness(&mut self) {
+            self.apply(Event::Timeout).await;
+            for i in 1..VALIDATORS {
+                self.apply(Event::PreProposal(i)).await;
+            }
+            self.apply(Event::Proposal).await;
+            self.check_safety();
+
+            let proposal = self
+                .observed
+                .iter()
+                .rev()
+                .find_map(|state| match state {
+                    ConsensusState::Finalization(f) => f.proposal.as_ref(),
+                    _ => None
+                })
+                .expect("round didn't finalize");
+            assert_eq!(proposal.source, self.signers[self.leader].my_id);
+        }
+    }
+
+    fn block_on(f: impl Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    fn run(leader: usize, events: Vec<Event>) {
+        block_on(async {
+            let mut harness = Harness::new(leader);
+            for event in events {
+                harness.apply(event).await;
+                harness.check_safety();
+            }
+            harness.check_liveness().await;
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn leader_round(events in prop::collection::vec(event(), 0..24)) {
+            run(0, events);
+        }
+
+        #[test]
+        fn follower_round(events in prop::collection::vec(event(), 0..24)) {
+            run(1, events);
+        }
+    }
+
+    #[test]
+    fn leader_ignores_proposals() {
+        block_on(async {
+            let mut harness = Harness::new(0);
+            let msg =
+                StromConsensusEvent::Proposal(harness.signers[1].my_id, harness.proposal_from(1));
+            assert!(harness.machine.on_strom_message(msg).is_none());
+            harness.drive().await;
+
+            assert!(!harness.finalized());
+            harness.check_liveness().await;
+        });
+    }
+
+    #[test]
+    fn follower_ignores_proposals_not_from_the_leader() {
+        block_on(async {
+            let mut harness = Harness::new(1);
+            harness.apply(Event::Timeout).await;
+            let forged = harness.proposal_from(2);
+            assert!(forged.is_valid());
+            let msg = StromConsensusEvent::Proposal(harness.signers[2].my_id, forged);
+            assert!(harness.machine.on_strom_message(msg).is_none());
+            harness.drive().await;
+
+            assert!(!harness.finalized());
+            harness.check_liveness().await;
+        });
+    }
+
+    struct FailingSimulator;
+
+    impl BundleSimulator for FailingSimulator {
+        fn simulate(
+            &self,
+            _: &AngstromBundle,
+            _: BlockNumber
+        ) -> Result<(), validation::bundle::BundleSimError> {
+            Err(validation::bundle::BundleSimError::Simulation("no state".to_string()))
+        }
+    }
+
+    #[test]
+    fn leader_finalizes_without_a_proposal() {
+        block_on(async {
+            let mut harness = Harness::new(0);
+            harness.machine = harness
+                .machine
+                .with_bundle_simulator(Arc::new(FailingSimulator));
+            harness.apply(Event::PreProposal(1)).await;
+            harness.apply(Event::PreProposal(2)).await;
+            harness.apply(Event::Timeout).await;
+            // the simulation runs on the blocking pool
+            while !matches!(harness.observed.last(), Some(ConsensusState::Finalization(_))) {
+                let state = harness.machine.next().await.unwrap();
+                harness.observed.push(state);
+            }
+
+            assert!(matches!(
+                harness.observed.last(),
+                Some(ConsensusState::Finalization(Finalization { proposal: None, .. }))
+            ));
+            harness.check_safety();
+        });
+    }
+
+    #[test]
+    fn leader_proposes_on_early_quorum() {
+        block_on(async {
+            let mut harness = Harness::new(0);
+            harness.apply(Event::PreProposal(1)).await;
+            harness.apply(Event::PreProposal(2)).await;
+            assert!(harness.observed.is_empty());
+
+            harness.apply(Event::Timeout).await;
+            assert!(matches!(
+                harness.observed.last(),
+                Some(ConsensusState::Finalization(Finalization { proposal: Some(_), .. }))
+            ));
+            harness.check_safety();
+        });
+    }
+
+    #[test]
+    fn quorum_needs_strictly_more_than_two_thirds_of_the_stake() {
+        block_on(async {
+            let harness = Harness::new(0).with_stakes([100, 99, 1, 100]);
+            let machine = &harness.machine;
+            assert_eq!(machine.total_voting_power(), 300);
+            assert_eq!(machine.quorum_threshold(), 201);
+
+            // 2/3 - 1
+            assert!(!machine.has_quorum(&harness.peers(&[0, 1])));
+            // exactly 2/3
+            assert!(!machine.has_quorum(&harness.peers(&[0, 3])));
+            // 2/3 + 1
+            assert!(machine.has_quorum(&harness.peers(&[0, 2, 3])));
+            // voting twice doesn't count twice
+            assert!(!machine.has_quorum(&harness.peers(&[0, 3, 3])));
+            assert_eq!(machine.voting_power_of(&PeerId::random()), 0);
+        });
+    }
+
+    #[test]
+    fn quorum_threshold_does_not_overflow_with_large_stakes() {
+        block_on(async {
+            let stake = u64::MAX / 4;
+            let harness = Harness::new(0).with_stakes([stake; VALIDATORS]);
+            let machine = &harness.machine;
+            let total = machine.total_voting_power();
+            assert_eq!(total, stake * 4);
+            assert_eq!(machine.quorum_threshold() as u128, total as u128 * 2 / 3 + 1);
+
+            assert!(!machine.has_quorum(&harness.peers(&[0, 1])));
+            assert!(machine.has_quorum(&harness.peers(&[0, 1, 2])));
+        });
+    }
+
+    #[test]
+    fn quorum_follows_stake_not_head_count() {
+        block_on(async {
+            let harness = Harness::new(0).with_stakes([700, 100, 100, 100]);
+            let machine = &harness.machine;
+
+            // three of four validators but only 30% of the stake
+            assert!(!machine.has_quorum(&harness.peers(&[1, 2, 3])));
+            assert!(machine.has_quorum(&harness.peers(&[0])));
+        });
+    }
+
+    #[test]
+    fn leader_waits_for_a_stake_weighted_quorum() {
+        block_on(async {
+            let mut harness = Harness::new(0).with_stakes([100, 100, 100, 400]);
+            // three of four pre-proposals would be a quorum with equal stakes
+            harness.apply(Event::PreProposal(1)).await;
+            harness.apply(Event::PreProposal(2)).await;
+            harness.apply(Event::Timeout).await;
+            assert!(!harness
+                .observed
+                .iter()
+                .any(|state| matches!(state, ConsensusState::Finalization(_))));
+
+            harness.apply(Event::PreProposal(3)).await;
+            assert!(matches!(harness.observed.last(), Some(ConsensusState::Finalization(_))));
+            harness.check_safety();
+        });
+    }
+
+    /// Knows a fixed set of pools.
+    pub(crate) struct StaticPools(pub(crate) BundlePools);
+
+    impl BundlePoolSource for StaticPools {
+        fn bundle_pools(
+            &self,
+            pool_ids: Vec<PoolId>
+        ) -> BoxFuture<'static, eyre::Result<BundlePools>> {
+            let pools = pool_ids
+                .into_iter()
+                .map(|id| {
+                    let pool = self.0.get(&id).ok_or_else(|| eyre::eyre!("unknown pool"))?;
+                    Ok((id, pool.clone()))
+                })
+                .collect();
+            Box::pin(async move { pools })
+        }
+    }
+
+    /// A pool of tokens 1 and 2 with id 1.
+    pub(crate) fn pools() -> BundlePools {
+        let snapshot = PoolSnapshot::new(
+            vec![LiqRange::new(-6000, 6000, 1_000_000_000_000_000_000).unwrap()],
+            SqrtPriceX96::at_tick(0).unwrap()
+        )
+        .unwrap();
+
+        BundlePools::from([(
+            PoolId::with_last_byte(1),
+            (Address::with_last_byte(1), Address::with_last_byte(2), snapshot, 0)
+        )])
+    }
+
+    #[test]
+    fn proposals_are_bundled_against_their_pools() {
+        block_on(async {
+            let source: Arc<dyn BundlePoolSource> = Arc::new(StaticPools(pools()));
+            let solutions =
+                vec![PoolSolution { id: PoolId::with_last_byte(1), ..Default::default() }];
+            assert!(solution_pools(None, &solutions).await.is_err());
+            assert!(solution_pools(Some(&source), &[]).await.unwrap().is_empty());
+
+            let pools = solution_pools(Some(&source), &solutions).await.unwrap();
+            let proposal = Proposal { solutions, ..Default::default() };
+            let bundle = proposal_bundle(&proposal, &pools, Default::default()).unwrap();
+            assert_eq!(bundle.pairs.len(), 1);
+            assert_eq!(
+                bundle
+                    .assets
+                    .iter()
+                    .map(|asset| asset.addr)
+                    .collect::<Vec<_>>(),
+                [Address::with_last_byte(1), Address::with_last_byte(2)]
+            );
+
+            let unknown =
+                vec![PoolSolution { id: PoolId::with_last_byte(2), ..Default::default() }];
+            assert!(solution_pools(Some(&source), &unknown).await.is_err());
+        });
+    }
+}
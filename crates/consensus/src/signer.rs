@@ -1,6 +1,7 @@
-use alloy::primitives::{BlockNumber, FixedBytes};
+use alloy::primitives::{BlockNumber, FixedBytes, B256};
 use angstrom_types::{
-    consensus::{PreProposal, Proposal},
+    consensus::{AttestationKind, BundleAttestation, PreProposal, Proposal},
+    contract_payloads::angstrom::AngstromBundle,
     orders::PoolSolution,
     primitive::PeerId
 };
@@ -35,4 +36,30 @@ impl Signer {
     ) -> Proposal {
         Proposal::generate_proposal(ethereum_block, self.my_id, preproposals, solutions, &self.key)
     }
+
+    pub fn sign_trimmed_proposal(
+        &self,
+        ethereum_block: BlockNumber,
+        preproposals: Vec<PreProposal>,
+        solutions: Vec<PoolSolution>,
+        excluded: Vec<B256>
+    ) -> Proposal {
+        Proposal::generate_trimmed_proposal(
+            ethereum_block,
+            self.my_id,
+            preproposals,
+            solutions,
+            excluded,
+            &self.key
+        )
+    }
+
+    pub fn sign_attestation(
+        &self,
+        kind: AttestationKind,
+        proposal: &Proposal,
+        bundle: &AngstromBundle
+    ) -> BundleAttestation {
+        BundleAttestation::new(kind, proposal, bundle, self.my_id, &self.key)
+    }
 }
@@ -0,0 +1,364 @@
+//! Simulates the bundle of our proposal before it is sent out. Orders that
+//! make the bundle revert are left out of the proposal, so that the leader
+//! never submits a bundle that reverts.
+use std::sync::Arc;
+
+use alloy::primitives::B256;
+use angstrom_metrics::ConsensusMetricsWrapper;
+use angstrom_types::{
+    consensus::{PreProposal, Proposal},
+    orders::{PoolSolution, PriceLevelPriority}
+};
+use matching_engine::manager::MatchingManager;
+use tokio::runtime::Handle;
+use validation::bundle::{BundlePools, BundleSimError, BundleSimulator};
+
+use crate::{round::proposal_bundle, Signer};
+
+/// Simulates the bundle of `proposal` built against `pools` on top of its
+/// block. When it reverts, the proposal is matched again without the orders
+/// at fault and signed with them excluded, so that verifiers leave them out
+/// of their own matching. `None` when the bundle can't be made to pass, in
+/// which case nothing should be proposed.
+pub(crate) async fn simulated_proposal(
+    simulator: Arc<dyn BundleSimulator>,
+    pools: Arc<BundlePools>,
+    priority: PriceLevelPriority,
+    signer: &Signer,
+    proposal: Proposal,
+    metrics: &ConsensusMetricsWrapper
+) -> Option<Proposal> {
+    let block_height = proposal.block_height;
+    let preproposals = proposal.preproposals.clone();
+    let solutions = proposal.solutions.clone();
+    let handle = Handle::current();
+    let result = tokio::task::spawn_blocking(move || {
+        let rematch = rematch(handle, priority, &preproposals);
+        trim_reverting_orders(solutions, rematch, |solutions, excluded| {
+            let unsigned = Proposal {
+                block_height,
+                preproposals: preproposals.clone(),
+                solutions: solutions.to_vec(),
+                excluded: excluded.to_vec(),
+                ..Default::default()
+            };
+            let bundle = proposal_bundle(&unsigned, &pools, priority)
+                .map_err(|e| BundleSimError::Simulation(e.to_string()))?;
+            simulator.simulate(&bundle, block_height)
+        })
+    })
+    .await
+    .unwrap_or_else(|e| Err(BundleSimError::Simulation(e.to_string())));
+
+    match result {
+        Ok((_, trimmed)) if trimmed.is_empty() => Some(proposal),
+        Ok((solutions, trimmed)) => {
+            tracing::warn!(
+                block_height,
+                ?trimmed,
+                "left out orders that made the bundle of our proposal revert"
+            );
+            metrics.incr_trimmed_orders(trimmed.len());
+            Some(signer.sign_trimmed_proposal(
+                block_height,
+                proposal.preproposals,
+                solutions,
+                trimmed
+            ))
+        }
+        Err(err) => {
+            tracing::error!(
+                error = %err,
+                block_height,
+                "Bundle of our proposal fails to simulate, not proposing"
+            );
+            metrics.incr_bundle_simulation_failures();
+            None
+        }
+    }
+}
+
+/// Matches `preproposals` again without the orders it is given. Matching
+/// spawns a task per pool, so it runs on the runtime of `handle`.
+fn rematch(
+    handle: Handle,
+    priority: PriceLevelPriority,
+    preproposals: &[PreProposal]
+) -> impl Fn(&[B256]) -> Result<Vec<PoolSolution>, BundleSimError> + '_ {
+    let matcher = MatchingManager::new(priority);
+    move |excluded| {
+        handle
+            .block_on(matcher.build_proposal(PreProposal::without_orders(preproposals, excluded)))
+            .map_err(BundleSimError::Simulation)
+    }
+}
+
+/// Leaves orders out of matching until `simulate` passes on the solutions
+/// `rematch` finds without them, returning the solutions that pass and the
+/// hashes of the orders left out. `simulate` is given the orders left out of
+/// the solutions along with them.
+///
+/// The contract stops at the first order that fails, so an order is taken to
+/// be at fault when the bundle passes without it or fails differently. Every
+/// order left out costs a matching and a simulation of each of the remaining
+/// ones.
+pub(crate) fn trim_reverting_orders(
+    mut solutions: Vec<PoolSolution>,
+    rematch: impl Fn(&[B256]) -> Result<Vec<PoolSolution>, BundleSimError>,
+    simulate: impl Fn(&[PoolSolution], &[B256]) -> Result<(), BundleSimError>
+) -> Result<(Vec<PoolSolution>, Vec<B256>), BundleSimError> {
+    let mut trimmed = Vec::new();
+    let mut error = match simulate(&solutions, &trimmed) {
+        Ok(()) => return Ok((solutions, trimmed)),
+        Err(err @ BundleSimError::Simulation(_)) => return Err(err),
+        Err(err) => err
+    };
+
+    'trim: loop {
+        for order in filled_orders(&solutions) {
+            let excluded = trimmed.iter().copied().chain([order]).collect::<Vec<_>>();
+            let candidate = rematch(&excluded)?;
+            match simulate(&candidate, &excluded) {
+                Ok(()) => return Ok((candidate, excluded)),
+                Err(err @ BundleSimError::Simulation(_)) => return Err(err),
+                Err(err) if err != error => {
+                    trimmed = excluded;
+                    solutions = candidate;
+                    error = err;
+                    continue 'trim
+                }
+                Err(_) => {}
+            }
+        }
+
+        return Err(error)
+    }
+}
+
+fn filled_orders(solutions: &[PoolSolution]) -> Vec<B256> {
+    solutions
+        .iter()
+        .flat_map(|solution| {
+            solution
+                .searcher
+                .iter()
+                .map(|order| order.order_id.hash)
+                .chain(
+                    solution
+                        .limit
+                        .iter()
+                        .filter(|outcome| outcome.is_filled())
+                        .map(|outcome| outcome.id.hash)
+                )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, BlockNumber, Bytes};
+    use angstrom_types::{
+        contract_payloads::angstrom::AngstromBundle,
+        matching::{
+            uniswap::{LiqRange, PoolSnapshot},
+            SqrtPriceX96
+        },
+        orders::{OrderFillState, OrderId, OrderOutcome},
+        primitive::PoolId,
+        sol_bindings::grouped_orders::OrderWithStorageData
+    };
+    use reth_network_peers::pk2id;
+    use secp256k1::{Secp256k1, SecretKey};
+    use testing_tools::type_generator::consensus::preproposal::PreproposalBuilder;
+
+    use super::*;
+
+    /// A signer whose id matches its key, so that its proposals are valid.
+    fn signer() -> Signer {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        Signer { my_id: pk2id(&key.public_key(&Secp256k1::new())), key }
+    }
+
+    fn solution(searcher: u8, limit: &[u8]) -> PoolSolution {
+        PoolSolution {
+            searcher: Some(OrderWithStorageData {
+                order_id: OrderId { hash: B256::with_last_byte(searcher), ..Default::default() },
+                ..Default::default()
+            }),
+            limit: limit
+                .iter()
+                .map(|byte| OrderOutcome {
+                    id:      OrderId { hash: B256::with_last_byte(*byte), ..Default::default() },
+                    outcome: OrderFillState::CompleteFill
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Leaves the excluded orders out of `solutions`, as matching without them
+    /// would.
+    fn match_without(
+        solutions: &[PoolSolution]
+    ) -> impl Fn(&[B256]) -> Result<Vec<PoolSolution>, BundleSimError> + '_ {
+        move |excluded| {
+            Ok(solutions
+                .iter()
+                .cloned()
+                .map(|mut solution| {
+                    solution.searcher = solution
+                        .searcher
+                        .filter(|order| !excluded.contains(&order.order_id.hash));
+                    solution
+                        .limit
+                        .retain(|outcome| !excluded.contains(&outcome.id.hash));
+                    solution
+                })
+                .collect())
+        }
+    }
+
+    /// Reverts at the first of the `bad` orders still filled, like the
+    /// contract would.
+    fn simulate(
+        bad: &[u8]
+    ) -> impl Fn(&[PoolSolution], &[B256]) -> Result<(), BundleSimError> + '_ {
+        move |solutions, _| match filled_orders(solutions)
+            .into_iter()
+            .find(|order| bad.iter().any(|byte| *order == B256::with_last_byte(*byte)))
+        {
+            Some(order) => Err(BundleSimError::Reverted(Bytes::copy_from_slice(&order[..]))),
+            None => Ok(())
+        }
+    }
+
+    #[test]
+    fn trims_every_reverting_order() {
+        let solutions = vec![solution(1, &[2, 3]), solution(4, &[5])];
+
+        let (passing, trimmed) =
+            trim_reverting_orders(solutions.clone(), match_without(&solutions), simulate(&[]))
+                .unwrap();
+        assert_eq!(passing, solutions);
+        assert!(trimmed.is_empty());
+
+        let (passing, trimmed) =
+            trim_reverting_orders(solutions.clone(), match_without(&solutions), simulate(&[3, 4]))
+                .unwrap();
+        assert_eq!(trimmed, vec![B256::with_last_byte(3), B256::with_last_byte(4)]);
+        assert_eq!(
+            filled_orders(&passing),
+            vec![B256::with_last_byte(1), B256::with_last_byte(2), B256::with_last_byte(5)]
+        );
+        assert_eq!(passing[0].limit.len(), 1);
+    }
+
+    #[test]
+    fn gives_up_when_no_order_is_at_fault() {
+        let solutions = vec![solution(1, &[2])];
+        let error = BundleSimError::Halted("OutOfGas".to_string());
+
+        assert_eq!(
+            trim_reverting_orders(solutions.clone(), match_without(&solutions), |_, _| {
+                Err(error.clone())
+            }),
+            Err(error)
+        );
+    }
+
+    /// Reverts while the bundle still has a top of block order.
+    struct RevertsOnSearchers;
+
+    impl BundleSimulator for RevertsOnSearchers {
+        fn simulate(&self, bundle: &AngstromBundle, _: BlockNumber) -> Result<(), BundleSimError> {
+            if bundle.top_of_block_orders.is_empty() {
+                Ok(())
+            } else {
+                Err(BundleSimError::Reverted(Bytes::new()))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn simulates_the_bundle_of_the_proposal() {
+        let pool_id = PoolId::with_last_byte(1);
+        let snapshot = PoolSnapshot::new(
+            vec![LiqRange::new(-6000, 6000, 1_000_000_000_000_000_000).unwrap()],
+            SqrtPriceX96::at_tick(0).unwrap()
+        )
+        .unwrap();
+        let pools = Arc::new(BundlePools::from([(
+            pool_id,
+            (Address::with_last_byte(1), Address::with_last_byte(2), snapshot, 0)
+        )]));
+        let signer = signer();
+        let proposal = signer.sign_proposal(
+            10,
+            vec![],
+            vec![PoolSolution { id: pool_id, ..solution(1, &[]) }]
+        );
+
+        let simulated = simulated_proposal(
+            Arc::new(RevertsOnSearchers),
+            pools,
+            Default::default(),
+            &signer,
+            proposal,
+            &ConsensusMetricsWrapper::default()
+        )
+        .await
+        .expect("the searcher order is at fault");
+        // nothing is left to match once the searcher order is excluded
+        assert!(simulated.solutions.is_empty());
+        assert_eq!(simulated.excluded, vec![B256::with_last_byte(1)]);
+        assert!(simulated.is_valid());
+    }
+
+    #[tokio::test]
+    async fn trimmed_proposals_pass_verification() {
+        let preproposals: Vec<PreProposal> = (0..3)
+            .map(|_| {
+                PreproposalBuilder::new()
+                    .order_count(10)
+                    .for_random_pools(2)
+                    .for_block(10)
+                    .build()
+            })
+            .collect();
+        let matcher = MatchingManager::default();
+        let solutions = matcher.build_proposal(preproposals.clone()).await.unwrap();
+        let bad = *filled_orders(&solutions)
+            .first()
+            .expect("some order is filled");
+
+        let handle = Handle::current();
+        let matched_from = preproposals.clone();
+        let (solutions, trimmed) = tokio::task::spawn_blocking(move || {
+            trim_reverting_orders(
+                solutions,
+                rematch(handle, Default::default(), &matched_from),
+                |solutions, _| {
+                    if filled_orders(solutions).contains(&bad) {
+                        Err(BundleSimError::Reverted(Bytes::copy_from_slice(&bad[..])))
+                    } else {
+                        Ok(())
+                    }
+                }
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(trimmed, vec![bad]);
+
+        let signer = signer();
+        let proposal = signer.sign_trimmed_proposal(10, preproposals, solutions, trimmed);
+        assert!(proposal.is_valid());
+        assert_eq!(matcher.verify_proposal(&proposal).await, Ok(()));
+
+        // the same solutions without the exclusion don't match the preproposals
+        let untrimmed =
+            signer.sign_proposal(10, proposal.preproposals.clone(), proposal.solutions.clone());
+        assert!(matcher.verify_proposal(&untrimmed).await.is_err());
+    }
+}
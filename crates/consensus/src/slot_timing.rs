@@ -0,0 +1,90 @@
+//! Ties the phases of a round to the slot schedule of the beacon chain, so
+//! the bundle is submitted comfortably before the deadline of the next block
+//! no matter how late the current block arrived.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_SLOT_DURATION: Duration = Duration::from_secs(12);
+/// Time the bid aggregation and building of the proposal are given.
+pub const DEFAULT_AGGREGATION_BUDGET: Duration = Duration::from_secs(3);
+/// How long before the next slot starts the bundle has to be submitted to make
+/// it into the block.
+pub const DEFAULT_SUBMISSION_MARGIN: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotTiming {
+    /// unix timestamp (in seconds) of the beacon chain genesis
+    pub genesis_time:       u64,
+    pub slot_duration:      Duration,
+    pub aggregation_budget: Duration,
+    pub submission_margin:  Duration
+}
+
+impl SlotTiming {
+    pub fn new(genesis_time: u64, slot_duration: Duration) -> Self {
+        Self {
+            genesis_time,
+            slot_duration,
+            aggregation_budget: DEFAULT_AGGREGATION_BUDGET,
+            submission_margin: DEFAULT_SUBMISSION_MARGIN
+        }
+    }
+
+    pub fn with_aggregation_budget(mut self, aggregation_budget: Duration) -> Self {
+        self.aggregation_budget = aggregation_budget;
+        self
+    }
+
+    pub fn with_submission_margin(mut self, submission_margin: Duration) -> Self {
+        self.submission_margin = submission_margin;
+        self
+    }
+
+    /// Start of the first slot after `now`.
+    pub fn next_slot_start(&self, now: SystemTime) -> SystemTime {
+        let genesis = UNIX_EPOCH + Duration::from_secs(self.genesis_time);
+        let Ok(since_genesis) = now.duration_since(genesis) else { return genesis };
+        let slot = since_genesis.as_nanos() / self.slot_duration.as_nanos().max(1);
+
+        genesis + self.slot_duration * (slot as u32 + 1)
+    }
+
+    /// Latest time the bundle of a round started at `now` can be submitted.
+    pub fn submission_deadline(&self, now: SystemTime) -> SystemTime {
+        self.next_slot_start(now) - self.submission_margin
+    }
+
+    /// How long bids are collected for in a round started at `now`, leaving
+    /// the aggregation budget before the submission deadline. Zero if the
+    /// round started too late to collect any bids.
+    pub fn bid_submission_duration(&self, now: SystemTime) -> Duration {
+        (self.submission_deadline(now) - self.aggregation_budget)
+            .duration_since(now)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS: u64 = 1_606_824_023;
+
+    fn at(secs_after_genesis: f64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(GENESIS) + Duration::from_secs_f64(secs_after_genesis)
+    }
+
+    #[test]
+    fn phase_ends_follow_the_slot() {
+        let timing = SlotTiming::new(GENESIS, DEFAULT_SLOT_DURATION);
+
+        // block arrived right at the start of slot 100
+        let now = at(1200.0);
+        assert_eq!(timing.next_slot_start(now), at(1212.0));
+        assert_eq!(timing.submission_deadline(now), at(1210.0));
+        assert_eq!(timing.bid_submission_duration(now), Duration::from_secs(7));
+
+        // a late block leaves less time to collect bids
+        assert_eq!(timing.bid_submission_duration(at(1204.5)), Duration::from_millis(2500));
+        assert_eq!(timing.bid_submission_duration(at(1208.0)), Duration::ZERO);
+    }
+}
@@ -0,0 +1,125 @@
+//! Live view of the current consensus round, for operators to see what the
+//! node is doing without digging through logs.
+use std::sync::{Arc, RwLock};
+
+use alloy::primitives::{BlockNumber, B256};
+use angstrom_types::primitive::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::{round::ConsensusState, AngstromValidator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundPhase {
+    BidSubmission,
+    BidAggregation,
+    Finalization
+}
+
+impl From<&ConsensusState> for RoundPhase {
+    fn from(state: &ConsensusState) -> Self {
+        match state {
+            ConsensusState::BidSubmission(_) => Self::BidSubmission,
+            ConsensusState::BidAggregation(_) => Self::BidAggregation,
+            ConsensusState::Finalization(_) => Self::Finalization
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStatus {
+    pub peer_id:      PeerId,
+    pub voting_power: u64
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundStatus {
+    pub block_height:  BlockNumber,
+    pub phase:         RoundPhase,
+    pub leader:        PeerId,
+    pub validators:    Vec<ValidatorStatus>,
+    /// pre-proposals we received or built this round, see
+    /// [`PreProposal::payload_hash`](angstrom_types::consensus::PreProposal::payload_hash)
+    pub pre_proposals: Vec<B256>
+}
+
+impl RoundStatus {
+    pub(crate) fn new(
+        state: &ConsensusState,
+        leader: PeerId,
+        validators: &[AngstromValidator]
+    ) -> Self {
+        let mut pre_proposals = state
+            .pre_proposals()
+            .iter()
+            .map(|pre_proposal| pre_proposal.payload_hash())
+            .collect::<Vec<_>>();
+        pre_proposals.sort_unstable();
+
+        Self {
+            block_height: state.block_height(),
+            phase: state.into(),
+            leader,
+            validators: validators
+                .iter()
+                .map(|validator| ValidatorStatus {
+                    peer_id:      validator.peer_id(),
+                    voting_power: validator.voting_power()
+                })
+                .collect(),
+            pre_proposals
+        }
+    }
+}
+
+/// Written by the consensus manager, shared with whoever serves the status.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentRound(Arc<RwLock<Option<RoundStatus>>>);
+
+impl CurrentRound {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `None` until the consensus manager started.
+    pub fn get(&self) -> Option<RoundStatus> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub(crate) fn set(&self, status: RoundStatus) {
+        *self.0.write().unwrap() = Some(status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use angstrom_types::consensus::PreProposal;
+
+    use super::*;
+    use crate::round::BidAggregation;
+
+    #[test]
+    fn reports_the_round() {
+        let leader = PeerId::repeat_byte(1);
+        let validators = vec![AngstromValidator::new(leader, 10)];
+        let pre_proposal = PreProposal { block_height: 5, source: leader, ..Default::default() };
+        let state = ConsensusState::BidAggregation(BidAggregation {
+            block_height:  5,
+            pre_proposals: HashSet::from([pre_proposal.clone()])
+        });
+
+        let current = CurrentRound::new();
+        assert_eq!(current.get(), None);
+        current.set(RoundStatus::new(&state, leader, &validators));
+
+        let status = current.get().unwrap();
+        assert_eq!(status.block_height, 5);
+        assert_eq!(status.phase, RoundPhase::BidAggregation);
+        assert_eq!(status.leader, leader);
+        assert_eq!(status.validators, vec![ValidatorStatus { peer_id: leader, voting_power: 10 }]);
+        assert_eq!(status.pre_proposals, vec![pre_proposal.payload_hash()]);
+    }
+}
@@ -0,0 +1,377 @@
+//! Lands the bundle of our proposals on chain, either as a flashbots style
+//! `eth_sendBundle` to a list of private relays or, when asked for, as a
+//! public transaction through the provider.
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant}
+};
+
+use alloy::{
+    eips::eip2718::Encodable2718,
+    network::{Ethereum, EthereumWallet, TransactionBuilder},
+    primitives::{hex, keccak256, Address, BlockNumber, Bytes},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    signers::{local::PrivateKeySigner, SignerSync},
+    transports::{
+        http::reqwest::{self, Url},
+        Transport
+    }
+};
+use angstrom_metrics::BundleSubmissionMetricsWrapper;
+use angstrom_types::contract_payloads::angstrom::AngstromBundle;
+use futures::future::join_all;
+use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Header flashbots style relays authenticate the searcher with.
+const FLASHBOTS_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+/// Label of the public mempool in the submission metrics.
+const PUBLIC_TARGET: &str = "public";
+/// Times a bundle is sent to a target before giving up on it.
+pub const DEFAULT_SUBMISSION_ATTEMPTS: u32 = 3;
+/// Relay bundles are sent to when none is given.
+pub const DEFAULT_BUNDLE_RELAY: &str = "https://relay.flashbots.net";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmissionTarget {
+    /// the public mempool, through the provider
+    Public,
+    /// each of the relays, for the block after the one the bundle was built on
+    Relays(Vec<Url>)
+}
+
+impl Default for SubmissionTarget {
+    fn default() -> Self {
+        Self::Relays(vec![DEFAULT_BUNDLE_RELAY.parse().unwrap()])
+    }
+}
+
+impl SubmissionTarget {
+    /// The default relay when no relay is given, bundles only go to the
+    /// public mempool when it is asked for with [`Self::Public`].
+    pub fn from_relays(relays: Vec<Url>) -> Self {
+        if relays.is_empty() {
+            Self::default()
+        } else {
+            Self::Relays(relays)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SubmissionRetry {
+    /// amount of times a target is tried before giving up on the bundle
+    pub attempts:   u32,
+    /// delay before the first retry, doubled for every retry after it
+    pub base_delay: Duration
+}
+
+impl Default for SubmissionRetry {
+    fn default() -> Self {
+        Self { attempts: DEFAULT_SUBMISSION_ATTEMPTS, base_delay: Duration::from_millis(250) }
+    }
+}
+
+impl SubmissionRetry {
+    fn delay(&self, retry: u32) -> Duration {
+        self.base_delay * 2u32.saturating_pow(retry)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SubmissionError {
+    #[error("failed to build the transaction: {0}")]
+    Transaction(String),
+    #[error("provider error: {0}")]
+    Provider(String),
+    #[error("relay {relay} returned {message}")]
+    Relay { relay: String, message: String },
+    #[error("no relay accepted the bundle")]
+    NoRelayAccepted
+}
+
+/// Hands the bundles of our proposals to the [`BundleSubmitter`].
+#[derive(Debug, Clone)]
+pub struct BundleSubmitterHandle(UnboundedSender<(BlockNumber, AngstromBundle)>);
+
+impl BundleSubmitterHandle {
+    pub fn new(tx: UnboundedSender<(BlockNumber, AngstromBundle)>) -> Self {
+        Self(tx)
+    }
+
+    /// Submits the bundle built on top of `block_height`.
+    pub fn submit(&self, block_height: BlockNumber, bundle: AngstromBundle) {
+        let _ = self.0.send((block_height, bundle));
+    }
+}
+
+pub struct BundleSubmitter<P, T> {
+    provider:         Arc<P>,
+    /// the node key, `execute` is only open to nodes
+    signer:           PrivateKeySigner,
+    angstrom_address: Address,
+    target:           SubmissionTarget,
+    retry:            SubmissionRetry,
+    http:             reqwest::Client,
+    metrics:          BundleSubmissionMetricsWrapper,
+    _phantom:         PhantomData<T>
+}
+
+impl<P, T> BundleSubmitter<P, T>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone
+{
+    pub fn new(
+        provider: Arc<P>,
+        signer: PrivateKeySigner,
+        angstrom_address: Address,
+        target: SubmissionTarget
+    ) -> Self {
+        Self {
+            provider,
+            signer,
+            angstrom_address,
+            target,
+            retry: SubmissionRetry::default(),
+            http: reqwest::Client::new(),
+            metrics: BundleSubmissionMetricsWrapper::new(),
+            _phantom: PhantomData
+        }
+    }
+
+    pub fn with_retry(mut self, retry: SubmissionRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Submits the bundles received one after the other, until the handles
+    /// are dropped.
+    pub async fn run(self, mut bundles: UnboundedReceiver<(BlockNumber, AngstromBundle)>) {
+        while let Some((block_height, bundle)) = bundles.recv().await {
+            if let Err(err) = self.submit(block_height, &bundle).await {
+                tracing::error!(error = %err, block_height, "failed to submit our bundle");
+            }
+        }
+    }
+
+    async fn submit(
+        &self,
+        block_height: BlockNumber,
+        bundle: &AngstromBundle
+    ) -> Result<(), SubmissionError> {
+        let tx = self.signed_transaction(bundle).await?;
+
+        match &self.target {
+            SubmissionTarget::Public => {
+                let tx = &tx;
+                self.with_retries(PUBLIC_TARGET, || async move {
+                    self.provider
+                        .send_raw_transaction(tx)
+                        .await
+                        .map(|pending| *pending.tx_hash())
+                        .map_err(|e| SubmissionError::Provider(e.to_string()))
+                })
+                .await
+                .map(|tx_hash| tracing::info!(%tx_hash, block_height, "sent our bundle"))
+            }
+            SubmissionTarget::Relays(relays) => {
+                let request = send_bundle_request(&tx, block_height + 1);
+                let signature = flashbots_signature(&self.signer, &request)?;
+                let accepted = join_all(relays.iter().map(|relay| {
+                    let label = relay.host_str().unwrap_or(relay.as_str());
+                    self.with_retries(label, || self.send_bundle(relay, &request, &signature))
+                }))
+                .await
+                .into_iter()
+                .filter(Result::is_ok)
+                .count();
+
+                if accepted == 0 {
+                    return Err(SubmissionError::NoRelayAccepted)
+                }
+                tracing::info!(accepted, relays = relays.len(), block_height, "sent our bundle");
+                Ok(())
+            }
+        }
+    }
+
+    /// The `execute` call with the bundle, signed for the next nonce of the
+    /// node.
+    async fn signed_transaction(&self, bundle: &AngstromBundle) -> Result<Bytes, SubmissionError> {
+        let provider_error =
+            |e: alloy::transports::TransportError| SubmissionError::Provider(e.to_string());
+        let from = self.signer.address();
+        let nonce = self
+            .provider
+            .get_transaction_count(from)
+            .await
+            .map_err(provider_error)?;
+        let chain_id = self.provider.get_chain_id().await.map_err(provider_error)?;
+        let fees = self
+            .provider
+            .estimate_eip1559_fees(None)
+            .await
+            .map_err(provider_error)?;
+
+        let request = TransactionRequest::default()
+            .with_from(from)
+            .with_to(self.angstrom_address)
+            .with_input(bundle.execute_calldata())
+            .with_nonce(nonce)
+            .with_chain_id(chain_id)
+            .with_max_fee_per_gas(fees.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+        let gas = self
+            .provider
+            .estimate_gas(&request)
+            .await
+            .map_err(provider_error)?;
+
+        let envelope = request
+            .with_gas_limit(gas)
+            .build(&EthereumWallet::from(self.signer.clone()))
+            .await
+            .map_err(|e| SubmissionError::Transaction(e.to_string()))?;
+
+        Ok(envelope.encoded_2718().into())
+    }
+
+    async fn send_bundle(
+        &self,
+        relay: &Url,
+        request: &str,
+        signature: &str
+    ) -> Result<(), SubmissionError> {
+        let relay_error =
+            |message: String| SubmissionError::Relay { relay: relay.to_string(), message };
+        let response = self
+            .http
+            .post(relay.as_str())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(FLASHBOTS_SIGNATURE_HEADER, signature)
+            .body(request.to_string())
+            .send()
+            .await
+            .map_err(|e| relay_error(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| relay_error(e.to_string()))?;
+        if !status.is_success() {
+            return Err(relay_error(format!("{status}: {body}")))
+        }
+
+        let body: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| relay_error(e.to_string()))?;
+        match body.get("error") {
+            Some(error) => Err(relay_error(error.to_string())),
+            None => Ok(())
+        }
+    }
+
+    /// Runs `submit` until it succeeds or runs out of attempts, recording the
+    /// outcome under `target`.
+    async fn with_retries<R, F, Fut>(&self, target: &str, submit: F) -> Result<R, SubmissionError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<R, SubmissionError>>
+    {
+        let started = Instant::now();
+        let mut retry = 0;
+        loop {
+            match submit().await {
+                Ok(res) => {
+                    self.metrics
+                        .incr_submissions(target, started.elapsed().as_millis() as u64);
+                    return Ok(res)
+                }
+                Err(err) if retry + 1 < self.retry.attempts => {
+                    tracing::warn!(error = %err, target, retry, "retrying bundle submission");
+                    self.metrics.incr_retries(target);
+                    tokio::time::sleep(self.retry.delay(retry)).await;
+                    retry += 1;
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, target, "giving up on bundle submission");
+                    self.metrics.incr_failures(target);
+                    return Err(err)
+                }
+            }
+        }
+    }
+}
+
+/// The json-rpc body of an `eth_sendBundle` with the single transaction, for
+/// `block_number`.
+fn send_bundle_request(tx: &Bytes, block_number: BlockNumber) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_sendBundle",
+        "params": [{
+            "txs": [tx],
+            "blockNumber": format!("{block_number:#x}")
+        }]
+    })
+    .to_string()
+}
+
+/// `address:signature`, the signature being over the hex of the hash of the
+/// request body.
+fn flashbots_signature(
+    signer: &PrivateKeySigner,
+    request: &str
+) -> Result<String, SubmissionError> {
+    let message = format!("{:?}", keccak256(request));
+    let signature = signer
+        .sign_message_sync(message.as_bytes())
+        .map_err(|e| SubmissionError::Transaction(e.to_string()))?;
+
+    Ok(format!("{}:{}", signer.address(), hex::encode_prefixed(signature.as_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Signature;
+
+    use super::*;
+
+    #[test]
+    fn defaults_to_a_private_relay() {
+        assert_eq!(
+            SubmissionTarget::from_relays(vec![]),
+            SubmissionTarget::Relays(vec![DEFAULT_BUNDLE_RELAY.parse().unwrap()])
+        );
+        let relay: Url = "https://rpc.titanbuilder.xyz".parse().unwrap();
+        assert_eq!(
+            SubmissionTarget::from_relays(vec![relay.clone()]),
+            SubmissionTarget::Relays(vec![relay])
+        );
+    }
+
+    #[test]
+    fn signs_the_relay_request() {
+        let signer = PrivateKeySigner::random();
+        let request = send_bundle_request(&Bytes::from_static(&[0xab, 0xcd]), 16);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&request).unwrap()["params"][0],
+            serde_json::json!({ "txs": ["0xabcd"], "blockNumber": "0x10" })
+        );
+
+        let header = flashbots_signature(&signer, &request).unwrap();
+        let (address, signature) = header.split_once(':').unwrap();
+        assert_eq!(address.parse::<Address>().unwrap(), signer.address());
+        let signature = Signature::try_from(hex::decode(signature).unwrap().as_slice()).unwrap();
+        let message = format!("{:?}", keccak256(&request));
+        assert_eq!(
+            signature
+                .recover_address_from_msg(message.as_bytes())
+                .unwrap(),
+            signer.address()
+        );
+    }
+}
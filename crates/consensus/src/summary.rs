@@ -0,0 +1,94 @@
+//! Bounded history of how the consensus rounds went, so that operators can
+//! see where the time of a round was spent without digging through metrics.
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock}
+};
+
+use alloy::primitives::{BlockNumber, B256};
+use serde::{Deserialize, Serialize};
+
+/// Number of rounds a summary is kept for.
+const MAX_ROUNDS: usize = 256;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundSummary {
+    pub block_height:       BlockNumber,
+    /// connected peers when the round started
+    pub peer_count:         usize,
+    /// `None` until the phase ended, or if the round never got to it
+    pub bid_submission_ms:  Option<u64>,
+    pub bid_aggregation_ms: Option<u64>,
+    pub finalization_ms:    Option<u64>,
+    /// the bundle we proposed or verified, see
+    /// [`AngstromBundle::bundle_hash`](angstrom_types::contract_payloads::angstrom::AngstromBundle::bundle_hash)
+    pub bundle_hash:        Option<B256>
+}
+
+/// Written by the consensus manager, shared with whoever serves the
+/// summaries.
+#[derive(Debug, Clone, Default)]
+pub struct RoundSummaries(Arc<RwLock<VecDeque<RoundSummary>>>);
+
+impl RoundSummaries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, block_height: BlockNumber) -> Option<RoundSummary> {
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|round| round.block_height == block_height)
+            .cloned()
+    }
+
+    /// A round restarted at the same height after a reorg replaces the
+    /// previous one.
+    pub(crate) fn start_round(&self, block_height: BlockNumber, peer_count: usize) {
+        let mut rounds = self.0.write().unwrap();
+        rounds.retain(|round| round.block_height != block_height);
+        if rounds.len() == MAX_ROUNDS {
+            rounds.pop_front();
+        }
+        rounds.push_back(RoundSummary { block_height, peer_count, ..Default::default() });
+    }
+
+    pub(crate) fn update(&self, block_height: BlockNumber, f: impl FnOnce(&mut RoundSummary)) {
+        let mut rounds = self.0.write().unwrap();
+        if let Some(round) = rounds
+            .iter_mut()
+            .rev()
+            .find(|round| round.block_height == block_height)
+        {
+            f(round);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_last_rounds() {
+        let summaries = RoundSummaries::new();
+        summaries.start_round(1, 3);
+        summaries.update(1, |round| round.bid_submission_ms = Some(10));
+        // reorged back to the same height
+        summaries.start_round(1, 4);
+        assert_eq!(
+            summaries.get(1),
+            Some(RoundSummary { block_height: 1, peer_count: 4, ..Default::default() })
+        );
+
+        for block in 2..(MAX_ROUNDS as u64 + 2) {
+            summaries.start_round(block, 3);
+        }
+        assert_eq!(summaries.get(1), None);
+        assert!(summaries.get(2).is_some());
+    }
+}
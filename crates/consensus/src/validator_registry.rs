@@ -0,0 +1,210 @@
+//! Loads the validator set and its voting power from the on-chain stake
+//! registry, at startup and again at the start of every epoch.
+use std::{marker::PhantomData, sync::Arc};
+
+use alloy::{
+    network::Network,
+    primitives::{aliases::U96, Address, BlockNumber},
+    providers::Provider,
+    sol,
+    transports::Transport
+};
+use angstrom_types::primitive::PeerId;
+use futures::StreamExt;
+use reth_provider::CanonStateNotifications;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::AngstromValidator;
+
+/// Blocks between two reloads of the validator set, about a day.
+pub const DEFAULT_VALIDATOR_EPOCH_LENGTH: u64 = 7200;
+/// Blocks after the start of an epoch the set read at it takes effect, so that
+/// every node switches at the same height no matter how long loading took.
+pub const VALIDATOR_SET_ACTIVATION_DELAY: u64 = 32;
+/// Stake (in wei) worth one unit of voting power. A uint96 stake is then at
+/// most ~8e10 voting power, so the total of any realistic set and the quorum
+/// math on it stay far from overflowing a u64.
+const STAKE_PER_VOTING_POWER: u64 = 1_000_000_000_000_000_000;
+
+/// A validator set read from the registry and the height it takes effect at.
+#[derive(Debug, Clone)]
+pub struct ValidatorSetUpdate {
+    pub activation_block: BlockNumber,
+    pub validators:       Vec<AngstromValidator>
+}
+
+/// The start of the epoch once its set took effect, the previous one before.
+fn active_set_block(epoch_length: u64, block_number: BlockNumber) -> BlockNumber {
+    if epoch_length == 0 {
+        return block_number
+    }
+    let epoch_start = block_number - block_number % epoch_length;
+    if block_number < epoch_start + VALIDATOR_SET_ACTIVATION_DELAY && epoch_start >= epoch_length {
+        return epoch_start - epoch_length
+    }
+
+    epoch_start
+}
+
+/// Voting power of a stake, one per whole token staked.
+fn voting_power(stake: U96) -> u64 {
+    (stake / U96::from(STAKE_PER_VOTING_POWER)).to::<u64>()
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface IValidatorRegistry {
+        struct Validator {
+            /// the uncompressed secp256k1 public key of the node
+            bytes peerId;
+            uint96 stake;
+        }
+
+        function getValidators() external view returns (Validator[] memory validators);
+    }
+}
+
+pub struct ValidatorRegistry<P, T, N> {
+    provider:     Arc<P>,
+    address:      Address,
+    epoch_length: u64,
+    _phantom:     PhantomData<(T, N)>
+}
+
+impl<P, T, N> ValidatorRegistry<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network
+{
+    pub fn new(provider: Arc<P>, address: Address, epoch_length: u64) -> Self {
+        Self { provider, address, epoch_length, _phantom: PhantomData }
+    }
+
+    pub fn is_epoch_start(&self, block_number: BlockNumber) -> bool {
+        self.epoch_length != 0 && block_number % self.epoch_length == 0
+    }
+
+    /// The block the validator set that is active at `block_number` was read
+    /// at, so that a node starting mid-epoch loads the same set as the others.
+    pub fn active_set_block(&self, block_number: BlockNumber) -> BlockNumber {
+        active_set_block(self.epoch_length, block_number)
+    }
+
+    /// The validator set as of `block_number`. Validators with a malformed
+    /// peer id or less than one voting power of stake are left out.
+    pub async fn validators(
+        &self,
+        block_number: BlockNumber
+    ) -> eyre::Result<Vec<AngstromValidator>> {
+        let validators = IValidatorRegistry::new(self.address, &*self.provider)
+            .getValidators()
+            .block(block_number.into())
+            .call()
+            .await?
+            .validators;
+
+        let validators = validators
+            .into_iter()
+            .filter_map(|validator| {
+                let power = voting_power(validator.stake);
+                if validator.peerId.len() != PeerId::len_bytes() || power == 0 {
+                    tracing::warn!(
+                        peer_id = %validator.peerId,
+                        stake = %validator.stake,
+                        "skipping invalid validator"
+                    );
+                    return None
+                }
+                Some(AngstromValidator::new(PeerId::from_slice(&validator.peerId), power))
+            })
+            .collect::<Vec<_>>();
+        if validators.is_empty() {
+            eyre::bail!("registry {} has no valid validators at block {block_number}", self.address)
+        }
+
+        Ok(validators)
+    }
+
+    /// Reloads the validator set at the start of every epoch, sending it to
+    /// the consensus manager to take effect [`VALIDATOR_SET_ACTIVATION_DELAY`]
+    /// blocks later. A set that fails to load keeps the current one.
+    pub async fn refresh_on_epochs(
+        self,
+        start_block: BlockNumber,
+        canonical_state: CanonStateNotifications,
+        updates: UnboundedSender<ValidatorSetUpdate>
+    ) {
+        // the set of the current epoch might not have taken effect yet
+        if self.epoch_length != 0 {
+            let epoch_start = start_block - start_block % self.epoch_length;
+            if self.active_set_block(start_block) != epoch_start
+                && !self.load_epoch(epoch_start, &updates).await
+            {
+                return
+            }
+        }
+
+        let mut blocks = BroadcastStream::new(canonical_state);
+        while let Some(notification) = blocks.next().await {
+            let Ok(notification) = notification else { continue };
+            let block_number = notification.tip().block.number;
+            if self.is_epoch_start(block_number) && !self.load_epoch(block_number, &updates).await {
+                return
+            }
+        }
+    }
+
+    /// Loads the set read at the start of an epoch, returning false once the
+    /// consensus manager is gone.
+    async fn load_epoch(
+        &self,
+        epoch_start: BlockNumber,
+        updates: &UnboundedSender<ValidatorSetUpdate>
+    ) -> bool {
+        match self.validators(epoch_start).await {
+            Ok(validators) => {
+                tracing::info!(epoch_start, count = validators.len(), "loaded validator set");
+                let activation_block = epoch_start + VALIDATOR_SET_ACTIVATION_DELAY;
+                updates
+                    .send(ValidatorSetUpdate { activation_block, validators })
+                    .is_ok()
+            }
+            Err(e) => {
+                tracing::error!(%e, epoch_start, "failed to load the validator set");
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_stakes_to_voting_power() {
+        let token = U96::from(STAKE_PER_VOTING_POWER);
+        assert_eq!(voting_power(token - U96::from(1)), 0);
+        assert_eq!(voting_power(token * U96::from(3)), 3);
+
+        let max = voting_power(U96::MAX);
+        assert!(max
+            .checked_mul(1000)
+            .and_then(|total| total.checked_mul(2))
+            .is_some());
+    }
+
+    #[test]
+    fn picks_the_set_active_at_a_height() {
+        let epoch = 100;
+        assert_eq!(active_set_block(epoch, 10), 0);
+        assert_eq!(active_set_block(epoch, 100), 0);
+        assert_eq!(active_set_block(epoch, 100 + VALIDATOR_SET_ACTIVATION_DELAY - 1), 0);
+        assert_eq!(active_set_block(epoch, 100 + VALIDATOR_SET_ACTIVATION_DELAY), 100);
+        assert_eq!(active_set_block(epoch, 250), 200);
+        assert_eq!(active_set_block(0, 250), 250);
+    }
+}
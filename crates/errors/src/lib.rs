@@ -0,0 +1,400 @@
+//! Typed error domains shared across the node. Component crates keep their own
+//! detailed errors and convert them into these at their boundaries, so that
+//! RPC responses and metrics labels are derived from a single taxonomy instead
+//! of from error strings.
+use serde::{Deserialize, Serialize};
+
+/// The component an error originated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorDomain {
+    Validation,
+    Pool,
+    Consensus,
+    Network,
+    Sim
+}
+
+impl ErrorDomain {
+    /// Start of the json-rpc error code range of the domain. These sit in the
+    /// implementation defined server error range.
+    pub const fn code_base(&self) -> i32 {
+        match self {
+            Self::Validation => -32100,
+            Self::Pool => -32200,
+            Self::Consensus => -32300,
+            Self::Network => -32400,
+            Self::Sim => -32500
+        }
+    }
+
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Validation => "validation",
+            Self::Pool => "pool",
+            Self::Consensus => "consensus",
+            Self::Network => "network",
+            Self::Sim => "sim"
+        }
+    }
+}
+
+/// Implemented by every error domain so that the error code and metric label
+/// are always derived the same way.
+pub trait ErrorCode {
+    fn domain(&self) -> ErrorDomain;
+
+    /// Offset of the error within its domain.
+    fn offset(&self) -> i32;
+
+    /// Short snake case label, used as a metric label.
+    fn label(&self) -> &'static str;
+
+    fn code(&self) -> i32 {
+        self.domain().code_base() - self.offset()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("order has expired")]
+    Expired,
+    #[error("order deadline is beyond the max horizon")]
+    BeyondDeadlineHorizon,
+    #[error("order is below the dust threshold")]
+    Dust,
+    #[error("no pool exists for the order")]
+    UnknownPool,
+    #[error("invalid nonce")]
+    InvalidNonce,
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    #[error("insufficient approval")]
+    InsufficientApproval,
+    #[error("order was validated for a different block")]
+    BlockMismatch,
+    #[error("order has been cancelled")]
+    Cancelled,
+    #[error("an order with the same nonce and an equal or higher bid exists")]
+    ReplacementUnderpriced,
+    #[error("order was parked after repeated transient simulation failures")]
+    SimUnstable,
+    #[error("order was already submitted")]
+    Duplicate,
+    #[error("account has triggered its kill switch")]
+    AccountDisabled,
+    #[error("order was turned away by compliance screening")]
+    Screened,
+    #[error("order simulation reverted: {0}")]
+    SimReverted(String),
+    #[error("malformed order: {0}")]
+    Malformed(String),
+    #[error("{0}")]
+    Other(String)
+}
+
+impl ErrorCode for ValidationError {
+    fn domain(&self) -> ErrorDomain {
+        ErrorDomain::Validation
+    }
+
+    fn offset(&self) -> i32 {
+        match self {
+            Self::InvalidSignature => 1,
+            Self::Expired => 2,
+            Self::BeyondDeadlineHorizon => 3,
+            Self::Dust => 4,
+            Self::UnknownPool => 5,
+            Self::InvalidNonce => 6,
+            Self::InsufficientBalance => 7,
+            Self::InsufficientApproval => 8,
+            Self::BlockMismatch => 9,
+            Self::Cancelled => 10,
+            Self::Malformed(_) => 11,
+            Self::ReplacementUnderpriced => 12,
+            Self::SimUnstable => 13,
+            Self::Duplicate => 14,
+            Self::AccountDisabled => 15,
+            Self::SimReverted(_) => 16,
+            Self::Screened => 17,
+            Self::Other(_) => 0
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::InvalidSignature => "invalid_signature",
+            Self::Expired => "expired",
+            Self::BeyondDeadlineHorizon => "beyond_deadline_horizon",
+            Self::Dust => "dust",
+            Self::UnknownPool => "unknown_pool",
+            Self::InvalidNonce => "invalid_nonce",
+            Self::InsufficientBalance => "insufficient_balance",
+            Self::InsufficientApproval => "insufficient_approval",
+            Self::BlockMismatch => "block_mismatch",
+            Self::Cancelled => "cancelled",
+            Self::ReplacementUnderpriced => "replacement_underpriced",
+            Self::SimUnstable => "sim_unstable",
+            Self::Duplicate => "duplicate",
+            Self::AccountDisabled => "account_disabled",
+            Self::SimReverted(_) => "sim_reverted",
+            Self::Screened => "screened",
+            Self::Malformed(_) => "malformed",
+            Self::Other(_) => "other"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PoolError {
+    #[error("pool is full")]
+    MaxSize,
+    #[error("no pool was found for the order")]
+    NoPool,
+    #[error("duplicate order")]
+    DuplicateOrder,
+    #[error("an order with the same nonce already exists")]
+    DuplicateNonce,
+    #[error("account has triggered its kill switch")]
+    DisabledAccount,
+    #[error("service is not enabled")]
+    Disabled,
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("order pool is not running")]
+    Unavailable,
+    #[error("{0}")]
+    Other(String)
+}
+
+impl ErrorCode for PoolError {
+    fn domain(&self) -> ErrorDomain {
+        ErrorDomain::Pool
+    }
+
+    fn offset(&self) -> i32 {
+        match self {
+            Self::MaxSize => 1,
+            Self::NoPool => 2,
+            Self::DuplicateOrder => 3,
+            Self::DuplicateNonce => 4,
+            Self::DisabledAccount => 5,
+            Self::Disabled => 6,
+            Self::InvalidRequest(_) => 7,
+            Self::Unavailable => 8,
+            Self::Other(_) => 0
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::MaxSize => "max_size",
+            Self::NoPool => "no_pool",
+            Self::DuplicateOrder => "duplicate_order",
+            Self::DuplicateNonce => "duplicate_nonce",
+            Self::DisabledAccount => "disabled_account",
+            Self::Disabled => "disabled",
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::Unavailable => "unavailable",
+            Self::Other(_) => "other"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConsensusError {
+    #[error("invalid evidence")]
+    InvalidEvidence,
+    #[error("quorum was not reached")]
+    QuorumNotReached,
+    #[error("invalid proposal")]
+    InvalidProposal,
+    #[error("{0}")]
+    Other(String)
+}
+
+impl ErrorCode for ConsensusError {
+    fn domain(&self) -> ErrorDomain {
+        ErrorDomain::Consensus
+    }
+
+    fn offset(&self) -> i32 {
+        match self {
+            Self::InvalidEvidence => 1,
+            Self::QuorumNotReached => 2,
+            Self::InvalidProposal => 3,
+            Self::Other(_) => 0
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::InvalidEvidence => "invalid_evidence",
+            Self::QuorumNotReached => "quorum_not_reached",
+            Self::InvalidProposal => "invalid_proposal",
+            Self::Other(_) => "other"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NetworkError {
+    #[error("handshake failed: {0}")]
+    Handshake(String),
+    #[error("message too big")]
+    MessageTooBig,
+    #[error("invalid message")]
+    InvalidMessage,
+    #[error("{0}")]
+    Other(String)
+}
+
+impl ErrorCode for NetworkError {
+    fn domain(&self) -> ErrorDomain {
+        ErrorDomain::Network
+    }
+
+    fn offset(&self) -> i32 {
+        match self {
+            Self::Handshake(_) => 1,
+            Self::MessageTooBig => 2,
+            Self::InvalidMessage => 3,
+            Self::Other(_) => 0
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Handshake(_) => "handshake",
+            Self::MessageTooBig => "message_too_big",
+            Self::InvalidMessage => "invalid_message",
+            Self::Other(_) => "other"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SimError {
+    #[error("swap simulation failed: {0}")]
+    Swap(String),
+    #[error("execution reverted: {0}")]
+    Revert(String),
+    #[error("out of gas")]
+    OutOfGas,
+    /// the state needed for the simulation couldn't be fetched, so retrying
+    /// later can succeed
+    #[error("state provider error: {0}")]
+    Provider(String),
+    #[error("{0}")]
+    Other(String)
+}
+
+impl SimError {
+    /// Whether the simulation may succeed when retried against the same
+    /// state. Reverts and running out of gas are deterministic.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Provider(_))
+    }
+}
+
+impl ErrorCode for SimError {
+    fn domain(&self) -> ErrorDomain {
+        ErrorDomain::Sim
+    }
+
+    fn offset(&self) -> i32 {
+        match self {
+            Self::Swap(_) => 1,
+            Self::Revert(_) => 2,
+            Self::OutOfGas => 3,
+            Self::Provider(_) => 4,
+            Self::Other(_) => 0
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Swap(_) => "swap",
+            Self::Revert(_) => "revert",
+            Self::OutOfGas => "out_of_gas",
+            Self::Provider(_) => "provider",
+            Self::Other(_) => "other"
+        }
+    }
+}
+
+/// An error from any component of the node.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AngstromError {
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    #[error(transparent)]
+    Pool(#[from] PoolError),
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+    #[error(transparent)]
+    Sim(#[from] SimError)
+}
+
+impl AngstromError {
+    fn inner(&self) -> &dyn ErrorCode {
+        match self {
+            Self::Validation(e) => e,
+            Self::Pool(e) => e,
+            Self::Consensus(e) => e,
+            Self::Network(e) => e,
+            Self::Sim(e) => e
+        }
+    }
+
+    /// `<domain>.<error>`, used to label metrics
+    pub fn metric_label(&self) -> String {
+        format!("{}.{}", self.domain().label(), self.label())
+    }
+}
+
+impl ErrorCode for AngstromError {
+    fn domain(&self) -> ErrorDomain {
+        self.inner().domain()
+    }
+
+    fn offset(&self) -> i32 {
+        self.inner().offset()
+    }
+
+    fn label(&self) -> &'static str {
+        self.inner().label()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_stay_within_their_domain() {
+        let errors: Vec<AngstromError> = vec![
+            ValidationError::Cancelled.into(),
+            PoolError::InvalidRequest(String::new()).into(),
+            ConsensusError::InvalidProposal.into(),
+            NetworkError::InvalidMessage.into(),
+            SimError::OutOfGas.into(),
+        ];
+
+        for error in errors {
+            let base = error.domain().code_base();
+            assert!(error.code() <= base && error.code() > base - 100);
+        }
+    }
+
+    #[test]
+    fn labels_include_domain() {
+        let error: AngstromError = ValidationError::InvalidSignature.into();
+        assert_eq!(error.code(), -32101);
+        assert_eq!(error.metric_label(), "validation.invalid_signature");
+    }
+}
@@ -1,26 +1,39 @@
 use std::{
     collections::HashSet,
     sync::Arc,
-    task::{Context, Poll}
+    task::{Context, Poll},
+    time::{Duration, Instant}
 };
 
 use alloy::{
     primitives::{Address, B256},
     sol_types::SolEvent
 };
+use angstrom_metrics::{health, EthMetricsWrapper};
 use angstrom_types::{
     contract_bindings, contract_payloads::angstrom::AngstromBundle, primitive::NewInitializedPool
 };
 use futures::Future;
 use futures_util::{FutureExt, StreamExt};
 use pade::PadeDecode;
-use reth_provider::{CanonStateNotification, CanonStateNotifications, Chain, StateProviderFactory};
+use reth_provider::{
+    CanonStateNotification, CanonStateNotifications, CanonStateSubscriptions, Chain,
+    StateProviderFactory
+};
 use reth_tasks::TaskSpawner;
-use tokio::sync::mpsc::{Receiver, Sender, UnboundedSender};
+use tokio::{
+    sync::mpsc::{Receiver, Sender, UnboundedSender},
+    time::Interval
+};
 use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 
 use crate::handle::{EthCommand, EthHandle};
 
+pub const SLOT_TIME: Duration = Duration::from_secs(12);
+/// Default amount of slots without a canonical block before we consider the
+/// canonical state stream stalled.
+pub const DEFAULT_STALL_SLOTS: u32 = 3;
+
 alloy::sol!(
     event Transfer(address indexed _from, address indexed _to, uint256 _value);
     event Approval(address indexed _owner, address indexed _spender, uint256 _value);
@@ -41,13 +54,80 @@ pub struct EthDataCleanser<DB> {
     angstrom_tokens:   HashSet<Address>,
     /// used to fetch data from db
     #[allow(dead_code)]
-    db:                DB
+    db:                DB,
+    watchdog:          CanonStreamWatchdog
+}
+
+/// Keeps track of when we last saw a canonical block, so that a stalled
+/// canonical state stream doesn't silently freeze everything downstream on
+/// the prior block.
+struct CanonStreamWatchdog {
+    stall_timeout: Duration,
+    last_update:   Instant,
+    stalled:       bool,
+    interval:      Interval,
+    metrics:       EthMetricsWrapper
+}
+
+impl CanonStreamWatchdog {
+    fn new(stall_slots: u32) -> Self {
+        Self {
+            stall_timeout: SLOT_TIME * stall_slots.max(1),
+            last_update:   Instant::now(),
+            stalled:       false,
+            interval:      tokio::time::interval(SLOT_TIME),
+            metrics:       EthMetricsWrapper::new()
+        }
+    }
+
+    fn on_update(&mut self) {
+        self.last_update = Instant::now();
+        self.metrics.set_seconds_since_canonical_block(0);
+        if self.stalled {
+            tracing::info!("canonical state stream recovered");
+            self.set_stalled(false);
+        }
+    }
+
+    /// Returns true if we should re-subscribe to the canonical state stream.
+    fn poll_stalled(&mut self, cx: &mut Context<'_>) -> bool {
+        let mut should_resubscribe = false;
+        while self.interval.poll_tick(cx).is_ready() {
+            let elapsed = self.last_update.elapsed();
+            self.metrics
+                .set_seconds_since_canonical_block(elapsed.as_secs());
+            if elapsed < self.stall_timeout {
+                continue
+            }
+
+            if !self.stalled {
+                tracing::error!(
+                    ?elapsed,
+                    "no canonical block seen within the stall timeout, re-subscribing"
+                );
+                self.set_stalled(true);
+            }
+            // restart the timer so we only re-subscribe once per timeout
+            self.last_update = Instant::now();
+            self.metrics.incr_canonical_stream_resubscribes();
+            should_resubscribe = true;
+        }
+
+        should_resubscribe
+    }
+
+    fn set_stalled(&mut self, stalled: bool) {
+        self.stalled = stalled;
+        self.metrics.set_canonical_stream_stalled(stalled);
+        health::set_canonical_stream_stalled(stalled);
+    }
 }
 
 impl<DB> EthDataCleanser<DB>
 where
-    DB: StateProviderFactory + Send + Sync + Unpin + 'static
+    DB: StateProviderFactory + CanonStateSubscriptions + Send + Sync + Unpin + 'static
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn<TP: TaskSpawner>(
         angstrom_address: Address,
         canonical_updates: CanonStateNotifications,
@@ -55,7 +135,8 @@ where
         tp: TP,
         tx: Sender<EthCommand>,
         rx: Receiver<EthCommand>,
-        angstrom_tokens: HashSet<Address>
+        angstrom_tokens: HashSet<Address>,
+        stall_slots: u32
     ) -> anyhow::Result<EthHandle> {
         let stream = ReceiverStream::new(rx);
 
@@ -65,7 +146,8 @@ where
             commander: stream,
             event_listeners: Vec::new(),
             angstrom_tokens,
-            db
+            db,
+            watchdog: CanonStreamWatchdog::new(stall_slots)
         };
         tp.spawn_critical("eth handle", this.boxed());
 
@@ -103,12 +185,22 @@ where
         let difference: Vec<_> = old_filled.difference(&new_filled).copied().collect();
         let reorged_orders = EthEvent::ReorgedOrders(difference);
 
+        // funds can disappear in any block of the reorged chain, including for the
+        // receivers of a transfer that is no longer canonical
+        let mut affected_users = self.get_reorg_affected_users(&old);
+        affected_users.extend(self.get_reorg_affected_users(&new));
+        let chain_reorg = EthEvent::ChainReorg {
+            block_number: new.tip().number,
+            addresses:    affected_users.into_iter().collect()
+        };
+
         let transitions = EthEvent::NewBlockTransitions {
             block_number:      new.tip().number,
             filled_orders:     new_filled.into_iter().collect(),
             address_changeset: eoas
         };
         self.send_events(transitions);
+        self.send_events(chain_reorg);
         self.send_events(reorged_orders);
     }
 
@@ -155,6 +247,9 @@ where
                 let mut input: &[u8] = transaction.input();
                 AngstromBundle::pade_decode(&mut input, None).ok()
             })
+            .inspect(|bundle| {
+                tracing::debug!(bundle_hash = %bundle.bundle_hash(), "angstrom bundle landed")
+            })
             .flat_map(move |bundle| bundle.get_order_hashes().collect::<Vec<_>>())
     }
 
@@ -177,6 +272,24 @@ where
             .collect()
     }
 
+    /// All senders, receivers and approvers of angstrom tokens over every block
+    /// of the chain.
+    fn get_reorg_affected_users(&self, chain: &Chain) -> HashSet<Address> {
+        chain
+            .range()
+            .flat_map(|block| chain.execution_outcome().receipts_by_block(block).iter())
+            .flatten()
+            .flat_map(|receipt| &receipt.logs)
+            .filter(|log| self.angstrom_tokens.contains(&log.address))
+            .flat_map(|log| {
+                Transfer::decode_log(log, true)
+                    .map(|log| vec![log._from, log._to])
+                    .or_else(|_| Approval::decode_log(log, true).map(|log| vec![log._owner]))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
     /// gets any newly initialized pools in this block
     /// do we want to use logs here?
     fn get_new_pools(chain: &Chain) -> impl Iterator<Item = NewInitializedPool> + '_ {
@@ -196,17 +309,25 @@ where
 
 impl<DB> Future for EthDataCleanser<DB>
 where
-    DB: StateProviderFactory + Send + Sync + Unpin + 'static
+    DB: StateProviderFactory + CanonStateSubscriptions + Send + Sync + Unpin + 'static
 {
     type Output = ();
 
     fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.watchdog.poll_stalled(cx) {
+            let updates = self.db.subscribe_to_canonical_state();
+            self.canonical_updates = BroadcastStream::new(updates);
+        }
+
         // poll all canonical updates
         while let Poll::Ready(is_some) = self.canonical_updates.poll_next_unpin(cx).map(|res| {
             res.transpose()
                 .ok()
                 .flatten()
-                .map(|update| self.on_canon_update(update))
+                .map(|update| {
+                    self.watchdog.on_update();
+                    self.on_canon_update(update)
+                })
                 .is_some()
         }) {
             if !is_some {
@@ -232,6 +353,40 @@ pub enum EthEvent {
         address_changeset: Vec<Address>
     },
     ReorgedOrders(Vec<B256>),
+    /// users whose balances, approvals or nonces may have changed in a reorg
+    ChainReorg {
+        block_number: u64,
+        addresses:    Vec<Address>
+    },
     FinalizedBlock(u64),
     NewPool(NewInitializedPool)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn resubscribes_once_per_stall_timeout() {
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut watchdog = CanonStreamWatchdog::new(1);
+
+        // the first tick is immediate, and a block was just seen
+        assert!(!watchdog.poll_stalled(&mut cx));
+        assert!(!watchdog.stalled);
+
+        watchdog.last_update = Instant::now() - SLOT_TIME;
+        watchdog.interval = tokio::time::interval(SLOT_TIME);
+        assert!(watchdog.poll_stalled(&mut cx));
+        assert!(watchdog.stalled);
+        assert!(!health::is_healthy());
+
+        // the timer restarted, so there is no resubscribe until it runs out again
+        assert!(!watchdog.poll_stalled(&mut cx));
+
+        watchdog.on_update();
+        assert!(!watchdog.stalled);
+        assert!(health::is_healthy());
+    }
+}
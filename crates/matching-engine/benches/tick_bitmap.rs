@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use alloy::primitives::U256;
+use matching_engine::cfmm::uniswap::tick_bitmap::TickBitmapCursor;
+use rand::{thread_rng, Rng};
+
+/// fraction of ticks in the bitmap that are initialized
+const DENSITY: &[f64] = &[0.01, 0.1, 0.5];
+
+const TICK_SPACING: i32 = 60;
+const WORDS: i16 = 16;
+
+fn main() {
+    divan::main();
+}
+
+fn random_bitmap(density: f64) -> HashMap<i16, U256> {
+    let mut rng = thread_rng();
+    (-WORDS..WORDS)
+        .map(|word_pos| {
+            let word = (0..256)
+                .filter(|_| rng.gen_bool(density))
+                .fold(U256::ZERO, |word, bit| word | (U256::from(1) << bit));
+            (word_pos, word)
+        })
+        .filter(|(_, word)| !word.is_zero())
+        .collect()
+}
+
+/// Steps from the top of the bitmap to the bottom the same way a zero for one
+/// swap does, returning the amount of initialized ticks crossed.
+fn walk_down(mut next: impl FnMut(i32) -> (i32, bool)) -> usize {
+    let mut tick = WORDS as i32 * 256 * TICK_SPACING - 1;
+    let mut crossed = 0;
+    while tick > -(WORDS as i32) * 256 * TICK_SPACING {
+        let (tick_next, initialized) = next(tick);
+        crossed += initialized as usize;
+        tick = tick_next - 1;
+    }
+    crossed
+}
+
+#[divan::bench(args = DENSITY)]
+fn hashmap_lookup(bencher: divan::Bencher, density: f64) {
+    bencher
+        .with_inputs(|| random_bitmap(density))
+        .bench_refs(|bitmap| {
+            walk_down(|tick| {
+                uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                    bitmap,
+                    tick,
+                    TICK_SPACING,
+                    true
+                )
+                .unwrap()
+            })
+        });
+}
+
+#[divan::bench(args = DENSITY)]
+fn cached_word_cursor(bencher: divan::Bencher, density: f64) {
+    bencher
+        .with_inputs(|| random_bitmap(density))
+        .bench_refs(|bitmap| {
+            let mut cursor = TickBitmapCursor::new(bitmap, TICK_SPACING);
+            walk_down(|tick| cursor.next_initialized_tick_within_one_word(tick, true))
+        });
+}
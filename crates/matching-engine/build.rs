@@ -0,0 +1,51 @@
+//! Fails the build when the checked in batch request artifacts no longer
+//! match `src/cfmm/uniswap/artifacts.lock`, i.e. they were edited by hand or
+//! the manifest wasn't regenerated along with them.
+use std::path::Path;
+
+const ARTIFACT_DIR: &str = "src/cfmm/uniswap";
+const MANIFEST: &str = "src/cfmm/uniswap/artifacts.lock";
+
+fn main() {
+    println!("cargo:rerun-if-changed={MANIFEST}");
+    let manifest = std::fs::read_to_string(MANIFEST).expect("missing artifact manifest");
+
+    for line in manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let [artifact, source, source_hash, digest] =
+            line.split_whitespace().collect::<Vec<_>>()[..]
+        else {
+            panic!("malformed artifact manifest entry: {line}")
+        };
+
+        let path = Path::new(ARTIFACT_DIR).join(artifact);
+        println!("cargo:rerun-if-changed={}", path.display());
+        let contents = std::fs::read(&path)
+            .unwrap_or_else(|e| panic!("failed to read artifact {}: {e}", path.display()));
+
+        let compiled_from = format!("\"{source}\":{{\"keccak256\":\"{source_hash}\"");
+        if !contents
+            .windows(compiled_from.len())
+            .any(|w| w == compiled_from.as_bytes())
+        {
+            panic!(
+                "{artifact} wasn't compiled from {source} at {source_hash}, run `cargo xtask \
+                 regen-artifacts`"
+            );
+        }
+        if format!("{:016x}", fnv1a64(&contents)) != digest {
+            panic!(
+                "{artifact} doesn't match its manifest digest, run `cargo xtask regen-artifacts`"
+            );
+        }
+    }
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
@@ -1,5 +1,9 @@
 use angstrom_types::{
-    matching::uniswap::PoolPriceVec,
+    matching::{
+        price::{AskPrice, BidPrice, PriceError},
+        uniswap::PoolPriceVec,
+        SqrtPriceX96
+    },
     orders::{OrderID, OrderId, OrderPrice, OrderVolume},
     primitive::PoolId,
     sol_bindings::{
@@ -71,6 +75,16 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         matches!(self, Self::AMM(_))
     }
 
+    /// Is the order on the bid side of the book. An AMM that is bought from is
+    /// on the ask side
+    pub fn is_bid(&self) -> bool {
+        match self {
+            Self::BookOrder(o) => o.is_bid,
+            Self::BookOrderFragment(o) => o.is_bid,
+            Self::AMM(o) => !o.is_buy()
+        }
+    }
+
     /// Is the underlying order a Partial Fill compatible order
     pub fn is_partial(&self) -> bool {
         match self {
@@ -92,11 +106,12 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         }
     }
 
-    /// Retrieve the quantity available within the bounds of a given order
+    /// Retrieve the quantity available within the bounds of a given order,
+    /// less what has already been filled of it
     pub fn quantity(&self, limit_price: OrderPrice) -> OrderVolume {
         match self {
-            Self::BookOrder(o) => o.quantity(),
-            Self::BookOrderFragment(o) => o.quantity(),
+            Self::BookOrder(o) => o.remaining_quantity(),
+            Self::BookOrderFragment(o) => o.remaining_quantity(),
             Self::AMM(ammo) => ammo.quantity(limit_price).0
         }
     }
@@ -106,7 +121,20 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         match self {
             Self::BookOrder(o) => o.price().into(),
             Self::BookOrderFragment(o) => o.price().into(),
-            Self::AMM(o) => (*o.start_bound.price()).into()
+            Self::AMM(o) if self.is_bid() => {
+                BidPrice::from_sqrt_price(*o.start_bound.price()).into()
+            }
+            Self::AMM(o) => AskPrice::from_sqrt_price(*o.start_bound.price()).into()
+        }
+    }
+
+    /// The price of the order as a sqrt price, rounded towards its side of the
+    /// book
+    pub fn sqrt_price(&self) -> Result<SqrtPriceX96, PriceError> {
+        match self {
+            Self::AMM(o) => Ok(*o.start_bound.price()),
+            _ if self.is_bid() => BidPrice(self.price().into()).to_sqrt_price(),
+            _ => AskPrice(self.price().into()).to_sqrt_price()
         }
     }
 
@@ -1,11 +1,16 @@
-use angstrom_types::sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData};
+use angstrom_types::{
+    orders::PriceLevelPriority,
+    sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
+};
 
 /// There are lots of different ways we can sort the orders we get in, so let's
 /// make this modular
 
 pub enum SortStrategy {
     Unsorted,
-    ByPriceByVolume
+    ByPriceByVolume,
+    /// orders at the same price by the time they reached a node
+    ByPriceByArrival
 }
 
 impl Default for SortStrategy {
@@ -15,19 +20,33 @@ impl Default for SortStrategy {
 }
 
 impl SortStrategy {
+    /// The strategy ranking the orders like the bundle does.
+    pub fn by_price(priority: PriceLevelPriority) -> Self {
+        match priority {
+            PriceLevelPriority::Volume => Self::ByPriceByVolume,
+            PriceLevelPriority::Arrival => Self::ByPriceByArrival
+        }
+    }
+
+    fn priority(&self) -> Option<PriceLevelPriority> {
+        match self {
+            Self::Unsorted => None,
+            Self::ByPriceByVolume => Some(PriceLevelPriority::Volume),
+            Self::ByPriceByArrival => Some(PriceLevelPriority::Arrival)
+        }
+    }
+
     pub fn sort_bids(&self, bids: &mut [OrderWithStorageData<GroupedVanillaOrder>]) {
-        if let Self::ByPriceByVolume = self {
-            // Sort by price and then by volume - highest price first, highest volume first
-            // for same price
-            bids.sort_by(|a, b| b.priority_data.cmp(&a.priority_data));
+        // highest price first, the same price by the priority of the price level
+        if let Some(priority) = self.priority() {
+            bids.sort_by(|a, b| priority.cmp_bids(a, b));
         }
     }
 
     pub fn sort_asks(&self, asks: &mut [OrderWithStorageData<GroupedVanillaOrder>]) {
-        if let Self::ByPriceByVolume = self {
-            // Sort by price and then by volume - lowest price first, highest volume first
-            // for same price
-            asks.sort_by(|a, b| a.priority_data.cmp(&b.priority_data));
+        // lowest price first, the same price by the priority of the price level
+        if let Some(priority) = self.priority() {
+            asks.sort_by(|a, b| priority.cmp_asks(a, b));
         }
     }
 }
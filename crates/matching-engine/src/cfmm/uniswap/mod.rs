@@ -1,6 +1,8 @@
 pub mod pool;
 pub mod pool_manager;
 pub mod pool_providers;
+pub mod tick_bitmap;
+pub mod tick_window;
 pub mod tob;
 
 #[cfg(test)]
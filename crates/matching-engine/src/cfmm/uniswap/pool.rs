@@ -16,13 +16,17 @@ use amms::{
     },
     errors::{AMMError, EventLogError}
 };
+use angstrom_types::matching::{
+    uniswap::{LiqRange, PoolSnapshot},
+    SqrtPriceX96
+};
 use thiserror::Error;
 use uniswap_v3_math::{
     error::UniswapV3MathError,
     tick_math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK}
 };
 
-use crate::cfmm::uniswap::pool_manager::PoolManagerError;
+use crate::cfmm::uniswap::{pool_manager::PoolManagerError, tick_bitmap::TickBitmapCursor};
 
 sol! {
     #[allow(missing_docs)]
@@ -67,17 +71,20 @@ const MAX_TICKS_PER_REQUEST: u16 = 150;
 
 #[derive(Debug, Clone)]
 pub struct EnhancedUniswapV3Pool {
-    inner:                  UniswapV3Pool,
-    sync_swap_with_sim:     bool,
-    initial_ticks_per_side: u16
+    inner:              UniswapV3Pool,
+    sync_swap_with_sim: bool,
+    ticks_per_side:     u16,
+    /// lowest and highest tick of the last tick sync
+    synced_range:       Option<(i32, i32)>
 }
 
 impl EnhancedUniswapV3Pool {
     pub fn new(address: Address, initial_ticks_per_side: u16) -> Self {
         Self {
-            inner: UniswapV3Pool { address, ..Default::default() },
-            initial_ticks_per_side,
-            sync_swap_with_sim: false
+            inner:              UniswapV3Pool { address, ..Default::default() },
+            ticks_per_side:     initial_ticks_per_side,
+            sync_swap_with_sim: false,
+            synced_range:       None
         }
     }
 
@@ -97,6 +104,46 @@ impl EnhancedUniswapV3Pool {
         self.sync_swap_with_sim = sync_swap_with_sim;
     }
 
+    /// Number of ticks synced on each side of the current tick.
+    pub fn ticks_per_side(&self) -> u16 {
+        self.ticks_per_side
+    }
+
+    /// Takes effect on the next [`Self::sync_ticks`].
+    pub fn set_ticks_per_side(&mut self, ticks_per_side: u16) {
+        self.ticks_per_side = ticks_per_side;
+    }
+
+    /// The lowest and highest tick of the last tick sync.
+    pub fn synced_tick_range(&self) -> Option<(i32, i32)> {
+        self.synced_range
+    }
+
+    /// Adds a position to the loaded ticks the way minting it would, for
+    /// pools whose state isn't loaded from the chain.
+    pub fn add_liquidity(&mut self, tick_lower: i32, tick_upper: i32, liquidity: u128) {
+        for (tick, liquidity_net) in
+            [(tick_lower, liquidity as i128), (tick_upper, -(liquidity as i128))]
+        {
+            let info = self.inner.ticks.entry(tick).or_insert(Info {
+                initialized:     false,
+                liquidity_gross: 0,
+                liquidity_net:   0
+            });
+            let flipped = !info.initialized;
+            info.initialized = true;
+            info.liquidity_gross += liquidity;
+            info.liquidity_net += liquidity_net;
+            if flipped {
+                self.inner.flip_tick(tick, self.inner.tick_spacing);
+            }
+        }
+
+        if tick_lower <= self.tick && self.tick < tick_upper {
+            self.liquidity += liquidity;
+        }
+    }
+
     pub async fn get_uniswap_v3_tick_data_batch_request<P, T, N>(
         &self,
         tick_start: i32,
@@ -169,12 +216,12 @@ impl EnhancedUniswapV3Pool {
         self.ticks.clear();
         self.tick_bitmap.clear();
 
-        let total_ticks_to_fetch = self.initial_ticks_per_side * 2;
+        let total_ticks_to_fetch = self.ticks_per_side * 2;
         let mut remaining_ticks = total_ticks_to_fetch;
         //  +1 because the retrieve is gt start_tick, i.e. start one step back to
         // include the tick
         let mut start_tick = (self.tick / self.tick_spacing) * self.tick_spacing
-            - self.tick_spacing * (self.initial_ticks_per_side + 1) as i32;
+            - self.tick_spacing * (self.ticks_per_side + 1) as i32;
 
         // Fetch ticks from left to right
         let mut fetched_ticks = Vec::new();
@@ -199,6 +246,11 @@ impl EnhancedUniswapV3Pool {
             }
         }
 
+        self.synced_range = fetched_ticks
+            .first()
+            .zip(fetched_ticks.last())
+            .map(|(low, high)| (low.tick, high.tick));
+
         fetched_ticks
             .into_iter()
             .filter(|tick| tick.initialized)
@@ -270,6 +322,7 @@ impl EnhancedUniswapV3Pool {
         let mut sqrt_price_x_96 = self.sqrt_price;
         let mut tick = self.tick;
         let mut liquidity = self.liquidity;
+        let mut tick_bitmap = TickBitmapCursor::new(&self.tick_bitmap, self.tick_spacing);
 
         tracing::trace!(
             token_in = ?token_in,
@@ -290,12 +343,7 @@ impl EnhancedUniswapV3Pool {
         while amount_specified_remaining != I256::ZERO && sqrt_price_x_96 != sqrt_price_limit_x96 {
             let sqrt_price_start_x_96 = sqrt_price_x_96;
             let (tick_next, initialized) =
-                uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
-                    &self.tick_bitmap,
-                    tick,
-                    self.tick_spacing,
-                    zero_for_one
-                )?;
+                tick_bitmap.next_initialized_tick_within_one_word(tick, zero_for_one);
 
             let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
             let sqrt_price_next_x96 =
@@ -409,6 +457,47 @@ impl EnhancedUniswapV3Pool {
         Ok((swap_result.amount0, swap_result.amount1))
     }
 
+    /// Builds a [`PoolSnapshot`] out of the currently loaded ticks. The
+    /// liquidity of every range is derived from the current liquidity, so
+    /// only the loaded window of ticks is covered.
+    pub fn fetch_pool_snapshot(&self) -> eyre::Result<PoolSnapshot> {
+        let mut ticks = self
+            .ticks
+            .iter()
+            .filter(|(_, info)| info.initialized)
+            .map(|(tick, info)| (*tick, info.liquidity_net))
+            .collect::<Vec<_>>();
+        ticks.sort_by_key(|(tick, _)| *tick);
+
+        let Some(current) = ticks
+            .windows(2)
+            .position(|w| w[0].0 <= self.tick && self.tick < w[1].0)
+        else {
+            eyre::bail!("current tick {} is outside of the loaded ticks", self.tick)
+        };
+
+        let mut liquidity = vec![0u128; ticks.len() - 1];
+        liquidity[current] = self.liquidity;
+        for i in current + 1..liquidity.len() {
+            liquidity[i] = liquidity[i - 1]
+                .checked_add_signed(ticks[i].1)
+                .ok_or_else(|| eyre::eyre!("liquidity overflow at tick {}", ticks[i].0))?;
+        }
+        for i in (0..current).rev() {
+            liquidity[i] = liquidity[i + 1]
+                .checked_add_signed(-ticks[i + 1].1)
+                .ok_or_else(|| eyre::eyre!("liquidity underflow at tick {}", ticks[i + 1].0))?;
+        }
+
+        let ranges = ticks
+            .windows(2)
+            .zip(liquidity)
+            .map(|(w, liquidity)| LiqRange::new(w[0].0, w[1].0, liquidity))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        PoolSnapshot::new(ranges, SqrtPriceX96::from(self.sqrt_price))
+    }
+
     pub fn sync_from_swap_log(&mut self, log: Log) -> Result<(), PoolManagerError> {
         if self.sync_swap_with_sim {
             self.sync_swap_with_sim(log)
@@ -552,6 +641,12 @@ pub enum SwapSimulationError {
     ZeroAmountSpecified
 }
 
+impl From<SwapSimulationError> for angstrom_errors::SimError {
+    fn from(value: SwapSimulationError) -> Self {
+        Self::Swap(value.to_string())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{str::FromStr, sync::Arc};
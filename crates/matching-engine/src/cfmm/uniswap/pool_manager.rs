@@ -3,23 +3,31 @@ use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc
-    }
+    },
+    time::Instant
 };
 
 use alloy::{
+    network::Network,
     primitives::{Address, BlockNumber},
-    rpc::types::eth::{Block, Filter}
+    providers::Provider,
+    rpc::types::eth::{Block, Filter},
+    transports::Transport
 };
 use alloy_primitives::Log;
-use amms::{amm::AutomatedMarketMaker, errors::EventLogError};
+use amms::{
+    amm::AutomatedMarketMaker,
+    errors::{AMMError, EventLogError}
+};
+use angstrom_metrics::PoolSyncMetricsWrapper;
 use angstrom_types::matching::{
     uniswap::{LiqRange, PoolSnapshot},
     SqrtPriceX96
 };
 use arraydeque::ArrayDeque;
 use eyre::Error;
-use futures::StreamExt;
-use futures_util::stream::BoxStream;
+use futures::{stream::FuturesUnordered, StreamExt};
+use futures_util::{future::BoxFuture, stream::BoxStream};
 use itertools::Itertools;
 use thiserror::Error;
 use tokio::{
@@ -31,7 +39,11 @@ use tokio::{
 };
 
 use super::pool::SwapSimulationError;
-use crate::cfmm::uniswap::{pool::EnhancedUniswapV3Pool, pool_providers::PoolManagerProvider};
+use crate::cfmm::uniswap::{
+    pool::EnhancedUniswapV3Pool,
+    pool_providers::PoolManagerProvider,
+    tick_window::{TickLoader, TickWindowTracker}
+};
 
 pub type StateChangeCache = HashMap<Address, ArrayDeque<StateChange, 150>>;
 
@@ -42,7 +54,10 @@ pub struct UniswapPoolManager<P> {
     state_change_buffer: usize,
     state_change_cache:  Arc<RwLock<StateChangeCache>>,
     provider:            Arc<P>,
-    sync_started:        AtomicBool
+    sync_started:        AtomicBool,
+    /// loads ticks when a pool's window gets resized, windows stay fixed
+    /// without one
+    tick_loader:         Option<TickLoader>
 }
 
 impl<P> UniswapPoolManager<P>
@@ -65,10 +80,38 @@ where
             state_change_buffer,
             state_change_cache: Arc::new(RwLock::new(HashMap::new())),
             provider,
-            sync_started: AtomicBool::new(false)
+            sync_started: AtomicBool::new(false),
+            tick_loader: None
         }
     }
 
+    /// Resizes the tick window of each pool with its price movement, loading
+    /// the ticks of resized windows from `provider` in the background.
+    pub fn with_tick_window_resizing<Pr, T, N>(mut self, provider: Arc<Pr>) -> Self
+    where
+        Pr: Provider<T, N> + 'static,
+        T: Transport + Clone,
+        N: Network
+    {
+        self.tick_loader = Some(Arc::new(
+            move |mut pool: EnhancedUniswapV3Pool,
+                  block: BlockNumber|
+                  -> BoxFuture<'static, Result<EnhancedUniswapV3Pool, AMMError>> {
+                let provider = provider.clone();
+                Box::pin(async move {
+                    pool.sync_ticks(Some(block), provider).await?;
+                    Ok(pool)
+                })
+            }
+        ));
+        self
+    }
+
+    /// The addresses of all the pools being tracked.
+    pub fn pool_addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        self.pools.keys().copied()
+    }
+
     pub fn blocking_pool(
         &self,
         address: &Address
@@ -150,6 +193,8 @@ where
         let provider = Arc::clone(&self.provider);
         let filter = self.filter().await;
         let state_change_cache = Arc::clone(&self.state_change_cache);
+        let mut tick_windows = self.tick_loader.clone().map(TickWindowTracker::new);
+        let metrics = PoolSyncMetricsWrapper::new();
         let updated_pool_handle = tokio::spawn(async move {
             let mut block_stream: BoxStream<Option<u64>> = provider.subscribe_blocks();
             while let Some(block_number) = block_stream.next().await {
@@ -176,6 +221,13 @@ where
 
                     // set the last synced block to the head block number
                     last_synced_block = chain_head_block_number - 1;
+                    if let Some(tick_windows) = tick_windows.as_mut() {
+                        tick_windows.on_reorg(chain_head_block_number);
+                    }
+                }
+
+                if let Some(tick_windows) = tick_windows.as_mut() {
+                    tick_windows.apply_resized(&pools).await;
                 }
 
                 let logs = provider
@@ -188,36 +240,49 @@ where
                     )
                     .await?;
 
-                let logs_by_address = logs
+                let started = Instant::now();
+                // each pool is synced in its own task, only taking its own write lock
+                let synced = logs
                     .into_iter()
                     .map(|log| (log.address, log))
-                    .into_group_map();
-
-                for (addr, logs) in logs_by_address {
-                    if logs.is_empty() {
-                        continue;
+                    .into_group_map()
+                    .into_iter()
+                    .filter(|(addr, logs)| !logs.is_empty() && pools.contains_key(addr))
+                    .map(|(addr, logs)| {
+                        let pools = pools.clone();
+                        tokio::spawn(async move {
+                            let mut pool_guard = pools[&addr].write().await;
+                            Self::sync_pool_from_logs(&mut pool_guard, logs)
+                        })
+                    })
+                    .collect::<FuturesUnordered<_>>();
+
+                // a failed pool doesn't cut the block short, the pools that did sync
+                // still have to be recorded so that they can be unwound
+                let (synced, failed) = join_pool_syncs(synced).await;
+                let pools_synced = synced.len();
+                for pool in synced {
+                    if let Some(tick_windows) = tick_windows.as_mut() {
+                        tick_windows.on_state_change(&pool, chain_head_block_number);
                     }
-
-                    let Some(pool) = pools.get(&addr) else {
-                        continue;
-                    };
-
-                    let mut pool_guard = pool.write().await;
-                    let mut state_change_cache = state_change_cache.write().await;
-                    Self::handle_state_changes_from_logs(
-                        &mut pool_guard,
-                        &mut state_change_cache,
-                        logs,
-                        chain_head_block_number
+                    let address = pool.address();
+                    Self::add_state_change_to_cache(
+                        &mut *state_change_cache.write().await,
+                        StateChange::new(Some(pool), chain_head_block_number),
+                        address
                     )?;
 
                     if let Some(tx) = &pool_updated_tx {
-                        tx.send((pool_guard.address(), chain_head_block_number))
+                        tx.send((address, chain_head_block_number))
                             .await
                             .map_err(|e| tracing::error!("Failed to send pool update: {}", e))
                             .ok();
                     }
                 }
+                metrics.set_block_sync(started.elapsed().as_millis() as u64, pools_synced);
+                if let Some(e) = failed {
+                    return Err(e)
+                }
 
                 last_synced_block = chain_head_block_number;
             }
@@ -282,22 +347,16 @@ where
             .map_err(|_| PoolManagerError::CapacityError)
     }
 
-    fn handle_state_changes_from_logs(
+    /// Applies the logs to the pool, returning the pool as it is after them.
+    fn sync_pool_from_logs(
         pool: &mut EnhancedUniswapV3Pool,
-        state_change_cache: &mut StateChangeCache,
-        logs: Vec<Log>,
-        block_number: BlockNumber
-    ) -> Result<(), PoolManagerError> {
+        logs: Vec<Log>
+    ) -> Result<EnhancedUniswapV3Pool, PoolManagerError> {
         for log in logs {
             pool.sync_from_log(log)?;
         }
 
-        let pool_clone = pool.clone();
-        Self::add_state_change_to_cache(
-            state_change_cache,
-            StateChange::new(Some(pool_clone), block_number),
-            pool.address()
-        )
+        Ok(pool.clone())
     }
 
     pub fn get_market_snapshot(&self, address: Address) -> Result<PoolSnapshot, Error> {
@@ -343,6 +402,26 @@ impl StateChange {
     }
 }
 
+/// Waits for every pool sync of a block, returning the pools that synced along
+/// with the first error of those that didn't.
+async fn join_pool_syncs<T>(
+    mut syncs: FuturesUnordered<JoinHandle<Result<T, PoolManagerError>>>
+) -> (Vec<T>, Option<PoolManagerError>) {
+    let mut synced = Vec::with_capacity(syncs.len());
+    let mut failed = None;
+    while let Some(sync) = syncs.next().await {
+        match sync.map_err(PoolManagerError::from).and_then(|pool| pool) {
+            Ok(pool) => synced.push(pool),
+            Err(e) => {
+                tracing::error!(%e, "failed to sync pool");
+                failed.get_or_insert(e);
+            }
+        }
+    }
+
+    (synced, failed)
+}
+
 #[derive(Error, Debug)]
 pub enum PoolManagerError {
     #[error("Invalid block range")]
@@ -382,3 +461,52 @@ pub enum PoolManagerError {
     #[error("Synchronization has already been started")]
     SyncAlreadyStarted
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_for_every_pool_when_one_fails() {
+        let syncs = [(0, Ok(1)), (50, Ok(2)), (10, Err(PoolManagerError::ProviderError))]
+            .into_iter()
+            .map(|(delay, result)| {
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    result
+                })
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let (mut synced, failed) = join_pool_syncs(syncs).await;
+        synced.sort();
+        assert_eq!(synced, vec![1, 2]);
+        assert!(matches!(failed, Some(PoolManagerError::ProviderError)));
+    }
+
+    #[tokio::test]
+    async fn keeps_the_first_error_and_panicked_syncs() {
+        let syncs = FuturesUnordered::new();
+        syncs.push(tokio::spawn(async { Err::<u8, _>(PoolManagerError::ProviderError) }));
+        let (synced, failed) = join_pool_syncs(syncs).await;
+        assert!(synced.is_empty());
+        assert!(matches!(failed, Some(PoolManagerError::ProviderError)));
+
+        let syncs: FuturesUnordered<JoinHandle<Result<u8, PoolManagerError>>> =
+            FuturesUnordered::new();
+        syncs.push(tokio::spawn(async { panic!("sync panicked") }));
+        syncs.push(tokio::spawn(async { Ok(1) }));
+        let (synced, failed) = join_pool_syncs(syncs).await;
+        assert_eq!(synced, vec![1]);
+        assert!(matches!(failed, Some(PoolManagerError::JoinError(_))));
+    }
+
+    #[tokio::test]
+    async fn joins_a_block_without_syncs() {
+        let (synced, failed) = join_pool_syncs::<u8>(FuturesUnordered::new()).await;
+        assert!(synced.is_empty());
+        assert!(failed.is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! Backoff for hosted rpc providers that rate limit us. The delay between
+//! requests grows every time we get throttled and decays again with every
+//! successful request, so a node that runs close to its rate limit slows down
+//! instead of hammering the provider.
+use std::time::Duration;
+
+use alloy::transports::{RpcError, TransportError, TransportErrorKind};
+
+/// Error code some providers use instead of 429 when a rate limit is hit.
+const LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub base_delay:   Duration,
+    pub max_delay:    Duration,
+    /// amount of times a throttled request is retried before giving up
+    pub retry_budget: u32
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            base_delay:   Duration::from_millis(250),
+            max_delay:    Duration::from_secs(30),
+            retry_budget: 8
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AdaptiveBackoff {
+    config: RetryConfig,
+    delay:  Duration
+}
+
+impl AdaptiveBackoff {
+    pub fn new(config: RetryConfig) -> Self {
+        Self { config, delay: Duration::ZERO }
+    }
+
+    pub fn config(&self) -> RetryConfig {
+        self.config
+    }
+
+    /// How long to wait before sending the next request.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub fn on_throttled(&mut self) -> Duration {
+        self.delay = (self.delay * 2).clamp(self.config.base_delay, self.config.max_delay);
+        self.delay
+    }
+
+    pub fn on_success(&mut self) {
+        self.delay /= 2;
+        if self.delay < self.config.base_delay {
+            self.delay = Duration::ZERO;
+        }
+    }
+}
+
+pub fn is_rate_limited(error: &TransportError) -> bool {
+    match error {
+        RpcError::ErrorResp(payload) => {
+            let message = payload.message.to_lowercase();
+            payload.code == 429
+                || payload.code == LIMIT_EXCEEDED_CODE
+                || message.contains("rate limit")
+                || message.contains("too many requests")
+        }
+        RpcError::Transport(TransportErrorKind::HttpError(e)) => e.status == 429,
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{rpc::json_rpc::ErrorPayload, transports::HttpError};
+
+    use super::*;
+
+    #[test]
+    fn backoff_grows_and_decays() {
+        let config = RetryConfig {
+            base_delay:   Duration::from_millis(100),
+            max_delay:    Duration::from_millis(350),
+            retry_budget: 3
+        };
+        let mut backoff = AdaptiveBackoff::new(config);
+        assert_eq!(backoff.delay(), Duration::ZERO);
+
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(100));
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(200));
+        assert_eq!(backoff.on_throttled(), Duration::from_millis(350));
+
+        backoff.on_success();
+        assert_eq!(backoff.delay(), Duration::from_millis(175));
+        backoff.on_success();
+        assert_eq!(backoff.delay(), Duration::ZERO);
+    }
+
+    #[test]
+    fn detects_rate_limits() {
+        let payload = |code, message: &str| {
+            RpcError::ErrorResp(ErrorPayload {
+                code,
+                message: message.to_owned().into(),
+                data: None
+            })
+        };
+
+        assert!(is_rate_limited(&payload(429, "")));
+        assert!(is_rate_limited(&payload(LIMIT_EXCEEDED_CODE, "")));
+        assert!(is_rate_limited(&payload(-32000, "Too Many Requests")));
+        assert!(!is_rate_limited(&payload(-32000, "execution reverted")));
+        assert!(is_rate_limited(&RpcError::Transport(TransportErrorKind::HttpError(HttpError {
+            status: 429,
+            body:   String::new()
+        }))));
+    }
+}
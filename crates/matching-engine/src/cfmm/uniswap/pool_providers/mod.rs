@@ -4,6 +4,7 @@ use alloy::rpc::types::eth::Filter;
 use alloy_primitives::Log;
 
 use crate::cfmm::uniswap::pool_manager::PoolManagerError;
+pub mod backoff;
 pub mod canonical_state_adapter;
 pub mod mock_block_stream;
 pub mod provider_adapter;
@@ -1,4 +1,7 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex}
+};
 
 use alloy::{
     network::{BlockResponse, HeaderResponse, Network},
@@ -7,9 +10,16 @@ use alloy::{
     transports::Transport
 };
 use alloy_primitives::Log;
-use futures_util::{FutureExt, StreamExt};
+use angstrom_metrics::PoolProviderMetricsWrapper;
+use futures_util::{stream::BoxStream, StreamExt};
 
-use crate::cfmm::uniswap::{pool_manager::PoolManagerError, pool_providers::PoolManagerProvider};
+use crate::cfmm::uniswap::{
+    pool_manager::PoolManagerError,
+    pool_providers::{
+        backoff::{is_rate_limited, AdaptiveBackoff, RetryConfig},
+        PoolManagerProvider
+    }
+};
 
 pub struct ProviderAdapter<P, T, N>
 where
@@ -18,6 +28,8 @@ where
     N: Network + Send + Sync
 {
     inner:    Arc<P>,
+    backoff:  Arc<Mutex<AdaptiveBackoff>>,
+    metrics:  PoolProviderMetricsWrapper,
     _phantom: PhantomData<(T, N)>
 }
 
@@ -28,30 +40,114 @@ where
     N: Network + Send + Sync
 {
     pub fn new(inner: Arc<P>) -> Self {
-        Self { inner, _phantom: PhantomData }
+        Self {
+            inner,
+            backoff: Arc::new(Mutex::new(AdaptiveBackoff::new(RetryConfig::default()))),
+            metrics: PoolProviderMetricsWrapper::new(),
+            _phantom: PhantomData
+        }
+    }
+
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.backoff = Arc::new(Mutex::new(AdaptiveBackoff::new(config)));
+        self
     }
 }
 
+/// Waits out the current backoff delay.
+async fn pace(backoff: &Mutex<AdaptiveBackoff>) {
+    let delay = backoff.lock().unwrap().delay();
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn on_throttled(backoff: &Mutex<AdaptiveBackoff>, metrics: &PoolProviderMetricsWrapper) {
+    let delay = backoff.lock().unwrap().on_throttled();
+    metrics.incr_throttled_requests();
+    metrics.set_backoff_delay_millis(delay.as_millis() as u64);
+}
+
+fn on_success(backoff: &Mutex<AdaptiveBackoff>, metrics: &PoolProviderMetricsWrapper) {
+    let mut backoff = backoff.lock().unwrap();
+    backoff.on_success();
+    metrics.set_backoff_delay_millis(backoff.delay().as_millis() as u64);
+}
+
 impl<P, T, N> PoolManagerProvider for ProviderAdapter<P, T, N>
 where
     P: Provider<T, N> + 'static + Send + Sync,
     T: Transport + Clone + Send + Sync,
     N: Network + Send + Sync
 {
+    /// Re-subscribes whenever the subscription fails or ends, so that a
+    /// flaky provider never ends the stream.
     fn subscribe_blocks(&self) -> futures::stream::BoxStream<Option<u64>> {
-        let provider = self.inner.clone();
-        async move { provider.subscribe_blocks().await.unwrap().into_stream() }
-            .flatten_stream()
-            .map(|b| Some(b.header().number()))
-            .boxed()
+        let state = (
+            self.inner.clone(),
+            self.backoff.clone(),
+            self.metrics.clone(),
+            None::<BoxStream<'static, u64>>
+        );
+        futures_util::stream::unfold(state, |(provider, backoff, metrics, mut blocks)| async move {
+            loop {
+                if let Some(stream) = blocks.as_mut() {
+                    if let Some(number) = stream.next().await {
+                        return Some((Some(number), (provider, backoff, metrics, blocks)))
+                    }
+                    tracing::warn!("block subscription ended, resubscribing");
+                    metrics.incr_block_resubscribes();
+                    blocks = None;
+                }
+
+                pace(&backoff).await;
+                match provider.subscribe_blocks().await {
+                    Ok(subscription) => {
+                        on_success(&backoff, &metrics);
+                        blocks = Some(
+                            subscription
+                                .into_stream()
+                                .map(|b| b.header().number())
+                                .boxed()
+                        );
+                    }
+                    Err(e) => {
+                        if is_rate_limited(&e) {
+                            on_throttled(&backoff, &metrics);
+                        } else {
+                            tracing::warn!(%e, "failed to subscribe to blocks, retrying");
+                            backoff.lock().unwrap().on_throttled();
+                        }
+                    }
+                }
+            }
+        })
+        .boxed()
     }
 
     async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, PoolManagerError> {
-        let alloy_logs = self
-            .inner
-            .get_logs(filter)
-            .await
-            .map_err(PoolManagerError::from)?;
+        let retry_budget = self.backoff.lock().unwrap().config().retry_budget;
+        let mut retries = 0;
+        let alloy_logs = loop {
+            pace(&self.backoff).await;
+            match self.inner.get_logs(filter).await {
+                Ok(logs) => {
+                    on_success(&self.backoff, &self.metrics);
+                    break logs
+                }
+                Err(e) if is_rate_limited(&e) && retries < retry_budget => {
+                    on_throttled(&self.backoff, &self.metrics);
+                    retries += 1;
+                    tracing::debug!(retries, "get_logs was throttled, backing off");
+                }
+                Err(e) => {
+                    if is_rate_limited(&e) {
+                        self.metrics.incr_exhausted_retries();
+                    }
+                    return Err(e.into())
+                }
+            }
+        };
 
         let reth_logs = alloy_logs
             .iter()
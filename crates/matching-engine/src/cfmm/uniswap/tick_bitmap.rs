@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use alloy::primitives::U256;
+
+/// Walks a uniswap tick bitmap while remembering the last word it loaded.
+///
+/// `uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word` hashes
+/// into the bitmap on every step of a swap. Swaps through dense liquidity keep
+/// stepping inside the same 256 tick word, so caching that word turns most of
+/// those lookups into a compare. The results are identical to the uniswap
+/// implementation.
+#[derive(Debug, Clone)]
+pub struct TickBitmapCursor<'a> {
+    bitmap:       &'a HashMap<i16, U256>,
+    tick_spacing: i32,
+    /// the word position and value of the last word we loaded
+    cached:       Option<(i16, U256)>
+}
+
+impl<'a> TickBitmapCursor<'a> {
+    pub fn new(bitmap: &'a HashMap<i16, U256>, tick_spacing: i32) -> Self {
+        Self { bitmap, tick_spacing, cached: None }
+    }
+
+    /// Returns the next initialized tick contained in the same word as `tick`,
+    /// searching to the left when `lte` is set and to the right otherwise. If
+    /// no tick in the word is initialized, the word boundary is returned along
+    /// with `false`.
+    pub fn next_initialized_tick_within_one_word(&mut self, tick: i32, lte: bool) -> (i32, bool) {
+        let mut compressed = tick / self.tick_spacing;
+        // round towards negative infinity
+        if tick < 0 && tick % self.tick_spacing != 0 {
+            compressed -= 1;
+        }
+
+        if lte {
+            let (word_pos, bit_pos) = position(compressed);
+            // all the bits at or to the right of the current bit
+            let mask = (U256::from(1) << bit_pos) - U256::from(1) + (U256::from(1) << bit_pos);
+            let masked = self.word(word_pos) & mask;
+
+            if masked.is_zero() {
+                ((compressed - bit_pos as i32) * self.tick_spacing, false)
+            } else {
+                let msb = 255 - masked.leading_zeros() as i32;
+                ((compressed - (bit_pos as i32 - msb)) * self.tick_spacing, true)
+            }
+        } else {
+            // start from the word of the next tick, since the current tick state
+            // doesn't matter
+            let (word_pos, bit_pos) = position(compressed + 1);
+            // all the bits at or to the left of the current bit
+            let mask = !((U256::from(1) << bit_pos) - U256::from(1));
+            let masked = self.word(word_pos) & mask;
+
+            if masked.is_zero() {
+                ((compressed + 1 + (255 - bit_pos as i32)) * self.tick_spacing, false)
+            } else {
+                let lsb = masked.trailing_zeros() as i32;
+                ((compressed + 1 + (lsb - bit_pos as i32)) * self.tick_spacing, true)
+            }
+        }
+    }
+
+    fn word(&mut self, word_pos: i16) -> U256 {
+        match self.cached {
+            Some((pos, word)) if pos == word_pos => word,
+            _ => {
+                let word = self.bitmap.get(&word_pos).copied().unwrap_or_default();
+                self.cached = Some((word_pos, word));
+                word
+            }
+        }
+    }
+}
+
+/// Splits a compressed tick into the position of its word and its bit inside
+/// that word.
+fn position(compressed: i32) -> (i16, u8) {
+    ((compressed >> 8) as i16, (compressed & 0xff) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    fn random_bitmap(words: i16, density: f64) -> HashMap<i16, U256> {
+        let mut rng = thread_rng();
+        let mut bitmap = HashMap::new();
+        for word_pos in -words..words {
+            let mut word = U256::ZERO;
+            for bit in 0..256 {
+                if rng.gen_bool(density) {
+                    word |= U256::from(1) << bit;
+                }
+            }
+            if !word.is_zero() {
+                bitmap.insert(word_pos, word);
+            }
+        }
+        bitmap
+    }
+
+    #[test]
+    fn matches_uniswap_implementation() {
+        let mut rng = thread_rng();
+        for tick_spacing in [1, 10, 60, 200] {
+            for density in [0.0, 0.01, 0.5] {
+                let bitmap = random_bitmap(4, density);
+                let mut cursor = TickBitmapCursor::new(&bitmap, tick_spacing);
+                let bound = 4 * 256 * tick_spacing;
+
+                for _ in 0..2000 {
+                    let tick = rng.gen_range(-bound..bound);
+                    let lte = rng.gen_bool(0.5);
+                    let expected =
+                        uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                            &bitmap,
+                            tick,
+                            tick_spacing,
+                            lte
+                        )
+                        .unwrap();
+
+                    assert_eq!(
+                        cursor.next_initialized_tick_within_one_word(tick, lte),
+                        expected,
+                        "tick {tick}, spacing {tick_spacing}, lte {lte}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walks_through_a_word_in_both_directions() {
+        let bitmap = HashMap::from([(0_i16, (U256::from(1) << 3) | (U256::from(1) << 7))]);
+        let mut cursor = TickBitmapCursor::new(&bitmap, 10);
+
+        assert_eq!(cursor.next_initialized_tick_within_one_word(0, false), (30, true));
+        assert_eq!(cursor.next_initialized_tick_within_one_word(30, false), (70, true));
+        assert_eq!(cursor.next_initialized_tick_within_one_word(70, false), (2550, false));
+
+        assert_eq!(cursor.next_initialized_tick_within_one_word(69, true), (30, true));
+        assert_eq!(cursor.next_initialized_tick_within_one_word(29, true), (0, false));
+        assert_eq!(cursor.next_initialized_tick_within_one_word(-1, true), (-2560, false));
+    }
+}
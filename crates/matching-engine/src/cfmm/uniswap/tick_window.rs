@@ -0,0 +1,224 @@
+//! Sizes the window of ticks synced around the current tick of a pool by how
+//! far its price actually moves. Stable pairs barely leave their tick, so a
+//! narrow window saves requests, while volatile pairs need a wide one so that
+//! swaps don't run off the synced ticks.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc
+};
+
+use alloy::primitives::{Address, BlockNumber};
+use amms::errors::AMMError;
+use angstrom_metrics::TickWindowMetricsWrapper;
+use futures::future::BoxFuture;
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    RwLock
+};
+
+use crate::cfmm::uniswap::pool::EnhancedUniswapV3Pool;
+
+/// ticks per side pools start out with, before we have seen them move
+pub const DEFAULT_TICKS_PER_SIDE: u16 = 200;
+pub const MIN_TICKS_PER_SIDE: u16 = 50;
+pub const MAX_TICKS_PER_SIDE: u16 = 1000;
+/// number of state changes the movement of a pool is tracked over
+const MOVEMENT_HISTORY: usize = 300;
+/// the window covers this many times the largest move in the history
+const MOVEMENT_HEADROOM: u32 = 8;
+/// windows are only shrunk once we have seen this many state changes
+const MIN_HISTORY_TO_SHRINK: usize = 50;
+
+/// Loads the ticks of a pool at a block, with the window the pool is set to.
+pub type TickLoader = Arc<
+    dyn Fn(
+            EnhancedUniswapV3Pool,
+            BlockNumber
+        ) -> BoxFuture<'static, Result<EnhancedUniswapV3Pool, AMMError>>
+        + Send
+        + Sync
+>;
+
+/// A pool loaded with its new window, at the block it was loaded at.
+type Resized = (Address, BlockNumber, Result<EnhancedUniswapV3Pool, AMMError>);
+
+/// The realized tick movement of a single pool.
+#[derive(Debug, Clone)]
+pub struct TickWindow {
+    last_tick: i32,
+    /// moves per state change in multiples of the tick spacing, oldest first
+    moves:     VecDeque<u32>
+}
+
+impl TickWindow {
+    pub fn new(tick: i32) -> Self {
+        Self { last_tick: tick, moves: VecDeque::with_capacity(MOVEMENT_HISTORY) }
+    }
+
+    /// Records the tick of the pool after a block that changed its state.
+    pub fn on_new_tick(&mut self, tick: i32, tick_spacing: i32) {
+        let moved = self.last_tick.abs_diff(tick) / tick_spacing.unsigned_abs().max(1);
+        self.last_tick = tick;
+
+        if self.moves.len() == MOVEMENT_HISTORY {
+            self.moves.pop_front();
+        }
+        self.moves.push_back(moved);
+    }
+
+    /// The ticks per side that cover the largest recent move with headroom.
+    pub fn ticks_per_side(&self) -> u16 {
+        let Some(max_move) = self.moves.iter().max() else { return DEFAULT_TICKS_PER_SIDE };
+        max_move
+            .saturating_mul(MOVEMENT_HEADROOM)
+            .clamp(MIN_TICKS_PER_SIDE as u32, MAX_TICKS_PER_SIDE as u32) as u16
+    }
+
+    /// Whether the pool should be re-synced with [`Self::ticks_per_side`]
+    /// ticks. Windows grow as soon as they are too small, but only shrink once
+    /// they are more than twice as large as needed so that small changes in
+    /// movement don't cause a re-sync. Pools whose tick drifted within an
+    /// eighth of the window of its edge are re-synced around the new tick.
+    pub fn needs_resync(&self, pool: &EnhancedUniswapV3Pool) -> bool {
+        let target = self.ticks_per_side();
+        let current = pool.ticks_per_side();
+        if target > current || (target < current / 2 && self.moves.len() >= MIN_HISTORY_TO_SHRINK) {
+            return true
+        }
+
+        let Some((low, high)) = pool.synced_tick_range() else { return false };
+        let margin = (high - low) / 8;
+        pool.tick < low + margin || pool.tick > high - margin
+    }
+}
+
+/// Tracks the movement of every pool and re-syncs their windows in the
+/// background. Re-synced pools are only swapped in if the pool didn't change
+/// while its ticks were loading, otherwise the next change tries again.
+pub(crate) struct TickWindowTracker {
+    windows:     HashMap<Address, TickWindow>,
+    /// block of the last state change of each pool
+    last_change: HashMap<Address, BlockNumber>,
+    /// pools that are being re-synced
+    in_flight:   HashSet<Address>,
+    loader:      TickLoader,
+    resized_tx:  UnboundedSender<Resized>,
+    resized_rx:  UnboundedReceiver<Resized>,
+    metrics:     TickWindowMetricsWrapper
+}
+
+impl TickWindowTracker {
+    pub(crate) fn new(loader: TickLoader) -> Self {
+        let (resized_tx, resized_rx) = unbounded_channel();
+        Self {
+            windows: HashMap::new(),
+            last_change: HashMap::new(),
+            in_flight: HashSet::new(),
+            loader,
+            resized_tx,
+            resized_rx,
+            metrics: TickWindowMetricsWrapper::new()
+        }
+    }
+
+    /// Records the state change of the pool, starting a re-sync if its window
+    /// no longer fits.
+    pub(crate) fn on_state_change(&mut self, pool: &EnhancedUniswapV3Pool, block: BlockNumber) {
+        let address = pool.address;
+        self.last_change.insert(address, block);
+
+        let window = self.windows.entry(address).or_insert_with(|| {
+            self.metrics
+                .set_ticks_per_side(address, pool.ticks_per_side());
+            TickWindow::new(pool.tick)
+        });
+        window.on_new_tick(pool.tick, pool.tick_spacing);
+
+        if self.in_flight.contains(&address) || !window.needs_resync(pool) {
+            return
+        }
+
+        let ticks_per_side = window.ticks_per_side();
+        tracing::debug!(
+            ?address,
+            from = pool.ticks_per_side(),
+            to = ticks_per_side,
+            "resyncing tick window"
+        );
+        self.in_flight.insert(address);
+
+        let mut resized = pool.clone();
+        resized.set_ticks_per_side(ticks_per_side);
+        let load = (self.loader)(resized, block);
+        let resized_tx = self.resized_tx.clone();
+        tokio::spawn(async move {
+            let _ = resized_tx.send((address, block, load.await));
+        });
+    }
+
+    /// State changes after the reorged block are gone, so pools loaded at
+    /// those blocks must not be swapped in.
+    pub(crate) fn on_reorg(&mut self, block: BlockNumber) {
+        self.last_change.retain(|_, changed| *changed < block);
+    }
+
+    /// Swaps in the pools that finished re-syncing.
+    pub(crate) async fn apply_resized(
+        &mut self,
+        pools: &HashMap<Address, RwLock<EnhancedUniswapV3Pool>>
+    ) {
+        while let Ok((address, block, resized)) = self.resized_rx.try_recv() {
+            self.in_flight.remove(&address);
+            let resized = match resized {
+                Ok(resized) => resized,
+                Err(e) => {
+                    tracing::warn!(?address, %e, "failed to resync tick window");
+                    continue
+                }
+            };
+
+            if self.last_change.get(&address) != Some(&block) {
+                continue
+            }
+            let Some(pool) = pools.get(&address) else { continue };
+
+            self.metrics
+                .set_ticks_per_side(address, resized.ticks_per_side());
+            self.metrics.incr_resizes();
+            *pool.write().await = resized;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_the_window_by_the_largest_move() {
+        let mut window = TickWindow::new(0);
+        assert_eq!(window.ticks_per_side(), DEFAULT_TICKS_PER_SIDE);
+
+        // stable pools get the smallest window
+        window.on_new_tick(10, 10);
+        window.on_new_tick(0, 10);
+        assert_eq!(window.ticks_per_side(), MIN_TICKS_PER_SIDE);
+
+        window.on_new_tick(300, 10);
+        assert_eq!(window.ticks_per_side(), 30 * MOVEMENT_HEADROOM as u16);
+
+        window.on_new_tick(-100_000, 10);
+        assert_eq!(window.ticks_per_side(), MAX_TICKS_PER_SIDE);
+    }
+
+    #[test]
+    fn forgets_old_moves() {
+        let mut window = TickWindow::new(0);
+        window.on_new_tick(1000, 1);
+        for _ in 0..MOVEMENT_HISTORY {
+            window.on_new_tick(1000, 1);
+        }
+
+        assert_eq!(window.ticks_per_side(), MIN_TICKS_PER_SIDE);
+    }
+}
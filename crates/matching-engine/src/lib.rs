@@ -3,7 +3,7 @@ use std::collections::HashSet;
 use angstrom_types::{
     consensus::PreProposal,
     matching::uniswap::PoolSnapshot,
-    orders::PoolSolution,
+    orders::{PoolSolution, PriceLevelPriority},
     primitive::PoolId,
     sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
 };
@@ -14,10 +14,13 @@ pub mod book;
 pub mod cfmm;
 pub mod manager;
 pub mod matcher;
+pub mod quote_engine;
 pub mod simulation;
+pub mod solver;
 pub mod strategy;
 
 pub use manager::MatchingManager;
+pub use quote_engine::{QuoteEngine, QuotedPool};
 
 pub trait MatchingEngineHandle: Send + Sync + Clone + Unpin + 'static {
     fn solve_pools(
@@ -29,9 +32,12 @@ pub trait MatchingEngineHandle: Send + Sync + Clone + Unpin + 'static {
 pub fn build_book(
     id: PoolId,
     amm: Option<PoolSnapshot>,
-    orders: HashSet<OrderWithStorageData<GroupedVanillaOrder>>
+    orders: HashSet<OrderWithStorageData<GroupedVanillaOrder>>,
+    priority: PriceLevelPriority
 ) -> OrderBook {
     let (bids, asks) = orders.into_iter().partition(|o| o.is_bid);
 
-    OrderBook::new(id, amm, bids, asks, Some(book::sort::SortStrategy::ByPriceByVolume))
+    let sort = book::sort::SortStrategy::by_price(priority);
+
+    OrderBook::new(id, amm, bids, asks, Some(sort))
 }
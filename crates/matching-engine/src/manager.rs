@@ -1,8 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
 use angstrom_types::{
-    consensus::PreProposal,
-    orders::PoolSolution,
+    consensus::{PreProposal, Proposal},
+    orders::{PoolSolution, PriceLevelPriority},
     primitive::PoolId,
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
@@ -19,12 +19,17 @@ use tokio::{
     task::JoinSet
 };
 
-use crate::{
-    book::OrderBook,
-    build_book,
-    strategy::{MatchingStrategy, SimpleCheckpointStrategy},
-    MatchingEngineHandle
-};
+use crate::{book::OrderBook, build_book, solver::solve_pool, MatchingEngineHandle};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ProposalVerificationError {
+    #[error("solution for pool {0} does not match")]
+    Mismatch(PoolId),
+    #[error("proposal has a solution for pool {0} that has no orders")]
+    UnexpectedPool(PoolId),
+    #[error("matching task failed")]
+    MatchingFailed
+}
 
 pub enum MatcherCommand {
     BuildProposal(Vec<PreProposal>, oneshot::Sender<Result<Vec<PoolSolution>, String>>)
@@ -59,13 +64,21 @@ impl MatchingEngineHandle for MatcherHandle {
     }
 }
 
-pub struct MatchingManager {}
+#[derive(Debug, Default)]
+pub struct MatchingManager {
+    /// how the orders at the same price are ranked
+    priority: PriceLevelPriority
+}
 
 impl MatchingManager {
-    pub fn spawn<TP: TaskSpawner>(tp: TP) -> MatcherHandle {
+    pub fn new(priority: PriceLevelPriority) -> Self {
+        Self { priority }
+    }
+
+    pub fn spawn<TP: TaskSpawner>(tp: TP, priority: PriceLevelPriority) -> MatcherHandle {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
 
-        let fut = manager_thread(rx).boxed();
+        let fut = manager_thread(rx, priority).boxed();
         tp.spawn_critical("matching_engine", fut);
 
         MatcherHandle { sender: tx }
@@ -74,17 +87,13 @@ impl MatchingManager {
     pub fn orders_by_pool_id(
         preproposals: &[PreProposal]
     ) -> HashMap<PoolId, HashSet<OrderWithStorageData<GroupedVanillaOrder>>> {
-        preproposals
-            .iter()
-            .flat_map(|p| p.limit.iter())
-            .cloned()
-            .fold(HashMap::new(), |mut acc, order| {
-                acc.entry(order.pool_id).or_default().insert(order);
-                acc
-            })
+        PreProposal::orders_by_pool_id(preproposals)
     }
 
-    pub fn build_books(preproposals: &[PreProposal]) -> Vec<OrderBook> {
+    pub fn build_books(
+        preproposals: &[PreProposal],
+        priority: PriceLevelPriority
+    ) -> Vec<OrderBook> {
         // Pull all the orders out of all the preproposals and build OrderPools out of
         // them.  This is ugly and inefficient right now
         let book_sources = Self::orders_by_pool_id(preproposals);
@@ -93,18 +102,16 @@ impl MatchingManager {
             .into_iter()
             .map(|(id, orders)| {
                 let amm = None;
-                build_book(id, amm, orders)
+                build_book(id, amm, orders, priority)
             })
             .collect()
     }
 
-    pub async fn build_proposal(
-        &self,
-        preproposals: Vec<PreProposal>
-    ) -> Result<Vec<PoolSolution>, String> {
+    /// Spawns the matching of every pool on its own blocking task.
+    fn solve_books(&self, preproposals: &[PreProposal]) -> JoinSet<(PoolId, Option<PoolSolution>)> {
         // Pull all the orders out of all the preproposals and build OrderPools out of
         // them.  This is ugly and inefficient right now
-        let books = Self::build_books(&preproposals);
+        let books = Self::build_books(preproposals, self.priority);
 
         let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> = preproposals
             .iter()
@@ -123,22 +130,63 @@ impl MatchingManager {
             // not a problem while I'm testing, but leaving this note here as it may be
             // important for future efficiency gains
             solution_set.spawn_blocking(move || {
-                SimpleCheckpointStrategy::run(&b).map(|s| s.solution(searcher))
+                (b.id(), solve_pool(&b, searcher).map(|solved| solved.solution))
             });
         });
+
+        solution_set
+    }
+
+    pub async fn build_proposal(
+        &self,
+        preproposals: Vec<PreProposal>
+    ) -> Result<Vec<PoolSolution>, String> {
+        let mut solution_set = self.solve_books(&preproposals);
         let mut solutions = Vec::new();
         while let Some(res) = solution_set.join_next().await {
-            if let Ok(Some(r)) = res {
+            if let Ok((_, Some(r))) = res {
                 solutions.push(r);
             }
         }
+        // pools finish in any order, proposals need them sorted
+        solutions.sort_by_key(|solution| solution.id);
 
         Ok(solutions)
     }
+
+    /// Re-runs the matching of every pool of the proposal in parallel,
+    /// returning as soon as a pool doesn't match the solution of the proposal.
+    pub async fn verify_proposal(
+        &self,
+        proposal: &Proposal
+    ) -> Result<(), ProposalVerificationError> {
+        let mut expected = proposal
+            .solutions
+            .iter()
+            .map(|solution| (solution.id, solution))
+            .collect::<HashMap<_, _>>();
+
+        let mut solution_set = self.solve_books(&proposal.matched_preproposals());
+        while let Some(res) = solution_set.join_next().await {
+            let Ok((pool_id, solution)) = res else {
+                solution_set.abort_all();
+                return Err(ProposalVerificationError::MatchingFailed)
+            };
+            if solution.as_ref() != expected.remove(&pool_id) {
+                solution_set.abort_all();
+                return Err(ProposalVerificationError::Mismatch(pool_id))
+            }
+        }
+
+        match expected.into_keys().min() {
+            Some(pool_id) => Err(ProposalVerificationError::UnexpectedPool(pool_id)),
+            None => Ok(())
+        }
+    }
 }
 
-pub async fn manager_thread(mut input: Receiver<MatcherCommand>) {
-    let manager = MatchingManager {};
+pub async fn manager_thread(mut input: Receiver<MatcherCommand>, priority: PriceLevelPriority) {
+    let manager = MatchingManager::new(priority);
 
     while let Some(c) = input.recv().await {
         match c {
@@ -154,21 +202,48 @@ mod tests {
     use std::collections::HashSet;
 
     use alloy::primitives::FixedBytes;
-    use angstrom_types::consensus::PreProposal;
+    use angstrom_types::consensus::{PreProposal, Proposal};
     use testing_tools::type_generator::consensus::preproposal::PreproposalBuilder;
 
-    use super::MatchingManager;
+    use super::{MatchingManager, ProposalVerificationError};
 
     #[tokio::test]
     async fn can_build_proposal() {
-        let manager = MatchingManager {};
+        let manager = MatchingManager::default();
         let preproposals = vec![];
         let _ = manager.build_proposal(preproposals).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn verifies_own_proposal() {
+        let manager = MatchingManager::default();
+        let preproposals: Vec<PreProposal> = (0..3)
+            .map(|_| {
+                PreproposalBuilder::new()
+                    .order_count(10)
+                    .for_random_pools(2)
+                    .for_block(100)
+                    .build()
+            })
+            .collect();
+        let solutions = manager.build_proposal(preproposals.clone()).await.unwrap();
+        assert!(solutions.windows(2).all(|w| w[0].id <= w[1].id));
+
+        let mut proposal = Proposal { preproposals, solutions, ..Default::default() };
+        assert_eq!(manager.verify_proposal(&proposal).await, Ok(()));
+
+        // dropping the solution of a pool that has orders is caught
+        assert!(!proposal.solutions.is_empty(), "every pool with orders has a solution");
+        let pool_id = proposal.solutions.remove(0).id;
+        assert_eq!(
+            manager.verify_proposal(&proposal).await,
+            Err(ProposalVerificationError::Mismatch(pool_id))
+        );
+    }
+
     #[tokio::test]
     async fn will_combine_preproposals() {
-        let manager = MatchingManager {};
+        let manager = MatchingManager::default();
         let preproposals: Vec<PreProposal> = (0..3)
             .map(|_| {
                 PreproposalBuilder::new()
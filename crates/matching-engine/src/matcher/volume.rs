@@ -2,7 +2,7 @@ use std::{cell::Cell, cmp::Ordering};
 
 use alloy::primitives::U256;
 use angstrom_types::{
-    matching::{uniswap::PoolPrice, Ray, SqrtPriceX96},
+    matching::{price::BidPrice, uniswap::PoolPrice, Ray},
     orders::{NetAmmOrder, OrderFillState, OrderOutcome, PoolSolution},
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
@@ -208,9 +208,9 @@ impl<'a> VolumeFillMatcher<'a> {
                 match bid_q.cmp(&ask_q) {
                     Ordering::Equal => {
                         // We annihilated
+                        // rounding down keeps the price at or above the ask
                         self.results.price =
-                            Some((*(ask.price() + bid.price()) / U256::from(2)).into());
-                        // self.results.price = Some((ask.price() + bid.price()) / 2.0_f64);
+                            Some(BidPrice::midpoint(ask.price().into(), bid.price().into()).into());
                         // Mark as filled if non-AMM order
                         if !ask.is_amm() {
                             self.ask_outcomes[self.ask_idx.get()] = OrderFillState::CompleteFill
@@ -285,8 +285,9 @@ impl<'a> VolumeFillMatcher<'a> {
         let book_order = book.get(cur_idx);
         // See if our AMM takes precedence
         amm.and_then(|amm_price| {
-            let target_price = book_order
-                .map(|o| SqrtPriceX96::from(Ray::from(*OrderContainer::BookOrder(o).price())));
+            // a book price past the highest sqrt price doesn't bound the AMM
+            let target_price =
+                book_order.and_then(|o| OrderContainer::BookOrder(o).sqrt_price().ok());
             amm_price.order_to_target(target_price, !is_bid)
         })
         .map(OrderContainer::AMM)
@@ -410,6 +411,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partial_bid_fills_across_price_levels() {
+        let pool_id = PoolId::random();
+        let bid_order = UserOrderBuilder::new()
+            .partial()
+            .amount(100)
+            .min_price(Ray::from(Uint::from(1_000_000_000_u128)))
+            .with_storage()
+            .bid()
+            .build();
+        let asks = [(1_000_u128, 30_u128), (2_000, 30), (3_000, 50)]
+            .into_iter()
+            .map(|(price, amount)| {
+                UserOrderBuilder::new()
+                    .exact()
+                    .amount(amount)
+                    .min_price(Ray::from(Uint::from(price)))
+                    .with_storage()
+                    .ask()
+                    .build()
+            })
+            .collect();
+        let book = OrderBook::new(pool_id, None, vec![bid_order], asks, None);
+        let mut matcher = VolumeFillMatcher::new(&book);
+        let _fill_outcome = matcher.fill();
+
+        // the last ask is more than what is left of the bid and can't be
+        // partially filled, so the bid stops at the first two
+        let checkpoint = matcher.from_checkpoint().unwrap();
+        assert_eq!(checkpoint.bid_outcomes, vec![OrderFillState::PartialFill(Uint::from(60))]);
+        assert_eq!(
+            checkpoint.ask_outcomes,
+            vec![
+                OrderFillState::CompleteFill,
+                OrderFillState::CompleteFill,
+                OrderFillState::Unfilled
+            ]
+        );
+    }
+
     fn basic_order_book(
         is_bid: bool,
         count: usize,
@@ -0,0 +1,275 @@
+//! Pool tracking, order book and matching without a node. Lets a router get
+//! quotes consistent with what Angstrom would clear inside its own service,
+//! following the chain through an rpc provider instead of consensus and
+//! networking.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc
+};
+
+use alloy::{
+    network::Network,
+    primitives::{Address, BlockNumber, B256},
+    providers::Provider,
+    transports::Transport
+};
+use angstrom_types::{
+    matching::Ray,
+    orders::{OrderFillState, PoolSolution, PriceLevelPriority},
+    primitive::PoolId,
+    sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use crate::{
+    book::OrderBook,
+    build_book,
+    cfmm::uniswap::{
+        pool::EnhancedUniswapV3Pool,
+        pool_manager::{PoolManagerError, UniswapPoolManager},
+        pool_providers::{provider_adapter::ProviderAdapter, PoolManagerProvider}
+    },
+    strategy::{MatchingStrategy, SimpleCheckpointStrategy}
+};
+
+/// Ticks loaded on each side of the current tick of a pool.
+pub const DEFAULT_QUOTE_TICKS_PER_SIDE: u16 = 400;
+
+#[derive(Debug, thiserror::Error)]
+pub enum QuoteError {
+    #[error("pool {0} is not quoted")]
+    UnknownPool(PoolId),
+    #[error("uniswap pool {0} is not tracked")]
+    UntrackedPool(Address),
+    #[error("failed to snapshot pool {0}: {1}")]
+    Snapshot(PoolId, eyre::Report),
+    #[error("matching task failed")]
+    MatchingFailed,
+    #[error("book has no solution")]
+    NoSolution
+}
+
+/// A pool to quote and the uniswap pool it settles against.
+#[derive(Debug, Clone, Copy)]
+pub struct QuotedPool {
+    pub pool_id: PoolId,
+    pub address: Address
+}
+
+/// How an order would fare if it were in the book of the next block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderQuote {
+    /// uniform clearing price of the pool, as t1/t0
+    pub ucp:     Ray,
+    pub outcome: OrderFillState
+}
+
+pub struct QuoteEngine<P> {
+    pool_manager: UniswapPoolManager<P>,
+    pools:        HashMap<PoolId, Address>,
+    books:        RwLock<HashMap<PoolId, HashSet<OrderWithStorageData<GroupedVanillaOrder>>>>,
+    priority:     PriceLevelPriority
+}
+
+impl<P, T, N> QuoteEngine<ProviderAdapter<P, T, N>>
+where
+    P: Provider<T, N> + Send + Sync + 'static,
+    T: Transport + Clone + Send + Sync,
+    N: Network + Send + Sync
+{
+    /// Loads the pools at `block_number` from `provider` and follows the
+    /// chain from there. The returned task ends when syncing the pools fails.
+    pub async fn connect(
+        provider: Arc<P>,
+        pools: Vec<QuotedPool>,
+        block_number: BlockNumber,
+        ticks_per_side: u16
+    ) -> eyre::Result<(Self, JoinHandle<Result<(), PoolManagerError>>)> {
+        let mut uniswap_pools = Vec::with_capacity(pools.len());
+        for pool in &pools {
+            let mut uniswap_pool = EnhancedUniswapV3Pool::new(pool.address, ticks_per_side);
+            uniswap_pool
+                .initialize(Some(block_number), provider.clone())
+                .await
+                .map_err(|e| {
+                    eyre::eyre!(
+                        "failed to initialize uniswap pool {} at block {block_number}: {e}",
+                        pool.address
+                    )
+                })?;
+            uniswap_pools.push(uniswap_pool);
+        }
+
+        let state_change_buffer = 100;
+        let pool_manager = UniswapPoolManager::new(
+            uniswap_pools,
+            block_number,
+            state_change_buffer,
+            Arc::new(ProviderAdapter::new(provider.clone()))
+        )
+        .with_tick_window_resizing(provider);
+        let sync = pool_manager.watch_state_changes().await?;
+
+        Ok((Self::new(pool_manager, pools), sync))
+    }
+}
+
+impl<P> QuoteEngine<P>
+where
+    P: PoolManagerProvider + Send + Sync + 'static
+{
+    pub fn new(pool_manager: UniswapPoolManager<P>, pools: Vec<QuotedPool>) -> Self {
+        Self {
+            pool_manager,
+            pools: pools
+                .into_iter()
+                .map(|pool| (pool.pool_id, pool.address))
+                .collect(),
+            books: RwLock::default(),
+            priority: PriceLevelPriority::default()
+        }
+    }
+
+    /// Ranks the orders at the same price like the validators do.
+    pub fn with_price_level_priority(mut self, priority: PriceLevelPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Adds a validated order to the book of its pool, replacing the order
+    /// with the same hash.
+    pub async fn insert_order(
+        &self,
+        order: OrderWithStorageData<GroupedVanillaOrder>
+    ) -> Result<(), QuoteError> {
+        if !self.pools.contains_key(&order.pool_id) {
+            return Err(QuoteError::UnknownPool(order.pool_id))
+        }
+        let mut books = self.books.write().await;
+        let book = books.entry(order.pool_id).or_default();
+        book.replace(order);
+
+        Ok(())
+    }
+
+    /// Removes a filled or cancelled order, returning whether it was in the
+    /// book.
+    pub async fn remove_order(&self, pool_id: PoolId, order_hash: B256) -> bool {
+        let mut books = self.books.write().await;
+        let Some(book) = books.get_mut(&pool_id) else { return false };
+        let len = book.len();
+        book.retain(|order| order.order_id.hash != order_hash);
+
+        book.len() != len
+    }
+
+    /// Matches the current book of the pool against its AMM.
+    pub async fn solve(&self, pool_id: PoolId) -> Result<PoolSolution, QuoteError> {
+        let book = self.book(pool_id, None).await?;
+        solve_book(book).await
+    }
+
+    /// How `order` would be filled if it were added to the current book of
+    /// its pool.
+    pub async fn quote(
+        &self,
+        order: OrderWithStorageData<GroupedVanillaOrder>
+    ) -> Result<OrderQuote, QuoteError> {
+        let order_hash = order.order_id.hash;
+        let book = self.book(order.pool_id, Some(order)).await?;
+        let solution = solve_book(book).await?;
+
+        Ok(order_quote(&solution, order_hash))
+    }
+
+    async fn book(
+        &self,
+        pool_id: PoolId,
+        extra: Option<OrderWithStorageData<GroupedVanillaOrder>>
+    ) -> Result<OrderBook, QuoteError> {
+        let address = self
+            .pools
+            .get(&pool_id)
+            .ok_or(QuoteError::UnknownPool(pool_id))?;
+        let snapshot = self
+            .pool_manager
+            .pool(address)
+            .await
+            .ok_or(QuoteError::UntrackedPool(*address))?
+            .fetch_pool_snapshot()
+            .map_err(|e| QuoteError::Snapshot(pool_id, e))?;
+
+        let mut orders = self
+            .books
+            .read()
+            .await
+            .get(&pool_id)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(order) = extra {
+            orders.replace(order);
+        }
+
+        Ok(build_book(pool_id, Some(snapshot), orders, self.priority))
+    }
+}
+
+/// Matches the book on a blocking task, like the matching of a proposal.
+async fn solve_book(book: OrderBook) -> Result<PoolSolution, QuoteError> {
+    tokio::task::spawn_blocking(move || {
+        SimpleCheckpointStrategy::run(&book).map(|solver| solver.solution(None))
+    })
+    .await
+    .map_err(|_| QuoteError::MatchingFailed)?
+    .ok_or(QuoteError::NoSolution)
+}
+
+fn order_quote(solution: &PoolSolution, order_hash: B256) -> OrderQuote {
+    let outcome = solution
+        .limit
+        .iter()
+        .find(|outcome| outcome.id.hash == order_hash)
+        .map(|outcome| outcome.outcome.clone())
+        .unwrap_or_default();
+
+    OrderQuote { ucp: solution.ucp, outcome }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Uint;
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn quotes_an_order_against_the_book() {
+        let pool_id = PoolId::random();
+        let ask = UserOrderBuilder::new()
+            .partial()
+            .amount(100)
+            .min_price(Ray::from(Uint::from(1_000_u128)))
+            .with_storage()
+            .pool_id(pool_id)
+            .ask()
+            .build();
+        let bid = UserOrderBuilder::new()
+            .exact()
+            .amount(10)
+            .min_price(Ray::from(Uint::from(1_000_000_000_u128)))
+            .with_storage()
+            .pool_id(pool_id)
+            .bid()
+            .build();
+        let bid_hash = bid.order_id.hash;
+
+        let book = build_book(pool_id, None, HashSet::from([ask, bid]), Default::default());
+        let solution = solve_book(book).await.unwrap();
+        let quote = order_quote(&solution, bid_hash);
+        assert_eq!(quote.ucp, solution.ucp);
+        assert_eq!(quote.outcome, OrderFillState::CompleteFill);
+
+        // orders that aren't part of the solution are never filled
+        assert_eq!(order_quote(&solution, B256::ZERO).outcome, OrderFillState::Unfilled);
+    }
+}
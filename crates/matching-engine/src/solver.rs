@@ -0,0 +1,190 @@
+//! End to end solve of a single pool. The limit orders of the book are matched
+//! at a uniform clearing price with the AMM of the pool taking either side, and
+//! the searcher order that goes on top of the block is priced against the AMM.
+use angstrom_types::{
+    contract_payloads::tob::ToBOutcome,
+    orders::PoolSolution,
+    sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
+};
+
+use crate::{
+    book::OrderBook,
+    strategy::{MatchingStrategy, SimpleCheckpointStrategy}
+};
+
+/// The solution of a pool along with what its searcher order pays the pool.
+#[derive(Debug)]
+pub struct SolvedPool {
+    /// clearing price, net AMM order, fill state of every limit order and the
+    /// searcher order
+    pub solution: PoolSolution,
+    /// donations and tribute of the searcher order. `None` without a searcher
+    /// order or an AMM, or when the searcher order can't pay for its swap
+    pub tob:      Option<ToBOutcome>
+}
+
+/// Solves the pool of the book against the AMM the book was built with. `None`
+/// if the book can't be brought to a valid state.
+pub fn solve_pool(
+    book: &OrderBook,
+    searcher: Option<OrderWithStorageData<TopOfBlockOrder>>
+) -> Option<SolvedPool> {
+    let matcher = SimpleCheckpointStrategy::run(book)?;
+    let tob = searcher
+        .as_ref()
+        .zip(book.amm())
+        .and_then(|(searcher, amm)| ToBOutcome::from_tob_and_snapshot(searcher, amm).ok());
+
+    Some(SolvedPool { solution: matcher.solution(searcher), tob })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Uint, U256};
+    use angstrom_types::{
+        matching::Ray,
+        orders::{NetAmmOrder, OrderFillState},
+        primitive::PoolId,
+        sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use testing_tools::type_generator::{
+        amm::generate_single_position_amm_at_tick,
+        orders::{generate_top_of_block_order, UserOrderBuilder}
+    };
+
+    use super::*;
+
+    /// a price of one in Ray
+    const ONE: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+    fn random_orders(
+        rng: &mut StdRng,
+        is_bid: bool
+    ) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
+        random_orders_in(rng, is_bid, 1_000..2_000)
+    }
+
+    fn random_orders_in(
+        rng: &mut StdRng,
+        is_bid: bool,
+        prices: std::ops::Range<u128>
+    ) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
+        (0..rng.gen_range(0..20))
+            .map(|_| {
+                UserOrderBuilder::new()
+                    .is_exact(rng.gen_bool(0.5))
+                    .amount(rng.gen_range(1..1_000))
+                    .min_price(Ray::from(Uint::from(rng.gen_range(prices.clone()))))
+                    .with_storage()
+                    .is_bid(is_bid)
+                    .build()
+            })
+            .collect()
+    }
+
+    /// How much of the orders was filled, in the order of the solution.
+    fn filled<'a>(
+        orders: &[OrderWithStorageData<GroupedVanillaOrder>],
+        outcomes: impl Iterator<Item = &'a OrderFillState>
+    ) -> U256 {
+        orders
+            .iter()
+            .zip(outcomes)
+            .map(|(order, outcome)| match outcome {
+                OrderFillState::CompleteFill => order.remaining_quantity(),
+                OrderFillState::PartialFill(quantity) => *quantity,
+                OrderFillState::Unfilled | OrderFillState::Killed => U256::ZERO
+            })
+            .sum()
+    }
+
+    #[test]
+    fn conserves_token_balances() {
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let bids = random_orders(&mut rng, true);
+            let asks = random_orders(&mut rng, false);
+            let book = OrderBook::new(PoolId::random(), None, bids, asks, None);
+
+            let SolvedPool { solution, tob } = solve_pool(&book, None).unwrap();
+            assert_eq!(solution.limit.len(), book.bids().len() + book.asks().len());
+            assert!(solution.amm_quantity.is_none());
+            assert!(tob.is_none());
+
+            // every order clears at the same price, so the token0 bought by the
+            // bids has to match what the asks sold for token1 to balance too
+            let outcomes = solution.limit.iter().map(|outcome| &outcome.outcome);
+            let bought = filled(book.bids(), outcomes.clone());
+            let sold = filled(book.asks(), outcomes.skip(book.bids().len()));
+            assert_eq!(bought, sold, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn conserves_token_balances_with_an_amm() {
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            // with both sides of the book priced above the AMM it can only sell
+            // to the bids, and with both below only buy from the asks
+            let amm_sells = rng.gen_bool(0.5);
+            let prices = if amm_sells { ONE * 102 / 100..ONE * 2 } else { ONE / 2..ONE * 98 / 100 };
+            let bids = random_orders_in(&mut rng, true, prices.clone());
+            let asks = random_orders_in(&mut rng, false, prices);
+            // priced at one tick above one, with room to move either way
+            let amm = generate_single_position_amm_at_tick(0, 10_000, 1_000_000);
+            let book = OrderBook::new(PoolId::random(), Some(amm), bids, asks, None);
+
+            let SolvedPool { solution, .. } = solve_pool(&book, None).unwrap();
+            let amm_volume = SimpleCheckpointStrategy::run(&book)
+                .unwrap()
+                .results()
+                .amm_volume;
+            match &solution.amm_quantity {
+                // the net AMM order is a buy when the bids buy from it
+                Some(NetAmmOrder::Buy(..)) => assert!(amm_sells, "seed {seed}"),
+                Some(NetAmmOrder::Sell(..)) => assert!(!amm_sells, "seed {seed}"),
+                None => assert_eq!(amm_volume, U256::ZERO, "seed {seed}")
+            }
+
+            // what the AMM sold makes up for what the asks didn't, and what it
+            // bought for what the bids didn't
+            let outcomes = solution.limit.iter().map(|outcome| &outcome.outcome);
+            let bought = filled(book.bids(), outcomes.clone());
+            let sold = filled(book.asks(), outcomes.skip(book.bids().len()));
+            if amm_sells {
+                assert_eq!(bought, sold + amm_volume, "seed {seed}");
+            } else {
+                assert_eq!(bought + amm_volume, sold, "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn prices_the_searcher_order_against_the_amm() {
+        let mut rng = rand::thread_rng();
+        let searcher = generate_top_of_block_order(
+            &mut rng,
+            true,
+            None,
+            None,
+            Some(10_000_000_000_000_u128),
+            Some(100_000_000_u128)
+        );
+        let amm = generate_single_position_amm_at_tick(100_000, 1_000, 100_000_000_000_000);
+        let book = OrderBook::new(PoolId::random(), Some(amm), vec![], vec![], None);
+
+        let SolvedPool { solution, tob } = solve_pool(&book, Some(searcher.clone())).unwrap();
+        assert_eq!(solution.searcher, Some(searcher.clone()));
+        assert_eq!(
+            tob,
+            Some(ToBOutcome::from_tob_and_snapshot(&searcher, book.amm().unwrap()).unwrap())
+        );
+
+        // without an AMM there is nothing to price the searcher order against
+        let book = OrderBook::new(PoolId::random(), None, vec![], vec![], None);
+        let SolvedPool { solution, tob } = solve_pool(&book, Some(searcher.clone())).unwrap();
+        assert_eq!(solution.searcher, Some(searcher));
+        assert!(tob.is_none());
+    }
+}
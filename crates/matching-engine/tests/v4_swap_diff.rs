@@ -0,0 +1,49 @@
+use alloy::primitives::I256;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use testing_tools::contracts::v4_swap_diff::V4SwapDiff;
+use uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick;
+
+const TICK_SPACING: i32 = 60;
+/// seed of the swaps, overridden with `V4_SWAP_DIFF_SEED` to try others
+const DEFAULT_SEED: u64 = 0x5eed;
+
+#[test]
+fn simulated_swaps_match_the_pool_manager() -> eyre::Result<()> {
+    let seed = match std::env::var("V4_SWAP_DIFF_SEED") {
+        Ok(seed) => seed.parse()?,
+        Err(_) => DEFAULT_SEED
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for _ in 0..8 {
+        let start_tick = rng.gen_range(-100..100) * TICK_SPACING + rng.gen_range(0..TICK_SPACING);
+        let mut diff = V4SwapDiff::new(get_sqrt_ratio_at_tick(start_tick)?)?;
+
+        // one position around the price so every swap finds liquidity, and a
+        // few more to cross
+        let start = start_tick.div_euclid(TICK_SPACING) * TICK_SPACING;
+        diff.add_liquidity(start - 50 * TICK_SPACING, start + 50 * TICK_SPACING, 10u128.pow(21))?;
+        for _ in 0..rng.gen_range(1..6) {
+            let lower = start + rng.gen_range(-40..40) * TICK_SPACING;
+            let upper = lower + rng.gen_range(1..20) * TICK_SPACING;
+            diff.add_liquidity(lower, upper, rng.gen_range(10u128.pow(15)..10u128.pow(20)))?;
+        }
+
+        for _ in 0..16 {
+            let zero_for_one = rng.gen_bool(0.5);
+            let amount = I256::try_from(rng.gen_range(1..10i128.pow(18)))?;
+            let amount_specified = if rng.gen_bool(0.5) { amount } else { -amount };
+
+            let (contract, simulation) = diff.swap(zero_for_one, amount_specified)?;
+            assert_eq!(
+                contract,
+                simulation,
+                "seed: {seed}, zero_for_one: {zero_for_one}, amount specified: \
+                 {amount_specified}, tick: {}",
+                diff.pool().tick
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use prometheus::{IntCounterVec, IntGaugeVec};
+
+use crate::METRICS_ENABLED;
+
+/// Every metric is labelled with the target the bundle was sent to, either
+/// `public` or the host of the relay.
+#[derive(Clone)]
+struct BundleSubmissionMetrics {
+    // number of bundles accepted by the target
+    submissions:        IntCounterVec,
+    // number of bundles the target didn't accept after all retries
+    failures:           IntCounterVec,
+    // number of retried submissions
+    retries:            IntCounterVec,
+    // time (ms) the last accepted submission took, retries included
+    submission_time_ms: IntGaugeVec
+}
+
+impl Default for BundleSubmissionMetrics {
+    fn default() -> Self {
+        let submissions = prometheus::register_int_counter_vec!(
+            "bundle_submissions",
+            "number of bundles accepted by the target",
+            &["target"]
+        )
+        .unwrap();
+
+        let failures = prometheus::register_int_counter_vec!(
+            "bundle_submission_failures",
+            "number of bundles the target didn't accept after all retries",
+            &["target"]
+        )
+        .unwrap();
+
+        let retries = prometheus::register_int_counter_vec!(
+            "bundle_submission_retries",
+            "number of retried submissions",
+            &["target"]
+        )
+        .unwrap();
+
+        let submission_time_ms = prometheus::register_int_gauge_vec!(
+            "bundle_submission_time_ms",
+            "time (ms) the last accepted submission took, retries included",
+            &["target"]
+        )
+        .unwrap();
+
+        Self { submissions, failures, retries, submission_time_ms }
+    }
+}
+
+impl BundleSubmissionMetrics {
+    pub fn incr_submissions(&self, target: &str, millis: u64) {
+        self.submissions.with_label_values(&[target]).inc();
+        self.submission_time_ms
+            .with_label_values(&[target])
+            .set(millis as i64);
+    }
+
+    pub fn incr_failures(&self, target: &str) {
+        self.failures.with_label_values(&[target]).inc();
+    }
+
+    pub fn incr_retries(&self, target: &str) {
+        self.retries.with_label_values(&[target]).inc();
+    }
+}
+
+#[derive(Clone)]
+pub struct BundleSubmissionMetricsWrapper(Option<BundleSubmissionMetrics>);
+
+impl Default for BundleSubmissionMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BundleSubmissionMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(BundleSubmissionMetrics::default)
+        )
+    }
+
+    pub fn incr_submissions(&self, target: &str, millis: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_submissions(target, millis)
+        }
+    }
+
+    pub fn incr_failures(&self, target: &str) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_failures(target)
+        }
+    }
+
+    pub fn incr_retries(&self, target: &str) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_retries(target)
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, time::Instant};
 
-use prometheus::{IntGauge, IntGaugeVec};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 
 use crate::METRICS_ENABLED;
 
@@ -14,6 +14,23 @@ struct ConsensusMetrics {
     proposal_build_time_per_block: IntGaugeVec,
     // time (ms) it takes proposal verification per block
     proposal_verification_time_per_block: IntGaugeVec,
+    // voting power of the pre-proposals collected for the current block
+    quorum_voting_power: IntGauge,
+    // voting power needed to reach a quorum for the current block
+    quorum_threshold: IntGauge,
+    // number of our proposals whose bundle failed to simulate
+    bundle_simulation_failures: IntCounter,
+    // number of orders left out of our proposals as their bundle reverted
+    trimmed_orders: IntCounter,
+    // number of rounds no leader could be chosen for
+    leaderless_rounds: IntCounter,
+    // number of rounds we had no pre-proposal of our own to broadcast
+    missing_pre_proposals: IntCounter,
+    // number of our proposals whose bundle couldn't be built
+    bundle_build_failures: IntCounter,
+    // number of proposals ignored as they weren't from the round leader or
+    // reached the leader itself
+    ignored_proposals: IntCounter,
     // map of block numbers to their consensus start times
     block_consensus_start_times: HashMap<u64, Instant>
 }
@@ -45,11 +62,68 @@ impl Default for ConsensusMetrics {
         )
         .unwrap();
 
+        let quorum_voting_power = prometheus::register_int_gauge!(
+            "consensus_quorum_voting_power",
+            "voting power of the pre-proposals collected for the current block",
+        )
+        .unwrap();
+
+        let quorum_threshold = prometheus::register_int_gauge!(
+            "consensus_quorum_threshold",
+            "voting power needed to reach a quorum for the current block",
+        )
+        .unwrap();
+
+        let bundle_simulation_failures = prometheus::register_int_counter!(
+            "consensus_bundle_simulation_failures",
+            "number of our proposals whose bundle failed to simulate",
+        )
+        .unwrap();
+
+        let trimmed_orders = prometheus::register_int_counter!(
+            "consensus_trimmed_orders",
+            "number of orders left out of our proposals as their bundle reverted",
+        )
+        .unwrap();
+
+        let leaderless_rounds = prometheus::register_int_counter!(
+            "consensus_leaderless_rounds",
+            "number of rounds no leader could be chosen for",
+        )
+        .unwrap();
+
+        let missing_pre_proposals = prometheus::register_int_counter!(
+            "consensus_missing_pre_proposals",
+            "number of rounds we had no pre-proposal of our own to broadcast",
+        )
+        .unwrap();
+
+        let bundle_build_failures = prometheus::register_int_counter!(
+            "consensus_bundle_build_failures",
+            "number of our proposals whose bundle couldn't be built",
+        )
+        .unwrap();
+
+        let ignored_proposals = prometheus::register_int_counter!(
+            "consensus_ignored_proposals",
+            "number of proposals ignored as they weren't from the round leader or reached the \
+             leader itself",
+        )
+        .unwrap();
+
         Self {
             block_height,
             proposal_build_time_per_block,
             completion_time_per_block,
             proposal_verification_time_per_block,
+            quorum_voting_power,
+            quorum_threshold,
+            bundle_simulation_failures,
+            trimmed_orders,
+            leaderless_rounds,
+            missing_pre_proposals,
+            bundle_build_failures,
+            ignored_proposals,
             block_consensus_start_times: HashMap::default()
         }
     }
@@ -77,6 +151,35 @@ impl ConsensusMetrics {
             .set(time as i64);
     }
 
+    pub fn set_quorum_progress(&self, voting_power: u64, threshold: u64) {
+        self.quorum_voting_power.set(voting_power as i64);
+        self.quorum_threshold.set(threshold as i64);
+    }
+
+    pub fn incr_bundle_simulation_failures(&self) {
+        self.bundle_simulation_failures.inc();
+    }
+
+    pub fn incr_trimmed_orders(&self, count: usize) {
+        self.trimmed_orders.inc_by(count as u64);
+    }
+
+    pub fn incr_leaderless_rounds(&self) {
+        self.leaderless_rounds.inc();
+    }
+
+    pub fn incr_missing_pre_proposals(&self) {
+        self.missing_pre_proposals.inc();
+    }
+
+    pub fn incr_bundle_build_failures(&self) {
+        self.bundle_build_failures.inc();
+    }
+
+    pub fn incr_ignored_proposals(&self) {
+        self.ignored_proposals.inc();
+    }
+
     pub fn set_block_height(&mut self, block_number: u64) {
         self.block_height.set(block_number as i64);
         self.block_consensus_start_times
@@ -135,6 +238,48 @@ impl ConsensusMetricsWrapper {
         }
     }
 
+    pub fn set_quorum_progress(&self, voting_power: u64, threshold: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_quorum_progress(voting_power, threshold)
+        }
+    }
+
+    pub fn incr_bundle_simulation_failures(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_bundle_simulation_failures()
+        }
+    }
+
+    pub fn incr_trimmed_orders(&self, count: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_trimmed_orders(count)
+        }
+    }
+
+    pub fn incr_leaderless_rounds(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_leaderless_rounds()
+        }
+    }
+
+    pub fn incr_missing_pre_proposals(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_missing_pre_proposals()
+        }
+    }
+
+    pub fn incr_bundle_build_failures(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_bundle_build_failures()
+        }
+    }
+
+    pub fn incr_ignored_proposals(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_ignored_proposals()
+        }
+    }
+
     pub fn set_block_height(&mut self, block_number: u64) {
         if let Some(this) = self.0.as_mut() {
             this.set_block_height(block_number)
@@ -0,0 +1,94 @@
+use prometheus::{IntCounter, IntGauge};
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct EthMetrics {
+    // 1 if no canonical block has been seen within the stall timeout
+    canonical_stream_stalled:      IntGauge,
+    // seconds since the last canonical block was seen
+    seconds_since_canonical_block: IntGauge,
+    // number of times we re-subscribed to the canonical state stream
+    canonical_stream_resubscribes: IntCounter
+}
+
+impl Default for EthMetrics {
+    fn default() -> Self {
+        let canonical_stream_stalled = prometheus::register_int_gauge!(
+            "eth_canonical_stream_stalled",
+            "1 if no canonical block has been seen within the stall timeout",
+        )
+        .unwrap();
+
+        let seconds_since_canonical_block = prometheus::register_int_gauge!(
+            "eth_seconds_since_canonical_block",
+            "seconds since the last canonical block was seen",
+        )
+        .unwrap();
+
+        let canonical_stream_resubscribes = prometheus::register_int_counter!(
+            "eth_canonical_stream_resubscribes",
+            "number of times we re-subscribed to the canonical state stream",
+        )
+        .unwrap();
+
+        Self {
+            canonical_stream_stalled,
+            seconds_since_canonical_block,
+            canonical_stream_resubscribes
+        }
+    }
+}
+
+impl EthMetrics {
+    pub fn set_canonical_stream_stalled(&self, stalled: bool) {
+        self.canonical_stream_stalled.set(stalled as i64);
+    }
+
+    pub fn set_seconds_since_canonical_block(&self, secs: u64) {
+        self.seconds_since_canonical_block.set(secs as i64);
+    }
+
+    pub fn incr_canonical_stream_resubscribes(&self) {
+        self.canonical_stream_resubscribes.inc();
+    }
+}
+
+#[derive(Clone)]
+pub struct EthMetricsWrapper(Option<EthMetrics>);
+
+impl Default for EthMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EthMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(EthMetrics::default)
+        )
+    }
+
+    pub fn set_canonical_stream_stalled(&self, stalled: bool) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_canonical_stream_stalled(stalled)
+        }
+    }
+
+    pub fn set_seconds_since_canonical_block(&self, secs: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_seconds_since_canonical_block(secs)
+        }
+    }
+
+    pub fn incr_canonical_stream_resubscribes(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_canonical_stream_resubscribes()
+        }
+    }
+}
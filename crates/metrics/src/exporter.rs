@@ -8,7 +8,7 @@ use std::{
 use eyre::WrapErr;
 use hyper::{
     service::{make_service_fn, service_fn},
-    Body, Request, Response, Server
+    Body, Request, Response, Server, StatusCode
 };
 use metrics::Unit;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
@@ -57,19 +57,25 @@ async fn start_endpoint<F: Hook + 'static>(
         let handle = handle.clone();
         let hook = Arc::clone(&hook);
         async move {
-            Ok::<_, Infallible>(service_fn(move |_: Request<Body>| {
-                (hook)();
-                let mut metrics_render = handle.render();
-
-                let mut buffer = Vec::new();
-                let encoder = TextEncoder::new();
-                // Gather the metrics.
-                let metric_families = prometheus::gather();
-                // Encode them to send.
-                encoder.encode(&metric_families, &mut buffer).unwrap();
-                metrics_render += &String::from_utf8(buffer.clone()).unwrap();
-
-                async move { Ok::<_, Infallible>(Response::new(Body::from(metrics_render))) }
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let response = if req.uri().path() == "/health" {
+                    health_response()
+                } else {
+                    (hook)();
+                    let mut metrics_render = handle.render();
+
+                    let mut buffer = Vec::new();
+                    let encoder = TextEncoder::new();
+                    // Gather the metrics.
+                    let metric_families = prometheus::gather();
+                    // Encode them to send.
+                    encoder.encode(&metric_families, &mut buffer).unwrap();
+                    metrics_render += &String::from_utf8(buffer.clone()).unwrap();
+
+                    Response::new(Body::from(metrics_render))
+                };
+
+                async move { Ok::<_, Infallible>(response) }
             }))
         }
     });
@@ -82,6 +88,18 @@ async fn start_endpoint<F: Hook + 'static>(
     Ok(())
 }
 
+fn health_response() -> Response<Body> {
+    let (status, body) = if crate::health::is_healthy() {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "canonical state stream stalled")
+    };
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+
+    response
+}
+
 /// Installs Prometheus as the metrics recorder and serves it over HTTP with
 /// database and process metrics.
 pub async fn initialize_prometheus_metrics(port: u16) -> eyre::Result<()> {
@@ -0,0 +1,14 @@
+//! Node health, served on the `/health` path of the metrics endpoint.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CANONICAL_STREAM_STALLED: AtomicBool = AtomicBool::new(false);
+
+/// Marks the canonical state stream as stalled, which fails the health check
+/// until it recovers.
+pub fn set_canonical_stream_stalled(stalled: bool) {
+    CANONICAL_STREAM_STALLED.store(stalled, Ordering::Relaxed);
+}
+
+pub fn is_healthy() -> bool {
+    !CANONICAL_STREAM_STALLED.load(Ordering::Relaxed)
+}
@@ -12,4 +12,30 @@ pub use order_pool::*;
 mod consensus;
 pub use consensus::*;
 
+mod validation;
+pub use validation::*;
+
+mod eth;
+pub use eth::*;
+
+mod rpc;
+pub use rpc::*;
+
+mod pool_provider;
+pub use pool_provider::*;
+
+mod bundle_submission;
+pub use bundle_submission::*;
+
+mod tick_window;
+pub use tick_window::*;
+
+mod pool_sync;
+pub use pool_sync::*;
+
+mod pool_labels;
+pub use pool_labels::*;
+
+pub mod health;
+
 pub static METRICS_ENABLED: OnceLock<bool> = OnceLock::new();
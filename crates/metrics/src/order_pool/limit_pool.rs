@@ -1,7 +1,7 @@
 use angstrom_types::primitive::PoolId;
 use prometheus::{IntGauge, IntGaugeVec};
 
-use crate::METRICS_ENABLED;
+use crate::{pool_label, METRICS_ENABLED};
 
 #[derive(Clone)]
 struct VanillaLimitOrderPoolMetrics {
@@ -92,7 +92,7 @@ impl VanillaLimitOrderPoolMetrics {
 
     pub fn incr_parked_orders(&self, pool_id: PoolId, count: usize) {
         self.parked_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .add(count as i64);
         self.incr_total_parked_orders(count);
@@ -100,7 +100,7 @@ impl VanillaLimitOrderPoolMetrics {
 
     pub fn decr_parked_orders(&self, pool_id: PoolId, count: usize) {
         self.parked_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .sub(count as i64);
         self.decr_total_parked_orders(count);
@@ -108,7 +108,7 @@ impl VanillaLimitOrderPoolMetrics {
 
     pub fn incr_pending_orders(&self, pool_id: PoolId, count: usize) {
         self.pending_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .add(count as i64);
         self.incr_total_pending_orders(count);
@@ -116,7 +116,7 @@ impl VanillaLimitOrderPoolMetrics {
 
     pub fn decr_pending_orders(&self, pool_id: PoolId, count: usize) {
         self.pending_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .sub(count as i64);
         self.decr_total_pending_orders(count);
@@ -206,7 +206,7 @@ impl ComposableLimitOrderPoolMetrics {
 
     pub fn incr_all_orders(&self, pool_id: PoolId, count: usize) {
         self.all_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .add(count as i64);
 
@@ -215,7 +215,7 @@ impl ComposableLimitOrderPoolMetrics {
 
     pub fn decr_all_orders(&self, pool_id: PoolId, count: usize) {
         self.all_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .sub(count as i64);
 
@@ -9,3 +9,9 @@ pub use searcher_pool::*;
 
 mod finalization_pool;
 pub use finalization_pool::*;
+
+mod surveillance;
+pub use surveillance::*;
+
+mod rejections;
+pub use rejections::*;
@@ -0,0 +1,59 @@
+use prometheus::IntCounterVec;
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct OrderRejectionMetrics {
+    // orders turned away by validation or the pool per reason
+    orders_rejected: IntCounterVec
+}
+
+impl Default for OrderRejectionMetrics {
+    fn default() -> Self {
+        let orders_rejected = prometheus::register_int_counter_vec!(
+            "order_pool_orders_rejected",
+            "orders turned away by validation or the pool per reason",
+            &["reason"]
+        )
+        .unwrap();
+
+        Self { orders_rejected }
+    }
+}
+
+impl OrderRejectionMetrics {
+    pub fn incr_orders_rejected(&self, reason: &str) {
+        self.orders_rejected
+            .get_metric_with_label_values(&[reason])
+            .unwrap()
+            .inc();
+    }
+}
+
+#[derive(Clone)]
+pub struct OrderRejectionMetricsWrapper(Option<OrderRejectionMetrics>);
+
+impl Default for OrderRejectionMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderRejectionMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(OrderRejectionMetrics::default)
+        )
+    }
+
+    /// `reason` is the metric label of the error the order was rejected with
+    pub fn incr_orders_rejected(&self, reason: &str) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_orders_rejected(reason)
+        }
+    }
+}
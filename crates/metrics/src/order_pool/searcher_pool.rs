@@ -1,7 +1,7 @@
 use angstrom_types::primitive::PoolId;
 use prometheus::{IntGauge, IntGaugeVec};
 
-use crate::METRICS_ENABLED;
+use crate::{pool_label, METRICS_ENABLED};
 
 #[derive(Clone)]
 struct SearcherOrderPoolMetrics {
@@ -41,7 +41,7 @@ impl SearcherOrderPoolMetrics {
 
     pub fn incr_all_orders(&self, pool_id: PoolId, count: usize) {
         self.all_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .add(count as i64);
 
@@ -50,7 +50,7 @@ impl SearcherOrderPoolMetrics {
 
     pub fn decr_all_orders(&self, pool_id: PoolId, count: usize) {
         self.all_orders
-            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
             .unwrap()
             .sub(count as i64);
 
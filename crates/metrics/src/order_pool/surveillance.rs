@@ -0,0 +1,125 @@
+use angstrom_types::primitive::PoolId;
+use prometheus::{GaugeVec, IntGaugeVec};
+
+use crate::{pool_label, METRICS_ENABLED};
+
+#[derive(Clone)]
+struct SurveillanceMetrics {
+    // (bid - ask) / (bid + ask) resting depth per pool
+    depth_imbalance:       GaugeVec,
+    // cancels per fill over the last block per pool
+    cancel_to_trade_ratio: GaugeVec,
+    // new orders over the last block per pool and origin
+    arrivals:              IntGaugeVec,
+    // searcher bids over the last block per pool
+    tob_bids:              IntGaugeVec
+}
+
+impl Default for SurveillanceMetrics {
+    fn default() -> Self {
+        let depth_imbalance = prometheus::register_gauge_vec!(
+            "surveillance_depth_imbalance",
+            "(bid - ask) / (bid + ask) resting depth per pool",
+            &["pool_id"]
+        )
+        .unwrap();
+
+        let cancel_to_trade_ratio = prometheus::register_gauge_vec!(
+            "surveillance_cancel_to_trade_ratio",
+            "cancels per fill over the last block per pool",
+            &["pool_id"]
+        )
+        .unwrap();
+
+        let arrivals = prometheus::register_int_gauge_vec!(
+            "surveillance_order_arrivals",
+            "new orders over the last block per pool and origin",
+            &["pool_id", "origin"]
+        )
+        .unwrap();
+
+        let tob_bids = prometheus::register_int_gauge_vec!(
+            "surveillance_tob_bids",
+            "searcher bids over the last block per pool",
+            &["pool_id"]
+        )
+        .unwrap();
+
+        Self { depth_imbalance, cancel_to_trade_ratio, arrivals, tob_bids }
+    }
+}
+
+impl SurveillanceMetrics {
+    pub fn set_depth_imbalance(&self, pool_id: PoolId, imbalance: f64) {
+        self.depth_imbalance
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
+            .unwrap()
+            .set(imbalance);
+    }
+
+    pub fn set_cancel_to_trade_ratio(&self, pool_id: PoolId, ratio: f64) {
+        self.cancel_to_trade_ratio
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
+            .unwrap()
+            .set(ratio);
+    }
+
+    pub fn set_arrivals(&self, pool_id: PoolId, origin: &str, count: u64) {
+        self.arrivals
+            .get_metric_with_label_values(&[&pool_label(&pool_id), origin])
+            .unwrap()
+            .set(count as i64);
+    }
+
+    pub fn set_tob_bids(&self, pool_id: PoolId, count: u64) {
+        self.tob_bids
+            .get_metric_with_label_values(&[&pool_label(&pool_id)])
+            .unwrap()
+            .set(count as i64);
+    }
+}
+
+#[derive(Clone)]
+pub struct SurveillanceMetricsWrapper(Option<SurveillanceMetrics>);
+
+impl Default for SurveillanceMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SurveillanceMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(SurveillanceMetrics::default)
+        )
+    }
+
+    pub fn set_depth_imbalance(&self, pool_id: PoolId, imbalance: f64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_depth_imbalance(pool_id, imbalance)
+        }
+    }
+
+    pub fn set_cancel_to_trade_ratio(&self, pool_id: PoolId, ratio: f64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_cancel_to_trade_ratio(pool_id, ratio)
+        }
+    }
+
+    pub fn set_arrivals(&self, pool_id: PoolId, origin: &str, count: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_arrivals(pool_id, origin, count)
+        }
+    }
+
+    pub fn set_tob_bids(&self, pool_id: PoolId, count: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_tob_bids(pool_id, count)
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use angstrom_types::primitive::PoolId;
+
+/// human readable names of the configured pools, e.g `WETH/USDC`
+static POOL_LABELS: OnceLock<HashMap<PoolId, String>> = OnceLock::new();
+
+/// Sets the names used to label the metrics and logs of each pool. Can only be
+/// set once, later calls are ignored.
+pub fn init_pool_labels(labels: HashMap<PoolId, String>) {
+    let _ = POOL_LABELS.set(labels);
+}
+
+/// The name of the pool if we know its tokens, otherwise the raw pool id.
+pub fn pool_label(pool_id: &PoolId) -> String {
+    POOL_LABELS
+        .get()
+        .and_then(|labels| labels.get(pool_id).cloned())
+        .unwrap_or_else(|| pool_id.to_string())
+}
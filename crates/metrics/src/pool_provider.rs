@@ -0,0 +1,108 @@
+use prometheus::{IntCounter, IntGauge};
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct PoolProviderMetrics {
+    // number of requests throttled by the provider
+    throttled_requests:   IntCounter,
+    // number of requests that ran out of retries
+    exhausted_retries:    IntCounter,
+    // number of times we re-subscribed to new blocks
+    block_resubscribes:   IntCounter,
+    // current backoff delay in milliseconds
+    backoff_delay_millis: IntGauge
+}
+
+impl Default for PoolProviderMetrics {
+    fn default() -> Self {
+        let throttled_requests = prometheus::register_int_counter!(
+            "pool_provider_throttled_requests",
+            "number of requests throttled by the provider",
+        )
+        .unwrap();
+
+        let exhausted_retries = prometheus::register_int_counter!(
+            "pool_provider_exhausted_retries",
+            "number of requests that ran out of retries",
+        )
+        .unwrap();
+
+        let block_resubscribes = prometheus::register_int_counter!(
+            "pool_provider_block_resubscribes",
+            "number of times we re-subscribed to new blocks",
+        )
+        .unwrap();
+
+        let backoff_delay_millis = prometheus::register_int_gauge!(
+            "pool_provider_backoff_delay_millis",
+            "current backoff delay in milliseconds",
+        )
+        .unwrap();
+
+        Self { throttled_requests, exhausted_retries, block_resubscribes, backoff_delay_millis }
+    }
+}
+
+impl PoolProviderMetrics {
+    pub fn incr_throttled_requests(&self) {
+        self.throttled_requests.inc();
+    }
+
+    pub fn incr_exhausted_retries(&self) {
+        self.exhausted_retries.inc();
+    }
+
+    pub fn incr_block_resubscribes(&self) {
+        self.block_resubscribes.inc();
+    }
+
+    pub fn set_backoff_delay_millis(&self, millis: u64) {
+        self.backoff_delay_millis.set(millis as i64);
+    }
+}
+
+#[derive(Clone)]
+pub struct PoolProviderMetricsWrapper(Option<PoolProviderMetrics>);
+
+impl Default for PoolProviderMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolProviderMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(PoolProviderMetrics::default)
+        )
+    }
+
+    pub fn incr_throttled_requests(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_throttled_requests()
+        }
+    }
+
+    pub fn incr_exhausted_retries(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_exhausted_retries()
+        }
+    }
+
+    pub fn incr_block_resubscribes(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_block_resubscribes()
+        }
+    }
+
+    pub fn set_backoff_delay_millis(&self, millis: u64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_backoff_delay_millis(millis)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use prometheus::IntGauge;
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct PoolSyncMetrics {
+    // time (ms) it took to apply the logs of the last block to the pools
+    block_sync_time_ms: IntGauge,
+    // number of pools the last block changed
+    pools_synced:       IntGauge
+}
+
+impl Default for PoolSyncMetrics {
+    fn default() -> Self {
+        let block_sync_time_ms = prometheus::register_int_gauge!(
+            "pool_sync_block_time_ms",
+            "time (ms) it took to apply the logs of the last block to the pools"
+        )
+        .unwrap();
+
+        let pools_synced = prometheus::register_int_gauge!(
+            "pool_sync_pools_synced",
+            "number of pools the last block changed"
+        )
+        .unwrap();
+
+        Self { block_sync_time_ms, pools_synced }
+    }
+}
+
+impl PoolSyncMetrics {
+    pub fn set_block_sync(&self, millis: u64, pools: usize) {
+        self.block_sync_time_ms.set(millis as i64);
+        self.pools_synced.set(pools as i64);
+    }
+}
+
+#[derive(Clone)]
+pub struct PoolSyncMetricsWrapper(Option<PoolSyncMetrics>);
+
+impl Default for PoolSyncMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolSyncMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(PoolSyncMetrics::default)
+        )
+    }
+
+    pub fn set_block_sync(&self, millis: u64, pools: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_block_sync(millis, pools)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use prometheus::{IntCounterVec, IntGaugeVec};
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct RpcMetrics {
+    // orders accepted per api key
+    key_orders_submitted: IntCounterVec,
+    // orders rejected per api key and reason
+    key_orders_rejected:  IntCounterVec,
+    // open orders per api key
+    key_open_orders:      IntGaugeVec
+}
+
+impl Default for RpcMetrics {
+    fn default() -> Self {
+        let key_orders_submitted = prometheus::register_int_counter_vec!(
+            "rpc_key_orders_submitted",
+            "orders accepted per api key",
+            &["api_key"]
+        )
+        .unwrap();
+
+        let key_orders_rejected = prometheus::register_int_counter_vec!(
+            "rpc_key_orders_rejected",
+            "orders rejected per api key and reason",
+            &["api_key", "reason"]
+        )
+        .unwrap();
+
+        let key_open_orders = prometheus::register_int_gauge_vec!(
+            "rpc_key_open_orders",
+            "open orders per api key",
+            &["api_key"]
+        )
+        .unwrap();
+
+        Self { key_orders_submitted, key_orders_rejected, key_open_orders }
+    }
+}
+
+impl RpcMetrics {
+    pub fn incr_orders_submitted(&self, api_key: &str) {
+        self.key_orders_submitted
+            .get_metric_with_label_values(&[api_key])
+            .unwrap()
+            .inc();
+    }
+
+    pub fn incr_orders_rejected(&self, api_key: &str, reason: &str) {
+        self.key_orders_rejected
+            .get_metric_with_label_values(&[api_key, reason])
+            .unwrap()
+            .inc();
+    }
+
+    pub fn set_open_orders(&self, api_key: &str, open_orders: usize) {
+        self.key_open_orders
+            .get_metric_with_label_values(&[api_key])
+            .unwrap()
+            .set(open_orders as i64);
+    }
+}
+
+#[derive(Clone)]
+pub struct RpcMetricsWrapper(Option<RpcMetrics>);
+
+impl Default for RpcMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RpcMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(RpcMetrics::default)
+        )
+    }
+
+    pub fn incr_orders_submitted(&self, api_key: &str) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_orders_submitted(api_key)
+        }
+    }
+
+    pub fn incr_orders_rejected(&self, api_key: &str, reason: &str) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_orders_rejected(api_key, reason)
+        }
+    }
+
+    pub fn set_open_orders(&self, api_key: &str, open_orders: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_open_orders(api_key, open_orders)
+        }
+    }
+}
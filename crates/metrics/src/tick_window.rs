@@ -0,0 +1,77 @@
+use alloy_primitives::Address;
+use prometheus::{IntCounter, IntGaugeVec};
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct TickWindowMetrics {
+    // ticks synced on each side of the current tick per pool
+    ticks_per_side: IntGaugeVec,
+    // number of times a pool was re-synced with a different window
+    resizes:        IntCounter
+}
+
+impl Default for TickWindowMetrics {
+    fn default() -> Self {
+        let ticks_per_side = prometheus::register_int_gauge_vec!(
+            "tick_window_ticks_per_side",
+            "ticks synced on each side of the current tick per pool",
+            &["pool"]
+        )
+        .unwrap();
+
+        let resizes = prometheus::register_int_counter!(
+            "tick_window_resizes",
+            "number of times a pool was re-synced with a different window",
+        )
+        .unwrap();
+
+        Self { ticks_per_side, resizes }
+    }
+}
+
+impl TickWindowMetrics {
+    pub fn set_ticks_per_side(&self, pool: Address, ticks_per_side: u16) {
+        self.ticks_per_side
+            .get_metric_with_label_values(&[&pool.to_string()])
+            .unwrap()
+            .set(ticks_per_side as i64);
+    }
+
+    pub fn incr_resizes(&self) {
+        self.resizes.inc();
+    }
+}
+
+#[derive(Clone)]
+pub struct TickWindowMetricsWrapper(Option<TickWindowMetrics>);
+
+impl Default for TickWindowMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TickWindowMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(TickWindowMetrics::default)
+        )
+    }
+
+    pub fn set_ticks_per_side(&self, pool: Address, ticks_per_side: u16) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_ticks_per_side(pool, ticks_per_side)
+        }
+    }
+
+    pub fn incr_resizes(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_resizes()
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::OnceLock;
+
+use prometheus::{IntCounter, IntGauge};
+
+use crate::METRICS_ENABLED;
+
+struct ValidationMetrics {
+    // number of validation tasks currently running on the threadpool
+    threadpool_active_tasks:  IntGauge,
+    // number of validation tasks waiting on a per user permit
+    threadpool_queued_tasks:  IntGauge,
+    // number of users that have had orders validated
+    threadpool_tracked_users: IntGauge,
+    // number of worker threads backing the validation runtime
+    worker_threads:           IntGauge
+}
+
+impl Default for ValidationMetrics {
+    fn default() -> Self {
+        let threadpool_active_tasks = prometheus::register_int_gauge!(
+            "validation_threadpool_active_tasks",
+            "number of validation tasks currently running on the threadpool",
+        )
+        .unwrap();
+
+        let threadpool_queued_tasks = prometheus::register_int_gauge!(
+            "validation_threadpool_queued_tasks",
+            "number of validation tasks waiting on a per user permit",
+        )
+        .unwrap();
+
+        let threadpool_tracked_users = prometheus::register_int_gauge!(
+            "validation_threadpool_tracked_users",
+            "number of users that have had orders validated",
+        )
+        .unwrap();
+
+        let worker_threads = prometheus::register_int_gauge!(
+            "validation_worker_threads",
+            "number of worker threads backing the validation runtime",
+        )
+        .unwrap();
+
+        Self {
+            threadpool_active_tasks,
+            threadpool_queued_tasks,
+            threadpool_tracked_users,
+            worker_threads
+        }
+    }
+}
+
+impl ValidationMetrics {
+    pub fn set_threadpool_utilization(&self, active: usize, queued: usize, users: usize) {
+        self.threadpool_active_tasks.set(active as i64);
+        self.threadpool_queued_tasks.set(queued as i64);
+        self.threadpool_tracked_users.set(users as i64);
+    }
+
+    pub fn set_worker_threads(&self, workers: usize) {
+        self.worker_threads.set(workers as i64);
+    }
+}
+
+// the validator and the node both record validation metrics, which would
+// otherwise register the same metrics twice
+static VALIDATION_METRICS: OnceLock<ValidationMetrics> = OnceLock::new();
+
+#[derive(Clone, Copy)]
+pub struct ValidationMetricsWrapper(Option<&'static ValidationMetrics>);
+
+impl Default for ValidationMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(|| VALIDATION_METRICS.get_or_init(ValidationMetrics::default))
+        )
+    }
+
+    pub fn set_threadpool_utilization(&self, active: usize, queued: usize, users: usize) {
+        if let Some(this) = self.0 {
+            this.set_threadpool_utilization(active, queued, users)
+        }
+    }
+
+    pub fn set_worker_threads(&self, workers: usize) {
+        if let Some(this) = self.0 {
+            this.set_worker_threads(workers)
+        }
+    }
+}
+
+struct SignerCacheMetrics {
+    // number of signature recoveries answered from the cache
+    hits:    IntCounter,
+    // number of signatures that had to be recovered
+    misses:  IntCounter,
+    // number of signers held by the cache
+    entries: IntGauge
+}
+
+impl Default for SignerCacheMetrics {
+    fn default() -> Self {
+        let hits = prometheus::register_int_counter!(
+            "signer_cache_hits",
+            "number of signature recoveries answered from the cache",
+        )
+        .unwrap();
+
+        let misses = prometheus::register_int_counter!(
+            "signer_cache_misses",
+            "number of signatures that had to be recovered",
+        )
+        .unwrap();
+
+        let entries = prometheus::register_int_gauge!(
+            "signer_cache_entries",
+            "number of signers held by the cache",
+        )
+        .unwrap();
+
+        Self { hits, misses, entries }
+    }
+}
+
+// the cache is shared by several components, which would otherwise register
+// the same metrics twice
+static SIGNER_CACHE_METRICS: OnceLock<SignerCacheMetrics> = OnceLock::new();
+
+#[derive(Clone, Copy)]
+pub struct SignerCacheMetricsWrapper(Option<&'static SignerCacheMetrics>);
+
+impl Default for SignerCacheMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignerCacheMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(|| SIGNER_CACHE_METRICS.get_or_init(SignerCacheMetrics::default))
+        )
+    }
+
+    pub fn record_lookup(&self, hit: bool, entries: usize) {
+        if let Some(this) = self.0 {
+            if hit {
+                this.hits.inc();
+            } else {
+                this.misses.inc();
+            }
+            this.entries.set(entries as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ValidationMetricsWrapper, METRICS_ENABLED};
+
+    #[test]
+    fn validation_metrics_can_be_built_more_than_once() {
+        METRICS_ENABLED.get_or_init(|| true);
+
+        let first = ValidationMetricsWrapper::new();
+        let second = ValidationMetricsWrapper::new();
+        first.set_worker_threads(4);
+        second.set_threadpool_utilization(1, 2, 3);
+
+        assert!(std::ptr::eq(first.0.unwrap(), second.0.unwrap()));
+        assert_eq!(second.0.unwrap().worker_threads.get(), 4);
+    }
+}
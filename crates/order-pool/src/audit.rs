@@ -0,0 +1,169 @@
+//! Bounded in-memory trail of the mutations to each order's storage data, so
+//! that it can be traced why an order did or didn't fill. Trails outlive the
+//! orders themselves, the oldest ones are dropped once the limit is reached.
+use std::collections::{HashMap, VecDeque};
+
+use alloy::primitives::{BlockNumber, B256};
+use serde::{Deserialize, Serialize};
+
+/// Max number of mutations kept per order, older ones are dropped first.
+const MAX_TRAIL_LEN: usize = 32;
+/// Max number of orders a trail is kept for.
+const MAX_TRAILS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum OrderMutation {
+    /// passed validation and was added to the book
+    Validated {
+        gas:     u128,
+        pending: bool
+    },
+    /// moved from the parked to the pending pool on revalidation
+    Promoted {
+        gas: u128
+    },
+    /// moved to the parked pool, `by` is the order that took over its balance
+    Parked {
+        by: Option<B256>
+    },
+    /// dropped for an order of the same user with the same nonce
+    InvalidatedBy {
+        order_hash: B256
+    },
+    Invalid {
+        reason: String
+    },
+    Rejected {
+        reason: String
+    },
+    SimFailed {
+        reason: String
+    },
+    Cancelled,
+    Expired,
+    Filled,
+    Reorged
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderTrailEntry {
+    pub block_number: BlockNumber,
+    #[serde(flatten)]
+    pub mutation:     OrderMutation
+}
+
+#[derive(Debug, Default)]
+pub struct OrderAuditTrail {
+    trails:    HashMap<B256, VecDeque<OrderTrailEntry>>,
+    /// order hashes in the order their trail was started
+    insertion: VecDeque<B256>
+}
+
+impl OrderAuditTrail {
+    pub fn record(&mut self, order_hash: B256, block_number: BlockNumber, mutation: OrderMutation) {
+        if !self.trails.contains_key(&order_hash) {
+            if self.insertion.len() == MAX_TRAILS {
+                if let Some(oldest) = self.insertion.pop_front() {
+                    self.trails.remove(&oldest);
+                }
+            }
+            self.insertion.push_back(order_hash);
+        }
+
+        let trail = self.trails.entry(order_hash).or_default();
+        if trail.len() == MAX_TRAIL_LEN {
+            trail.pop_front();
+        }
+        trail.push_back(OrderTrailEntry { block_number, mutation });
+    }
+
+    /// Records a successful validation, as a promotion if the order was parked
+    /// until now.
+    pub fn record_validated(
+        &mut self,
+        order_hash: B256,
+        block_number: BlockNumber,
+        gas: u128,
+        pending: bool
+    ) {
+        let was_parked = self
+            .trails
+            .get(&order_hash)
+            .and_then(|trail| trail.back())
+            .is_some_and(|entry| {
+                matches!(
+                    entry.mutation,
+                    OrderMutation::Parked { .. }
+                        | OrderMutation::SimFailed { .. }
+                        | OrderMutation::Validated { pending: false, .. }
+                )
+            });
+
+        let mutation = if pending && was_parked {
+            OrderMutation::Promoted { gas }
+        } else {
+            OrderMutation::Validated { gas, pending }
+        };
+        self.record(order_hash, block_number, mutation);
+    }
+
+    pub fn trail(&self, order_hash: &B256) -> Vec<OrderTrailEntry> {
+        self.trails
+            .get(order_hash)
+            .map(|trail| trail.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_promotion_of_parked_orders() {
+        let mut audit = OrderAuditTrail::default();
+        let hash = B256::repeat_byte(1);
+
+        audit.record_validated(hash, 1, 10, true);
+        audit.record(hash, 1, OrderMutation::Parked { by: Some(B256::repeat_byte(2)) });
+        audit.record_validated(hash, 2, 12, true);
+
+        let kinds = audit
+            .trail(&hash)
+            .into_iter()
+            .map(|entry| entry.mutation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                OrderMutation::Validated { gas: 10, pending: true },
+                OrderMutation::Parked { by: Some(B256::repeat_byte(2)) },
+                OrderMutation::Promoted { gas: 12 }
+            ]
+        );
+    }
+
+    #[test]
+    fn trails_are_bounded() {
+        let mut audit = OrderAuditTrail::default();
+        let hash = B256::repeat_byte(1);
+
+        for block in 0..(MAX_TRAIL_LEN as u64 + 5) {
+            audit.record(hash, block, OrderMutation::Reorged);
+        }
+        let trail = audit.trail(&hash);
+        assert_eq!(trail.len(), MAX_TRAIL_LEN);
+        assert_eq!(trail[0].block_number, 5);
+
+        for i in 0..MAX_TRAILS {
+            audit.record(
+                B256::left_padding_from(&(i as u64 + 2).to_be_bytes()),
+                0,
+                OrderMutation::Invalid { reason: String::new() }
+            );
+        }
+        assert!(audit.trail(&hash).is_empty());
+    }
+}
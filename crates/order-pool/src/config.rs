@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use alloy::primitives::U256;
 use angstrom_types::primitive::PoolId;
 
 /// Guarantees max orders per sender
@@ -15,35 +18,98 @@ pub const SEARCHER_SUBPOOL_MAX_ORDERS_DEFAULT: usize = 100;
 /// The default maximum allowed size of the searcher subpool.
 pub const SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 5;
 
+/// The default maximum distance (in seconds) into the future that an order
+/// deadline may be set to. Orders beyond this horizon are rejected and never
+/// propagated, keeping the distributed book bounded across peers.
+pub const ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT: u64 = 24 * 60 * 60;
+
+/// Default number of transient simulation failures in a row after which an
+/// order is parked as unstable.
+pub const SIM_MAX_TRANSIENT_FAILURES_DEFAULT: u32 = 3;
+
+/// Default time into a round after which outbidding the best searcher order of
+/// a pool needs a minimum improvement.
+pub const TOB_REPLACEMENT_WINDOW_START_DEFAULT: Duration = Duration::from_secs(2);
+
+/// Default minimum improvement (in bps) over the best searcher order of a pool
+/// within the anti-sniping window.
+pub const TOB_MIN_IMPROVEMENT_BPS_DEFAULT: u64 = 100;
+
+/// Anti-sniping rule for searcher orders. Late in the round, a searcher order
+/// only replaces the best one of its pool if it beats its reward by a minimum
+/// margin, so that a bid can't be sniped by a marginally better one right
+/// before bids are aggregated. Every validator has to run the same rule,
+/// otherwise their books diverge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TobReplacementRule {
+    /// time since the start of the round after which the window opens
+    pub window_start:        Duration,
+    /// minimum improvement over the best reward within the window, in bps
+    pub min_improvement_bps: u64
+}
+
+impl TobReplacementRule {
+    /// Orders that don't outbid the best one, or arrive before the window, are
+    /// always accepted.
+    pub fn allows(&self, best_reward: U256, reward: U256, into_round: Duration) -> bool {
+        if into_round < self.window_start || reward <= best_reward {
+            return true
+        }
+
+        reward * U256::from(10_000) >= best_reward * U256::from(10_000 + self.min_improvement_bps)
+    }
+}
+
+impl Default for TobReplacementRule {
+    fn default() -> Self {
+        Self {
+            window_start:        TOB_REPLACEMENT_WINDOW_START_DEFAULT,
+            min_improvement_bps: TOB_MIN_IMPROVEMENT_BPS_DEFAULT
+        }
+    }
+}
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
     /// pool ids
-    pub ids:               Vec<PoolId>,
+    pub ids:                  Vec<PoolId>,
     /// Max number of transaction in the pending sub-pool
-    pub lo_pending_limit:  LimitSubPoolLimit,
+    pub lo_pending_limit:     LimitSubPoolLimit,
     /// Max number of transaction in the queued sub-pool
-    pub lo_queued_limit:   LimitSubPoolLimit,
+    pub lo_queued_limit:      LimitSubPoolLimit,
     /// Max number of transaction in the parked sub-pool
-    pub lo_parked_limit:   LimitSubPoolLimit,
+    pub lo_parked_limit:      LimitSubPoolLimit,
     /// Max number of transaction in the composable limit sub-pool
-    pub cl_pending_limit:  LimitSubPoolLimit,
+    pub cl_pending_limit:     LimitSubPoolLimit,
     /// Max number of transaction in the searcher & composable searcher sub-pool
-    pub s_pending_limit:   SearcherSubPoolLimit,
+    pub s_pending_limit:      SearcherSubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
-    pub max_account_slots: usize
+    pub max_account_slots:    usize,
+    /// Max number of seconds from now that an order deadline can be set to
+    pub max_deadline_horizon: u64,
+    /// Enables the TWAP order slicing service
+    pub twap_enabled:         bool,
+    /// Anti-sniping rule for replacing the best searcher order of a pool
+    pub tob_replacement:      TobReplacementRule,
+    /// Transient simulation failures in a row after which an order is parked
+    pub max_sim_failures:     u32
 }
 
 impl Default for PoolConfig {
     fn default() -> Self {
         Self {
-            ids:               vec![],
-            lo_pending_limit:  Default::default(),
-            lo_queued_limit:   Default::default(),
-            lo_parked_limit:   Default::default(),
-            cl_pending_limit:  Default::default(),
-            s_pending_limit:   Default::default(),
-            max_account_slots: ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER
+            ids:                  vec![],
+            lo_pending_limit:     Default::default(),
+            lo_queued_limit:      Default::default(),
+            lo_parked_limit:      Default::default(),
+            cl_pending_limit:     Default::default(),
+            s_pending_limit:      Default::default(),
+            max_account_slots:    ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_deadline_horizon: ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+            twap_enabled:         false,
+            tob_replacement:      Default::default(),
+            max_sim_failures:     SIM_MAX_TRANSIENT_FAILURES_DEFAULT
         }
     }
 }
@@ -100,3 +166,21 @@ impl Default for SearcherSubPoolLimit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn late_replacements_need_improvement() {
+        let rule = TobReplacementRule::default();
+        let best = U256::from(1_000);
+        let late = TOB_REPLACEMENT_WINDOW_START_DEFAULT;
+
+        assert!(rule.allows(best, U256::from(1_001), Duration::from_secs(1)));
+        assert!(!rule.allows(best, U256::from(1_001), late));
+        assert!(rule.allows(best, U256::from(1_010), late));
+        // lower bids don't replace the best one
+        assert!(rule.allows(best, U256::from(10), late));
+    }
+}
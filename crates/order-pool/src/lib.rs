@@ -1,3 +1,4 @@
+pub mod audit;
 mod common;
 mod config;
 mod finalization_pool;
@@ -6,23 +7,109 @@ mod order_indexer;
 pub mod order_storage;
 
 mod searcher;
+pub mod sim_breaker;
+pub mod snapshot;
+pub mod surveillance;
+pub mod twap;
 mod validator;
 
 use std::future::Future;
 
-use alloy::primitives::{Address, B256};
-use angstrom_types::{orders::OrderOrigin, sol_bindings::grouped_orders::AllOrders};
+use alloy::primitives::{Address, BlockNumber, B256};
+use angstrom_errors::PoolError;
+use angstrom_types::{
+    orders::{OrderOrigin, OrderPriorityData},
+    primitive::PoolId,
+    sol_bindings::{
+        grouped_orders::{AllOrders, OrderWithStorageData},
+        rpc_orders::TopOfBlockOrder
+    }
+};
 pub use angstrom_utils::*;
-pub use config::PoolConfig;
+use audit::OrderTrailEntry;
+pub use config::{
+    PoolConfig, TobReplacementRule, ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+    SIM_MAX_TRANSIENT_FAILURES_DEFAULT
+};
 pub use order_indexer::*;
+use order_storage::{OrderCursor, OrderFilter, OrderPage};
+use serde::{Deserialize, Serialize};
+use surveillance::{BlockOrderActivity, OrderStatus, PoolActivity};
 use tokio::sync::broadcast::Receiver;
+use twap::{TwapError, TwapInstruction, TwapStatus};
+use validation::order::OrderValidationResults;
 
 #[derive(Debug, Clone)]
 pub enum PoolManagerUpdate {
     NewOrder(AllOrders),
     FilledOrder((u64, AllOrders)),
-    UnfilledOrders(AllOrders),
-    CancelledOrder(B256)
+    /// The resting orders that did not fill, sent once per block transition
+    UnfilledOrders(UnfilledOrders),
+    CancelledOrder(B256),
+    /// A good-til-cancelled order could not be renewed, either because its
+    /// deadline passed or because it no longer passes validation
+    ExpiredOrder(B256)
+}
+
+/// Why an order was left unfilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnfilledReason {
+    /// its pool settled, but the order wasn't part of the solution
+    NotMatched,
+    /// the order can't cover the gas of its own execution
+    ExcludedForGas,
+    /// nothing settled in the pool of the order
+    PoolSkipped,
+    /// the order filled, but the block was reorged out
+    Reorged
+}
+
+impl UnfilledReason {
+    /// Why a resting order didn't fill, given whether anything settled in its
+    /// pool during the block.
+    pub fn classify(pool_settled: bool, priority: &OrderPriorityData) -> Self {
+        if !pool_settled {
+            Self::PoolSkipped
+        } else if priority.gas > priority.volume {
+            Self::ExcludedForGas
+        } else {
+            Self::NotMatched
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfilledOrder {
+    pub order_hash: B256,
+    pub reason:     UnfilledReason
+}
+
+/// All the orders left unfilled by the transition to `block_number`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfilledOrders {
+    pub block_number: u64,
+    pub orders:       Vec<UnfilledOrder>
+}
+
+/// A [`PoolManagerUpdate`] along with its sequence number, which increases by
+/// one with every update. Subscribers that lagged behind can line the stream
+/// up with a [`BookSnapshot`] again.
+#[derive(Debug, Clone)]
+pub struct SequencedUpdate {
+    pub seq:    u64,
+    pub update: PoolManagerUpdate
+}
+
+/// The resting orders of a pool as of the update with sequence number `seq`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookSnapshot {
+    pub pool_id: PoolId,
+    pub seq:     u64,
+    pub orders:  Vec<AllOrders>
 }
 
 /// The OrderPool Trait is how other processes can interact with the orderpool
@@ -31,6 +118,91 @@ pub enum PoolManagerUpdate {
 pub trait OrderPoolHandle: Send + Sync + Clone + Unpin + 'static {
     fn new_order(&self, origin: OrderOrigin, order: AllOrders)
         -> impl Future<Output = bool> + Send;
-    fn subscribe_orders(&self) -> Receiver<PoolManagerUpdate>;
+    /// Submits a standing order as good-til-cancelled. Instead of being
+    /// dropped when it was validated against a stale block, the order is
+    /// renewed for as long as its signed deadline allows.
+    fn new_gtc_order(
+        &self,
+        origin: OrderOrigin,
+        order: AllOrders
+    ) -> impl Future<Output = bool> + Send;
+    /// Submits a standing order that is parked while its estimated gas charge,
+    /// in its input token, is above `max_gas`, and promoted again once gas
+    /// falls back under it.
+    fn new_gas_capped_order(
+        &self,
+        origin: OrderOrigin,
+        order: AllOrders,
+        max_gas: u128,
+        good_til_cancelled: bool
+    ) -> impl Future<Output = bool> + Send;
+    /// Submits orders that are validated as a single batch, resolving to the
+    /// result of each order in the order they were given.
+    fn new_orders(
+        &self,
+        origin: OrderOrigin,
+        orders: Vec<AllOrders>
+    ) -> impl Future<Output = Vec<OrderValidationResults>> + Send;
+    fn subscribe_orders(&self) -> Receiver<SequencedUpdate>;
+    /// Fails if the pool isn't running, rather than passing an empty book off
+    /// as the current one.
+    fn book_snapshot(
+        &self,
+        pool_id: PoolId
+    ) -> impl Future<Output = Result<BookSnapshot, PoolError>> + Send;
+    /// Up to `limit` resting orders matching the filter that come after the
+    /// cursor, along with the cursor of the next page.
+    fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> impl Future<Output = OrderPage> + Send;
+    /// The searcher order currently winning the top of block auction of the
+    /// pool.
+    fn best_searcher_order(
+        &self,
+        pool_id: PoolId
+    ) -> impl Future<Output = Option<OrderWithStorageData<TopOfBlockOrder>>> + Send;
+    /// Schedules the slices of a TWAP instruction, returning its id.
+    fn submit_twap(
+        &self,
+        instruction: TwapInstruction
+    ) -> impl Future<Output = Result<B256, TwapError>> + Send;
+    fn twap_status(&self, id: B256) -> impl Future<Output = Option<TwapStatus>> + Send;
+    /// Activity stats of the last block, for all pools or a single one.
+    fn pool_activity(
+        &self,
+        pool_id: Option<PoolId>
+    ) -> impl Future<Output = Vec<PoolActivity>> + Send;
+    /// Order totals of a recent block across all pools.
+    fn block_activity(
+        &self,
+        block_number: BlockNumber
+    ) -> impl Future<Output = Option<BlockOrderActivity>> + Send;
+    /// Queue position and fill estimate of a resting limit order.
+    fn order_status(&self, order_hash: B256) -> impl Future<Output = Option<OrderStatus>> + Send;
+    /// Every recorded mutation of the order's storage data, oldest first.
+    fn order_trail(&self, order_hash: B256) -> impl Future<Output = Vec<OrderTrailEntry>> + Send;
     fn cancel_order(&self, sender: Address, order_hash: B256) -> impl Future<Output = bool> + Send;
+    /// parks all orders of the account and rejects any new ones until the
+    /// account is enabled again.
+    fn disable_account(&self, account: Address) -> impl Future<Output = bool> + Send;
+    fn enable_account(&self, account: Address) -> impl Future<Output = bool> + Send;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_unfilled_orders() {
+        let matchable = OrderPriorityData { volume: 100, gas: 10, ..Default::default() };
+        let unprofitable = OrderPriorityData { volume: 10, gas: 100, ..Default::default() };
+
+        assert_eq!(UnfilledReason::classify(false, &matchable), UnfilledReason::PoolSkipped);
+        assert_eq!(UnfilledReason::classify(false, &unprofitable), UnfilledReason::PoolSkipped);
+        assert_eq!(UnfilledReason::classify(true, &matchable), UnfilledReason::NotMatched);
+        assert_eq!(UnfilledReason::classify(true, &unprofitable), UnfilledReason::ExcludedForGas);
+    }
 }
@@ -8,6 +8,7 @@ use angstrom_types::{
     }
 };
 
+pub use self::pending::QueuePosition;
 use self::{composable::ComposableLimitPool, standard::LimitPool};
 use crate::common::SizeTracker;
 mod composable;
@@ -77,6 +78,17 @@ impl LimitOrderPool {
             })
     }
 
+    pub fn queue_position(&self, id: &OrderId) -> Option<QueuePosition> {
+        self.limit_orders.queue_position(id.pool_id, id.hash)
+    }
+
+    pub fn get_pending_order(
+        &self,
+        id: &OrderId
+    ) -> Option<&OrderWithStorageData<GroupedVanillaOrder>> {
+        self.limit_orders.get_pending_order(id.pool_id, id.hash)
+    }
+
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
         self.limit_orders.get_all_orders()
     }
@@ -100,3 +112,13 @@ pub enum LimitPoolError {
     #[error(transparent)]
     Unknown(#[from] eyre::Error)
 }
+
+impl From<LimitPoolError> for angstrom_errors::PoolError {
+    fn from(value: LimitPoolError) -> Self {
+        match value {
+            LimitPoolError::MaxSize => Self::MaxSize,
+            LimitPoolError::NoPool(_) => Self::NoPool,
+            e => Self::Other(e.to_string())
+        }
+    }
+}
@@ -8,6 +8,17 @@ use angstrom_types::{
     orders::OrderPriorityData, sol_bindings::grouped_orders::OrderWithStorageData
 };
 
+/// Where a resting order sits in its side of the book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueuePosition {
+    pub is_bid:      bool,
+    /// number of orders ahead of it at the same price
+    pub position:    usize,
+    /// volume of all orders that are matched before it
+    pub depth_ahead: u128,
+    pub volume:      u128
+}
+
 pub struct PendingPool<Order: Clone> {
     /// all order hashes
     orders: HashMap<FixedBytes<32>, OrderWithStorageData<Order>>,
@@ -48,6 +59,39 @@ impl<Order: Clone> PendingPool<Order> {
         Some(order)
     }
 
+    pub fn get_order(&self, id: FixedBytes<32>) -> Option<&OrderWithStorageData<Order>> {
+        self.orders.get(&id)
+    }
+
+    /// Walks the side of the book of the order in priority order up to the
+    /// order itself.
+    pub fn queue_position(&self, id: FixedBytes<32>) -> Option<QueuePosition> {
+        let order = self.orders.get(&id)?;
+        let target = order.priority_data;
+        let mut position =
+            QueuePosition { is_bid: order.is_bid, volume: target.volume, ..Default::default() };
+        let mut ahead = |priority: &OrderPriorityData| {
+            position.depth_ahead += priority.volume;
+            if priority.price == target.price {
+                position.position += 1;
+            }
+        };
+
+        if order.is_bid {
+            self.bids
+                .iter()
+                .take_while(|(_, hash)| **hash != id)
+                .for_each(|(Reverse(priority), _)| ahead(priority));
+        } else {
+            self.asks
+                .iter()
+                .take_while(|(_, hash)| **hash != id)
+                .for_each(|(priority, _)| ahead(priority));
+        }
+
+        Some(position)
+    }
+
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<Order>> {
         self.orders.values().cloned().collect()
     }
@@ -8,7 +8,10 @@ use angstrom_types::{
 };
 use angstrom_utils::map::OwnedMap;
 
-use super::{parked::ParkedPool, pending::PendingPool};
+use super::{
+    parked::ParkedPool,
+    pending::{PendingPool, QueuePosition}
+};
 use crate::limit::LimitPoolError;
 
 #[derive(Default)]
@@ -73,6 +76,23 @@ impl LimitPool {
             })
     }
 
+    pub fn get_pending_order(
+        &self,
+        pool_id: PoolId,
+        order_id: alloy::primitives::FixedBytes<32>
+    ) -> Option<&OrderWithStorageData<GroupedVanillaOrder>> {
+        self.pending_orders.get(&pool_id)?.get_order(order_id)
+    }
+
+    /// Only pending orders have a place in the book.
+    pub fn queue_position(
+        &self,
+        pool_id: PoolId,
+        order_id: alloy::primitives::FixedBytes<32>
+    ) -> Option<QueuePosition> {
+        self.pending_orders.get(&pool_id)?.queue_position(order_id)
+    }
+
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
         self.pending_orders
             .values()
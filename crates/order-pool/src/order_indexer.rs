@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    path::Path,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -7,6 +8,8 @@ use std::{
 };
 
 use alloy::primitives::{Address, BlockNumber, B256, U256};
+use angstrom_errors::{AngstromError, ValidationError};
+use angstrom_metrics::{pool_label, OrderRejectionMetricsWrapper};
 use angstrom_types::{
     orders::{OrderId, OrderOrigin, OrderSet},
     primitive::{NewInitializedPool, PeerId, PoolId},
@@ -16,17 +19,28 @@ use angstrom_types::{
         RawPoolOrder
     }
 };
-use futures_util::{Stream, StreamExt};
+use futures_util::{stream::FuturesUnordered, Stream, StreamExt};
 use tokio::sync::oneshot::Sender;
 use tracing::{error, trace};
-use validation::order::{
-    state::account::user::UserAddress, OrderValidationResults, OrderValidatorHandle
+use validation::{
+    common::signer_cache::SignerCache,
+    order::{
+        state::account::user::UserAddress, OrderValidationResults, OrderValidatorHandle,
+        ReorgFuture
+    }
 };
 
 use crate::{
-    order_storage::OrderStorage,
+    audit::{OrderAuditTrail, OrderMutation, OrderTrailEntry},
+    config::{ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT, SIM_MAX_TRANSIENT_FAILURES_DEFAULT},
+    order_storage::{OrderCursor, OrderFilter, OrderPage, OrderStorage},
+    sim_breaker::{SimCircuitBreaker, SimVerdict},
+    snapshot::OrderSnapshotError,
+    surveillance::{BlockOrderActivity, OrderStatus, PoolActivity, PoolSurveillance},
+    twap::{TwapError, TwapInstruction, TwapScheduler, TwapStatus},
     validator::{OrderValidator, OrderValidatorRes},
-    PoolManagerUpdate
+    BookSnapshot, PoolManagerUpdate, SequencedUpdate, UnfilledOrder, UnfilledOrders,
+    UnfilledReason
 };
 
 /// This is used to remove validated orders. During validation
@@ -37,6 +51,10 @@ const SEEN_INVALID_ORDERS_CAPACITY: usize = 10000;
 /// represents the maximum number of blocks that we allow for new orders to not
 /// propagate (again mostly arbitrary)
 const MAX_NEW_ORDER_DELAY_PROPAGATION: u64 = 7000;
+/// blocks between rechecks of the orders parked above their gas bound. Gas is
+/// priced over a window of blocks, so the charge barely moves from one block
+/// to the next
+const GAS_PARKED_RECHECK_INTERVAL: u64 = 5;
 
 struct CancelOrderRequest {
     /// The address of the entity requesting the cancellation.
@@ -60,12 +78,40 @@ pub struct OrderIndexer<V: OrderValidatorHandle> {
     seen_invalid_orders:    HashSet<B256>,
     /// Used to protect against late order propagation
     cancelled_orders:       HashMap<B256, CancelOrderRequest>,
+    /// Accounts that have triggered their kill switch
+    disabled_accounts:      HashSet<Address>,
+    /// Orders submitted as good-til-cancelled
+    gtc_orders:             HashSet<B256>,
+    /// Most gas the signers of orders are willing to be charged
+    max_gas:                HashMap<B256, u128>,
+    /// Orders parked because their estimated gas charge is above their bound
+    gas_parked:             HashSet<B256>,
+    /// Unix millis at which each order first reached us, over rpc or gossip
+    received_at:            HashMap<B256, u64>,
     /// Order Validator
     validator:              OrderValidator<V>,
     /// List of subscribers for order validation result
     order_validation_subs:  HashMap<B256, Vec<Sender<OrderValidationResults>>>,
     /// List of subscribers for order state change notifications
-    orders_subscriber_tx:   tokio::sync::broadcast::Sender<PoolManagerUpdate>
+    orders_subscriber_tx:   tokio::sync::broadcast::Sender<SequencedUpdate>,
+    /// Sequence number of the last update sent to the order subscribers
+    update_seq:             u64,
+    /// Max number of seconds from now that an order deadline can be set to
+    max_deadline_horizon:   u64,
+    /// Schedules TWAP slices, if the service is enabled
+    twap:                   Option<TwapScheduler>,
+    /// Per-pool activity stats for market surveillance
+    surveillance:           PoolSurveillance,
+    /// Parks orders whose simulations keep failing on provider errors
+    sim_breaker:            SimCircuitBreaker,
+    /// Mutations of the storage data of each order, for debugging fills
+    audit:                  OrderAuditTrail,
+    /// Re-checks of the users affected by a reorg that are still running
+    reorg_checks:           FuturesUnordered<ReorgFuture<'static>>,
+    /// Signers of the orders checked so far, shared with validation
+    signers:                SignerCache,
+    /// Orders turned away by validation or the pool, per reason
+    rejection_metrics:      OrderRejectionMetricsWrapper
 }
 
 impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
@@ -73,7 +119,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         validator: V,
         order_storage: Arc<OrderStorage>,
         block_number: BlockNumber,
-        orders_subscriber_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>
+        orders_subscriber_tx: tokio::sync::broadcast::Sender<SequencedUpdate>
     ) -> Self {
         Self {
             order_storage,
@@ -83,12 +129,155 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             order_hash_to_peer_id: HashMap::new(),
             seen_invalid_orders: HashSet::with_capacity(SEEN_INVALID_ORDERS_CAPACITY),
             cancelled_orders: HashMap::new(),
+            disabled_accounts: HashSet::new(),
+            gtc_orders: HashSet::new(),
+            max_gas: HashMap::new(),
+            gas_parked: HashSet::new(),
+            received_at: HashMap::new(),
             order_validation_subs: HashMap::new(),
             validator: OrderValidator::new(validator),
-            orders_subscriber_tx
+            orders_subscriber_tx,
+            update_seq: 0,
+            max_deadline_horizon: ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+            twap: None,
+            surveillance: PoolSurveillance::new(),
+            sim_breaker: SimCircuitBreaker::new(SIM_MAX_TRANSIENT_FAILURES_DEFAULT),
+            audit: OrderAuditTrail::default(),
+            reorg_checks: FuturesUnordered::new(),
+            signers: SignerCache::default(),
+            rejection_metrics: OrderRejectionMetricsWrapper::new()
         }
     }
 
+    pub fn with_twap(mut self, enabled: bool) -> Self {
+        self.twap = enabled.then(TwapScheduler::new);
+        self
+    }
+
+    pub fn with_max_sim_failures(mut self, max_sim_failures: u32) -> Self {
+        self.sim_breaker = SimCircuitBreaker::new(max_sim_failures);
+        self
+    }
+
+    /// Checks signatures through the given cache, so that orders the validator
+    /// already checked aren't recovered again.
+    pub fn with_signer_cache(mut self, signers: SignerCache) -> Self {
+        self.signers = signers;
+        self
+    }
+
+    /// The cache signatures are checked against, so signers can be recovered
+    /// ahead of admission
+    pub fn signer_cache(&self) -> &SignerCache {
+        &self.signers
+    }
+
+    pub fn submit_twap(&mut self, instruction: TwapInstruction) -> Result<B256, TwapError> {
+        let block_number = self.block_number;
+        let twap = self.twap.as_mut().ok_or(TwapError::Disabled)?;
+        if let Some(from) = instruction.slices.first().map(|slice| slice.from()) {
+            if self.disabled_accounts.contains(&from) {
+                return Err(TwapError::DisabledAccount)
+            }
+        }
+
+        twap.submit(instruction, block_number)
+    }
+
+    pub fn twap_status(&self, id: &B256) -> Option<TwapStatus> {
+        self.twap.as_ref().and_then(|twap| twap.status(id))
+    }
+
+    pub fn pool_activity(&self, pool_id: Option<PoolId>) -> Vec<PoolActivity> {
+        self.surveillance.activity(pool_id)
+    }
+
+    pub fn block_activity(&self, block_number: BlockNumber) -> Option<BlockOrderActivity> {
+        self.surveillance.block_activity(block_number)
+    }
+
+    /// Queue position and fill estimate of a resting limit order.
+    pub fn order_status(&self, order_hash: &B256) -> Option<OrderStatus> {
+        let order_id = self.order_hash_to_order_id.get(order_hash)?;
+        let position = self.order_storage.queue_position(order_id)?;
+
+        Some(
+            self.surveillance
+                .order_status(*order_hash, order_id.pool_id, position)
+        )
+    }
+
+    /// Every recorded mutation of the order, oldest first.
+    pub fn order_trail(&self, order_hash: &B256) -> Vec<OrderTrailEntry> {
+        self.audit.trail(order_hash)
+    }
+
+    /// The resting orders of the pool, consistent with every update up to and
+    /// including the current sequence number.
+    pub fn book_snapshot(&self, pool_id: PoolId) -> BookSnapshot {
+        BookSnapshot { pool_id, seq: self.update_seq, orders: self.resting_orders(Some(pool_id)) }
+    }
+
+    /// The searcher order currently winning the auction of the pool.
+    pub fn best_searcher_order(
+        &self,
+        pool_id: PoolId
+    ) -> Option<OrderWithStorageData<TopOfBlockOrder>> {
+        self.order_storage.top_tob_order_for_pool(&pool_id)
+    }
+
+    /// A page of the resting orders, see [`OrderStorage::orders_page`].
+    pub fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> OrderPage {
+        self.order_storage.orders_page(filter, after, limit)
+    }
+
+    /// The resting orders of a single pool, or of all pools if none is given.
+    pub fn resting_orders(&self, pool_id: Option<PoolId>) -> Vec<AllOrders> {
+        let in_pool = |id: &PoolId| pool_id.map_or(true, |pool_id| *id == pool_id);
+        let book = self.order_storage.get_all_orders();
+        book.limit
+            .into_iter()
+            .filter(|order| in_pool(&order.pool_id))
+            .map(|order| AllOrders::from(order.order))
+            .chain(
+                book.searcher
+                    .into_iter()
+                    .filter(|order| in_pool(&order.pool_id))
+                    .map(|order| AllOrders::TOB(order.order))
+            )
+            .collect()
+    }
+
+    /// Sets the max deadline horizon (in seconds). This needs to match the
+    /// horizon that is negotiated with peers during the handshake.
+    pub fn with_max_deadline_horizon(mut self, max_deadline_horizon: u64) -> Self {
+        self.max_deadline_horizon = max_deadline_horizon;
+        self
+    }
+
+    /// Whether the order is in the pool, already rejected or cancelled, in
+    /// which case there is no point in fetching it from a peer.
+    pub fn is_known_order(&self, order_hash: &B256) -> bool {
+        !self.is_missing(order_hash)
+            || self.is_seen_invalid(order_hash)
+            || self.is_cancelled(order_hash)
+    }
+
+    /// The pending orders among the given hashes, for peers pulling the
+    /// orders we announced.
+    pub fn pooled_orders(&self, order_hashes: &[B256]) -> Vec<AllOrders> {
+        let order_ids = order_hashes
+            .iter()
+            .filter_map(|hash| self.order_hash_to_order_id.get(hash).copied())
+            .collect::<Vec<_>>();
+        self.order_storage.pending_orders_by_id(&order_ids)
+    }
+
     fn is_missing(&self, order_hash: &B256) -> bool {
         !self.order_hash_to_order_id.contains_key(order_hash)
     }
@@ -101,6 +290,42 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.cancelled_orders.contains_key(order_hash)
     }
 
+    /// returns true if the order deadline is further in the future than the
+    /// network wide horizon allows.
+    fn is_beyond_deadline_horizon(&self, order: &AllOrders) -> bool {
+        let Some(deadline) = order.deadline() else { return false };
+        let horizon = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + self.max_deadline_horizon;
+
+        deadline > U256::from(horizon)
+    }
+
+    fn is_expired(&self, order: &AllOrders) -> bool {
+        let Some(deadline) = order.deadline() else { return false };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        deadline < U256::from(now)
+    }
+
+    /// Why the order fails the checks that don't depend on state, if it does.
+    fn stateless_rejection(&self, order: &AllOrders) -> Option<ValidationError> {
+        if self.is_beyond_deadline_horizon(order) {
+            Some(ValidationError::BeyondDeadlineHorizon)
+        } else if self.is_expired(order) {
+            Some(ValidationError::Expired)
+        } else if !self.signers.is_valid_signature(order) {
+            Some(ValidationError::InvalidSignature)
+        } else {
+            None
+        }
+    }
+
     fn is_duplicate(&self, order_hash: &B256) -> bool {
         if self.order_hash_to_order_id.contains_key(order_hash) || self.is_seen_invalid(order_hash)
         {
@@ -117,13 +342,82 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         order: AllOrders,
         validation_tx: tokio::sync::oneshot::Sender<OrderValidationResults>
     ) {
-        self.new_order(None, origin, order, Some(validation_tx))
+        self.new_order(None, origin, order, Some(validation_tx));
+    }
+
+    /// Counts the order as rejected under the metric label of its error.
+    fn record_rejection(&self, error: impl Into<AngstromError>) {
+        self.rejection_metrics
+            .incr_orders_rejected(&error.into().metric_label());
+    }
+
+    /// Only standing orders can be good-til-cancelled, as flash orders are
+    /// bound to a single block and searcher orders to a single auction.
+    pub fn new_gtc_order(
+        &mut self,
+        origin: OrderOrigin,
+        order: AllOrders,
+        validation_tx: tokio::sync::oneshot::Sender<OrderValidationResults>
+    ) {
+        let hash = order.order_hash();
+        if !matches!(order, AllOrders::Standing(_)) {
+            let error = ValidationError::Malformed(
+                "only standing orders can be good-til-cancelled".to_string()
+            );
+            self.record_rejection(error.clone());
+            let _ = validation_tx.send(OrderValidationResults::Invalid(hash, error));
+            return
+        }
+
+        self.gtc_orders.insert(hash);
+        self.new_order(None, origin, order, Some(validation_tx));
+    }
+
+    /// Stops tracking the order as good-til-cancelled, notifying subscribers
+    /// that it has expired.
+    fn expire_gtc_order(&mut self, order_hash: &B256) {
+        self.max_gas.remove(order_hash);
+        if self.gtc_orders.remove(order_hash) {
+            self.notify_order_subscribers(PoolManagerUpdate::ExpiredOrder(*order_hash));
+        }
+    }
+
+    /// The order is kept parked while its estimated gas charge is above
+    /// `max_gas`.
+    pub fn new_gas_capped_order(
+        &mut self,
+        origin: OrderOrigin,
+        order: AllOrders,
+        max_gas: u128,
+        good_til_cancelled: bool,
+        validation_tx: tokio::sync::oneshot::Sender<OrderValidationResults>
+    ) {
+        self.max_gas.insert(order.order_hash(), max_gas);
+        if good_til_cancelled {
+            self.new_gtc_order(origin, order, validation_tx);
+        } else {
+            self.new_rpc_order(origin, order, validation_tx);
+        }
     }
 
-    pub fn new_network_order(&mut self, peer_id: PeerId, origin: OrderOrigin, order: AllOrders) {
+    /// Returns [`PoolInnerEvent::RejectedOrder`] if the order can be rejected
+    /// without validating it against state, so that the propagating peer can
+    /// be notified.
+    pub fn new_network_order(
+        &mut self,
+        peer_id: PeerId,
+        origin: OrderOrigin,
+        order: AllOrders
+    ) -> PoolInnerEvent {
         self.new_order(Some(peer_id), origin, order, None)
     }
 
+    /// Orders replicated from a primary node. These were already validated by
+    /// the primary, so if they fail validation here it is not held against it.
+    pub fn new_replicated_order(&mut self, order: AllOrders) -> PoolInnerEvent {
+        self.new_order(None, OrderOrigin::External, order, None)
+    }
+
     pub fn cancel_order(&mut self, from: Address, order_hash: B256) -> bool {
         if self.is_seen_invalid(&order_hash) || self.is_cancelled(&order_hash) {
             return true
@@ -154,13 +448,60 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             let order = removed.unwrap();
             self.order_hash_to_order_id.remove(&order_hash);
             self.order_hash_to_peer_id.remove(&order_hash);
+            self.gtc_orders.remove(&order_hash);
+            self.max_gas.remove(&order_hash);
             self.insert_cancel_request_with_deadline(from, &order_hash, order.deadline());
+            self.surveillance.on_cancel(order.pool_id);
+            self.audit
+                .record(order_hash, self.block_number, OrderMutation::Cancelled);
             self.notify_order_subscribers(PoolManagerUpdate::CancelledOrder(order_hash));
         }
 
         removed_from_storage
     }
 
+    /// Kill switch for an account. Parks all of its limit orders, drops its
+    /// searcher orders and rejects any new orders until it is enabled again.
+    pub fn disable_account(&mut self, account: Address) -> bool {
+        if !self.disabled_accounts.insert(account) {
+            return true
+        }
+        tracing::info!(?account, "disabling account");
+
+        let order_ids = self
+            .address_to_orders
+            .get(&account)
+            .cloned()
+            .unwrap_or_default();
+        let (limit, searcher): (Vec<_>, Vec<_>) = order_ids
+            .into_iter()
+            .partition(|id| id.location == angstrom_types::orders::OrderLocation::Limit);
+
+        self.order_storage.park_orders(limit.iter().collect());
+        limit.iter().for_each(|id| {
+            self.audit
+                .record(id.hash, self.block_number, OrderMutation::Parked { by: None })
+        });
+        // searcher orders can't be parked, so we cancel them instead
+        searcher.into_iter().for_each(|id| {
+            self.cancel_order(account, id.hash);
+        });
+
+        true
+    }
+
+    /// Re-enables an account, sending its parked orders back through
+    /// validation.
+    pub fn enable_account(&mut self, account: Address) -> bool {
+        if !self.disabled_accounts.remove(&account) {
+            return false
+        }
+        tracing::info!(?account, "enabling account");
+        self.eoa_state_change(&[account]);
+
+        true
+    }
+
     fn insert_cancel_request_with_deadline(
         &mut self,
         from: Address,
@@ -192,7 +533,46 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         origin: OrderOrigin,
         order: AllOrders,
         validation_res_sub: Option<Sender<OrderValidationResults>>
+    ) -> PoolInnerEvent {
+        match self.admit_order(peer_id, origin, order, validation_res_sub) {
+            Ok(order) => {
+                self.validator.validate_order(origin, order);
+                PoolInnerEvent::None
+            }
+            Err(event) => event
+        }
+    }
+
+    /// Orders submitted together over rpc, which are validated as a single
+    /// batch. Each sender gets the result of its own order.
+    pub fn new_rpc_orders(
+        &mut self,
+        origin: OrderOrigin,
+        orders: Vec<(AllOrders, Sender<OrderValidationResults>)>
     ) {
+        let batch = orders
+            .into_iter()
+            .filter_map(|(order, validation_tx)| {
+                self.admit_order(None, origin, order, Some(validation_tx))
+                    .ok()
+                    .map(|order| (origin, order))
+            })
+            .collect::<Vec<_>>();
+
+        if !batch.is_empty() {
+            self.validator.validate_orders(batch);
+        }
+    }
+
+    /// Runs the checks that don't need state, returning the order if it
+    /// should be validated.
+    fn admit_order(
+        &mut self,
+        peer_id: Option<PeerId>,
+        origin: OrderOrigin,
+        order: AllOrders,
+        validation_res_sub: Option<Sender<OrderValidationResults>>
+    ) -> Result<AllOrders, PoolInnerEvent> {
         let hash = order.order_hash();
         let cancel_request = self.cancelled_orders.get(&hash);
         let is_valid_cancel_request =
@@ -203,8 +583,50 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 self.insert_cancel_request_with_deadline(order.from(), &hash, order.deadline());
                 self.order_storage.log_cancel_order(&order);
             }
-            self.notify_validation_subscribers(&hash, OrderValidationResults::Invalid(hash));
-            return
+            let error = if is_valid_cancel_request {
+                ValidationError::Cancelled
+            } else {
+                ValidationError::Duplicate
+            };
+            self.record_rejection(error.clone());
+            self.notify_validation_subscribers(&hash, OrderValidationResults::Invalid(hash, error));
+            self.gtc_orders.remove(&hash);
+            self.max_gas.remove(&hash);
+            return Err(PoolInnerEvent::None)
+        }
+
+        if self.disabled_accounts.contains(&order.from()) {
+            trace!(?hash, "order is from a disabled account");
+            self.record_rejection(ValidationError::AccountDisabled);
+            self.gtc_orders.remove(&hash);
+            self.max_gas.remove(&hash);
+            if let Some(validation_tx) = validation_res_sub {
+                let _ = validation_tx
+                    .send(OrderValidationResults::Invalid(hash, ValidationError::AccountDisabled));
+            }
+            return Err(PoolInnerEvent::None)
+        }
+
+        // these checks don't depend on state, so the order will be rejected by every
+        // honest peer. orders past the horizon are never validated, so they are never
+        // propagated
+        if let Some(error) = self.stateless_rejection(&order) {
+            trace!(?hash, %error, "order failed stateless validation");
+            self.record_rejection(error.clone());
+            self.seen_invalid_orders.insert(hash);
+            self.gtc_orders.remove(&hash);
+            self.max_gas.remove(&hash);
+            if let Some(validation_tx) = validation_res_sub {
+                let _ = validation_tx.send(OrderValidationResults::Invalid(hash, error));
+            }
+            let penalize = matches!(error, ValidationError::InvalidSignature);
+            return Err(peer_id
+                .map(|peer_id| PoolInnerEvent::RejectedOrder {
+                    order_hash: hash,
+                    peer_id,
+                    penalize
+                })
+                .unwrap_or(PoolInnerEvent::None))
         }
 
         let hash = order.order_hash();
@@ -221,7 +643,15 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 .or_default()
                 .push(validation_tx);
         }
-        self.validator.validate_order(origin, order);
+        self.surveillance.on_new_order(hash, origin);
+        self.received_at.entry(hash).or_insert_with(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64
+        });
+
+        Ok(order)
     }
 
     /// used to remove orders that expire before the next ethereum block
@@ -239,35 +669,88 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .map(|(k, _)| *k)
             .collect::<Vec<_>>();
 
-        // TODO: notify rpc of dead orders
-        let _expired_orders = hashes
+        hashes.iter().for_each(|hash| {
+            self.expire_gtc_order(hash);
+            self.audit
+                .record(*hash, block_number, OrderMutation::Expired);
+        });
+        self.remove_orders(&hashes);
+
+        hashes
+    }
+
+    /// Evicts the standing orders whose deadline passed since the last sweep,
+    /// instead of leaving them in the book until the next block.
+    pub fn evict_expired_orders(&mut self) -> Vec<B256> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let expired = self
+            .order_storage
+            .take_expired(U256::from(now))
+            .into_iter()
+            .filter(|hash| self.order_hash_to_order_id.contains_key(hash))
+            .collect::<Vec<_>>();
+        if expired.is_empty() {
+            return expired
+        }
+
+        trace!(count = expired.len(), "evicting expired orders");
+        self.remove_orders(&expired);
+        for hash in &expired {
+            self.gtc_orders.remove(hash);
+            self.max_gas.remove(hash);
+            self.order_hash_to_peer_id.remove(hash);
+            self.audit
+                .record(*hash, self.block_number, OrderMutation::Expired);
+            self.notify_order_subscribers(PoolManagerUpdate::ExpiredOrder(*hash));
+        }
+        self.validator.expire_orders(expired.clone());
+
+        expired
+    }
+
+    /// Removes the orders from the indexes and the underlying pools.
+    fn remove_orders(&mut self, hashes: &[B256]) {
+        let order_ids = hashes
             .iter()
-            // remove hash from id
-            .map(|hash| self.order_hash_to_order_id.remove(hash).unwrap())
-            .inspect(|order_id| {
-                self.address_to_orders
-                    .values_mut()
-                    // remove from address to orders
-                    .for_each(|v| v.retain(|o| o != order_id));
-            })
-            // remove from all underlying pools
-            .filter_map(|id| match id.location {
+            .filter_map(|hash| self.order_hash_to_order_id.remove(hash));
+
+        for order_id in order_ids {
+            self.sim_breaker.clear(&order_id.hash);
+            self.received_at.remove(&order_id.hash);
+            self.address_to_orders
+                .values_mut()
+                .for_each(|v| v.retain(|o| *o != order_id));
+
+            match order_id.location {
                 angstrom_types::orders::OrderLocation::Searcher => {
-                    self.order_storage.remove_searcher_order(&id)
+                    self.order_storage.remove_searcher_order(&order_id)
                 }
                 angstrom_types::orders::OrderLocation::Limit => {
-                    self.order_storage.remove_limit_order(&id)
+                    self.order_storage.remove_limit_order(&order_id)
                 }
-            })
-            .collect::<Vec<_>>();
-
-        hashes
+            };
+        }
     }
 
     fn eoa_state_change(&mut self, eoas: &[Address]) {
         eoas.iter()
-            .filter_map(|eoa| self.address_to_orders.remove(eoa))
-            .for_each(|order_ids| {
+            // orders of disabled accounts stay parked until they are enabled again
+            .filter(|eoa| !self.disabled_accounts.contains(*eoa))
+            .filter_map(|eoa| self.address_to_orders.remove_entry(eoa))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|(eoa, order_ids)| {
+                // unstable orders stay parked instead of being simulated again
+                let (unstable, order_ids): (Vec<_>, Vec<_>) = order_ids
+                    .into_iter()
+                    .partition(|id| self.sim_breaker.is_unstable(&id.hash));
+                if !unstable.is_empty() {
+                    self.address_to_orders.insert(eoa, unstable);
+                }
+
                 order_ids.into_iter().for_each(|id| {
                     let Some(order) = (match id.location {
                         angstrom_types::orders::OrderLocation::Limit => {
@@ -286,28 +769,141 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             });
     }
 
+    /// Sends the orders parked above their gas bound back through validation
+    /// every [`GAS_PARKED_RECHECK_INTERVAL`] blocks, which promotes the ones
+    /// whose gas charge fell under it.
+    fn recheck_gas_parked_orders(&mut self, block_number: BlockNumber) {
+        if block_number % GAS_PARKED_RECHECK_INTERVAL != 0 {
+            return
+        }
+
+        for hash in self.gas_parked.drain().collect::<Vec<_>>() {
+            let Some(id) = self.order_hash_to_order_id.remove(&hash) else { continue };
+            self.address_to_orders
+                .values_mut()
+                .for_each(|ids| ids.retain(|other| *other != id));
+            let Some(order) = self.order_storage.remove_limit_order(&id) else { continue };
+
+            self.validator
+                .validate_order(OrderOrigin::Local, order.order);
+        }
+    }
+
     pub fn finalized_block(&mut self, block_number: BlockNumber) {
         self.order_storage.finalized_block(block_number);
     }
 
     pub fn reorg(&mut self, orders: Vec<B256>) {
-        self.order_storage
+        let orders = self
+            .order_storage
             .reorg(orders)
             .into_iter()
-            .for_each(|order| {
-                self.notify_order_subscribers(PoolManagerUpdate::UnfilledOrders(order.clone()));
-                self.validator.validate_order(OrderOrigin::Local, order)
-            });
+            .into_iter()
+            .map(|order| {
+                let order_hash = order.order_hash();
+                self.audit
+                    .record(order_hash, self.block_number, OrderMutation::Reorged);
+                self.validator.validate_order(OrderOrigin::Local, order);
+                UnfilledOrder { order_hash, reason: UnfilledReason::Reorged }
+            })
+            .collect::<Vec<_>>();
+
+        if !orders.is_empty() {
+            self.notify_order_subscribers(PoolManagerUpdate::UnfilledOrders(UnfilledOrders {
+                block_number: self.block_number,
+                orders
+            }));
+        }
+    }
+
+    /// Has the validator re-check the balances, approvals and nonces of the
+    /// users affected by a reorg against the new canonical chain.
+    pub fn chain_reorg(&mut self, block_number: BlockNumber, addresses: Vec<Address>) {
+        self.reorg_checks
+            .push(self.validator.chain_reorg(block_number, addresses));
     }
 
-    /// Removes all filled orders from the pools and moves to regular pool
-    fn filled_orders(&mut self, block_number: BlockNumber, orders: &[B256]) {
+    /// Revalidates the orders that lost their funding or nonce in a reorg, so
+    /// they are parked or dropped against the new canonical state.
+    fn revalidate_unfunded_orders(&mut self, hashes: Vec<B256>) {
+        let order_ids = hashes
+            .iter()
+            .filter_map(|hash| self.order_hash_to_order_id.get(hash).copied())
+            .collect::<Vec<_>>();
+
+        for order_id in order_ids {
+            self.address_to_orders
+                .values_mut()
+                .for_each(|v| v.retain(|o| *o != order_id));
+
+            let Some(order) = (match order_id.location {
+                angstrom_types::orders::OrderLocation::Limit => {
+                    self.order_storage.remove_limit_order(&order_id)
+                }
+                angstrom_types::orders::OrderLocation::Searcher => {
+                    self.order_storage.remove_searcher_order(&order_id)
+                }
+            }) else {
+                continue
+            };
+
+            self.validator
+                .validate_order(OrderOrigin::Local, order.order);
+        }
+    }
+
+    /// Tells the subscribers which of the orders that were up for inclusion in
+    /// the block didn't fill, and why.
+    fn notify_unfilled_orders(
+        &mut self,
+        block_number: BlockNumber,
+        candidates: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>,
+        filled_orders: &[B256],
+        settled_pools: &HashSet<PoolId>
+    ) {
+        let filled_orders = filled_orders.iter().collect::<HashSet<_>>();
+        let orders = candidates
+            .limit
+            .iter()
+            .map(|order| (order.order_id.hash, order.pool_id, order.priority_data))
+            .chain(
+                candidates
+                    .searcher
+                    .iter()
+                    .map(|order| (order.order_id.hash, order.pool_id, order.priority_data))
+            )
+            .filter(|(order_hash, ..)| !filled_orders.contains(order_hash))
+            .map(|(order_hash, pool_id, priority_data)| UnfilledOrder {
+                order_hash,
+                reason: UnfilledReason::classify(settled_pools.contains(&pool_id), &priority_data)
+            })
+            .collect::<Vec<_>>();
+
         if orders.is_empty() {
             return
         }
+        self.notify_order_subscribers(PoolManagerUpdate::UnfilledOrders(UnfilledOrders {
+            block_number,
+            orders
+        }));
+    }
+
+    /// Removes all filled orders from the pools and moves to regular pool,
+    /// returning the pools that had fills
+    fn filled_orders(&mut self, block_number: BlockNumber, orders: &[B256]) -> HashSet<PoolId> {
+        if orders.is_empty() {
+            return HashSet::new()
+        }
 
         let filled_orders = orders
             .iter()
+            .inspect(|hash| {
+                self.gtc_orders.remove(*hash);
+                self.max_gas.remove(*hash);
+                if let Some(twap) = self.twap.as_mut() {
+                    twap.on_filled(hash);
+                }
+            })
             .filter_map(|hash| self.order_hash_to_order_id.remove(hash))
             .filter_map(|order_id| match order_id.location {
                 angstrom_types::orders::OrderLocation::Limit => {
@@ -320,13 +916,20 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .collect::<Vec<OrderWithStorageData<AllOrders>>>();
 
         filled_orders.iter().for_each(|order| {
+            self.audit
+                .record(order.order_hash(), block_number, OrderMutation::Filled);
+            self.surveillance
+                .on_fill(order.pool_id, order.is_bid, order.priority_data.volume);
             self.notify_order_subscribers(PoolManagerUpdate::FilledOrder((
                 block_number,
                 order.order.clone()
             )));
         });
+        let settled_pools = filled_orders.iter().map(|order| order.pool_id).collect();
         self.order_storage
             .add_filled_orders(block_number, filled_orders);
+
+        settled_pools
     }
 
     /// Given the nonce ordering rule. Sometimes new transactions can park old
@@ -344,50 +947,262 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         res: OrderValidationResults
     ) -> eyre::Result<PoolInnerEvent> {
         match res {
-            OrderValidationResults::Valid(valid) => {
+            OrderValidationResults::Valid(mut valid) => {
                 let hash = valid.order_hash();
+                self.sim_breaker.clear(&hash);
 
                 // what about the deadline?
+                if valid.valid_block != self.block_number && self.gtc_orders.contains(&hash) {
+                    // renew the order against the current block instead of dropping it. if it
+                    // can't be renewed, it's either expired or invalid, both of which are
+                    // handled when it comes back from validation
+                    trace!(?hash, "renewing good-til-cancelled order");
+                    if self.is_expired(&valid.order) {
+                        self.notify_validation_subscribers(
+                            &hash,
+                            OrderValidationResults::Invalid(hash, ValidationError::Expired)
+                        );
+                        self.expire_gtc_order(&hash);
+                    } else {
+                        self.validator
+                            .validate_order(OrderOrigin::Local, valid.order);
+                    }
+                    return Ok(PoolInnerEvent::None)
+                }
+
                 if valid.valid_block != self.block_number {
                     self.notify_validation_subscribers(
                         &hash,
-                        OrderValidationResults::Invalid(hash)
+                        OrderValidationResults::Invalid(hash, ValidationError::BlockMismatch)
                     );
 
                     self.seen_invalid_orders.insert(hash);
+                    self.received_at.remove(&hash);
                     let peers = self.order_hash_to_peer_id.remove(&hash).unwrap_or_default();
                     return Ok(PoolInnerEvent::BadOrderMessages(peers));
                 }
 
+                // the account might have been disabled while the order was being validated
+                let disabled = self.disabled_accounts.contains(&valid.from());
+                if disabled {
+                    if valid.order_id.location == angstrom_types::orders::OrderLocation::Searcher {
+                        self.notify_validation_subscribers(
+                            &hash,
+                            OrderValidationResults::Invalid(hash, ValidationError::AccountDisabled)
+                        );
+                        self.order_hash_to_peer_id.remove(&hash);
+                        self.received_at.remove(&hash);
+                        return Ok(PoolInnerEvent::None)
+                    }
+                    trace!(?hash, "parking order of a disabled account");
+                }
+
+                if matches!(valid.order, AllOrders::TOB(_)) {
+                    if let Err(e) = self
+                        .order_storage
+                        .check_tob_replacement(&valid.pool_id, valid.tob_reward)
+                    {
+                        // not the peer's fault, their clock might be slightly ahead of ours
+                        trace!(
+                            ?hash,
+                            pool = %pool_label(&valid.pool_id),
+                            %e,
+                            "searcher order rejected by the anti-sniping rule"
+                        );
+                        self.notify_validation_subscribers(
+                            &hash,
+                            OrderValidationResults::Invalid(
+                                hash,
+                                ValidationError::Other(e.to_string())
+                            )
+                        );
+                        self.surveillance.on_invalid_order(&hash);
+                        self.order_hash_to_peer_id.remove(&hash);
+                        self.received_at.remove(&hash);
+                        return Ok(PoolInnerEvent::None)
+                    }
+                }
+
+                if self
+                    .max_gas
+                    .get(&hash)
+                    .is_some_and(|max_gas| valid.priority_data.gas > *max_gas)
+                {
+                    trace!(
+                        ?hash,
+                        gas = valid.priority_data.gas,
+                        "parking order above its gas bound"
+                    );
+                    valid.is_currently_valid = false;
+                    self.gas_parked.insert(hash);
+                } else {
+                    self.gas_parked.remove(&hash);
+                }
+                if disabled {
+                    valid.is_currently_valid = false;
+                }
+                if let Some(received_at) = self.received_at.get(&hash) {
+                    valid.priority_data.received_at = *received_at;
+                }
+
                 self.notify_order_subscribers(PoolManagerUpdate::NewOrder(valid.order.clone()));
                 self.notify_validation_subscribers(
                     &hash,
                     OrderValidationResults::Valid(valid.clone())
                 );
 
+                let tob_reward =
+                    matches!(valid.order, AllOrders::TOB(_)).then_some(valid.tob_reward);
+                self.surveillance
+                    .on_valid_order(&hash, valid.pool_id, tob_reward);
+
                 let to_propagate = valid.order.clone();
+                self.audit.record_validated(
+                    hash,
+                    self.block_number,
+                    valid.priority_data.gas,
+                    valid.is_currently_valid
+                );
                 self.update_order_tracking(&hash, valid.from(), valid.order_id);
-                self.park_transactions(&valid.invalidates);
+                self.replace_or_park(&valid.order_id, &valid.invalidates);
                 self.insert_order(valid)?;
 
+                if disabled {
+                    return Ok(PoolInnerEvent::None)
+                }
                 Ok(PoolInnerEvent::Propagation(to_propagate))
             }
-            OrderValidationResults::Invalid(bad_hash) => {
+            OrderValidationResults::Invalid(bad_hash, error) => {
+                trace!(?bad_hash, %error, "order invalid");
+                self.record_rejection(error.clone());
+                self.audit.record(
+                    bad_hash,
+                    self.block_number,
+                    OrderMutation::Invalid { reason: error.to_string() }
+                );
                 self.notify_validation_subscribers(
                     &bad_hash,
-                    OrderValidationResults::Invalid(bad_hash)
+                    OrderValidationResults::Invalid(bad_hash, error)
                 );
+                self.expire_gtc_order(&bad_hash);
+                self.received_at.remove(&bad_hash);
+                self.surveillance.on_invalid_order(&bad_hash);
                 let peers = self
                     .order_hash_to_peer_id
                     .remove(&bad_hash)
                     .unwrap_or_default();
                 Ok(PoolInnerEvent::BadOrderMessages(peers))
             }
+            OrderValidationResults::Rejected(hash, error) => {
+                // losing a nonce to an order with a higher bid isn't something the
+                // propagating peer could have known about
+                trace!(?hash, %error, "order rejected");
+                self.record_rejection(error.clone());
+                self.audit.record(
+                    hash,
+                    self.block_number,
+                    OrderMutation::Rejected { reason: error.to_string() }
+                );
+                self.notify_validation_subscribers(
+                    &hash,
+                    OrderValidationResults::Rejected(hash, error)
+                );
+                self.expire_gtc_order(&hash);
+                self.order_hash_to_peer_id.remove(&hash);
+                self.received_at.remove(&hash);
+                Ok(PoolInnerEvent::None)
+            }
+            OrderValidationResults::SimFailed(mut order, error) => {
+                let hash = order.order_hash();
+                self.audit.record(
+                    hash,
+                    self.block_number,
+                    OrderMutation::SimFailed { reason: error.to_string() }
+                );
+                let result = match self.sim_breaker.on_failure(hash, &error) {
+                    SimVerdict::Invalid => {
+                        trace!(?hash, %error, "order simulation reverted");
+                        return self.handle_validated_order(OrderValidationResults::Invalid(
+                            hash,
+                            ValidationError::SimReverted(error.to_string())
+                        ))
+                    }
+                    // searcher orders only live for a block, so there is nothing to park
+                    _ if matches!(order.order, AllOrders::TOB(_)) => {
+                        self.sim_breaker.clear(&hash);
+                        self.notify_validation_subscribers(
+                            &hash,
+                            OrderValidationResults::Rejected(hash, ValidationError::SimUnstable)
+                        );
+                        self.order_hash_to_peer_id.remove(&hash);
+                        return Ok(PoolInnerEvent::None)
+                    }
+                    SimVerdict::Retry => {
+                        // a provider error isn't the order's fault, it is simulated again on
+                        // its next revalidation
+                        trace!(?hash, %error, "parking order after a transient simulation failure");
+                        order.is_currently_valid = false;
+                        OrderValidationResults::Valid(order.clone())
+                    }
+                    SimVerdict::Unstable => {
+                        trace!(?hash, %error, "parking order with an unstable simulation");
+                        order.is_currently_valid = false;
+                        OrderValidationResults::Rejected(hash, ValidationError::SimUnstable)
+                    }
+                };
+
+                self.notify_validation_subscribers(&hash, result);
+                self.order_hash_to_peer_id.remove(&hash);
+                self.update_order_tracking(&hash, order.from(), order.order_id);
+                self.insert_order(order)?;
+
+                Ok(PoolInnerEvent::None)
+            }
             OrderValidationResults::TransitionedToBlock => Ok(PoolInnerEvent::None)
         }
     }
 
+    /// Orders of the same user with the same nonce were outbid by the new
+    /// order and are dropped, the others can no longer be supported by the
+    /// user's balance and are parked.
+    fn replace_or_park(&mut self, new_order: &OrderId, invalidates: &[B256]) {
+        let (replaced, parked): (Vec<B256>, Vec<B256>) = invalidates.iter().partition(|hash| {
+            self.order_hash_to_order_id.get(hash).is_some_and(|id| {
+                id.address == new_order.address && id.reuse_avoidance == new_order.reuse_avoidance
+            })
+        });
+
+        if !replaced.is_empty() {
+            trace!(hash = ?new_order.hash, ?replaced, "order replaced orders with the same nonce");
+            self.remove_orders(&replaced);
+            for hash in replaced {
+                self.audit.record(
+                    hash,
+                    self.block_number,
+                    OrderMutation::InvalidatedBy { order_hash: new_order.hash }
+                );
+                self.gtc_orders.remove(&hash);
+                self.max_gas.remove(&hash);
+                self.order_hash_to_peer_id.remove(&hash);
+                self.notify_order_subscribers(PoolManagerUpdate::CancelledOrder(hash));
+            }
+        }
+        let tracked = parked
+            .iter()
+            .filter(|hash| self.order_hash_to_order_id.contains_key(*hash));
+        for hash in tracked {
+            self.audit.record(
+                *hash,
+                self.block_number,
+                OrderMutation::Parked { by: Some(new_order.hash) }
+            );
+        }
+        self.park_transactions(&parked);
+    }
+
     fn notify_order_subscribers(&mut self, update: PoolManagerUpdate) {
+        self.update_seq += 1;
+        let update = SequencedUpdate { seq: self.update_seq, update };
         if let Err(e) = self.orders_subscriber_tx.send(update) {
             error!("could not send order update {:?}", e)
         }
@@ -414,7 +1229,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                     })
                     .expect("should be unreachable")
                 )
-                .map_err(|e| eyre::anyhow!("{:?}", e)),
+                .map_err(|e| eyre::Report::new(angstrom_errors::PoolError::from(e))),
             angstrom_types::orders::OrderLocation::Limit => self
                 .order_storage
                 .add_new_limit_order(
@@ -431,7 +1246,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                     })
                     .expect("should be unreachable")
                 )
-                .map_err(|e| eyre::anyhow!("{:?}", e))
+                .map_err(|e| eyre::Report::new(angstrom_errors::PoolError::from(e)))
         }
     }
 
@@ -447,6 +1262,18 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.order_storage.get_all_orders()
     }
 
+    /// Imports the orders of a snapshot, sending them through validation as if
+    /// they were submitted locally.
+    pub fn import_orders(&mut self, path: impl AsRef<Path>) -> Result<usize, OrderSnapshotError> {
+        let orders = self.order_storage.import_orders(path)?;
+        let imported = orders.len();
+        orders.into_iter().for_each(|order| {
+            self.new_order(None, OrderOrigin::Local, order, None);
+        });
+
+        Ok(imported)
+    }
+
     pub fn new_pool(&self, pool: NewInitializedPool) {
         self.order_storage.new_pool(pool);
     }
@@ -468,10 +1295,14 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         mut completed_orders: Vec<B256>,
         address_changes: Vec<Address>
     ) {
+        // the orders that were up for inclusion in the block
+        let candidates = self.order_storage.get_all_orders();
         // deal with changed orders
         self.eoa_state_change(&address_changes);
+        self.recheck_gas_parked_orders(block_number);
         // deal with filled orders
-        self.filled_orders(block_number, &completed_orders);
+        let settled_pools = self.filled_orders(block_number, &completed_orders);
+        self.notify_unfilled_orders(block_number, candidates, &completed_orders, &settled_pools);
         // add expired orders to completed
         completed_orders.extend(self.remove_expired_orders(block_number));
 
@@ -486,6 +1317,20 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             completed_orders,
             address_changes
         );
+
+        let slices = self
+            .twap
+            .as_mut()
+            .map(|twap| twap.on_new_block(block_number))
+            .unwrap_or_default();
+        slices.into_iter().for_each(|slice| {
+            self.new_order(None, OrderOrigin::Local, slice, None);
+        });
+
+        self.order_storage.new_round();
+        // the book of the new block is paged through until bids are aggregated
+        let book = self.order_storage.pin_snapshot();
+        self.surveillance.on_new_block(block_number, &book.orders);
     }
 }
 
@@ -498,15 +1343,22 @@ where
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut validated = Vec::new();
 
+        while let Poll::Ready(Some(unfunded)) = self.reorg_checks.poll_next_unpin(cx) {
+            self.revalidate_unfunded_orders(unfunded);
+        }
+
         while let Poll::Ready(Some(next)) = self.validator.poll_next_unpin(cx) {
             match next {
                 OrderValidatorRes::EnsureClearForTransition { block, orders, addresses } => {
                     self.finish_new_block_processing(block, orders, addresses);
                 }
-                OrderValidatorRes::ValidatedOrder(next) => {
-                    if let Ok(prop) = self.handle_validated_order(next) {
-                        validated.push(prop);
-                    }
+                OrderValidatorRes::ValidatedOrder(next) => match self.handle_validated_order(next) {
+                    Ok(prop) => validated.push(prop),
+                    Err(error) => self.record_rejection(
+                        error
+                            .downcast::<angstrom_errors::PoolError>()
+                            .unwrap_or_else(|e| angstrom_errors::PoolError::Other(e.to_string()))
+                    )
                 }
             }
         }
@@ -522,6 +1374,16 @@ where
 pub enum PoolInnerEvent {
     Propagation(AllOrders),
     BadOrderMessages(Vec<PeerId>),
+    /// A propagated order failed validation in a way that doesn't depend on
+    /// state (bad signature, expired), so the peer that sent it can be told
+    /// to stop forwarding it
+    RejectedOrder {
+        order_hash: B256,
+        peer_id:    PeerId,
+        /// Only a bad signature is the peer's fault; an order can expire while
+        /// it is in flight
+        penalize:   bool
+    },
     None
 }
 
@@ -537,3 +1399,267 @@ pub enum PoolError {
     #[error("Duplicate order")]
     DuplicateOrder
 }
+
+impl From<PoolError> for angstrom_errors::PoolError {
+    fn from(value: PoolError) -> Self {
+        match value {
+            PoolError::MaxSize => Self::MaxSize,
+            PoolError::NoPool(_) => Self::NoPool,
+            PoolError::DuplicateNonce(_) => Self::DuplicateNonce,
+            PoolError::DuplicateOrder => Self::DuplicateOrder
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::aliases::U40;
+    use angstrom_types::{
+        orders::{OrderLocation, OrderPriorityData},
+        sol_bindings::rpc_orders::{ExactStandingOrder, OrderMeta}
+    };
+    use validation::order::ValidationFuture;
+
+    use super::*;
+
+    /// Never resolves, the tests only look at what was sent for validation.
+    #[derive(Debug, Clone)]
+    struct PendingValidator;
+
+    impl OrderValidatorHandle for PendingValidator {
+        type Order = AllOrders;
+
+        fn validate_order(&self, _: OrderOrigin, _: AllOrders) -> ValidationFuture {
+            Box::pin(std::future::pending())
+        }
+
+        fn new_block(&self, _: u64, _: Vec<B256>, _: Vec<Address>) -> ValidationFuture {
+            Box::pin(async { OrderValidationResults::TransitionedToBlock })
+        }
+
+        fn expire_orders(&self, _: Vec<B256>) {}
+
+        fn chain_reorg(&self, _: u64, _: Vec<Address>) -> ReorgFuture {
+            Box::pin(async { vec![] })
+        }
+    }
+
+    fn pool() -> NewInitializedPool {
+        NewInitializedPool {
+            currency_in:  Address::with_last_byte(1),
+            currency_out: Address::with_last_byte(2),
+            id:           PoolId::with_last_byte(3)
+        }
+    }
+
+    fn indexer() -> OrderIndexer<PendingValidator> {
+        let storage = Arc::new(OrderStorage::default());
+        storage.new_pool(pool());
+
+        OrderIndexer::new(PendingValidator, storage, 1, tokio::sync::broadcast::channel(10).0)
+    }
+
+    fn valid_order(from: Address) -> OrderWithStorageData<AllOrders> {
+        let order = AllOrders::Standing(StandingVariants::Exact(ExactStandingOrder {
+            amount: 10,
+            meta: OrderMeta { from, ..Default::default() },
+            ..Default::default()
+        }));
+        let pool_id = pool().id;
+
+        OrderWithStorageData {
+            order_id: OrderId {
+                address: from,
+                pool_id,
+                hash: order.order_hash(),
+                location: OrderLocation::Limit,
+                ..Default::default()
+            },
+            order,
+            priority_data: OrderPriorityData::default(),
+            invalidates: vec![],
+            pool_id,
+            is_currently_valid: true,
+            is_bid: true,
+            is_valid: true,
+            valid_block: 1,
+            tob_reward: U256::ZERO
+        }
+    }
+
+    fn validating(indexer: &OrderIndexer<PendingValidator>) -> usize {
+        let OrderValidator::RegularProcessing { remaining_futures, .. } = &indexer.validator else {
+            panic!("not processing orders")
+        };
+        remaining_futures.len()
+    }
+
+    #[test]
+    fn parks_orders_validated_after_their_account_was_disabled() {
+        let mut indexer = indexer();
+        let order = valid_order(Address::with_last_byte(9));
+        let id = order.order_id;
+        indexer.disable_account(order.from());
+
+        let event = indexer
+            .handle_validated_order(OrderValidationResults::Valid(order))
+            .unwrap();
+
+        assert!(matches!(event, PoolInnerEvent::None));
+        assert!(indexer.get_all_orders().limit.is_empty());
+        let parked = indexer.order_storage.remove_limit_order(&id).unwrap();
+        assert!(!parked.is_currently_valid);
+    }
+
+    #[test]
+    fn doesnt_revalidate_orders_of_disabled_accounts() {
+        let mut indexer = indexer();
+        let order = valid_order(Address::with_last_byte(9));
+        let from = order.from();
+        assert!(matches!(
+            indexer
+                .handle_validated_order(OrderValidationResults::Valid(order))
+                .unwrap(),
+            PoolInnerEvent::Propagation(_)
+        ));
+
+        indexer.disable_account(from);
+        indexer.eoa_state_change(&[from]);
+        assert_eq!(validating(&indexer), 0);
+        assert!(indexer.get_all_orders().limit.is_empty());
+
+        indexer.enable_account(from);
+        assert_eq!(validating(&indexer), 1);
+    }
+
+    /// A distinct order per signer, indexed under `deadline`.
+    fn expiring_order(from: u8, deadline: u64) -> OrderWithStorageData<AllOrders> {
+        let mut order = valid_order(Address::with_last_byte(from));
+        if let AllOrders::Standing(StandingVariants::Exact(inner)) = &mut order.order {
+            inner.nonce = from.into();
+        }
+        order.order_id.hash = order.order.order_hash();
+        order.order_id.deadline = Some(U256::from(deadline));
+        order
+    }
+
+    #[test]
+    fn evicts_only_the_expired_orders_still_in_the_book() {
+        let mut indexer = indexer();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let [expired, removed, live] =
+            [(1, now - 10), (2, now - 10), (3, now + 3600)].map(|(from, deadline)| {
+                let order = expiring_order(from, deadline);
+                let hash = order.order_hash();
+                indexer
+                    .handle_validated_order(OrderValidationResults::Valid(order))
+                    .unwrap();
+                hash
+            });
+        // left the book before its deadline
+        indexer.remove_orders(&[removed]);
+
+        assert_eq!(indexer.evict_expired_orders(), vec![expired]);
+        assert!(indexer.evict_expired_orders().is_empty());
+        let book = indexer.get_all_orders().limit;
+        assert_eq!(book.len(), 1);
+        assert_eq!(book[0].order_hash(), live);
+        assert_eq!(indexer.order_storage.deadlines.lock().unwrap().len(), 1);
+    }
+
+    fn with_gas(mut order: OrderWithStorageData<AllOrders>, gas: u128) -> OrderValidationResults {
+        order.priority_data.gas = gas;
+        OrderValidationResults::Valid(order)
+    }
+
+    #[test]
+    fn parks_orders_above_their_gas_bound() {
+        let mut indexer = indexer();
+        let order = valid_order(Address::with_last_byte(9));
+        let hash = order.order_hash();
+        indexer.max_gas.insert(hash, 100);
+
+        indexer
+            .handle_validated_order(with_gas(order, 101))
+            .unwrap();
+
+        assert!(indexer.gas_parked.contains(&hash));
+        assert!(indexer.get_all_orders().limit.is_empty());
+    }
+
+    #[test]
+    fn promotes_gas_parked_orders_once_their_charge_falls_under_the_bound() {
+        let mut indexer = indexer();
+        let order = valid_order(Address::with_last_byte(9));
+        let hash = order.order_hash();
+        indexer.max_gas.insert(hash, 100);
+        indexer
+            .handle_validated_order(with_gas(order.clone(), 101))
+            .unwrap();
+
+        // only rechecked every few blocks
+        indexer.recheck_gas_parked_orders(GAS_PARKED_RECHECK_INTERVAL + 1);
+        assert_eq!(validating(&indexer), 0);
+        indexer.recheck_gas_parked_orders(GAS_PARKED_RECHECK_INTERVAL * 2);
+        assert_eq!(validating(&indexer), 1);
+
+        indexer
+            .handle_validated_order(with_gas(order, 100))
+            .unwrap();
+        assert!(indexer.gas_parked.is_empty());
+        assert_eq!(indexer.get_all_orders().limit.len(), 1);
+    }
+
+    #[test]
+    fn stops_tracking_cancelled_gtc_orders() {
+        let mut indexer = indexer();
+        let order = valid_order(Address::with_last_byte(9));
+        let (from, hash) = (order.from(), order.order_hash());
+        indexer.gtc_orders.insert(hash);
+        indexer.max_gas.insert(hash, 100);
+        indexer
+            .handle_validated_order(OrderValidationResults::Valid(order))
+            .unwrap();
+
+        assert!(indexer.cancel_order(from, hash));
+        assert!(!indexer.gtc_orders.contains(&hash));
+        assert!(!indexer.max_gas.contains_key(&hash));
+    }
+
+    /// An unsigned order from `from` with `deadline`.
+    fn network_order(from: u8, deadline: u64) -> AllOrders {
+        AllOrders::Standing(StandingVariants::Exact(ExactStandingOrder {
+            amount: 10,
+            deadline: U40::from(deadline),
+            meta: OrderMeta { from: Address::with_last_byte(from), ..Default::default() },
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn only_penalizes_peers_for_invalid_signatures() {
+        let mut indexer = indexer();
+        let peer = PeerId::random();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let expired =
+            indexer.new_network_order(peer, OrderOrigin::External, network_order(1, now - 1));
+        assert!(matches!(
+            expired,
+            PoolInnerEvent::RejectedOrder { peer_id, penalize: false, .. } if peer_id == peer
+        ));
+
+        let unsigned =
+            indexer.new_network_order(peer, OrderOrigin::External, network_order(2, now + 60));
+        assert!(matches!(
+            unsigned,
+            PoolInnerEvent::RejectedOrder { peer_id, penalize: true, .. } if peer_id == peer
+        ));
+    }
+}
@@ -1,29 +1,122 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     default::Default,
     fmt::Debug,
-    sync::{Arc, Mutex},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex
+    },
     time::Instant
 };
 
-use alloy::primitives::{BlockNumber, FixedBytes, B256};
+use alloy::primitives::{Address, BlockNumber, FixedBytes, B256, U256};
 use angstrom_metrics::OrderStorageMetricsWrapper;
 use angstrom_types::{
     orders::{OrderId, OrderLocation, OrderSet},
+    persistence::Persisted,
     primitive::{NewInitializedPool, PoolId},
     sol_bindings::{
         grouped_orders::{AllOrders, GroupedUserOrder, GroupedVanillaOrder, OrderWithStorageData},
         rpc_orders::TopOfBlockOrder
     }
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     finalization_pool::FinalizationPool,
-    limit::{LimitOrderPool, LimitPoolError},
+    limit::{LimitOrderPool, LimitPoolError, QueuePosition},
     searcher::{SearcherPool, SearcherPoolError},
+    snapshot::{OrderSnapshot, OrderSnapshotError},
     PoolConfig
 };
 
+/// The book as it was at a version of the storage. Snapshots are never
+/// mutated, so every reader holding the same one sees the same orders. One is
+/// pinned per block phase, see [`OrderStorage::pin_snapshot`].
+#[derive(Debug)]
+pub struct OrderBookSnapshot {
+    /// the number of changes made to the book before the snapshot was taken
+    pub version:         u64,
+    /// the pending limit orders and the best searcher order of each pool
+    pub orders:          OrderSet<GroupedVanillaOrder, TopOfBlockOrder>,
+    /// indices of `orders.limit` in cursor order, to find pages with a binary
+    /// search
+    limit_page_index:    Vec<usize>,
+    /// indices of `orders.searcher` in cursor order
+    searcher_page_index: Vec<usize>
+}
+
+impl OrderBookSnapshot {
+    fn new(version: u64, orders: OrderSet<GroupedVanillaOrder, TopOfBlockOrder>) -> Self {
+        fn page_index<T>(searcher: bool, orders: &[OrderWithStorageData<T>]) -> Vec<usize> {
+            let mut index = (0..orders.len()).collect::<Vec<_>>();
+            index.sort_unstable_by_key(|i| OrderCursor::of(searcher, &orders[*i]));
+            index
+        }
+
+        Self {
+            version,
+            limit_page_index: page_index(false, &orders.limit),
+            searcher_page_index: page_index(true, &orders.searcher),
+            orders
+        }
+    }
+}
+
+impl Default for OrderBookSnapshot {
+    fn default() -> Self {
+        Self::new(0, OrderSet { limit: vec![], searcher: vec![] })
+    }
+}
+
+/// The orders a page is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderFilter {
+    All,
+    Pool(PoolId),
+    /// the orders signed by the address
+    Address(Address)
+}
+
+impl OrderFilter {
+    fn matches(&self, order_id: &OrderId) -> bool {
+        match self {
+            Self::All => true,
+            Self::Pool(pool_id) => order_id.pool_id == *pool_id,
+            Self::Address(address) => order_id.address == *address
+        }
+    }
+}
+
+/// The last order of a page, the next page starts after it. Limit orders come
+/// before searcher orders, each ordered by price and then hash, so a cursor
+/// stays valid while the book changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderCursor {
+    pub searcher: bool,
+    pub price:    U256,
+    pub hash:     B256
+}
+
+impl OrderCursor {
+    fn of<T>(searcher: bool, order: &OrderWithStorageData<T>) -> Self {
+        Self { searcher, price: order.priority_data.price, hash: order.order_id.hash }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPage {
+    /// version of the book the page was taken from
+    pub version: u64,
+    pub orders:  Vec<AllOrders>,
+    /// where the next page starts, `None` on the last page
+    pub next:    Option<OrderCursor>
+}
+
 /// The Storage of all verified orders.
 #[derive(Default, Clone)]
 pub struct OrderStorage {
@@ -33,6 +126,15 @@ pub struct OrderStorage {
     /// we store filled order hashes until they are expired time wise to ensure
     /// we don't waste processing power in the validator.
     pub filled_orders:               Arc<Mutex<HashMap<B256, Instant>>>,
+    /// standing orders ordered by their deadline. Orders that leave the book
+    /// before their deadline are only skipped once they come due
+    pub deadlines:                   Arc<Mutex<BTreeSet<(U256, B256)>>>,
+    /// bumped under the lock of the pool on every change to the limit or
+    /// searcher book
+    pub(crate) version:              Arc<AtomicU64>,
+    /// the snapshot of the current block phase, handed out until the next
+    /// one is pinned
+    pub(crate) snapshot:             Arc<Mutex<Arc<OrderBookSnapshot>>>,
     pub metrics:                     OrderStorageMetricsWrapper
 }
 
@@ -49,14 +151,17 @@ impl OrderStorage {
             &config.ids,
             Some(config.lo_pending_limit.max_size)
         )));
-        let searcher_orders = Arc::new(Mutex::new(SearcherPool::new(
-            &config.ids,
-            Some(config.s_pending_limit.max_size)
-        )));
+        let searcher_orders = Arc::new(Mutex::new(
+            SearcherPool::new(&config.ids, Some(config.s_pending_limit.max_size))
+                .with_replacement_rule(config.tob_replacement)
+        ));
         let pending_finalization_orders = Arc::new(Mutex::new(FinalizationPool::new()));
 
         Self {
             filled_orders: Arc::new(Mutex::new(HashMap::default())),
+            deadlines: Arc::new(Mutex::new(BTreeSet::new())),
+            version: Arc::default(),
+            snapshot: Arc::default(),
             limit_orders,
             searcher_orders,
             pending_finalization_orders,
@@ -85,12 +190,10 @@ impl OrderStorage {
         }
 
         match order_id.location {
-            angstrom_types::orders::OrderLocation::Limit => self
-                .limit_orders
-                .lock()
-                .expect("lock poisoned")
-                .remove_order(order_id)
-                .and_then(|order| {
+            angstrom_types::orders::OrderLocation::Limit => {
+                let mut limit_orders = self.limit_orders.lock().expect("lock poisoned");
+                limit_orders.remove_order(order_id).and_then(|order| {
+                    self.bump_version();
                     match order.order {
                         GroupedUserOrder::Composable(_) => {
                             self.metrics.incr_cancelled_composable_orders()
@@ -98,18 +201,18 @@ impl OrderStorage {
                         GroupedUserOrder::Vanilla(_) => self.metrics.incr_cancelled_vanilla_orders()
                     }
                     order.try_map_inner(|inner| Ok(inner.into())).ok()
-                }),
-            angstrom_types::orders::OrderLocation::Searcher => self
-                .searcher_orders
-                .lock()
-                .expect("lock poisoned")
-                .remove_order(order_id)
-                .map(|order| {
+                })
+            }
+            angstrom_types::orders::OrderLocation::Searcher => {
+                let mut searcher_orders = self.searcher_orders.lock().expect("lock poisoned");
+                searcher_orders.remove_order(order_id).map(|order| {
+                    self.bump_version();
                     self.metrics.incr_cancelled_searcher_orders();
                     order
                         .try_map_inner(|inner| Ok(AllOrders::TOB(inner)))
                         .unwrap()
                 })
+            }
         }
     }
 
@@ -127,8 +230,11 @@ impl OrderStorage {
                     tracing::debug!("tried to park searcher order. this is not supported");
                 }
             });
+        self.bump_version();
     }
 
+    /// The searcher order currently winning the auction of the pool. `None`
+    /// if the pool has no searcher orders or doesn't exist.
     pub fn top_tob_order_for_pool(
         &self,
         pool_id: &PoolId
@@ -136,30 +242,36 @@ impl OrderStorage {
         self.searcher_orders
             .lock()
             .expect("lock poisoned")
-            .get_orders_for_pool(pool_id)
-            .unwrap_or_else(|| panic!("pool {} does not exist", pool_id))
+            .get_orders_for_pool(pool_id)?
             .iter()
             .max_by_key(|order| order.tob_reward)
             .cloned()
     }
 
     pub fn top_tob_orders(&self) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
-        let mut top_orders = Vec::new();
-        let searcher_orders = self.searcher_orders.lock().expect("lock poisoned");
-
-        for pool_id in searcher_orders.get_all_pool_ids() {
-            if let Some(top_order) = self.top_tob_order_for_pool(&pool_id) {
-                top_orders.push(top_order);
-            }
-        }
+        Self::top_tob_orders_of(&self.searcher_orders.lock().expect("lock poisoned"))
+    }
 
-        top_orders
+    fn top_tob_orders_of(
+        searcher_orders: &SearcherPool
+    ) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
+        searcher_orders
+            .get_all_pool_ids()
+            .into_iter()
+            .filter_map(|pool_id| {
+                searcher_orders
+                    .get_orders_for_pool(&pool_id)?
+                    .into_iter()
+                    .max_by_key(|order| order.tob_reward)
+            })
+            .collect()
     }
 
     pub fn add_new_limit_order(
         &self,
         order: OrderWithStorageData<GroupedUserOrder>
     ) -> Result<(), LimitPoolError> {
+        let deadline = order.order_id.deadline.map(|d| (d, order.order_id.hash));
         if order.is_vanilla() {
             let mapped_order = order.try_map_inner(|this| {
                 let GroupedUserOrder::Vanilla(order) = this else {
@@ -168,10 +280,9 @@ impl OrderStorage {
                 Ok(order)
             })?;
 
-            self.limit_orders
-                .lock()
-                .expect("lock poisoned")
-                .add_vanilla_order(mapped_order)?;
+            let mut limit_orders = self.limit_orders.lock().expect("lock poisoned");
+            limit_orders.add_vanilla_order(mapped_order)?;
+            self.bump_version();
             self.metrics.incr_vanilla_limit_orders(1);
         } else {
             let mapped_order = order.try_map_inner(|this| {
@@ -181,24 +292,56 @@ impl OrderStorage {
                 Ok(order)
             })?;
 
-            self.limit_orders
-                .lock()
-                .expect("lock poisoned")
-                .add_composable_order(mapped_order)?;
+            let mut limit_orders = self.limit_orders.lock().expect("lock poisoned");
+            limit_orders.add_composable_order(mapped_order)?;
+            self.bump_version();
             self.metrics.incr_composable_limit_orders(1);
         }
+        self.deadlines.lock().expect("poisoned").extend(deadline);
 
         Ok(())
     }
 
-    pub fn add_new_searcher_order(
+    /// Takes the hashes of all orders with a deadline before `now`, earliest
+    /// first. Some of them might have already left the book.
+    pub fn take_expired(&self, now: U256) -> Vec<B256> {
+        let mut deadlines = self.deadlines.lock().expect("poisoned");
+        let live = deadlines.split_off(&(now, B256::ZERO));
+
+        std::mem::replace(&mut *deadlines, live)
+            .into_iter()
+            .map(|(_, hash)| hash)
+            .collect()
+    }
+
+    /// Checks a searcher order against the anti-sniping rule of its pool.
+    pub fn check_tob_replacement(
         &self,
-        order: OrderWithStorageData<TopOfBlockOrder>
+        pool_id: &PoolId,
+        reward: U256
     ) -> Result<(), SearcherPoolError> {
         self.searcher_orders
             .lock()
             .expect("lock poisoned")
-            .add_searcher_order(order)?;
+            .check_replacement(pool_id, reward)
+    }
+
+    /// Opens the searcher auction of a new round.
+    pub fn new_round(&self) {
+        self.searcher_orders
+            .lock()
+            .expect("lock poisoned")
+            .new_round();
+    }
+
+    pub fn add_new_searcher_order(
+        &self,
+        order: OrderWithStorageData<TopOfBlockOrder>
+    ) -> Result<(), SearcherPoolError> {
+        let mut searcher_orders = self.searcher_orders.lock().expect("lock poisoned");
+        searcher_orders.add_searcher_order(order)?;
+        self.bump_version();
+        drop(searcher_orders);
 
         self.metrics.incr_searcher_orders(1);
 
@@ -242,51 +385,327 @@ impl OrderStorage {
     }
 
     pub fn remove_searcher_order(&self, id: &OrderId) -> Option<OrderWithStorageData<AllOrders>> {
-        let order = self
-            .searcher_orders
-            .lock()
-            .expect("posioned")
-            .remove_order(id)
-            .map(|value| {
-                value
-                    .try_map_inner(|v| {
-                        self.metrics.decr_searcher_orders(1);
-                        Ok(AllOrders::TOB(v))
-                    })
-                    .unwrap()
-            });
+        let mut searcher_orders = self.searcher_orders.lock().expect("posioned");
+        let order = searcher_orders.remove_order(id).map(|value| {
+            self.bump_version();
+            value
+                .try_map_inner(|v| {
+                    self.metrics.decr_searcher_orders(1);
+                    Ok(AllOrders::TOB(v))
+                })
+                .unwrap()
+        });
 
         order
     }
 
     pub fn remove_limit_order(&self, id: &OrderId) -> Option<OrderWithStorageData<AllOrders>> {
+        let mut limit_orders = self.limit_orders.lock().expect("poisoned");
+        limit_orders.remove_order(id).and_then(|order| {
+            self.bump_version();
+            if order.is_vanilla() {
+                self.metrics.decr_vanilla_limit_orders(1);
+            } else if order.is_composable() {
+                self.metrics.decr_composable_limit_orders(1);
+            }
+
+            order.try_map_inner(|inner| Ok(inner.into())).ok()
+        })
+    }
+
+    pub fn queue_position(&self, id: &OrderId) -> Option<QueuePosition> {
         self.limit_orders
             .lock()
             .expect("poisoned")
-            .remove_order(id)
-            .and_then(|order| {
-                if order.is_vanilla() {
-                    self.metrics.decr_vanilla_limit_orders(1);
-                } else if order.is_composable() {
-                    self.metrics.decr_composable_limit_orders(1);
-                }
+            .queue_position(id)
+    }
+
+    /// The live book, read with both pool locks held.
+    pub fn get_all_orders(&self) -> OrderSet<GroupedVanillaOrder, TopOfBlockOrder> {
+        self.versioned_orders().1
+    }
+
+    /// The orders of the book and the version they were read at. The version
+    /// is read under both pool locks, so it matches the orders.
+    fn versioned_orders(&self) -> (u64, OrderSet<GroupedVanillaOrder, TopOfBlockOrder>) {
+        let limit_orders = self.limit_orders.lock().expect("poisoned");
+        let searcher_orders = self.searcher_orders.lock().expect("poisoned");
+        let version = self.version.load(Ordering::Acquire);
+        let limit = limit_orders.get_all_orders();
+        let searcher = Self::top_tob_orders_of(&searcher_orders);
+
+        (version, OrderSet { limit, searcher })
+    }
+
+    /// The snapshot of the current block phase.
+    pub fn snapshot(&self) -> Arc<OrderBookSnapshot> {
+        self.snapshot.lock().expect("poisoned").clone()
+    }
+
+    /// Copies the book into a new snapshot handed out until the next block
+    /// phase pins another, so the book is copied once a phase rather than on
+    /// every read after a change. Does nothing if the book hasn't changed
+    /// since the last pin.
+    pub fn pin_snapshot(&self) -> Arc<OrderBookSnapshot> {
+        let mut snapshot = self.snapshot.lock().expect("poisoned");
+        if snapshot.version != self.version.load(Ordering::Acquire) {
+            let (version, orders) = self.versioned_orders();
+            *snapshot = Arc::new(OrderBookSnapshot::new(version, orders));
+        }
+
+        snapshot.clone()
+    }
+
+    /// Up to `limit` orders matching `filter` after the cursor, taken from the
+    /// pinned snapshot without copying the rest of the book.
+    pub fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> OrderPage {
+        let snapshot = self.snapshot();
+        let limit = limit.max(1);
+        let OrderSet { limit: limit_orders, searcher: searcher_orders } = &snapshot.orders;
+        // the cursor sorts limit orders before searcher orders, so a cursor
+        // on a searcher order skips all limit orders
+        let limit_start = after.map_or(0, |after| {
+            snapshot
+                .limit_page_index
+                .partition_point(|i| OrderCursor::of(false, &limit_orders[*i]) <= after)
+        });
+        let searcher_start = after.map_or(0, |after| {
+            snapshot
+                .searcher_page_index
+                .partition_point(|i| OrderCursor::of(true, &searcher_orders[*i]) <= after)
+        });
+
+        let mut orders = snapshot.limit_page_index[limit_start..]
+            .iter()
+            .map(|i| &limit_orders[*i])
+            .filter(|order| filter.matches(&order.order_id))
+            .map(|order| (OrderCursor::of(false, order), AllOrders::from(order.order.clone())))
+            .chain(
+                snapshot.searcher_page_index[searcher_start..]
+                    .iter()
+                    .map(|i| &searcher_orders[*i])
+                    .filter(|order| filter.matches(&order.order_id))
+                    .map(|order| {
+                        (OrderCursor::of(true, order), AllOrders::TOB(order.order.clone()))
+                    })
+            )
+            .take(limit + 1)
+            .collect::<Vec<_>>();
 
-                order.try_map_inner(|inner| Ok(inner.into())).ok()
+        let next = (orders.len() > limit).then(|| orders[limit - 1].0);
+        orders.truncate(limit);
+
+        OrderPage {
+            version: snapshot.version,
+            orders: orders.into_iter().map(|(_, order)| order).collect(),
+            next
+        }
+    }
+
+    /// Must be called while holding the lock of the pool that changed.
+    fn bump_version(&self) {
+        self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// The pending limit and searcher orders among the given ids, looked up
+    /// one by one rather than copying the book.
+    pub fn pending_orders_by_id(&self, order_ids: &[OrderId]) -> Vec<AllOrders> {
+        let limit_orders = self.limit_orders.lock().expect("poisoned");
+        let searcher_orders = self.searcher_orders.lock().expect("poisoned");
+        order_ids
+            .iter()
+            .filter_map(|id| match id.location {
+                OrderLocation::Limit => limit_orders
+                    .get_pending_order(id)
+                    .map(|order| AllOrders::from(order.order.clone())),
+                OrderLocation::Searcher => searcher_orders
+                    .get_order(id)
+                    .map(|order| AllOrders::TOB(order.order.clone()))
             })
+            .collect()
     }
 
-    pub fn get_all_orders(&self) -> OrderSet<GroupedVanillaOrder, TopOfBlockOrder> {
+    /// Writes all live limit and searcher orders to a versioned snapshot at
+    /// the given path.
+    pub fn export_orders(&self, path: impl AsRef<Path>) -> Result<usize, OrderSnapshotError> {
         let limit = self.limit_orders.lock().expect("poisoned").get_all_orders();
-        let searcher = self.top_tob_orders();
+        let searcher = self
+            .searcher_orders
+            .lock()
+            .expect("poisoned")
+            .get_all_orders();
+
+        let orders = limit
+            .into_iter()
+            .map(|order| AllOrders::from(order.order))
+            .chain(
+                searcher
+                    .into_iter()
+                    .map(|order| AllOrders::TOB(order.order))
+            )
+            .collect::<Vec<_>>();
+        let exported = orders.len();
+        OrderSnapshot::new(orders).write(path)?;
+
+        Ok(exported)
+    }
 
-        OrderSet { limit, searcher }
+    /// Loads the orders of a snapshot, dropping any order with an invalid
+    /// signature. The orders are not inserted into storage as they still need
+    /// to go through validation against the current state.
+    pub fn import_orders(
+        &self,
+        path: impl AsRef<Path>
+    ) -> Result<Vec<AllOrders>, OrderSnapshotError> {
+        Ok(OrderSnapshot::read(path)?.into_valid_orders())
     }
 
     pub fn new_pool(&self, pool: NewInitializedPool) {
-        self.limit_orders.lock().expect("poisoned").new_pool(pool);
-        self.searcher_orders
-            .lock()
-            .expect("poisoned")
-            .new_pool(pool);
+        let mut limit_orders = self.limit_orders.lock().expect("poisoned");
+        let mut searcher_orders = self.searcher_orders.lock().expect("poisoned");
+        limit_orders.new_pool(pool);
+        searcher_orders.new_pool(pool);
+        self.bump_version();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::{
+        orders::OrderPriorityData,
+        sol_bindings::{grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder}
+    };
+
+    use super::*;
+
+    fn pool() -> NewInitializedPool {
+        NewInitializedPool {
+            currency_in:  Address::with_last_byte(1),
+            currency_out: Address::with_last_byte(2),
+            id:           PoolId::with_last_byte(3)
+        }
+    }
+
+    /// a distinct order per price
+    fn limit_order(price: u8, signer: u8) -> OrderWithStorageData<GroupedUserOrder> {
+        let pool_id = pool().id;
+        let order = GroupedVanillaOrder::Standing(StandingVariants::Exact(ExactStandingOrder {
+            minPrice: U256::from(price),
+            nonce: price.into(),
+            ..Default::default()
+        }));
+        OrderWithStorageData {
+            pool_id,
+            is_bid: true,
+            is_currently_valid: true,
+            priority_data: OrderPriorityData { price: U256::from(price), ..Default::default() },
+            order_id: OrderId {
+                address: Address::with_last_byte(signer),
+                pool_id,
+                hash: order.hash(),
+                ..Default::default()
+            },
+            order,
+            ..Default::default()
+        }
+        .try_map_inner(|order: GroupedVanillaOrder| Ok(GroupedUserOrder::Vanilla(order)))
+        .unwrap()
+    }
+
+    #[test]
+    fn takes_deadlines_strictly_before_now() {
+        let storage = OrderStorage::default();
+        storage.new_pool(pool());
+        let hashes = [(1, 10), (2, 11), (3, 12)].map(|(price, deadline)| {
+            let mut order = limit_order(price, 1);
+            order.order_id.deadline = Some(U256::from(deadline));
+            let hash = order.order_id.hash;
+            storage.add_new_limit_order(order).unwrap();
+            hash
+        });
+
+        // an order is still live at its deadline
+        assert_eq!(storage.take_expired(U256::from(11)), vec![hashes[0]]);
+        assert!(storage.take_expired(U256::from(11)).is_empty());
+        assert_eq!(storage.take_expired(U256::from(13)), hashes[1..]);
+    }
+
+    #[test]
+    fn hands_out_the_pinned_snapshot_until_the_next_pin() {
+        let storage = OrderStorage::default();
+        let before = storage.pin_snapshot();
+        assert!(Arc::ptr_eq(&before, &storage.snapshot()));
+
+        // changes to the book don't show until the next phase
+        storage.new_pool(pool());
+        storage.add_new_limit_order(limit_order(1, 1)).unwrap();
+        assert!(Arc::ptr_eq(&before, &storage.snapshot()));
+        assert_eq!(storage.get_all_orders().limit.len(), 1);
+
+        let after = storage.pin_snapshot();
+        assert_eq!(after.version, before.version + 2);
+        assert_eq!(after.orders.limit.len(), 1);
+        assert!(Arc::ptr_eq(&after, &storage.snapshot()));
+        // an unchanged book isn't copied again
+        assert!(Arc::ptr_eq(&after, &storage.pin_snapshot()));
+    }
+
+    #[test]
+    fn pages_through_the_book() {
+        let storage = OrderStorage::default();
+        storage.new_pool(pool());
+        for (price, signer) in [(3, 1), (1, 1), (2, 2)] {
+            storage
+                .add_new_limit_order(limit_order(price, signer))
+                .unwrap();
+        }
+        storage.pin_snapshot();
+        let hashes = |page: &OrderPage| {
+            page.orders
+                .iter()
+                .map(|o| o.order_hash())
+                .collect::<Vec<_>>()
+        };
+
+        let first = storage.orders_page(OrderFilter::All, None, 2);
+        assert_eq!(first.orders.len(), 2);
+        assert_eq!(first.next.map(|cursor| cursor.price), Some(U256::from(2)));
+        let last = storage.orders_page(OrderFilter::All, first.next, 2);
+        assert_eq!(last.orders.len(), 1);
+        assert_eq!(last.next, None);
+        assert_eq!(hashes(&last), vec![limit_order(3, 1).order_id.hash]);
+        assert!(!hashes(&first).contains(&hashes(&last)[0]));
+
+        let signed =
+            storage.orders_page(OrderFilter::Address(Address::with_last_byte(1)), None, 10);
+        assert_eq!(signed.orders.len(), 2);
+        assert_eq!(signed.next, None);
+    }
+
+    #[test]
+    fn paging_leaves_the_order_of_the_book_alone() {
+        let storage = OrderStorage::default();
+        storage.new_pool(pool());
+        for (price, signer) in [(3, 1), (1, 1), (2, 2)] {
+            storage
+                .add_new_limit_order(limit_order(price, signer))
+                .unwrap();
+        }
+        let book = |orders: Vec<OrderWithStorageData<GroupedVanillaOrder>>| {
+            orders
+                .into_iter()
+                .map(|order| order.order_id.hash)
+                .collect::<Vec<_>>()
+        };
+
+        let unsorted = book(storage.limit_orders.lock().unwrap().get_all_orders());
+        storage.pin_snapshot();
+        storage.orders_page(OrderFilter::All, None, 1);
+        assert_eq!(book(storage.get_all_orders().limit), unsorted);
     }
 }
@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 
+use alloy::primitives::U256;
 use angstrom_metrics::SearcherOrderPoolMetricsWrapper;
 use angstrom_types::{
     orders::OrderId,
@@ -9,20 +10,35 @@ use angstrom_types::{
 use angstrom_utils::map::OwnedMap;
 use pending::PendingPool;
 
-use crate::common::SizeTracker;
+use crate::{common::SizeTracker, config::TobReplacementRule};
 
 mod pending;
 
 #[allow(dead_code)]
 pub const SEARCHER_POOL_MAX_SIZE: usize = 15;
 
-#[derive(Default)]
 pub struct SearcherPool {
     /// Holds all non composable searcher order pools
     searcher_orders: HashMap<PoolId, PendingPool>,
     /// The size of the current transactions.
     size:            SizeTracker,
-    metrics:         SearcherOrderPoolMetricsWrapper
+    metrics:         SearcherOrderPoolMetricsWrapper,
+    replacement:     TobReplacementRule,
+    /// when the current round started, the anti-sniping window is relative to
+    /// it
+    round_start:     Instant
+}
+
+impl Default for SearcherPool {
+    fn default() -> Self {
+        Self {
+            searcher_orders: Default::default(),
+            size:            Default::default(),
+            metrics:         Default::default(),
+            replacement:     Default::default(),
+            round_start:     Instant::now()
+        }
+    }
 }
 
 impl SearcherPool {
@@ -31,7 +47,42 @@ impl SearcherPool {
         Self {
             searcher_orders,
             size: SizeTracker { max: max_size, current: 0 },
-            metrics: SearcherOrderPoolMetricsWrapper::default()
+            metrics: SearcherOrderPoolMetricsWrapper::default(),
+            replacement: TobReplacementRule::default(),
+            round_start: Instant::now()
+        }
+    }
+
+    pub fn with_replacement_rule(mut self, replacement: TobReplacementRule) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    pub fn new_round(&mut self) {
+        self.round_start = Instant::now();
+    }
+
+    /// Checks the order against the anti-sniping rule.
+    pub fn check_replacement(
+        &self,
+        pool_id: &PoolId,
+        reward: U256
+    ) -> Result<(), SearcherPoolError> {
+        let Some(best) = self
+            .searcher_orders
+            .get(pool_id)
+            .and_then(|pool| pool.best_reward())
+        else {
+            return Ok(())
+        };
+
+        if self
+            .replacement
+            .allows(best, reward, self.round_start.elapsed())
+        {
+            Ok(())
+        } else {
+            Err(SearcherPoolError::InsufficientImprovement(best, reward))
         }
     }
 
@@ -62,6 +113,10 @@ impl SearcherPool {
             .owned_map(|| self.metrics.decr_all_orders(id.pool_id, 1))
     }
 
+    pub fn get_order(&self, id: &OrderId) -> Option<&OrderWithStorageData<TopOfBlockOrder>> {
+        self.searcher_orders.get(&id.pool_id)?.get_order(id.hash)
+    }
+
     pub fn get_all_pool_ids(&self) -> Vec<PoolId> {
         self.searcher_orders.keys().cloned().collect()
     }
@@ -97,6 +152,18 @@ pub enum SearcherPoolError {
     MaxSize,
     #[error("No pool was found for address: {0} ")]
     NoPool(PoolId),
+    #[error("Reward {1} doesn't improve enough on the best reward {0} this late in the round")]
+    InsufficientImprovement(U256, U256),
     #[error(transparent)]
     Unknown(#[from] eyre::Error)
 }
+
+impl From<SearcherPoolError> for angstrom_errors::PoolError {
+    fn from(value: SearcherPoolError) -> Self {
+        match value {
+            SearcherPoolError::MaxSize => Self::MaxSize,
+            SearcherPoolError::NoPool(_) => Self::NoPool,
+            e => Self::Other(e.to_string())
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     collections::{BTreeMap, HashMap}
 };
 
-use alloy::primitives::FixedBytes;
+use alloy::primitives::{FixedBytes, U256};
 use angstrom_types::{
     orders::OrderPriorityData,
     sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
@@ -52,6 +52,14 @@ impl PendingPool {
         Some(order)
     }
 
+    pub fn get_order(&self, id: FixedBytes<32>) -> Option<&OrderWithStorageData<TopOfBlockOrder>> {
+        self.orders.get(&id)
+    }
+
+    pub fn best_reward(&self) -> Option<U256> {
+        self.orders.values().map(|order| order.tob_reward).max()
+    }
+
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<TopOfBlockOrder>> {
         // TODO:  This should maybe only return the one best Searcher order we've seen?
         self.orders.values().cloned().collect()
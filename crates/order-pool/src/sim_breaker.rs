@@ -0,0 +1,98 @@
+//! Per-order circuit breaker for simulations. Provider errors are retried when
+//! the order is revalidated, but after enough of them in a row the order is
+//! parked as unstable so that it stops costing a revm run on every block.
+use std::collections::{HashMap, HashSet};
+
+use alloy::primitives::B256;
+use angstrom_errors::SimError;
+
+/// What to do with an order whose simulation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimVerdict {
+    /// the failure was deterministic, the order is invalid
+    Invalid,
+    /// park the order and simulate it again on its next revalidation
+    Retry,
+    /// park the order and stop revalidating it
+    Unstable
+}
+
+#[derive(Debug)]
+pub struct SimCircuitBreaker {
+    /// transient failures in a row after which an order is unstable
+    max_transient_failures: u32,
+    transient_failures:     HashMap<B256, u32>,
+    unstable:               HashSet<B256>
+}
+
+impl SimCircuitBreaker {
+    pub fn new(max_transient_failures: u32) -> Self {
+        Self {
+            max_transient_failures,
+            transient_failures: HashMap::new(),
+            unstable: HashSet::new()
+        }
+    }
+
+    pub fn on_failure(&mut self, order_hash: B256, error: &SimError) -> SimVerdict {
+        if !error.is_transient() {
+            self.clear(&order_hash);
+            return SimVerdict::Invalid
+        }
+
+        let failures = self.transient_failures.entry(order_hash).or_default();
+        *failures += 1;
+        if *failures < self.max_transient_failures {
+            return SimVerdict::Retry
+        }
+
+        self.transient_failures.remove(&order_hash);
+        self.unstable.insert(order_hash);
+        SimVerdict::Unstable
+    }
+
+    /// The order simulated successfully or left the pool.
+    pub fn clear(&mut self, order_hash: &B256) {
+        self.transient_failures.remove(order_hash);
+        self.unstable.remove(order_hash);
+    }
+
+    pub fn is_unstable(&self, order_hash: &B256) -> bool {
+        self.unstable.contains(order_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_after_repeated_transient_failures() {
+        let mut breaker = SimCircuitBreaker::new(3);
+        let hash = B256::repeat_byte(1);
+        let error = SimError::Provider("timeout".to_string());
+
+        assert_eq!(breaker.on_failure(hash, &error), SimVerdict::Retry);
+        assert_eq!(breaker.on_failure(hash, &error), SimVerdict::Retry);
+        assert!(!breaker.is_unstable(&hash));
+        assert_eq!(breaker.on_failure(hash, &error), SimVerdict::Unstable);
+        assert!(breaker.is_unstable(&hash));
+
+        breaker.clear(&hash);
+        assert!(!breaker.is_unstable(&hash));
+        assert_eq!(breaker.on_failure(hash, &error), SimVerdict::Retry);
+    }
+
+    #[test]
+    fn reverts_are_not_retried() {
+        let mut breaker = SimCircuitBreaker::new(3);
+        let hash = B256::repeat_byte(1);
+
+        breaker.on_failure(hash, &SimError::Provider("timeout".to_string()));
+        assert_eq!(
+            breaker.on_failure(hash, &SimError::Revert("0x".to_string())),
+            SimVerdict::Invalid
+        );
+        assert!(!breaker.is_unstable(&hash));
+    }
+}
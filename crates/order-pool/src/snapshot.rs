@@ -0,0 +1,119 @@
+//! Versioned snapshot of the live orders in the pool. Used to migrate the
+//! resting book of a node to new hardware.
+
+use angstrom_types::{
+    persistence::{Persisted, PersistenceError},
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use serde::{Deserialize, Serialize};
+
+/// The current version of the snapshot format. Needs to be bumped whenever
+/// the layout of [`OrderSnapshot`] changes.
+pub const ORDER_SNAPSHOT_VERSION: u8 = 1;
+
+pub type OrderSnapshotError = PersistenceError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderSnapshot {
+    /// only the signed orders are stored as all of the storage data is
+    /// re-derived when the orders are validated again on import.
+    pub orders: Vec<AllOrders>
+}
+
+impl Persisted for OrderSnapshot {
+    const VERSION: u8 = ORDER_SNAPSHOT_VERSION;
+}
+
+impl OrderSnapshot {
+    pub fn new(orders: Vec<AllOrders>) -> Self {
+        Self { orders }
+    }
+
+    /// Consumes the snapshot, returning all orders that have a valid
+    /// signature.
+    pub fn into_valid_orders(self) -> Vec<AllOrders> {
+        self.orders
+            .into_iter()
+            .filter(|order| {
+                let valid = order.is_valid_signature();
+                if !valid {
+                    tracing::warn!(hash=?order.order_hash(), "dropping snapshot order with invalid signature");
+                }
+                valid
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Bytes, U256};
+    use angstrom_types::matching::Ray;
+    use testing_tools::type_generator::orders::{build_top_of_block_order, UserOrderBuilder};
+
+    use super::*;
+
+    /// an order of every kind, with the fields the builders set filled in
+    fn orders() -> Vec<AllOrders> {
+        let user_order = |builder: UserOrderBuilder| {
+            AllOrders::from(
+                builder
+                    .block(12)
+                    .nonce(7)
+                    .recipient(Address::with_last_byte(3))
+                    .asset_in(Address::with_last_byte(1))
+                    .asset_out(Address::with_last_byte(2))
+                    .amount(1_000)
+                    .min_price(Ray::from(U256::from(5_u8)))
+                    .hook_payload(Bytes::from_static(&[1, 2, 3]))
+                    .build()
+            )
+        };
+
+        vec![
+            user_order(UserOrderBuilder::new().standing().exact()),
+            user_order(UserOrderBuilder::new().standing().partial()),
+            user_order(UserOrderBuilder::new().kill_or_fill().exact()),
+            user_order(UserOrderBuilder::new().kill_or_fill().partial()),
+            AllOrders::TOB(build_top_of_block_order(10, 20)),
+        ]
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.json");
+        let newer = serde_json::json!({ "version": ORDER_SNAPSHOT_VERSION + 1, "orders": [] });
+        std::fs::write(&path, newer.to_string()).unwrap();
+
+        assert!(matches!(
+            OrderSnapshot::read(&path),
+            Err(OrderSnapshotError::UnsupportedVersion { found, .. })
+                if found == ORDER_SNAPSHOT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.json");
+
+        for orders in [vec![], orders()] {
+            let snapshot = OrderSnapshot::new(orders);
+            snapshot.write(&path).unwrap();
+
+            assert_eq!(OrderSnapshot::read(&path).unwrap(), snapshot);
+        }
+    }
+
+    #[test]
+    fn drops_unsigned_orders_on_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.json");
+        OrderSnapshot::new(orders()).write(&path).unwrap();
+
+        let snapshot = OrderSnapshot::read(&path).unwrap();
+        assert_eq!(snapshot.orders.len(), 5);
+        assert!(snapshot.into_valid_orders().is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Per-pool activity statistics, computed every block, to help operators spot
+//! manipulation patterns such as spoofing (high cancel-to-trade ratios with a
+//! lopsided book) or searcher bid wars.
+use std::collections::{HashMap, VecDeque};
+
+use alloy::primitives::{BlockNumber, B256, U256};
+use angstrom_metrics::SurveillanceMetricsWrapper;
+use angstrom_types::{
+    orders::{OrderOrigin, OrderSet},
+    primitive::PoolId,
+    sol_bindings::{grouped_orders::GroupedVanillaOrder, rpc_orders::TopOfBlockOrder}
+};
+use serde::{Deserialize, Serialize};
+
+use crate::limit::QueuePosition;
+
+/// Number of blocks the taker flow used for fill estimates is averaged over.
+const TAKER_FLOW_WINDOW: usize = 10;
+/// Number of blocks the order totals are kept for.
+const BLOCK_HISTORY: usize = 256;
+
+/// Activity of a single pool over a block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolActivity {
+    pub pool_id:               PoolId,
+    pub block_number:          BlockNumber,
+    /// resting volume on each side of the book at the end of the block
+    pub bid_depth:             u128,
+    pub ask_depth:             u128,
+    /// `(bid - ask) / (bid + ask)`, `0` if the book is empty
+    pub depth_imbalance:       f64,
+    pub cancels:               u64,
+    pub fills:                 u64,
+    /// `None` if nothing filled during the block
+    pub cancel_to_trade_ratio: Option<f64>,
+    pub local_arrivals:        u64,
+    pub external_arrivals:     u64,
+    pub private_arrivals:      u64,
+    /// number of searcher bids received
+    pub tob_bids:              u64,
+    /// highest searcher reward bid
+    pub tob_max_reward:        U256
+}
+
+/// Order totals of a block across all pools.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOrderActivity {
+    pub block_number:  BlockNumber,
+    /// orders submitted, valid or not
+    pub received:      u64,
+    pub validated:     u64,
+    pub filled:        u64,
+    /// pools that had at least one order filled, sorted
+    pub pools_touched: Vec<PoolId>
+}
+
+/// Where a resting limit order sits in the book and how likely it is to fill
+/// next block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatus {
+    pub order_hash:       B256,
+    pub pool_id:          PoolId,
+    pub is_bid:           bool,
+    /// number of orders ahead of it at its price level
+    pub queue_position:   usize,
+    /// volume resting ahead of it, including better priced levels
+    pub depth_ahead:      u128,
+    /// filled volume on its side of the book over the last blocks divided by
+    /// the volume up to and including the order, capped at `1`. `None` until
+    /// a block has been observed.
+    pub fill_probability: Option<f64>
+}
+
+#[derive(Debug, Default)]
+struct BlockCounters {
+    cancels:           u64,
+    fills:             u64,
+    local_arrivals:    u64,
+    external_arrivals: u64,
+    private_arrivals:  u64,
+    tob_bids:          u64,
+    tob_max_reward:    U256,
+    /// filled volume of bids and asks
+    filled_volume:     (u128, u128)
+}
+
+#[derive(Default)]
+pub struct PoolSurveillance {
+    /// origin of orders that are still being validated, as the pool of an
+    /// order is only known once it's validated
+    pending_origins: HashMap<B256, OrderOrigin>,
+    current:         HashMap<PoolId, BlockCounters>,
+    /// orders submitted since the last block
+    received:        u64,
+    last_block:      HashMap<PoolId, PoolActivity>,
+    /// order totals of the last [`BLOCK_HISTORY`] blocks, oldest first
+    history:         VecDeque<BlockOrderActivity>,
+    /// filled volume of bids and asks per pool of the last
+    /// [`TAKER_FLOW_WINDOW`] blocks
+    taker_flow:      VecDeque<HashMap<PoolId, (u128, u128)>>,
+    metrics:         SurveillanceMetricsWrapper
+}
+
+impl PoolSurveillance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_new_order(&mut self, order_hash: B256, origin: OrderOrigin) {
+        self.received += 1;
+        self.pending_origins.insert(order_hash, origin);
+    }
+
+    pub fn on_invalid_order(&mut self, order_hash: &B256) {
+        self.pending_origins.remove(order_hash);
+    }
+
+    pub fn on_valid_order(&mut self, order_hash: &B256, pool_id: PoolId, tob_reward: Option<U256>) {
+        let origin = self
+            .pending_origins
+            .remove(order_hash)
+            .unwrap_or(OrderOrigin::External);
+        let counters = self.current.entry(pool_id).or_default();
+        match origin {
+            OrderOrigin::Local => counters.local_arrivals += 1,
+            OrderOrigin::External => counters.external_arrivals += 1,
+            OrderOrigin::Private => counters.private_arrivals += 1
+        }
+
+        if let Some(reward) = tob_reward {
+            counters.tob_bids += 1;
+            counters.tob_max_reward = counters.tob_max_reward.max(reward);
+        }
+    }
+
+    pub fn on_cancel(&mut self, pool_id: PoolId) {
+        self.current.entry(pool_id).or_default().cancels += 1;
+    }
+
+    pub fn on_fill(&mut self, pool_id: PoolId, is_bid: bool, volume: u128) {
+        let counters = self.current.entry(pool_id).or_default();
+        counters.fills += 1;
+        if is_bid {
+            counters.filled_volume.0 += volume;
+        } else {
+            counters.filled_volume.1 += volume;
+        }
+    }
+
+    /// Closes the stats of the block against the resting book and exports
+    /// them.
+    pub fn on_new_block(
+        &mut self,
+        block_number: BlockNumber,
+        book: &OrderSet<GroupedVanillaOrder, TopOfBlockOrder>
+    ) {
+        let mut depth: HashMap<PoolId, (u128, u128)> = HashMap::new();
+        book.limit.iter().for_each(|order| {
+            let (bids, asks) = depth.entry(order.pool_id).or_default();
+            if order.is_bid {
+                *bids += order.priority_data.volume;
+            } else {
+                *asks += order.priority_data.volume;
+            }
+        });
+
+        let mut counters = std::mem::take(&mut self.current);
+        let mut pools_touched = counters
+            .iter()
+            .filter(|(_, c)| c.fills != 0)
+            .map(|(pool_id, _)| *pool_id)
+            .collect::<Vec<_>>();
+        pools_touched.sort_unstable();
+        if self.history.len() == BLOCK_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(BlockOrderActivity {
+            block_number,
+            received: std::mem::take(&mut self.received),
+            validated: counters
+                .values()
+                .map(|c| c.local_arrivals + c.external_arrivals + c.private_arrivals)
+                .sum(),
+            filled: counters.values().map(|c| c.fills).sum(),
+            pools_touched
+        });
+
+        if self.taker_flow.len() == TAKER_FLOW_WINDOW {
+            self.taker_flow.pop_front();
+        }
+        self.taker_flow.push_back(
+            counters
+                .iter()
+                .map(|(pool_id, c)| (*pool_id, c.filled_volume))
+                .collect()
+        );
+
+        let pools = depth
+            .keys()
+            .chain(counters.keys())
+            .copied()
+            .collect::<std::collections::HashSet<_>>();
+
+        self.last_block = pools
+            .into_iter()
+            .map(|pool_id| {
+                let (bid_depth, ask_depth) = depth.get(&pool_id).copied().unwrap_or_default();
+                let c = counters.remove(&pool_id).unwrap_or_default();
+                let total_depth = bid_depth as f64 + ask_depth as f64;
+                let activity = PoolActivity {
+                    pool_id,
+                    block_number,
+                    bid_depth,
+                    ask_depth,
+                    depth_imbalance: if total_depth == 0.0 {
+                        0.0
+                    } else {
+                        (bid_depth as f64 - ask_depth as f64) / total_depth
+                    },
+                    cancels: c.cancels,
+                    fills: c.fills,
+                    cancel_to_trade_ratio: (c.fills != 0)
+                        .then(|| c.cancels as f64 / c.fills as f64),
+                    local_arrivals: c.local_arrivals,
+                    external_arrivals: c.external_arrivals,
+                    private_arrivals: c.private_arrivals,
+                    tob_bids: c.tob_bids,
+                    tob_max_reward: c.tob_max_reward
+                };
+                self.export(&activity);
+
+                (pool_id, activity)
+            })
+            .collect();
+    }
+
+    fn export(&self, activity: &PoolActivity) {
+        let pool = activity.pool_id;
+        self.metrics
+            .set_depth_imbalance(pool, activity.depth_imbalance);
+        self.metrics
+            .set_cancel_to_trade_ratio(pool, activity.cancel_to_trade_ratio.unwrap_or_default());
+        self.metrics
+            .set_arrivals(pool, "local", activity.local_arrivals);
+        self.metrics
+            .set_arrivals(pool, "external", activity.external_arrivals);
+        self.metrics
+            .set_arrivals(pool, "private", activity.private_arrivals);
+        self.metrics.set_tob_bids(pool, activity.tob_bids);
+    }
+
+    /// Naive estimate of the chance the order fills next block, assuming the
+    /// average taker flow of the last blocks keeps consuming its side of the
+    /// book in priority order.
+    pub fn order_status(
+        &self,
+        order_hash: B256,
+        pool_id: PoolId,
+        position: QueuePosition
+    ) -> OrderStatus {
+        let fill_probability = (!self.taker_flow.is_empty()).then(|| {
+            let flow = self
+                .taker_flow
+                .iter()
+                .filter_map(|block| block.get(&pool_id))
+                .map(|&(bids, asks)| if position.is_bid { bids as f64 } else { asks as f64 })
+                .sum::<f64>()
+                / self.taker_flow.len() as f64;
+            let needed = position.depth_ahead as f64 + position.volume as f64;
+
+            if needed == 0.0 {
+                1.0
+            } else {
+                (flow / needed).min(1.0)
+            }
+        });
+
+        OrderStatus {
+            order_hash,
+            pool_id,
+            is_bid: position.is_bid,
+            queue_position: position.position,
+            depth_ahead: position.depth_ahead,
+            fill_probability
+        }
+    }
+
+    /// Stats of the last completed block, for all pools or a single one.
+    pub fn activity(&self, pool_id: Option<PoolId>) -> Vec<PoolActivity> {
+        match pool_id {
+            Some(pool_id) => self.last_block.get(&pool_id).cloned().into_iter().collect(),
+            None => self.last_block.values().cloned().collect()
+        }
+    }
+
+    /// Order totals of a recent block, `None` once it's out of the history.
+    pub fn block_activity(&self, block_number: BlockNumber) -> Option<BlockOrderActivity> {
+        self.history
+            .iter()
+            .rev()
+            .find(|block| block.block_number == block_number)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_block_stats() {
+        let pool = PoolId::with_last_byte(1);
+        let mut surveillance = PoolSurveillance::new();
+
+        surveillance.on_new_order(B256::with_last_byte(1), OrderOrigin::Local);
+        surveillance.on_new_order(B256::with_last_byte(2), OrderOrigin::External);
+        surveillance.on_valid_order(&B256::with_last_byte(1), pool, None);
+        surveillance.on_valid_order(&B256::with_last_byte(2), pool, Some(U256::from(7)));
+        surveillance.on_cancel(pool);
+        surveillance.on_cancel(pool);
+        surveillance.on_cancel(pool);
+        surveillance.on_fill(pool, true, 100);
+
+        surveillance.on_new_block(1, &OrderSet { limit: vec![], searcher: vec![] });
+        let activity = surveillance.activity(Some(pool)).pop().unwrap();
+        assert_eq!(activity.local_arrivals, 1);
+        assert_eq!(activity.external_arrivals, 1);
+        assert_eq!(activity.tob_bids, 1);
+        assert_eq!(activity.tob_max_reward, U256::from(7));
+        assert_eq!(activity.cancel_to_trade_ratio, Some(3.0));
+        assert_eq!(activity.depth_imbalance, 0.0);
+
+        // counters reset every block
+        surveillance.on_new_block(2, &OrderSet { limit: vec![], searcher: vec![] });
+        assert!(surveillance.activity(Some(pool)).is_empty());
+    }
+
+    #[test]
+    fn keeps_order_totals_per_block() {
+        let pool = PoolId::with_last_byte(1);
+        let mut surveillance = PoolSurveillance::new();
+
+        surveillance.on_new_order(B256::with_last_byte(1), OrderOrigin::Local);
+        surveillance.on_new_order(B256::with_last_byte(2), OrderOrigin::External);
+        surveillance.on_new_order(B256::with_last_byte(3), OrderOrigin::External);
+        surveillance.on_valid_order(&B256::with_last_byte(1), pool, None);
+        surveillance.on_valid_order(&B256::with_last_byte(2), PoolId::with_last_byte(2), None);
+        surveillance.on_invalid_order(&B256::with_last_byte(3));
+        surveillance.on_fill(pool, true, 100);
+        surveillance.on_new_block(1, &OrderSet { limit: vec![], searcher: vec![] });
+        surveillance.on_new_block(2, &OrderSet { limit: vec![], searcher: vec![] });
+
+        assert_eq!(
+            surveillance.block_activity(1),
+            Some(BlockOrderActivity {
+                block_number:  1,
+                received:      3,
+                validated:     2,
+                filled:        1,
+                pools_touched: vec![pool]
+            })
+        );
+        assert_eq!(surveillance.block_activity(2).unwrap().received, 0);
+        assert_eq!(surveillance.block_activity(3), None);
+
+        for block in 3..(BLOCK_HISTORY as u64 + 2) {
+            surveillance.on_new_block(block, &OrderSet { limit: vec![], searcher: vec![] });
+        }
+        assert_eq!(surveillance.block_activity(1), None);
+        assert!(surveillance.block_activity(2).is_some());
+    }
+
+    #[test]
+    fn estimates_fill_probability() {
+        let pool = PoolId::with_last_byte(1);
+        let mut surveillance = PoolSurveillance::new();
+        let position =
+            QueuePosition { is_bid: true, position: 1, depth_ahead: 150, volume: 50 };
+        assert_eq!(
+            surveillance
+                .order_status(B256::ZERO, pool, position)
+                .fill_probability,
+            None
+        );
+
+        surveillance.on_fill(pool, true, 100);
+        surveillance.on_fill(pool, false, 1000);
+        surveillance.on_new_block(1, &OrderSet { limit: vec![], searcher: vec![] });
+        surveillance.on_new_block(2, &OrderSet { limit: vec![], searcher: vec![] });
+
+        let status = surveillance.order_status(B256::ZERO, pool, position);
+        assert_eq!(status.queue_position, 1);
+        assert_eq!(status.depth_ahead, 150);
+        // 100 filled over two blocks against 200 to fill
+        assert_eq!(status.fill_probability, Some(0.25));
+
+        let ask = QueuePosition { is_bid: false, ..position };
+        assert_eq!(
+            surveillance
+                .order_status(B256::ZERO, pool, ask)
+                .fill_probability,
+            Some(1.0)
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! Optional execution service that slices a parent TWAP instruction into child
+//! flash orders, submitting one per block and tracking their fills.
+//!
+//! As the node can't sign on behalf of users, the children are pre-signed by
+//! the user, each one bound to the block it should be executed in. A child
+//! that expires without being filled means the market traded through its
+//! limit price, so the parent is cancelled once too many children miss.
+use std::collections::HashMap;
+
+use alloy::primitives::{keccak256, BlockNumber, B256};
+use angstrom_types::sol_bindings::{
+    grouped_orders::{AllOrders, FlashVariants},
+    RawPoolOrder
+};
+use serde::{Deserialize, Serialize};
+
+/// Blocks the status of a completed or cancelled parent stays queryable for.
+pub const FINISHED_TWAP_RETENTION_BLOCKS: u64 = 64;
+
+/// A parent TWAP instruction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapInstruction {
+    /// the pre-signed children, ordered by the block they are valid for
+    pub slices:            Vec<FlashVariants>,
+    /// amount of children that can go unfilled before the parent is cancelled
+    #[serde(default)]
+    pub max_missed_slices: u32
+}
+
+impl TwapInstruction {
+    pub fn id(&self) -> B256 {
+        let hashes = self
+            .slices
+            .iter()
+            .flat_map(|slice| slice.order_hash().0)
+            .collect::<Vec<_>>();
+
+        keccak256(hashes)
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum TwapError {
+    #[error("twap service is not enabled")]
+    Disabled,
+    #[error("twap instruction has no slices")]
+    Empty,
+    #[error("slices must be from the same account and trade the same pair")]
+    MismatchedSlices,
+    #[error("slices must be valid for strictly increasing future blocks")]
+    InvalidSchedule,
+    #[error("slice {0} has an invalid signature")]
+    InvalidSignature(usize),
+    #[error("account has triggered its kill switch")]
+    DisabledAccount,
+    #[error("twap instruction was already submitted")]
+    Duplicate
+}
+
+impl From<TwapError> for angstrom_errors::PoolError {
+    fn from(value: TwapError) -> Self {
+        match value {
+            TwapError::Disabled => Self::Disabled,
+            TwapError::DisabledAccount => Self::DisabledAccount,
+            TwapError::Duplicate => Self::DuplicateOrder,
+            e => Self::InvalidRequest(e.to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TwapState {
+    Active,
+    Completed,
+    /// cancelled as too many slices went unfilled
+    Cancelled
+}
+
+/// Progress of a parent TWAP instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapStatus {
+    pub id:            B256,
+    pub state:         TwapState,
+    pub total_slices:  usize,
+    pub filled_slices: usize,
+    pub missed_slices: usize,
+    /// sum of the input amounts of the filled slices
+    pub filled_amount: u128,
+    /// block of the next slice that will be submitted
+    pub next_block:    Option<BlockNumber>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SliceState {
+    Pending,
+    Submitted,
+    Filled,
+    Missed
+}
+
+#[derive(Debug)]
+struct ParentOrder {
+    instruction: TwapInstruction,
+    slices:      Vec<SliceState>,
+    state:       TwapState,
+    /// block the parent completed or was cancelled in
+    finished_at: Option<BlockNumber>
+}
+
+impl ParentOrder {
+    fn count(&self, state: SliceState) -> usize {
+        self.slices.iter().filter(|s| **s == state).count()
+    }
+
+    fn status(&self, id: B256) -> TwapStatus {
+        let filled_amount = self
+            .instruction
+            .slices
+            .iter()
+            .zip(&self.slices)
+            .filter(|(_, state)| **state == SliceState::Filled)
+            .map(|(slice, _)| slice.amount_in())
+            .sum();
+        let next_block = self
+            .instruction
+            .slices
+            .iter()
+            .zip(&self.slices)
+            .find(|(_, state)| **state == SliceState::Pending)
+            .and_then(|(slice, _)| slice.flash_block())
+            .filter(|_| self.state == TwapState::Active);
+
+        TwapStatus {
+            id,
+            state: self.state,
+            total_slices: self.slices.len(),
+            filled_slices: self.count(SliceState::Filled),
+            missed_slices: self.count(SliceState::Missed),
+            filled_amount,
+            next_block
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TwapScheduler {
+    parents:         HashMap<B256, ParentOrder>,
+    /// child order hash to (parent id, slice index)
+    slice_to_parent: HashMap<B256, (B256, usize)>
+}
+
+impl TwapScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn submit(
+        &mut self,
+        instruction: TwapInstruction,
+        block_number: BlockNumber
+    ) -> Result<B256, TwapError> {
+        let first = instruction.slices.first().ok_or(TwapError::Empty)?;
+        if instruction.slices.iter().any(|slice| {
+            slice.from() != first.from()
+                || slice.token_in() != first.token_in()
+                || slice.token_out() != first.token_out()
+        }) {
+            return Err(TwapError::MismatchedSlices)
+        }
+
+        let mut last_block = block_number;
+        for slice in &instruction.slices {
+            match slice.flash_block() {
+                Some(block) if block > last_block => last_block = block,
+                _ => return Err(TwapError::InvalidSchedule)
+            }
+        }
+
+        if let Some(i) = instruction
+            .slices
+            .iter()
+            .position(|slice| !slice.is_valid_signature())
+        {
+            return Err(TwapError::InvalidSignature(i))
+        }
+
+        self.insert(instruction)
+    }
+
+    fn insert(&mut self, instruction: TwapInstruction) -> Result<B256, TwapError> {
+        let id = instruction.id();
+        if self.parents.contains_key(&id) {
+            return Err(TwapError::Duplicate)
+        }
+
+        instruction
+            .slices
+            .iter()
+            .enumerate()
+            .for_each(|(i, slice)| {
+                self.slice_to_parent.insert(slice.order_hash(), (id, i));
+            });
+        let slices = vec![SliceState::Pending; instruction.slices.len()];
+        self.parents.insert(
+            id,
+            ParentOrder { instruction, slices, state: TwapState::Active, finished_at: None }
+        );
+
+        Ok(id)
+    }
+
+    pub fn status(&self, id: &B256) -> Option<TwapStatus> {
+        self.parents.get(id).map(|parent| parent.status(*id))
+    }
+
+    pub fn on_filled(&mut self, order_hash: &B256) {
+        let Some((id, i)) = self.slice_to_parent.get(order_hash) else { return };
+        if let Some(parent) = self.parents.get_mut(id) {
+            parent.slices[*i] = SliceState::Filled;
+        }
+    }
+
+    /// Marks the submitted slices of past blocks that didn't fill as missed,
+    /// cancels parents that missed too many and returns the children that
+    /// should be submitted for the new block. Parents that finished more than
+    /// [`FINISHED_TWAP_RETENTION_BLOCKS`] ago are dropped.
+    pub fn on_new_block(&mut self, block_number: BlockNumber) -> Vec<AllOrders> {
+        self.parents.retain(|_, parent| {
+            parent.finished_at.map_or(true, |finished_at| {
+                block_number < finished_at + FINISHED_TWAP_RETENTION_BLOCKS
+            })
+        });
+
+        let mut to_submit = vec![];
+        let mut finished = false;
+        for (id, parent) in self.parents.iter_mut() {
+            if parent.state != TwapState::Active {
+                continue
+            }
+
+            for (slice, state) in parent.instruction.slices.iter().zip(&mut parent.slices) {
+                let slice_block = slice.flash_block().unwrap_or_default();
+                match *state {
+                    SliceState::Submitted if slice_block < block_number => {
+                        *state = SliceState::Missed
+                    }
+                    // the node missed the slot of the slice
+                    SliceState::Pending if slice_block < block_number => {
+                        *state = SliceState::Missed
+                    }
+                    _ => {}
+                }
+            }
+
+            if parent.count(SliceState::Missed) > parent.instruction.max_missed_slices as usize {
+                tracing::info!(?id, "cancelling twap after too many unfilled slices");
+                parent.state = TwapState::Cancelled;
+                parent.finished_at = Some(block_number);
+                finished = true;
+                continue
+            }
+
+            for (slice, state) in parent.instruction.slices.iter().zip(&mut parent.slices) {
+                if *state == SliceState::Pending && slice.flash_block() == Some(block_number) {
+                    *state = SliceState::Submitted;
+                    to_submit.push(AllOrders::Flash(slice.clone()));
+                }
+            }
+
+            if !parent
+                .slices
+                .iter()
+                .any(|s| matches!(s, SliceState::Pending | SliceState::Submitted))
+            {
+                parent.state = TwapState::Completed;
+                parent.finished_at = Some(block_number);
+                finished = true;
+            }
+        }
+
+        // finished parents have no slices left that could fill
+        if finished {
+            self.slice_to_parent.retain(|_, (id, _)| {
+                self.parents
+                    .get(id)
+                    .is_some_and(|parent| parent.state == TwapState::Active)
+            });
+        }
+
+        to_submit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+    use angstrom_types::sol_bindings::rpc_orders::ExactFlashOrder;
+
+    use super::*;
+
+    fn slice(block: BlockNumber, amount: u128) -> FlashVariants {
+        FlashVariants::Exact(ExactFlashOrder { validForBlock: block, amount, ..Default::default() })
+    }
+
+    fn instruction(blocks: &[BlockNumber], max_missed_slices: u32) -> TwapInstruction {
+        let slices = blocks.iter().map(|block| slice(*block, 100)).collect();
+        TwapInstruction { slices, max_missed_slices }
+    }
+
+    #[test]
+    fn rejects_malformed_instructions() {
+        let mut scheduler = TwapScheduler::new();
+        assert_eq!(scheduler.submit(instruction(&[], 0), 10), Err(TwapError::Empty));
+        assert_eq!(
+            scheduler.submit(instruction(&[10, 11], 0), 10),
+            Err(TwapError::InvalidSchedule)
+        );
+        assert_eq!(
+            scheduler.submit(instruction(&[12, 11], 0), 10),
+            Err(TwapError::InvalidSchedule)
+        );
+
+        let FlashVariants::Exact(mut other_pair) = slice(12, 100) else { unreachable!() };
+        other_pair.assetOut = Address::with_last_byte(1);
+        let slices = vec![slice(11, 100), FlashVariants::Exact(other_pair)];
+        assert_eq!(
+            scheduler.submit(TwapInstruction { slices, max_missed_slices: 0 }, 10),
+            Err(TwapError::MismatchedSlices)
+        );
+    }
+
+    #[test]
+    fn submits_a_slice_per_block_until_completed() {
+        let mut scheduler = TwapScheduler::new();
+        let twap = instruction(&[11, 12], 0);
+        let hashes = twap
+            .slices
+            .iter()
+            .map(|slice| slice.order_hash())
+            .collect::<Vec<_>>();
+        let id = scheduler.insert(twap.clone()).unwrap();
+        assert_eq!(scheduler.insert(twap), Err(TwapError::Duplicate));
+
+        for (block, hash) in [11, 12].into_iter().zip(&hashes) {
+            let submitted = scheduler.on_new_block(block);
+            assert_eq!(submitted.len(), 1);
+            assert_eq!(submitted[0].order_hash(), *hash);
+            scheduler.on_filled(hash);
+        }
+        assert!(scheduler.on_new_block(13).is_empty());
+
+        let status = scheduler.status(&id).unwrap();
+        assert_eq!(status.state, TwapState::Completed);
+        assert_eq!((status.filled_slices, status.filled_amount), (2, 200));
+        assert_eq!(status.next_block, None);
+        assert!(scheduler.slice_to_parent.is_empty());
+    }
+
+    #[test]
+    fn cancels_after_too_many_missed_slices() {
+        let mut scheduler = TwapScheduler::new();
+        let id = scheduler.insert(instruction(&[11, 12, 13], 0)).unwrap();
+
+        assert_eq!(scheduler.on_new_block(11).len(), 1);
+        // the slice of block 11 never filled
+        assert!(scheduler.on_new_block(12).is_empty());
+
+        let status = scheduler.status(&id).unwrap();
+        assert_eq!(status.state, TwapState::Cancelled);
+        assert_eq!(status.missed_slices, 1);
+        assert!(scheduler.slice_to_parent.is_empty());
+        assert!(scheduler.on_new_block(13).is_empty());
+    }
+
+    #[test]
+    fn drops_finished_parents_after_the_retention() {
+        let mut scheduler = TwapScheduler::new();
+        let id = scheduler.insert(instruction(&[11], 0)).unwrap();
+        scheduler.on_new_block(11);
+        // missed, so cancelled at block 12
+        scheduler.on_new_block(12);
+
+        scheduler.on_new_block(12 + FINISHED_TWAP_RETENTION_BLOCKS - 1);
+        assert!(scheduler.status(&id).is_some());
+        scheduler.on_new_block(12 + FINISHED_TWAP_RETENTION_BLOCKS);
+        assert!(scheduler.status(&id).is_none());
+        assert!(scheduler.parents.is_empty());
+    }
+}
@@ -8,7 +8,7 @@ use alloy::primitives::{Address, B256};
 use angstrom_types::{orders::OrderOrigin, sol_bindings::grouped_orders::AllOrders};
 use futures_util::{stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt};
 use tracing::info;
-use validation::order::{OrderValidationResults, OrderValidatorHandle};
+use validation::order::{OrderValidationResults, OrderValidatorHandle, ReorgFuture};
 
 type ValidationFuture = Pin<Box<dyn Future<Output = OrderValidationResults> + Send + Sync>>;
 
@@ -87,6 +87,30 @@ where
         }
     }
 
+    /// Releases the state the validator holds for orders that were evicted
+    /// between blocks. This doesn't depend on the block transition so it can
+    /// be sent in any state.
+    pub fn expire_orders(&self, orders: Vec<B256>) {
+        let (Self::ClearingForNewBlock { validator, .. }
+        | Self::WaitingForStorageCleanup { validator, .. }
+        | Self::InformState { validator, .. }
+        | Self::RegularProcessing { validator, .. }) = self;
+
+        validator.expire_orders(orders);
+    }
+
+    /// Has the validator re-check the users affected by a reorg. Like expiries
+    /// this can be sent in any state.
+    pub fn chain_reorg(&self, block_number: u64, addresses: Vec<Address>) -> ReorgFuture<'static> {
+        let (Self::ClearingForNewBlock { validator, .. }
+        | Self::WaitingForStorageCleanup { validator, .. }
+        | Self::InformState { validator, .. }
+        | Self::RegularProcessing { validator, .. }) = self;
+
+        let validator = validator.clone();
+        Box::pin(async move { validator.chain_reorg(block_number, addresses).await })
+    }
+
     pub fn notify_validation_on_changes(
         &mut self,
         block_number: u64,
@@ -130,6 +154,27 @@ where
         }
     }
 
+    /// Validates the orders in a single batch. The batch is split back into
+    /// the result of each order, so they are handled like any other order.
+    pub fn validate_orders(&mut self, orders: Vec<(OrderOrigin, AllOrders)>) {
+        let Self::RegularProcessing { remaining_futures, validator } = self else {
+            orders
+                .into_iter()
+                .for_each(|(origin, order)| self.validate_order(origin, order));
+            return
+        };
+
+        let count = orders.len();
+        let val = validator.clone();
+        let batch = async move { val.validate_orders(orders).await }
+            .boxed()
+            .shared();
+        for i in 0..count {
+            let batch = batch.clone();
+            remaining_futures.push(Box::pin(async move { batch.await[i].clone() }));
+        }
+    }
+
     fn is_transitioning(&self) -> bool {
         matches!(self, Self::ClearingForNewBlock { .. } | Self::InformState { .. })
     }
@@ -113,6 +113,9 @@ fn build_struct_impl(name: &Ident, generics: &Generics, s: &DataStruct) -> Token
                   <#tys as pade::PadeEncode>::PADE_VARIANT_MAP_BITS;
               )*
              let bitmap_bytes = bitmap_bits.div_ceil(8);
+              if buf.len() < bitmap_bytes {
+                  return Err(())
+              }
               let mut bitmap = pade::bitvec::vec::BitVec::<u8, pade::bitvec::order::Msb0>::from_slice(&buf[0..bitmap_bytes]);
               bitmap = bitmap.split_off(bitmap_bytes * 8 - bitmap_bits);
               *buf = &buf[bitmap_bytes..];
@@ -206,11 +209,14 @@ fn build_enum_impl(name: &Ident, generics: &Generics, e: &DataEnum) -> TokenStre
                 Self: Sized
             {
                 // the variant will either be the first byte or passed in
-                let variant = var.unwrap_or_else(|| {
-                    let ch = buf[0];
-                    *buf = &buf[1..];
-                    ch
-                });
+                let variant = match var {
+                    Some(variant) => variant,
+                    None => {
+                        let Some(ch) = buf.first().copied() else { return Err(()) };
+                        *buf = &buf[1..];
+                        ch
+                    }
+                };
 
                 match variant {
                     #(#branches)*
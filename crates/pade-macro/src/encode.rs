@@ -86,9 +86,47 @@ fn build_struct_impl(name: &Ident, generics: &Generics, s: &DataStruct) -> Token
         })
         .collect();
 
+    // Variant maps are hoisted into the header so each field is counted without
+    // its own
+    let field_sizes: Vec<TokenStream> = field_list
+        .iter()
+        .enumerate()
+        .map(|(idx, f)| {
+            let name = f
+                .ident
+                .as_ref()
+                .map(|i| quote! { self.#i })
+                .unwrap_or_else(|| {
+                    let index = Index::from(idx);
+                    quote! { self.#index }
+                });
+            let size = f
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("pade_width"))
+                .and_then(|attr| attr.parse_args::<Literal>().ok())
+                .map(|w| quote! { #w })
+                .unwrap_or_else(|| {
+                    quote! { #name.pade_size_hint() - #name.pade_variant_map_bits().div_ceil(8) }
+                });
+            quote! {
+                variant_map_bits += #name.pade_variant_map_bits();
+                size += #size;
+            }
+        })
+        .collect();
+
     quote! {
         #[automatically_derived]
         impl #impl_gen pade::PadeEncode for #name #ty_gen #where_clause {
+            fn pade_size_hint(&self) -> usize {
+                let mut variant_map_bits = 0usize;
+                let mut size = 0usize;
+                #(#field_sizes)*
+
+                variant_map_bits.div_ceil(8) + size
+            }
+
             fn pade_encode(&self) -> Vec<u8> {
                 let mut headers = pade::bitvec::vec::BitVec::<u8, pade::bitvec::order::Msb0>::new();
                 let mut output: Vec<u8> = Vec::new();
@@ -124,6 +162,34 @@ fn build_enum_impl(name: &Ident, generics: &Generics, e: &DataEnum) -> TokenStre
     let variant_bits = (variant_count.ilog2() + 1) as usize;
     let variant_bytes = variant_bits.div_ceil(8);
     // Each variant gets a clause in the match
+    let size_clauses = e.variants.iter().map(|v| {
+        let name = &v.ident;
+        match v.fields {
+            Fields::Named(ref fields) => {
+                let field_names: Vec<&Ident> = fields
+                    .named
+                    .iter()
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect();
+                quote! {
+                    Self::#name { #(#field_names),* } => {
+                        #variant_bytes #(+ pade::PadeEncode::pade_size_hint(#field_names))*
+                    }
+                }
+            }
+            Fields::Unnamed(ref fields) => {
+                let field_names: Vec<Ident> = (0..fields.unnamed.len())
+                    .map(|i| format_ident!("field_{}", Index::from(i)))
+                    .collect();
+                quote! {
+                    Self::#name(#(#field_names),*) => {
+                        #variant_bytes #(+ pade::PadeEncode::pade_size_hint(#field_names))*
+                    }
+                }
+            }
+            Fields::Unit => quote! { Self::#name => #variant_bytes }
+        }
+    });
     let clauses = e.variants.iter().enumerate().map(|(i, v)| {
         let raw_number = number_to_literals(i, variant_bytes);
         let number_encoder = std::iter::once(quote! {
@@ -173,6 +239,13 @@ fn build_enum_impl(name: &Ident, generics: &Generics, e: &DataEnum) -> TokenStre
         #[automatically_derived]
         impl #impl_gen pade::PadeEncode for #name #ty_gen #where_clause {
             const PADE_VARIANT_MAP_BITS: usize = #variant_bits;
+
+            fn pade_size_hint(&self) -> usize {
+                match self {
+                    #(#size_clauses),*
+                }
+            }
+
             fn pade_encode(&self) -> Vec<u8> {
                 match self {
                     #(#clauses),*
@@ -145,3 +145,40 @@ fn bool_ordering_lower() {
 
     assert_eq!(outer, decoded);
 }
+
+#[test]
+fn supports_struct_with_options() {
+    #[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+    struct WithOptions {
+        flag:    bool,
+        #[pade_width(5)]
+        value:   u64,
+        present: Option<u128>,
+        missing: Option<u128>,
+        nested:  Option<Inner>,
+        list:    Vec<Inner>
+    }
+
+    #[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+    struct Inner {
+        flag:  bool,
+        value: Option<u16>
+    }
+
+    let outer = WithOptions {
+        flag:    true,
+        value:   1234,
+        present: Some(100),
+        missing: None,
+        nested:  Some(Inner { flag: false, value: Some(7) }),
+        list:    vec![Inner { flag: true, value: None }, Inner { flag: false, value: Some(3) }]
+    };
+
+    let encoded = outer.pade_encode();
+    assert_eq!(outer.pade_size_hint(), encoded.len());
+
+    let mut slice = encoded.as_slice();
+    let decoded = WithOptions::pade_decode(&mut slice, None).unwrap();
+    assert_eq!(outer, decoded);
+    assert!(slice.is_empty());
+}
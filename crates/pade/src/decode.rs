@@ -39,38 +39,41 @@ impl<T: PadeDecode + Debug, const N: usize> PadeDecode for [T; N] {
 // Option<T: PadeEncode> encodes as an enum
 impl<T: PadeDecode> PadeDecode for Option<T> {
     fn pade_decode(buf: &mut &[u8], var: Option<u8>) -> Result<Self, ()> {
-        if buf.is_empty() {
-            return Err(())
-        }
-        // check first byte;
-        let ctr = buf[0] != 0;
-        // progress buffer
-        *buf = &buf[1..];
-
-        if ctr {
-            Ok(Some(T::pade_decode(buf, var)?))
+        if option_variant(buf, var)? {
+            Ok(Some(T::pade_decode(buf, None)?))
         } else {
             Ok(None)
         }
     }
 
     fn pade_decode_with_width(buf: &mut &[u8], width: usize, var: Option<u8>) -> Result<Self, ()> {
-        if buf.is_empty() {
-            return Err(())
-        }
-        // check first byte;
-        let ctr = buf[0] != 0;
-        // progress buffer
-        *buf = &buf[1..];
-
-        if ctr {
-            Ok(Some(T::pade_decode_with_width(buf, width, var)?))
+        if option_variant(buf, var)? {
+            Ok(Some(T::pade_decode_with_width(buf, width, None)?))
         } else {
             Ok(None)
         }
     }
 }
 
+/// Whether the option is `Some`. When the option is a struct field its
+/// variant bit is hoisted into the struct's bitmap and passed in as `var`,
+/// otherwise it is the first byte of the buffer.
+fn option_variant(buf: &mut &[u8], var: Option<u8>) -> Result<bool, ()> {
+    if let Some(var) = var {
+        return Ok(var != 0)
+    }
+
+    if buf.is_empty() {
+        return Err(())
+    }
+    // check first byte;
+    let ctr = buf[0] != 0;
+    // progress buffer
+    *buf = &buf[1..];
+
+    Ok(ctr)
+}
+
 impl PadeDecode for bool {
     fn pade_decode(buf: &mut &[u8], var: Option<u8>) -> Result<Self, ()> {
         if let Some(var) = var {
@@ -96,22 +99,13 @@ impl PadeDecode for bool {
 // don't want to hoist them in a struct
 impl<T: PadeDecode> PadeDecode for Vec<T> {
     fn pade_decode(buf: &mut &[u8], var: Option<u8>) -> Result<Self, ()> {
-        if buf.len() < 3 {
-            return Err(())
-        }
-        // read vec length.
-        let length = &buf[0..3];
-        let length = usize::from_be_bytes([0, 0, 0, 0, 0, length[0], length[1], length[2]]);
-
-        // progress buf pass offset
-        *buf = &buf[3..];
+        let length = list_length(buf)?;
         // capture length to ensure we don't over decode.
         let mut decode_slice = &buf[0..length];
         let mut res = Vec::new();
-        while let Ok(d) = T::pade_decode(&mut decode_slice, var) {
-            res.push(d);
+        while !decode_slice.is_empty() {
+            res.push(T::pade_decode(&mut decode_slice, var)?);
         }
-        assert!(decode_slice.is_empty());
 
         // progress
         *buf = &buf[length..];
@@ -120,18 +114,14 @@ impl<T: PadeDecode> PadeDecode for Vec<T> {
     }
 
     fn pade_decode_with_width(buf: &mut &[u8], width: usize, var: Option<u8>) -> Result<Self, ()> {
-        if buf.len() < 3 {
+        let length = list_length(buf)?;
+        // the length is in bytes, every item takes up exactly `width` of them
+        if width == 0 || length % width != 0 {
             return Err(())
         }
-        // read vec length.
-        let length = &buf[0..3];
-        let length = usize::from_be_bytes([0, 0, 0, 0, 0, length[0], length[1], length[2]]);
-
-        // progress buf
-        *buf = &buf[3..];
 
-        let mut res = Vec::with_capacity(length);
-        for _ in 0..length {
+        let mut res = Vec::with_capacity(length / width);
+        for _ in 0..length / width {
             res.push(T::pade_decode_with_width(buf, width, var)?);
         }
 
@@ -139,6 +129,25 @@ impl<T: PadeDecode> PadeDecode for Vec<T> {
     }
 }
 
+/// Reads the 3 byte length header of a list and makes sure the buffer holds
+/// that many bytes after it.
+fn list_length(buf: &mut &[u8]) -> Result<usize, ()> {
+    if buf.len() < 3 {
+        return Err(())
+    }
+    // read vec length.
+    let length = &buf[0..3];
+    let length = usize::from_be_bytes([0, 0, 0, 0, 0, length[0], length[1], length[2]]);
+
+    // progress buf pass offset
+    *buf = &buf[3..];
+    if buf.len() < length {
+        return Err(())
+    }
+
+    Ok(length)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -167,4 +176,37 @@ mod tests {
         let decoded: Vec<u128> = super::PadeDecode::pade_decode(&mut slice, None).unwrap();
         assert_eq!(vec, decoded);
     }
+
+    #[test]
+    fn can_encode_decode_vec_with_width() {
+        let vec = vec![100_u128, 300_u128, 256_u128];
+        let bytes = vec.pade_encode_with_width(4);
+        let mut slice = bytes.as_slice();
+
+        let decoded: Vec<u128> =
+            super::PadeDecode::pade_decode_with_width(&mut slice, 4, None).unwrap();
+        assert_eq!(vec, decoded);
+        assert!(slice.is_empty());
+    }
+
+    #[test]
+    fn hoisted_option_uses_passed_variant() {
+        let bytes = 100_u128.pade_encode();
+
+        let decoded: Option<u128> =
+            super::PadeDecode::pade_decode(&mut bytes.as_slice(), Some(1)).unwrap();
+        assert_eq!(decoded, Some(100));
+
+        let decoded: Option<u128> =
+            super::PadeDecode::pade_decode(&mut bytes.as_slice(), Some(0)).unwrap();
+        assert_eq!(decoded, None);
+    }
+
+    #[test]
+    fn truncated_vec_is_an_error() {
+        let bytes = vec![100_u128, 300_u128].pade_encode();
+        let mut slice = &bytes[..bytes.len() - 1];
+
+        assert!(<Vec<u128> as super::PadeDecode>::pade_decode(&mut slice, None).is_err());
+    }
 }
@@ -4,6 +4,12 @@ pub trait PadeEncode {
 
     fn pade_encode(&self) -> Vec<u8>;
 
+    /// The number of bytes `pade_encode` produces, so callers can preallocate
+    /// their buffers without encoding twice
+    fn pade_size_hint(&self) -> usize {
+        self.pade_encode().len()
+    }
+
     fn pade_encode_with_width(&self, width: usize) -> Vec<u8> {
         let bytes = self.pade_encode();
         let encoded_len = bytes.len();
@@ -39,6 +45,10 @@ impl<T: PadeEncode, const N: usize> PadeEncode for [T; N] {
         self.iter().flat_map(|item| item.pade_encode()).collect()
     }
 
+    fn pade_size_hint(&self) -> usize {
+        self.iter().map(PadeEncode::pade_size_hint).sum()
+    }
+
     fn pade_encode_with_width(&self, width: usize) -> Vec<u8> {
         self.iter()
             .flat_map(|item| item.pade_encode_with_width(width))
@@ -57,6 +67,10 @@ impl<T: PadeEncode> PadeEncode for Option<T> {
         }
     }
 
+    fn pade_size_hint(&self) -> usize {
+        1 + self.as_ref().map_or(0, PadeEncode::pade_size_hint)
+    }
+
     fn pade_encode_with_width(&self, width: usize) -> Vec<u8> {
         match self {
             Some(v) => std::iter::once(1_u8)
@@ -77,6 +91,10 @@ impl PadeEncode for bool {
             false => vec![0_u8]
         }
     }
+
+    fn pade_size_hint(&self) -> usize {
+        1
+    }
 }
 // Decided on a generic List<3> implementation - no variant bits because we
 // don't want to hoist them in a struct
@@ -91,6 +109,10 @@ impl<T: PadeEncode> PadeEncode for Vec<T> {
         [len, items].concat()
     }
 
+    fn pade_size_hint(&self) -> usize {
+        3 + self.iter().map(PadeEncode::pade_size_hint).sum::<usize>()
+    }
+
     fn pade_encode_with_width(&self, width: usize) -> Vec<u8> {
         let items: Vec<u8> = self
             .iter()
@@ -121,4 +143,13 @@ mod tests {
         assert!(vec.pade_header_bits() == 24);
         assert!(vec.pade_variant_map_bits() == 0);
     }
+
+    #[test]
+    fn size_hint_matches_encoding() {
+        let vec = vec![Some(100_u128), None, Some(256_u128)];
+        assert_eq!(vec.pade_size_hint(), vec.pade_encode().len());
+
+        let array = [true, false];
+        assert_eq!(array.pade_size_hint(), array.pade_encode().len());
+    }
 }
@@ -10,12 +10,16 @@ use crate::{PadeDecode, PadeEncode};
 /// standard encoding for them.  This macro is only meant to run here, so we
 /// don't have to worry about it being externally sound
 macro_rules! use_alloy_default {
-    ($( $x:ty ), *) => {
+    ($( $x:ty: $bytes:literal ), *) => {
         $(
             impl PadeEncode for $x {
                 fn pade_encode(&self) -> Vec<u8> {
                     self.abi_encode_packed()
                 }
+
+                fn pade_size_hint(&self) -> usize {
+                    $bytes
+                }
             }
         )*
     };
@@ -45,6 +49,9 @@ macro_rules! prim_decode {
                     Self: Sized
                 {
                     const BYTES: usize  = <$x>::BITS as usize / 8usize;
+                    if size > BYTES || buf.len() < size {
+                        return Err(())
+                    }
 
                     // item size in bytes vs given rep.
                     let padding_offset = BYTES - size;
@@ -73,12 +80,16 @@ macro_rules! prim_decode {
 }
 
 prim_decode!(u8, u16, u64, i32, I24, U256, u128);
-use_alloy_default!(u16, u64, i32, I24, U256, u128, Address);
+use_alloy_default!(u16: 2, u64: 8, i32: 4, I24: 3, U256: 32, u128: 16, Address: 20);
 
 impl PadeEncode for u8 {
     fn pade_encode(&self) -> Vec<u8> {
         vec![*self]
     }
+
+    fn pade_size_hint(&self) -> usize {
+        1
+    }
 }
 
 impl PadeDecode for Address {
@@ -102,6 +113,9 @@ impl PadeDecode for Address {
         Self: Sized
     {
         const BYTES: usize = 160 / 8usize;
+        if size < BYTES || buf.len() < size {
+            return Err(())
+        }
         // grab the padding amount
         let offset = size - BYTES;
         let subslice = &buf[offset..size];
@@ -143,6 +157,10 @@ impl PadeEncode for Bytes {
 
         [vec![len[5], len[6], len[7]], bytes].concat()
     }
+
+    fn pade_size_hint(&self) -> usize {
+        3 + self.len()
+    }
 }
 
 // Custom impl for Signature which needs validation
@@ -157,6 +175,10 @@ impl PadeEncode for Signature {
         sig[33..65].copy_from_slice(&self.s().to_be_bytes::<32>());
         sig.to_vec()
     }
+
+    fn pade_size_hint(&self) -> usize {
+        65
+    }
 }
 
 impl PadeDecode for Signature {
@@ -164,8 +186,12 @@ impl PadeDecode for Signature {
     where
         Self: Sized
     {
+        if buf.len() < 65 {
+            return Err(())
+        }
         let bytes = &buf[0..65];
-        let v = bytes[0];
+        // the parity is encoded as 27 or 28
+        let v = bytes[0].checked_sub(27).unwrap_or(bytes[0]);
         let r = U256::from_be_slice(&bytes[1..33]);
         let s = U256::from_be_slice(&bytes[33..65]);
 
@@ -184,11 +210,38 @@ impl PadeDecode for Signature {
 
 #[cfg(test)]
 mod tests {
-    use crate::PadeEncode;
+    use alloy::primitives::{aliases::I24, Address, Bytes, Signature, U256};
+
+    use crate::{PadeDecode, PadeEncode};
 
     #[test]
     fn implemented_pade() {
         let tim = 128_u128;
         println!("{:?}", tim.pade_header_bits());
     }
+
+    #[test]
+    fn size_hint_matches_encoding() {
+        assert_eq!(I24::MINUS_ONE.pade_size_hint(), I24::MINUS_ONE.pade_encode().len());
+        assert_eq!(U256::MAX.pade_size_hint(), U256::MAX.pade_encode().len());
+        assert_eq!(Address::ZERO.pade_size_hint(), Address::ZERO.pade_encode().len());
+
+        let bytes = Bytes::from_static(&[1, 2, 3]);
+        assert_eq!(bytes.pade_size_hint(), bytes.pade_encode().len());
+    }
+
+    #[test]
+    fn signature_round_trips() {
+        for parity in [false, true] {
+            let sig = Signature::new(
+                U256::from(1),
+                U256::from(2),
+                alloy::primitives::Parity::Parity(parity)
+            );
+            let bytes = sig.pade_encode();
+
+            let decoded = Signature::pade_decode(&mut bytes.as_slice(), None).unwrap();
+            assert_eq!(decoded, sig);
+        }
+    }
 }
@@ -0,0 +1,229 @@
+//! Golden encodings for every primitive and macro feature. The settlement
+//! contract decodes these bytes, so any change to them is a breaking change
+//! to the wire format.
+
+use std::{collections::BTreeMap, fmt::Debug, path::PathBuf};
+
+use alloy::{
+    hex,
+    primitives::{aliases::I24, Address, Bytes, Parity, Signature, U256}
+};
+use pade::{PadeDecode, PadeEncode};
+use pade_macro::{PadeDecode, PadeEncode};
+
+const GOLDEN_FILE: &str = "tests/golden/encodings.txt";
+/// Set to rewrite the golden file from the current encodings.
+const UPDATE_ENV: &str = "PADE_UPDATE_GOLDEN";
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct Named {
+    a: u16,
+    b: u64
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct Unnamed(u16, u16);
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct Bools {
+    a: bool,
+    b: bool,
+    c: u16
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct NineBools {
+    b1: bool,
+    b2: bool,
+    b3: bool,
+    b4: bool,
+    b5: bool,
+    b6: bool,
+    b7: bool,
+    b8: bool,
+    b9: bool
+}
+
+impl NineBools {
+    fn only(idx: usize) -> Self {
+        let b = |i| i == idx;
+        Self {
+            b1: b(1),
+            b2: b(2),
+            b3: b(3),
+            b4: b(4),
+            b5: b(5),
+            b6: b(6),
+            b7: b(7),
+            b8: b(8),
+            b9: b(9)
+        }
+    }
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+enum Choice {
+    A,
+    B(u16)
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+enum Shape {
+    Point { x: u16 },
+    Line { a: u16, b: u16 }
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct WithEnum {
+    flag:   bool,
+    choice: Choice,
+    value:  u16
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct WithOptions {
+    a: Option<u16>,
+    b: Option<u16>
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct WithWidth {
+    #[pade_width(5)]
+    deadline: u64
+}
+
+#[derive(PadeEncode, PadeDecode, PartialEq, Eq, Debug)]
+struct Nested {
+    inner: Named,
+    list:  Vec<Named>
+}
+
+#[derive(Default)]
+struct Encodings(BTreeMap<&'static str, String>);
+
+impl Encodings {
+    /// Records the encoding of the value, making sure it decodes back to it.
+    fn add<T: PadeDecode + PartialEq + Debug>(&mut self, name: &'static str, value: T) {
+        let encoded = value.pade_encode();
+        assert_eq!(value.pade_size_hint(), encoded.len(), "{name}: wrong size hint");
+
+        let mut slice = encoded.as_slice();
+        let decoded = T::pade_decode(&mut slice, None).unwrap();
+        assert_eq!(decoded, value, "{name}: didn't round trip");
+        assert!(slice.is_empty(), "{name}: trailing bytes");
+
+        self.insert(name, encoded);
+    }
+
+    fn add_with_width<T: PadeDecode + PartialEq + Debug>(
+        &mut self,
+        name: &'static str,
+        value: T,
+        width: usize
+    ) {
+        let encoded = value.pade_encode_with_width(width);
+
+        let mut slice = encoded.as_slice();
+        let decoded = T::pade_decode_with_width(&mut slice, width, None).unwrap();
+        assert_eq!(decoded, value, "{name}: didn't round trip");
+        assert!(slice.is_empty(), "{name}: trailing bytes");
+
+        self.insert(name, encoded);
+    }
+
+    fn insert(&mut self, name: &'static str, encoded: Vec<u8>) {
+        assert!(self.0.insert(name, hex::encode(encoded)).is_none(), "{name}: duplicate case");
+    }
+}
+
+fn primitives(cases: &mut Encodings) {
+    cases.add("u8", 0xab_u8);
+    cases.add("u16", 0x1234_u16);
+    cases.add("u64", 0x0102030405060708_u64);
+    cases.add("i32_negative", -2_i32);
+    cases.add("i24_negative", I24::try_from(-2_i32).unwrap());
+    cases.add("u128", 1_u128);
+    cases.add("u256", U256::from(1));
+    cases.add("address", Address::with_last_byte(0xaa));
+    cases.add("bool_true", true);
+    cases.add("bool_false", false);
+    cases.add("option_some", Some(0x0102_u16));
+    cases.add("option_none", None::<u16>);
+    cases.add("vec", vec![1_u16, 2]);
+    cases.add("vec_empty", Vec::<u16>::new());
+    cases.add("bytes", Bytes::from_static(&[0xde, 0xad]));
+    cases.add("array", [1_u16, 2]);
+    cases.add("signature", Signature::new(U256::from(1), U256::from(2), Parity::Parity(false)));
+    cases.add_with_width("width_u64", 0x0102030405_u64, 5);
+    cases.add_with_width("width_i32", 123_i32, 3);
+}
+
+fn macro_features(cases: &mut Encodings) {
+    cases.add("struct_named", Named { a: 1, b: 2 });
+    cases.add("struct_unnamed", Unnamed(0x0a, 0x0b));
+    cases.add("struct_bools_first", Bools { a: true, b: false, c: 1 });
+    cases.add("struct_bools_second", Bools { a: false, b: true, c: 1 });
+    cases.add("struct_nine_bools_first", NineBools::only(1));
+    cases.add("struct_nine_bools_last", NineBools::only(9));
+    cases.add("struct_with_enum", WithEnum { flag: true, choice: Choice::B(5), value: 7 });
+    cases.add("struct_with_options", WithOptions { a: Some(0x0102), b: None });
+    cases.add("struct_with_width", WithWidth { deadline: 0x0102030405 });
+    cases.add(
+        "struct_nested",
+        Nested { inner: Named { a: 1, b: 2 }, list: vec![Named { a: 3, b: 4 }] }
+    );
+    cases.add("enum_unit", Choice::A);
+    cases.add("enum_unnamed", Choice::B(5));
+    cases.add("enum_named", Shape::Line { a: 1, b: 2 });
+}
+
+fn golden_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_FILE)
+}
+
+fn parse_golden(file: &str) -> BTreeMap<String, String> {
+    file.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, hex) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("malformed golden line: {line}"));
+            (name.to_string(), hex.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn encodings_match_golden_file() {
+    let mut cases = Encodings::default();
+    primitives(&mut cases);
+    macro_features(&mut cases);
+
+    let file = std::fs::read_to_string(golden_path()).unwrap();
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        let header = file
+            .lines()
+            .take_while(|line| line.starts_with('#'))
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        let body = cases
+            .0
+            .iter()
+            .map(|(name, hex)| format!("{name} {hex}\n"))
+            .collect::<String>();
+        std::fs::write(golden_path(), header + &body).unwrap();
+        return
+    }
+
+    let golden = parse_golden(&file);
+    for (name, hex) in &cases.0 {
+        match golden.get(*name) {
+            Some(expected) => assert_eq!(hex, expected, "{name}: wire format changed"),
+            None => panic!("{name}: missing from {GOLDEN_FILE}")
+        }
+    }
+    for name in golden.keys() {
+        assert!(cases.0.contains_key(name.as_str()), "{name}: golden case is no longer encoded");
+    }
+}
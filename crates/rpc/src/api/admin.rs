@@ -0,0 +1,20 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use validation::order::state::config::{listing::ListingDecision, TokenSlots};
+
+/// Operator endpoints that change the configuration of the running node. These
+/// are only served over ipc.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstromAdmin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstromAdmin"))]
+#[async_trait::async_trait]
+pub trait AdminApi {
+    /// Starts validating orders for a token with the given balance and
+    /// approval slots, once they pass the probes against the latest state.
+    /// Registering a token that is already configured replaces its slots.
+    #[method(name = "registerTokenSlots")]
+    async fn register_token_slots(&self, slots: TokenSlots) -> RpcResult<bool>;
+
+    /// Whether each pool of the validation config passed the listing policy,
+    /// and why it didn't, as of the last time the config was loaded.
+    #[method(name = "listingDecisions")]
+    async fn listing_decisions(&self) -> RpcResult<Vec<ListingDecision>>;
+}
@@ -1,4 +1,4 @@
-use consensus::ConsensusState;
+use consensus::status::RoundStatus;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 use crate::types::subscriptions::ConsensusSubscriptionKind;
@@ -7,8 +7,10 @@ use crate::types::subscriptions::ConsensusSubscriptionKind;
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom_consensus"))]
 #[async_trait::async_trait]
 pub trait ConsensusApi {
+    /// The phase, leader, validator set and pre-proposals of the current
+    /// round.
     #[method(name = "current_state")]
-    async fn consensus_state(&self) -> RpcResult<ConsensusState>;
+    async fn consensus_state(&self) -> RpcResult<RoundStatus>;
 
     #[subscription(
         name = "consensus_state",
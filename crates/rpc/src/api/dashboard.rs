@@ -0,0 +1,16 @@
+use alloy_primitives::BlockNumber;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::BlockSummary;
+
+/// Read only views for node operator dashboards.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom"))]
+#[async_trait::async_trait]
+pub trait DashboardApi {
+    /// The order flow and consensus round of a recent block in one place.
+    /// `None` if the node has no record of the block, either because it
+    /// didn't see it or because it's too old to be kept.
+    #[method(name = "blockSummary")]
+    async fn block_summary(&self, block_number: BlockNumber) -> RpcResult<Option<BlockSummary>>;
+}
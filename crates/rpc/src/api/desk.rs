@@ -0,0 +1,20 @@
+use angstrom_types::sol_bindings::grouped_orders::AllOrders;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::{api::CancelOrderRequest, types::ApiKeyUsage};
+
+/// Order entry scoped to an api key. Every key has its own order rate quota
+/// and open order limit.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom_desk"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom_desk"))]
+#[async_trait::async_trait]
+pub trait DeskApi {
+    #[method(name = "sendOrder")]
+    async fn send_order(&self, api_key: String, order: AllOrders) -> RpcResult<bool>;
+
+    #[method(name = "cancelOrder")]
+    async fn cancel_order(&self, api_key: String, request: CancelOrderRequest) -> RpcResult<bool>;
+
+    #[method(name = "usage")]
+    async fn usage(&self, api_key: String) -> RpcResult<ApiKeyUsage>;
+}
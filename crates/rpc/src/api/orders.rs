@@ -1,25 +1,103 @@
-use alloy_primitives::B256;
+use alloy_primitives::{keccak256, Address, B256};
 use angstrom_types::{
-    primitive::Signature,
-    sol_bindings::rpc_orders::{
-        ExactFlashOrder, ExactStandingOrder, PartialFlashOrder, PartialStandingOrder,
-        TopOfBlockOrder
+    primitive::{PoolId, Signature},
+    sol_bindings::{
+        grouped_orders::{AllOrders, StandingVariants},
+        rpc_orders::{
+            ExactFlashOrder, ExactStandingOrder, PartialFlashOrder, PartialStandingOrder,
+            TopOfBlockOrder
+        }
     }
 };
 use jsonrpsee::{
     core::{RpcResult, Serialize},
     proc_macros::rpc
 };
+use order_pool::{
+    audit::OrderTrailEntry,
+    order_storage::{OrderCursor, OrderFilter, OrderPage},
+    surveillance::{OrderStatus, PoolActivity},
+    twap::{TwapInstruction, TwapStatus},
+    BookSnapshot
+};
 use serde::Deserialize;
 
 use crate::types::OrderSubscriptionKind;
 
+/// Submission envelope for standing orders that carries options which aren't
+/// part of the signed order.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StandingOrderEnvelope {
+    pub order:              StandingVariants,
+    /// keep the order in the book, renewing it every block, until it's
+    /// cancelled or its deadline passes
+    #[serde(default)]
+    pub good_til_cancelled: bool,
+    /// most gas the order may be charged, in the units of its estimated gas.
+    /// The order is parked while its estimated gas charge is above it
+    #[serde(default)]
+    pub max_gas:            Option<u128>
+}
+
+/// Outcome of a single order of a batch submission.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum OrderSubmissionStatus {
+    #[serde(rename_all = "camelCase")]
+    Accepted { order_hash: B256, estimated_gas: u128 },
+    /// `code` is the json-rpc error code the order would have been rejected
+    /// with if it was sent on its own
+    #[serde(rename_all = "camelCase")]
+    Rejected { order_hash: B256, code: i32, reason: String }
+}
+
+/// What validation would charge an order for gas.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderGasEstimate {
+    pub gas_units:    u128,
+    /// cost of the gas at the gas price orders are charged at
+    pub gas_wei:      u128,
+    pub token_in:     Address,
+    /// cost of the gas in `token_in`, 0 when the token can't be priced as it
+    /// isn't charged then
+    pub gas_in_token: u128
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CancelOrderRequest {
     pub signature: Signature,
     pub hash:      B256
 }
 
+/// Authorizes toggling the kill switch of an account. The signature is over
+/// [`AccountKillSwitchRequest::message`] and has to be from the account itself.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccountKillSwitchRequest {
+    pub address:   Address,
+    /// has to be higher than the nonce of the last accepted request of the
+    /// account, so a request can't be replayed
+    pub nonce:     u64,
+    /// unix timestamp (in seconds) of when the request was signed
+    pub timestamp: u64,
+    pub signature: Signature
+}
+
+impl AccountKillSwitchRequest {
+    /// keccak256(action || address || nonce || timestamp)
+    pub fn message(&self, disable: bool) -> B256 {
+        let action: &[u8] = if disable { b"disableAccount" } else { b"enableAccount" };
+        let mut buf = Vec::with_capacity(action.len() + 36);
+        buf.extend_from_slice(action);
+        buf.extend_from_slice(self.address.as_slice());
+        buf.extend_from_slice(&self.nonce.to_be_bytes());
+        buf.extend_from_slice(&self.timestamp.to_be_bytes());
+
+        keccak256(buf)
+    }
+}
+
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom"))]
 #[async_trait::async_trait]
@@ -31,6 +109,12 @@ pub trait OrderApi {
     #[method(name = "sendExactStandingOrder")]
     async fn send_exact_standing_order(&self, order: ExactStandingOrder) -> RpcResult<bool>;
 
+    /// Sends a standing order along with its submission options. An expiry
+    /// notification is sent to `ExpiredOrders` subscribers once a
+    /// good-til-cancelled order can no longer be renewed.
+    #[method(name = "sendStandingOrder")]
+    async fn send_standing_order(&self, envelope: StandingOrderEnvelope) -> RpcResult<bool>;
+
     #[method(name = "sendSearcherOrder")]
     async fn send_searcher_order(&self, order: TopOfBlockOrder) -> RpcResult<bool>;
 
@@ -40,13 +124,82 @@ pub trait OrderApi {
     #[method(name = "sendExactFlashOrder")]
     async fn send_exact_flash_order(&self, order: ExactFlashOrder) -> RpcResult<bool>;
 
+    /// Submits up to [`MAX_BATCH_ORDERS`] orders in one request. They are
+    /// validated as a single batch, and the status of each order is returned
+    /// in the order they were sent.
+    ///
+    /// [`MAX_BATCH_ORDERS`]: crate::impls::MAX_BATCH_ORDERS
+    #[method(name = "sendOrders")]
+    async fn send_orders(&self, orders: Vec<AllOrders>) -> RpcResult<Vec<OrderSubmissionStatus>>;
+
+    /// The gas validation would charge the order, in wei and in its input
+    /// token. The order isn't validated or added to the pool.
+    #[method(name = "estimateOrderGas")]
+    async fn estimate_order_gas(&self, order: AllOrders) -> RpcResult<OrderGasEstimate>;
+
     #[method(name = "cancelOrder")]
     async fn cancel_order(&self, request: CancelOrderRequest) -> RpcResult<bool>;
 
+    /// Parks all orders of the account and rejects new ones until the account
+    /// is enabled again.
+    #[method(name = "disableAccount")]
+    async fn disable_account(&self, request: AccountKillSwitchRequest) -> RpcResult<bool>;
+
+    #[method(name = "enableAccount")]
+    async fn enable_account(&self, request: AccountKillSwitchRequest) -> RpcResult<bool>;
+
+    /// Schedules the pre-signed slices of a TWAP instruction to be submitted
+    /// one per block, returning the id of the instruction.
+    #[method(name = "sendTwapOrder")]
+    async fn send_twap_order(&self, instruction: TwapInstruction) -> RpcResult<B256>;
+
+    #[method(name = "twapStatus")]
+    async fn twap_status(&self, id: B256) -> RpcResult<Option<TwapStatus>>;
+
+    /// Order book depth, order flow and searcher bid stats of the last block,
+    /// for all pools or a single one.
+    #[method(name = "poolActivity")]
+    async fn pool_activity(&self, pool_id: Option<PoolId>) -> RpcResult<Vec<PoolActivity>>;
+
+    /// Queue position at its price level, depth ahead of it and a naive fill
+    /// probability of a resting limit order. `None` if the order isn't
+    /// resting in the book.
+    #[method(name = "orderStatus")]
+    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatus>>;
+
+    /// Every recorded mutation of the order's storage data, oldest first, such
+    /// as it being parked, promoted or invalidated by another order. Kept for
+    /// a bounded number of recent orders, empty if the order is unknown.
+    #[method(name = "orderTrail")]
+    async fn order_trail(&self, order_hash: B256) -> RpcResult<Vec<OrderTrailEntry>>;
+
+    /// The resting orders of the pool along with the sequence number of the
+    /// last order update they include. Subscribers that lagged behind resync by
+    /// applying only the updates with a higher sequence number on top. `None`
+    /// if `seq` is already the latest sequence number.
+    #[method(name = "bookSnapshot")]
+    async fn book_snapshot(&self, pool_id: PoolId, seq: u64) -> RpcResult<Option<BookSnapshot>>;
+
+    /// Pages through the resting orders of all pools, a single pool or a single
+    /// signer, limit orders first, each ordered by price. Pass the `next`
+    /// cursor of a page to get the one after it. At most
+    /// [`MAX_ORDERS_PAGE_SIZE`] orders are returned per page. Pages are taken
+    /// from the book as of the start of the current block phase, the `version`
+    /// of a page changes when the next phase begins.
+    ///
+    /// [`MAX_ORDERS_PAGE_SIZE`]: crate::impls::MAX_ORDERS_PAGE_SIZE
+    #[method(name = "ordersPage")]
+    async fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> RpcResult<OrderPage>;
+
     #[subscription(
         name = "subscribeOrders",
         unsubscribe = "unsubscribeOrders",
-        item = crate::types::subscriptions::OrderSubscriptionResult
+        item = crate::types::subscriptions::SequencedOrderSubscriptionResult
     )]
     async fn subscribe_orders(
         &self,
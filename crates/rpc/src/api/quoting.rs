@@ -1,20 +1,48 @@
 use alloy_primitives::{Address, U256};
+use angstrom_types::primitive::PoolId;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-use crate::types::subscriptions::{QuotingSubscriptionKind, QuotingSubscriptionParam};
+use crate::types::{
+    subscriptions::{QuotingSubscriptionKind, QuotingSubscriptionParam},
+    BookDepth, SwapQuote, TobAuctionStatus
+};
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "quoting"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "quoting"))]
 #[async_trait::async_trait]
 pub trait QuotingApi {
+    /// Quotes swapping `amount_in` of `token_in`, filling from the resting
+    /// orders priced better than the pool first and swapping the rest
+    /// against the pool.
     #[method(name = "get_quote")]
     async fn quote_transaction(
         &self,
         token_in: Address,
         token_out: Address,
-        amount_in: U256,
-        amount_out: U256
-    ) -> RpcResult<U256>;
+        amount_in: U256
+    ) -> RpcResult<SwapQuote>;
+
+    /// Up to `levels` price levels on either side of the pool, aggregating the
+    /// resting limit orders at each price along with the liquidity of the pool
+    /// in between. At most [`MAX_DEPTH_LEVELS`] levels are returned per side.
+    ///
+    /// [`MAX_DEPTH_LEVELS`]: crate::impls::MAX_DEPTH_LEVELS
+    #[method(name = "order_book_depth")]
+    async fn order_book_depth(&self, pool_id: PoolId, levels: usize) -> RpcResult<BookDepth>;
+
+    /// The searcher order currently winning the top of block auction of the
+    /// pool, `None` while nobody bids.
+    #[method(name = "tob_auction_status")]
+    async fn tob_auction_status(&self, pool_id: PoolId) -> RpcResult<Option<TobAuctionStatus>>;
+
+    /// Sends the status of the top of block auction of the pool right away,
+    /// then every time it changes.
+    #[subscription(
+        name = "subscribe_tob_auction",
+        unsubscribe = "unsubscribe_tob_auction",
+        item = Option<crate::types::TobAuctionStatus>
+    )]
+    async fn subscribe_tob_auction(&self, pool_id: PoolId) -> jsonrpsee::core::SubscriptionResult;
 
     #[subscription(
         name = "subscribe_BBO", 
@@ -0,0 +1,95 @@
+use jsonrpsee::core::RpcResult;
+use validation::{
+    order::state::{
+        config::{listing::ListingDecision, TokenSlots},
+        db_state_utils::TokenSlotError
+    },
+    validator::ValidationClient
+};
+
+use super::{invalid_params_rpc_err, rpc_err};
+use crate::api::AdminApiServer;
+
+pub struct AdminApi {
+    validator: ValidationClient
+}
+
+impl AdminApi {
+    pub fn new(validator: ValidationClient) -> Self {
+        Self { validator }
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminApiServer for AdminApi {
+    async fn register_token_slots(&self, slots: TokenSlots) -> RpcResult<bool> {
+        match self.validator.register_token_slots(slots).await {
+            Ok(()) => Ok(true),
+            Err(e @ TokenSlotError::ValidatorStopped) => {
+                Err(rpc_err(jsonrpsee::types::error::INTERNAL_ERROR_CODE, e.to_string(), None))
+            }
+            Err(e) => Err(invalid_params_rpc_err(e.to_string()))
+        }
+    }
+
+    async fn listing_decisions(&self) -> RpcResult<Vec<ListingDecision>> {
+        self.validator
+            .listing_decisions()
+            .await
+            .map_err(|e| rpc_err(jsonrpsee::types::error::INTERNAL_ERROR_CODE, e.to_string(), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+    use tokio::sync::mpsc::unbounded_channel;
+    use validation::{
+        order::state::config::{HashMethod, TokenApprovalSlot, TokenBalanceSlot},
+        validator::ValidationRequest
+    };
+
+    use super::*;
+
+    fn slots() -> TokenSlots {
+        let token = Address::with_last_byte(1);
+        TokenSlots {
+            balance:      TokenBalanceSlot {
+                token,
+                hash_method: HashMethod::Solidity,
+                slot_index: 0
+            },
+            approval:     TokenApprovalSlot {
+                token,
+                hash_method: HashMethod::Solidity,
+                slot_index: 1
+            },
+            probe_holder: Address::with_last_byte(2)
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_probes_are_invalid_params() {
+        let (tx, mut rx) = unbounded_channel();
+        let api = AdminApi::new(ValidationClient(tx));
+        tokio::spawn(async move {
+            let Some(ValidationRequest::RegisterTokenSlots { sender, .. }) = rx.recv().await else {
+                panic!("expected a token slot registration")
+            };
+            let _ = sender.send(Err(TokenSlotError::NoCode(Address::with_last_byte(1))));
+        });
+
+        let err = api.register_token_slots(slots()).await.unwrap_err();
+        assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+    }
+
+    #[tokio::test]
+    async fn stopped_validator_is_an_internal_error() {
+        let (tx, rx) = unbounded_channel();
+        drop(rx);
+        let api = AdminApi::new(ValidationClient(tx));
+
+        let err = api.register_token_slots(slots()).await.unwrap_err();
+        assert_eq!(err.code(), jsonrpsee::types::error::INTERNAL_ERROR_CODE);
+    }
+}
@@ -1,26 +1,85 @@
-use consensus::ConsensusState;
-use jsonrpsee::{core::RpcResult, PendingSubscriptionSink};
+use consensus::{
+    status::{CurrentRound, RoundStatus},
+    AttestationSubscriptions
+};
+use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
+use reth_tasks::TaskSpawner;
+use tokio::sync::broadcast::error::RecvError;
 
-use crate::{api::ConsensusApiServer, types::ConsensusSubscriptionKind};
+use super::rpc_err;
+use crate::{
+    api::ConsensusApiServer,
+    types::{ConsensusSubscriptionKind, ConsensusSubscriptionResult}
+};
 
-pub struct ConsensusApi<C> {
-    pub consensus: C
+pub struct ConsensusApi<C, Spawner> {
+    pub consensus:     C,
+    pub current_round: CurrentRound,
+    pub task_spawner:  Spawner
+}
+
+impl<C, Spawner> ConsensusApi<C, Spawner> {
+    pub fn new(consensus: C, current_round: CurrentRound, task_spawner: Spawner) -> Self {
+        Self { consensus, current_round, task_spawner }
+    }
 }
 
 #[async_trait::async_trait]
-impl<C> ConsensusApiServer for ConsensusApi<C>
+impl<C, Spawner> ConsensusApiServer for ConsensusApi<C, Spawner>
 where
-    C: Send + Sync + 'static
+    C: AttestationSubscriptions,
+    Spawner: TaskSpawner + 'static
 {
-    async fn consensus_state(&self) -> RpcResult<ConsensusState> {
-        todo!()
+    async fn consensus_state(&self) -> RpcResult<RoundStatus> {
+        self.current_round.get().ok_or_else(|| {
+            rpc_err(
+                jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+                "consensus has not started yet",
+                None
+            )
+        })
     }
 
     async fn subscribe_consensus_state(
         &self,
-        _pending: PendingSubscriptionSink,
-        _kind: ConsensusSubscriptionKind
+        pending: PendingSubscriptionSink,
+        kind: ConsensusSubscriptionKind
     ) -> jsonrpsee::core::SubscriptionResult {
-        todo!()
+        if kind != ConsensusSubscriptionKind::BundleAttestations {
+            return Err("subscription kind is not supported yet".into())
+        }
+
+        let sink = pending.accept().await?;
+        let mut subscription = self.consensus.subscribe_attestations();
+
+        self.task_spawner.spawn(Box::pin(async move {
+            loop {
+                let attestation = match subscription.recv().await {
+                    Ok(attestation) => attestation,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "attestation subscriber lagged behind");
+                        continue
+                    }
+                    Err(RecvError::Closed) => break
+                };
+                if sink.is_closed() {
+                    break;
+                }
+
+                let result = ConsensusSubscriptionResult::BundleAttestation(attestation);
+                match SubscriptionMessage::from_json(&result) {
+                    Ok(message) => {
+                        if sink.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to serialize subscription message: {:?}", e);
+                    }
+                }
+            }
+        }));
+
+        Ok(())
     }
 }
@@ -0,0 +1,66 @@
+use alloy_primitives::BlockNumber;
+use consensus::summary::RoundSummaries;
+use jsonrpsee::core::RpcResult;
+use order_pool::OrderPoolHandle;
+
+use crate::{api::DashboardApiServer, types::BlockSummary};
+
+pub struct DashboardApi<OrderPool> {
+    pool:   OrderPool,
+    rounds: RoundSummaries
+}
+
+impl<OrderPool> DashboardApi<OrderPool> {
+    pub fn new(pool: OrderPool, rounds: RoundSummaries) -> Self {
+        Self { pool, rounds }
+    }
+}
+
+#[async_trait::async_trait]
+impl<OrderPool> DashboardApiServer for DashboardApi<OrderPool>
+where
+    OrderPool: OrderPoolHandle
+{
+    async fn block_summary(&self, block_number: BlockNumber) -> RpcResult<Option<BlockSummary>> {
+        let orders = self.pool.block_activity(block_number).await;
+        let round = self.rounds.get(block_number);
+
+        Ok((orders.is_some() || round.is_some()).then_some(BlockSummary {
+            block_number,
+            orders,
+            round
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_network::pool_manager::{OrderCommand, PoolHandle};
+    use order_pool::surveillance::BlockOrderActivity;
+    use tokio::sync::{broadcast, mpsc::unbounded_channel};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn joins_order_activity_with_the_round() {
+        let (manager_tx, mut manager_rx) = unbounded_channel();
+        let pool = PoolHandle { manager_tx, pool_manager_tx: broadcast::channel(1).0 };
+        tokio::spawn(async move {
+            while let Some(command) = manager_rx.recv().await {
+                let OrderCommand::BlockActivity(block_number, tx) = command else { continue };
+                let activity = (block_number == 1).then(|| BlockOrderActivity {
+                    block_number,
+                    received: 3,
+                    ..Default::default()
+                });
+                let _ = tx.send(activity);
+            }
+        });
+        let api = DashboardApi::new(pool, RoundSummaries::new());
+
+        let summary = api.block_summary(1).await.unwrap().unwrap();
+        assert_eq!(summary.orders.unwrap().received, 3);
+        assert_eq!(summary.round, None);
+        assert_eq!(api.block_summary(2).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH}
+};
+
+use alloy_primitives::{Address, B256};
+use angstrom_metrics::RpcMetricsWrapper;
+use angstrom_types::{
+    orders::OrderOrigin,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use jsonrpsee::core::RpcResult;
+use order_pool::{OrderPoolHandle, PoolManagerUpdate, SequencedUpdate};
+use reth_tasks::TaskSpawner;
+use validation::order::stages::StaticChecksStage;
+
+use crate::{
+    api::{CancelOrderRequest, DeskApiServer},
+    impls::{angstrom_rpc_err, invalid_params_rpc_err},
+    types::{ApiKeyConfig, ApiKeyUsage}
+};
+
+const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+/// Flash orders are only valid for a single block, so they are considered
+/// closed after a slot even if we never hear back about them.
+const FLASH_ORDER_LIFETIME: Duration = Duration::from_secs(12);
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DeskApiError {
+    #[error("unknown api key")]
+    UnknownApiKey,
+    #[error("order rate quota exceeded")]
+    RateLimited,
+    #[error("max open orders reached")]
+    TooManyOpenOrders,
+    #[error("invalid transaction signature")]
+    InvalidSignature,
+    #[error("order was not submitted with this api key")]
+    UnknownOrder
+}
+
+impl DeskApiError {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::UnknownApiKey => "unknown_api_key",
+            Self::RateLimited => "rate_limited",
+            Self::TooManyOpenOrders => "too_many_open_orders",
+            Self::InvalidSignature => "invalid_signature",
+            Self::UnknownOrder => "unknown_order"
+        }
+    }
+}
+
+impl From<DeskApiError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(error: DeskApiError) -> Self {
+        invalid_params_rpc_err(error.to_string())
+    }
+}
+
+#[derive(Debug)]
+struct KeyState {
+    config:           ApiKeyConfig,
+    /// submission times within the quota window
+    window:           VecDeque<Instant>,
+    /// open orders along with when they are considered expired
+    open_orders:      HashMap<B256, Instant>,
+    submitted_orders: u64,
+    rejected_orders:  u64
+}
+
+impl KeyState {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .window
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= QUOTA_WINDOW)
+        {
+            self.window.pop_front();
+        }
+        self.open_orders.retain(|_, expiry| *expiry > now);
+    }
+}
+
+/// Tracks the quotas and usage of every api key.
+#[derive(Debug, Default)]
+pub struct ApiKeyRegistry {
+    keys: HashMap<String, KeyState>
+}
+
+impl ApiKeyRegistry {
+    pub fn new(keys: Vec<ApiKeyConfig>) -> Self {
+        let keys = keys
+            .into_iter()
+            .map(|config| {
+                (
+                    config.key.clone(),
+                    KeyState {
+                        config,
+                        window: VecDeque::new(),
+                        open_orders: HashMap::new(),
+                        submitted_orders: 0,
+                        rejected_orders: 0
+                    }
+                )
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Checks the quotas of the key, returning the name of the desk if the
+    /// order can be submitted.
+    pub fn check_quota(&mut self, api_key: &str, now: Instant) -> Result<String, DeskApiError> {
+        let state = self
+            .keys
+            .get_mut(api_key)
+            .ok_or(DeskApiError::UnknownApiKey)?;
+        state.prune(now);
+
+        let res = if state.window.len() >= state.config.orders_per_minute as usize {
+            Err(DeskApiError::RateLimited)
+        } else if state.open_orders.len() >= state.config.max_open_orders {
+            Err(DeskApiError::TooManyOpenOrders)
+        } else {
+            state.window.push_back(now);
+            Ok(state.config.name.clone())
+        };
+        if res.is_err() {
+            state.rejected_orders += 1;
+        }
+
+        res
+    }
+
+    /// Records the outcome of an order that passed the quota checks. Returns
+    /// the amount of open orders of the key.
+    pub fn record_order(
+        &mut self,
+        api_key: &str,
+        order_hash: B256,
+        expiry: Instant,
+        accepted: bool
+    ) -> usize {
+        let Some(state) = self.keys.get_mut(api_key) else { return 0 };
+        if accepted {
+            state.submitted_orders += 1;
+            state.open_orders.insert(order_hash, expiry);
+        } else {
+            state.rejected_orders += 1;
+        }
+
+        state.open_orders.len()
+    }
+
+    /// Removes a closed order from whichever key it was submitted with,
+    /// returning the name of the desk and its open orders.
+    pub fn close_order(&mut self, order_hash: &B256) -> Option<(String, usize)> {
+        self.keys.values_mut().find_map(|state| {
+            state
+                .open_orders
+                .remove(order_hash)
+                .map(|_| (state.config.name.clone(), state.open_orders.len()))
+        })
+    }
+
+    pub fn name(&self, api_key: &str) -> Option<String> {
+        self.keys
+            .get(api_key)
+            .map(|state| state.config.name.clone())
+    }
+
+    pub fn owns_order(&self, api_key: &str, order_hash: &B256) -> Result<(), DeskApiError> {
+        let state = self.keys.get(api_key).ok_or(DeskApiError::UnknownApiKey)?;
+        state
+            .open_orders
+            .contains_key(order_hash)
+            .then_some(())
+            .ok_or(DeskApiError::UnknownOrder)
+    }
+
+    pub fn usage(&mut self, api_key: &str, now: Instant) -> Result<ApiKeyUsage, DeskApiError> {
+        let state = self
+            .keys
+            .get_mut(api_key)
+            .ok_or(DeskApiError::UnknownApiKey)?;
+        state.prune(now);
+
+        Ok(ApiKeyUsage {
+            name:               state.config.name.clone(),
+            submitted_orders:   state.submitted_orders,
+            rejected_orders:    state.rejected_orders,
+            open_orders:        state.open_orders.len(),
+            max_open_orders:    state.config.max_open_orders,
+            orders_last_minute: state.window.len() as u32,
+            orders_per_minute:  state.config.orders_per_minute
+        })
+    }
+}
+
+pub struct DeskApi<OrderPool> {
+    pool:          OrderPool,
+    registry:      Arc<Mutex<ApiKeyRegistry>>,
+    metrics:       RpcMetricsWrapper,
+    static_checks: StaticChecksStage
+}
+
+impl<OrderPool> DeskApi<OrderPool>
+where
+    OrderPool: OrderPoolHandle
+{
+    pub fn new<Spawner: TaskSpawner>(
+        pool: OrderPool,
+        task_spawner: Spawner,
+        keys: Vec<ApiKeyConfig>
+    ) -> Self {
+        let registry = Arc::new(Mutex::new(ApiKeyRegistry::new(keys)));
+        let metrics = RpcMetricsWrapper::new();
+
+        // closes orders once the pool tells us they are no longer open
+        let mut subscription = pool.subscribe_orders();
+        let (tracked, tracking_metrics) = (registry.clone(), metrics.clone());
+        task_spawner.spawn(Box::pin(async move {
+            while let Ok(SequencedUpdate { update, .. }) = subscription.recv().await {
+                let hash = match update {
+                    PoolManagerUpdate::FilledOrder((_, order)) => order.order_hash(),
+                    PoolManagerUpdate::CancelledOrder(hash)
+                    | PoolManagerUpdate::ExpiredOrder(hash) => hash,
+                    _ => continue
+                };
+                if let Some((name, open)) = tracked.lock().unwrap().close_order(&hash) {
+                    tracking_metrics.set_open_orders(&name, open);
+                }
+            }
+        }));
+
+        Self { pool, registry, metrics, static_checks: StaticChecksStage::default() }
+    }
+
+    /// When the order is considered closed if we never hear back about it.
+    fn order_expiry(order: &AllOrders, now: Instant) -> Instant {
+        if order.flash_block().is_some() {
+            return now + FLASH_ORDER_LIFETIME
+        }
+
+        let now_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let remaining = order
+            .deadline()
+            .map(|deadline| deadline.saturating_to::<u64>().saturating_sub(now_secs))
+            .unwrap_or(u64::MAX / 2);
+
+        now + Duration::from_secs(remaining)
+    }
+}
+
+#[async_trait::async_trait]
+impl<OrderPool> DeskApiServer for DeskApi<OrderPool>
+where
+    OrderPool: OrderPoolHandle
+{
+    async fn send_order(&self, api_key: String, order: AllOrders) -> RpcResult<bool> {
+        if let Err(e) = self.static_checks.check(&order) {
+            if let Some(name) = self.registry.lock().unwrap().name(&api_key) {
+                self.metrics.incr_orders_rejected(&name, "malformed");
+            }
+            return Err(angstrom_rpc_err(angstrom_errors::ValidationError::from(e)))
+        }
+
+        let now = Instant::now();
+        let checked = {
+            let mut registry = self.registry.lock().unwrap();
+            registry
+                .check_quota(&api_key, now)
+                .map_err(|e| (registry.name(&api_key), e))
+        };
+        let name = match checked {
+            Ok(name) => name,
+            Err((name, e)) => {
+                if let Some(name) = name {
+                    self.metrics.incr_orders_rejected(&name, e.label());
+                }
+                return Err(e.into())
+            }
+        };
+
+        let order_hash = order.order_hash();
+        let expiry = Self::order_expiry(&order, now);
+        let accepted = self.pool.new_order(OrderOrigin::External, order).await;
+
+        let open = self
+            .registry
+            .lock()
+            .unwrap()
+            .record_order(&api_key, order_hash, expiry, accepted);
+        if accepted {
+            self.metrics.incr_orders_submitted(&name);
+            self.metrics.set_open_orders(&name, open);
+        } else {
+            self.metrics.incr_orders_rejected(&name, "invalid_order");
+        }
+
+        Ok(accepted)
+    }
+
+    async fn cancel_order(&self, api_key: String, request: CancelOrderRequest) -> RpcResult<bool> {
+        self.registry
+            .lock()
+            .unwrap()
+            .owns_order(&api_key, &request.hash)?;
+
+        let sender = request
+            .signature
+            .recover_signer_full_public_key(request.hash)
+            .map(|s| Address::from_raw_public_key(&*s))
+            .map_err(|_| DeskApiError::InvalidSignature)?;
+
+        Ok(self.pool.cancel_order(sender, request.hash).await)
+    }
+
+    async fn usage(&self, api_key: String) -> RpcResult<ApiKeyUsage> {
+        Ok(self
+            .registry
+            .lock()
+            .unwrap()
+            .usage(&api_key, Instant::now())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(orders_per_minute: u32, max_open_orders: usize) -> ApiKeyRegistry {
+        ApiKeyRegistry::new(vec![ApiKeyConfig {
+            name: "desk".into(),
+            key: "secret".into(),
+            orders_per_minute,
+            max_open_orders
+        }])
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let mut registry = registry(10, 10);
+        assert_eq!(registry.check_quota("other", Instant::now()), Err(DeskApiError::UnknownApiKey));
+    }
+
+    #[test]
+    fn enforces_order_rate() {
+        let mut registry = registry(2, 10);
+        let now = Instant::now();
+        assert!(registry.check_quota("secret", now).is_ok());
+        assert!(registry.check_quota("secret", now).is_ok());
+        assert_eq!(registry.check_quota("secret", now), Err(DeskApiError::RateLimited));
+        // the window rolls over
+        assert!(registry.check_quota("secret", now + QUOTA_WINDOW).is_ok());
+    }
+
+    #[test]
+    fn enforces_open_orders() {
+        let mut registry = registry(10, 1);
+        let now = Instant::now();
+        let hash = B256::with_last_byte(1);
+        registry.check_quota("secret", now).unwrap();
+        registry.record_order("secret", hash, now + QUOTA_WINDOW, true);
+        assert_eq!(registry.check_quota("secret", now), Err(DeskApiError::TooManyOpenOrders));
+
+        assert_eq!(registry.close_order(&hash), Some(("desk".into(), 0)));
+        assert!(registry.check_quota("secret", now).is_ok());
+
+        let usage = registry.usage("secret", now).unwrap();
+        assert_eq!(usage.submitted_orders, 1);
+        assert_eq!(usage.rejected_orders, 1);
+        assert_eq!(usage.orders_last_minute, 2);
+    }
+}
@@ -1,7 +1,13 @@
+mod admin;
 mod consensus;
+mod dashboard;
+mod desk;
 mod orders;
 mod quoting;
 
+pub use admin::*;
 pub use consensus::*;
+pub use dashboard::*;
+pub use desk::*;
 pub use orders::*;
 pub use quoting::*;
@@ -1,32 +1,123 @@
-use alloy_primitives::Address;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use alloy_primitives::{Address, B256};
+use angstrom_errors::{AngstromError, ErrorCode, PoolError, ValidationError};
 use angstrom_types::{
     orders::OrderOrigin,
+    primitive::PoolId,
     sol_bindings::{
         grouped_orders::{AllOrders, FlashVariants, StandingVariants},
         rpc_orders::{
             ExactFlashOrder, ExactStandingOrder, PartialFlashOrder, PartialStandingOrder,
             TopOfBlockOrder
-        }
+        },
+        RawPoolOrder
     }
 };
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
-use order_pool::{OrderPoolHandle, PoolManagerUpdate};
+use order_pool::{
+    audit::OrderTrailEntry,
+    order_storage::{OrderCursor, OrderFilter, OrderPage},
+    surveillance::{OrderStatus, PoolActivity},
+    twap::{TwapInstruction, TwapStatus},
+    BookSnapshot, OrderPoolHandle, PoolManagerUpdate, SequencedUpdate
+};
 use reth_tasks::TaskSpawner;
+use tokio::sync::broadcast::error::RecvError;
+use validation::{
+    order::{stages::StaticChecksStage, OrderValidationResults},
+    validator::ValidationClient
+};
 
 use crate::{
-    api::{CancelOrderRequest, OrderApiServer},
-    types::{OrderSubscriptionKind, OrderSubscriptionResult},
-    OrderApiError::InvalidSignature
+    api::{
+        AccountKillSwitchRequest, CancelOrderRequest, OrderApiServer, OrderGasEstimate,
+        OrderSubmissionStatus, StandingOrderEnvelope
+    },
+    screening::OrderScreener,
+    types::{
+        OrderSubscriptionKind, OrderSubscriptionResult, PricedOrder,
+        SequencedOrderSubscriptionResult
+    },
+    OrderApiError::{ExpiredAuthorization, InvalidSignature, ReusedNonce}
 };
 
+/// How long a signed kill switch request stays valid for.
+const KILL_SWITCH_AUTH_VALIDITY_SECS: u64 = 5 * 60;
+/// Most orders that can be sent in a single `sendOrders` request.
+pub const MAX_BATCH_ORDERS: usize = 100;
+/// Most orders returned in a single `ordersPage` request.
+pub const MAX_ORDERS_PAGE_SIZE: usize = 1000;
+
 pub struct OrderApi<OrderPool, Spawner> {
-    pool:         OrderPool,
-    task_spawner: Spawner
+    pool:               OrderPool,
+    task_spawner:       Spawner,
+    /// used to normalize the prices we return
+    token_decimals:     Arc<HashMap<Address, u8>>,
+    static_checks:      StaticChecksStage,
+    /// screens the accounts of the orders when set
+    screener:           Option<Arc<OrderScreener>>,
+    /// estimates the gas of orders when set
+    validator:          Option<ValidationClient>,
+    /// nonce of the last accepted kill switch request of each account
+    kill_switch_nonces: Arc<Mutex<HashMap<Address, u64>>>
 }
 
 impl<OrderPool, Spawner> OrderApi<OrderPool, Spawner> {
     pub fn new(pool: OrderPool, task_spawner: Spawner) -> Self {
-        Self { pool, task_spawner }
+        Self {
+            pool,
+            task_spawner,
+            token_decimals: Default::default(),
+            static_checks: Default::default(),
+            screener: None,
+            validator: None,
+            kill_switch_nonces: Default::default()
+        }
+    }
+
+    /// Sets the max deadline horizon (in seconds) orders are checked against
+    /// on submission.
+    pub fn with_max_deadline_horizon(mut self, max_deadline_horizon: u64) -> Self {
+        self.static_checks = StaticChecksStage::new(max_deadline_horizon);
+        self
+    }
+
+    /// Rejects orders of screened accounts with
+    /// [`ValidationError::Screened`](angstrom_errors::ValidationError::Screened).
+    pub fn with_screening(mut self, screener: OrderScreener) -> Self {
+        self.screener = Some(Arc::new(screener));
+        self
+    }
+
+    /// Answers gas estimates of orders through the validator.
+    pub fn with_validator(mut self, validator: ValidationClient) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Rejects malformed and screened orders before they are sent to the
+    /// pool, so they never take up a spot in the validation queue.
+    async fn precheck(&self, order: &AllOrders) -> RpcResult<()> {
+        self.check(order).await.map_err(angstrom_rpc_err)
+    }
+
+    async fn check(&self, order: &AllOrders) -> Result<(), ValidationError> {
+        self.static_checks.check(order)?;
+        if let Some(screener) = &self.screener {
+            screener.check(order).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn with_token_decimals(mut self, token_decimals: HashMap<Address, u8>) -> Self {
+        self.token_decimals = Arc::new(token_decimals);
+        self
     }
 }
 
@@ -38,29 +129,111 @@ where
 {
     async fn send_partial_standing_order(&self, order: PartialStandingOrder) -> RpcResult<bool> {
         let order = AllOrders::Standing(StandingVariants::Partial(order));
+        self.precheck(&order).await?;
         Ok(self.pool.new_order(OrderOrigin::External, order).await)
     }
 
     async fn send_exact_standing_order(&self, order: ExactStandingOrder) -> RpcResult<bool> {
         let order = AllOrders::Standing(StandingVariants::Exact(order));
+        self.precheck(&order).await?;
         Ok(self.pool.new_order(OrderOrigin::External, order).await)
     }
 
+    async fn send_standing_order(&self, envelope: StandingOrderEnvelope) -> RpcResult<bool> {
+        let order = AllOrders::Standing(envelope.order);
+        self.precheck(&order).await?;
+        if let Some(max_gas) = envelope.max_gas {
+            Ok(self
+                .pool
+                .new_gas_capped_order(
+                    OrderOrigin::External,
+                    order,
+                    max_gas,
+                    envelope.good_til_cancelled
+                )
+                .await)
+        } else if envelope.good_til_cancelled {
+            Ok(self.pool.new_gtc_order(OrderOrigin::External, order).await)
+        } else {
+            Ok(self.pool.new_order(OrderOrigin::External, order).await)
+        }
+    }
+
     async fn send_searcher_order(&self, order: TopOfBlockOrder) -> RpcResult<bool> {
         let order = AllOrders::TOB(order);
+        self.precheck(&order).await?;
         Ok(self.pool.new_order(OrderOrigin::External, order).await)
     }
 
     async fn send_partial_flash_order(&self, order: PartialFlashOrder) -> RpcResult<bool> {
         let order = AllOrders::Flash(FlashVariants::Partial(order));
+        self.precheck(&order).await?;
         Ok(self.pool.new_order(OrderOrigin::External, order).await)
     }
 
     async fn send_exact_flash_order(&self, order: ExactFlashOrder) -> RpcResult<bool> {
         let order = AllOrders::Flash(FlashVariants::Exact(order));
+        self.precheck(&order).await?;
         Ok(self.pool.new_order(OrderOrigin::External, order).await)
     }
 
+    async fn send_orders(&self, orders: Vec<AllOrders>) -> RpcResult<Vec<OrderSubmissionStatus>> {
+        if orders.len() > MAX_BATCH_ORDERS {
+            return Err(invalid_params_rpc_err(format!(
+                "at most {MAX_BATCH_ORDERS} orders can be sent at once"
+            )))
+        }
+
+        // malformed and screened orders are rejected up front, the rest are
+        // validated together
+        let mut statuses = vec![None; orders.len()];
+        let mut batch = Vec::with_capacity(orders.len());
+        for (i, order) in orders.into_iter().enumerate() {
+            match self.check(&order).await {
+                Ok(()) => batch.push((i, order)),
+                Err(e) => {
+                    statuses[i] = Some(OrderSubmissionStatus::rejected(order.order_hash(), e));
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            let (indices, batch): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+            let hashes = batch
+                .iter()
+                .map(|order| order.order_hash())
+                .collect::<Vec<_>>();
+            let results = self.pool.new_orders(OrderOrigin::External, batch).await;
+            for ((i, order_hash), result) in indices.into_iter().zip(hashes).zip(results) {
+                statuses[i] = Some(OrderSubmissionStatus::from_result(order_hash, result));
+            }
+        }
+
+        Ok(statuses.into_iter().flatten().collect())
+    }
+
+    async fn estimate_order_gas(&self, order: AllOrders) -> RpcResult<OrderGasEstimate> {
+        let internal_err =
+            |msg: String| rpc_err(jsonrpsee::types::error::INTERNAL_ERROR_CODE, msg, None);
+        let validator = self
+            .validator
+            .as_ref()
+            .ok_or_else(|| internal_err("gas estimation is not enabled".to_string()))?;
+
+        let token_in = order.token_in();
+        let estimate = validator
+            .estimate_order_gas(order)
+            .await
+            .map_err(|e| internal_err(e.to_string()))?;
+
+        Ok(OrderGasEstimate {
+            gas_units: estimate.gas_units,
+            gas_wei: estimate.gas_wei,
+            token_in,
+            gas_in_token: estimate.gas_in_token
+        })
+    }
+
     async fn cancel_order(&self, request: CancelOrderRequest) -> RpcResult<bool> {
         let sender = request
             .signature
@@ -72,6 +245,63 @@ where
         Ok(self.pool.cancel_order(sender.unwrap(), request.hash).await)
     }
 
+    async fn disable_account(&self, request: AccountKillSwitchRequest) -> RpcResult<bool> {
+        let account = self.verify_kill_switch_auth(&request, true)?;
+        Ok(self.pool.disable_account(account).await)
+    }
+
+    async fn enable_account(&self, request: AccountKillSwitchRequest) -> RpcResult<bool> {
+        let account = self.verify_kill_switch_auth(&request, false)?;
+        Ok(self.pool.enable_account(account).await)
+    }
+
+    async fn send_twap_order(&self, instruction: TwapInstruction) -> RpcResult<B256> {
+        self.pool
+            .submit_twap(instruction)
+            .await
+            .map_err(|e| angstrom_rpc_err(angstrom_errors::PoolError::from(e)))
+    }
+
+    async fn twap_status(&self, id: B256) -> RpcResult<Option<TwapStatus>> {
+        Ok(self.pool.twap_status(id).await)
+    }
+
+    async fn pool_activity(&self, pool_id: Option<PoolId>) -> RpcResult<Vec<PoolActivity>> {
+        Ok(self.pool.pool_activity(pool_id).await)
+    }
+
+    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatus>> {
+        Ok(self.pool.order_status(order_hash).await)
+    }
+
+    async fn order_trail(&self, order_hash: B256) -> RpcResult<Vec<OrderTrailEntry>> {
+        Ok(self.pool.order_trail(order_hash).await)
+    }
+
+    async fn book_snapshot(&self, pool_id: PoolId, seq: u64) -> RpcResult<Option<BookSnapshot>> {
+        let snapshot = self
+            .pool
+            .book_snapshot(pool_id)
+            .await
+            .map_err(angstrom_rpc_err)?;
+        Ok((snapshot.seq != seq).then_some(snapshot))
+    }
+
+    async fn orders_page(
+        &self,
+        filter: OrderFilter,
+        after: Option<OrderCursor>,
+        limit: usize
+    ) -> RpcResult<OrderPage> {
+        if limit == 0 || limit > MAX_ORDERS_PAGE_SIZE {
+            return Err(invalid_params_rpc_err(format!(
+                "page size must be between 1 and {MAX_ORDERS_PAGE_SIZE}"
+            )))
+        }
+
+        Ok(self.pool.orders_page(filter, after, limit).await)
+    }
+
     async fn subscribe_orders(
         &self,
         pending: PendingSubscriptionSink,
@@ -79,14 +309,25 @@ where
     ) -> jsonrpsee::core::SubscriptionResult {
         let sink = pending.accept().await?;
         let mut subscription = self.pool.subscribe_orders();
+        let token_decimals = self.token_decimals.clone();
 
         self.task_spawner.spawn(Box::pin(async move {
-            while let Ok(order) = subscription.recv().await {
+            loop {
+                let SequencedUpdate { seq, update } = match subscription.recv().await {
+                    Ok(update) => update,
+                    // the gap in sequence numbers tells the subscriber to resync
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "order subscriber lagged behind");
+                        continue
+                    }
+                    Err(RecvError::Closed) => break
+                };
                 if sink.is_closed() {
                     break;
                 }
 
-                let msg = Self::return_order(&kind, order);
+                let msg = Self::return_order(&kind, update, &token_decimals)
+                    .map(|update| SequencedOrderSubscriptionResult { seq, update });
                 if let Some(result) = msg {
                     match SubscriptionMessage::from_json(&result) {
                         Ok(message) => {
@@ -106,20 +347,62 @@ where
     }
 }
 
+impl OrderSubmissionStatus {
+    fn rejected(order_hash: B256, error: ValidationError) -> Self {
+        let error = AngstromError::from(error);
+        Self::Rejected { order_hash, code: error.code(), reason: error.to_string() }
+    }
+
+    /// The status of the order with the given hash, the result of a
+    /// validation cut short by a new block doesn't carry it.
+    fn from_result(order_hash: B256, result: OrderValidationResults) -> Self {
+        match result {
+            OrderValidationResults::Valid(order) => Self::Accepted {
+                order_hash:    order.order_hash(),
+                estimated_gas: order.priority_data.gas_units
+            },
+            OrderValidationResults::Rejected(order_hash, error) => {
+                Self::rejected(order_hash, error)
+            }
+            OrderValidationResults::Invalid(order_hash, error) => Self::rejected(order_hash, error),
+            OrderValidationResults::SimFailed(order, error) => Self::rejected(
+                order.order_hash(),
+                ValidationError::Other(format!("order simulation failed: {error}"))
+            ),
+            OrderValidationResults::TransitionedToBlock => {
+                Self::rejected(order_hash, ValidationError::BlockMismatch)
+            }
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum OrderApiError {
     #[error("invalid transaction signature")]
-    InvalidSignature
+    InvalidSignature,
+    #[error("authorization has expired")]
+    ExpiredAuthorization,
+    #[error("nonce was already used by an earlier authorization")]
+    ReusedNonce
 }
 
 impl From<OrderApiError> for jsonrpsee::types::ErrorObjectOwned {
     fn from(error: OrderApiError) -> Self {
         match error {
-            OrderApiError::InvalidSignature => invalid_params_rpc_err(error.to_string())
+            OrderApiError::InvalidSignature => invalid_params_rpc_err(error.to_string()),
+            OrderApiError::ExpiredAuthorization => invalid_params_rpc_err(error.to_string()),
+            OrderApiError::ReusedNonce => invalid_params_rpc_err(error.to_string())
         }
     }
 }
 
+/// Converts an error from any component into a json-rpc error, using the error
+/// code of its domain.
+pub fn angstrom_rpc_err(error: impl Into<AngstromError>) -> jsonrpsee::types::ErrorObjectOwned {
+    let error = error.into();
+    rpc_err(error.code(), error.to_string(), None)
+}
+
 pub fn invalid_params_rpc_err(msg: impl Into<String>) -> jsonrpsee::types::ErrorObjectOwned {
     rpc_err(jsonrpsee::types::error::INVALID_PARAMS_CODE, msg, None)
 }
@@ -144,26 +427,73 @@ where
     OrderPool: OrderPoolHandle,
     Spawner: 'static + TaskSpawner
 {
+    /// Verifies that the kill switch request was signed by the account it
+    /// targets, that it hasn't expired and that its nonce wasn't used before.
+    fn verify_kill_switch_auth(
+        &self,
+        request: &AccountKillSwitchRequest,
+        disable: bool
+    ) -> Result<Address, OrderApiError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if request.timestamp > now || now - request.timestamp > KILL_SWITCH_AUTH_VALIDITY_SECS {
+            return Err(ExpiredAuthorization)
+        }
+
+        let signer = request
+            .signature
+            .recover_signer_full_public_key(request.message(disable))
+            .map(|s| Address::from_raw_public_key(&*s))
+            .map_err(|_| InvalidSignature)?;
+        if signer != request.address {
+            return Err(InvalidSignature)
+        }
+
+        let mut nonces = self.kill_switch_nonces.lock().unwrap();
+        if nonces
+            .get(&signer)
+            .is_some_and(|last| request.nonce <= *last)
+        {
+            return Err(ReusedNonce)
+        }
+        nonces.insert(signer, request.nonce);
+
+        Ok(signer)
+    }
+
     fn return_order(
         kind: &OrderSubscriptionKind,
-        order: PoolManagerUpdate
+        order: PoolManagerUpdate,
+        token_decimals: &HashMap<Address, u8>
     ) -> Option<OrderSubscriptionResult> {
         match (&kind, order) {
-            (OrderSubscriptionKind::NewOrders, PoolManagerUpdate::NewOrder(order_update)) => {
-                Some(OrderSubscriptionResult::NewOrder(order_update))
-            }
             (
-                OrderSubscriptionKind::FilledOrders,
+                OrderSubscriptionKind::NewOrders | OrderSubscriptionKind::OrderFlow,
+                PoolManagerUpdate::NewOrder(order_update)
+            ) => Some(OrderSubscriptionResult::NewOrder(PricedOrder::new(
+                order_update,
+                token_decimals
+            ))),
+            (
+                OrderSubscriptionKind::FilledOrders | OrderSubscriptionKind::OrderFlow,
                 PoolManagerUpdate::FilledOrder((block_number, filled_order))
-            ) => Some(OrderSubscriptionResult::FilledOrder((block_number, filled_order))),
+            ) => Some(OrderSubscriptionResult::FilledOrder((
+                block_number,
+                PricedOrder::new(filled_order, token_decimals)
+            ))),
             (
-                OrderSubscriptionKind::UnfilleOrders,
-                PoolManagerUpdate::UnfilledOrders(unfilled_order)
-            ) => Some(OrderSubscriptionResult::UnfilledOrder(unfilled_order)),
+                OrderSubscriptionKind::UnfilleOrders | OrderSubscriptionKind::OrderFlow,
+                PoolManagerUpdate::UnfilledOrders(unfilled_orders)
+            ) => Some(OrderSubscriptionResult::UnfilledOrders(unfilled_orders)),
             (
                 OrderSubscriptionKind::CancelledOrders,
                 PoolManagerUpdate::CancelledOrder(order_hash)
             ) => Some(OrderSubscriptionResult::CancelledOrder(order_hash)),
+            (OrderSubscriptionKind::ExpiredOrders, PoolManagerUpdate::ExpiredOrder(order_hash)) => {
+                Some(OrderSubscriptionResult::ExpiredOrder(order_hash))
+            }
             (OrderSubscriptionKind::NewOrders, PoolManagerUpdate::FilledOrder(_)) => None,
             (OrderSubscriptionKind::NewOrders, PoolManagerUpdate::UnfilledOrders(_)) => None,
             (OrderSubscriptionKind::FilledOrders, PoolManagerUpdate::NewOrder(_)) => None,
@@ -175,7 +505,10 @@ where
             (OrderSubscriptionKind::UnfilleOrders, PoolManagerUpdate::CancelledOrder(_)) => None,
             (OrderSubscriptionKind::CancelledOrders, PoolManagerUpdate::NewOrder(_)) => None,
             (OrderSubscriptionKind::CancelledOrders, PoolManagerUpdate::FilledOrder(_)) => None,
-            (OrderSubscriptionKind::CancelledOrders, PoolManagerUpdate::UnfilledOrders(_)) => None
+            (OrderSubscriptionKind::CancelledOrders, PoolManagerUpdate::UnfilledOrders(_)) => None,
+            (OrderSubscriptionKind::OrderFlow, PoolManagerUpdate::CancelledOrder(_)) => None,
+            (OrderSubscriptionKind::ExpiredOrders, _) => None,
+            (_, PoolManagerUpdate::ExpiredOrder(_)) => None
         }
     }
 }
@@ -184,25 +517,46 @@ where
 mod tests {
     use std::{future, future::Future};
 
-    use alloy_primitives::{Address, B256};
+    use alloy_primitives::{aliases::U40, Address, BlockNumber, B256, U256};
     use angstrom_network::pool_manager::OrderCommand;
-    use angstrom_types::sol_bindings::rpc_orders::{
-        ExactFlashOrder, ExactStandingOrder, PartialFlashOrder, PartialStandingOrder,
-        TopOfBlockOrder
+    use angstrom_types::{
+        orders::OrderPriorityData,
+        sol_bindings::{
+            grouped_orders::OrderWithStorageData,
+            rpc_orders::{
+                ExactFlashOrder, ExactStandingOrder, PartialFlashOrder, PartialStandingOrder,
+                TopOfBlockOrder
+            }
+        }
+    };
+    use order_pool::{
+        surveillance::BlockOrderActivity, twap::TwapError, UnfilledOrder, UnfilledOrders,
+        UnfilledReason
     };
-    use order_pool::PoolManagerUpdate;
     use reth_tasks::TokioTaskExecutor;
     use tokio::sync::{
         broadcast::Receiver,
         mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender}
     };
+    use validation::{
+        order::state::gas::{OrderGasEstimate as ValidatorGasEstimate, TOB_ORDER_GAS},
+        validator::ValidationRequest
+    };
 
     use super::*;
+    use crate::screening::AddressListScreening;
 
     #[tokio::test]
     async fn test_send_partial_standing_order() {
         let (_handle, api) = setup_order_api();
-        let order = PartialStandingOrder::default();
+        let order = PartialStandingOrder {
+            maxAmountIn: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            deadline: deadline(),
+            ..Default::default()
+        };
         assert!(api
             .send_partial_standing_order(order)
             .await
@@ -212,17 +566,53 @@ mod tests {
     #[tokio::test]
     async fn test_send_exact_standing_order() {
         let (_handle, api) = setup_order_api();
-        let order = ExactStandingOrder::default();
+        let order = ExactStandingOrder {
+            amount: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            deadline: deadline(),
+            ..Default::default()
+        };
         assert!(api
             .send_exact_standing_order(order)
             .await
             .expect("to not throw error"));
     }
 
+    #[tokio::test]
+    async fn test_send_standing_order_with_max_gas() {
+        let (mut handle, api) = setup_order_api();
+        let order = ExactStandingOrder {
+            amount: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            deadline: deadline(),
+            ..Default::default()
+        };
+        let envelope = StandingOrderEnvelope {
+            order:              StandingVariants::Exact(order),
+            good_til_cancelled: true,
+            max_gas:            Some(100)
+        };
+        assert!(api.send_standing_order(envelope).await.unwrap());
+        assert!(matches!(
+            handle.from_api.recv().await,
+            Some(OrderCommand::NewGasCappedOrder { max_gas: 100, good_til_cancelled: true, .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_send_searcher_order() {
         let (_handle, api) = setup_order_api();
-        let order = TopOfBlockOrder::default();
+        let order = TopOfBlockOrder {
+            quantityIn: 10,
+            quantityOut: 10,
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            ..Default::default()
+        };
         assert!(api
             .send_searcher_order(order)
             .await
@@ -232,7 +622,13 @@ mod tests {
     #[tokio::test]
     async fn test_send_partial_flash_order() {
         let (_handle, api) = setup_order_api();
-        let order = PartialFlashOrder::default();
+        let order = PartialFlashOrder {
+            maxAmountIn: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            ..Default::default()
+        };
         assert!(api
             .send_partial_flash_order(order)
             .await
@@ -242,13 +638,274 @@ mod tests {
     #[tokio::test]
     async fn test_send_exact_flash_order() {
         let (_handle, api) = setup_order_api();
-        let order = ExactFlashOrder::default();
+        let order = ExactFlashOrder {
+            amount: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            ..Default::default()
+        };
         assert!(api
             .send_exact_flash_order(order)
             .await
             .expect("to not throw error"));
     }
 
+    #[tokio::test]
+    async fn test_rejects_malformed_order() {
+        let (mut handle, api) = setup_order_api();
+        let order = PartialFlashOrder::default();
+        let err = api.send_partial_flash_order(order).await.unwrap_err();
+        assert_eq!(err.code(), angstrom_errors::ValidationError::Malformed(String::new()).code());
+        // never made it to the pool
+        assert!(handle.from_api.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_screened_order() {
+        let (mut handle, api) = setup_order_api();
+        let order = ExactFlashOrder {
+            amount: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            recipient: Address::with_last_byte(3),
+            ..Default::default()
+        };
+        let screening = AddressListScreening::new([Address::with_last_byte(3)]);
+        let api = api.with_screening(OrderScreener::new(Arc::new(screening)));
+
+        let err = api.send_exact_flash_order(order).await.unwrap_err();
+        assert_eq!(err.code(), angstrom_errors::ValidationError::Screened.code());
+        assert!(handle.from_api.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_orders_returns_status_per_order() {
+        let (mut handle, api) = setup_order_api();
+        let malformed = AllOrders::Flash(FlashVariants::Partial(PartialFlashOrder::default()));
+        let order = AllOrders::Flash(FlashVariants::Exact(ExactFlashOrder {
+            amount: 10,
+            minPrice: U256::from(1),
+            assetIn: Address::with_last_byte(1),
+            assetOut: Address::with_last_byte(2),
+            ..Default::default()
+        }));
+
+        let statuses = api
+            .send_orders(vec![malformed.clone(), order.clone()])
+            .await
+            .expect("to not throw error");
+        assert_eq!(statuses.len(), 2);
+        assert!(matches!(
+            &statuses[0],
+            OrderSubmissionStatus::Rejected { order_hash, code, .. }
+                if *order_hash == malformed.order_hash()
+                    && *code == angstrom_errors::ValidationError::Malformed(String::new()).code()
+        ));
+        assert!(matches!(
+            &statuses[1],
+            OrderSubmissionStatus::Rejected { order_hash, .. } if *order_hash == order.order_hash()
+        ));
+
+        // only the well formed order made it to the pool
+        let Ok(OrderCommand::NewOrders(_, orders)) = handle.from_api.try_recv() else {
+            panic!("expected a batch of orders")
+        };
+        assert_eq!(orders.len(), 1);
+    }
+
+    #[test]
+    fn interrupted_validation_is_rejected() {
+        let order_hash = B256::with_last_byte(1);
+        let status = OrderSubmissionStatus::from_result(
+            order_hash,
+            OrderValidationResults::TransitionedToBlock
+        );
+        assert!(matches!(
+            status,
+            OrderSubmissionStatus::Rejected { order_hash: hash, code, .. }
+                if hash == order_hash
+                    && code == angstrom_errors::ValidationError::BlockMismatch.code()
+        ));
+
+        let order = OrderWithStorageData {
+            order: AllOrders::TOB(TopOfBlockOrder::default()),
+            priority_data: OrderPriorityData { gas: 7, gas_units: 50_000, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(matches!(
+            OrderSubmissionStatus::from_result(
+                order.order_hash(),
+                OrderValidationResults::Valid(order)
+            ),
+            OrderSubmissionStatus::Accepted { estimated_gas: 50_000, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn estimates_order_gas_through_the_validator() {
+        let (_handle, api) = setup_order_api();
+        let order = AllOrders::TOB(TopOfBlockOrder {
+            assetIn: Address::with_last_byte(1),
+            ..Default::default()
+        });
+        // without a validator there is nothing to estimate with
+        assert!(api.estimate_order_gas(order.clone()).await.is_err());
+
+        let (tx, mut rx) = unbounded_channel();
+        let api = api.with_validator(ValidationClient(tx));
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                let ValidationRequest::EstimateOrderGas { order, sender } = request else {
+                    panic!("only gas estimates are expected")
+                };
+                let _ = sender.send(ValidatorGasEstimate::new(&order, 10, None));
+            }
+        });
+
+        let estimate = api.estimate_order_gas(order).await.unwrap();
+        assert_eq!(
+            estimate,
+            OrderGasEstimate {
+                gas_units:    TOB_ORDER_GAS,
+                gas_wei:      TOB_ORDER_GAS * 10,
+                token_in:     Address::with_last_byte(1),
+                gas_in_token: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn book_snapshot_is_only_sent_when_it_moved_on() {
+        let (handle, api) = setup_order_api();
+        let pool_id = PoolId::with_last_byte(1);
+
+        assert!(api.book_snapshot(pool_id, 0).await.unwrap().is_none());
+        let snapshot = api.book_snapshot(pool_id, 1).await.unwrap().unwrap();
+        assert_eq!((snapshot.pool_id, snapshot.seq), (pool_id, 0));
+
+        drop(handle);
+        let err = api.book_snapshot(pool_id, 1).await.unwrap_err();
+        assert_eq!(err.code(), PoolError::Unavailable.code());
+    }
+
+    #[tokio::test]
+    async fn test_send_orders_rejects_oversized_batches() {
+        let (_handle, api) = setup_order_api();
+        let orders = vec![AllOrders::TOB(TopOfBlockOrder::default()); MAX_BATCH_ORDERS + 1];
+        let err = api.send_orders(orders).await.unwrap_err();
+        assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+    }
+
+    #[tokio::test]
+    async fn test_orders_page_rejects_oversized_pages() {
+        let (_handle, api) = setup_order_api();
+        for limit in [0, MAX_ORDERS_PAGE_SIZE + 1] {
+            let err = api
+                .orders_page(OrderFilter::All, None, limit)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+        }
+        assert!(api
+            .orders_page(OrderFilter::All, None, MAX_ORDERS_PAGE_SIZE)
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn order_flow_forwards_new_filled_and_unfilled_orders() {
+        let order = AllOrders::TOB(TopOfBlockOrder::default());
+        let decimals = HashMap::new();
+        let flow = |update| {
+            OrderApi::<MockOrderPoolHandle, TokioTaskExecutor>::return_order(
+                &OrderSubscriptionKind::OrderFlow,
+                update,
+                &decimals
+            )
+        };
+
+        assert!(matches!(
+            flow(PoolManagerUpdate::NewOrder(order.clone())),
+            Some(OrderSubscriptionResult::NewOrder(_))
+        ));
+        assert!(matches!(
+            flow(PoolManagerUpdate::FilledOrder((1, order))),
+            Some(OrderSubscriptionResult::FilledOrder((1, _)))
+        ));
+        assert!(matches!(
+            flow(PoolManagerUpdate::UnfilledOrders(UnfilledOrders {
+                block_number: 1,
+                orders:       vec![UnfilledOrder {
+                    order_hash: B256::ZERO,
+                    reason:     UnfilledReason::NotMatched
+                }]
+            })),
+            Some(OrderSubscriptionResult::UnfilledOrders(_))
+        ));
+        assert_eq!(flow(PoolManagerUpdate::CancelledOrder(B256::ZERO)), None);
+        assert_eq!(flow(PoolManagerUpdate::ExpiredOrder(B256::ZERO)), None);
+    }
+
+    #[tokio::test]
+    async fn kill_switch_requests_cant_be_replayed() {
+        let (mut handle, api) = setup_order_api();
+        let key = B256::repeat_byte(7);
+
+        assert!(api
+            .disable_account(kill_switch_request(key, 1, true))
+            .await
+            .unwrap());
+        assert!(matches!(handle.from_api.recv().await, Some(OrderCommand::DisableAccount(..))));
+
+        // the same request, or one with a lower nonce, is rejected
+        assert!(api
+            .disable_account(kill_switch_request(key, 1, true))
+            .await
+            .is_err());
+        assert!(api
+            .enable_account(kill_switch_request(key, 0, false))
+            .await
+            .is_err());
+        assert!(handle.from_api.try_recv().is_err());
+
+        assert!(api
+            .enable_account(kill_switch_request(key, 2, false))
+            .await
+            .unwrap());
+        assert!(matches!(handle.from_api.recv().await, Some(OrderCommand::EnableAccount(..))));
+    }
+
+    fn kill_switch_request(key: B256, nonce: u64, disable: bool) -> AccountKillSwitchRequest {
+        let sign = |message| {
+            angstrom_types::primitive::Signature(
+                reth_primitives::sign_message(key, message).unwrap()
+            )
+        };
+        let address = Address::from_raw_public_key(
+            &*sign(B256::ZERO)
+                .recover_signer_full_public_key(B256::ZERO)
+                .unwrap()
+        );
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut request =
+            AccountKillSwitchRequest { address, nonce, timestamp, signature: Default::default() };
+        request.signature = sign(request.message(disable));
+        request
+    }
+
+    fn deadline() -> U40 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        U40::from(now + 60)
+    }
+
     fn setup_order_api() -> (OrderApiTestHandle, OrderApi<MockOrderPoolHandle, TokioTaskExecutor>) {
         let (to_pool, pool_rx) = unbounded_channel();
         let pool_handle = MockOrderPoolHandle { sender: to_pool };
@@ -281,10 +938,136 @@ mod tests {
             future::ready(true)
         }
 
-        fn subscribe_orders(&self) -> Receiver<PoolManagerUpdate> {
+        fn new_gtc_order(
+            &self,
+            origin: OrderOrigin,
+            order: AllOrders
+        ) -> impl Future<Output = bool> + Send {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let res = self
+                .sender
+                .send(OrderCommand::NewGtcOrder(origin, order, tx))
+                .is_ok();
+            future::ready(true)
+        }
+
+        fn new_gas_capped_order(
+            &self,
+            origin: OrderOrigin,
+            order: AllOrders,
+            max_gas: u128,
+            good_til_cancelled: bool
+        ) -> impl Future<Output = bool> + Send {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let res = self
+                .sender
+                .send(OrderCommand::NewGasCappedOrder {
+                    origin,
+                    order,
+                    max_gas,
+                    good_til_cancelled,
+                    validation_tx: tx
+                })
+                .is_ok();
+            future::ready(true)
+        }
+
+        fn new_orders(
+            &self,
+            origin: OrderOrigin,
+            orders: Vec<AllOrders>
+        ) -> impl Future<Output = Vec<OrderValidationResults>> + Send {
+            let (orders, results): (Vec<_>, Vec<_>) = orders
+                .into_iter()
+                .map(|order| {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    let hash = order.order_hash();
+                    (
+                        (order, tx),
+                        OrderValidationResults::Invalid(
+                            hash,
+                            ValidationError::Other("order failed validation".to_string())
+                        )
+                    )
+                })
+                .unzip();
+            let res = self
+                .sender
+                .send(OrderCommand::NewOrders(origin, orders))
+                .is_ok();
+            future::ready(results)
+        }
+
+        fn subscribe_orders(&self) -> Receiver<SequencedUpdate> {
             unimplemented!("Not needed for this test")
         }
 
+        fn book_snapshot(
+            &self,
+            pool_id: PoolId
+        ) -> impl Future<Output = Result<BookSnapshot, PoolError>> + Send {
+            // a dropped test handle stands in for a stopped pool
+            future::ready(if self.sender.is_closed() {
+                Err(PoolError::Unavailable)
+            } else {
+                Ok(BookSnapshot { pool_id, seq: 0, orders: vec![] })
+            })
+        }
+
+        fn orders_page(
+            &self,
+            _filter: OrderFilter,
+            _after: Option<OrderCursor>,
+            _limit: usize
+        ) -> impl Future<Output = OrderPage> + Send {
+            future::ready(OrderPage::default())
+        }
+
+        fn best_searcher_order(
+            &self,
+            _pool_id: PoolId
+        ) -> impl Future<Output = Option<OrderWithStorageData<TopOfBlockOrder>>> + Send {
+            future::ready(None)
+        }
+
+        fn submit_twap(
+            &self,
+            instruction: TwapInstruction
+        ) -> impl Future<Output = Result<B256, TwapError>> + Send {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let res = self
+                .sender
+                .send(OrderCommand::SubmitTwap(instruction, tx))
+                .is_ok();
+            future::ready(Ok(B256::ZERO))
+        }
+
+        fn twap_status(&self, id: B256) -> impl Future<Output = Option<TwapStatus>> + Send {
+            future::ready(None)
+        }
+
+        fn pool_activity(
+            &self,
+            _: Option<PoolId>
+        ) -> impl Future<Output = Vec<PoolActivity>> + Send {
+            future::ready(vec![])
+        }
+
+        fn block_activity(
+            &self,
+            _: BlockNumber
+        ) -> impl Future<Output = Option<BlockOrderActivity>> + Send {
+            future::ready(None)
+        }
+
+        fn order_status(&self, _: B256) -> impl Future<Output = Option<OrderStatus>> + Send {
+            future::ready(None)
+        }
+
+        fn order_trail(&self, _: B256) -> impl Future<Output = Vec<OrderTrailEntry>> + Send {
+            future::ready(vec![])
+        }
+
         fn cancel_order(
             &self,
             from: Address,
@@ -297,5 +1080,23 @@ mod tests {
                 .is_ok();
             future::ready(true)
         }
+
+        fn disable_account(&self, account: Address) -> impl Future<Output = bool> + Send {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let res = self
+                .sender
+                .send(OrderCommand::DisableAccount(account, tx))
+                .is_ok();
+            future::ready(true)
+        }
+
+        fn enable_account(&self, account: Address) -> impl Future<Output = bool> + Send {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let res = self
+                .sender
+                .send(OrderCommand::EnableAccount(account, tx))
+                .is_ok();
+            future::ready(true)
+        }
     }
 }
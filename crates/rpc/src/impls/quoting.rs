@@ -1,28 +1,181 @@
+use std::collections::BTreeMap;
+
 use alloy_primitives::{Address, U256};
-use jsonrpsee::{core::RpcResult, PendingSubscriptionSink};
+use angstrom_types::{
+    contract_payloads::tob::ToBOutcome,
+    matching::{
+        price::{self, AskPrice, BidPrice, Rounding},
+        uniswap::PoolSnapshot,
+        Ray
+    },
+    primitive::PoolId,
+    sol_bindings::{
+        grouped_orders::{AllOrders, FlashVariants, OrderWithStorageData, StandingVariants},
+        rpc_orders::TopOfBlockOrder,
+        RawPoolOrder
+    }
+};
+use jsonrpsee::{
+    core::RpcResult, types::ErrorObjectOwned, PendingSubscriptionSink, SubscriptionMessage
+};
+use order_pool::{OrderPoolHandle, PoolManagerUpdate, SequencedUpdate};
+use tokio::sync::broadcast::error::RecvError;
+use validation::{order::state::amm_swap::AmmSwapError, validator::ValidationClient};
 
+use super::{angstrom_rpc_err, invalid_params_rpc_err, rpc_err};
 use crate::{
     api::QuotingApiServer,
-    types::{QuotingSubscriptionKind, QuotingSubscriptionParam}
+    types::{
+        BookDepth, DepthLevel, QuoteFill, QuotingSubscriptionKind, QuotingSubscriptionParam,
+        SwapQuote, TobAuctionStatus
+    }
 };
 
+/// Most price levels returned per side by `order_book_depth`.
+pub const MAX_DEPTH_LEVELS: usize = 100;
+
 pub struct QuotesApi<OrderPool> {
-    pub pool: OrderPool
+    pool:      OrderPool,
+    validator: ValidationClient
+}
+
+impl<OrderPool> QuotesApi<OrderPool> {
+    pub fn new(pool: OrderPool, validator: ValidationClient) -> Self {
+        Self { pool, validator }
+    }
 }
 
 #[async_trait::async_trait]
 impl<OrderPool> QuotingApiServer for QuotesApi<OrderPool>
 where
-    OrderPool: Send + Sync + 'static
+    OrderPool: OrderPoolHandle
 {
     async fn quote_transaction(
         &self,
-        _token_in: Address,
-        _token_out: Address,
-        _amount_in: U256,
-        _amount_out: U256
-    ) -> RpcResult<U256> {
-        todo!()
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256
+    ) -> RpcResult<SwapQuote> {
+        if amount_in.is_zero() {
+            return Err(invalid_params_rpc_err("amount in must be non-zero"))
+        }
+
+        let full_swap = self
+            .validator
+            .simulate_amm_swap(token_in, token_out, amount_in)
+            .await
+            .map_err(amm_swap_err)?;
+        let snapshot = self
+            .pool
+            .book_snapshot(full_swap.pool_id)
+            .await
+            .map_err(angstrom_rpc_err)?;
+        let book =
+            fill_from_book(token_in, token_out, amount_in, full_swap.spot_price, &snapshot.orders);
+
+        let remaining = amount_in - book.amount_in;
+        let amm = if remaining == amount_in {
+            QuoteFill { amount_in, amount_out: full_swap.amount_out, orders: 0 }
+        } else if remaining.is_zero() {
+            QuoteFill::default()
+        } else {
+            let swap = self
+                .validator
+                .simulate_amm_swap(token_in, token_out, remaining)
+                .await
+                .map_err(amm_swap_err)?;
+            QuoteFill { amount_in: remaining, amount_out: swap.amount_out, orders: 0 }
+        };
+
+        let amount_out = book.amount_out + amm.amount_out;
+        if amount_out.is_zero() {
+            return Err(invalid_params_rpc_err("not enough liquidity to quote the swap"))
+        }
+
+        let sells_t0 = token_in < token_out;
+        let execution_price = if sells_t0 {
+            Ray::calc_price(amount_in, amount_out)
+        } else {
+            Ray::calc_price(amount_out, amount_in)
+        };
+
+        Ok(SwapQuote {
+            pool_id: full_swap.pool_id,
+            amount_in,
+            amount_out,
+            execution_price,
+            spot_price: full_swap.spot_price,
+            price_impact_bps: price_impact_bps(sells_t0, execution_price, full_swap.spot_price),
+            book,
+            amm
+        })
+    }
+
+    async fn order_book_depth(&self, pool_id: PoolId, levels: usize) -> RpcResult<BookDepth> {
+        if levels == 0 || levels > MAX_DEPTH_LEVELS {
+            return Err(invalid_params_rpc_err(format!(
+                "levels must be between 1 and {MAX_DEPTH_LEVELS}"
+            )))
+        }
+
+        let snapshot = self
+            .pool
+            .book_snapshot(pool_id)
+            .await
+            .map_err(angstrom_rpc_err)?;
+        // the book is still worth showing while the pool syncs
+        let amm = self
+            .validator
+            .amm_snapshot(pool_id)
+            .await
+            .inspect_err(|e| tracing::debug!(?pool_id, error = %e, "no pool snapshot for depth"))
+            .ok();
+
+        Ok(book_depth(pool_id, &snapshot.orders, amm.as_ref(), levels))
+    }
+
+    async fn tob_auction_status(&self, pool_id: PoolId) -> RpcResult<Option<TobAuctionStatus>> {
+        Ok(tob_auction_status(&self.pool, &self.validator, pool_id).await)
+    }
+
+    async fn subscribe_tob_auction(
+        &self,
+        pending: PendingSubscriptionSink,
+        pool_id: PoolId
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        let mut updates = self.pool.subscribe_orders();
+        let pool = self.pool.clone();
+        let validator = self.validator.clone();
+
+        tokio::spawn(async move {
+            let mut last = None;
+            loop {
+                if sink.is_closed() {
+                    break
+                }
+                let status = tob_auction_status(&pool, &validator, pool_id).await;
+                if last.as_ref() != Some(&status) {
+                    let Ok(message) = SubscriptionMessage::from_json(&status) else { break };
+                    if sink.send(message).await.is_err() {
+                        break
+                    }
+                    last = Some(status);
+                }
+
+                // wait for an update that can change the winner, a lagged
+                // subscriber checks right away
+                loop {
+                    match updates.recv().await {
+                        Ok(SequencedUpdate { update, .. }) if !moves_the_auction(&update) => {}
+                        Ok(_) | Err(RecvError::Lagged(_)) => break,
+                        Err(RecvError::Closed) => return
+                    }
+                }
+            }
+        });
+
+        Ok(())
     }
 
     async fn subscribe_quotes(
@@ -31,6 +184,362 @@ where
         _kind: QuotingSubscriptionKind,
         _params: Option<QuotingSubscriptionParam>
     ) -> jsonrpsee::core::SubscriptionResult {
-        todo!()
+        Err("quote subscriptions are not supported yet".into())
+    }
+}
+
+async fn tob_auction_status<OrderPool: OrderPoolHandle>(
+    pool: &OrderPool,
+    validator: &ValidationClient,
+    pool_id: PoolId
+) -> Option<TobAuctionStatus> {
+    let order = pool.best_searcher_order(pool_id).await?;
+    // the bid is still worth showing while the pool syncs
+    let amm = validator.amm_snapshot(pool_id).await.ok();
+
+    Some(auction_status(pool_id, &order, amm.as_ref()))
+}
+
+fn auction_status(
+    pool_id: PoolId,
+    order: &OrderWithStorageData<TopOfBlockOrder>,
+    amm: Option<&PoolSnapshot>
+) -> TobAuctionStatus {
+    let tribute = amm
+        .and_then(|amm| ToBOutcome::from_tob_and_snapshot(order, amm).ok())
+        .map(|outcome| outcome.tribute);
+
+    TobAuctionStatus {
+        pool_id,
+        order_hash: order.order_id.hash,
+        valid_for_block: order.validForBlock,
+        quantity_in: order.quantityIn,
+        net_bid: order.tob_reward,
+        tribute
+    }
+}
+
+/// Whether the update can change the winner of a top of block auction.
+/// Cancellations don't say what kind of order left, and the unfilled orders
+/// are sent when a block opens a new auction.
+fn moves_the_auction(update: &PoolManagerUpdate) -> bool {
+    match update {
+        PoolManagerUpdate::NewOrder(order) | PoolManagerUpdate::FilledOrder((_, order)) => {
+            matches!(order, AllOrders::TOB(_))
+        }
+        PoolManagerUpdate::CancelledOrder(_) | PoolManagerUpdate::UnfilledOrders(_) => true,
+        // only standing orders are renewed
+        PoolManagerUpdate::ExpiredOrder(_) => false
+    }
+}
+
+fn amm_swap_err(e: AmmSwapError) -> ErrorObjectOwned {
+    match e {
+        AmmSwapError::UntrackedPool(_) | AmmSwapError::ValidatorStopped => {
+            rpc_err(jsonrpsee::types::error::INTERNAL_ERROR_CODE, e.to_string(), None)
+        }
+        e => invalid_params_rpc_err(e.to_string())
+    }
+}
+
+/// Fills as much of `amount_in` as possible from the resting orders on the
+/// other side of the pair that are priced better than the pool, best first.
+fn fill_from_book(
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    spot_price: Ray,
+    orders: &[AllOrders]
+) -> QuoteFill {
+    // prices are t1/t0 on both sides, selling t0 takes the bids and buying it
+    // takes the asks
+    let sells_t0 = token_in < token_out;
+    let mut counterparties = orders
+        .iter()
+        .filter(|order| !matches!(order, AllOrders::TOB(_)))
+        .filter(|order| order.token_in() == token_out && order.token_out() == token_in)
+        .map(|order| (Ray::from(order.limit_price()), order))
+        .filter(|(price, _)| {
+            !price.is_zero() && if sells_t0 { *price > spot_price } else { *price < spot_price }
+        })
+        .collect::<Vec<_>>();
+    counterparties.sort_by(|(a, _), (b, _)| if sells_t0 { b.cmp(a) } else { a.cmp(b) });
+
+    let mut fill = QuoteFill::default();
+    for (price, order) in counterparties {
+        let remaining = amount_in - fill.amount_in;
+        if remaining.is_zero() {
+            break
+        }
+
+        let offered = U256::from(order.amount_in());
+        // how much of `token_in` the order wants for everything it offers. A
+        // level whose amounts overflow can't be quoted and is skipped
+        let Ok(capacity) = (if sells_t0 {
+            price.checked_inverse_quantity(offered, Rounding::Up)
+        } else {
+            price.checked_mul_quantity(offered, Rounding::Up)
+        }) else {
+            continue
+        };
+        let (taken, received) = if capacity <= remaining {
+            (capacity, offered)
+        } else if is_partial(order) {
+            // what the taker receives rounds down, against them
+            let Ok(received) = (if sells_t0 {
+                price.checked_mul_quantity(remaining, Rounding::Down)
+            } else {
+                price.checked_inverse_quantity(remaining, Rounding::Down)
+            }) else {
+                continue
+            };
+            (remaining, received.min(offered))
+        } else {
+            continue
+        };
+
+        fill.amount_in += taken;
+        fill.amount_out += received;
+        fill.orders += 1;
+    }
+
+    fill
+}
+
+/// Aggregates the limit orders of the pool by price, keeping the best `levels`
+/// prices on either side.
+fn book_depth(
+    pool_id: PoolId,
+    orders: &[AllOrders],
+    amm: Option<&PoolSnapshot>,
+    levels: usize
+) -> BookDepth {
+    let mut bids = BTreeMap::<Ray, DepthLevel>::new();
+    let mut asks = BTreeMap::<Ray, DepthLevel>::new();
+    for order in orders
+        .iter()
+        .filter(|order| !matches!(order, AllOrders::TOB(_)))
+    {
+        let price = Ray::from(order.limit_price());
+        let offered = U256::from(order.amount_in());
+        // bids offer t1 for t0, asks offer t0 for t1
+        let is_bid = order.token_in() > order.token_out();
+        let (side, quantity) = if is_bid {
+            let Ok(quantity) = price::t0_for_t1(price, offered, Rounding::Down) else { continue };
+            (&mut bids, quantity)
+        } else {
+            (&mut asks, offered)
+        };
+
+        let level = side
+            .entry(price)
+            .or_insert_with(|| DepthLevel { price, ..Default::default() });
+        level.quantity += quantity;
+        level.orders += 1;
+    }
+
+    BookDepth {
+        pool_id,
+        spot_price: amm.map(|amm| Ray::from(amm.current_price().as_sqrtpricex96())),
+        bids: with_amm_liquidity(bids.into_values().rev().take(levels), amm, true),
+        asks: with_amm_liquidity(asks.into_values().take(levels), amm, false)
+    }
+}
+
+/// Adds what the pool trades between consecutive levels of one side. The pool
+/// bids below its price and asks above it.
+fn with_amm_liquidity(
+    levels: impl Iterator<Item = DepthLevel>,
+    amm: Option<&PoolSnapshot>,
+    is_bid: bool
+) -> Vec<DepthLevel> {
+    let Some(amm) = amm else { return levels.collect() };
+
+    let mut traded = U256::ZERO;
+    levels
+        .map(|mut level| {
+            let target = if is_bid {
+                BidPrice(level.price).to_sqrt_price()
+            } else {
+                AskPrice(level.price).to_sqrt_price()
+            };
+            let to_level = target
+                .ok()
+                .and_then(|target| amm.current_price().order_to_target(Some(target), !is_bid))
+                .map_or(U256::ZERO, |swap| swap.d_t0);
+            level.amm_quantity = to_level.saturating_sub(traded);
+            traded = traded.max(to_level);
+            level
+        })
+        .collect()
+}
+
+fn is_partial(order: &AllOrders) -> bool {
+    matches!(
+        order,
+        AllOrders::Standing(StandingVariants::Partial(_))
+            | AllOrders::Flash(FlashVariants::Partial(_))
+    )
+}
+
+/// Positive when the swap executes at a worse price than the spot price.
+fn price_impact_bps(sells_t0: bool, execution_price: Ray, spot_price: Ray) -> i64 {
+    let ratio = execution_price.as_f64() / spot_price.as_f64();
+    let impact = if sells_t0 { 1.0 - ratio } else { ratio - 1.0 };
+    (impact * 10_000.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::rpc_orders::{ExactStandingOrder, PartialStandingOrder};
+
+    use super::*;
+
+    fn bid(min_price: Ray, amount_in: u128) -> AllOrders {
+        AllOrders::Standing(StandingVariants::Partial(PartialStandingOrder {
+            maxAmountIn: amount_in,
+            minPrice: *min_price,
+            assetIn: Address::with_last_byte(2),
+            assetOut: Address::with_last_byte(1),
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn fills_the_best_bids_above_spot_first() {
+        let (t0, t1) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let spot = Ray::calc_price(U256::from(1), U256::from(1));
+        let orders = vec![
+            bid(Ray::calc_price(U256::from(1), U256::from(2)), 100),
+            // below spot, the pool is the better fill
+            bid(Ray::calc_price(U256::from(2), U256::from(1)), 100),
+            bid(Ray::calc_price(U256::from(1), U256::from(4)), 40),
+            AllOrders::Standing(StandingVariants::Exact(ExactStandingOrder {
+                amount: 1_000,
+                minPrice: *Ray::calc_price(U256::from(1), U256::from(3)),
+                assetIn: t1,
+                assetOut: t0,
+                ..Default::default()
+            })),
+        ];
+
+        // 10 t0 at 4 then the remaining 50 at 2, the exact order is too large
+        let fill = fill_from_book(t0, t1, U256::from(60), spot, &orders);
+        assert_eq!(fill.amount_in, U256::from(60));
+        assert_eq!(fill.amount_out, U256::from(140));
+        assert_eq!(fill.orders, 2);
+
+        let execution_price = Ray::calc_price(U256::from(60), U256::from(140));
+        assert!(price_impact_bps(true, execution_price, spot) < 0);
+    }
+
+    #[test]
+    fn skips_levels_whose_amounts_overflow() {
+        let (t0, t1) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let ask = |min_price: Ray, amount_in: u128| {
+            AllOrders::Standing(StandingVariants::Partial(PartialStandingOrder {
+                maxAmountIn: amount_in,
+                minPrice: *min_price,
+                assetIn: t0,
+                assetOut: t1,
+                ..Default::default()
+            }))
+        };
+        let orders = vec![
+            ask(Ray::calc_price(U256::from(1), U256::from(2)), 10),
+            // all of its t0 is worth more t1 than fits in a U256
+            ask(Ray::from(U256::MAX / U256::from(2)), u128::MAX),
+        ];
+
+        // 10 t0 for 20 t1, the rest of the t1 finds no other level
+        let fill = fill_from_book(t1, t0, U256::from(100), Ray::from(U256::MAX), &orders);
+        assert_eq!(fill.amount_in, U256::from(20));
+        assert_eq!(fill.amount_out, U256::from(10));
+        assert_eq!(fill.orders, 1);
+    }
+
+    #[test]
+    fn aggregates_the_best_levels_of_either_side() {
+        let ask = |min_price: Ray, amount_in: u128| {
+            AllOrders::Standing(StandingVariants::Partial(PartialStandingOrder {
+                maxAmountIn: amount_in,
+                minPrice: *min_price,
+                assetIn: Address::with_last_byte(1),
+                assetOut: Address::with_last_byte(2),
+                ..Default::default()
+            }))
+        };
+        let price = |t1: u64| Ray::calc_price(U256::from(1), U256::from(t1));
+        let orders = vec![
+            bid(price(2), 100),
+            bid(price(2), 40),
+            bid(price(3), 30),
+            bid(price(1), 10),
+            ask(price(4), 50),
+            ask(price(5), 20),
+        ];
+
+        let depth = book_depth(PoolId::default(), &orders, None, 2);
+        assert_eq!(depth.spot_price, None);
+        // bid quantities are the t0 their t1 buys
+        assert_eq!(
+            depth.bids,
+            vec![
+                DepthLevel {
+                    price: price(3),
+                    quantity: U256::from(10),
+                    orders: 1,
+                    ..Default::default()
+                },
+                DepthLevel {
+                    price: price(2),
+                    quantity: U256::from(70),
+                    orders: 2,
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            depth.asks,
+            vec![
+                DepthLevel {
+                    price: price(4),
+                    quantity: U256::from(50),
+                    orders: 1,
+                    ..Default::default()
+                },
+                DepthLevel {
+                    price: price(5),
+                    quantity: U256::from(20),
+                    orders: 1,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_bid_without_a_synced_pool() {
+        let order = OrderWithStorageData {
+            order: TopOfBlockOrder { quantityIn: 500, validForBlock: 7, ..Default::default() },
+            tob_reward: U256::from(120),
+            ..Default::default()
+        };
+
+        let status = auction_status(PoolId::default(), &order, None);
+        assert_eq!(status.valid_for_block, 7);
+        assert_eq!(status.quantity_in, 500);
+        assert_eq!(status.net_bid, U256::from(120));
+        assert_eq!(status.tribute, None);
+    }
+
+    #[test]
+    fn only_searcher_orders_move_the_auction() {
+        let tob = AllOrders::TOB(TopOfBlockOrder::default());
+        assert!(moves_the_auction(&PoolManagerUpdate::NewOrder(tob.clone())));
+        assert!(moves_the_auction(&PoolManagerUpdate::FilledOrder((1, tob))));
+        assert!(moves_the_auction(&PoolManagerUpdate::CancelledOrder(Default::default())));
+        assert!(!moves_the_auction(&PoolManagerUpdate::NewOrder(bid(Ray::from(U256::from(1)), 1))));
+        assert!(!moves_the_auction(&PoolManagerUpdate::ExpiredOrder(Default::default())));
     }
 }
@@ -2,6 +2,7 @@
 
 pub mod api;
 pub mod impls;
+pub mod screening;
 pub mod types;
 
 pub use impls::*;
@@ -0,0 +1,252 @@
+//! Optional screening of the accounts behind the orders submitted over RPC,
+//! for operators that have to keep sanctioned addresses out of their book.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant}
+};
+
+use alloy_primitives::Address;
+use angstrom_errors::ValidationError;
+use angstrom_types::sol_bindings::{
+    grouped_orders::{AllOrders, FlashVariants, StandingVariants},
+    RawPoolOrder
+};
+
+/// How long the answer for an address is reused before asking again.
+pub const DEFAULT_SCREENING_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Most addresses whose answer is cached, the oldest answers are dropped past
+/// it.
+pub const DEFAULT_SCREENING_CACHE_SIZE: usize = 100_000;
+
+pub type ScreeningFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<bool, ScreeningError>> + Send + 'a>>;
+
+/// Decides whether orders of an address are turned away.
+pub trait AddressScreening: Send + Sync {
+    /// `true` when the address is blocked.
+    fn is_blocked(&self, address: Address) -> ScreeningFuture<'_>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScreeningError {
+    #[error("screening service is unavailable: {0}")]
+    Unavailable(String)
+}
+
+/// Blocks a fixed list of addresses.
+#[derive(Debug, Clone, Default)]
+pub struct AddressListScreening(HashSet<Address>);
+
+impl AddressListScreening {
+    pub fn new(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self(addresses.into_iter().collect())
+    }
+}
+
+impl AddressScreening for AddressListScreening {
+    fn is_blocked(&self, address: Address) -> ScreeningFuture<'_> {
+        let blocked = self.0.contains(&address);
+        Box::pin(async move { Ok(blocked) })
+    }
+}
+
+/// What happens to an order when its accounts can't be screened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScreeningFailureMode {
+    /// the order is let through
+    #[default]
+    Open,
+    /// the order is rejected
+    Closed
+}
+
+/// Screens the signer and the recipient of orders, caching the answers.
+pub struct OrderScreener {
+    screening:    Arc<dyn AddressScreening>,
+    failure_mode: ScreeningFailureMode,
+    cache_ttl:    Duration,
+    cache:        Mutex<ScreeningCache>
+}
+
+impl OrderScreener {
+    pub fn new(screening: Arc<dyn AddressScreening>) -> Self {
+        Self {
+            screening,
+            failure_mode: ScreeningFailureMode::default(),
+            cache_ttl: DEFAULT_SCREENING_CACHE_TTL,
+            cache: Mutex::new(ScreeningCache::new(DEFAULT_SCREENING_CACHE_SIZE))
+        }
+    }
+
+    pub fn with_failure_mode(mut self, failure_mode: ScreeningFailureMode) -> Self {
+        self.failure_mode = failure_mode;
+        self
+    }
+
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    pub fn with_cache_size(mut self, cache_size: usize) -> Self {
+        self.cache = Mutex::new(ScreeningCache::new(cache_size));
+        self
+    }
+
+    pub async fn check(&self, order: &AllOrders) -> Result<(), ValidationError> {
+        for address in order_accounts(order) {
+            match self.is_blocked(address).await {
+                Ok(false) => {}
+                Ok(true) => return Err(ValidationError::Screened),
+                Err(err) => {
+                    tracing::warn!(error = %err, %address, "failed to screen order");
+                    if self.failure_mode == ScreeningFailureMode::Closed {
+                        return Err(ValidationError::Screened)
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn is_blocked(&self, address: Address) -> Result<bool, ScreeningError> {
+        if let Some((blocked, at)) = self.cache.lock().expect("poisoned").answers.get(&address) {
+            if at.elapsed() < self.cache_ttl {
+                return Ok(*blocked)
+            }
+        }
+
+        // failures aren't cached, so the next order asks again
+        let blocked = self.screening.is_blocked(address).await?;
+        self.cache
+            .lock()
+            .expect("poisoned")
+            .insert(address, (blocked, Instant::now()));
+
+        Ok(blocked)
+    }
+}
+
+/// Screening answers by address, the oldest are dropped once `limit`
+/// addresses are cached. An answer that is renewed keeps its place.
+#[derive(Debug)]
+struct ScreeningCache {
+    limit:   usize,
+    answers: HashMap<Address, (bool, Instant)>,
+    /// cached addresses, oldest first
+    order:   VecDeque<Address>
+}
+
+impl ScreeningCache {
+    fn new(limit: usize) -> Self {
+        Self { limit, answers: HashMap::new(), order: VecDeque::new() }
+    }
+
+    fn insert(&mut self, address: Address, answer: (bool, Instant)) {
+        if self.answers.insert(address, answer).is_none() {
+            self.order.push_back(address);
+        }
+        while self.answers.len() > self.limit {
+            let Some(oldest) = self.order.pop_front() else { break };
+            self.answers.remove(&oldest);
+        }
+    }
+}
+
+/// The signer and, when it is set, the recipient of the order.
+fn order_accounts(order: &AllOrders) -> Vec<Address> {
+    let recipient = match order {
+        AllOrders::Standing(StandingVariants::Partial(o)) => o.recipient,
+        AllOrders::Standing(StandingVariants::Exact(o)) => o.recipient,
+        AllOrders::Flash(FlashVariants::Partial(o)) => o.recipient,
+        AllOrders::Flash(FlashVariants::Exact(o)) => o.recipient,
+        AllOrders::TOB(o) => o.recipient
+    };
+
+    let mut accounts = vec![order.from()];
+    if !recipient.is_zero() && recipient != order.from() {
+        accounts.push(recipient);
+    }
+    accounts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use angstrom_types::sol_bindings::rpc_orders::{ExactFlashOrder, OrderMeta};
+
+    use super::*;
+
+    /// Counts the calls, failing all of them when `fail` is set.
+    #[derive(Default)]
+    struct Service {
+        calls: AtomicUsize,
+        fail:  bool
+    }
+
+    impl AddressScreening for Service {
+        fn is_blocked(&self, address: Address) -> ScreeningFuture<'_> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let result = if self.fail {
+                Err(ScreeningError::Unavailable("down".to_string()))
+            } else {
+                Ok(address == Address::with_last_byte(2))
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    fn order(from: u8, recipient: u8) -> AllOrders {
+        AllOrders::Flash(FlashVariants::Exact(ExactFlashOrder {
+            recipient: Address::with_last_byte(recipient),
+            meta: OrderMeta { from: Address::with_last_byte(from), ..Default::default() },
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn screens_signer_and_recipient() {
+        let service = Arc::new(Service::default());
+        let screener = OrderScreener::new(service.clone());
+
+        assert_eq!(screener.check(&order(1, 0)).await, Ok(()));
+        assert_eq!(screener.check(&order(2, 0)).await, Err(ValidationError::Screened));
+        assert_eq!(screener.check(&order(1, 2)).await, Err(ValidationError::Screened));
+        // both addresses were cached on the first two calls
+        assert_eq!(service.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_drops_the_oldest_answers() {
+        let service = Arc::new(Service::default());
+        let screener = OrderScreener::new(service.clone()).with_cache_size(2);
+
+        for from in [1, 3, 4] {
+            assert_eq!(screener.check(&order(from, 0)).await, Ok(()));
+        }
+        assert_eq!(screener.cache.lock().unwrap().answers.len(), 2);
+
+        // the first answer was dropped, the last two are still cached
+        assert_eq!(screener.check(&order(4, 3)).await, Ok(()));
+        assert_eq!(service.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(screener.check(&order(1, 0)).await, Ok(()));
+        assert_eq!(service.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn fails_open_or_closed() {
+        let service = Arc::new(Service { fail: true, ..Default::default() });
+
+        let open = OrderScreener::new(service.clone());
+        assert_eq!(open.check(&order(1, 0)).await, Ok(()));
+
+        let closed = OrderScreener::new(service).with_failure_mode(ScreeningFailureMode::Closed);
+        assert_eq!(closed.check(&order(1, 0)).await, Err(ValidationError::Screened));
+    }
+}
@@ -0,0 +1,17 @@
+use alloy_primitives::BlockNumber;
+use consensus::summary::RoundSummary;
+use order_pool::surveillance::BlockOrderActivity;
+use serde::{Deserialize, Serialize};
+
+/// Activity of the node over a single block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockSummary {
+    pub block_number: BlockNumber,
+    /// orders received, validated and filled, along with the pools they
+    /// filled in
+    pub orders:       Option<BlockOrderActivity>,
+    /// phase durations, bundle hash and peer count of the consensus round,
+    /// always `None` on relay only nodes
+    pub round:        Option<RoundSummary>
+}
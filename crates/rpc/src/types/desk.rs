@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// An api key that scopes access to the `desk` namespace, allowing an operator
+/// to share a node between multiple trading desks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyConfig {
+    /// name of the desk, used as the metric label so that the key itself is
+    /// never exposed
+    pub name:              String,
+    pub key:               String,
+    /// max orders that can be submitted within a rolling minute
+    pub orders_per_minute: u32,
+    /// max orders that can be open at any given time
+    pub max_open_orders:   usize
+}
+
+/// Usage of an api key since the node started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyUsage {
+    pub name:               String,
+    pub submitted_orders:   u64,
+    pub rejected_orders:    u64,
+    pub open_orders:        usize,
+    pub max_open_orders:    usize,
+    /// orders submitted within the last minute
+    pub orders_last_minute: u32,
+    pub orders_per_minute:  u32
+}
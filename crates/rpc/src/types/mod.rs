@@ -1,5 +1,9 @@
+pub mod dashboard;
+pub mod desk;
 pub mod quoting;
 pub mod subscriptions;
 
+pub use dashboard::*;
+pub use desk::*;
 pub use quoting::*;
 pub use subscriptions::*;
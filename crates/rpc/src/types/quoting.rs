@@ -1,7 +1,81 @@
-use alloy_primitives::U256;
-use angstrom_types::primitive::Angstrom::PoolKey;
+use alloy_primitives::{B256, U256};
+use angstrom_types::{
+    matching::Ray,
+    primitive::{Angstrom::PoolKey, PoolId}
+};
 use serde::{Deserialize, Serialize};
 
+/// Indicative execution of a swap against the resting orders and the pool.
+/// Nothing is reserved, the actual fill depends on what lands before it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapQuote {
+    pub pool_id:          PoolId,
+    pub amount_in:        U256,
+    pub amount_out:       U256,
+    /// average price of the swap, as t1/t0
+    pub execution_price:  Ray,
+    /// price of the pool before the swap, as t1/t0
+    pub spot_price:       Ray,
+    /// how much worse than the spot price the swap executes, negative if the
+    /// book fills it at better prices
+    pub price_impact_bps: i64,
+    pub book:             QuoteFill,
+    pub amm:              QuoteFill
+}
+
+/// The part of a quoted swap filled by a single source of liquidity.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteFill {
+    pub amount_in:  U256,
+    pub amount_out: U256,
+    /// number of resting orders filled, always zero for the pool
+    pub orders:     usize
+}
+
+/// Resting liquidity of a pool, best levels first on either side.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BookDepth {
+    pub pool_id:    PoolId,
+    /// price of the pool, as t1/t0. `None` when the pool isn't synced
+    pub spot_price: Option<Ray>,
+    pub bids:       Vec<DepthLevel>,
+    pub asks:       Vec<DepthLevel>
+}
+
+/// The resting orders at a single price, along with what the pool trades on
+/// its way to that price.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthLevel {
+    /// limit price of the orders, as t1/t0
+    pub price:        Ray,
+    /// t0 bought or sold by the orders
+    pub quantity:     U256,
+    pub orders:       usize,
+    /// t0 the pool trades between the previous level, or its current price,
+    /// and this one
+    pub amm_quantity: U256
+}
+
+/// The searcher order currently winning the top of block auction of a pool.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TobAuctionStatus {
+    pub pool_id:         PoolId,
+    pub order_hash:      B256,
+    /// the block the order bids for
+    pub valid_for_block: u64,
+    pub quantity_in:     u128,
+    /// what is left of the quantity in once the swap of the order is paid for
+    pub net_bid:         U256,
+    /// the part of the net bid that isn't donated to the liquidity of the
+    /// pool. `None` when the pool isn't synced
+    pub tribute:         Option<U256>
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct BBO {
     pub pool:   PoolKey,
@@ -1,9 +1,13 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256};
 use angstrom_types::{
-    consensus::*, primitive::Angstrom::PoolKey, sol_bindings::grouped_orders::AllOrders
+    consensus::*,
+    matching::{FormattedPrice, Ray},
+    primitive::Angstrom::PoolKey,
+    sol_bindings::{ext::RawPoolOrder, grouped_orders::AllOrders}
 };
+use order_pool::UnfilledOrders;
 use serde::{Deserialize, Serialize};
 
 use super::quoting::{Depth25, Depth5, BBO};
@@ -18,7 +22,10 @@ pub enum ConsensusSubscriptionKind {
     /// current best
     NewBestPreProposal,
     /// Sends the proposal upon receiving it from the proposer
-    Proposal
+    Proposal,
+    /// Sends the signed attestation of every bundle this node proposes or
+    /// checks
+    BundleAttestations
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,7 +34,8 @@ pub enum ConsensusSubscriptionKind {
 pub enum ConsensusSubscriptionResult {
     /// Preprosal
     PreProposal(Arc<PreProposal>),
-    Proposal(Arc<Proposal>)
+    Proposal(Arc<Proposal>),
+    BundleAttestation(BundleAttestation)
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,20 +46,63 @@ pub enum OrderSubscriptionKind {
     NewOrders,
     /// Any new filled orders
     FilledOrders,
-    /// Any new reorged orders
+    /// The orders left unfilled by each block, with the reason why
     UnfilleOrders,
     /// Any new cancelled orders
-    CancelledOrders
+    CancelledOrders,
+    /// Good-til-cancelled orders that could not be renewed
+    ExpiredOrders,
+    /// New, filled and unfilled orders in a single stream
+    OrderFlow
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
 pub enum OrderSubscriptionResult {
-    NewOrder(AllOrders),
-    FilledOrder((u64, AllOrders)),
-    UnfilledOrder(AllOrders),
-    CancelledOrder(B256)
+    NewOrder(PricedOrder),
+    FilledOrder((u64, PricedOrder)),
+    UnfilledOrders(UnfilledOrders),
+    CancelledOrder(B256),
+    ExpiredOrder(B256)
+}
+
+/// An order update along with the sequence number of the pool update it
+/// originates from, see `bookSnapshot`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SequencedOrderSubscriptionResult {
+    pub seq:    u64,
+    pub update: OrderSubscriptionResult
+}
+
+/// An order along with its limit price in both raw and human readable form.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PricedOrder {
+    pub order: AllOrders,
+    /// `None` for top of block orders, as they don't have a limit price
+    pub price: Option<FormattedPrice>
+}
+
+impl PricedOrder {
+    pub fn new(order: AllOrders, token_decimals: &HashMap<Address, u8>) -> Self {
+        let price = (!matches!(order, AllOrders::TOB(_))).then(|| {
+            let (token0, token1) = if order.token_in() < order.token_out() {
+                (order.token_in(), order.token_out())
+            } else {
+                (order.token_out(), order.token_in())
+            };
+            let decimals = token_decimals
+                .get(&token0)
+                .zip(token_decimals.get(&token1))
+                .map(|(d0, d1)| (*d0, *d1));
+
+            FormattedPrice::from_ray(Ray::from(order.limit_price()), decimals)
+        });
+
+        Self { order, price }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
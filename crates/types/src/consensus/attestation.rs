@@ -0,0 +1,114 @@
+use alloy::primitives::{keccak256, BlockNumber, B256};
+use bytes::Bytes;
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+
+use super::Proposal;
+use crate::{
+    contract_payloads::angstrom::AngstromBundle,
+    primitive::{PeerId, Signature}
+};
+
+/// What the signer of an attestation did with the proposal.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttestationKind {
+    /// the leader built and proposed the bundle
+    Proposed,
+    /// the bundle matched what we computed from the same pre-proposals
+    Verified,
+    /// the bundle didn't match what we computed from the same pre-proposals
+    Rejected
+}
+
+/// A signed digest of a proposed bundle. The leader publishes one for every
+/// bundle it proposes and the other validators one for every proposal they
+/// check, leaving an off-chain audit trail of who saw which bundle.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleAttestation {
+    pub block_height: BlockNumber,
+    /// the node that signed the attestation
+    pub source:       PeerId,
+    /// the leader that proposed the bundle
+    pub proposer:     PeerId,
+    pub kind:         AttestationKind,
+    /// see [`AngstromBundle::bundle_hash`]
+    pub bundle_hash:  B256,
+    pub order_count:  u64,
+    /// signature over all of the above
+    pub signature:    Signature
+}
+
+impl BundleAttestation {
+    pub fn new(
+        kind: AttestationKind,
+        proposal: &Proposal,
+        bundle: &AngstromBundle,
+        source: PeerId,
+        sk: &SecretKey
+    ) -> Self {
+        let mut attestation = Self {
+            block_height: proposal.block_height,
+            source,
+            proposer: proposal.source,
+            kind,
+            bundle_hash: bundle.bundle_hash(),
+            order_count: proposal.order_count() as u64,
+            signature: Signature::default()
+        };
+
+        let hash = keccak256(attestation.payload());
+        let sig = reth_primitives::sign_message(sk.secret_bytes().into(), hash).unwrap();
+        attestation.signature = Signature(sig);
+        attestation
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let hash = keccak256(self.payload());
+        let Ok(source) = self.signature.recover_signer_full_public_key(hash) else {
+            return false;
+        };
+        source == self.source
+    }
+
+    fn payload(&self) -> Bytes {
+        let mut buf = vec![];
+        buf.extend(bincode::serialize(&self.block_height).unwrap());
+        buf.extend(*self.source);
+        buf.extend(*self.proposer);
+        buf.extend(bincode::serialize(&self.kind).unwrap());
+        buf.extend(self.bundle_hash);
+        buf.extend(bincode::serialize(&self.order_count).unwrap());
+
+        Bytes::from_iter(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+    use reth_network_peers::pk2id;
+    use secp256k1::Secp256k1;
+
+    use super::*;
+
+    #[test]
+    fn attestations_are_signed_by_their_source() {
+        let sk = SecretKey::new(&mut thread_rng());
+        let source = pk2id(&sk.public_key(&Secp256k1::new()));
+        let proposal = Proposal::generate_proposal(100, source, vec![], vec![], &sk);
+        let bundle =
+            AngstromBundle::from_proposal(&proposal, &Default::default(), Default::default())
+                .unwrap();
+
+        let attestation =
+            BundleAttestation::new(AttestationKind::Verified, &proposal, &bundle, source, &sk);
+        assert!(attestation.is_valid());
+        assert_eq!(attestation.bundle_hash, bundle.bundle_hash());
+        assert_eq!(attestation.order_count, 0);
+
+        let tampered = BundleAttestation { kind: AttestationKind::Rejected, ..attestation };
+        assert!(!tampered.is_valid());
+    }
+}
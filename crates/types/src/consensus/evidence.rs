@@ -6,6 +6,14 @@ pub enum EvidenceError {
     InvalidEvidence
 }
 
+impl From<EvidenceError> for angstrom_errors::ConsensusError {
+    fn from(value: EvidenceError) -> Self {
+        match value {
+            EvidenceError::InvalidEvidence => Self::InvalidEvidence
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Evidence {
     DuplicateVoteEvidence(DuplicateVoteEvidence)
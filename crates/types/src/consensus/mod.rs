@@ -1,8 +1,10 @@
+pub mod attestation;
 pub mod evidence;
 pub mod order_buffer;
 pub mod pre_prepose;
 pub mod proposal;
 
+pub use attestation::*;
 pub use evidence::*;
 pub use order_buffer::*;
 pub use pre_prepose::*;
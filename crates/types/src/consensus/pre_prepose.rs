@@ -3,7 +3,7 @@ use std::{
     hash::{Hash, Hasher}
 };
 
-use alloy::primitives::{keccak256, BlockNumber};
+use alloy::primitives::{keccak256, BlockNumber, B256};
 use bytes::Bytes;
 use reth_network_peers::PeerId;
 use secp256k1::SecretKey;
@@ -115,6 +115,11 @@ impl PreProposal {
         source == self.source
     }
 
+    /// Hash of the signed payload, which identifies the pre-proposal.
+    pub fn payload_hash(&self) -> B256 {
+        keccak256(self.payload())
+    }
+
     fn serialize_payload(
         block_height: &BlockNumber,
         limit: &Vec<OrderWithStorageData<GroupedVanillaOrder>>,
@@ -131,13 +136,54 @@ impl PreProposal {
         Bytes::from(Self::serialize_payload(&self.block_height, &self.limit, &self.searcher))
     }
 
+    /// The pre-proposals without the orders in `excluded`. Their signatures
+    /// no longer cover them once an order is left out, so they are only good
+    /// for matching.
+    pub fn without_orders(preproposals: &[PreProposal], excluded: &[B256]) -> Vec<PreProposal> {
+        if excluded.is_empty() {
+            return preproposals.to_vec()
+        }
+        let excluded = excluded.iter().collect::<HashSet<_>>();
+
+        preproposals
+            .iter()
+            .map(|preproposal| {
+                let mut preproposal = preproposal.clone();
+                preproposal
+                    .limit
+                    .retain(|order| !excluded.contains(&order.order_id.hash));
+                preproposal
+                    .searcher
+                    .retain(|order| !excluded.contains(&order.order_id.hash));
+                preproposal
+            })
+            .collect()
+    }
+
+    /// The limit orders of all pre-proposals by pool. An order in several
+    /// pre-proposals is taken with the median of the times it was received at,
+    /// so that a single validator can't move it ahead and every node ranks it
+    /// the same.
     pub fn orders_by_pool_id(
         preproposals: &[PreProposal]
     ) -> HashMap<PoolId, HashSet<OrderWithStorageData<GroupedVanillaOrder>>> {
         preproposals
             .iter()
             .flat_map(|p| p.limit.iter())
-            .cloned()
+            .fold(HashMap::<B256, (&OrderWithStorageData<_>, Vec<u64>)>::new(), |mut acc, order| {
+                acc.entry(order.order_id.hash)
+                    .or_insert_with(|| (order, vec![]))
+                    .1
+                    .push(order.priority_data.received_at);
+                acc
+            })
+            .into_values()
+            .map(|(order, mut received_at)| {
+                received_at.sort_unstable();
+                let mut order = order.clone();
+                order.priority_data.received_at = received_at[(received_at.len() - 1) / 2];
+                order
+            })
             .fold(HashMap::new(), |mut acc, order| {
                 acc.entry(order.pool_id).or_default().insert(order);
                 acc
@@ -153,6 +199,10 @@ mod tests {
     use secp256k1::Secp256k1;
 
     use super::{PreProposal, SecretKey};
+    use crate::{
+        orders::{OrderId, OrderPriorityData},
+        sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
+    };
 
     #[test]
     fn can_be_constructed() {
@@ -181,4 +231,30 @@ mod tests {
 
         assert!(preproposal.is_valid(), "Unable to validate self");
     }
+
+    #[test]
+    fn merges_orders_at_the_median_arrival() {
+        let order = |received_at| OrderWithStorageData::<GroupedVanillaOrder> {
+            priority_data: OrderPriorityData { received_at, ..Default::default() },
+            order_id: OrderId { hash: FixedBytes::with_last_byte(1), ..Default::default() },
+            ..Default::default()
+        };
+        let preproposal = |limit| PreProposal { limit, ..Default::default() };
+        let merged = |preproposals: &[PreProposal]| {
+            PreProposal::orders_by_pool_id(preproposals)
+                .into_values()
+                .flatten()
+                .map(|order| order.priority_data.received_at)
+                .collect::<Vec<_>>()
+        };
+
+        // an outlier stamp doesn't move the order ahead
+        let preproposals = [
+            preproposal(vec![order(500)]),
+            preproposal(vec![order(1)]),
+            preproposal(vec![order(400)])
+        ];
+        assert_eq!(merged(&preproposals), [400]);
+        assert_eq!(merged(&preproposals[..2]), [1]);
+    }
 }
@@ -1,4 +1,4 @@
-use alloy::primitives::BlockNumber;
+use alloy::primitives::{BlockNumber, B256};
 use alloy_primitives::keccak256;
 use bytes::Bytes;
 use secp256k1::SecretKey;
@@ -19,6 +19,9 @@ pub struct Proposal {
     pub preproposals: Vec<PreProposal>,
     /// PoolSolutions sorted by PoolId
     pub solutions:    Vec<PoolSolution>,
+    /// Orders of the preproposals left out of matching, as they made the
+    /// bundle revert. Sorted by hash
+    pub excluded:     Vec<B256>,
     /// This signature is over (etheruem_block | hash(vanilla_bundle) |
     /// hash(order_buffer) | hash(lower_bound))
     pub signature:    Signature
@@ -26,14 +29,35 @@ pub struct Proposal {
 
 impl Proposal {
     pub fn generate_proposal(
+        ethereum_height: BlockNumber,
+        source: PeerId,
+        preproposals: Vec<PreProposal>,
+        solutions: Vec<PoolSolution>,
+        sk: &SecretKey
+    ) -> Self {
+        Self::generate_trimmed_proposal(
+            ethereum_height,
+            source,
+            preproposals,
+            solutions,
+            vec![],
+            sk
+        )
+    }
+
+    /// A proposal whose solutions were matched without the `excluded` orders
+    /// of the preproposals.
+    pub fn generate_trimmed_proposal(
         ethereum_height: BlockNumber,
         source: PeerId,
         preproposals: Vec<PreProposal>,
         mut solutions: Vec<PoolSolution>,
+        mut excluded: Vec<B256>,
         sk: &SecretKey
     ) -> Self {
         // Sort our solutions
         solutions.sort_by_key(|sol| sol.id);
+        excluded.sort_unstable();
 
         // Build our hash and sign
         let mut buf = Vec::new();
@@ -41,6 +65,7 @@ impl Proposal {
         buf.extend(*source);
         buf.extend(bincode::serialize(&preproposals).unwrap());
         buf.extend(bincode::serialize(&solutions).unwrap());
+        buf.extend(bincode::serialize(&excluded).unwrap());
 
         let hash = keccak256(buf);
         let sig = reth_primitives::sign_message(sk.secret_bytes().into(), hash).unwrap();
@@ -50,6 +75,7 @@ impl Proposal {
             source,
             preproposals,
             solutions,
+            excluded,
             signature: Signature(sig)
         }
     }
@@ -58,6 +84,27 @@ impl Proposal {
         &self.preproposals
     }
 
+    /// The preproposals without the excluded orders, which is what the
+    /// solutions were matched from.
+    pub fn matched_preproposals(&self) -> Vec<PreProposal> {
+        PreProposal::without_orders(&self.preproposals, &self.excluded)
+    }
+
+    /// Number of searcher and limit orders filled by the bundle.
+    pub fn order_count(&self) -> usize {
+        self.solutions
+            .iter()
+            .map(|solution| {
+                solution.searcher.iter().count()
+                    + solution
+                        .limit
+                        .iter()
+                        .filter(|order| order.is_filled())
+                        .count()
+            })
+            .sum()
+    }
+
     pub fn is_valid(&self) -> bool {
         // All our preproposals have to be valid
         if !self.preproposals.iter().all(|i| i.is_valid()) {
@@ -77,6 +124,7 @@ impl Proposal {
         buf.extend(*self.source);
         buf.extend(bincode::serialize(&self.preproposals).unwrap());
         buf.extend(bincode::serialize(&self.solutions).unwrap());
+        buf.extend(bincode::serialize(&self.excluded).unwrap());
 
         Bytes::from_iter(buf)
     }
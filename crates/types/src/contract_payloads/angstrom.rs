@@ -1,23 +1,29 @@
 use std::collections::HashMap;
 
-use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, B256, U256};
+use alloy::{
+    primitives::{keccak256, Address, Bytes, FixedBytes, B256, U256},
+    sol_types::SolCall
+};
+use pade::PadeEncode as _;
 use pade_macro::{PadeDecode, PadeEncode};
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use super::{
     asset::builder::{AssetBuilder, AssetBuilderStage},
+    netting::SettlementLedger,
     rewards::PoolUpdate,
     tob::ToBOutcome,
     Asset, Pair
 };
 use crate::{
     consensus::{PreProposal, Proposal},
-    matching::{uniswap::PoolSnapshot, Ray},
-    orders::{OrderFillState, OrderOutcome},
+    matching::{price::Rounding, uniswap::PoolSnapshot, Ray},
+    orders::{OrderFillState, OrderOutcome, PriceLevelPriority},
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
-        rpc_orders::TopOfBlockOrder as RpcTopOfBlockOrder
+        rpc_orders::TopOfBlockOrder as RpcTopOfBlockOrder,
+        sol::AngstromContract
     }
 };
 
@@ -66,7 +72,7 @@ impl TopOfBlockOrder {
     }
 }
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub struct StandingValidation {
     nonce:    u64,
     // 40 bits wide in reality
@@ -74,13 +80,13 @@ pub struct StandingValidation {
     deadline: u64
 }
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub enum OrderQuantities {
     Exact { quantity: u128 },
     Partial { min_quantity_in: u128, max_quantity_in: u128, filled_quantity: u128 }
 }
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub struct UserOrder {
     pub use_internal:        bool,
     pub pair_index:          u16,
@@ -137,7 +143,11 @@ impl UserOrder {
     }
 }
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+/// Prefixed to the encoded bundle before hashing so that a bundle hash can't
+/// collide with the hash of any other payload.
+pub const BUNDLE_HASH_DOMAIN: &[u8] = b"AngstromBundle";
+
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub struct AngstromBundle {
     pub assets:              Vec<Asset>,
     pub pairs:               Vec<Pair>,
@@ -147,6 +157,19 @@ pub struct AngstromBundle {
 }
 
 impl AngstromBundle {
+    /// The identifier of the bundle, the keccak of its PADE encoding, i.e. the
+    /// calldata the contract executes, behind [`BUNDLE_HASH_DOMAIN`].
+    pub fn bundle_hash(&self) -> B256 {
+        keccak256([BUNDLE_HASH_DOMAIN, &self.pade_encode()].concat())
+    }
+
+    /// Calldata of `Angstrom::execute` with the bundle.
+    pub fn execute_calldata(&self) -> Bytes {
+        AngstromContract::executeCall { data: self.pade_encode().into() }
+            .abi_encode()
+            .into()
+    }
+
     pub fn get_order_hashes(&self) -> impl Iterator<Item = B256> + '_ {
         self.top_of_block_orders
             .iter()
@@ -154,10 +177,37 @@ impl AngstromBundle {
             .chain(self.user_orders.iter().map(|order| order.order_hash()))
     }
 
+    /// Lays out the orders of every price level by `priority`, which has to
+    /// be the one they were matched with.
     pub fn from_proposal(
         proposal: &Proposal,
-        pools: &HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)>
+        pools: &HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)>,
+        priority: PriceLevelPriority
     ) -> eyre::Result<Self> {
+        Self::build_from_proposal(proposal, pools, priority).map(|(bundle, _)| bundle)
+    }
+
+    /// Builds the bundle along with the user settlements netted across all
+    /// pairs. The contract still settles every order on its own, so the
+    /// netted settlements only show what netting would save. Until the
+    /// contract supports netted settlements this is only available behind the
+    /// `cross-pool-netting` feature.
+    #[cfg(feature = "cross-pool-netting")]
+    pub fn from_proposal_with_netting(
+        proposal: &Proposal,
+        pools: &HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)>,
+        priority: PriceLevelPriority
+    ) -> eyre::Result<(Self, Vec<super::netting::NetSettlement>)> {
+        let (bundle, ledger) = Self::build_from_proposal(proposal, pools, priority)?;
+        Ok((bundle, ledger.net()?))
+    }
+
+    fn build_from_proposal(
+        proposal: &Proposal,
+        pools: &HashMap<FixedBytes<32>, (Address, Address, PoolSnapshot, u16)>,
+        priority: PriceLevelPriority
+    ) -> eyre::Result<(Self, SettlementLedger)> {
+        let mut settlements = SettlementLedger::new();
         let mut top_of_block_orders = Vec::new();
         let mut pool_updates = Vec::new();
         let mut pairs = Vec::new();
@@ -165,10 +215,13 @@ impl AngstromBundle {
         let mut asset_builder = AssetBuilder::new();
 
         // Break out our input orders into lists of orders by pool
-        let orders_by_pool = PreProposal::orders_by_pool_id(&proposal.preproposals);
+        let orders_by_pool = PreProposal::orders_by_pool_id(&proposal.matched_preproposals());
 
-        // Walk through our solutions to add them to the structure
-        for solution in proposal.solutions.iter() {
+        // Walk through our solutions to add them to the structure, in pool order so
+        // that every node lays out the bundle the same way
+        let mut solutions = proposal.solutions.iter().collect::<Vec<_>>();
+        solutions.sort_by_key(|solution| solution.id);
+        for solution in solutions {
             // Get the information for the pool or skip this solution if we can't find a
             // pool for it
             let Some((t0, t1, snapshot, store_index)) = pools.get(&solution.id) else {
@@ -179,6 +232,7 @@ impl AngstromBundle {
             };
             // Make sure the involved assets are in our assets array and we have the
             // appropriate asset index for them
+            debug_assert!(t0 < t1, "pool tokens out of order: {t0} {t1}");
             let t0_idx = asset_builder.add_or_get_asset(*t0) as u16;
             let t1_idx = asset_builder.add_or_get_asset(*t1) as u16;
             // Build our Pair featuring our uniform clearing price
@@ -248,7 +302,7 @@ impl AngstromBundle {
             // Account for our reward
             asset_builder.allocate(AssetBuilderStage::Reward, *t0, tob_outcome.total_reward.to());
             let rewards_update = tob_outcome.to_rewards_update();
-            // Push the pool update
+            // Push the pool update, empty updates are dropped when we canonicalize
             pool_updates.push(PoolUpdate {
                 zero_for_one: false,
                 pair_index: pair_idx as u16,
@@ -267,6 +321,13 @@ impl AngstromBundle {
                     tob.quantityIn,
                     tob.quantityOut
                 );
+                settlements.record_fill(
+                    tob.from(),
+                    asset_in,
+                    asset_out,
+                    tob.quantityIn,
+                    tob.quantityOut
+                );
                 let contract_tob = TopOfBlockOrder::of(tob, asset_in_index, asset_out_index);
                 top_of_block_orders.push(contract_tob);
             }
@@ -279,8 +340,8 @@ impl AngstromBundle {
             // Sort the user order list so we can properly associate it with our
             // OrderOutcomes.  First bids by price then asks by price.
             order_list.sort_by(|a, b| match (a.is_bid, b.is_bid) {
-                (true, true) => b.priority_data.cmp(&a.priority_data),
-                (false, false) => a.priority_data.cmp(&b.priority_data),
+                (true, true) => priority.cmp_bids(a, b),
+                (false, false) => priority.cmp_asks(a, b),
                 (..) => b.is_bid.cmp(&a.is_bid)
             });
             // Loop through our filled user orders, do accounting, and add them to our user
@@ -295,12 +356,14 @@ impl AngstromBundle {
                     OrderFillState::PartialFill(p) => p,
                     _ => order.quantity()
                 };
-                // Calculate the price of this order given the amount filled and the UCP
+                // Calculate the price of this order given the amount filled and the UCP,
+                // rounding what the order pays up so the bundle never pays out dust
                 let quantity_in = if order.is_bid {
-                    Ray::from(ucp).mul_quantity(quantity_out)
+                    Ray::from(ucp).checked_mul_quantity(quantity_out, Rounding::Up)
                 } else {
-                    Ray::from(ucp).inverse_quantity(quantity_out)
-                };
+                    Ray::from(ucp).checked_inverse_quantity(quantity_out, Rounding::Up)
+                }
+                .map_err(|e| eyre::eyre!("can't price order {:?}: {e}", order.order_id.hash))?;
                 // Account for our user order
                 let (asset_in, asset_out) = if order.is_bid { (*t1, *t0) } else { (*t0, *t1) };
                 asset_builder.external_swap(
@@ -310,16 +373,56 @@ impl AngstromBundle {
                     quantity_in.to(),
                     quantity_out.to()
                 );
+                settlements.record_fill(
+                    order.from(),
+                    asset_in,
+                    asset_out,
+                    quantity_in.to(),
+                    quantity_out.to()
+                );
                 user_orders.push(UserOrder::from_internal_order(order, outcome, pair_idx as u16));
             }
         }
-        Ok(Self::new(
+        // Assets were indexed in the order we came across them, move them into
+        // address order and point everything that indexes them at the new slots
+        let remap = asset_builder.canonicalize();
+        for pair in pairs.iter_mut() {
+            pair.index0 = remap[pair.index0 as usize] as u16;
+            pair.index1 = remap[pair.index1 as usize] as u16;
+        }
+        for tob in top_of_block_orders.iter_mut() {
+            tob.asset_in_index = remap[tob.asset_in_index as usize] as u16;
+            tob.asset_out_index = remap[tob.asset_out_index as usize] as u16;
+        }
+        // The contract also wants pairs in increasing (index0, index1) order, which
+        // moving the assets breaks, so sort them and point everything that indexes
+        // them at the new slots as well
+        let mut indexed_pairs = pairs.into_iter().enumerate().collect::<Vec<_>>();
+        indexed_pairs.sort_by_key(|(_, pair)| (pair.index0, pair.index1));
+        let mut pair_remap = vec![0_u16; indexed_pairs.len()];
+        for (new_idx, (old_idx, _)) in indexed_pairs.iter().enumerate() {
+            pair_remap[*old_idx] = new_idx as u16;
+        }
+        let pairs = indexed_pairs
+            .into_iter()
+            .map(|(_, pair)| pair)
+            .collect::<Vec<_>>();
+        for update in pool_updates.iter_mut() {
+            update.pair_index = pair_remap[update.pair_index as usize];
+        }
+        for order in user_orders.iter_mut() {
+            order.pair_index = pair_remap[order.pair_index as usize];
+        }
+
+        let bundle = Self::new(
             asset_builder.get_asset_array(),
             pairs,
-            pool_updates,
+            PoolUpdate::canonicalize(pool_updates)?,
             top_of_block_orders,
             user_orders
-        ))
+        );
+
+        Ok((bundle, settlements))
     }
 }
 
@@ -337,16 +440,414 @@ impl AngstromBundle {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
 
-    use super::AngstromBundle;
+    use alloy::primitives::{aliases::I24, keccak256, Address, Bytes, FixedBytes, B256, U256};
+    use pade::{PadeDecode, PadeEncode};
+    use proptest::prelude::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::{
+        AngstromBundle, OrderQuantities, StandingValidation, TopOfBlockOrder, UserOrder,
+        BUNDLE_HASH_DOMAIN
+    };
+    use crate::{
+        consensus::{PreProposal, Proposal},
+        contract_payloads::{
+            rewards::{PoolUpdate, PoolUpdateError, RewardsUpdate},
+            Asset, Pair
+        },
+        matching::{
+            uniswap::{LiqRange, PoolSnapshot},
+            SqrtPriceX96
+        },
+        orders::{NetAmmOrder, OrderFillState, OrderId, OrderOutcome, PoolSolution},
+        sol_bindings::{
+            grouped_orders::{GroupedVanillaOrder, OrderWithStorageData, StandingVariants},
+            rpc_orders::{ExactStandingOrder, OrderMeta}
+        }
+    };
+
+    fn update(pair_index: u16, swap_in_quantity: u128, rewards: u128) -> PoolUpdate {
+        PoolUpdate {
+            zero_for_one: true,
+            pair_index,
+            swap_in_quantity,
+            rewards_update: RewardsUpdate::CurrentOnly { amount: rewards }
+        }
+    }
 
     #[test]
     fn can_be_constructed() {
         let _result = AngstromBundle::new(vec![], vec![], vec![], vec![], vec![]);
     }
 
+    #[test]
+    fn pool_updates_skip_empty() {
+        let updates = vec![update(0, 0, 0), update(1, 100, 0), update(2, 0, 10)];
+        let canonical = PoolUpdate::canonicalize(updates).unwrap();
+        assert_eq!(canonical, vec![update(1, 100, 0), update(2, 0, 10)]);
+
+        let empty_multi = PoolUpdate {
+            rewards_update: RewardsUpdate::MultiTick {
+                start_tick:      I24::ZERO,
+                start_liquidity: 10,
+                quantities:      vec![0, 0]
+            },
+            ..update(3, 0, 0)
+        };
+        assert!(empty_multi.is_empty());
+    }
+
+    #[test]
+    fn pool_updates_are_ordered() {
+        let updates = vec![update(2, 20, 0), update(0, 5, 1), update(1, 7, 0)];
+        let canonical = PoolUpdate::canonicalize(updates).unwrap();
+        assert_eq!(canonical, vec![update(0, 5, 1), update(1, 7, 0), update(2, 20, 0)]);
+        // canonicalizing is idempotent
+        assert_eq!(PoolUpdate::canonicalize(canonical.clone()).unwrap(), canonical);
+    }
+
+    #[test]
+    fn pool_updates_reject_duplicate_pairs() {
+        let updates = vec![update(2, 20, 0), update(0, 5, 1), update(2, 30, 0)];
+        assert_eq!(PoolUpdate::canonicalize(updates), Err(PoolUpdateError::DuplicatePair(2)));
+
+        // an empty update doesn't count as updating the pair
+        let updates = vec![update(2, 20, 0), update(2, 0, 0)];
+        assert_eq!(PoolUpdate::canonicalize(updates).unwrap(), vec![update(2, 20, 0)]);
+    }
+
+    #[test]
+    fn pool_updates_round_trip() {
+        let updates =
+            PoolUpdate::canonicalize(vec![update(1, 7, 0), update(0, 0, 0), update(0, 5, 1)])
+                .unwrap();
+        let bundle = AngstromBundle::new(vec![], vec![], updates.clone(), vec![], vec![]);
+
+        let encoded = bundle.pade_encode();
+        let decoded = AngstromBundle::pade_decode(&mut encoded.as_slice(), None).unwrap();
+
+        assert_eq!(decoded.pool_updates, updates);
+        assert_eq!(PoolUpdate::canonicalize(decoded.pool_updates).unwrap(), updates);
+    }
+
+    #[test]
+    fn bundle_hash_is_over_the_encoding() {
+        let bundle = AngstromBundle::new(vec![], vec![], vec![update(0, 5, 1)], vec![], vec![]);
+        let encoded = bundle.pade_encode();
+        let decoded = AngstromBundle::pade_decode(&mut encoded.as_slice(), None).unwrap();
+
+        assert_eq!(decoded.bundle_hash(), bundle.bundle_hash());
+        assert_eq!(
+            bundle.bundle_hash(),
+            keccak256([BUNDLE_HASH_DOMAIN, encoded.as_slice()].concat())
+        );
+        assert_ne!(bundle.bundle_hash(), keccak256(&encoded));
+
+        let other = AngstromBundle::new(vec![], vec![], vec![update(0, 6, 1)], vec![], vec![]);
+        assert_ne!(other.bundle_hash(), bundle.bundle_hash());
+    }
+
+    fn address() -> impl Strategy<Value = Address> {
+        any::<[u8; 20]>().prop_map(Address::from)
+    }
+
+    fn u256() -> impl Strategy<Value = U256> {
+        any::<[u8; 32]>().prop_map(U256::from_be_bytes)
+    }
+
+    fn bytes() -> impl Strategy<Value = Bytes> {
+        prop::collection::vec(any::<u8>(), 0..80).prop_map(Bytes::from)
+    }
+
+    prop_compose! {
+        fn asset()(addr in address(), borrow: u128, save: u128, settle: u128) -> Asset {
+            Asset { addr, borrow, save, settle }
+        }
+    }
+
+    prop_compose! {
+        fn pair()(index0: u16, index1: u16, store_index: u16, price_1over0 in u256()) -> Pair {
+            Pair { index0, index1, store_index, price_1over0 }
+        }
+    }
+
+    fn rewards_update() -> impl Strategy<Value = RewardsUpdate> {
+        prop_oneof![
+            (
+                -(1_i32 << 23)..(1_i32 << 23),
+                any::<u128>(),
+                prop::collection::vec(any::<u128>(), 0..8)
+            )
+                .prop_map(|(tick, start_liquidity, quantities)| {
+                    RewardsUpdate::MultiTick {
+                        start_tick: I24::try_from(tick).unwrap(),
+                        start_liquidity,
+                        quantities
+                    }
+                }),
+            any::<u128>().prop_map(|amount| RewardsUpdate::CurrentOnly { amount })
+        ]
+    }
+
+    prop_compose! {
+        fn pool_update()(
+            zero_for_one: bool,
+            pair_index: u16,
+            swap_in_quantity: u128,
+            rewards_update in rewards_update()
+        ) -> PoolUpdate {
+            PoolUpdate { zero_for_one, pair_index, swap_in_quantity, rewards_update }
+        }
+    }
+
+    prop_compose! {
+        fn tob_order()(
+            use_internal: bool,
+            quantity_in: u128,
+            quantity_out: u128,
+            asset_in_index: u16,
+            asset_out_index: u16,
+            recipient in prop::option::of(address()),
+            hook_data in prop::option::of(bytes()),
+            signature in bytes()
+        ) -> TopOfBlockOrder {
+            TopOfBlockOrder {
+                use_internal,
+                quantity_in,
+                quantity_out,
+                asset_in_index,
+                asset_out_index,
+                recipient,
+                hook_data,
+                signature
+            }
+        }
+    }
+
+    prop_compose! {
+        // the deadline is only 40 bits wide on the wire
+        fn standing_validation()(nonce: u64, deadline in 0..(1_u64 << 40)) -> StandingValidation {
+            StandingValidation { nonce, deadline }
+        }
+    }
+
+    fn order_quantities() -> impl Strategy<Value = OrderQuantities> {
+        prop_oneof![
+            any::<u128>().prop_map(|quantity| OrderQuantities::Exact { quantity }),
+            any::<(u128, u128, u128)>().prop_map(
+                |(min_quantity_in, max_quantity_in, filled_quantity)| OrderQuantities::Partial {
+                    min_quantity_in,
+                    max_quantity_in,
+                    filled_quantity
+                }
+            )
+        ]
+    }
+
+    prop_compose! {
+        fn user_order()(
+            use_internal: bool,
+            pair_index: u16,
+            min_price in u256(),
+            recipient in prop::option::of(address()),
+            hook_data in prop::option::of(bytes()),
+            a_to_b: bool,
+            standing_validation in prop::option::of(standing_validation()),
+            order_quantities in order_quantities(),
+            exact_in: bool,
+            signature in bytes()
+        ) -> UserOrder {
+            UserOrder {
+                use_internal,
+                pair_index,
+                min_price,
+                recipient,
+                hook_data,
+                a_to_b,
+                standing_validation,
+                order_quantities,
+                exact_in,
+                signature
+            }
+        }
+    }
+
+    prop_compose! {
+        fn bundle()(
+            assets in prop::collection::vec(asset(), 0..4),
+            pairs in prop::collection::vec(pair(), 0..4),
+            pool_updates in prop::collection::vec(pool_update(), 0..4),
+            top_of_block_orders in prop::collection::vec(tob_order(), 0..4),
+            user_orders in prop::collection::vec(user_order(), 0..4)
+        ) -> AngstromBundle {
+            AngstromBundle::new(assets, pairs, pool_updates, top_of_block_orders, user_orders)
+        }
+    }
+
+    fn assert_round_trip<T: PadeDecode + PartialEq + std::fmt::Debug>(value: T) {
+        let encoded = value.pade_encode();
+        assert_eq!(value.pade_size_hint(), encoded.len());
+
+        let mut slice = encoded.as_slice();
+        let decoded = T::pade_decode(&mut slice, None).unwrap();
+        assert_eq!(decoded, value);
+        assert!(slice.is_empty(), "{} trailing bytes", slice.len());
+    }
+
+    proptest! {
+        #[test]
+        fn tob_orders_round_trip(order in tob_order()) {
+            assert_round_trip(order);
+        }
+
+        #[test]
+        fn user_orders_round_trip(order in user_order()) {
+            assert_round_trip(order);
+        }
+
+        #[test]
+        fn standing_validations_round_trip(validation in standing_validation()) {
+            assert_round_trip(validation);
+        }
+
+        #[test]
+        fn order_quantities_round_trip(quantities in order_quantities()) {
+            assert_round_trip(quantities);
+        }
+
+        #[test]
+        fn bundles_round_trip(bundle in bundle()) {
+            assert_round_trip(bundle);
+        }
+    }
+
+    #[test]
+    fn truncated_bundle_is_an_error() {
+        let bundle = AngstromBundle::new(vec![], vec![], vec![update(0, 5, 1)], vec![], vec![]);
+        let encoded = bundle.pade_encode();
+
+        let mut slice = &encoded[..encoded.len() - 1];
+        assert!(AngstromBundle::pade_decode(&mut slice, None).is_err());
+    }
+
     #[test]
     fn can_be_cretaed_from_proposal() {
         // AngstromBundle::from_proposal(proposal, pools);
     }
+
+    #[test]
+    fn solution_order_does_not_change_the_bundle() {
+        let snapshot = PoolSnapshot::new(
+            vec![LiqRange::new(-6000, 6000, 1_000_000_000_000_000_000).unwrap()],
+            SqrtPriceX96::at_tick(0).unwrap()
+        )
+        .unwrap();
+        // pools share tokens so that asset indexes depend on which one is seen first,
+        // and visiting them by pool id puts the pairs out of index order
+        let tokens = [(4, 9), (1, 4), (2, 9), (1, 2)];
+        let pool_id = |store_index: usize| FixedBytes::with_last_byte(store_index as u8 + 1);
+        let pools = tokens
+            .iter()
+            .enumerate()
+            .map(|(store_index, &(t0, t1))| {
+                (
+                    pool_id(store_index),
+                    (
+                        Address::with_last_byte(t0),
+                        Address::with_last_byte(t1),
+                        snapshot.clone(),
+                        store_index as u16
+                    )
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        // every pool gets a swap and a filled order that can be traced back to it,
+        // the swap by its quantity and the order by its signature
+        let orders = (0..tokens.len())
+            .map(|store_index| OrderWithStorageData {
+                order: GroupedVanillaOrder::Standing(StandingVariants::Exact(ExactStandingOrder {
+                    meta: OrderMeta {
+                        signature: Bytes::from(vec![store_index as u8]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })),
+                pool_id: pool_id(store_index),
+                is_bid: true,
+                order_id: OrderId {
+                    hash: B256::with_last_byte(store_index as u8 + 1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut solutions = orders
+            .iter()
+            .enumerate()
+            .map(|(store_index, order)| PoolSolution {
+                id: order.pool_id,
+                amm_quantity: Some(NetAmmOrder::Sell(U256::from(store_index + 1), U256::from(1))),
+                limit: vec![OrderOutcome {
+                    id:      order.order_id,
+                    outcome: OrderFillState::CompleteFill
+                }],
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let preproposals = vec![PreProposal { limit: orders, ..Default::default() }];
+
+        let bundle = |solutions: Vec<PoolSolution>| {
+            let proposal =
+                Proposal { solutions, preproposals: preproposals.clone(), ..Default::default() };
+            AngstromBundle::from_proposal(&proposal, &pools, Default::default()).unwrap()
+        };
+        let canonical = bundle(solutions.clone());
+        let addrs = canonical
+            .assets
+            .iter()
+            .map(|asset| asset.addr)
+            .collect::<Vec<_>>();
+        assert_eq!(addrs, [1, 2, 4, 9].map(Address::with_last_byte));
+        assert!(addrs.windows(2).all(|w| w[0] < w[1]), "{addrs:?}");
+        for pair in &canonical.pairs {
+            assert!(pair.index0 < pair.index1, "{pair:?}");
+        }
+        assert!(
+            canonical
+                .pairs
+                .windows(2)
+                .all(|w| (w[0].index0, w[0].index1) < (w[1].index0, w[1].index1)),
+            "{:?}",
+            canonical.pairs
+        );
+
+        // the tokens of the pair at `pair_index` are still those of the pool
+        let pair_tokens = |pair_index: u16| {
+            let pair = &canonical.pairs[pair_index as usize];
+            (addrs[pair.index0 as usize], addrs[pair.index1 as usize])
+        };
+        let pool_tokens = |store_index: usize| {
+            let (t0, t1) = tokens[store_index];
+            (Address::with_last_byte(t0), Address::with_last_byte(t1))
+        };
+        assert_eq!(canonical.pool_updates.len(), tokens.len());
+        for update in &canonical.pool_updates {
+            let store_index = update.swap_in_quantity as usize - 1;
+            assert_eq!(pair_tokens(update.pair_index), pool_tokens(store_index));
+        }
+        assert_eq!(canonical.user_orders.len(), tokens.len());
+        for order in &canonical.user_orders {
+            let store_index = order.signature[0] as usize;
+            assert_eq!(pair_tokens(order.pair_index), pool_tokens(store_index));
+        }
+
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..16 {
+            solutions.shuffle(&mut rng);
+            let shuffled = bundle(solutions.clone());
+            assert_eq!(shuffled.pade_encode(), canonical.pade_encode());
+        }
+    }
 }
@@ -41,6 +41,10 @@ impl AssetBuilder {
         quantity_in: u128,
         quantity_out: u128
     ) {
+        debug_assert!(
+            asset_in < self.assets.len() && asset_out < self.assets.len(),
+            "swap between unknown assets {asset_in} and {asset_out}"
+        );
         let asset_in_addr = self.assets.get_asset_addr(asset_in);
         let asset_out_addr = self.assets.get_asset_addr(asset_out);
         self.get_stage(stage).uniswap_swap(
@@ -72,12 +76,23 @@ impl AssetBuilder {
         self.assets.add_or_get_asset_idx(asset)
     }
 
-    pub fn get_asset_array(&self) -> Vec<Asset> {
-        let combined_assets = self
-            .swaps
+    /// Puts the assets in canonical order, see [`AssetArray::canonicalize`].
+    /// Indexes handed out before this must be remapped through the returned
+    /// table.
+    pub fn canonicalize(&mut self) -> Vec<usize> {
+        self.assets.canonicalize()
+    }
+
+    fn combined_stages(&self) -> StageTracker {
+        self.swaps
             .and_then(&self.top_of_block)
             .and_then(&self.user_orders)
-            .and_then(&self.rewards);
+            .and_then(&self.rewards)
+    }
+
+    pub fn get_asset_array(&self) -> Vec<Asset> {
+        let combined_assets = self.combined_stages();
+        debug_assert!(combined_assets.is_balanced(), "asset accounting is unbalanced");
         self.assets
             .get_asset_array()
             .into_iter()
@@ -103,3 +118,99 @@ impl Default for AssetBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn random_stages_conserve_every_asset() {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..256 {
+            let mut builder = AssetBuilder::new();
+            let assets = (1..=3)
+                .map(|byte| {
+                    let addr = Address::with_last_byte(byte);
+                    builder.add_or_get_asset(addr);
+                    addr
+                })
+                .collect::<Vec<_>>();
+            let mut received: HashMap<Address, u128> = HashMap::new();
+            let mut allocated: HashMap<Address, u128> = HashMap::new();
+
+            for _ in 0..rng.gen_range(1..32) {
+                let stage = match rng.gen_range(0..4) {
+                    0 => AssetBuilderStage::Swap,
+                    1 => AssetBuilderStage::Reward,
+                    2 => AssetBuilderStage::TopOfBlock,
+                    _ => AssetBuilderStage::UserOrder
+                };
+                let asset_in = rng.gen_range(0..assets.len());
+                let asset_out = (asset_in + rng.gen_range(1..assets.len())) % assets.len();
+                let (quantity_in, quantity_out) =
+                    (rng.gen_range(0..1u128 << 96), rng.gen_range(0..1u128 << 96));
+
+                match rng.gen_range(0..3) {
+                    0 => {
+                        builder.uniswap_swap(stage, asset_in, asset_out, quantity_in, quantity_out)
+                    }
+                    1 => {
+                        let (asset_in, asset_out) = (assets[asset_in], assets[asset_out]);
+                        builder.external_swap(
+                            stage,
+                            asset_in,
+                            asset_out,
+                            quantity_in,
+                            quantity_out
+                        );
+                        *received.entry(asset_in).or_default() += quantity_in;
+                        *allocated.entry(asset_out).or_default() += quantity_out;
+                    }
+                    _ => {
+                        builder.allocate(stage, assets[asset_out], quantity_out);
+                        *allocated.entry(assets[asset_out]).or_default() += quantity_out;
+                    }
+                }
+            }
+
+            let combined = builder.combined_stages();
+            for asset in &assets {
+                let Some(tracker) = combined.get_asset(asset) else { continue };
+                assert!(tracker.is_balanced(), "{tracker:?}");
+                assert_eq!(tracker.received, received.get(asset).copied().unwrap_or_default());
+                assert_eq!(tracker.allocated, allocated.get(asset).copied().unwrap_or_default());
+            }
+            builder.get_asset_array();
+        }
+    }
+
+    #[test]
+    fn canonical_assets_are_sorted_by_address() {
+        let mut builder = AssetBuilder::new();
+        let added =
+            [3, 1, 2, 1].map(|byte| builder.add_or_get_asset(Address::with_last_byte(byte)));
+        assert_eq!(added, [0, 1, 2, 1]);
+        builder.external_swap(
+            AssetBuilderStage::UserOrder,
+            Address::with_last_byte(3),
+            Address::with_last_byte(1),
+            10,
+            10
+        );
+
+        let remap = builder.canonicalize();
+        assert_eq!(remap, vec![2, 0, 1]);
+        let assets = builder.get_asset_array();
+        assert_eq!(
+            assets.iter().map(|asset| asset.addr).collect::<Vec<_>>(),
+            [1, 2, 3].map(Address::with_last_byte)
+        );
+        // accounting follows the assets, not their old indexes
+        assert_eq!(assets[0].settle, 10);
+        assert_eq!(builder.add_or_get_asset(Address::with_last_byte(3)), 2);
+    }
+}
@@ -25,6 +25,14 @@ impl AssetArray {
         Self { assets: Vec::new(), assets_idx: HashMap::new() }
     }
 
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
     pub fn get_asset_addr(&self, idx: usize) -> Address {
         self.assets
             .get(idx)
@@ -43,6 +51,29 @@ impl AssetArray {
     pub fn get_asset_array(&self) -> Vec<Asset> {
         self.assets.clone()
     }
+
+    /// Sorts the assets by address so that their indexes don't depend on the
+    /// order they were added in. Returns the new index of every old index.
+    pub fn canonicalize(&mut self) -> Vec<usize> {
+        self.assets.sort_unstable_by_key(|asset| asset.addr);
+        let remap = self
+            .assets
+            .iter()
+            .map(|asset| self.assets_idx[&asset.addr])
+            .enumerate()
+            .fold(vec![0; self.assets.len()], |mut remap, (new, old)| {
+                remap[old] = new;
+                remap
+            });
+        self.assets_idx = self
+            .assets
+            .iter()
+            .enumerate()
+            .map(|(idx, asset)| (asset.addr, idx))
+            .collect();
+
+        remap
+    }
 }
 
 impl From<AssetArray> for Vec<Asset> {
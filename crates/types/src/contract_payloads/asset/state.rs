@@ -6,7 +6,11 @@ use alloy::primitives::Address;
 pub struct BorrowStateTracker {
     pub take:            u128,
     pub contract_liquid: u128,
-    pub settle:          u128
+    pub settle:          u128,
+    /// Tokens paid into the contract by users and searchers
+    pub received:        u128,
+    /// Tokens paid out of the contract to users, searchers and rewards
+    pub allocated:       u128
 }
 
 impl BorrowStateTracker {
@@ -22,6 +26,8 @@ impl BorrowStateTracker {
             self.loan(needed_borrow);
         }
         self.contract_liquid = self.contract_liquid.saturating_sub(q);
+        self.allocated += q;
+        debug_assert!(self.is_balanced(), "unbalanced allocation: {self:?}");
     }
 
     /// Add to what we owe to Uniswap, as a result of a pool swap
@@ -39,6 +45,7 @@ impl BorrowStateTracker {
     /// Gain external tokens into our contract liquidity
     pub fn recieve(&mut self, q: u128) {
         self.contract_liquid += q;
+        self.received += q;
     }
 
     /// Take a loan from Uniswap (adds to `take` and `settle`)
@@ -52,11 +59,22 @@ impl BorrowStateTracker {
         let amount_onhand =
             (self.contract_liquid.saturating_sub(other.take)) + other.contract_liquid;
         let amount_owed = self.settle + (other.settle.saturating_sub(self.contract_liquid));
-        Self {
+        let combined = Self {
             take:            borrow_needed,
             contract_liquid: amount_onhand,
-            settle:          amount_owed
-        }
+            settle:          amount_owed,
+            received:        self.received + other.received,
+            allocated:       self.allocated + other.allocated
+        };
+        debug_assert!(combined.is_balanced(), "unbalanced stages: {self:?} and then {other:?}");
+
+        combined
+    }
+
+    /// Every token that comes into the contract, either taken from Uniswap or
+    /// paid in, is either paid out or still on hand.
+    pub fn is_balanced(&self) -> bool {
+        self.take + self.received == self.allocated + self.contract_liquid
     }
 }
 
@@ -74,6 +92,10 @@ impl StageTracker {
         self.map.get(asset)
     }
 
+    pub fn is_balanced(&self) -> bool {
+        self.map.values().all(BorrowStateTracker::is_balanced)
+    }
+
     #[inline]
     fn get_state(&mut self, addr: Address) -> &mut BorrowStateTracker {
         self.map.entry(addr).or_default()
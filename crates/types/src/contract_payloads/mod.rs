@@ -3,11 +3,12 @@ use pade_macro::{PadeDecode, PadeEncode};
 
 pub mod angstrom;
 pub mod asset;
+pub mod netting;
 pub mod rewards;
 pub mod tob;
 
 sol! {
-    #[derive(Debug, PadeEncode, PadeDecode)]
+    #[derive(Debug, PartialEq, Eq, PadeEncode, PadeDecode)]
     struct Asset {
         address addr;
         uint128 borrow;
@@ -15,7 +16,7 @@ sol! {
         uint128 settle;
     }
 
-    #[derive(Debug, PadeEncode, PadeDecode)]
+    #[derive(Debug, PartialEq, Eq, PadeEncode, PadeDecode)]
     struct Pair {
         uint16 index0;
         uint16 index1;
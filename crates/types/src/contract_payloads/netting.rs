@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use alloy::primitives::Address;
+
+/// A single transfer between a user and the contract, as produced by settling
+/// one order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserTransfer {
+    pub user:   Address,
+    pub asset:  Address,
+    /// `true` if the user pays the contract, `false` if the contract pays the
+    /// user
+    pub debit:  bool,
+    pub amount: u128
+}
+
+/// The net amount a user owes or is owed of a single asset across all of the
+/// pairs they were filled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NetSettlement {
+    pub user:   Address,
+    pub asset:  Address,
+    pub debit:  bool,
+    pub amount: u128
+}
+
+/// The net amount of an asset a user owes or is owed doesn't fit in an
+/// `i128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("netted amount of {asset} for {user} overflows")]
+pub struct NettingOverflow {
+    pub user:  Address,
+    pub asset: Address
+}
+
+/// Collects the gross transfers of every filled order in a bundle so that a
+/// user with fills in multiple pairs sharing an asset only needs a single
+/// transfer for it.
+#[derive(Debug, Default, Clone)]
+pub struct SettlementLedger {
+    transfers: Vec<UserTransfer>
+}
+
+impl SettlementLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a fill where the user pays `amount_in` of `asset_in` and
+    /// receives `amount_out` of `asset_out`.
+    pub fn record_fill(
+        &mut self,
+        user: Address,
+        asset_in: Address,
+        asset_out: Address,
+        amount_in: u128,
+        amount_out: u128
+    ) {
+        self.transfers
+            .push(UserTransfer { user, asset: asset_in, debit: true, amount: amount_in });
+        self.transfers.push(UserTransfer {
+            user,
+            asset: asset_out,
+            debit: false,
+            amount: amount_out
+        });
+    }
+
+    pub fn gross_transfers(&self) -> &[UserTransfer] {
+        &self.transfers
+    }
+
+    /// Nets all recorded transfers per (user, asset). Pairs that cancel out
+    /// completely are dropped. The result is sorted by user and then asset so
+    /// that it's deterministic.
+    pub fn net(&self) -> Result<Vec<NetSettlement>, NettingOverflow> {
+        let mut balances: HashMap<(Address, Address), i128> = HashMap::new();
+        for transfer in self.transfers.iter().filter(|t| t.amount != 0) {
+            let overflow = NettingOverflow { user: transfer.user, asset: transfer.asset };
+            let amount = i128::try_from(transfer.amount).map_err(|_| overflow)?;
+            let balance = balances.entry((transfer.user, transfer.asset)).or_default();
+            *balance = if transfer.debit {
+                balance.checked_add(amount)
+            } else {
+                balance.checked_sub(amount)
+            }
+            .ok_or(overflow)?;
+        }
+
+        let mut net = balances
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((user, asset), amount)| NetSettlement {
+                user,
+                asset,
+                debit: amount > 0,
+                amount: amount.unsigned_abs()
+            })
+            .collect::<Vec<_>>();
+        net.sort_by_key(|s| (s.user, s.asset));
+
+        Ok(net)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy::primitives::Address;
+
+    use super::{NetSettlement, NettingOverflow, SettlementLedger, UserTransfer};
+
+    fn per_asset_balance(
+        transfers: impl Iterator<Item = (Address, bool, u128)>
+    ) -> HashMap<Address, i128> {
+        transfers.fold(HashMap::new(), |mut acc, (asset, debit, amount)| {
+            *acc.entry(asset).or_default() +=
+                if debit { amount as i128 } else { -(amount as i128) };
+            acc
+        })
+    }
+
+    fn gross_balance(ledger: &SettlementLedger) -> HashMap<Address, i128> {
+        per_asset_balance(
+            ledger
+                .gross_transfers()
+                .iter()
+                .map(|t: &UserTransfer| (t.asset, t.debit, t.amount))
+        )
+    }
+
+    fn net_balance(net: &[NetSettlement]) -> HashMap<Address, i128> {
+        per_asset_balance(net.iter().map(|t| (t.asset, t.debit, t.amount)))
+    }
+
+    #[test]
+    fn nets_shared_asset_across_pairs() {
+        let user = Address::with_last_byte(1);
+        let (a, b, c) =
+            (Address::with_last_byte(10), Address::with_last_byte(11), Address::with_last_byte(12));
+        let mut ledger = SettlementLedger::new();
+        // sells A for B in one pair, then B for C in another
+        ledger.record_fill(user, a, b, 100, 50);
+        ledger.record_fill(user, b, c, 30, 10);
+
+        let net = ledger.net().unwrap();
+        assert_eq!(
+            net,
+            vec![
+                NetSettlement { user, asset: a, debit: true, amount: 100 },
+                NetSettlement { user, asset: b, debit: false, amount: 20 },
+                NetSettlement { user, asset: c, debit: false, amount: 10 },
+            ]
+        );
+        assert!(net.len() < ledger.gross_transfers().len());
+    }
+
+    #[test]
+    fn drops_fully_offset_assets() {
+        let user = Address::with_last_byte(1);
+        let (a, b) = (Address::with_last_byte(10), Address::with_last_byte(11));
+        let mut ledger = SettlementLedger::new();
+        ledger.record_fill(user, a, b, 100, 40);
+        ledger.record_fill(user, b, a, 40, 90);
+
+        assert_eq!(
+            ledger.net(),
+            Ok(vec![NetSettlement { user, asset: a, debit: true, amount: 10 }])
+        );
+    }
+
+    #[test]
+    fn reports_amounts_that_overflow() {
+        let user = Address::with_last_byte(1);
+        let (a, b) = (Address::with_last_byte(10), Address::with_last_byte(11));
+        let overflow = NettingOverflow { user, asset: a };
+
+        let mut ledger = SettlementLedger::new();
+        ledger.record_fill(user, a, b, u128::MAX, 1);
+        assert_eq!(ledger.net(), Err(overflow));
+
+        // each amount fits but their sum doesn't
+        let mut ledger = SettlementLedger::new();
+        ledger.record_fill(user, a, b, i128::MAX as u128, 1);
+        ledger.record_fill(user, a, b, 1, 1);
+        assert_eq!(ledger.net(), Err(overflow));
+    }
+
+    #[test]
+    fn netting_conserves_every_asset() {
+        let users = (1..=4).map(Address::with_last_byte).collect::<Vec<_>>();
+        let assets = (10..=13).map(Address::with_last_byte).collect::<Vec<_>>();
+        let mut ledger = SettlementLedger::new();
+        for i in 0..64usize {
+            let user = users[i % users.len()];
+            let asset_in = assets[i % assets.len()];
+            let asset_out = assets[(i * 7 + 1) % assets.len()];
+            ledger.record_fill(user, asset_in, asset_out, (i as u128 + 1) * 13, (i as u128) * 7);
+        }
+
+        let net = ledger.net().unwrap();
+        let normalize = |m: HashMap<Address, i128>| {
+            m.into_iter()
+                .filter(|(_, v)| *v != 0)
+                .collect::<HashMap<_, _>>()
+        };
+        assert_eq!(normalize(gross_balance(&ledger)), normalize(net_balance(&net)));
+        // every user only has a single transfer per asset
+        let mut seen = std::collections::HashSet::new();
+        assert!(net.iter().all(|s| seen.insert((s.user, s.asset))));
+    }
+}
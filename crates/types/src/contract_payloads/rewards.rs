@@ -3,13 +3,31 @@ use pade_macro::{PadeDecode, PadeEncode};
 
 use super::{Asset, Pair};
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub enum RewardsUpdate {
     MultiTick { start_tick: I24, start_liquidity: u128, quantities: Vec<u128> },
     CurrentOnly { amount: u128 }
 }
 
-#[derive(Debug, PadeEncode, PadeDecode)]
+impl RewardsUpdate {
+    /// returns true if applying this update donates nothing to the pool
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::CurrentOnly { amount } => *amount == 0,
+            Self::MultiTick { quantities, .. } => quantities.iter().all(|q| *q == 0)
+        }
+    }
+}
+
+impl Default for RewardsUpdate {
+    fn default() -> Self {
+        Self::CurrentOnly { amount: 0 }
+    }
+}
+
+/// A single update to a pool. The contract applies the swap before the
+/// rewards, so the rewards are always donated at the post-swap price.
+#[derive(Debug, Clone, PartialEq, Eq, PadeEncode, PadeDecode)]
 pub struct PoolUpdate {
     pub zero_for_one:     bool,
     pub pair_index:       u16,
@@ -17,6 +35,38 @@ pub struct PoolUpdate {
     pub rewards_update:   RewardsUpdate
 }
 
+impl PoolUpdate {
+    /// returns true if the update neither swaps against nor donates to the
+    /// pool, in which case it is a noop for the contract
+    pub fn is_empty(&self) -> bool {
+        self.swap_in_quantity == 0 && self.rewards_update.is_empty()
+    }
+
+    /// Puts a set of pool updates into the canonical form the contract
+    /// expects. Updates are ordered by ascending pair index and empty updates
+    /// are dropped. A pair can only be updated once per bundle, so more than
+    /// one non-empty update for a pair is an error. Applying this to an
+    /// already canonical set is a noop.
+    pub fn canonicalize(mut updates: Vec<PoolUpdate>) -> Result<Vec<PoolUpdate>, PoolUpdateError> {
+        updates.retain(|update| !update.is_empty());
+        updates.sort_by_key(|update| update.pair_index);
+        if let Some(pair) = updates
+            .windows(2)
+            .find(|pair| pair[0].pair_index == pair[1].pair_index)
+        {
+            return Err(PoolUpdateError::DuplicatePair(pair[0].pair_index))
+        }
+
+        Ok(updates)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PoolUpdateError {
+    #[error("pair {0} is updated more than once")]
+    DuplicatePair(u16)
+}
+
 #[derive(PadeEncode, Debug)]
 pub struct MockContractMessage {
     pub assets: Vec<Asset>,
@@ -6,6 +6,7 @@ pub mod contract_bindings;
 pub mod contract_payloads;
 pub mod matching;
 pub mod orders;
+pub mod persistence;
 pub mod primitive;
 pub mod sol_bindings;
 
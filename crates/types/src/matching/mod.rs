@@ -5,6 +5,8 @@ use std::{
 
 use alloy::primitives::U256;
 
+pub mod price;
+mod price_format;
 mod ray;
 mod sqrtprice;
 pub mod uniswap;
@@ -13,6 +15,7 @@ use malachite::{
     num::{arithmetic::traits::PowerOf2, conversion::traits::FromSciString},
     Natural
 };
+pub use price_format::{decimal_adjusted_price, FormattedPrice};
 pub use ray::Ray;
 pub use sqrtprice::SqrtPriceX96;
 
@@ -0,0 +1,241 @@
+//! Checked conversions between the price and quantity formats used in
+//! matching, each naming the direction it rounds in. Prices on the bid side of
+//! the book round down and prices on the ask side round up, so rounding never
+//! moves an order past its limit.
+use std::ops::Deref;
+
+use alloy::primitives::{aliases::U320, Uint, U256, U512};
+use malachite::{
+    num::arithmetic::traits::{CeilingRoot, DivRound, FloorRoot},
+    rounding_modes::RoundingMode,
+    Natural
+};
+
+use super::{const_1e27, const_2_192, MatchingPrice, Ray, SqrtPriceX96};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PriceError {
+    #[error("price conversion overflowed")]
+    Overflow,
+    #[error("can't convert a quantity at a zero price")]
+    ZeroPrice
+}
+
+/// The direction a conversion rounds in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up
+}
+
+impl Rounding {
+    /// Bids round down and asks round up.
+    pub fn for_side(is_bid: bool) -> Self {
+        if is_bid {
+            Self::Down
+        } else {
+            Self::Up
+        }
+    }
+
+    fn mode(self) -> RoundingMode {
+        match self {
+            Self::Down => RoundingMode::Floor,
+            Self::Up => RoundingMode::Ceiling
+        }
+    }
+}
+
+fn to_uint<const BITS: usize, const LIMBS: usize>(
+    value: Natural
+) -> Result<Uint<BITS, LIMBS>, PriceError> {
+    Uint::checked_from_limbs_slice(&value.to_limbs_asc()).ok_or(PriceError::Overflow)
+}
+
+/// The price `P` in Ray of `sqrt(P)` in X96. Any [`SqrtPriceX96`] fits in a
+/// Ray, so this can't overflow.
+pub fn ray_from_sqrt_price(price: SqrtPriceX96, rounding: Rounding) -> Ray {
+    let squared: U320 = price.widening_mul(*price);
+    let numerator = Natural::from_limbs_asc(squared.as_limbs()) * const_1e27();
+    let (ray, _) = numerator.div_round(const_2_192(), rounding.mode());
+    Ray::from(to_uint::<256, 4>(ray).expect("squared sqrt price fits in a ray"))
+}
+
+/// `sqrt(P)` in X96 of the price `P` in Ray.
+pub fn sqrt_price_from_ray(price: Ray, rounding: Rounding) -> Result<SqrtPriceX96, PriceError> {
+    let numerator = Natural::from_limbs_asc(price.as_limbs()) * const_2_192();
+    // rounding both steps the same way rounds the result as a whole that way
+    let (squared, _) = numerator.div_round(const_1e27(), rounding.mode());
+    let root = match rounding {
+        Rounding::Down => squared.floor_root(2),
+        Rounding::Up => squared.ceiling_root(2)
+    };
+    to_uint(root).map(SqrtPriceX96::from)
+}
+
+/// What `quantity` of token0 is worth in token1 at a price of t1/t0.
+pub fn t1_for_t0(price: Ray, quantity: U256, rounding: Rounding) -> Result<U256, PriceError> {
+    let product: U512 = price.widening_mul(quantity);
+    let (t1, _) =
+        Natural::from_limbs_asc(product.as_limbs()).div_round(const_1e27(), rounding.mode());
+    to_uint(t1)
+}
+
+/// What `quantity` of token1 is worth in token0 at a price of t1/t0.
+pub fn t0_for_t1(price: Ray, quantity: U256, rounding: Rounding) -> Result<U256, PriceError> {
+    if price.is_zero() {
+        return Err(PriceError::ZeroPrice)
+    }
+    let numerator = Natural::from_limbs_asc(quantity.as_limbs()) * const_1e27();
+    let (t0, _) = numerator.div_round(Natural::from_limbs_asc(price.as_limbs()), rounding.mode());
+    to_uint(t0)
+}
+
+/// The price halfway between two prices.
+pub fn midpoint(a: Ray, b: Ray, rounding: Rounding) -> Ray {
+    let sum = Natural::from_limbs_asc(a.as_limbs()) + Natural::from_limbs_asc(b.as_limbs());
+    let (mid, _) = sum.div_round(Natural::from(2_u32), rounding.mode());
+    Ray::from(to_uint::<256, 4>(mid).expect("midpoint lies between two rays"))
+}
+
+macro_rules! side_price {
+    ($(#[$doc:meta])* $name:ident, $rounding:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name(pub Ray);
+
+        impl $name {
+            pub const ROUNDING: Rounding = $rounding;
+
+            pub fn from_sqrt_price(price: SqrtPriceX96) -> Self {
+                Self(ray_from_sqrt_price(price, Self::ROUNDING))
+            }
+
+            pub fn to_sqrt_price(self) -> Result<SqrtPriceX96, PriceError> {
+                sqrt_price_from_ray(self.0, Self::ROUNDING)
+            }
+
+            pub fn midpoint(a: Ray, b: Ray) -> Self {
+                Self(midpoint(a, b, Self::ROUNDING))
+            }
+        }
+
+        impl From<$name> for MatchingPrice {
+            fn from(value: $name) -> Self {
+                value.0.into()
+            }
+        }
+
+        impl Deref for $name {
+            type Target = Ray;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    };
+}
+
+side_price!(
+    /// A price on the bid side of the book. Conversions round down, so a bid
+    /// never ends up paying more than its limit.
+    BidPrice,
+    Rounding::Down
+);
+
+side_price!(
+    /// A price on the ask side of the book. Conversions round up, so an ask
+    /// never ends up receiving less than its limit.
+    AskPrice,
+    Rounding::Up
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U160;
+
+    use super::*;
+
+    const ONE: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+    fn ray(value: u128) -> Ray {
+        Ray::from(U256::from(value))
+    }
+
+    fn sqrt_price(value: U160) -> SqrtPriceX96 {
+        SqrtPriceX96::from(value)
+    }
+
+    #[test]
+    fn sides_round_away_from_each_other() {
+        assert_eq!(Rounding::for_side(true), Rounding::Down);
+        assert_eq!(Rounding::for_side(false), Rounding::Up);
+        assert_eq!(BidPrice::ROUNDING, Rounding::Down);
+        assert_eq!(AskPrice::ROUNDING, Rounding::Up);
+    }
+
+    #[test]
+    fn exact_conversions_ignore_rounding() {
+        let one = sqrt_price(U160::from(1_u8) << 96);
+        for rounding in [Rounding::Down, Rounding::Up] {
+            assert_eq!(ray_from_sqrt_price(one, rounding), ray(ONE));
+            assert_eq!(sqrt_price_from_ray(ray(ONE), rounding), Ok(one));
+            assert_eq!(ray_from_sqrt_price(SqrtPriceX96::default(), rounding), Ray::ZERO);
+            assert_eq!(sqrt_price_from_ray(Ray::ZERO, rounding), Ok(SqrtPriceX96::default()));
+            assert_eq!(t1_for_t0(ray(ONE * 3 / 2), U256::from(2), rounding), Ok(U256::from(3)));
+            assert_eq!(t0_for_t1(ray(ONE * 3 / 2), U256::from(3), rounding), Ok(U256::from(2)));
+            assert_eq!(midpoint(ray(1), ray(3), rounding), ray(2));
+        }
+    }
+
+    #[test]
+    fn inexact_conversions_differ_by_one() {
+        let sqrt = sqrt_price((U160::from(1_u8) << 96) + U160::from(1_u8));
+        let down = ray_from_sqrt_price(sqrt, Rounding::Down);
+        assert_eq!(*ray_from_sqrt_price(sqrt, Rounding::Up), *down + U256::from(1));
+
+        let down = sqrt_price_from_ray(ray(2 * ONE), Rounding::Down).unwrap();
+        let up = sqrt_price_from_ray(ray(2 * ONE), Rounding::Up).unwrap();
+        assert_eq!(*up, *down + U160::from(1_u8));
+
+        let price = ray(ONE * 3 / 2);
+        assert_eq!(t1_for_t0(price, U256::from(1), Rounding::Down), Ok(U256::from(1)));
+        assert_eq!(t1_for_t0(price, U256::from(1), Rounding::Up), Ok(U256::from(2)));
+        assert_eq!(t0_for_t1(price, U256::from(1), Rounding::Down), Ok(U256::ZERO));
+        assert_eq!(t0_for_t1(price, U256::from(1), Rounding::Up), Ok(U256::from(1)));
+
+        assert_eq!(midpoint(ray(1), ray(2), Rounding::Down), ray(1));
+        assert_eq!(midpoint(ray(1), ray(2), Rounding::Up), ray(2));
+    }
+
+    #[test]
+    fn round_trips_stay_on_their_side() {
+        for step in 1..=1_000_u128 {
+            let price = ray(step * ONE / 7 + step);
+            let bid = BidPrice(price).to_sqrt_price().unwrap();
+            let ask = AskPrice(price).to_sqrt_price().unwrap();
+            assert!(bid <= ask);
+            assert!(*BidPrice::from_sqrt_price(bid) <= price, "bid {price:?}");
+            assert!(*AskPrice::from_sqrt_price(ask) >= price, "ask {price:?}");
+        }
+        assert_eq!(*BidPrice::midpoint(ray(1), ray(2)), ray(1));
+        assert_eq!(*AskPrice::midpoint(ray(1), ray(2)), ray(2));
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        let max = Ray::from(U256::MAX);
+        assert_eq!(sqrt_price_from_ray(max, Rounding::Down), Err(PriceError::Overflow));
+        assert_eq!(t1_for_t0(max, U256::MAX, Rounding::Up), Err(PriceError::Overflow));
+        assert_eq!(t0_for_t1(ray(1), U256::MAX, Rounding::Down), Err(PriceError::Overflow));
+        assert_eq!(t0_for_t1(Ray::ZERO, U256::from(1), Rounding::Up), Err(PriceError::ZeroPrice));
+        // the largest prices still have a midpoint
+        assert_eq!(midpoint(max, max, Rounding::Up), max);
+        // and the largest sqrt price a ray
+        let sqrt_max = sqrt_price(U160::MAX);
+        assert!(
+            ray_from_sqrt_price(sqrt_max, Rounding::Down)
+                <= ray_from_sqrt_price(sqrt_max, Rounding::Up)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use super::{Ray, SqrtPriceX96};
+
+/// Adjusts a raw token1/token0 price, as quoted in the tokens' smallest units,
+/// to a price in whole tokens.
+pub fn decimal_adjusted_price(raw_price: f64, token0_decimals: u8, token1_decimals: u8) -> f64 {
+    raw_price * 10f64.powi(token0_decimals as i32 - token1_decimals as i32)
+}
+
+/// A price in its raw representations alongside the normalized decimal price
+/// (token1 per token0), so that consumers don't have to do the conversions
+/// themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedPrice {
+    pub ray:            U256,
+    pub sqrt_price_x96: U256,
+    /// `None` if the decimals of the pair are unknown
+    pub price:          Option<f64>
+}
+
+impl FormattedPrice {
+    /// `decimals` are the decimals of (token0, token1)
+    pub fn from_ray(ray: Ray, decimals: Option<(u8, u8)>) -> Self {
+        Self {
+            ray:            *ray,
+            sqrt_price_x96: SqrtPriceX96::from(ray).into(),
+            price:          decimals.map(|(d0, d1)| decimal_adjusted_price(ray.as_f64(), d0, d1))
+        }
+    }
+
+    pub fn from_sqrt_price(sqrt_price: SqrtPriceX96, decimals: Option<(u8, u8)>) -> Self {
+        Self {
+            ray:            *Ray::from(sqrt_price),
+            sqrt_price_x96: sqrt_price.into(),
+            price:          decimals
+                .map(|(d0, d1)| decimal_adjusted_price(sqrt_price.as_f64(), d0, d1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjusts_for_decimals() {
+        // 1 WETH (18 decimals) = 2500 USDC (6 decimals) in raw units
+        let raw = 2500.0 * 1e6 / 1e18;
+        let price = decimal_adjusted_price(raw, 18, 6);
+        assert!((price - 2500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ray_and_sqrt_price_agree() {
+        let ray = Ray::from(2.5e-9);
+        let from_ray = FormattedPrice::from_ray(ray, Some((18, 6)));
+        let from_sqrt = FormattedPrice::from_sqrt_price(SqrtPriceX96::from(ray), Some((18, 6)));
+
+        let (a, b) = (from_ray.price.unwrap(), from_sqrt.price.unwrap());
+        assert!((a - 2500.0).abs() < 1e-6);
+        assert!((a - b).abs() / a < 1e-9);
+        assert_eq!(FormattedPrice::from_ray(ray, None).price, None);
+    }
+}
@@ -1,17 +1,17 @@
 use std::ops::{Add, AddAssign, Deref, Sub, SubAssign};
 
-use alloy::primitives::{aliases::U320, Uint, U256, U512};
+use alloy::primitives::{Uint, U256};
 use malachite::{
-    num::{
-        arithmetic::traits::{DivRound, Pow},
-        conversion::traits::RoundingInto
-    },
+    num::{arithmetic::traits::Pow, conversion::traits::RoundingInto},
     rounding_modes::RoundingMode,
     Natural, Rational
 };
 use serde::{Deserialize, Serialize};
 
-use super::{const_2_192, MatchingPrice, SqrtPriceX96};
+use super::{
+    price::{self, PriceError, Rounding},
+    MatchingPrice, SqrtPriceX96
+};
 use crate::matching::const_1e27;
 #[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Ray(U256);
@@ -111,14 +111,7 @@ impl From<&Ray> for f64 {
 
 impl From<SqrtPriceX96> for Ray {
     fn from(value: SqrtPriceX96) -> Self {
-        let p: U320 = value.widening_mul(*value);
-
-        let numerator = Natural::from_limbs_asc(p.as_limbs()) * const_1e27();
-        let (res, _) =
-            numerator.div_round(const_2_192(), malachite::rounding_modes::RoundingMode::Floor);
-        let reslimbs = res.into_limbs_asc();
-        let output: U256 = Uint::from_limbs_slice(&reslimbs);
-        Self(output)
+        price::ray_from_sqrt_price(value, Rounding::Down)
     }
 }
 
@@ -170,31 +163,42 @@ impl Ray {
     }
 
     /// Given a price ratio t1/t0 calculates how much t1 would be needed to
-    /// output the provided amount of t0 (q)
+    /// output the provided amount of t0 (q). Rounds up, against the taker
+    /// paying for `q`, so the dust stays with the counterparty.
     pub fn mul_quantity(&self, q: U256) -> U256 {
-        let p: U512 = self.0.widening_mul(q);
-        let numerator = Natural::from_limbs_asc(p.as_limbs());
-        let (res, _) =
-            numerator.div_round(const_1e27(), malachite::rounding_modes::RoundingMode::Ceiling);
-        let reslimbs = res.into_limbs_asc();
-        Uint::from_limbs_slice(&reslimbs)
+        self.checked_mul_quantity(q, Rounding::Up)
+            .expect("quantity overflowed")
     }
 
     /// Given a price ratio t1/t0 calculates how much t0 would be needed to
-    /// output the provided amount of t1 (q)
+    /// output the provided amount of t1 (q). Rounds up like
+    /// [`Ray::mul_quantity`].
     pub fn inverse_quantity(&self, q: U256) -> U256 {
-        let numerator = Natural::from_limbs_asc(q.as_limbs()) * const_1e27();
-        let denominator = Natural::from_limbs_asc(self.0.as_limbs());
-        let output = Rational::from_naturals(numerator, denominator);
-        let (natout, _): (Natural, _) = output.rounding_into(RoundingMode::Ceiling);
-        U256::from_limbs_slice(&natout.to_limbs_asc())
+        self.checked_inverse_quantity(q, Rounding::Up)
+            .expect("quantity overflowed or zero price")
+    }
+
+    /// What `q` of t0 is worth in t1. Round up for what a taker pays and down
+    /// for what they receive.
+    pub fn checked_mul_quantity(&self, q: U256, rounding: Rounding) -> Result<U256, PriceError> {
+        price::t1_for_t0(*self, q, rounding)
+    }
+
+    /// What `q` of t1 is worth in t0. Round up for what a taker pays and down
+    /// for what they receive.
+    pub fn checked_inverse_quantity(
+        &self,
+        q: U256,
+        rounding: Rounding
+    ) -> Result<U256, PriceError> {
+        price::t0_for_t1(*self, q, rounding)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use alloy::primitives::U160;
-    use rand::{thread_rng, Rng};
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 
     use super::*;
 
@@ -240,4 +244,43 @@ mod tests {
         assert!(sp == sptwo);
         assert!(sp == spthree);
     }
+
+    #[test]
+    fn rounding_against_the_taker_never_creates_value() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1_000 {
+            let price = Ray::from(U256::from(rng.gen_range(1_u128..u128::MAX)));
+            let q = U256::from(rng.gen::<u64>());
+
+            // buying q of t0 never costs less than selling it back yields
+            let paid = price.mul_quantity(q);
+            let received = price.checked_mul_quantity(q, Rounding::Down).unwrap();
+            assert!(received <= paid && paid - received <= U256::from(1));
+            let paid = price.inverse_quantity(q);
+            let received = price.checked_inverse_quantity(q, Rounding::Down).unwrap();
+            assert!(received <= paid && paid - received <= U256::from(1));
+
+            // what a bundle takes in for an order is worth at least what it pays
+            // out, whichever side the order is on
+            let bid_in = price.mul_quantity(q);
+            assert!(
+                price
+                    .checked_inverse_quantity(bid_in, Rounding::Down)
+                    .unwrap()
+                    >= q
+            );
+            let ask_in = price.inverse_quantity(q);
+            assert!(price.checked_mul_quantity(ask_in, Rounding::Down).unwrap() >= q);
+        }
+    }
+
+    #[test]
+    fn checked_quantities_report_overflow() {
+        let max = Ray::from(U256::MAX);
+        assert_eq!(max.checked_mul_quantity(U256::MAX, Rounding::Up), Err(PriceError::Overflow));
+        assert_eq!(
+            Ray::ZERO.checked_inverse_quantity(U256::from(1), Rounding::Up),
+            Err(PriceError::ZeroPrice)
+        );
+    }
 }
@@ -3,14 +3,17 @@ use std::ops::Deref;
 use alloy::primitives::{Uint, U160, U256};
 use malachite::{
     num::{
-        arithmetic::traits::{CeilingRoot, DivRound, Pow, PowerOf2},
+        arithmetic::traits::{Pow, PowerOf2},
         conversion::traits::RoundingInto
     },
     Natural, Rational
 };
 use uniswap_v3_math::tick_math::{get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio};
 
-use super::{const_1e27, const_2_192, Ray};
+use super::{
+    price::{self, Rounding},
+    Ray
+};
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SqrtPriceX96(U160);
@@ -71,12 +74,6 @@ impl From<U160> for SqrtPriceX96 {
 
 impl From<Ray> for SqrtPriceX96 {
     fn from(value: Ray) -> Self {
-        let numerator = Natural::from_limbs_asc(value.as_limbs()) * const_2_192();
-        let (res, _) =
-            numerator.div_round(const_1e27(), malachite::rounding_modes::RoundingMode::Ceiling);
-        let root = res.ceiling_root(2);
-        let reslimbs = root.into_limbs_asc();
-        let output: U160 = Uint::from_limbs_slice(&reslimbs);
-        Self(output)
+        price::sqrt_price_from_ray(value, Rounding::Up).expect("ray out of sqrt price range")
     }
 }
@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, collections::HashMap};
 
-use alloy::primitives::{Uint, I256, U256};
+use alloy::primitives::{Uint, I256, U160, U256};
 use eyre::{eyre, Context, OptionExt};
 use uniswap_v3_math::{
     sqrt_price_math::{
@@ -12,7 +12,10 @@ use uniswap_v3_math::{
 
 use super::{poolprice::PoolPrice, Direction, LiqRangeRef, Quantity, Tick};
 use crate::{
-    matching::{Ray, SqrtPriceX96},
+    matching::{
+        price::{self, Rounding},
+        Ray, SqrtPriceX96
+    },
     orders::OrderPrice
 };
 
@@ -372,7 +375,10 @@ impl<'a> PoolPriceVec<'a> {
 
     /// Returns `(quantity, price)`
     pub fn quantity(&self, target_price: OrderPrice) -> (U256, U256) {
-        let t: SqrtPriceX96 = Ray::from(*target_price).into();
+        // the target is the price of the other side, which is a bid when the
+        // AMM is selling. A target past the highest sqrt price doesn't bound it
+        let t = price::sqrt_price_from_ray(target_price.into(), Rounding::for_side(self.is_buy()))
+            .unwrap_or(SqrtPriceX96::from(U160::MAX));
 
         // If our target price is past our end bound, our quantity is the entire range
         if (self.is_buy() && t > self.end_bound.price) || t < self.end_bound.price {
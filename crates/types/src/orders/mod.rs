@@ -1,11 +1,13 @@
 mod fillstate;
 mod origin;
+mod priority;
 use alloy::primitives::U256;
 pub mod orderpool;
 
 pub use fillstate::*;
 pub use orderpool::*;
 pub use origin::*;
+pub use priority::*;
 use serde::{Deserialize, Serialize};
 
 pub type BookID = u128;
@@ -39,13 +39,34 @@ impl OrderId {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Compared by price, volume and gas. `received_at` is left out, the orders at
+/// a price level are only ranked by it through
+/// [`super::PriceLevelPriority::Arrival`].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
 pub struct OrderPriorityData {
-    pub price:  U256,
-    pub volume: u128,
-    pub gas:    u128
+    pub price:       U256,
+    pub volume:      u128,
+    /// what the gas of the order costs in its input token, zero until state
+    /// validation prices it
+    pub gas:         u128,
+    /// gas the order is estimated to use
+    #[serde(default)]
+    pub gas_units:   u128,
+    /// unix time in millis the order first reached a node, set when it's
+    /// validated. Only ranks orders under
+    /// [`super::PriceLevelPriority::Arrival`]
+    #[serde(default)]
+    pub received_at: u64
 }
 
+impl PartialEq for OrderPriorityData {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for OrderPriorityData {}
+
 impl PartialOrd for OrderPriorityData {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -85,3 +106,22 @@ pub enum StateValidationError {
     #[error("order: {0:?} did not have enough of {1:?}")]
     NotEnoughBalance(B256, Address)
 }
+
+impl From<StateValidationError> for angstrom_errors::ValidationError {
+    fn from(value: StateValidationError) -> Self {
+        match value {
+            StateValidationError::InvalidNonce(..) => Self::InvalidNonce,
+            StateValidationError::NotEnoughApproval(..) => Self::InsufficientApproval,
+            StateValidationError::NotEnoughBalance(..) => Self::InsufficientBalance
+        }
+    }
+}
+
+impl From<ValidationError> for angstrom_errors::ValidationError {
+    fn from(value: ValidationError) -> Self {
+        match value {
+            ValidationError::StateValidationError(e) => e.into(),
+            ValidationError::BadSigner => Self::InvalidSignature
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sol_bindings::grouped_orders::OrderWithStorageData;
+
+/// How the orders at the same price are ranked when the books are matched
+/// and the bundle is laid out. Every validator has to use the same one, or
+/// they won't agree on the proposals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceLevelPriority {
+    /// larger orders first, then the ones paying more gas
+    #[default]
+    Volume,
+    /// the orders that reached a node first go first
+    Arrival
+}
+
+impl FromStr for PriceLevelPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "volume" => Ok(Self::Volume),
+            "arrival" => Ok(Self::Arrival),
+            _ => Err(format!("unknown price level priority {s}, expected volume or arrival"))
+        }
+    }
+}
+
+impl PriceLevelPriority {
+    /// Ranks the bids, best first.
+    pub fn cmp_bids<T>(self, a: &OrderWithStorageData<T>, b: &OrderWithStorageData<T>) -> Ordering {
+        match self {
+            Self::Volume => b.priority_data.cmp(&a.priority_data),
+            Self::Arrival => b
+                .priority_data
+                .price
+                .cmp(&a.priority_data.price)
+                .then_with(|| Self::cmp_arrival(a, b))
+        }
+    }
+
+    /// Ranks the asks, best first.
+    pub fn cmp_asks<T>(self, a: &OrderWithStorageData<T>, b: &OrderWithStorageData<T>) -> Ordering {
+        match self {
+            Self::Volume => a.priority_data.cmp(&b.priority_data),
+            Self::Arrival => a
+                .priority_data
+                .price
+                .cmp(&b.priority_data.price)
+                .then_with(|| Self::cmp_arrival(a, b))
+        }
+    }
+
+    /// Orders that arrived at the same time are ranked by hash, so that every
+    /// node ranks them the same.
+    fn cmp_arrival<T>(a: &OrderWithStorageData<T>, b: &OrderWithStorageData<T>) -> Ordering {
+        a.priority_data
+            .received_at
+            .cmp(&b.priority_data.received_at)
+            .then_with(|| a.order_id.hash.cmp(&b.order_id.hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+
+    use super::*;
+    use crate::orders::{OrderId, OrderPriorityData};
+
+    fn order(price: u64, volume: u128, received_at: u64) -> OrderWithStorageData<()> {
+        OrderWithStorageData {
+            priority_data: OrderPriorityData {
+                price: U256::from(price),
+                volume,
+                gas: 0,
+                gas_units: 0,
+                received_at
+            },
+            order_id: OrderId { hash: B256::with_last_byte(volume as u8), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn arrival_ranks_within_the_price_level() {
+        let mut bids = vec![order(10, 2, 300), order(10, 1, 200), order(11, 3, 400)];
+        bids.sort_by(|a, b| PriceLevelPriority::Arrival.cmp_bids(a, b));
+        assert_eq!(
+            bids.iter()
+                .map(|o| o.priority_data.volume)
+                .collect::<Vec<_>>(),
+            [3, 1, 2]
+        );
+        bids.sort_by(|a, b| PriceLevelPriority::Volume.cmp_bids(a, b));
+        assert_eq!(
+            bids.iter()
+                .map(|o| o.priority_data.volume)
+                .collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+
+        let mut asks = vec![order(10, 2, 200), order(10, 1, 300), order(9, 3, 400)];
+        asks.sort_by(|a, b| PriceLevelPriority::Arrival.cmp_asks(a, b));
+        assert_eq!(
+            asks.iter()
+                .map(|o| o.priority_data.volume)
+                .collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+        asks.sort_by(|a, b| PriceLevelPriority::Volume.cmp_asks(a, b));
+        assert_eq!(
+            asks.iter()
+                .map(|o| o.priority_data.volume)
+                .collect::<Vec<_>>(),
+            [3, 1, 2]
+        );
+    }
+}
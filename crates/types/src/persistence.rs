@@ -0,0 +1,233 @@
+//! Versioned files for the state a node keeps across restarts. Every file
+//! carries the version of its layout next to the state, so that a node reading
+//! the file of an older release migrates it instead of misreading it, and
+//! refuses the file of a newer release.
+//!
+//! A file is the JSON object of the state with an extra `version` key, files
+//! written before versioning was introduced have no key and are version 0.
+//! Types that opt in also get a `checksum` key, the keccak of the state with
+//! its keys sorted.
+use std::{collections::BTreeMap, fs::File, io::BufReader, path::Path};
+
+use alloy_primitives::{keccak256, B256};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+const VERSION_KEY: &str = "version";
+const CHECKSUM_KEY: &str = "checksum";
+
+/// Takes the state of one version to the next one.
+pub type Migration = fn(Value) -> eyre::Result<Value>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersistenceError {
+    #[error("version {found} is not supported, expected at most {current}")]
+    UnsupportedVersion { found: u8, current: u8 },
+    #[error("no migration from version {0}")]
+    MissingMigration(u8),
+    #[error("migration from version {from} failed: {reason}")]
+    Migration { from: u8, reason: eyre::Report },
+    #[error("persisted state has to be a JSON object")]
+    NotAnObject,
+    #[error("checksum {found} doesn't match the state, expected {expected}")]
+    ChecksumMismatch { found: B256, expected: B256 },
+    #[error("state has no checksum")]
+    MissingChecksum,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error)
+}
+
+/// The migrations of a persisted type, keyed by the version they migrate from.
+#[derive(Debug, Clone, Default)]
+pub struct Migrations(BTreeMap<u8, Migration>);
+
+impl Migrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration from `from` to `from + 1`.
+    pub fn with_migration(mut self, from: u8, migration: Migration) -> Self {
+        self.0.insert(from, migration);
+        self
+    }
+
+    /// For layouts that didn't change between versions.
+    pub fn unchanged(value: Value) -> eyre::Result<Value> {
+        Ok(value)
+    }
+}
+
+/// State that is written to disk. The state must serialize to a JSON object
+/// without a `version` key of its own.
+pub trait Persisted: Serialize + DeserializeOwned {
+    /// Version of the current layout. Needs to be bumped, and a migration
+    /// from the previous version registered, whenever the layout changes.
+    const VERSION: u8;
+
+    /// Whether files carry a checksum, files of older versions without one
+    /// are still read.
+    const CHECKSUMMED: bool = false;
+
+    fn migrations() -> Migrations {
+        Migrations::new()
+    }
+
+    /// Writes to a temporary file first so that a crash mid-write never
+    /// leaves a truncated file behind.
+    fn write(&self, path: impl AsRef<Path>) -> Result<(), PersistenceError> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        serde_json::to_writer(File::create(&tmp)?, &encode(self)?)?;
+        std::fs::rename(tmp, path)?;
+
+        Ok(())
+    }
+
+    fn read(path: impl AsRef<Path>) -> Result<Self, PersistenceError> {
+        let file = File::open(path)?;
+        decode(serde_json::from_reader(BufReader::new(file))?)
+    }
+}
+
+pub fn encode<T: Persisted>(state: &T) -> Result<Value, PersistenceError> {
+    let Value::Object(mut object) = serde_json::to_value(state)? else {
+        return Err(PersistenceError::NotAnObject)
+    };
+    if T::CHECKSUMMED {
+        let checksum = checksum(&object)?;
+        object.insert(CHECKSUM_KEY.to_string(), serde_json::to_value(checksum)?);
+    }
+    object.insert(VERSION_KEY.to_string(), T::VERSION.into());
+
+    Ok(Value::Object(object))
+}
+
+/// Migrates the persisted state to the current version of `T`.
+pub fn decode<T: Persisted>(value: Value) -> Result<T, PersistenceError> {
+    let Value::Object(mut object) = value else { return Err(PersistenceError::NotAnObject) };
+    let version = match object.remove(VERSION_KEY) {
+        Some(version) => serde_json::from_value(version)?,
+        None => 0
+    };
+    if version > T::VERSION {
+        return Err(PersistenceError::UnsupportedVersion { found: version, current: T::VERSION })
+    }
+    match object.remove(CHECKSUM_KEY) {
+        Some(found) => {
+            let found = serde_json::from_value(found)?;
+            let expected = checksum(&object)?;
+            if found != expected {
+                return Err(PersistenceError::ChecksumMismatch { found, expected })
+            }
+        }
+        None if T::CHECKSUMMED && version == T::VERSION => {
+            return Err(PersistenceError::MissingChecksum)
+        }
+        None => {}
+    }
+
+    let migrations = T::migrations();
+    let mut value = Value::Object(object);
+    for from in version..T::VERSION {
+        let migrate = migrations
+            .0
+            .get(&from)
+            .ok_or(PersistenceError::MissingMigration(from))?;
+        value = migrate(value).map_err(|reason| PersistenceError::Migration { from, reason })?;
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+fn checksum(object: &Map<String, Value>) -> Result<B256, PersistenceError> {
+    Ok(keccak256(serde_json::to_vec(&sorted(&Value::Object(object.clone())))?))
+}
+
+/// The same value with the keys of every object in order, so that the
+/// checksum doesn't depend on the order keys are read in.
+fn sorted(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), sorted(value)))
+                    .collect()
+            )
+        }
+        Value::Array(values) => Value::Array(values.iter().map(sorted).collect()),
+        value => value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        amount: u64,
+        owner:  String
+    }
+
+    impl Persisted for State {
+        const CHECKSUMMED: bool = true;
+        const VERSION: u8 = 2;
+
+        fn migrations() -> Migrations {
+            Migrations::new()
+                .with_migration(0, Migrations::unchanged)
+                // version 1 called the amount `value`
+                .with_migration(1, |mut value| {
+                    let amount = value
+                        .as_object_mut()
+                        .and_then(|object| object.remove("value"))
+                        .ok_or_else(|| eyre::eyre!("missing value"))?;
+                    value["amount"] = amount;
+                    Ok(value)
+                })
+        }
+    }
+
+    #[test]
+    fn migrates_older_versions() {
+        let state = State { amount: 5, owner: "alice".to_string() };
+        assert_eq!(decode::<State>(encode(&state).unwrap()).unwrap(), state);
+        assert_eq!(
+            decode::<State>(json!({ "version": 1, "value": 5, "owner": "alice" })).unwrap(),
+            state
+        );
+        assert_eq!(decode::<State>(json!({ "value": 5, "owner": "alice" })).unwrap(), state);
+    }
+
+    #[test]
+    fn rejects_unreadable_state() {
+        assert!(matches!(
+            decode::<State>(json!({ "version": 3, "amount": 5, "owner": "alice" })),
+            Err(PersistenceError::UnsupportedVersion { found: 3, current: 2 })
+        ));
+        assert!(matches!(
+            decode::<State>(json!({ "version": 1, "owner": "alice" })),
+            Err(PersistenceError::Migration { from: 1, .. })
+        ));
+
+        let mut tampered = encode(&State { amount: 5, owner: "alice".to_string() }).unwrap();
+        tampered["amount"] = 6.into();
+        assert!(matches!(
+            decode::<State>(tampered),
+            Err(PersistenceError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            decode::<State>(json!({ "version": 2, "amount": 5, "owner": "alice" })),
+            Err(PersistenceError::MissingChecksum)
+        ));
+    }
+}
@@ -37,6 +37,13 @@ impl StandingVariants {
         }
     }
 
+    pub fn hook(&self) -> Address {
+        match self {
+            StandingVariants::Exact(o) => o.hook,
+            StandingVariants::Partial(o) => o.hook
+        }
+    }
+
     pub fn hook_data(&self) -> &Bytes {
         match self {
             StandingVariants::Exact(o) => &o.hookPayload,
@@ -59,6 +66,13 @@ impl FlashVariants {
         }
     }
 
+    pub fn hook(&self) -> Address {
+        match self {
+            FlashVariants::Exact(o) => o.hook,
+            FlashVariants::Partial(o) => o.hook
+        }
+    }
+
     pub fn hook_data(&self) -> &Bytes {
         match self {
             FlashVariants::Exact(o) => &o.hookPayload,
@@ -292,23 +306,27 @@ impl RawPoolOrder for StandingVariants {
         None
     }
 
-    fn is_valid_signature(&self) -> bool {
+    fn recover_signer(&self) -> Option<Address> {
         match self {
-            StandingVariants::Exact(e) => e.is_valid_signature(),
-            StandingVariants::Partial(p) => p.is_valid_signature()
+            StandingVariants::Exact(e) => e.recover_signer(),
+            StandingVariants::Partial(p) => p.recover_signer()
         }
     }
 
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        self.hook_data()
+    }
 }
 
 impl RawPoolOrder for FlashVariants {
-    fn is_valid_signature(&self) -> bool {
+    fn recover_signer(&self) -> Option<Address> {
         match self {
-            FlashVariants::Exact(e) => e.is_valid_signature(),
-            FlashVariants::Partial(p) => p.is_valid_signature()
+            FlashVariants::Exact(e) => e.recover_signer(),
+            FlashVariants::Partial(p) => p.recover_signer()
         }
     }
 
@@ -385,6 +403,10 @@ impl RawPoolOrder for FlashVariants {
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        self.hook_data()
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -428,12 +450,29 @@ impl GroupedVanillaOrder {
         }
     }
 
+    /// The quantity filled so far, always zero for exact orders.
+    pub fn amount_filled(&self) -> U256 {
+        match self {
+            Self::Standing(StandingVariants::Partial(part)) => U256::from(part.amountFilled),
+            Self::KillOrFill(FlashVariants::Partial(part)) => U256::from(part.amountFilled),
+            _ => U256::ZERO
+        }
+    }
+
+    /// The quantity that is still left to fill.
+    pub fn remaining_quantity(&self) -> U256 {
+        self.quantity().saturating_sub(self.amount_filled())
+    }
+
+    /// Adds `filled_quantity` to what is filled of a partial order. Exact
+    /// orders are returned as they are.
     pub fn fill(&self, filled_quantity: U256) -> Self {
+        let filled = |amount_filled: u128| amount_filled.saturating_add(filled_quantity.to());
         match self {
             Self::Standing(p) => match p {
                 StandingVariants::Partial(part) => {
                     Self::Standing(StandingVariants::Partial(PartialStandingOrder {
-                        amountFilled: filled_quantity.to(),
+                        amountFilled: filled(part.amountFilled),
                         ..part.clone()
                     }))
                 }
@@ -442,7 +481,7 @@ impl GroupedVanillaOrder {
             Self::KillOrFill(kof) => match kof {
                 FlashVariants::Partial(part) => {
                     Self::KillOrFill(FlashVariants::Partial(PartialFlashOrder {
-                        amountFilled: filled_quantity.to(),
+                        amountFilled: filled(part.amountFilled),
                         ..part.clone()
                     }))
                 }
@@ -529,25 +568,29 @@ impl RawPoolOrder for TopOfBlockOrder {
         self.assetOut
     }
 
-    fn is_valid_signature(&self) -> bool {
-        let Ok(sig) = Signature::new_from_bytes(&self.meta.signature) else { return false };
+    fn recover_signer(&self) -> Option<Address> {
+        let sig = Signature::new_from_bytes(&self.meta.signature).ok()?;
         let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
         sig.recover_signer_full_public_key(hash)
-            .map(|pk| Address::from_raw_public_key(&*pk) == self.meta.from)
-            .unwrap_or_default()
+            .ok()
+            .map(|pk| Address::from_raw_public_key(&*pk))
     }
 
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Searcher
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        &self.hookPayload
+    }
 }
 impl RawPoolOrder for PartialStandingOrder {
-    fn is_valid_signature(&self) -> bool {
-        let Ok(sig) = Signature::new_from_bytes(&self.meta.signature) else { return false };
+    fn recover_signer(&self) -> Option<Address> {
+        let sig = Signature::new_from_bytes(&self.meta.signature).ok()?;
         let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
         sig.recover_signer_full_public_key(hash)
-            .map(|pk| Address::from_raw_public_key(&*pk) == self.meta.from)
-            .unwrap_or_default()
+            .ok()
+            .map(|pk| Address::from_raw_public_key(&*pk))
     }
 
     fn flash_block(&self) -> Option<u64> {
@@ -593,15 +636,19 @@ impl RawPoolOrder for PartialStandingOrder {
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        &self.hookPayload
+    }
 }
 
 impl RawPoolOrder for ExactStandingOrder {
-    fn is_valid_signature(&self) -> bool {
-        let Ok(sig) = Signature::new_from_bytes(&self.meta.signature) else { return false };
+    fn recover_signer(&self) -> Option<Address> {
+        let sig = Signature::new_from_bytes(&self.meta.signature).ok()?;
         let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
         sig.recover_signer_full_public_key(hash)
-            .map(|pk| Address::from_raw_public_key(&*pk) == self.meta.from)
-            .unwrap_or_default()
+            .ok()
+            .map(|pk| Address::from_raw_public_key(&*pk))
     }
 
     fn flash_block(&self) -> Option<u64> {
@@ -622,8 +669,7 @@ impl RawPoolOrder for ExactStandingOrder {
     }
 
     fn amount_in(&self) -> u128 {
-        todo!();
-        // self.amount
+        self.amount
     }
 
     fn deadline(&self) -> Option<U256> {
@@ -649,15 +695,19 @@ impl RawPoolOrder for ExactStandingOrder {
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        &self.hookPayload
+    }
 }
 
 impl RawPoolOrder for PartialFlashOrder {
-    fn is_valid_signature(&self) -> bool {
-        let Ok(sig) = Signature::new_from_bytes(&self.meta.signature) else { return false };
+    fn recover_signer(&self) -> Option<Address> {
+        let sig = Signature::new_from_bytes(&self.meta.signature).ok()?;
         let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
         sig.recover_signer_full_public_key(hash)
-            .map(|pk| Address::from_raw_public_key(&*pk) == self.meta.from)
-            .unwrap_or_default()
+            .ok()
+            .map(|pk| Address::from_raw_public_key(&*pk))
     }
 
     fn flash_block(&self) -> Option<u64> {
@@ -703,15 +753,19 @@ impl RawPoolOrder for PartialFlashOrder {
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        &self.hookPayload
+    }
 }
 
 impl RawPoolOrder for ExactFlashOrder {
-    fn is_valid_signature(&self) -> bool {
-        let Ok(sig) = Signature::new_from_bytes(&self.meta.signature) else { return false };
+    fn recover_signer(&self) -> Option<Address> {
+        let sig = Signature::new_from_bytes(&self.meta.signature).ok()?;
         let hash = self.no_meta_eip712_signing_hash(&ANGSTROM_DOMAIN);
         sig.recover_signer_full_public_key(hash)
-            .map(|pk| Address::from_raw_public_key(&*pk) == self.meta.from)
-            .unwrap_or_default()
+            .ok()
+            .map(|pk| Address::from_raw_public_key(&*pk))
     }
 
     fn flash_block(&self) -> Option<u64> {
@@ -757,14 +811,18 @@ impl RawPoolOrder for ExactFlashOrder {
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        &self.hookPayload
+    }
 }
 
 impl RawPoolOrder for AllOrders {
-    fn is_valid_signature(&self) -> bool {
+    fn recover_signer(&self) -> Option<Address> {
         match self {
-            AllOrders::Standing(p) => p.is_valid_signature(),
-            AllOrders::Flash(kof) => kof.is_valid_signature(),
-            AllOrders::TOB(tob) => tob.is_valid_signature()
+            AllOrders::Standing(p) => p.recover_signer(),
+            AllOrders::Flash(kof) => kof.recover_signer(),
+            AllOrders::TOB(tob) => tob.recover_signer()
         }
     }
 
@@ -855,13 +913,21 @@ impl RawPoolOrder for AllOrders {
             AllOrders::TOB(_) => OrderLocation::Searcher
         }
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        match self {
+            AllOrders::Standing(p) => p.hook_payload(),
+            AllOrders::Flash(kof) => kof.hook_payload(),
+            AllOrders::TOB(tob) => tob.hook_payload()
+        }
+    }
 }
 
 impl RawPoolOrder for GroupedVanillaOrder {
-    fn is_valid_signature(&self) -> bool {
+    fn recover_signer(&self) -> Option<Address> {
         match self {
-            GroupedVanillaOrder::Standing(p) => p.is_valid_signature(),
-            GroupedVanillaOrder::KillOrFill(kof) => kof.is_valid_signature()
+            GroupedVanillaOrder::Standing(p) => p.recover_signer(),
+            GroupedVanillaOrder::KillOrFill(kof) => kof.recover_signer()
         }
     }
 
@@ -941,6 +1007,13 @@ impl RawPoolOrder for GroupedVanillaOrder {
             GroupedVanillaOrder::KillOrFill(_) => OrderLocation::Limit
         }
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        match self {
+            GroupedVanillaOrder::Standing(p) => p.hook_payload(),
+            GroupedVanillaOrder::KillOrFill(kof) => kof.hook_payload()
+        }
+    }
 }
 
 impl RawPoolOrder for GroupedComposableOrder {
@@ -1014,10 +1087,10 @@ impl RawPoolOrder for GroupedComposableOrder {
         }
     }
 
-    fn is_valid_signature(&self) -> bool {
+    fn recover_signer(&self) -> Option<Address> {
         match self {
-            GroupedComposableOrder::Partial(p) => p.is_valid_signature(),
-            GroupedComposableOrder::KillOrFill(kof) => kof.is_valid_signature()
+            GroupedComposableOrder::Partial(p) => p.recover_signer(),
+            GroupedComposableOrder::KillOrFill(kof) => kof.recover_signer()
         }
     }
 
@@ -1027,4 +1100,11 @@ impl RawPoolOrder for GroupedComposableOrder {
             GroupedComposableOrder::KillOrFill(_) => OrderLocation::Limit
         }
     }
+
+    fn hook_payload(&self) -> &[u8] {
+        match self {
+            GroupedComposableOrder::Partial(p) => p.hook_payload(),
+            GroupedComposableOrder::KillOrFill(kof) => kof.hook_payload()
+        }
+    }
 }
@@ -41,9 +41,18 @@ pub trait RawPoolOrder: fmt::Debug + Send + Sync + Clone + Unpin + 'static {
     /// token out
     fn token_out(&self) -> Address;
 
-    fn is_valid_signature(&self) -> bool;
+    /// The address that signed the order, `None` if the signature doesn't
+    /// recover
+    fn recover_signer(&self) -> Option<Address>;
+
+    fn is_valid_signature(&self) -> bool {
+        self.recover_signer() == Some(self.from())
+    }
 
     fn order_location(&self) -> OrderLocation;
+
+    /// calldata passed to the order's hook
+    fn hook_payload(&self) -> &[u8];
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Copy)]
@@ -3,7 +3,10 @@ use std::{
     future::Future,
     hash::Hash,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc
+    },
     task::{Poll, Waker}
 };
 
@@ -19,6 +22,9 @@ pub struct KeySplitThreadpool<K: PartialEq + Eq + Hash + Clone, F: Future, TP: T
     pending_results: FuturesUnordered<PendingFut<F>>,
     permit_size:     usize,
     pending:         HashMap<K, Arc<Semaphore>>,
+    /// number of tasks that currently hold a permit and are running on the
+    /// threadpool
+    active_tasks:    Arc<AtomicUsize>,
     waker:           Option<Waker>
 }
 
@@ -35,10 +41,28 @@ where
             permit_size,
             pending: HashMap::default(),
             pending_results: FuturesUnordered::default(),
+            active_tasks: Arc::new(AtomicUsize::new(0)),
             waker: None
         }
     }
 
+    /// number of tasks that are currently executing on the threadpool
+    pub fn active_tasks(&self) -> usize {
+        self.active_tasks.load(Ordering::Relaxed)
+    }
+
+    /// number of tasks that are waiting on a permit for their key
+    pub fn queued_tasks(&self) -> usize {
+        self.pending_results
+            .len()
+            .saturating_sub(self.active_tasks())
+    }
+
+    /// number of distinct keys that have been seen by the threadpool
+    pub fn tracked_keys(&self) -> usize {
+        self.pending.len()
+    }
+
     pub fn add_new_task(&mut self, key: K, fut: F) {
         // grab semaphore
         let permit = self
@@ -47,10 +71,13 @@ where
             .or_insert_with(|| Arc::new(Semaphore::new(self.permit_size)));
         let permit_cloned = permit.clone();
         let tp_cloned = self.tp.clone();
+        let active_tasks = self.active_tasks.clone();
 
         let fut = Box::pin(async move {
             let permit = permit_cloned.acquire().await.expect("never");
+            active_tasks.fetch_add(1, Ordering::Relaxed);
             let res = tp_cloned.spawn(fut).await;
+            active_tasks.fetch_sub(1, Ordering::Relaxed);
             drop(permit);
 
             res
@@ -88,3 +115,52 @@ where
             .filter(|inner| inner.is_some())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// A task that runs until its sender is used.
+    fn blocked_task() -> (oneshot::Sender<()>, Task) {
+        let (tx, rx) = oneshot::channel();
+        (
+            tx,
+            Box::pin(async move {
+                let _ = rx.await;
+            })
+        )
+    }
+
+    #[tokio::test]
+    async fn tracks_running_and_queued_tasks() {
+        let mut pool: KeySplitThreadpool<u8, Task, _> =
+            KeySplitThreadpool::new(tokio::runtime::Handle::current(), 1);
+        let senders = [0, 0, 1]
+            .into_iter()
+            .map(|key| {
+                let (tx, task) = blocked_task();
+                pool.add_new_task(key, task);
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        // tasks only take a permit once polled, and each key has one
+        assert!(futures::poll!(pool.next()).is_pending());
+        assert_eq!(pool.active_tasks(), 2);
+        assert_eq!(pool.queued_tasks(), 1);
+        assert_eq!(pool.tracked_keys(), 2);
+
+        senders.into_iter().for_each(|tx| {
+            let _ = tx.send(());
+        });
+        for _ in 0..3 {
+            pool.next().await;
+        }
+        assert_eq!(pool.active_tasks(), 0);
+        assert_eq!(pool.queued_tasks(), 0);
+    }
+}
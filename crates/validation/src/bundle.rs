@@ -0,0 +1,143 @@
+//! Runs `Angstrom::execute` with a bundle under revm, so that the leader can
+//! check its bundle before proposing it, and snapshots the pools bundles are
+//! built against.
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, BlockNumber, Bytes, TxKind, U256};
+use angstrom_types::{
+    contract_payloads::angstrom::AngstromBundle, matching::uniswap::PoolSnapshot, primitive::PoolId
+};
+use futures::future::BoxFuture;
+use reth_provider::StateProviderFactory;
+use reth_revm::database::StateProviderDatabase;
+use revm::{primitives::ExecutionResult, Evm};
+use thiserror::Error;
+
+use crate::{order::state::config::ValidationConfig, validator::ValidationClient};
+
+/// Gas the simulated `execute` call may use.
+const SIMULATION_GAS_LIMIT: u64 = 30_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BundleSimError {
+    #[error("bundle reverted with {0}")]
+    Reverted(Bytes),
+    #[error("bundle halted: {0}")]
+    Halted(String),
+    /// the simulation itself failed, which says nothing about the bundle
+    #[error("failed to simulate the bundle: {0}")]
+    Simulation(String)
+}
+
+pub trait BundleSimulator: Send + Sync {
+    /// Executes the bundle on top of the state at `block_number`.
+    fn simulate(
+        &self,
+        bundle: &AngstromBundle,
+        block_number: BlockNumber
+    ) -> Result<(), BundleSimError>;
+}
+
+/// Simulates bundles against the state of the node's own database.
+pub struct RevmBundleSimulator<DB> {
+    db:               DB,
+    angstrom_address: Address,
+    /// the node the bundle is executed by, as `execute` is only open to nodes
+    node_address:     Address
+}
+
+impl<DB> RevmBundleSimulator<DB> {
+    pub fn new(db: DB, angstrom_address: Address, node_address: Address) -> Self {
+        Self { db, angstrom_address, node_address }
+    }
+}
+
+impl<DB: StateProviderFactory> BundleSimulator for RevmBundleSimulator<DB> {
+    fn simulate(
+        &self,
+        bundle: &AngstromBundle,
+        block_number: BlockNumber
+    ) -> Result<(), BundleSimError> {
+        let state = self
+            .db
+            .state_by_block_id(block_number.into())
+            .map_err(|e| BundleSimError::Simulation(e.to_string()))?;
+        let mut evm = Evm::builder()
+            .with_ref_db(StateProviderDatabase::new(state))
+            .modify_block_env(|block| block.number = U256::from(block_number + 1))
+            .modify_tx_env(|tx| {
+                tx.caller = self.node_address;
+                tx.transact_to = TxKind::Call(self.angstrom_address);
+                tx.data = bundle.execute_calldata();
+                tx.gas_limit = SIMULATION_GAS_LIMIT;
+            })
+            .build();
+        let result = evm
+            .transact()
+            .map_err(|e| BundleSimError::Simulation(e.to_string()))?
+            .result;
+
+        match result {
+            ExecutionResult::Success { .. } => Ok(()),
+            ExecutionResult::Revert { output, .. } => Err(BundleSimError::Reverted(output)),
+            ExecutionResult::Halt { reason, .. } => {
+                Err(BundleSimError::Halted(format!("{reason:?}")))
+            }
+        }
+    }
+}
+
+/// The pools a bundle is built against by pool id, with their tokens, a
+/// snapshot of their liquidity and their index in the angstrom pool store.
+pub type BundlePools = HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>;
+
+pub trait BundlePoolSource: Send + Sync {
+    /// Snapshots the given pools as they are now. Fails when any of them is
+    /// unknown, as the bundle would leave out its solution.
+    fn bundle_pools(&self, pool_ids: Vec<PoolId>) -> BoxFuture<'static, eyre::Result<BundlePools>>;
+}
+
+/// Snapshots the pools synced by the validator.
+pub struct ValidatorBundlePools {
+    client: ValidationClient,
+    /// tokens and store index of the configured pools
+    pools:  HashMap<PoolId, (Address, Address, u16)>
+}
+
+impl ValidatorBundlePools {
+    pub fn new(client: ValidationClient, config: &ValidationConfig) -> Self {
+        let pools = config
+            .pools
+            .iter()
+            .map(|pool| (pool.pool_id, (pool.token0, pool.token1, pool.store_index)))
+            .collect();
+
+        Self { client, pools }
+    }
+}
+
+impl BundlePoolSource for ValidatorBundlePools {
+    fn bundle_pools(&self, pool_ids: Vec<PoolId>) -> BoxFuture<'static, eyre::Result<BundlePools>> {
+        let client = self.client.clone();
+        let pools = pool_ids
+            .into_iter()
+            .map(|pool_id| {
+                let pool = self
+                    .pools
+                    .get(&pool_id)
+                    .ok_or_else(|| eyre::eyre!("no config for pool {pool_id:?}"))?;
+                Ok((pool_id, *pool))
+            })
+            .collect::<eyre::Result<Vec<_>>>();
+
+        Box::pin(async move {
+            let mut bundle_pools = BundlePools::new();
+            for (pool_id, (token0, token1, store_index)) in pools? {
+                let snapshot = client.amm_snapshot(pool_id).await?;
+                bundle_pools.insert(pool_id, (token0, token1, snapshot, store_index));
+            }
+
+            Ok(bundle_pools)
+        })
+    }
+}
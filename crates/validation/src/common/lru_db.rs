@@ -1,6 +1,9 @@
 use std::{
     collections::HashMap,
-    sync::{atomic::AtomicU64, Arc}
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc
+    }
 };
 
 use alloy::primitives::{Address, BlockNumber, StorageKey, StorageValue};
@@ -64,13 +67,27 @@ impl<T: StateProviderFactory> BlockStateProviderFactory for T {
     }
 }
 
+/// The view of the chain a [`RevmLRU`] reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockView {
+    /// Follows the block watcher.
+    Latest,
+    /// Pinned to a single block. As the shared cache is updated in place on
+    /// every new block, it's only read while the epoch it was pinned at is
+    /// still current.
+    Pinned { epoch: Option<u64> }
+}
+
 pub struct RevmLRU<DB> {
     state_overrides:    RwLock<HashMap<Address, HashMap<U256, U256>>>,
     bytecode_overrides: RwLock<HashMap<Address, Bytecode>>,
     accounts:           Arc<RwLock<LruMap<Address, DbAccount, ByMemoryUsage>>>,
     contracts:          Arc<RwLock<LruMap<B256, Bytecode, ByMemoryUsage>>>,
     db:                 Arc<DB>,
-    current_block:      Arc<AtomicU64>
+    current_block:      Arc<AtomicU64>,
+    /// bumped every time the block or the cached state changes
+    epoch:              Arc<AtomicU64>,
+    view:               BlockView
 }
 
 impl<DB: Clone> Clone for RevmLRU<DB> {
@@ -81,7 +98,9 @@ impl<DB: Clone> Clone for RevmLRU<DB> {
             accounts:           self.accounts.clone(),
             contracts:          self.contracts.clone(),
             db:                 self.db.clone(),
-            current_block:      self.current_block.clone()
+            current_block:      self.current_block.clone(),
+            epoch:              self.epoch.clone(),
+            view:               self.view
         }
     }
 }
@@ -92,6 +111,7 @@ where
 {
     fn update_evm_state(&self, slot_changes: &AddressSlots) -> eyre::Result<()> {
         let mut accounts = self.accounts.write();
+        self.epoch.fetch_add(1, Ordering::SeqCst);
 
         for (addr, storage) in slot_changes.iter() {
             let acct_storage = accounts
@@ -125,13 +145,56 @@ where
             contracts,
             db,
             state_overrides: HashMap::default().into(),
-            bytecode_overrides: HashMap::default().into()
+            bytecode_overrides: HashMap::default().into(),
+            epoch: Arc::new(AtomicU64::new(0)),
+            view: BlockView::Latest
         }
     }
 
     pub fn update_block_number(&self, block_number: u64) {
-        self.current_block
-            .store(block_number, std::sync::atomic::Ordering::SeqCst)
+        if let BlockView::Pinned { .. } = self.view {
+            tracing::warn!(block_number, "tried to update the block of a pinned view");
+            return
+        }
+
+        // under the cache lock, so that readers see the block and the cache
+        // change together
+        let _accounts = self.accounts.write();
+        let prev = self.current_block.swap(block_number, Ordering::SeqCst);
+        if prev != block_number {
+            self.epoch.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns a view that reads all state at `block` for as long as it lives,
+    /// regardless of any block updates that land in the meantime. Validation
+    /// tasks should pin their block so that they never mix state across
+    /// blocks.
+    pub fn pinned_at_block(&self, block: u64) -> Self {
+        // take the epoch before the block so a concurrent update can only make
+        // us skip the cache, never read it for the wrong block
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let cache_is_current = self.current_block.load(Ordering::SeqCst) == block;
+
+        Self {
+            state_overrides:    self.state_overrides.read().clone().into(),
+            bytecode_overrides: self.bytecode_overrides.read().clone().into(),
+            accounts:           self.accounts.clone(),
+            contracts:          self.contracts.clone(),
+            db:                 self.db.clone(),
+            current_block:      Arc::new(AtomicU64::new(block)),
+            epoch:              self.epoch.clone(),
+            view:               BlockView::Pinned { epoch: cache_is_current.then_some(epoch) }
+        }
+    }
+
+    /// Whether the shared cache no longer reflects the block of this view.
+    /// Only final while the cache lock is held.
+    pub fn is_stale(&self) -> bool {
+        match self.view {
+            BlockView::Latest => false,
+            BlockView::Pinned { epoch } => epoch != Some(self.epoch.load(Ordering::SeqCst))
+        }
     }
 
     pub fn set_state_overrides(&self, overrides: HashMap<Address, HashMap<U256, U256>>) {
@@ -163,7 +226,7 @@ where
 
     fn get_current_provider(&self) -> ProviderResult<P> {
         self.db
-            .state_by_block(self.current_block.load(std::sync::atomic::Ordering::SeqCst))
+            .state_by_block(self.current_block.load(Ordering::SeqCst))
     }
 }
 
@@ -197,7 +260,13 @@ where
     type Error = RethError;
 
     fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        // checked under the lock, the cache can't move to another block between
+        // the check and the read
         let mut accounts = self.accounts.write();
+        if self.is_stale() {
+            drop(accounts);
+            return self.basic_ref_no_cache(&address)
+        }
 
         accounts
             .get(&address)
@@ -219,6 +288,10 @@ where
         }
 
         let mut accounts = self.accounts.write();
+        if self.is_stale() {
+            drop(accounts);
+            return self.storage_ref_no_cache(&address, index)
+        }
 
         Ok(accounts
             .get(&address)
@@ -244,3 +317,94 @@ where
                        // blocks
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+
+    const SLOT: U256 = U256::ZERO;
+
+    /// Every account holds the block number in [`SLOT`], cached values are set
+    /// apart from it so that the tests see where a read was served from.
+    struct BlockNumberState(u64);
+
+    impl BlockStateProvider for BlockNumberState {
+        fn get_basic_account(&self, _: Address) -> ProviderResult<Option<Account>> {
+            Ok(Some(Account::default()))
+        }
+
+        fn get_storage(&self, _: Address, _: StorageKey) -> ProviderResult<Option<StorageValue>> {
+            Ok(Some(U256::from(self.0)))
+        }
+    }
+
+    struct BlockNumberDb;
+
+    impl BlockStateProviderFactory for BlockNumberDb {
+        type Provider = BlockNumberState;
+
+        fn state_by_block(&self, block: u64) -> ProviderResult<BlockNumberState> {
+            Ok(BlockNumberState(block))
+        }
+
+        fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+            Ok(0)
+        }
+    }
+
+    fn lru_at(block: u64) -> RevmLRU<BlockNumberDb> {
+        RevmLRU::new(1_000_000, Arc::new(BlockNumberDb), Arc::new(AtomicU64::new(block)))
+    }
+
+    fn account(value: u64) -> DbAccount {
+        let mut account = DbAccount::default();
+        account.storage.insert(SLOT, U256::from(value));
+        account
+    }
+
+    /// Caches `value` for the slot, as a state update of the cached block
+    /// would.
+    fn cache(lru: &RevmLRU<BlockNumberDb>, address: Address, value: u64) {
+        lru.accounts.write().insert(address, account(value));
+    }
+
+    #[test]
+    fn pinned_views_skip_the_cache_once_it_moves_on() {
+        let lru = lru_at(1);
+        let address = Address::with_last_byte(1);
+        cache(&lru, address, 10);
+
+        let pinned = lru.pinned_at_block(1);
+        assert_eq!(pinned.storage_ref(address, SLOT).unwrap(), U256::from(10));
+
+        lru.update_block_number(2);
+        cache(&lru, address, 20);
+        assert!(pinned.is_stale());
+        // read from the database at the pinned block
+        assert_eq!(pinned.storage_ref(address, SLOT).unwrap(), U256::from(1));
+        assert_eq!(lru.storage_ref(address, SLOT).unwrap(), U256::from(20));
+
+        // a view pinned at a block the cache isn't at never reads it
+        assert!(lru.pinned_at_block(1).is_stale());
+    }
+
+    #[test]
+    fn staleness_is_checked_under_the_cache_lock() {
+        let lru = lru_at(1);
+        let address = Address::with_last_byte(1);
+        cache(&lru, address, 10);
+        let pinned = lru.pinned_at_block(1);
+
+        // move the cache to the next block while the read waits on the lock
+        let mut accounts = lru.accounts.write();
+        let reader = thread::spawn(move || pinned.storage_ref(address, SLOT).unwrap());
+        thread::sleep(Duration::from_millis(50));
+        lru.epoch.fetch_add(1, Ordering::SeqCst);
+        accounts.insert(address, account(20));
+        drop(accounts);
+
+        assert_eq!(reader.join().unwrap(), U256::from(1));
+    }
+}
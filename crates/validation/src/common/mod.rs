@@ -1,6 +1,8 @@
 pub mod executor;
 pub mod lru_db;
+pub mod remote_db;
 pub mod revm;
+pub mod signer_cache;
 pub mod state;
 
 use reth_provider::StateProviderFactory;
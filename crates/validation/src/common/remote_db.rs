@@ -0,0 +1,179 @@
+//! State access through the json-rpc of a remote archive node, for follower
+//! deployments that don't run next to a reth database. Every read is a round
+//! trip to the node, so this should only ever sit behind a [`RevmLRU`].
+//!
+//! [`RevmLRU`]: crate::common::lru_db::RevmLRU
+use std::{future::IntoFuture, marker::PhantomData, sync::Arc};
+
+use alloy::{
+    primitives::{Address, BlockNumber, StorageKey, StorageValue, B256},
+    providers::Provider,
+    rpc::types::EIP1186AccountProofResponse,
+    transports::Transport
+};
+use reth_primitives::{Account, KECCAK_EMPTY};
+use reth_provider::{ProviderError, ProviderResult};
+use tokio::runtime::Handle;
+
+use crate::common::lru_db::{BlockStateProvider, BlockStateProviderFactory};
+
+/// Reads state with `eth_getProof` and `eth_getStorageAt` pinned to the
+/// requested block.
+pub struct RemoteStateProviderFactory<P, T> {
+    provider:   Arc<P>,
+    /// runtime the requests are driven on, validation reads state from sync
+    /// code
+    handle:     Handle,
+    _transport: PhantomData<T>
+}
+
+impl<P, T> Clone for RemoteStateProviderFactory<P, T> {
+    fn clone(&self) -> Self {
+        Self {
+            provider:   self.provider.clone(),
+            handle:     self.handle.clone(),
+            _transport: PhantomData
+        }
+    }
+}
+
+impl<P, T> RemoteStateProviderFactory<P, T>
+where
+    P: Provider<T> + 'static,
+    T: Transport + Clone
+{
+    /// Must be called from within a multi-threaded tokio runtime, which is
+    /// used to drive the requests.
+    pub fn new(provider: Arc<P>) -> Self {
+        Self { provider, handle: Handle::current(), _transport: PhantomData }
+    }
+}
+
+impl<P, T> BlockStateProviderFactory for RemoteStateProviderFactory<P, T>
+where
+    P: Provider<T> + 'static,
+    T: Transport + Clone
+{
+    type Provider = RemoteStateProvider<P, T>;
+
+    fn state_by_block(&self, block: u64) -> ProviderResult<Self::Provider> {
+        Ok(RemoteStateProvider { block, factory: self.clone() })
+    }
+
+    fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+        block_on(&self.handle, self.provider.get_block_number()).map_err(|e| {
+            tracing::warn!(%e, "failed to fetch the best block of the remote node");
+            ProviderError::BestBlockNotFound
+        })
+    }
+}
+
+pub struct RemoteStateProvider<P, T> {
+    block:   u64,
+    factory: RemoteStateProviderFactory<P, T>
+}
+
+impl<P, T> BlockStateProvider for RemoteStateProvider<P, T>
+where
+    P: Provider<T> + 'static,
+    T: Transport + Clone
+{
+    fn get_basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        let proof = self
+            .factory
+            .provider
+            .get_proof(address, vec![])
+            .block_id(self.block.into());
+
+        block_on(&self.factory.handle, proof)
+            .map(account_from_proof)
+            .map_err(|e| {
+                tracing::warn!(%e, ?address, block = self.block, "failed to fetch remote account");
+                ProviderError::AccountChangesetNotFound { block_number: self.block, address }
+            })
+    }
+
+    fn get_storage(
+        &self,
+        address: Address,
+        key: StorageKey
+    ) -> ProviderResult<Option<StorageValue>> {
+        let storage = self
+            .factory
+            .provider
+            .get_storage_at(address, key.into())
+            .block_id(self.block.into());
+
+        block_on(&self.factory.handle, storage)
+            .map(Some)
+            .map_err(|e| {
+                tracing::warn!(%e, ?address, block = self.block, "failed to fetch remote storage");
+                ProviderError::StorageChangesetNotFound {
+                    block_number: self.block,
+                    address,
+                    storage_key: Box::new(key)
+                }
+            })
+    }
+}
+
+/// `eth_getProof` answers with an empty account for addresses that don't
+/// exist, which reth represents as no account at all.
+fn account_from_proof(proof: EIP1186AccountProofResponse) -> Option<Account> {
+    let has_code = proof.code_hash != KECCAK_EMPTY && proof.code_hash != B256::ZERO;
+    if proof.nonce == 0 && proof.balance.is_zero() && !has_code {
+        return None
+    }
+
+    Some(Account {
+        nonce:         proof.nonce,
+        balance:       proof.balance,
+        bytecode_hash: has_code.then_some(proof.code_hash)
+    })
+}
+
+/// Blocks on the request, stepping out of the runtime first if we are called
+/// from one of its workers.
+fn block_on<F: IntoFuture>(handle: &Handle, request: F) -> F::Output {
+    let request = request.into_future();
+    if Handle::try_current().is_ok() {
+        tokio::task::block_in_place(|| handle.block_on(request))
+    } else {
+        handle.block_on(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{b256, U256};
+
+    use super::*;
+
+    #[test]
+    fn empty_proofs_are_missing_accounts() {
+        let proof = EIP1186AccountProofResponse { code_hash: KECCAK_EMPTY, ..Default::default() };
+        assert_eq!(account_from_proof(proof), None);
+
+        let eoa = EIP1186AccountProofResponse {
+            nonce: 1,
+            balance: U256::from(10),
+            code_hash: KECCAK_EMPTY,
+            ..Default::default()
+        };
+        assert_eq!(
+            account_from_proof(eoa),
+            Some(Account { nonce: 1, balance: U256::from(10), bytecode_hash: None })
+        );
+
+        let code_hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let contract = EIP1186AccountProofResponse { code_hash, ..Default::default() };
+        assert_eq!(
+            account_from_proof(contract),
+            Some(Account {
+                nonce:         0,
+                balance:       U256::ZERO,
+                bytecode_hash: Some(code_hash)
+            })
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Bounded cache of the signers recovered from order signatures. Recovering a
+//! signer is the most expensive of the stateless checks, and the same order is
+//! checked when it is received, gossiped and validated, so the components
+//! doing so share one cache.
+use std::sync::Arc;
+
+use alloy::primitives::{Address, B256};
+use angstrom_metrics::SignerCacheMetricsWrapper;
+use angstrom_types::sol_bindings::ext::RawPoolOrder;
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+
+/// Default max amount of signers held by the cache.
+pub const DEFAULT_SIGNER_CACHE_SIZE: u32 = 100_000;
+
+/// Maps order hashes to the signer recovered from the signature of the order,
+/// `None` for signatures that don't recover. The order hash covers the
+/// signature, so a cached signer always belongs to the signature it was
+/// recovered from.
+#[derive(Clone)]
+pub struct SignerCache {
+    signers: Arc<Mutex<LruMap<B256, Option<Address>, ByLength>>>,
+    metrics: SignerCacheMetricsWrapper
+}
+
+impl Default for SignerCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIGNER_CACHE_SIZE)
+    }
+}
+
+impl SignerCache {
+    pub fn new(max_signers: u32) -> Self {
+        Self {
+            signers: Arc::new(Mutex::new(LruMap::new(ByLength::new(max_signers)))),
+            metrics: SignerCacheMetricsWrapper::new()
+        }
+    }
+
+    pub fn recover_signer(&self, order: &impl RawPoolOrder) -> Option<Address> {
+        let hash = order.order_hash();
+        if let Some(signer) = self.cached(&hash) {
+            return signer
+        }
+
+        // recovered without holding the lock, so lookups of other orders
+        // aren't held up
+        let signer = order.recover_signer();
+        let mut signers = self.signers.lock();
+        signers.insert(hash, signer);
+        self.metrics.record_lookup(false, signers.len());
+
+        signer
+    }
+
+    pub fn is_valid_signature(&self, order: &impl RawPoolOrder) -> bool {
+        self.recover_signer(order) == Some(order.from())
+    }
+
+    pub fn len(&self) -> usize {
+        self.signers.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn cached(&self, hash: &B256) -> Option<Option<Address>> {
+        let mut signers = self.signers.lock();
+        let signer = signers.get(hash).copied()?;
+        self.metrics.record_lookup(true, signers.len());
+
+        Some(signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::{
+        grouped_orders::{AllOrders, FlashVariants},
+        rpc_orders::{ExactFlashOrder, OrderMeta}
+    };
+
+    use super::*;
+
+    /// An order whose signature doesn't recover.
+    fn order(block: u64, from: Address) -> AllOrders {
+        AllOrders::Flash(FlashVariants::Exact(ExactFlashOrder {
+            validForBlock: block,
+            meta: OrderMeta { from, ..Default::default() },
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn caches_signers_by_order_hash() {
+        let cache = SignerCache::new(4);
+
+        let unsigned = order(1, Address::with_last_byte(1));
+        assert_eq!(cache.recover_signer(&unsigned), None);
+        assert!(!cache.is_valid_signature(&unsigned));
+        assert_eq!(cache.len(), 1);
+
+        // the signer is part of the order hash, so claiming another one is a
+        // lookup of its own
+        assert!(!cache.is_valid_signature(&order(1, Address::with_last_byte(2))));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_signer() {
+        let cache = SignerCache::new(2);
+        for block in 0..3 {
+            cache.recover_signer(&order(block, Address::ZERO));
+        }
+
+        assert_eq!(cache.len(), 2);
+        let oldest = order(0, Address::ZERO).order_hash();
+        assert!(cache.signers.lock().peek(&oldest).is_none());
+    }
+}
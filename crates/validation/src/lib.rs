@@ -4,176 +4,460 @@
 #![allow(unused_variables)]
 #![allow(unreachable_code)]
 
+pub mod bundle;
 pub mod common;
 pub mod order;
 pub mod validator;
 
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc
-    }
+    },
+    time::{Duration, SystemTime}
 };
 
 use alloy::{
     network::Network, primitives::Address, providers::Provider,
     signers::k256::elliptic_curve::rand_core::block::BlockRngCore, transports::Transport
 };
+use angstrom_metrics::ValidationMetricsWrapper;
 use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
 use common::lru_db::{BlockStateProviderFactory, RevmLRU};
 use futures::Stream;
 use matching_engine::cfmm::uniswap::{
     pool::EnhancedUniswapV3Pool, pool_manager::UniswapPoolManager,
-    pool_providers::canonical_state_adapter::CanonicalStateAdapter
+    pool_providers::canonical_state_adapter::CanonicalStateAdapter,
+    tick_window::DEFAULT_TICKS_PER_SIDE
 };
 use order::state::{
-    config::load_validation_config,
+    config::{
+        listing::ListingDecision, load_validation_config, slot_probe::TokenSlotProber, PoolConfig,
+        ValidationConfig
+    },
     db_state_utils::{FetchUtils, StateFetchUtils},
-    pools::{AngstromPoolsTracker, PoolsTracker}
+    pools::{AngstromPoolsTracker, PoolsTracker},
+    token_pricing::TokenPriceGenerator
 };
 use reth_provider::{CanonStateNotifications, FullProvider, StateProviderFactory};
-use tokio::sync::mpsc::unbounded_channel;
-use validator::Validator;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use validator::{ValidationRequest, Validator};
 
 use crate::{
+    common::signer_cache::SignerCache,
     order::{
-        order_validator::OrderValidator, sim::SimValidation,
+        order_validator::OrderValidator,
+        sim::SimValidation,
+        stages::{SignatureStage, ValidationStage, ValidationStages},
         state::config::load_data_fetcher_config
     },
     validator::ValidationClient
 };
 
+/// Default path of the pool and token slot config, relative to the working
+/// directory.
 pub const TOKEN_CONFIG_FILE: &str = "crates/validation/src/state_config.toml";
 
-pub fn init_validation<DB: BlockStateProviderFactory + Unpin + Clone + 'static>(
-    db: DB,
+/// How often the config file is checked for changes.
+pub const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The amount of worker threads the validation runtime uses when autoscaling
+/// is disabled.
+pub const DEFAULT_VALIDATION_WORKER_THREADS: usize = 4;
+
+/// Returns the amount of worker threads to spawn the validation runtime with.
+/// If a max is given, the worker count scales with the available parallelism
+/// of the machine, bounded by the max.
+pub fn validation_worker_threads(max_workers: Option<usize>) -> usize {
+    let Some(max_workers) = max_workers else { return DEFAULT_VALIDATION_WORKER_THREADS };
+
+    std::thread::available_parallelism()
+        .map(|cores| cores.get())
+        .unwrap_or(DEFAULT_VALIDATION_WORKER_THREADS)
+        .clamp(1, max_workers.max(1))
+}
+
+/// Default size of the validation state cache, in bytes.
+pub const DEFAULT_VALIDATION_CACHE_BYTES: usize = 1_000_000;
+
+type RequestChannel = (UnboundedSender<ValidationRequest>, UnboundedReceiver<ValidationRequest>);
+
+/// Builds the order validator. By default orders go through the signature
+/// stage and the account checks; integrators can add the balance, gas and
+/// price band stages or their own, or replace the default ones.
+pub struct OrderValidatorBuilder<DB> {
+    db:                 DB,
     state_notification: CanonStateNotifications,
-    cache_max_bytes: usize
-) -> ValidationClient {
-    let (validator_tx, validator_rx) = unbounded_channel();
-    let config_path = Path::new(TOKEN_CONFIG_FILE);
-    let validation_config = load_validation_config(config_path).unwrap();
-    let data_fetcher_config = load_data_fetcher_config(config_path).unwrap();
-    let current_block = Arc::new(AtomicU64::new(db.best_block_number().unwrap()));
-    let revm_lru = Arc::new(RevmLRU::new(cache_max_bytes, Arc::new(db), current_block.clone()));
-    let fetch = FetchUtils::new(data_fetcher_config.clone(), revm_lru.clone());
-
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .worker_threads(4)
-            .build()
-            .unwrap();
-        let handle = rt.handle().clone();
-        // load storage slot state + pools
-        let pools = AngstromPoolsTracker::new(validation_config.clone());
-
-        // TODO: make the pool work with new styles addresses
-        let mut uniswap_pools: Vec<EnhancedUniswapV3Pool> = validation_config
-            .pools
-            .iter()
-            .map(|pool| {
-                let initial_ticks_per_side = 200;
-                EnhancedUniswapV3Pool::new(
-                    Address::from_slice(&pool.pool_id[..20]),
-                    initial_ticks_per_side
+    config_path:        PathBuf,
+    cache_max_bytes:    usize,
+    max_worker_threads: Option<usize>,
+    stages:             Vec<Box<dyn ValidationStage>>,
+    signers:            SignerCache,
+    requests:           Option<RequestChannel>
+}
+
+impl<DB: BlockStateProviderFactory + Unpin + Clone + 'static> OrderValidatorBuilder<DB> {
+    pub fn new(db: DB, state_notification: CanonStateNotifications) -> Self {
+        let signers = SignerCache::default();
+        Self {
+            db,
+            state_notification,
+            config_path: PathBuf::from(TOKEN_CONFIG_FILE),
+            cache_max_bytes: DEFAULT_VALIDATION_CACHE_BYTES,
+            max_worker_threads: None,
+            stages: vec![Box::new(SignatureStage::new(signers.clone()))],
+            signers,
+            requests: None
+        }
+    }
+
+    /// The signer cache of the default signature stage, for the components
+    /// that check the same orders to share.
+    pub fn signer_cache(&self) -> SignerCache {
+        self.signers.clone()
+    }
+
+    /// Serves requests from a channel created up front, for callers that need
+    /// a [`ValidationClient`] before the validator is built.
+    pub fn with_request_channel(
+        mut self,
+        tx: UnboundedSender<ValidationRequest>,
+        rx: UnboundedReceiver<ValidationRequest>
+    ) -> Self {
+        self.requests = Some((tx, rx));
+        self
+    }
+
+    /// Reads the pools and token slots from the given file instead of
+    /// [`TOKEN_CONFIG_FILE`]. The file is read again whenever it changes and
+    /// on every SIGHUP.
+    pub fn with_config_path(mut self, config_path: impl Into<PathBuf>) -> Self {
+        self.config_path = config_path.into();
+        self
+    }
+
+    pub fn with_cache_size(mut self, cache_max_bytes: usize) -> Self {
+        self.cache_max_bytes = cache_max_bytes;
+        self
+    }
+
+    /// Autoscales the validation worker threads, bounded by the given max.
+    pub fn with_max_workers(mut self, max_worker_threads: Option<usize>) -> Self {
+        self.max_worker_threads = max_worker_threads;
+        self
+    }
+
+    /// Appends a stage, run after all of the stages already added.
+    pub fn with_stage(mut self, stage: impl ValidationStage) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Drops all stages, including the default ones.
+    pub fn without_stages(mut self) -> Self {
+        self.stages.clear();
+        self
+    }
+
+    /// Loads the uniswap pools through `provider` at the current block and
+    /// spawns the validator. Fails if any of the configured pools can't be
+    /// loaded, as orders would otherwise be validated against empty pools.
+    pub async fn build<P, T, N>(self, provider: Arc<P>) -> eyre::Result<ValidationClient>
+    where
+        P: Provider<T, N> + 'static,
+        T: Transport + Clone,
+        N: Network
+    {
+        let mut data_fetcher_config = load_data_fetcher_config(&self.config_path)?;
+        let (client, _) = self
+            .spawn(provider, move |validation_config, db| {
+                data_fetcher_config
+                    .probe_missing_slots(&validation_config.pools, &TokenSlotProber::new(&*db));
+                (
+                    FetchUtils::new(data_fetcher_config, db),
+                    AngstromPoolsTracker::new(validation_config.clone())
                 )
             })
-            .collect();
-        uniswap_pools.iter_mut().for_each(|pool| {
-            // TODO: initialize the pool
-            // pool.initialize(Some(current_block.load(Ordering::SeqCst)),
-            // db.into())
-        });
-        let state_change_buffer = 100;
-        let pool_manager = UniswapPoolManager::new(
-            uniswap_pools,
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Same as [`Self::build`], but account state and pools come from the
+    /// given trackers instead of the database and the config. Also returns
+    /// the state cache, so that tests can seed it.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub async fn build_with_trackers<State, Pools, P, T, N>(
+        self,
+        provider: Arc<P>,
+        state: State,
+        pools: Pools
+    ) -> eyre::Result<(ValidationClient, Arc<RevmLRU<DB>>)>
+    where
+        State: StateFetchUtils + Send + Sync + 'static,
+        Pools: PoolsTracker + Send + Sync + 'static,
+        P: Provider<T, N> + 'static,
+        T: Transport + Clone,
+        N: Network
+    {
+        self.spawn(provider, move |_, _| (state, pools)).await
+    }
+
+    async fn spawn<State, Pools, P, T, N>(
+        self,
+        provider: Arc<P>,
+        trackers: impl FnOnce(&ValidationConfig, Arc<RevmLRU<DB>>) -> (State, Pools)
+    ) -> eyre::Result<(ValidationClient, Arc<RevmLRU<DB>>)>
+    where
+        State: StateFetchUtils + Send + Sync + 'static,
+        Pools: PoolsTracker + Send + Sync + 'static,
+        P: Provider<T, N> + 'static,
+        T: Transport + Clone,
+        N: Network
+    {
+        let (validator_tx, validator_rx) = self.requests.unwrap_or_else(unbounded_channel);
+        let config_path = self.config_path;
+        let mut validation_config = load_validation_config(&config_path)?;
+        let current_block = Arc::new(AtomicU64::new(self.db.best_block_number()?));
+        let listings = validation_config
+            .listing
+            .review(&validation_config.pools, current_block.load(Ordering::SeqCst), &*provider)
+            .await;
+        retain_listed(&mut validation_config, &listings);
+        let uniswap_pools = load_uniswap_pools(
+            &validation_config.pools,
             current_block.load(Ordering::SeqCst),
-            state_change_buffer,
-            Arc::new(CanonicalStateAdapter::new(state_notification))
-        );
-        let thread_pool =
-            KeySplitThreadpool::new(handle, validation_config.max_validation_per_user);
-        let sim = SimValidation::new(revm_lru.clone());
-        let pool_watcher_handle = rt
-            .block_on(async { pool_manager.watch_state_changes().await })
-            .unwrap();
-        let order_validator =
-            OrderValidator::new(sim, current_block, pools, fetch, pool_manager, thread_pool);
-
-        rt.block_on(async { Validator::new(validator_rx, order_validator).await })
-    });
+            provider.clone()
+        )
+        .await?;
+        let revm_lru =
+            Arc::new(RevmLRU::new(self.cache_max_bytes, Arc::new(self.db), current_block.clone()));
+        let (fetch, pools) = trackers(&validation_config, revm_lru.clone());
+        let worker_threads = validation_worker_threads(self.max_worker_threads);
+        let stages = ValidationStages::new(self.stages);
+        let state_notification = self.state_notification;
+        let reload_tx = validator_tx.clone();
+        let task_db = revm_lru.clone();
+        let gas_pricing = validation_config.gas.clone();
+        ValidationMetricsWrapper::new().set_worker_threads(worker_threads);
 
-    ValidationClient(validator_tx)
-}
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .worker_threads(worker_threads)
+                .build()
+                .unwrap();
+            let handle = rt.handle().clone();
+            #[cfg(unix)]
+            handle.spawn(reload_config_on_change(
+                config_path,
+                reload_tx,
+                provider.clone(),
+                task_db.clone()
+            ));
+            let state_change_buffer = 100;
+            let pool_manager = UniswapPoolManager::new(
+                uniswap_pools,
+                current_block.load(Ordering::SeqCst),
+                state_change_buffer,
+                Arc::new(CanonicalStateAdapter::new(state_notification))
+            );
+            let thread_pool =
+                KeySplitThreadpool::new(handle, validation_config.max_validation_per_user);
+            let sim = SimValidation::new(task_db);
+            let pool_watcher_handle = rt
+                .block_on(async { pool_manager.watch_state_changes().await })
+                .unwrap();
+            let token_prices = rt
+                .block_on(TokenPriceGenerator::new(
+                    provider,
+                    current_block.load(Ordering::SeqCst),
+                    &pool_manager
+                ))
+                .unwrap_or_else(|e| {
+                    tracing::warn!(%e, "failed to load past pool prices, gas is priced from now on");
+                    TokenPriceGenerator::default()
+                })
+                .with_max_hops(gas_pricing.max_hops);
+            let order_validator =
+                OrderValidator::new(sim, current_block, pools, fetch, pool_manager, thread_pool)
+                    .with_stages(stages)
+                    .with_gas_pricing(token_prices, gas_pricing.gas_price_gwei);
 
-pub fn init_validation_tests<
-    DB: BlockStateProviderFactory + Unpin + Clone + 'static,
-    State: StateFetchUtils + Sync + 'static,
-    Pool: PoolsTracker + Sync + 'static
->(
-    db: DB,
-    cache_max_bytes: usize,
-    state_notification: CanonStateNotifications,
-    state: State,
-    pool: Pool
-) -> (ValidationClient, Arc<RevmLRU<DB>>) {
-    let (tx, rx) = unbounded_channel();
-    let config_path = Path::new(TOKEN_CONFIG_FILE);
-    let validation_config = load_validation_config(config_path).unwrap();
-    let fetcher_config = load_data_fetcher_config(config_path).unwrap();
-    let current_block = Arc::new(AtomicU64::new(db.best_block_number().unwrap()));
-    let revm_lru = Arc::new(RevmLRU::new(cache_max_bytes, Arc::new(db), current_block.clone()));
-    let task_db = revm_lru.clone();
-
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .worker_threads(4)
-            .build()
-            .unwrap();
-        let handle = rt.handle().clone();
-        let thread_pool =
-            KeySplitThreadpool::new(handle, validation_config.max_validation_per_user);
-        let sim = SimValidation::new(task_db);
-
-        let mut uniswap_pools: Vec<EnhancedUniswapV3Pool> = validation_config
-            .pools
-            .iter()
-            .map(|pool| {
-                let initial_ticks_per_side = 200;
-                // TODO: make the pool work with UniswapV4 addresses
-                EnhancedUniswapV3Pool::new(
-                    Address::from_slice(&pool.pool_id[..20]),
-                    initial_ticks_per_side
-                )
+            rt.block_on(async {
+                Validator::new(validator_rx, order_validator)
+                    .with_listings(listings)
+                    .await
             })
-            .collect();
-        uniswap_pools.iter_mut().for_each(|pool| {
-            // TODO: initialize the pool
-            // pool.initialize(Some(current_block.load(Ordering::SeqCst)),
-            // db.into())
         });
-        let state_change_buffer = 100;
-        let pool_manager = UniswapPoolManager::new(
-            uniswap_pools,
-            current_block.load(Ordering::SeqCst),
-            state_change_buffer,
-            Arc::new(CanonicalStateAdapter::new(state_notification))
-        );
-        let pool_watcher_handle = rt
-            .block_on(async { pool_manager.watch_state_changes().await })
-            .unwrap();
-        let order_validator =
-            OrderValidator::new(sim, current_block, pool, state, pool_manager, thread_pool);
-
-        rt.block_on(Validator::new(rx, order_validator))
+
+        Ok((ValidationClient(validator_tx), revm_lru))
+    }
+}
+
+/// Leaves out the pools the listing policy rejected.
+fn retain_listed(config: &mut ValidationConfig, listings: &[ListingDecision]) {
+    config.pools.retain(|pool| {
+        listings
+            .iter()
+            .any(|listing| listing.pool_id == pool.pool_id && listing.is_listed())
     });
+}
+
+/// Reads the config file again whenever its modification time changes or on
+/// SIGHUP, and hands it to the validator after reviewing its pools against the
+/// listing policy at the latest block. A config that fails to load is logged
+/// and the current one is kept.
+#[cfg(unix)]
+async fn reload_config_on_change<DB, P, T, N>(
+    config_path: PathBuf,
+    validator: UnboundedSender<ValidationRequest>,
+    provider: Arc<P>,
+    db: Arc<RevmLRU<DB>>
+) where
+    DB: BlockStateProviderFactory + 'static,
+    P: Provider<T, N> + 'static,
+    T: Transport + Clone,
+    N: Network
+{
+    let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .inspect_err(|e| {
+            tracing::error!(%e, "failed to listen for SIGHUP, only file changes reload the config")
+        })
+        .ok();
+    let mut polls = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    let mut modified = config_modified_at(&config_path);
+
+    loop {
+        tokio::select! {
+            Some(_) = async {
+                match hangups.as_mut() {
+                    Some(hangups) => hangups.recv().await,
+                    None => std::future::pending().await
+                }
+            } => {}
+            _ = polls.tick() => {
+                let now = config_modified_at(&config_path);
+                if now == modified {
+                    continue
+                }
+                modified = now;
+            }
+        }
+
+        let config = load_validation_config(&config_path)
+            .and_then(|validation| Ok((validation, load_data_fetcher_config(&config_path)?)));
+        match config {
+            Ok((mut validation, mut data_fetcher)) => {
+                let block_number = match provider.get_block_number().await {
+                    Ok(block_number) => block_number,
+                    Err(e) => {
+                        tracing::warn!(%e, "failed to load the latest block, config not reloaded");
+                        continue
+                    }
+                };
+                let listings = validation
+                    .listing
+                    .review(&validation.pools, block_number, &*provider)
+                    .await;
+                retain_listed(&mut validation, &listings);
+                data_fetcher.probe_missing_slots(&validation.pools, &TokenSlotProber::new(&*db));
+                if validator
+                    .send(ValidationRequest::ReloadConfig { validation, data_fetcher, listings })
+                    .is_err()
+                {
+                    return
+                }
+            }
+            Err(e) => {
+                tracing::warn!(path = %config_path.display(), %e, "failed to reload validation config")
+            }
+        }
+    }
+}
+
+/// When the file was last modified, none if it can't be read.
+fn config_modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Loads the data and ticks of every configured pool at `block_number`,
+/// failing on the first pool that can't be loaded.
+pub async fn load_uniswap_pools<P, T, N>(
+    pools: &[PoolConfig],
+    block_number: u64,
+    provider: Arc<P>
+) -> eyre::Result<Vec<EnhancedUniswapV3Pool>>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network
+{
+    let mut uniswap_pools = Vec::with_capacity(pools.len());
+    for pool in pools {
+        // configured ids are taken to start with the address of a v3 pool, the id of a
+        // v4 pool isn't an address so it fails to initialize here
+        let address = Address::from_slice(&pool.pool_id[..20]);
+        let mut uniswap_pool = EnhancedUniswapV3Pool::new(address, DEFAULT_TICKS_PER_SIDE);
+        uniswap_pool
+            .initialize(Some(block_number), provider.clone())
+            .await
+            .map_err(|e| {
+                eyre::eyre!(
+                    "failed to initialize uniswap pool {address} ({}/{}) at block {block_number}: \
+                     {e}",
+                    pool.token0,
+                    pool.token1
+                )
+            })?;
+        uniswap_pools.push(uniswap_pool);
+    }
+
+    Ok(uniswap_pools)
+}
 
-    (ValidationClient(tx), revm_lru)
+pub async fn init_validation<DB, P, T, N>(
+    db: DB,
+    provider: Arc<P>,
+    config_path: &Path,
+    state_notification: CanonStateNotifications,
+    cache_max_bytes: usize,
+    max_worker_threads: Option<usize>
+) -> eyre::Result<ValidationClient>
+where
+    DB: BlockStateProviderFactory + Unpin + Clone + 'static,
+    P: Provider<T, N> + 'static,
+    T: Transport + Clone,
+    N: Network
+{
+    OrderValidatorBuilder::new(db, state_notification)
+        .with_config_path(config_path)
+        .with_cache_size(cache_max_bytes)
+        .with_max_workers(max_worker_threads)
+        .build(provider)
+        .await
 }
 
 pub trait BundleValidator: Send + Sync + Clone + Unpin + 'static {}
 
 impl BundleValidator for ValidationClient {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_the_worker_threads_by_the_max() {
+        assert_eq!(validation_worker_threads(None), DEFAULT_VALIDATION_WORKER_THREADS);
+        assert_eq!(validation_worker_threads(Some(1)), 1);
+        // a max of zero still leaves a worker to validate with
+        assert_eq!(validation_worker_threads(Some(0)), 1);
+
+        let cores = std::thread::available_parallelism()
+            .map_or(DEFAULT_VALIDATION_WORKER_THREADS, |cores| cores.get());
+        assert_eq!(validation_worker_threads(Some(usize::MAX)), cores);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, future::Future, pin::Pin};
 
 use alloy::primitives::{Address, B256};
+use angstrom_errors::{SimError, ValidationError};
 use angstrom_types::{
     orders::{OrderId, OrderOrigin},
     sol_bindings::{
@@ -18,6 +19,7 @@ use crate::validator::ValidationRequest;
 
 pub mod order_validator;
 pub mod sim;
+pub mod stages;
 pub mod state;
 
 use crate::validator::ValidationClient;
@@ -28,43 +30,78 @@ pub type ValidationFuture<'a> =
 pub type ValidationsFuture<'a> =
     Pin<Box<dyn Future<Output = Vec<OrderValidationResults>> + Send + Sync + 'a>>;
 
+pub type ReorgFuture<'a> = Pin<Box<dyn Future<Output = Vec<B256>> + Send + Sync + 'a>>;
+
 pub enum OrderValidationRequest {
     ValidateOrder(Sender<OrderValidationResults>, AllOrders, OrderOrigin)
 }
 
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq, Eq)]
+pub enum HookDataError {
+    #[error("order sets a hook without a hook payload")]
+    MissingPayload,
+    #[error("order sets a hook payload without a hook")]
+    MissingHook
+}
+
+impl From<HookDataError> for ValidationError {
+    fn from(value: HookDataError) -> Self {
+        Self::Malformed(value.to_string())
+    }
+}
+
+/// Whether the order calls a hook. The hook and its payload have to be set
+/// together.
+fn is_composable(hook: Address, hook_data: &[u8]) -> Result<bool, HookDataError> {
+    match (hook.is_zero(), hook_data.is_empty()) {
+        (true, true) => Ok(false),
+        (false, false) => Ok(true),
+        (false, true) => Err(HookDataError::MissingPayload),
+        (true, false) => Err(HookDataError::MissingHook)
+    }
+}
+
+/// An order that couldn't be routed to validation, along with where to send
+/// its rejection.
+pub struct MalformedOrder {
+    pub sender:     Sender<OrderValidationResults>,
+    pub order_hash: B256,
+    pub error:      HookDataError
+}
+
 /// TODO: not a fan of all the conversions. can def simplify
-impl From<OrderValidationRequest> for OrderValidation {
-    fn from(value: OrderValidationRequest) -> Self {
-        match value {
-            OrderValidationRequest::ValidateOrder(tx, order, orign) => match order {
-                AllOrders::Standing(p) => {
-                    // TODO: check hook data and deal with composable
-                    // if p.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
-                    // } else {
-                    //
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::Partial(p),
-                    //         orign
-                    //     )
-                    // }
-                }
-                AllOrders::Flash(kof) => {
-                    // TODO: check hook data and deal with composable
-                    // if kof.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
-                    // } else {
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::KillOrFill(kof),
-                    //         orign
-                    //     )
-                    // }
-                }
-                AllOrders::TOB(tob) => OrderValidation::Searcher(tx, tob, orign)
+impl TryFrom<OrderValidationRequest> for OrderValidation {
+    type Error = MalformedOrder;
+
+    fn try_from(value: OrderValidationRequest) -> Result<Self, Self::Error> {
+        let OrderValidationRequest::ValidateOrder(tx, order, orign) = value;
+        let hook = match &order {
+            AllOrders::Standing(p) => is_composable(p.hook(), p.hook_data()),
+            AllOrders::Flash(kof) => is_composable(kof.hook(), kof.hook_data()),
+            AllOrders::TOB(_) => Ok(false)
+        };
+        let is_composable = match hook {
+            Ok(is_composable) => is_composable,
+            Err(error) => {
+                return Err(MalformedOrder { sender: tx, order_hash: order.order_hash(), error })
             }
-        }
+        };
+
+        Ok(match order {
+            AllOrders::Standing(p) if is_composable => {
+                OrderValidation::LimitComposable(tx, GroupedComposableOrder::Partial(p), orign)
+            }
+            AllOrders::Standing(p) => {
+                OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
+            }
+            AllOrders::Flash(kof) if is_composable => {
+                OrderValidation::LimitComposable(tx, GroupedComposableOrder::KillOrFill(kof), orign)
+            }
+            AllOrders::Flash(kof) => {
+                OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
+            }
+            AllOrders::TOB(tob) => OrderValidation::Searcher(tx, tob, orign)
+        })
     }
 }
 
@@ -75,8 +112,14 @@ pub enum ValidationMessage {
 #[derive(Debug, Clone)]
 pub enum OrderValidationResults {
     Valid(OrderWithStorageData<AllOrders>),
-    // the raw hash to be removed
-    Invalid(B256),
+    /// the raw hash to be removed, along with why the order is invalid
+    Invalid(B256, ValidationError),
+    /// the order conflicts with the user's other orders, such as a nonce that
+    /// is taken by an order with an equal or higher bid, or is malformed in a
+    /// way the submitter should be told about
+    Rejected(B256, ValidationError),
+    /// the order passed the state checks, but simulating it failed
+    SimFailed(OrderWithStorageData<AllOrders>, SimError),
     TransitionedToBlock
 }
 
@@ -120,6 +163,15 @@ pub trait OrderValidatorHandle: Send + Sync + Clone + Debug + Unpin + 'static {
         completed_orders: Vec<B256>,
         addresses: Vec<Address>
     ) -> ValidationFuture;
+
+    /// orders that expired between blocks and were evicted from the pool, so
+    /// that they stop holding on to their nonces and balances.
+    fn expire_orders(&self, orders: Vec<B256>);
+
+    /// Re-checks the balances, approvals and nonces of the given users against
+    /// the new canonical chain after a reorg. Resolves to the orders whose
+    /// funding or nonce is gone.
+    fn chain_reorg(&self, block_number: u64, addresses: Vec<Address>) -> ReorgFuture;
 }
 
 impl OrderValidatorHandle for ValidationClient {
@@ -144,6 +196,21 @@ impl OrderValidatorHandle for ValidationClient {
         })
     }
 
+    fn expire_orders(&self, orders: Vec<B256>) {
+        let _ = self.0.send(ValidationRequest::ExpiredOrders { orders });
+    }
+
+    fn chain_reorg(&self, block_number: u64, addresses: Vec<Address>) -> ReorgFuture {
+        Box::pin(async move {
+            let (tx, rx) = channel();
+            let _ =
+                self.0
+                    .send(ValidationRequest::ChainReorg { sender: tx, block_number, addresses });
+
+            rx.await.unwrap_or_default()
+        })
+    }
+
     fn validate_order(&self, origin: OrderOrigin, transaction: Self::Order) -> ValidationFuture {
         Box::pin(async move {
             let (tx, rx) = channel();
@@ -159,3 +226,45 @@ impl OrderValidatorHandle for ValidationClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use angstrom_types::sol_bindings::{
+        grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder
+    };
+
+    use super::*;
+
+    fn validation(
+        hook: Address,
+        hook_payload: &'static [u8]
+    ) -> Result<OrderValidation, HookDataError> {
+        let order = ExactStandingOrder {
+            hook,
+            hookPayload: Bytes::from_static(hook_payload),
+            ..Default::default()
+        };
+        let (tx, _) = channel();
+        let request = OrderValidationRequest::ValidateOrder(
+            tx,
+            AllOrders::Standing(StandingVariants::Exact(order)),
+            OrderOrigin::Local
+        );
+        OrderValidation::try_from(request).map_err(|e| e.error)
+    }
+
+    #[test]
+    fn routes_orders_by_hook() {
+        assert!(matches!(validation(Address::ZERO, &[]), Ok(OrderValidation::Limit(..))));
+        assert!(matches!(
+            validation(Address::with_last_byte(1), &[1]),
+            Ok(OrderValidation::LimitComposable(..))
+        ));
+        assert_eq!(
+            validation(Address::with_last_byte(1), &[]).err(),
+            Some(HookDataError::MissingPayload)
+        );
+        assert_eq!(validation(Address::ZERO, &[1]).err(), Some(HookDataError::MissingHook));
+    }
+}
@@ -4,8 +4,13 @@ use std::{
     task::Poll
 };
 
-use alloy::primitives::{Address, BlockNumber, B256};
-use angstrom_types::primitive::NewInitializedPool;
+use alloy::primitives::{Address, BlockNumber, B256, U256};
+use angstrom_metrics::ValidationMetricsWrapper;
+use angstrom_types::{
+    matching::uniswap::PoolSnapshot,
+    primitive::{NewInitializedPool, PoolId},
+    sol_bindings::grouped_orders::AllOrders
+};
 use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
 use futures::{Future, StreamExt};
 use matching_engine::cfmm::uniswap::{
@@ -15,11 +20,18 @@ use tokio::runtime::Handle;
 
 use super::{
     sim::SimValidation,
+    stages::ValidationStages,
     state::{
-        account::user::UserAddress, db_state_utils::StateFetchUtils, pools::PoolsTracker,
+        account::user::UserAddress,
+        amm_swap::{AmmSwap, AmmSwapError},
+        config::{DataFetcherConfig, TokenSlots, ValidationConfig},
+        db_state_utils::{StateFetchUtils, TokenSlotError},
+        gas::OrderGasEstimate,
+        pools::PoolsTracker,
+        token_pricing::TokenPriceGenerator,
         StateValidation
     },
-    OrderValidationRequest
+    MalformedOrder, OrderValidationRequest, OrderValidationResults
 };
 use crate::{
     common::lru_db::BlockStateProviderFactory,
@@ -30,7 +42,8 @@ pub struct OrderValidator<DB, Pools, Fetch, Provider> {
     sim:          SimValidation<DB>,
     state:        StateValidation<Pools, Fetch, Provider>,
     thread_pool:  KeySplitThreadpool<UserAddress, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>,
-    block_number: Arc<AtomicU64>
+    block_number: Arc<AtomicU64>,
+    metrics:      ValidationMetricsWrapper
 }
 
 impl<DB, Pools, Fetch, Provider> OrderValidator<DB, Pools, Fetch, Provider>
@@ -60,7 +73,24 @@ where
             pools,
             pool_manager
         );
-        Self { state, sim, block_number, thread_pool }
+        Self { state, sim, block_number, thread_pool, metrics: ValidationMetricsWrapper::new() }
+    }
+
+    /// Replaces the default stages run on top of the account checks.
+    pub fn with_stages(mut self, stages: ValidationStages) -> Self {
+        self.state = self.state.with_stages(stages);
+        self
+    }
+
+    /// Charges the gas of orders in their input token, see
+    /// [`StateValidation::with_gas_pricing`].
+    pub fn with_gas_pricing(
+        mut self,
+        token_prices: TokenPriceGenerator,
+        gas_price_gwei: u64
+    ) -> Self {
+        self.state = self.state.with_gas_pricing(token_prices, gas_price_gwei);
+        self
     }
 
     pub fn on_new_block(
@@ -73,12 +103,42 @@ where
             .store(block_number, std::sync::atomic::Ordering::SeqCst);
         self.state
             .new_block(block_number, completed_orders, address_changes);
+
+        // on a task of its own, the pools might be locked by a sync in progress
+        let state = self.state.clone();
+        tokio::spawn(async move { state.update_token_prices(block_number).await });
+    }
+
+    /// Re-checks the balances, approvals and nonces of the users affected by a
+    /// reorg against the new canonical chain, returning the orders that lost
+    /// their funding.
+    pub fn on_chain_reorg(&mut self, block_number: BlockNumber, users: Vec<Address>) -> Vec<B256> {
+        self.state.chain_reorg(block_number, users)
+    }
+
+    pub fn on_expired_orders(&mut self, orders: Vec<B256>) {
+        self.state.expire_orders(orders);
+    }
+
+    pub fn register_token_slots(&mut self, slots: TokenSlots) -> Result<(), TokenSlotError> {
+        self.state.register_token_slots(slots)
+    }
+
+    pub fn reload_config(&mut self, validation: ValidationConfig, data_fetcher: DataFetcherConfig) {
+        self.state.reload_config(validation, data_fetcher);
     }
 
     /// only checks state
     pub fn validate_order(&mut self, order: OrderValidationRequest) {
         let block_number = self.block_number.load(std::sync::atomic::Ordering::SeqCst);
-        let order_validation: OrderValidation = order.into();
+        let order_validation = match OrderValidation::try_from(order) {
+            Ok(order_validation) => order_validation,
+            Err(MalformedOrder { sender, order_hash, error }) => {
+                tracing::trace!(?order_hash, %error, "malformed order");
+                let _ = sender.send(OrderValidationResults::Rejected(order_hash, error.into()));
+                return
+            }
+        };
         let user = order_validation.user();
         let cloned_state = self.state.clone();
 
@@ -90,6 +150,41 @@ where
         );
     }
 
+    /// Simulates the swap on a task of its own, the pool it runs against
+    /// might be locked by a sync in progress.
+    pub fn simulate_amm_swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+        sender: tokio::sync::oneshot::Sender<Result<AmmSwap, AmmSwapError>>
+    ) {
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(
+                state
+                    .simulate_amm_swap(token_in, token_out, amount_in)
+                    .await
+            );
+        });
+    }
+
+    pub fn estimate_order_gas(&self, order: &AllOrders) -> OrderGasEstimate {
+        self.state.estimate_order_gas(order)
+    }
+
+    /// Snapshots the pool on a task of its own, for the same reason.
+    pub fn amm_snapshot(
+        &self,
+        pool_id: PoolId,
+        sender: tokio::sync::oneshot::Sender<Result<PoolSnapshot, AmmSwapError>>
+    ) {
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(state.amm_snapshot(pool_id).await);
+        });
+    }
+
     pub fn index_new_pool(&mut self, pool: NewInitializedPool) {
         self.state.index_new_pool(pool);
     }
@@ -112,6 +207,12 @@ where
 
         while let Poll::Ready(Some(_)) = self.thread_pool.poll_next_unpin(cx) {}
 
+        self.metrics.set_threadpool_utilization(
+            self.thread_pool.active_tasks(),
+            self.thread_pool.queued_tasks(),
+            self.thread_pool.tracked_keys()
+        );
+
         Poll::Pending
     }
 }
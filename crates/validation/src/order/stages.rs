@@ -0,0 +1,461 @@
+//! Composable checks run by the order validator on top of the account checks
+//! (nonce, balance and approval), which always run as they produce the storage
+//! data of the order.
+//!
+//! Hooks aren't simulated by a stage,
+//! [`SimValidation`](super::sim::SimValidation) doesn't simulate them yet.
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use alloy::primitives::{Address, U256};
+use angstrom_types::{
+    primitive::PoolId,
+    sol_bindings::{
+        ext::RawPoolOrder,
+        grouped_orders::{AllOrders, OrderWithStorageData}
+    }
+};
+
+use crate::common::signer_cache::SignerCache;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error("{stage} rejected order: {reason}")]
+pub struct StageError {
+    pub stage:  &'static str,
+    pub reason: String
+}
+
+impl StageError {
+    pub fn new(stage: &'static str, reason: impl Into<String>) -> Self {
+        Self { stage, reason: reason.into() }
+    }
+}
+
+impl From<StageError> for angstrom_errors::ValidationError {
+    fn from(value: StageError) -> Self {
+        match value.stage {
+            SIGNATURE_STAGE => Self::InvalidSignature,
+            _ => Self::Malformed(value.to_string())
+        }
+    }
+}
+
+/// A single validation stage. A stage can check the signed order before any
+/// state is read, the order along with its account and pool data, or both.
+pub trait ValidationStage: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    fn validate_order(&self, _order: &AllOrders) -> Result<(), StageError> {
+        Ok(())
+    }
+
+    fn validate_state(&self, _order: &OrderWithStorageData<AllOrders>) -> Result<(), StageError> {
+        Ok(())
+    }
+}
+
+const SIGNATURE_STAGE: &str = "signature";
+
+/// Rejects orders whose signature doesn't recover to their signer.
+#[derive(Clone, Default)]
+pub struct SignatureStage {
+    signers: SignerCache
+}
+
+impl SignatureStage {
+    pub fn new(signers: SignerCache) -> Self {
+        Self { signers }
+    }
+}
+
+impl ValidationStage for SignatureStage {
+    fn name(&self) -> &'static str {
+        SIGNATURE_STAGE
+    }
+
+    fn validate_order(&self, order: &AllOrders) -> Result<(), StageError> {
+        self.signers
+            .is_valid_signature(order)
+            .then_some(())
+            .ok_or_else(|| StageError::new(self.name(), "invalid signature"))
+    }
+}
+
+/// Default max amount of seconds from now that an order deadline can be set
+/// to, matching the default of the order pool.
+pub const STATIC_CHECKS_MAX_DEADLINE_HORIZON_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum StaticCheckError {
+    #[error("order amount is zero")]
+    ZeroAmount,
+    #[error("order price is zero")]
+    ZeroPrice,
+    #[error("order price overflows when applied to its amount")]
+    PriceOverflow,
+    #[error("order trades a token against itself")]
+    SameToken,
+    #[error("order trades the zero address")]
+    ZeroToken,
+    #[error("order has expired")]
+    Expired,
+    #[error("order deadline is beyond the max horizon")]
+    BeyondDeadlineHorizon
+}
+
+impl From<StaticCheckError> for angstrom_errors::ValidationError {
+    fn from(value: StaticCheckError) -> Self {
+        match value {
+            StaticCheckError::Expired => Self::Expired,
+            StaticCheckError::BeyondDeadlineHorizon => Self::BeyondDeadlineHorizon,
+            e => Self::Malformed(e.to_string())
+        }
+    }
+}
+
+/// Checks on the shape of an order that don't need any state, cheap enough to
+/// run synchronously when the order is received, before it's queued anywhere.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticChecksStage {
+    max_deadline_horizon: u64
+}
+
+impl Default for StaticChecksStage {
+    fn default() -> Self {
+        Self::new(STATIC_CHECKS_MAX_DEADLINE_HORIZON_SECS)
+    }
+}
+
+impl StaticChecksStage {
+    pub fn new(max_deadline_horizon: u64) -> Self {
+        Self { max_deadline_horizon }
+    }
+
+    pub fn check(&self, order: &AllOrders) -> Result<(), StaticCheckError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.check_at(order, now)
+    }
+
+    pub fn check_at(&self, order: &AllOrders, now: u64) -> Result<(), StaticCheckError> {
+        let amount_in = order.amount_in();
+        if amount_in == 0 {
+            return Err(StaticCheckError::ZeroAmount)
+        }
+
+        match order {
+            // the price of a searcher order is derived from its amounts
+            AllOrders::TOB(tob) => {
+                if tob.quantityOut == 0 {
+                    return Err(StaticCheckError::ZeroAmount)
+                }
+            }
+            _ => {
+                let price = order.limit_price();
+                if price.is_zero() {
+                    return Err(StaticCheckError::ZeroPrice)
+                }
+                if price.checked_mul(U256::from(amount_in)).is_none() {
+                    return Err(StaticCheckError::PriceOverflow)
+                }
+            }
+        }
+
+        let (token_in, token_out) = (order.token_in(), order.token_out());
+        if token_in == token_out {
+            return Err(StaticCheckError::SameToken)
+        }
+        if token_in == Address::ZERO || token_out == Address::ZERO {
+            return Err(StaticCheckError::ZeroToken)
+        }
+
+        if let Some(deadline) = order.deadline() {
+            if deadline < U256::from(now) {
+                return Err(StaticCheckError::Expired)
+            }
+            if deadline > U256::from(now.saturating_add(self.max_deadline_horizon)) {
+                return Err(StaticCheckError::BeyondDeadlineHorizon)
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidationStage for StaticChecksStage {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn validate_order(&self, order: &AllOrders) -> Result<(), StageError> {
+        self.check(order)
+            .map_err(|e| StageError::new(self.name(), e.to_string()))
+    }
+}
+
+/// Rejects limit orders priced outside of a configured `(min, max)` band of
+/// their pool. Pools without a band are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct PriceBandStage {
+    bands: HashMap<PoolId, (U256, U256)>
+}
+
+impl PriceBandStage {
+    pub fn new(bands: HashMap<PoolId, (U256, U256)>) -> Self {
+        Self { bands }
+    }
+}
+
+impl ValidationStage for PriceBandStage {
+    fn name(&self) -> &'static str {
+        "price_band"
+    }
+
+    fn validate_state(&self, order: &OrderWithStorageData<AllOrders>) -> Result<(), StageError> {
+        if matches!(order.order, AllOrders::TOB(_)) {
+            return Ok(())
+        }
+        let Some((min, max)) = self.bands.get(&order.pool_id) else { return Ok(()) };
+
+        let price = order.priority_data.price;
+        (*min..=*max)
+            .contains(&price)
+            .then_some(())
+            .ok_or_else(|| StageError::new(self.name(), format!("price {price} outside of band")))
+    }
+}
+
+/// Rejects orders the account checks found unfunded, which are otherwise kept
+/// until their balance or approval shows up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BalanceStage;
+
+impl ValidationStage for BalanceStage {
+    fn name(&self) -> &'static str {
+        "balance"
+    }
+
+    fn validate_state(&self, order: &OrderWithStorageData<AllOrders>) -> Result<(), StageError> {
+        order
+            .is_currently_valid
+            .then_some(())
+            .ok_or_else(|| StageError::new(self.name(), "insufficient balance or approval"))
+    }
+}
+
+/// Rejects orders whose estimated gas is above `max_gas_units`, or whose gas
+/// charge in their input token takes up their whole amount.
+#[derive(Debug, Clone, Copy)]
+pub struct GasStage {
+    max_gas_units: u128
+}
+
+impl Default for GasStage {
+    fn default() -> Self {
+        Self::new(u128::MAX)
+    }
+}
+
+impl GasStage {
+    pub fn new(max_gas_units: u128) -> Self {
+        Self { max_gas_units }
+    }
+}
+
+impl ValidationStage for GasStage {
+    fn name(&self) -> &'static str {
+        "gas"
+    }
+
+    fn validate_state(&self, order: &OrderWithStorageData<AllOrders>) -> Result<(), StageError> {
+        let priority = &order.priority_data;
+        if priority.gas_units > self.max_gas_units {
+            return Err(StageError::new(
+                self.name(),
+                format!("{} gas is above the max of {}", priority.gas_units, self.max_gas_units)
+            ))
+        }
+        if priority.gas >= order.amount_in() {
+            return Err(StageError::new(
+                self.name(),
+                format!("gas charge {} doesn't leave anything to trade", priority.gas)
+            ))
+        }
+
+        Ok(())
+    }
+}
+
+/// The ordered stages of a validation pipeline.
+#[derive(Clone)]
+pub struct ValidationStages(Arc<Vec<Box<dyn ValidationStage>>>);
+
+impl Default for ValidationStages {
+    fn default() -> Self {
+        Self::new(vec![Box::new(SignatureStage::default())])
+    }
+}
+
+impl ValidationStages {
+    pub fn new(stages: Vec<Box<dyn ValidationStage>>) -> Self {
+        Self(Arc::new(stages))
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.0.iter().map(|stage| stage.name()).collect()
+    }
+
+    pub fn validate_order(&self, order: &AllOrders) -> Result<(), StageError> {
+        self.0
+            .iter()
+            .try_for_each(|stage| stage.validate_order(order))
+    }
+
+    pub fn validate_state(
+        &self,
+        order: &OrderWithStorageData<AllOrders>
+    ) -> Result<(), StageError> {
+        self.0
+            .iter()
+            .try_for_each(|stage| stage.validate_state(order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::aliases::U40;
+    use angstrom_types::sol_bindings::{
+        grouped_orders::StandingVariants, rpc_orders::PartialStandingOrder
+    };
+
+    use super::*;
+
+    #[test]
+    fn price_band_only_checks_configured_pools() {
+        let pool = PoolId::with_last_byte(1);
+        let stages = ValidationStages::new(vec![Box::new(PriceBandStage::new(HashMap::from([(
+            pool,
+            (U256::from(10), U256::from(20))
+        )])))]);
+
+        let mut order = OrderWithStorageData { pool_id: pool, ..Default::default() }
+            .try_map_inner(|_: ()| {
+                Ok(AllOrders::Standing(StandingVariants::Exact(Default::default())))
+            })
+            .unwrap();
+        order.priority_data.price = U256::from(15);
+        assert!(stages.validate_state(&order).is_ok());
+
+        order.priority_data.price = U256::from(21);
+        assert_eq!(stages.validate_state(&order).unwrap_err().stage, "price_band");
+
+        order.pool_id = PoolId::with_last_byte(2);
+        assert!(stages.validate_state(&order).is_ok());
+    }
+
+    fn standing_order(amount_in: u128) -> OrderWithStorageData<AllOrders> {
+        OrderWithStorageData::default()
+            .try_map_inner(|_: ()| {
+                Ok(AllOrders::Standing(StandingVariants::Partial(PartialStandingOrder {
+                    maxAmountIn: amount_in,
+                    ..Default::default()
+                })))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn balance_stage_rejects_unfunded_orders() {
+        let mut order = standing_order(10);
+        order.is_currently_valid = true;
+        assert!(BalanceStage.validate_state(&order).is_ok());
+
+        order.is_currently_valid = false;
+        assert_eq!(BalanceStage.validate_state(&order).unwrap_err().stage, "balance");
+    }
+
+    #[test]
+    fn gas_stage_caps_gas_units_and_charge() {
+        let stage = GasStage::new(100_000);
+        let mut order = standing_order(10);
+        order.priority_data.gas_units = 100_000;
+        order.priority_data.gas = 9;
+        assert!(stage.validate_state(&order).is_ok());
+
+        order.priority_data.gas = 10;
+        assert_eq!(stage.validate_state(&order).unwrap_err().stage, "gas");
+
+        order.priority_data.gas = 0;
+        order.priority_data.gas_units = 100_001;
+        assert_eq!(stage.validate_state(&order).unwrap_err().stage, "gas");
+    }
+
+    #[test]
+    fn static_checks_reject_malformed_orders() {
+        const NOW: u64 = 1_000;
+        let checks = StaticChecksStage::new(100);
+        let order = |f: fn(&mut PartialStandingOrder)| {
+            let mut order = PartialStandingOrder {
+                maxAmountIn: 10,
+                minPrice: U256::from(1),
+                assetIn: Address::with_last_byte(1),
+                assetOut: Address::with_last_byte(2),
+                deadline: U40::from(NOW + 50),
+                ..Default::default()
+            };
+            f(&mut order);
+            AllOrders::Standing(StandingVariants::Partial(order))
+        };
+
+        assert_eq!(checks.check_at(&order(|_| {}), NOW), Ok(()));
+        assert_eq!(
+            checks.check_at(&order(|o| o.maxAmountIn = 0), NOW),
+            Err(StaticCheckError::ZeroAmount)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.minPrice = U256::ZERO), NOW),
+            Err(StaticCheckError::ZeroPrice)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.minPrice = U256::MAX), NOW),
+            Err(StaticCheckError::PriceOverflow)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.assetOut = o.assetIn), NOW),
+            Err(StaticCheckError::SameToken)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.assetIn = Address::ZERO), NOW),
+            Err(StaticCheckError::ZeroToken)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.deadline = U40::from(NOW - 1)), NOW),
+            Err(StaticCheckError::Expired)
+        );
+        assert_eq!(
+            checks.check_at(&order(|o| o.deadline = U40::from(NOW + 101)), NOW),
+            Err(StaticCheckError::BeyondDeadlineHorizon)
+        );
+    }
+
+    #[test]
+    fn stage_errors_map_to_validation_errors() {
+        let error: angstrom_errors::ValidationError =
+            StageError::new(SIGNATURE_STAGE, "invalid signature").into();
+        assert_eq!(error, angstrom_errors::ValidationError::InvalidSignature);
+
+        let error: angstrom_errors::ValidationError =
+            StageError::new("price_band", "price 21 outside of band").into();
+        assert_eq!(
+            error,
+            angstrom_errors::ValidationError::Malformed(
+                "price_band rejected order: price 21 outside of band".to_string()
+            )
+        );
+    }
+}
@@ -2,13 +2,18 @@
 
 use alloy::primitives::{Address, BlockNumber, B256, U256};
 use angstrom_types::{
-    orders::OrderId,
+    orders::{OrderId, OrderPriorityData},
     sol_bindings::{ext::RawPoolOrder, grouped_orders::OrderWithStorageData}
 };
 use thiserror::Error;
 use user::UserAccounts;
 
-use super::{db_state_utils::StateFetchUtils, pools::UserOrderPoolInfo};
+use super::{
+    config::{DataFetcherConfig, TokenSlots},
+    db_state_utils::{StateFetchUtils, TokenSlotError},
+    gas::estimate_order_gas,
+    pools::UserOrderPoolInfo
+};
 use crate::common::lru_db::BlockStateProviderFactory;
 
 pub mod user;
@@ -43,6 +48,26 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
         self.user_accounts.new_block(users, orders);
     }
 
+    /// Re-checks the pending orders of the users affected by a reorg against
+    /// the new canonical chain, returning the ones it can no longer support.
+    pub fn reorg(&self, block: BlockNumber, users: Vec<Address>) -> Vec<B256> {
+        let fetch_utils = self.fetch_utils.pinned_at_block(block);
+        self.user_accounts.reorg(users, &fetch_utils)
+    }
+
+    /// orders that left the pool between blocks
+    pub fn remove_orders(&self, orders: &[B256]) {
+        self.user_accounts.remove_orders(orders);
+    }
+
+    pub fn register_token_slots(&self, slots: TokenSlots) -> Result<(), TokenSlotError> {
+        self.fetch_utils.register_token_slots(slots)
+    }
+
+    pub fn reload_token_slots(&self, config: DataFetcherConfig) {
+        self.fetch_utils.reload_token_slots(config)
+    }
+
     pub fn verify_order<O: RawPoolOrder>(
         &self,
         order: O,
@@ -52,13 +77,15 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
     ) -> Result<OrderWithStorageData<O>, UserAccountVerificationError<O>> {
         let user = order.from();
         let order_hash = order.order_hash();
+        // pin the block so all state for this order is read from the same block
+        let fetch_utils = self.fetch_utils.pinned_at_block(block);
 
         // very nonce hasn't been used historically
         //
         let respend = order.respend_avoidance_strategy();
         match respend {
             angstrom_types::sol_bindings::RespendAvoidanceMethod::Nonce(nonce) => {
-                if !self.fetch_utils.is_valid_nonce(user, nonce) {
+                if !fetch_utils.is_valid_nonce(user, nonce) {
                     return Err(UserAccountVerificationError::DuplicateNonce(order_hash))
                 }
             }
@@ -69,15 +96,25 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
             }
         }
 
-        // very we don't have a respend conflict
+        // very we don't have a respend conflict. a new order only replaces the
+        // pending orders with the same nonce if it bids strictly more gas than all of
+        // them, equal bids go to the lowest hash
         let conflicting_orders = self.user_accounts.respend_conflicts(user, respend);
         if conflicting_orders
             .iter()
-            .any(|o| o.order_hash <= order_hash)
+            .any(|o| o.order_hash == order_hash)
         {
             return Err(UserAccountVerificationError::DuplicateNonce(order_hash))
         }
-        // if new order has lower hash cancel all orders with the same nonce
+        let gas = order.priority_data().gas_units;
+        if let Some(existing) = conflicting_orders.iter().find(|o| {
+            o.priority.gas_units > gas || (o.priority.gas_units == gas && o.order_hash < order_hash)
+        }) {
+            return Err(UserAccountVerificationError::ReplacementUnderpriced {
+                order_hash,
+                existing: existing.order_hash
+            })
+        }
         conflicting_orders.iter().for_each(|order| {
             self.user_accounts.cancel_order(&user, &order.order_hash);
         });
@@ -86,7 +123,7 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
             user,
             pool_info.token,
             respend,
-            &self.fetch_utils
+            &fetch_utils
         );
 
         // ensure that the current live state is enough to satisfy the order
@@ -118,6 +155,16 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
 impl<T: RawPoolOrder> StorageWithData for T {}
 
 pub trait StorageWithData: RawPoolOrder {
+    fn priority_data(&self) -> OrderPriorityData {
+        OrderPriorityData {
+            price:       self.limit_price(),
+            volume:      self.amount_in(),
+            gas:         0,
+            gas_units:   estimate_order_gas(self),
+            received_at: 0
+        }
+    }
+
     fn into_order_storage_with_data(
         self,
         block: u64,
@@ -128,11 +175,7 @@ pub trait StorageWithData: RawPoolOrder {
         invalidates: Vec<B256>
     ) -> OrderWithStorageData<Self> {
         OrderWithStorageData {
-            priority_data: angstrom_types::orders::OrderPriorityData {
-                price:  self.limit_price(),
-                volume: self.amount_in(),
-                gas:    0
-            },
+            priority_data: self.priority_data(),
             pool_id: pool_info.pool_id,
             is_currently_valid: is_cur_valid,
             is_bid: pool_info.is_bid,
@@ -154,10 +197,26 @@ pub enum UserAccountVerificationError<O: RawPoolOrder> {
     OrderIsCancelled(B256),
     #[error("Nonce exists for a current order hash: {0:?}")]
     DuplicateNonce(B256),
+    #[error("order {order_hash:?} doesn't outbid {existing:?} which has the same nonce")]
+    ReplacementUnderpriced { order_hash: B256, existing: B256 },
     #[error("block for flash order is not current block")]
     BadBlock
 }
 
+impl<O: RawPoolOrder> From<UserAccountVerificationError<O>> for angstrom_errors::ValidationError {
+    fn from(value: UserAccountVerificationError<O>) -> Self {
+        match value {
+            UserAccountVerificationError::BlockMissMatch { .. }
+            | UserAccountVerificationError::BadBlock => Self::BlockMismatch,
+            UserAccountVerificationError::OrderIsCancelled(_) => Self::Cancelled,
+            UserAccountVerificationError::DuplicateNonce(_) => Self::InvalidNonce,
+            UserAccountVerificationError::ReplacementUnderpriced { .. } => {
+                Self::ReplacementUnderpriced
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::collections::HashSet;
@@ -345,6 +404,132 @@ pub mod tests {
         assert_eq!(res.invalidates, vec![order0_hash]);
     }
 
+    #[test]
+    fn test_same_nonce_replacement_requires_higher_bid() {
+        let block = 420;
+        let mut processor = setup_test_account_processor(block);
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mut mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        // a longer hook payload costs more gas to execute
+        let order = |payload_len: usize, amount: u128| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .nonce(420)
+                .amount(amount)
+                .hook_payload(vec![1u8; payload_len].into())
+                .build()
+        };
+        let order0 = order(0, 100);
+        let order1 = order(10, 100);
+        let order2 = order(5, 500);
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order0)
+            .expect("pool tracker should have valid state");
+
+        let user = order0.from();
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(1000));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(1000));
+
+        let order0_hash = order0.hash();
+        let order1_hash = order1.hash();
+        processor
+            .verify_order(order0, pool_info.clone(), 420, true)
+            .expect("order should be valid");
+
+        // bids more gas than the pending order, so replaces it
+        let res = processor
+            .verify_order(order1, pool_info.clone(), 420, true)
+            .expect("should be valid");
+        assert_eq!(res.invalidates, vec![order0_hash]);
+
+        // a better price doesn't outbid the replacement's gas
+        let Err(e) = processor.verify_order(order2, pool_info, 420, true) else {
+            panic!("verifying order should of failed")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::ReplacementUnderpriced { existing, .. }
+                if existing == order1_hash
+        ));
+    }
+
+    #[test]
+    fn test_same_nonce_equal_bids_go_to_the_lowest_hash() {
+        let block = 420;
+        let mut processor = setup_test_account_processor(block);
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mut mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        let order = |amount: u128| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .nonce(420)
+                .amount(amount)
+                .build()
+        };
+        // amounts of the orders with the lower and the higher hash
+        let (mut low, mut high) = (100, 200);
+        if order(low).hash() > order(high).hash() {
+            std::mem::swap(&mut low, &mut high);
+        }
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order(low))
+            .expect("pool tracker should have valid state");
+
+        let user = order(low).from();
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(1000));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(1000));
+
+        let low_hash = order(low).hash();
+        let high_hash = order(high).hash();
+        processor
+            .verify_order(order(high), pool_info.clone(), 420, true)
+            .expect("order should be valid");
+
+        // same gas, lower hash wins
+        let res = processor
+            .verify_order(order(low), pool_info.clone(), 420, true)
+            .expect("should be valid");
+        assert_eq!(res.invalidates, vec![high_hash]);
+
+        // and keeps winning against the higher hash
+        let Err(e) = processor.verify_order(order(high), pool_info, 420, true) else {
+            panic!("verifying order should of failed")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::ReplacementUnderpriced { existing, .. }
+                if existing == low_hash
+        ));
+    }
+
     #[test]
     fn test_nonce_rejection() {
         let block = 420;
@@ -384,4 +569,58 @@ pub mod tests {
 
         assert!(matches!(e, UserAccountVerificationError::DuplicateNonce(..)));
     }
+
+    #[test]
+    fn test_reorg_drops_orders_that_lost_funding() {
+        let block = 420;
+        let processor = setup_test_account_processor(block);
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mut mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        let order = |nonce: u64| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .nonce(nonce)
+                .amount(100)
+                .build()
+        };
+        let order0 = order(1);
+        let order1 = order(2);
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order0)
+            .expect("pool tracker should have valid state");
+
+        let user = order0.from();
+        let funding = U256::from(order0.amount_in()) + U256::from(order1.amount_in());
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, funding);
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, funding);
+
+        let order1_hash = order1.hash();
+        processor
+            .verify_order(order0, pool_info.clone(), 420, true)
+            .expect("order should be valid");
+        processor
+            .verify_order(order1, pool_info, 420, true)
+            .expect("order should be valid");
+
+        // the transfer that funded the second order is no longer canonical
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, funding - U256::from(1));
+        assert_eq!(processor.reorg(421, vec![user]), vec![order1_hash]);
+        assert!(processor.reorg(421, vec![user]).is_empty());
+    }
 }
@@ -4,9 +4,13 @@ use std::{
 };
 
 use alloy::primitives::{Address, B256, U256};
-use angstrom_types::sol_bindings::{ext::RawPoolOrder, RespendAvoidanceMethod};
+use angstrom_types::{
+    orders::OrderPriorityData,
+    sol_bindings::{ext::RawPoolOrder, RespendAvoidanceMethod}
+};
 use dashmap::DashMap;
 
+use super::StorageWithData;
 use crate::order::state::{db_state_utils::StateFetchUtils, pools::UserOrderPoolInfo};
 
 pub type UserAddress = Address;
@@ -40,6 +44,7 @@ impl LiveState {
         Some(PendingUserAction {
             order_hash:     order.order_hash(),
             respend:        order.respend_avoidance_strategy(),
+            priority:       order.priority_data(),
             token_address:  pool_info.token,
             token_delta:    amount_in,
             token_approval: amount_in,
@@ -54,6 +59,9 @@ pub struct PendingUserAction {
     /// hash of order
     pub order_hash:     B256,
     pub respend:        RespendAvoidanceMethod,
+    /// what the order bids, orders with the same nonce replace it only by
+    /// bidding strictly more
+    pub priority:       OrderPriorityData,
     // for each order, there will be two different deltas
     pub token_address:  TokenAddress,
     // although we have deltas for two tokens, we only
@@ -92,12 +100,61 @@ impl UserAccounts {
         });
 
         // remove all singular orders
+        self.remove_orders(&orders);
+    }
+
+    /// Drops the pending actions of the given orders, releasing the balances
+    /// and nonces they reserved.
+    pub fn remove_orders(&self, orders: &[B256]) {
         self.pending_actions.retain(|user, pending_orders| {
             pending_orders.retain(|p| !orders.contains(&p.order_hash));
             !pending_orders.is_empty()
         });
     }
 
+    /// Reloads the balances and approvals of the given users from the new
+    /// canonical chain and drops the pending actions that it no longer
+    /// supports, either because the nonce was used or the funding is gone.
+    /// Returns the hashes of the dropped orders.
+    pub fn reorg<S: StateFetchUtils>(&self, users: Vec<Address>, utils: &S) -> Vec<B256> {
+        let mut invalid = vec![];
+        for user in users {
+            self.last_known_state.remove(&user);
+            let Some(mut pending_orders) = self.pending_actions.get_mut(&user) else { continue };
+
+            pending_orders.retain(|p| match p.respend {
+                RespendAvoidanceMethod::Nonce(nonce) if !utils.is_valid_nonce(user, nonce) => {
+                    invalid.push(p.order_hash);
+                    false
+                }
+                _ => true
+            });
+            let mut tokens = pending_orders
+                .iter()
+                .map(|p| p.token_address)
+                .collect::<Vec<_>>();
+            drop(pending_orders);
+            tokens.sort_unstable();
+            tokens.dedup();
+
+            let unfunded = tokens
+                .into_iter()
+                .flat_map(|token| {
+                    self.load_state_for(user, token, utils);
+                    self.fetch_all_invalidated_orders(user, token)
+                })
+                .collect::<Vec<_>>();
+            self.pending_actions
+                .remove_if_mut(&user, |_, pending_orders| {
+                    pending_orders.retain(|p| !unfunded.contains(&p.order_hash));
+                    pending_orders.is_empty()
+                });
+            invalid.extend(unfunded);
+        }
+
+        invalid
+    }
+
     /// returns true if the order cancel has been processed successfully
     pub fn cancel_order(&self, user: &UserAddress, order_hash: &B256) -> bool {
         let Some(mut inner_orders) = self.pending_actions.get_mut(user) else { return false };
@@ -0,0 +1,28 @@
+//! Swaps simulated against the tracked uniswap pools, for quoting.
+use alloy::primitives::{Address, U256};
+use angstrom_types::{matching::Ray, primitive::PoolId};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmmSwap {
+    pub pool_id:    PoolId,
+    /// price of the pool before the swap, as t1/t0
+    pub spot_price: Ray,
+    pub amount_out: U256
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum AmmSwapError {
+    #[error("no pool for {0:?} and {1:?}")]
+    UnknownPool(Address, Address),
+    #[error("pool {0:?} isn't synced")]
+    UntrackedPool(PoolId),
+    #[error("amount in is too large")]
+    AmountTooLarge,
+    #[error("swap simulation failed: {0}")]
+    Simulation(String),
+    #[error("pool snapshot failed: {0}")]
+    Snapshot(String),
+    #[error("validator is not running")]
+    ValidatorStopped
+}
@@ -0,0 +1,328 @@
+//! Policy on which of the configured pools the node validates orders for.
+//! Every pool of the config is reviewed when the config is loaded, pools that
+//! fail the policy are left out and the decision is logged and kept for the
+//! admin rpc.
+use alloy::{
+    network::Network,
+    primitives::{Address, BlockNumber},
+    providers::Provider,
+    sol,
+    transports::Transport
+};
+use angstrom_types::primitive::PoolId;
+use serde::{Deserialize, Serialize};
+
+use super::PoolConfig;
+
+sol! {
+    #[sol(rpc)]
+    interface IUniswapV3PoolLiquidity {
+        function liquidity() external view returns (uint128);
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ListingError {
+    #[error("validator is not running")]
+    ValidatorStopped
+}
+
+/// An empty policy lists every configured pool.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ListingPolicyConfig {
+    /// when not empty, only pools with both tokens in the list are listed
+    #[serde(default)]
+    pub allow:         Vec<Address>,
+    /// pools with either token in the list are never listed
+    #[serde(default)]
+    pub deny:          Vec<Address>,
+    /// smallest in range liquidity of the uniswap pool
+    #[serde(default)]
+    pub min_liquidity: u128,
+    /// blocks since both tokens were deployed
+    #[serde(default)]
+    pub min_token_age: u64
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum ListingRejection {
+    Denied {
+        token: Address
+    },
+    NotAllowed {
+        token: Address
+    },
+    #[serde(rename_all = "camelCase")]
+    Liquidity {
+        liquidity:     u128,
+        min_liquidity: u128
+    },
+    #[serde(rename_all = "camelCase")]
+    TokenAge {
+        token:         Address,
+        age:           u64,
+        min_token_age: u64
+    },
+    /// the on-chain state needed for a check couldn't be loaded
+    Unavailable {
+        check: String
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingDecision {
+    pub pool_id:      PoolId,
+    pub token0:       Address,
+    pub token1:       Address,
+    pub block_number: BlockNumber,
+    /// empty when the pool is listed
+    pub rejections:   Vec<ListingRejection>
+}
+
+impl ListingDecision {
+    pub fn is_listed(&self) -> bool {
+        self.rejections.is_empty()
+    }
+}
+
+/// What the policy needs to know of a pool from the chain. `None` when it
+/// isn't needed or couldn't be loaded.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PoolListingState {
+    pub liquidity:  Option<u128>,
+    /// blocks since each token was deployed
+    pub token_ages: [Option<u64>; 2]
+}
+
+impl ListingPolicyConfig {
+    fn needs_chain_state(&self) -> bool {
+        self.min_liquidity != 0 || self.min_token_age != 0
+    }
+
+    pub fn decide(
+        &self,
+        pool: &PoolConfig,
+        block_number: BlockNumber,
+        state: PoolListingState
+    ) -> ListingDecision {
+        let tokens = [pool.token0, pool.token1];
+        let mut rejections = Vec::new();
+        for token in tokens {
+            if self.deny.contains(&token) {
+                rejections.push(ListingRejection::Denied { token });
+            } else if !self.allow.is_empty() && !self.allow.contains(&token) {
+                rejections.push(ListingRejection::NotAllowed { token });
+            }
+        }
+
+        if self.min_liquidity != 0 {
+            match state.liquidity {
+                Some(liquidity) if liquidity < self.min_liquidity => {
+                    rejections.push(ListingRejection::Liquidity {
+                        liquidity,
+                        min_liquidity: self.min_liquidity
+                    })
+                }
+                Some(_) => {}
+                None => rejections
+                    .push(ListingRejection::Unavailable { check: "liquidity".to_string() })
+            }
+        }
+
+        if self.min_token_age != 0 {
+            for (token, age) in tokens.into_iter().zip(state.token_ages) {
+                match age {
+                    Some(age) if age < self.min_token_age => {
+                        rejections.push(ListingRejection::TokenAge {
+                            token,
+                            age,
+                            min_token_age: self.min_token_age
+                        })
+                    }
+                    Some(_) => {}
+                    None => rejections
+                        .push(ListingRejection::Unavailable { check: format!("age of {token}") })
+                }
+            }
+        }
+
+        ListingDecision {
+            pool_id: pool.pool_id,
+            token0: pool.token0,
+            token1: pool.token1,
+            block_number,
+            rejections
+        }
+    }
+
+    /// Decides on every pool against the state at `block_number`. The state
+    /// is only loaded when the policy has on-chain checks.
+    pub async fn review<P, T, N>(
+        &self,
+        pools: &[PoolConfig],
+        block_number: BlockNumber,
+        provider: &P
+    ) -> Vec<ListingDecision>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network
+    {
+        let mut decisions = Vec::with_capacity(pools.len());
+        for pool in pools {
+            let state = if self.needs_chain_state() {
+                self.pool_listing_state(pool, block_number, provider).await
+            } else {
+                PoolListingState::default()
+            };
+            let decision = self.decide(pool, block_number, state);
+            if decision.is_listed() {
+                tracing::info!(
+                    target: "angstrom::listing",
+                    pool_id = ?pool.pool_id,
+                    token0 = ?pool.token0,
+                    token1 = ?pool.token1,
+                    "pool listed"
+                );
+            } else {
+                tracing::warn!(
+                    target: "angstrom::listing",
+                    pool_id = ?pool.pool_id,
+                    token0 = ?pool.token0,
+                    token1 = ?pool.token1,
+                    rejections = ?decision.rejections,
+                    "pool rejected by the listing policy"
+                );
+            }
+            decisions.push(decision);
+        }
+
+        decisions
+    }
+
+    async fn pool_listing_state<P, T, N>(
+        &self,
+        pool: &PoolConfig,
+        block_number: BlockNumber,
+        provider: &P
+    ) -> PoolListingState
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network
+    {
+        let mut state = PoolListingState::default();
+        if self.min_liquidity != 0 {
+            // liquidity is read from the v3 pool at the address in the pool id, v4 pools
+            // have no contract of their own so their liquidity shows up as unavailable
+            let address = Address::from_slice(&pool.pool_id[..20]);
+            state.liquidity = IUniswapV3PoolLiquidity::new(address, provider)
+                .liquidity()
+                .block(block_number.into())
+                .call()
+                .await
+                .map(|liquidity| liquidity._0)
+                .map_err(|e| tracing::warn!(%e, ?address, "failed to load the pool liquidity"))
+                .ok();
+        }
+
+        if self.min_token_age != 0 {
+            for (age, token) in state.token_ages.iter_mut().zip([pool.token0, pool.token1]) {
+                *age = deployment_block(token, block_number, provider)
+                    .await
+                    .map_err(|e| tracing::warn!(%e, ?token, "failed to load the token deployment"))
+                    .ok()
+                    .flatten()
+                    .map(|deployed| block_number - deployed);
+            }
+        }
+
+        state
+    }
+}
+
+/// First block with code at `token`, searched for up to `block_number`.
+/// `None` when there is no code at `block_number` either.
+async fn deployment_block<P, T, N>(
+    token: Address,
+    block_number: BlockNumber,
+    provider: &P
+) -> eyre::Result<Option<BlockNumber>>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network
+{
+    let has_code = |block: BlockNumber| async move {
+        let code = provider.get_code_at(token).block_id(block.into()).await?;
+        eyre::Ok(!code.is_empty())
+    };
+    if !has_code(block_number).await? {
+        return Ok(None)
+    }
+
+    let (mut without, mut with) = (0, block_number);
+    if has_code(without).await? {
+        return Ok(Some(without))
+    }
+    while with - without > 1 {
+        let mid = without + (with - without) / 2;
+        if has_code(mid).await? {
+            with = mid;
+        } else {
+            without = mid;
+        }
+    }
+
+    Ok(Some(with))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+
+    fn pool() -> PoolConfig {
+        PoolConfig {
+            token0:      Address::with_last_byte(1),
+            token1:      Address::with_last_byte(2),
+            pool_id:     B256::with_last_byte(3),
+            store_index: 0
+        }
+    }
+
+    #[test]
+    fn empty_policy_lists_every_pool() {
+        let decision = ListingPolicyConfig::default().decide(&pool(), 10, Default::default());
+        assert!(decision.is_listed());
+    }
+
+    #[test]
+    fn rejects_every_failed_check() {
+        let (token0, token1) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let policy = ListingPolicyConfig {
+            allow:         vec![token0],
+            deny:          vec![],
+            min_liquidity: 1_000,
+            min_token_age: 100
+        };
+        let state = PoolListingState { liquidity: Some(10), token_ages: [Some(500), None] };
+
+        let decision = policy.decide(&pool(), 10, state);
+        assert_eq!(
+            decision.rejections,
+            vec![
+                ListingRejection::NotAllowed { token: token1 },
+                ListingRejection::Liquidity { liquidity: 10, min_liquidity: 1_000 },
+                ListingRejection::Unavailable { check: format!("age of {token1}") },
+            ]
+        );
+
+        let policy = ListingPolicyConfig { deny: vec![token0], ..Default::default() };
+        let decision = policy.decide(&pool(), 10, Default::default());
+        assert_eq!(decision.rejections, vec![ListingRejection::Denied { token: token0 }]);
+    }
+}
@@ -0,0 +1,545 @@
+pub mod listing;
+pub mod slot_probe;
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path
+};
+
+use alloy::primitives::{keccak256, Address, U256};
+use angstrom_types::primitive::PoolId;
+use listing::ListingPolicyConfig;
+use reth_revm::DatabaseRef;
+use serde::{Deserialize, Serialize};
+use slot_probe::TokenSlotProber;
+
+use super::token_pricing::{TokenPriceGenerator, DEFAULT_MAX_HOPS};
+use crate::common::lru_db::{BlockStateProviderFactory, RevmLRU};
+
+/// Gas price orders are charged at when the config sets none.
+pub const DEFAULT_GAS_PRICE_GWEI: u64 = 10;
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataFetcherConfig {
+    pub approvals: Vec<TokenApprovalSlot>,
+    pub balances:  Vec<TokenBalanceSlot>
+}
+
+impl DataFetcherConfig {
+    /// The tokens with a balance or approval slot.
+    pub fn tokens(&self) -> HashSet<Address> {
+        self.balances
+            .iter()
+            .map(|bal| bal.token)
+            .chain(self.approvals.iter().map(|app| app.token))
+            .collect()
+    }
+
+    /// Probes the slots of the pool tokens the config has no balance or
+    /// approval slot for. Tokens that can't be probed are logged and left
+    /// out, their orders fail validation as before.
+    pub fn probe_missing_slots<DB>(&mut self, pools: &[PoolConfig], prober: &TokenSlotProber<DB>)
+    where
+        DB: DatabaseRef,
+        DB::Error: std::fmt::Display
+    {
+        let mut tokens = pools
+            .iter()
+            .flat_map(|pool| [pool.token0, pool.token1])
+            .collect::<Vec<_>>();
+        tokens.sort_unstable();
+        tokens.dedup();
+
+        for token in tokens {
+            let has_balance = self.balances.iter().any(|slot| slot.token == token);
+            let has_approval = self.approvals.iter().any(|slot| slot.token == token);
+            if has_balance && has_approval {
+                continue
+            }
+
+            match prober.probe(token) {
+                Ok((balance, approval)) => {
+                    if !has_balance {
+                        self.balances.push(balance);
+                    }
+                    if !has_approval {
+                        self.approvals.push(approval);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(?token, %e, "token has no configured slots and probing failed")
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ValidationConfig {
+    pub pools:                   Vec<PoolConfig>,
+    pub max_validation_per_user: usize,
+    #[serde(default)]
+    pub dust:                    DustConfig,
+    #[serde(default)]
+    pub listing:                 ListingPolicyConfig,
+    #[serde(default)]
+    pub gas:                     GasPricingConfig
+}
+
+/// How the gas of an order is charged in its input token. We have no view of
+/// the base fee during validation, so gas is priced at the static
+/// `gas_price_gwei` and converted into the token through our pools.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GasPricingConfig {
+    #[serde(default = "default_gas_price_gwei")]
+    pub gas_price_gwei: u64,
+    /// max number of pools to convert through when a token has no WETH pool
+    #[serde(default = "default_max_hops")]
+    pub max_hops:       usize
+}
+
+impl GasPricingConfig {
+    pub fn gas_price_wei(&self) -> u128 {
+        self.gas_price_gwei as u128 * 1_000_000_000
+    }
+}
+
+impl Default for GasPricingConfig {
+    fn default() -> Self {
+        Self { gas_price_gwei: default_gas_price_gwei(), max_hops: default_max_hops() }
+    }
+}
+
+fn default_gas_price_gwei() -> u64 {
+    DEFAULT_GAS_PRICE_GWEI
+}
+
+fn default_max_hops() -> usize {
+    DEFAULT_MAX_HOPS
+}
+
+/// Orders that move less than `usd_floor` worth of their input token are
+/// rejected as dust. What a token is worth in usd follows the token price
+/// generator, with `usd_token` standing in for the dollar. Tokens without a
+/// config are never considered dust, and nothing is when `usd_token` can't be
+/// priced.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct DustConfig {
+    #[serde(default)]
+    pub usd_floor: f64,
+    /// a dollar stablecoin, which has to be among `tokens`
+    #[serde(default)]
+    pub usd_token: Option<Address>,
+    #[serde(default)]
+    pub tokens:    Vec<TokenDustConfig>
+}
+
+impl DustConfig {
+    /// The dust threshold of `token` at the current prices, in its smallest
+    /// unit.
+    pub fn threshold(&self, token: Address, prices: &TokenPriceGenerator) -> u128 {
+        let Some(config) = self.token(token) else { return 0 };
+        match self.usd_price(config, prices) {
+            Ok(usd_price) => config.threshold(self.usd_floor, usd_price),
+            Err(e) => {
+                tracing::trace!(?token, %e, "can't price the token in usd");
+                0
+            }
+        }
+    }
+
+    /// The usd price of a whole `token`, from what ETH is worth in it and in
+    /// `usd_token`.
+    pub fn usd_price(
+        &self,
+        token: &TokenDustConfig,
+        prices: &TokenPriceGenerator
+    ) -> eyre::Result<f64> {
+        let usd_token = self
+            .usd_token
+            .and_then(|usd_token| self.token(usd_token))
+            .ok_or_else(|| eyre::eyre!("no usd token with known decimals"))?;
+
+        // what a whole token is worth in wei
+        let wei_per_token = |token: &TokenDustConfig| -> eyre::Result<f64> {
+            let per_wei = prices.get_eth_conversion_price(token.token)?.as_f64();
+            if per_wei <= 0.0 {
+                eyre::bail!("{} has no price", token.token)
+            }
+            Ok(10f64.powi(token.decimals as i32) / per_wei)
+        };
+
+        Ok(wei_per_token(token)? / wei_per_token(usd_token)?)
+    }
+
+    fn token(&self, token: Address) -> Option<&TokenDustConfig> {
+        self.tokens.iter().find(|config| config.token == token)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenDustConfig {
+    pub token:    Address,
+    pub decimals: u8,
+    /// ticker used to label the metrics and logs of pools with this token
+    #[serde(default)]
+    pub symbol:   Option<String>
+}
+
+impl TokenDustConfig {
+    /// Smallest amount worth `usd_floor` at `usd_price` per whole token.
+    pub fn threshold(&self, usd_floor: f64, usd_price: f64) -> u128 {
+        if usd_price <= 0.0 || usd_floor <= 0.0 {
+            return 0
+        }
+
+        (usd_floor * 10f64.powi(self.decimals as i32) / usd_price) as u128
+    }
+}
+
+/// How a token lays out its balance and approval mappings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashMethod {
+    /// `keccak256(key . slot)`
+    #[serde(rename = "sol")]
+    Solidity,
+    /// `keccak256(slot . key)`
+    #[serde(rename = "vyper")]
+    Vyper,
+    /// a solidity mapping to a struct, with the amount the given number of
+    /// slots into the struct
+    #[serde(rename = "offset")]
+    Offset(u8)
+}
+
+impl HashMethod {
+    /// Slot of `key` in the mapping at `slot`.
+    fn mapping_slot(&self, slot: U256, key: Address) -> U256 {
+        let mut buf = [0u8; 64];
+        let (key_at, slot_at) = match self {
+            HashMethod::Solidity | HashMethod::Offset(_) => (0, 32),
+            HashMethod::Vyper => (32, 0)
+        };
+        buf[key_at + 12..key_at + 32].copy_from_slice(&**key);
+        buf[slot_at..slot_at + 32].copy_from_slice(&slot.to_be_bytes::<32>());
+
+        U256::from_be_bytes(*keccak256(buf))
+    }
+
+    /// Slots between the start of the mapping value and the amount.
+    const fn value_offset(&self) -> u8 {
+        match self {
+            HashMethod::Offset(offset) => *offset,
+            HashMethod::Solidity | HashMethod::Vyper => 0
+        }
+    }
+}
+
+impl ValidationConfig {
+    /// `SYM0/SYM1` names of the configured pools whose tokens both have a
+    /// symbol.
+    pub fn pool_labels(&self) -> HashMap<PoolId, String> {
+        let symbols = self
+            .dust
+            .tokens
+            .iter()
+            .filter_map(|token| Some((token.token, token.symbol.as_deref()?)))
+            .collect::<HashMap<_, _>>();
+
+        self.pools
+            .iter()
+            .filter_map(|pool| {
+                let token0 = symbols.get(&pool.token0)?;
+                let token1 = symbols.get(&pool.token1)?;
+                Some((pool.pool_id, format!("{token0}/{token1}")))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolConfig {
+    pub token0:      Address,
+    pub token1:      Address,
+    pub pool_id:     PoolId,
+    /// index of the pool in the pool store of the angstrom contract
+    pub store_index: u16
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBalanceSlot {
+    pub token:       Address,
+    pub hash_method: HashMethod,
+    pub slot_index:  u8
+}
+
+impl TokenBalanceSlot {
+    pub fn generate_slot(&self, of: Address) -> eyre::Result<U256> {
+        let slot = self
+            .hash_method
+            .mapping_slot(U256::from(self.slot_index), of);
+
+        Ok(slot.wrapping_add(U256::from(self.hash_method.value_offset())))
+    }
+
+    pub fn load_balance<DB: BlockStateProviderFactory>(
+        &self,
+        of: Address,
+        db: &RevmLRU<DB>
+    ) -> eyre::Result<U256> {
+        Ok(db.storage_ref(self.token, self.generate_slot(of)?)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenApprovalSlot {
+    pub token:       Address,
+    pub hash_method: HashMethod,
+    pub slot_index:  u8
+}
+
+impl TokenApprovalSlot {
+    /// Slot of `allowance[user][contract]`.
+    pub fn generate_slot(&self, user: Address, contract: Address) -> eyre::Result<U256> {
+        let inner = self
+            .hash_method
+            .mapping_slot(U256::from(self.slot_index), user);
+        let slot = self.hash_method.mapping_slot(inner, contract);
+
+        Ok(slot.wrapping_add(U256::from(self.hash_method.value_offset())))
+    }
+
+    pub fn load_approval_amount<DB: BlockStateProviderFactory>(
+        &self,
+        user: Address,
+        contract: Address,
+        db: &RevmLRU<DB>
+    ) -> eyre::Result<U256> {
+        Ok(db.storage_ref(self.token, self.generate_slot(user, contract)?)?)
+    }
+}
+
+/// The slots of a token registered at runtime, instead of through the config
+/// file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenSlots {
+    pub balance:      TokenBalanceSlot,
+    pub approval:     TokenApprovalSlot,
+    /// an account that holds some of the token, used to check that the
+    /// balance slot points at real balances
+    pub probe_holder: Address
+}
+
+#[cfg(not(feature = "testnet"))]
+pub fn load_data_fetcher_config(config_path: &Path) -> eyre::Result<DataFetcherConfig> {
+    let file = std::fs::read_to_string(config_path)?;
+    Ok(toml::from_str(&file)?)
+}
+
+#[cfg(feature = "testnet")]
+pub fn load_data_fetcher_config(_config_path: &Path) -> eyre::Result<DataFetcherConfig> {
+    Ok(DataFetcherConfig { approvals: vec![], balances: vec![] })
+}
+
+#[cfg(not(feature = "testnet"))]
+pub fn load_validation_config(config_path: &Path) -> eyre::Result<ValidationConfig> {
+    let file = std::fs::read_to_string(config_path)?;
+    Ok(toml::from_str(&file)?)
+}
+
+#[cfg(feature = "testnet")]
+pub fn load_validation_config(_config_path: &Path) -> eyre::Result<ValidationConfig> {
+    Ok(ValidationConfig {
+        pools:                   vec![PoolConfig {
+            token0:      alloy::primitives::address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+            token1:      alloy::primitives::address!("dAC17F958D2ee523a2206206994597C13D831ec7"),
+            pool_id:     alloy::primitives::b256!(
+                "f3d07fe972c84e425ea04c19b19ca12e463d494680251f1aaac588870254d245"
+            ),
+            store_index: 0
+        }],
+        max_validation_per_user: 1,
+        dust:                    DustConfig::default(),
+        listing:                 ListingPolicyConfig::default(),
+        gas:                     GasPricingConfig::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{keccak256, Address, B256, U256};
+    use angstrom_types::matching::Ray;
+
+    use super::{
+        DustConfig, HashMethod, PoolConfig, TokenApprovalSlot, TokenBalanceSlot, TokenDustConfig,
+        ValidationConfig, DEFAULT_GAS_PRICE_GWEI, DEFAULT_MAX_HOPS
+    };
+    use crate::order::state::token_pricing::{PairsWithPrice, TokenPriceGenerator, WETH_ADDRESS};
+
+    #[test]
+    fn slots_follow_the_hash_method() {
+        let token = Address::with_last_byte(1);
+        let user = Address::with_last_byte(2);
+        let contract = Address::with_last_byte(3);
+        let slot = |hash_method| TokenBalanceSlot { token, hash_method, slot_index: 4 };
+        let hash = |a: B256, b: B256| U256::from_be_bytes(*keccak256([a.0, b.0].concat()));
+        let (user_word, index_word) = (user.into_word(), B256::from(U256::from(4)));
+
+        let solidity = hash(user_word, index_word);
+        assert_eq!(slot(HashMethod::Solidity).generate_slot(user).unwrap(), solidity);
+        assert_eq!(
+            slot(HashMethod::Offset(2)).generate_slot(user).unwrap(),
+            solidity + U256::from(2)
+        );
+        assert_eq!(
+            slot(HashMethod::Vyper).generate_slot(user).unwrap(),
+            hash(index_word, user_word)
+        );
+
+        // allowance[user][contract]
+        let contract_word = contract.into_word();
+        let approval = |hash_method| TokenApprovalSlot { token, hash_method, slot_index: 4 };
+        let inner = B256::from(hash(user_word, index_word));
+        assert_eq!(
+            approval(HashMethod::Solidity)
+                .generate_slot(user, contract)
+                .unwrap(),
+            hash(contract_word, inner)
+        );
+        let inner = B256::from(hash(index_word, user_word));
+        assert_eq!(
+            approval(HashMethod::Vyper)
+                .generate_slot(user, contract)
+                .unwrap(),
+            hash(inner, contract_word)
+        );
+    }
+
+    const USDT: Address = Address::with_last_byte(2);
+
+    fn dust_config(usd_floor: f64) -> DustConfig {
+        DustConfig {
+            usd_floor,
+            usd_token: Some(USDT),
+            tokens: vec![
+                TokenDustConfig { token: WETH_ADDRESS, decimals: 18, symbol: None },
+                TokenDustConfig { token: USDT, decimals: 6, symbol: None },
+            ]
+        }
+    }
+
+    /// Moves every block of the averaging window to `usd_per_eth`.
+    fn price_eth(prices: &mut TokenPriceGenerator, usd_per_eth: f64) {
+        let block = prices.current_block();
+        for block in block + 1..=block + 5 {
+            prices.on_new_block(
+                block,
+                vec![PairsWithPrice {
+                    token0:         WETH_ADDRESS,
+                    token1:         USDT,
+                    block_num:      block,
+                    // usdt units per wei
+                    price_1_over_0: Ray::from(usd_per_eth * 1e-12)
+                }]
+            );
+        }
+    }
+
+    fn assert_close(threshold: u128, expected: u128) {
+        let error = threshold.abs_diff(expected) as f64 / expected as f64;
+        assert!(error < 1e-6, "{threshold} isn't close to {expected}");
+    }
+
+    #[test]
+    fn dust_threshold_respects_decimals() {
+        let config = dust_config(5.0);
+        let mut prices = TokenPriceGenerator::default();
+        price_eth(&mut prices, 2500.0);
+
+        assert_close(config.threshold(WETH_ADDRESS, &prices), 2_000_000_000_000_000);
+        assert_close(config.threshold(USDT, &prices), 5_000_000);
+    }
+
+    #[test]
+    fn dust_threshold_follows_the_price() {
+        let config = dust_config(5.0);
+        let mut prices = TokenPriceGenerator::default();
+        price_eth(&mut prices, 2500.0);
+        assert_close(config.threshold(WETH_ADDRESS, &prices), 2_000_000_000_000_000);
+
+        price_eth(&mut prices, 5000.0);
+        assert_close(config.threshold(WETH_ADDRESS, &prices), 1_000_000_000_000_000);
+        assert_close(config.threshold(USDT, &prices), 5_000_000);
+    }
+
+    #[test]
+    fn unpriced_tokens_have_no_threshold() {
+        let config = dust_config(5.0);
+        let mut prices = TokenPriceGenerator::default();
+        assert_eq!(config.threshold(WETH_ADDRESS, &prices), 0);
+
+        price_eth(&mut prices, 2500.0);
+        // not configured for dust
+        assert_eq!(config.threshold(Address::with_last_byte(3), &prices), 0);
+        // no usd token to price against
+        let config = DustConfig { usd_token: None, ..config };
+        assert_eq!(config.threshold(WETH_ADDRESS, &prices), 0);
+    }
+
+    #[test]
+    fn pools_are_labelled_by_token_symbols() {
+        let token = |byte, symbol: Option<&str>| TokenDustConfig {
+            token:    Address::with_last_byte(byte),
+            decimals: 18,
+            symbol:   symbol.map(Into::into)
+        };
+        let pool = |byte, token0, token1| PoolConfig {
+            token0:      Address::with_last_byte(token0),
+            token1:      Address::with_last_byte(token1),
+            pool_id:     B256::with_last_byte(byte),
+            store_index: byte as u16
+        };
+        let config = ValidationConfig {
+            pools:                   vec![pool(1, 1, 2), pool(2, 1, 3)],
+            max_validation_per_user: 1,
+            dust:                    DustConfig {
+                usd_floor: 0.0,
+                usd_token: None,
+                tokens:    vec![token(1, Some("WETH")), token(2, Some("USDC")), token(3, None)]
+            },
+            listing:                 Default::default(),
+            gas:                     Default::default()
+        };
+
+        let labels = config.pool_labels();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[&B256::with_last_byte(1)], "WETH/USDC");
+    }
+
+    #[test]
+    fn gas_pricing_falls_back_to_the_defaults() {
+        let config: ValidationConfig = toml::from_str(
+            r#"
+            pools = []
+            max_validation_per_user = 1
+            "#
+        )
+        .unwrap();
+        assert_eq!(config.gas.gas_price_gwei, DEFAULT_GAS_PRICE_GWEI);
+        assert_eq!(config.gas.max_hops, DEFAULT_MAX_HOPS);
+
+        let config: ValidationConfig = toml::from_str(
+            r#"
+            pools = []
+            max_validation_per_user = 1
+
+            [gas]
+            max_hops = 1
+            "#
+        )
+        .unwrap();
+        assert_eq!(config.gas.gas_price_gwei, DEFAULT_GAS_PRICE_GWEI);
+        assert_eq!(config.gas.max_hops, 1);
+        assert_eq!(config.gas.gas_price_wei(), DEFAULT_GAS_PRICE_GWEI as u128 * 1_000_000_000);
+    }
+}
@@ -0,0 +1,239 @@
+//! Finds the balance and approval slots of an erc20 by calling `balanceOf` and
+//! `allowance` under revm and matching the storage the token reads against
+//! the slots of every mapping index, so they don't have to be looked up by
+//! hand for the config. Solidity and vyper mappings are found, amounts stored
+//! at an offset into a struct are not.
+use alloy::{
+    primitives::{address, Address, TxKind, U256},
+    sol,
+    sol_types::SolCall
+};
+use dashmap::DashMap;
+use reth_revm::DatabaseRef;
+use revm::{
+    inspector_handle_register,
+    interpreter::{opcode, Interpreter},
+    Database, Evm, EvmContext, Inspector
+};
+use thiserror::Error;
+
+use super::{HashMethod, TokenApprovalSlot, TokenBalanceSlot};
+use crate::order::state::db_state_utils::ANGSTROM_CONTRACT;
+
+sol! {
+    function balanceOf(address owner) external view returns (uint256);
+    function allowance(address owner, address spender) external view returns (uint256);
+}
+
+/// Layouts tried, in order.
+const HASH_METHODS: [HashMethod; 2] = [HashMethod::Solidity, HashMethod::Vyper];
+
+/// Account the calls are made for. It doesn't need to hold any tokens, the
+/// mapping is read all the same.
+const PROBE_OWNER: Address = address!("00000000000000000000000000000000000a5105");
+
+#[derive(Debug, Clone, Error)]
+pub enum SlotProbeError {
+    #[error("failed to simulate {call} of {token:?}: {reason}")]
+    Simulation { token: Address, call: &'static str, reason: String },
+    #[error("{call} of {token:?} reverted")]
+    Reverted { token: Address, call: &'static str },
+    #[error("{call} of {token:?} didn't read a mapping of the owner")]
+    NotFound { token: Address, call: &'static str }
+}
+
+/// Probes tokens once, the slots found are kept for as long as the prober
+/// lives.
+pub struct TokenSlotProber<DB> {
+    db:    DB,
+    found: DashMap<Address, (TokenBalanceSlot, TokenApprovalSlot)>
+}
+
+impl<DB> TokenSlotProber<DB>
+where
+    DB: DatabaseRef,
+    DB::Error: std::fmt::Display
+{
+    pub fn new(db: DB) -> Self {
+        Self { db, found: DashMap::new() }
+    }
+
+    pub fn probe(
+        &self,
+        token: Address
+    ) -> Result<(TokenBalanceSlot, TokenApprovalSlot), SlotProbeError> {
+        if let Some(found) = self.found.get(&token) {
+            return Ok(found.clone())
+        }
+
+        let slots = (self.balance_slot(token)?, self.approval_slot(token)?);
+        self.found.insert(token, slots.clone());
+        tracing::debug!(
+            ?token,
+            balance = slots.0.slot_index,
+            approval = slots.1.slot_index,
+            "probed token slots"
+        );
+
+        Ok(slots)
+    }
+
+    pub fn balance_slot(&self, token: Address) -> Result<TokenBalanceSlot, SlotProbeError> {
+        let call = "balanceOf";
+        let reads =
+            self.storage_reads(token, call, balanceOfCall { owner: PROBE_OWNER }.abi_encode())?;
+
+        HASH_METHODS
+            .into_iter()
+            .flat_map(|hash_method| {
+                (0..=u8::MAX).map(move |slot_index| TokenBalanceSlot {
+                    token,
+                    hash_method,
+                    slot_index
+                })
+            })
+            .find(|slot| {
+                slot.generate_slot(PROBE_OWNER)
+                    .is_ok_and(|slot| reads.contains(&slot))
+            })
+            .ok_or(SlotProbeError::NotFound { token, call })
+    }
+
+    pub fn approval_slot(&self, token: Address) -> Result<TokenApprovalSlot, SlotProbeError> {
+        let call = "allowance";
+        let calldata =
+            allowanceCall { owner: PROBE_OWNER, spender: ANGSTROM_CONTRACT }.abi_encode();
+        let reads = self.storage_reads(token, call, calldata)?;
+
+        HASH_METHODS
+            .into_iter()
+            .flat_map(|hash_method| {
+                (0..=u8::MAX).map(move |slot_index| TokenApprovalSlot {
+                    token,
+                    hash_method,
+                    slot_index
+                })
+            })
+            .find(|slot| {
+                slot.generate_slot(PROBE_OWNER, ANGSTROM_CONTRACT)
+                    .is_ok_and(|slot| reads.contains(&slot))
+            })
+            .ok_or(SlotProbeError::NotFound { token, call })
+    }
+
+    /// Slots of `token` read by the call, including reads made through a
+    /// delegatecall to an implementation contract.
+    fn storage_reads(
+        &self,
+        token: Address,
+        call: &'static str,
+        calldata: Vec<u8>
+    ) -> Result<Vec<U256>, SlotProbeError> {
+        let mut reads = StorageReads { token, slots: vec![] };
+        let mut evm = Evm::builder()
+            .with_ref_db(&self.db)
+            .with_external_context(&mut reads)
+            .append_handler_register(inspector_handle_register)
+            .modify_tx_env(|tx| {
+                tx.caller = PROBE_OWNER;
+                tx.transact_to = TxKind::Call(token);
+                tx.data = calldata.into();
+            })
+            .build();
+        let result = evm
+            .transact()
+            .map_err(|e| SlotProbeError::Simulation { token, call, reason: e.to_string() })?
+            .result;
+        drop(evm);
+
+        if !result.is_success() {
+            return Err(SlotProbeError::Reverted { token, call })
+        }
+
+        Ok(reads.slots)
+    }
+}
+
+/// Records the keys of every `SLOAD` on the storage of `token`.
+struct StorageReads {
+    token: Address,
+    slots: Vec<U256>
+}
+
+impl<DB: Database> Inspector<DB> for StorageReads {
+    fn step(&mut self, interp: &mut Interpreter, _: &mut EvmContext<DB>) {
+        if interp.current_opcode() != opcode::SLOAD || interp.contract.target_address != self.token
+        {
+            return
+        }
+        if let Ok(slot) = interp.stack().peek(0) {
+            self.slots.push(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountInfo, Bytecode}
+    };
+
+    use super::*;
+
+    /// Returns `balances[owner]` for whatever is called, with `balances` at
+    /// slot 3.
+    const BALANCE_AT_SLOT_3: [u8; 25] = [
+        0x60, 0x04, 0x35, 0x60, 0x00, 0x52, // mstore(0, calldataload(4))
+        0x60, 0x03, 0x60, 0x20, 0x52, // mstore(32, 3)
+        0x60, 0x40, 0x60, 0x00, 0x20, 0x54, // sload(keccak256(0, 64))
+        0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3 // return(0, 32)
+    ];
+
+    /// Returns `allowances[owner][spender]` for whatever is called, with
+    /// `allowances` at slot 2.
+    const ALLOWANCE_AT_SLOT_2: [u8; 39] = [
+        0x60, 0x04, 0x35, 0x60, 0x00, 0x52, // mstore(0, calldataload(4))
+        0x60, 0x02, 0x60, 0x20, 0x52, // mstore(32, 2)
+        0x60, 0x40, 0x60, 0x00, 0x20, 0x60, 0x20, 0x52, // mstore(32, keccak256(0, 64))
+        0x60, 0x24, 0x35, 0x60, 0x00, 0x52, // mstore(0, calldataload(36))
+        0x60, 0x40, 0x60, 0x00, 0x20, 0x54, // sload(keccak256(0, 64))
+        0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3 // return(0, 32)
+    ];
+
+    fn prober_for(token: Address, code: &'static [u8]) -> TokenSlotProber<CacheDB<EmptyDB>> {
+        let code = Bytecode::new_raw(Bytes::from_static(code));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            token,
+            AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() }
+        );
+        TokenSlotProber::new(db)
+    }
+
+    #[test]
+    fn finds_the_allowance_mapping() {
+        let token = Address::with_last_byte(0x71);
+        let prober = prober_for(token, &ALLOWANCE_AT_SLOT_2);
+
+        let approval = prober.approval_slot(token).unwrap();
+        assert_eq!(approval.slot_index, 2);
+        assert_eq!(approval.hash_method, HashMethod::Solidity);
+    }
+
+    #[test]
+    fn finds_the_balance_mapping() {
+        let token = Address::with_last_byte(0x70);
+        let prober = prober_for(token, &BALANCE_AT_SLOT_3);
+
+        assert_eq!(prober.balance_slot(token).unwrap().slot_index, 3);
+        // the allowance call reads the balance mapping too, which isn't a
+        // nested mapping of the owner and spender
+        assert!(matches!(
+            prober.approval_slot(token),
+            Err(SlotProbeError::NotFound { call: "allowance", .. })
+        ));
+        assert!(prober.probe(token).is_err());
+    }
+}
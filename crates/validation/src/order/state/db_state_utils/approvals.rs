@@ -11,12 +11,18 @@ use reth_revm::DatabaseRef;
 use super::ANGSTROM_CONTRACT;
 use crate::order::state::{config::TokenApprovalSlot, BlockStateProviderFactory, RevmLRU};
 
+/// Shared between all clones, so that slots registered at runtime are picked
+/// up everywhere.
 #[derive(Clone)]
-pub struct Approvals(HashMap<Address, TokenApprovalSlot>);
+pub struct Approvals(Arc<RwLock<HashMap<Address, TokenApprovalSlot>>>);
 
 impl Approvals {
     pub fn new(current_slots: HashMap<Address, TokenApprovalSlot>) -> Self {
-        Self(current_slots)
+        Self(Arc::new(RwLock::new(current_slots)))
+    }
+
+    pub(super) fn slots(&self) -> &RwLock<HashMap<Address, TokenApprovalSlot>> {
+        &self.0
     }
 
     pub fn fetch_approval_balance_for_token_overrides<DB: BlockStateProviderFactory>(
@@ -26,7 +32,8 @@ impl Approvals {
         db: Arc<RevmLRU<DB>>,
         overrides: &HashMap<Address, HashMap<U256, U256>>
     ) -> Option<U256> {
-        self.0.get(&token).and_then(|slot| {
+        let slot = self.0.read().get(&token).cloned();
+        slot.and_then(|slot| {
             let slot_addr = slot.generate_slot(user, ANGSTROM_CONTRACT).ok()?;
             if let Some(address_slots) = overrides.get(&token) {
                 if let Some(s_override) = address_slots.get(&slot_addr) {
@@ -44,8 +51,7 @@ impl Approvals {
         token: Address,
         db: &RevmLRU<DB>
     ) -> Option<U256> {
-        self.0
-            .get(&token)
-            .and_then(|slot| slot.load_approval_amount(user, ANGSTROM_CONTRACT, db).ok())
+        let slot = self.0.read().get(&token).cloned();
+        slot.and_then(|slot| slot.load_approval_amount(user, ANGSTROM_CONTRACT, db).ok())
     }
 }
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use alloy::primitives::{Address, U256};
+use parking_lot::RwLock;
 use reth_revm::DatabaseRef;
 
 use crate::{
@@ -8,12 +9,18 @@ use crate::{
     order::state::config::TokenBalanceSlot
 };
 
+/// Shared between all clones, so that slots registered at runtime are picked
+/// up everywhere.
 #[derive(Clone)]
-pub struct Balances(HashMap<Address, TokenBalanceSlot>);
+pub struct Balances(Arc<RwLock<HashMap<Address, TokenBalanceSlot>>>);
 
 impl Balances {
     pub fn new(slots: HashMap<Address, TokenBalanceSlot>) -> Self {
-        Self(slots)
+        Self(Arc::new(RwLock::new(slots)))
+    }
+
+    pub(super) fn slots(&self) -> &RwLock<HashMap<Address, TokenBalanceSlot>> {
+        &self.0
     }
 
     pub fn fetch_balance_for_token_overrides<DB: BlockStateProviderFactory>(
@@ -23,7 +30,8 @@ impl Balances {
         db: Arc<RevmLRU<DB>>,
         overrides: &HashMap<Address, HashMap<U256, U256>>
     ) -> Option<U256> {
-        self.0.get(&token).and_then(|slot| {
+        let slot = self.0.read().get(&token).cloned();
+        slot.and_then(|slot| {
             let slot_addr = slot.generate_slot(user).ok()?;
             if let Some(address_slots) = overrides.get(&token) {
                 if let Some(s_override) = address_slots.get(&slot_addr) {
@@ -40,8 +48,7 @@ impl Balances {
         token: Address,
         db: &RevmLRU<DB>
     ) -> Option<U256> {
-        self.0
-            .get(&token)
-            .and_then(|slot| slot.load_balance(user, db).ok())
+        let slot = self.0.read().get(&token).cloned();
+        slot.and_then(|slot| slot.load_balance(user, db).ok())
     }
 }
@@ -2,14 +2,21 @@ pub mod approvals;
 pub mod balances;
 pub mod nonces;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc
+};
 
 use alloy::primitives::{Address, U256};
 use angstrom_types::sol_bindings::ext::RawPoolOrder;
+use parking_lot::RwLock;
+use reth_primitives::KECCAK_EMPTY;
+use reth_revm::DatabaseRef;
 use revm::{Database, Inspector};
+use thiserror::Error;
 
 use self::{approvals::Approvals, balances::Balances, nonces::Nonces};
-use super::config::DataFetcherConfig;
+use super::config::{DataFetcherConfig, TokenSlots};
 use crate::common::lru_db::{BlockStateProvider, BlockStateProviderFactory, RevmLRU};
 
 pub const ANGSTROM_CONTRACT: Address = Address::new([0; 20]);
@@ -34,6 +41,37 @@ pub trait StateFetchUtils: Clone + Send + Unpin {
     ) -> Option<U256>;
 
     fn fetch_balance_for_token(&self, user: Address, token: Address) -> Option<U256>;
+
+    /// Returns fetch utils that read all state at `block`, unaffected by any
+    /// block updates that land while they are in use.
+    fn pinned_at_block(&self, block: u64) -> Self;
+
+    /// Starts reading the balances and approvals of a token from the given
+    /// slots, once they pass the probes.
+    fn register_token_slots(&self, slots: TokenSlots) -> Result<(), TokenSlotError>;
+
+    /// Picks up the slots of a reloaded config file. Tokens in the config get
+    /// its slots, tokens dropped from the config lose theirs and the slots
+    /// registered at runtime are kept.
+    fn reload_token_slots(&self, config: DataFetcherConfig);
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum TokenSlotError {
+    #[error("balance slot is for {balance:?} but approval slot is for {approval:?}")]
+    TokenMismatch { balance: Address, approval: Address },
+    #[error("balance and approval slots can't be the same mapping")]
+    SameSlot,
+    #[error("token slots can't be generated: {0}")]
+    UnsupportedHashMethod(String),
+    #[error("token {0:?} has no code")]
+    NoCode(Address),
+    #[error("{holder:?} has no balance of {token:?} at the given slot")]
+    EmptyProbe { token: Address, holder: Address },
+    #[error("failed to read token state: {0}")]
+    Db(String),
+    #[error("validator is not running")]
+    ValidatorStopped
 }
 
 #[derive(Debug)]
@@ -52,7 +90,9 @@ pub struct FetchUtils<DB> {
     pub approvals: Approvals,
     pub balances:  Balances,
     pub nonces:    Nonces,
-    pub db:        Arc<RevmLRU<DB>>
+    pub db:        Arc<RevmLRU<DB>>,
+    /// tokens whose slots came from the config file
+    config_tokens: Arc<RwLock<HashSet<Address>>>
 }
 
 impl<DB> StateFetchUtils for FetchUtils<DB>
@@ -94,11 +134,70 @@ where
     fn fetch_balance_for_token(&self, user: Address, token: Address) -> Option<U256> {
         self.balances.fetch_balance_for_token(user, token, &self.db)
     }
+
+    fn pinned_at_block(&self, block: u64) -> Self {
+        Self { db: Arc::new(self.db.pinned_at_block(block)), ..self.clone() }
+    }
+
+    fn register_token_slots(&self, slots: TokenSlots) -> Result<(), TokenSlotError> {
+        let TokenSlots { balance, approval, probe_holder } = slots;
+        let token = balance.token;
+        if approval.token != token {
+            return Err(TokenSlotError::TokenMismatch { balance: token, approval: approval.token })
+        }
+        if approval.slot_index == balance.slot_index {
+            return Err(TokenSlotError::SameSlot)
+        }
+        balance
+            .generate_slot(probe_holder)
+            .and_then(|_| approval.generate_slot(probe_holder, ANGSTROM_CONTRACT))
+            .map_err(|e| TokenSlotError::UnsupportedHashMethod(e.to_string()))?;
+
+        let account = self
+            .db
+            .basic_ref(token)
+            .map_err(|e| TokenSlotError::Db(e.to_string()))?;
+        if account.map_or(true, |account| account.code_hash == KECCAK_EMPTY) {
+            return Err(TokenSlotError::NoCode(token))
+        }
+
+        let held = balance
+            .load_balance(probe_holder, &self.db)
+            .map_err(|e| TokenSlotError::Db(e.to_string()))?;
+        if held.is_zero() {
+            return Err(TokenSlotError::EmptyProbe { token, holder: probe_holder })
+        }
+
+        // both locks are held so that the token never has only one of its slots
+        let mut approvals = self.approvals.slots().write();
+        let mut balances = self.balances.slots().write();
+        approvals.insert(token, approval);
+        balances.insert(token, balance);
+        tracing::info!(?token, "registered token slots");
+
+        Ok(())
+    }
+
+    fn reload_token_slots(&self, config: DataFetcherConfig) {
+        let tokens = config.tokens();
+        let mut approvals = self.approvals.slots().write();
+        let mut balances = self.balances.slots().write();
+        let mut config_tokens = self.config_tokens.write();
+        for token in config_tokens.difference(&tokens) {
+            tracing::info!(?token, "token dropped from the config, removing its slots");
+            approvals.remove(token);
+            balances.remove(token);
+        }
+        approvals.extend(config.approvals.into_iter().map(|app| (app.token, app)));
+        balances.extend(config.balances.into_iter().map(|bal| (bal.token, bal)));
+        *config_tokens = tokens;
+    }
 }
 
 impl<DB: BlockStateProviderFactory> FetchUtils<DB> {
     pub fn new(config: DataFetcherConfig, db: Arc<RevmLRU<DB>>) -> Self {
         Self {
+            config_tokens: Arc::new(RwLock::new(config.tokens())),
             approvals: Approvals::new(
                 config
                     .approvals
@@ -119,7 +218,7 @@ impl<DB: BlockStateProviderFactory> FetchUtils<DB> {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_fetching {
     use std::collections::{HashMap, HashSet};
 
@@ -192,5 +291,120 @@ pub mod test_fetching {
                 .get(&user)
                 .and_then(|inner| inner.value().get(&token).cloned())
         }
+
+        fn pinned_at_block(&self, _: u64) -> Self {
+            self.clone()
+        }
+
+        fn register_token_slots(&self, _: TokenSlots) -> Result<(), TokenSlotError> {
+            Ok(())
+        }
+
+        fn reload_token_slots(&self, _: DataFetcherConfig) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+
+    use alloy::primitives::{BlockNumber, StorageKey, StorageValue};
+    use reth_primitives::Account;
+    use reth_provider::ProviderResult;
+
+    use super::*;
+    use crate::{
+        common::lru_db::BlockStateProvider,
+        order::state::config::{HashMethod, TokenApprovalSlot, TokenBalanceSlot}
+    };
+
+    struct EmptyState;
+
+    impl BlockStateProvider for EmptyState {
+        fn get_basic_account(&self, _: Address) -> ProviderResult<Option<Account>> {
+            Ok(None)
+        }
+
+        fn get_storage(&self, _: Address, _: StorageKey) -> ProviderResult<Option<StorageValue>> {
+            Ok(None)
+        }
+    }
+
+    struct EmptyDb;
+
+    impl BlockStateProviderFactory for EmptyDb {
+        type Provider = EmptyState;
+
+        fn state_by_block(&self, _: u64) -> ProviderResult<EmptyState> {
+            Ok(EmptyState)
+        }
+
+        fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+            Ok(0)
+        }
+    }
+
+    fn config(tokens: &[Address]) -> DataFetcherConfig {
+        DataFetcherConfig {
+            approvals: tokens
+                .iter()
+                .map(|&token| TokenApprovalSlot {
+                    token,
+                    hash_method: HashMethod::Solidity,
+                    slot_index: 1
+                })
+                .collect(),
+            balances:  tokens
+                .iter()
+                .map(|&token| TokenBalanceSlot {
+                    token,
+                    hash_method: HashMethod::Solidity,
+                    slot_index: 0
+                })
+                .collect()
+        }
+    }
+
+    fn slotted_tokens(fetch: &FetchUtils<EmptyDb>) -> HashSet<Address> {
+        let balances = fetch.balances.slots().read().keys().copied().collect();
+        let approvals = fetch
+            .approvals
+            .slots()
+            .read()
+            .keys()
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(balances, approvals);
+        balances
+    }
+
+    #[test]
+    fn reloading_drops_the_slots_of_removed_tokens() {
+        let (kept, removed, added, registered) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+            Address::with_last_byte(4)
+        );
+        let db = Arc::new(RevmLRU::new(1_000, Arc::new(EmptyDb), Arc::new(AtomicU64::new(0))));
+        let fetch = FetchUtils::new(config(&[kept, removed]), db);
+        // as if registered at runtime, which skips the config
+        let runtime = config(&[registered]);
+        fetch
+            .approvals
+            .slots()
+            .write()
+            .extend(runtime.approvals.into_iter().map(|app| (app.token, app)));
+        fetch
+            .balances
+            .slots()
+            .write()
+            .extend(runtime.balances.into_iter().map(|bal| (bal.token, bal)));
+
+        fetch.reload_token_slots(config(&[kept, added]));
+        assert_eq!(slotted_tokens(&fetch), HashSet::from([kept, added, registered]));
+
+        fetch.reload_token_slots(config(&[]));
+        assert_eq!(slotted_tokens(&fetch), HashSet::from([registered]));
     }
 }
@@ -0,0 +1,133 @@
+//! Estimates how much gas executing an order in a bundle costs, which is what
+//! orders bid against each other with.
+use alloy::primitives::U256;
+use angstrom_types::{
+    matching::{price::Rounding, Ray},
+    orders::OrderLocation,
+    sol_bindings::ext::RawPoolOrder
+};
+use thiserror::Error;
+
+/// gas to settle a user order in a bundle
+pub const USER_ORDER_GAS: u128 = 45_000;
+/// gas to settle a top of block order in a bundle
+pub const TOB_ORDER_GAS: u128 = 60_000;
+/// gas to call into a hook, on top of what the hook itself uses
+pub const HOOK_CALL_GAS: u128 = 10_000;
+/// calldata gas per byte of the hook payload
+pub const HOOK_PAYLOAD_GAS_PER_BYTE: u128 = 16;
+
+/// The gas executing `order` is estimated to use.
+pub fn estimate_order_gas<O: RawPoolOrder>(order: &O) -> u128 {
+    let base = match order.order_location() {
+        OrderLocation::Limit => USER_ORDER_GAS,
+        OrderLocation::Searcher => TOB_ORDER_GAS
+    };
+    let payload = order.hook_payload();
+    if payload.is_empty() {
+        return base
+    }
+
+    base + HOOK_CALL_GAS + payload.len() as u128 * HOOK_PAYLOAD_GAS_PER_BYTE
+}
+
+/// What validation charges an order for gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderGasEstimate {
+    pub gas_units:    u128,
+    /// cost of the gas at the configured gas price
+    pub gas_wei:      u128,
+    /// cost of the gas in the input token of the order, 0 when the token
+    /// can't be priced as it isn't charged then
+    pub gas_in_token: u128
+}
+
+impl OrderGasEstimate {
+    /// The gas of `order` at `gas_price_wei`, where ETH is worth `eth_price`
+    /// of its input token.
+    pub fn new<O: RawPoolOrder>(order: &O, gas_price_wei: u128, eth_price: Option<Ray>) -> Self {
+        let gas_units = estimate_order_gas(order);
+        Self {
+            gas_units,
+            gas_wei: gas_units.saturating_mul(gas_price_wei),
+            gas_in_token: eth_price
+                .map(|eth_price| gas_charge(gas_units, gas_price_wei, eth_price))
+                .unwrap_or_default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum GasEstimateError {
+    #[error("validator is not running")]
+    ValidatorStopped
+}
+
+/// What `gas_units` cost at `gas_price_wei` in a token worth `eth_price` of
+/// itself per ETH, rounded up. Saturates instead of overflowing.
+pub fn gas_charge(gas_units: u128, gas_price_wei: u128, eth_price: Ray) -> u128 {
+    let wei = U256::from(gas_units) * U256::from(gas_price_wei);
+    eth_price
+        .checked_mul_quantity(wei, Rounding::Up)
+        .map(|charge| charge.saturating_to())
+        .unwrap_or(u128::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::{
+        grouped_orders::{GroupedVanillaOrder, StandingVariants},
+        rpc_orders::{ExactStandingOrder, TopOfBlockOrder}
+    };
+
+    use super::*;
+
+    #[test]
+    fn charges_hook_payloads_by_length() {
+        let plain = GroupedVanillaOrder::Standing(StandingVariants::Exact(ExactStandingOrder {
+            ..Default::default()
+        }));
+        assert_eq!(estimate_order_gas(&plain), USER_ORDER_GAS);
+
+        let hooked = GroupedVanillaOrder::Standing(StandingVariants::Exact(ExactStandingOrder {
+            hookPayload: vec![1u8; 10].into(),
+            ..Default::default()
+        }));
+        assert_eq!(
+            estimate_order_gas(&hooked),
+            USER_ORDER_GAS + HOOK_CALL_GAS + 10 * HOOK_PAYLOAD_GAS_PER_BYTE
+        );
+
+        assert_eq!(estimate_order_gas(&TopOfBlockOrder::default()), TOB_ORDER_GAS);
+    }
+
+    #[test]
+    fn charges_gas_in_the_token() {
+        let gas_price_wei = 10_000_000_000;
+        let ray = |units: u128| Ray::from(U256::from(units));
+        // the token is ETH itself
+        let weth_per_wei = ray(10u128.pow(27));
+        assert_eq!(gas_charge(USER_ORDER_GAS, gas_price_wei, weth_per_wei), 450_000_000_000_000);
+        // a 6 decimal token at 2000 per ETH
+        let usdc_per_wei = ray(2 * 10u128.pow(18));
+        assert_eq!(gas_charge(USER_ORDER_GAS, gas_price_wei, usdc_per_wei), 900_000);
+        assert_eq!(gas_charge(0, gas_price_wei, usdc_per_wei), 0);
+    }
+
+    #[test]
+    fn estimates_gas_in_wei_and_the_input_token() {
+        let gas_price_wei = 10_000_000_000;
+        let order = TopOfBlockOrder::default();
+        let usdc_per_wei = Ray::from(U256::from(2 * 10u128.pow(18)));
+
+        let estimate = OrderGasEstimate::new(&order, gas_price_wei, Some(usdc_per_wei));
+        assert_eq!(estimate.gas_units, TOB_ORDER_GAS);
+        assert_eq!(estimate.gas_wei, TOB_ORDER_GAS * gas_price_wei);
+        assert_eq!(estimate.gas_in_token, 1_200_000);
+
+        // tokens that can't be priced aren't charged
+        let estimate = OrderGasEstimate::new(&order, gas_price_wei, None);
+        assert_eq!(estimate.gas_wei, TOB_ORDER_GAS * gas_price_wei);
+        assert_eq!(estimate.gas_in_token, 0);
+    }
+}
@@ -1,26 +1,44 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc
+    }
+};
 
-use account::UserAccountProcessor;
-use alloy::primitives::{Address, B256, U256};
+use account::{UserAccountProcessor, UserAccountVerificationError};
+use alloy::primitives::{Address, B256, I256, U256};
+use amm_swap::{AmmSwap, AmmSwapError};
+use angstrom_errors::ValidationError;
 use angstrom_types::{
-    primitive::NewInitializedPool,
-    sol_bindings::{ext::RawPoolOrder, grouped_orders::AllOrders}
+    matching::{uniswap::PoolSnapshot, Ray, SqrtPriceX96},
+    primitive::{NewInitializedPool, PoolId},
+    sol_bindings::{
+        ext::RawPoolOrder,
+        grouped_orders::{AllOrders, OrderWithStorageData}
+    }
 };
-use db_state_utils::StateFetchUtils;
+use config::{DataFetcherConfig, TokenSlots, ValidationConfig, DEFAULT_GAS_PRICE_GWEI};
+use db_state_utils::{StateFetchUtils, TokenSlotError};
 use futures::{Stream, StreamExt};
+use gas::{gas_charge, OrderGasEstimate};
 use matching_engine::cfmm::uniswap::{
     pool_manager::UniswapPoolManager, pool_providers::PoolManagerProvider, tob::calculate_reward
 };
 use parking_lot::RwLock;
 use pools::PoolsTracker;
+use token_pricing::{PairsWithPrice, TokenPriceGenerator};
 
-use super::{OrderValidation, OrderValidationResults};
+use super::{stages::ValidationStages, OrderValidation, OrderValidationResults};
 use crate::common::lru_db::{BlockStateProviderFactory, RevmLRU};
 
 pub mod account;
+pub mod amm_swap;
 pub mod config;
 pub mod db_state_utils;
+pub mod gas;
 pub mod pools;
+pub mod token_pricing;
 
 type HookOverrides = HashMap<Address, HashMap<U256, U256>>;
 
@@ -37,7 +55,12 @@ pub struct StateValidation<Pools, Fetch, Provider> {
     /// tracks all info about the current angstrom pool state.
     pool_tacker:          Arc<RwLock<Pools>>,
     /// keeps up-to-date with the on-chain pool
-    pool_manager:         Arc<UniswapPoolManager<Provider>>
+    pool_manager:         Arc<UniswapPoolManager<Provider>>,
+    /// checks run on top of the account checks
+    stages:               ValidationStages,
+    /// prices the gas of orders in their input token
+    token_prices:         Arc<RwLock<TokenPriceGenerator>>,
+    gas_price_gwei:       Arc<AtomicU64>
 }
 
 impl<Pools, Fetch, Provider> Clone for StateValidation<Pools, Fetch, Provider> {
@@ -45,7 +68,10 @@ impl<Pools, Fetch, Provider> Clone for StateValidation<Pools, Fetch, Provider> {
         Self {
             user_account_tracker: Arc::clone(&self.user_account_tracker),
             pool_tacker:          Arc::clone(&self.pool_tacker),
-            pool_manager:         Arc::clone(&self.pool_manager)
+            pool_manager:         Arc::clone(&self.pool_manager),
+            stages:               self.stages.clone(),
+            token_prices:         Arc::clone(&self.token_prices),
+            gas_price_gwei:       Arc::clone(&self.gas_price_gwei)
         }
     }
 }
@@ -61,10 +87,30 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils, Provider: PoolManagerProvider
         Self {
             pool_tacker:          Arc::new(RwLock::new(pools)),
             user_account_tracker: Arc::new(user_account_tracker),
-            pool_manager:         Arc::new(pool_manager)
+            pool_manager:         Arc::new(pool_manager),
+            stages:               ValidationStages::default(),
+            token_prices:         Default::default(),
+            gas_price_gwei:       Arc::new(AtomicU64::new(DEFAULT_GAS_PRICE_GWEI))
         }
     }
 
+    pub fn with_stages(mut self, stages: ValidationStages) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    /// Charges the gas of orders at `gas_price_gwei`, converted into their
+    /// input token through `token_prices`.
+    pub fn with_gas_pricing(
+        mut self,
+        token_prices: TokenPriceGenerator,
+        gas_price_gwei: u64
+    ) -> Self {
+        self.token_prices = Arc::new(RwLock::new(token_prices));
+        self.gas_price_gwei = Arc::new(AtomicU64::new(gas_price_gwei));
+        self
+    }
+
     pub fn new_block(
         &self,
         block_number: u64,
@@ -75,6 +121,18 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils, Provider: PoolManagerProvider
             .prepare_for_new_block(address_changes, completed_orders)
     }
 
+    pub fn chain_reorg(&self, block_number: u64, users: Vec<Address>) -> Vec<B256> {
+        self.user_account_tracker.reorg(block_number, users)
+    }
+
+    pub fn expire_orders(&self, orders: Vec<B256>) {
+        self.user_account_tracker.remove_orders(&orders)
+    }
+
+    pub fn register_token_slots(&self, slots: TokenSlots) -> Result<(), TokenSlotError> {
+        self.user_account_tracker.register_token_slots(slots)
+    }
+
     fn handle_regular_order<O: RawPoolOrder + Into<AllOrders>>(
         &self,
         order: O,
@@ -82,20 +140,88 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils, Provider: PoolManagerProvider
         is_limit: bool
     ) -> OrderValidationResults {
         let order_hash = order.order_hash();
-        if !order.is_valid_signature() {
-            return OrderValidationResults::Invalid(order_hash)
+        if let Err(e) = self.stages.validate_order(&order.clone().into()) {
+            tracing::trace!(?order_hash, %e);
+            return OrderValidationResults::Invalid(order_hash, e.into())
         }
 
-        let Some(pool_info) = self.pool_tacker.read().fetch_pool_info_for_order(&order) else {
-            return OrderValidationResults::Invalid(order_hash)
+        let pool_info = {
+            let pools = self.pool_tacker.read();
+            if pools.is_dust(&order, &self.token_prices.read()) {
+                return OrderValidationResults::Invalid(order_hash, ValidationError::Dust)
+            }
+            pools.fetch_pool_info_for_order(&order)
+        };
+        let Some(pool_info) = pool_info else {
+            return OrderValidationResults::Invalid(order_hash, ValidationError::UnknownPool)
         };
 
-        self.user_account_tracker
+        let verified = match self
+            .user_account_tracker
             .verify_order::<O>(order, pool_info, block, is_limit)
-            .map(|o: _| {
-                OrderValidationResults::Valid(o.try_map_inner(|inner| Ok(inner.into())).unwrap())
-            })
-            .unwrap_or_else(|_| OrderValidationResults::Invalid(order_hash))
+        {
+            Ok(verified) => verified,
+            Err(e @ UserAccountVerificationError::ReplacementUnderpriced { .. }) => {
+                tracing::trace!(?order_hash, %e);
+                return OrderValidationResults::Rejected(order_hash, e.into())
+            }
+            Err(e) => {
+                tracing::trace!(?order_hash, %e);
+                return OrderValidationResults::Invalid(order_hash, e.into())
+            }
+        };
+
+        let mut verified: OrderWithStorageData<AllOrders> =
+            verified.try_map_inner(|inner| Ok(inner.into())).unwrap();
+        verified.priority_data.gas =
+            self.charge_gas(verified.token_in(), verified.priority_data.gas_units);
+        if let Err(e) = self.stages.validate_state(&verified) {
+            tracing::trace!(?order_hash, %e);
+            return OrderValidationResults::Invalid(order_hash, e.into())
+        }
+        OrderValidationResults::Valid(verified)
+    }
+
+    /// What `gas_units` cost in `token`. Tokens we can't convert into aren't
+    /// charged.
+    fn charge_gas(&self, token: Address, gas_units: u128) -> u128 {
+        self.eth_price(token)
+            .map(|eth_price| gas_charge(gas_units, self.gas_price_wei(), eth_price))
+            .unwrap_or_default()
+    }
+
+    /// What validation would charge `order` for gas, without validating it.
+    pub fn estimate_order_gas(&self, order: &AllOrders) -> OrderGasEstimate {
+        OrderGasEstimate::new(order, self.gas_price_wei(), self.eth_price(order.token_in()))
+    }
+
+    /// The amount of `token` one unit of ETH is worth.
+    fn eth_price(&self, token: Address) -> Option<Ray> {
+        self.token_prices
+            .read()
+            .get_eth_conversion_price(token)
+            .inspect_err(|e| tracing::trace!(?token, %e, "can't price gas in the token"))
+            .ok()
+    }
+
+    fn gas_price_wei(&self) -> u128 {
+        self.gas_price_gwei.load(Ordering::Relaxed) as u128 * 1_000_000_000
+    }
+
+    /// Moves the gas prices to the block with the current price of every
+    /// synced pool. Pools still syncing to the block are read at their last
+    /// price.
+    pub async fn update_token_prices(&self, block_number: u64) {
+        let mut updates = Vec::new();
+        for address in self.pool_manager.pool_addresses() {
+            if let Some(pool) = self.pool_manager.pool(&address).await {
+                updates.push(PairsWithPrice::from_pool(&pool, block_number));
+            }
+        }
+
+        self.token_prices
+            .write()
+            .on_new_block(block_number, updates);
     }
 
     pub fn validate_state_of_regular_order(&self, order: OrderValidation, block: u64) {
@@ -104,6 +230,11 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils, Provider: PoolManagerProvider
                 let results = self.handle_regular_order(order, block, true);
                 let _ = tx.send(results);
             }
+            OrderValidation::LimitComposable(tx, order, origin) => {
+                // TODO: simulate the hooks once hook simulation is implemented
+                let results = self.handle_regular_order(order, block, true);
+                let _ = tx.send(results);
+            }
             OrderValidation::Searcher(tx, order, origin) => {
                 let mut results = self.handle_regular_order(order, block, false);
                 if let OrderValidationResults::Valid(ref mut order_with_storage) = results {
@@ -124,11 +255,69 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils, Provider: PoolManagerProvider
 
                 let _ = tx.send(results);
             }
-            _ => unreachable!()
         }
     }
 
+    /// Simulates swapping `amount_in` of `token_in` for `token_out` against
+    /// the synced pool of the pair, without changing the pool.
+    pub async fn simulate_amm_swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256
+    ) -> Result<AmmSwap, AmmSwapError> {
+        let pool_id = self
+            .pool_tacker
+            .read()
+            .pool_id(token_in, token_out)
+            .ok_or(AmmSwapError::UnknownPool(token_in, token_out))?;
+        let amount_in = I256::try_from(amount_in).map_err(|_| AmmSwapError::AmountTooLarge)?;
+        let pool_address = Address::from_slice(&pool_id[..20]);
+        let pool = self
+            .pool_manager
+            .pool(&pool_address)
+            .await
+            .ok_or(AmmSwapError::UntrackedPool(pool_id))?;
+
+        let (amount0, amount1) = pool
+            .simulate_swap(token_in, amount_in, None)
+            .map_err(|e| AmmSwapError::Simulation(e.to_string()))?;
+        let amount_out = if token_in < token_out { amount1 } else { amount0 };
+
+        Ok(AmmSwap {
+            pool_id,
+            spot_price: Ray::from(SqrtPriceX96::from(pool.sqrt_price)),
+            amount_out: amount_out.unsigned_abs()
+        })
+    }
+
+    /// Snapshots the synced pool with the given id.
+    pub async fn amm_snapshot(&self, pool_id: PoolId) -> Result<PoolSnapshot, AmmSwapError> {
+        let pool_address = Address::from_slice(&pool_id[..20]);
+        let pool = self
+            .pool_manager
+            .pool(&pool_address)
+            .await
+            .ok_or(AmmSwapError::UntrackedPool(pool_id))?;
+
+        pool.fetch_pool_snapshot()
+            .map_err(|e| AmmSwapError::Snapshot(e.to_string()))
+    }
+
     pub fn index_new_pool(&mut self, pool: NewInitializedPool) {
         self.pool_tacker.write().index_new_pool(pool);
     }
+
+    /// Picks up the pools, dust thresholds, gas pricing and token slots of a
+    /// reloaded config, see [`PoolsTracker::reload_pools`] for dropped pools.
+    pub fn reload_config(&self, validation: ValidationConfig, data_fetcher: DataFetcherConfig) {
+        self.gas_price_gwei
+            .store(validation.gas.gas_price_gwei, Ordering::Relaxed);
+        let mut token_prices = self.token_prices.write();
+        *token_prices = std::mem::take(&mut *token_prices).with_max_hops(validation.gas.max_hops);
+        drop(token_prices);
+
+        self.pool_tacker.write().reload_pools(&validation);
+        self.user_account_tracker.reload_token_slots(data_fetcher);
+    }
 }
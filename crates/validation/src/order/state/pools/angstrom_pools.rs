@@ -70,4 +70,10 @@ impl AngstromPools {
         self.key_to_id.insert(key, id);
         self.id_to_key.insert(id, key);
     }
+
+    pub fn remove_pool(&mut self, id: PoolId) {
+        if let Some((_, key)) = self.id_to_key.remove(&id) {
+            self.key_to_id.remove(&key);
+        }
+    }
 }
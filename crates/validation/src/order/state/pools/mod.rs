@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use alloy::primitives::Address;
 use angstrom_pools::AngstromPools;
 use angstrom_types::{
@@ -6,7 +8,10 @@ use angstrom_types::{
 };
 use dashmap::DashMap;
 
-use super::config::ValidationConfig;
+use super::{
+    config::{DustConfig, ValidationConfig},
+    token_pricing::TokenPriceGenerator
+};
 
 pub mod angstrom_pools;
 
@@ -16,6 +21,19 @@ pub trait PoolsTracker: Send + Unpin {
 
     /// indexes a new pool into the tracker
     fn index_new_pool(&mut self, pool: NewInitializedPool);
+
+    /// Tracks the pools of a reloaded config, the pools dropped from it are
+    /// no longer tracked. Orders resting in them stay until they fill or
+    /// expire, new ones are rejected.
+    fn reload_pools(&mut self, config: &ValidationConfig);
+
+    /// The pool of the token pair, in either order
+    fn pool_id(&self, token_a: Address, token_b: Address) -> Option<PoolId>;
+
+    /// whether the order is too small to be worth including at `prices`
+    fn is_dust<O: RawPoolOrder>(&self, _order: &O, _prices: &TokenPriceGenerator) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -29,7 +47,11 @@ pub struct UserOrderPoolInfo {
 /// keeps track of all valid pools and the mappings of asset id to pool id
 pub struct AngstromPoolsTracker {
     /// TODO: we can most likely flatten this but will circle back
-    pub pools: AngstromPools
+    pub pools:    AngstromPools,
+    /// min usd amount in per token
+    dust:         DustConfig,
+    /// pools that came from the config file
+    config_pools: HashSet<PoolId>
 }
 
 impl AngstromPoolsTracker {
@@ -41,7 +63,11 @@ impl AngstromPoolsTracker {
             .collect::<DashMap<_, _>>();
         let angstrom_pools = AngstromPools::new(pools);
 
-        Self { pools: angstrom_pools }
+        Self {
+            pools:        angstrom_pools,
+            dust:         config.dust,
+            config_pools: config.pools.iter().map(|pool| pool.pool_id).collect()
+        }
     }
 
     /// Get the token addresses for a pool specified by Uniswap PoolId.  By
@@ -65,9 +91,38 @@ impl PoolsTracker for AngstromPoolsTracker {
     fn index_new_pool(&mut self, pool: NewInitializedPool) {
         self.pools.new_pool(pool);
     }
+
+    fn reload_pools(&mut self, config: &ValidationConfig) {
+        let pools = config
+            .pools
+            .iter()
+            .map(|pool| pool.pool_id)
+            .collect::<HashSet<_>>();
+        for pool_id in self.config_pools.difference(&pools) {
+            tracing::info!(?pool_id, "pool dropped from the config, no longer tracked");
+            self.pools.remove_pool(*pool_id);
+        }
+        for pool in &config.pools {
+            self.pools.new_pool(NewInitializedPool {
+                currency_in:  pool.token0,
+                currency_out: pool.token1,
+                id:           pool.pool_id
+            });
+        }
+        self.dust = config.dust.clone();
+        self.config_pools = pools;
+    }
+
+    fn pool_id(&self, token_a: Address, token_b: Address) -> Option<PoolId> {
+        self.pools.get_poolid(token_a, token_b)
+    }
+
+    fn is_dust<O: RawPoolOrder>(&self, order: &O, prices: &TokenPriceGenerator) -> bool {
+        order.amount_in() < self.dust.threshold(order.token_in(), prices)
+    }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod pool_tracker_mock {
     use alloy::primitives::Address;
     use angstrom_types::primitive::PoolId;
@@ -107,5 +162,61 @@ pub mod pool_tracker_mock {
             self.pools
                 .insert((pool.currency_in, pool.currency_out), pool.id);
         }
+
+        fn reload_pools(&mut self, config: &ValidationConfig) {
+            self.pools.clear();
+            for pool in &config.pools {
+                self.add_pool(pool.token0, pool.token1, pool.pool_id);
+            }
+        }
+
+        fn pool_id(&self, token_a: Address, token_b: Address) -> Option<PoolId> {
+            self.pools.get(&(token_a, token_b)).map(|pool_id| *pool_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::state::config::{
+        listing::ListingPolicyConfig, DustConfig, GasPricingConfig, PoolConfig
+    };
+
+    fn config(pools: &[(u8, u8)]) -> ValidationConfig {
+        ValidationConfig {
+            pools:                   pools
+                .iter()
+                .map(|&(token0, token1)| PoolConfig {
+                    token0:      Address::with_last_byte(token0),
+                    token1:      Address::with_last_byte(token1),
+                    pool_id:     PoolId::with_last_byte(token0 * 16 + token1),
+                    store_index: 0
+                })
+                .collect(),
+            max_validation_per_user: 1,
+            dust:                    DustConfig::default(),
+            listing:                 ListingPolicyConfig::default(),
+            gas:                     GasPricingConfig::default()
+        }
+    }
+
+    #[test]
+    fn reloading_untracks_the_removed_pools() {
+        let mut tracker = AngstromPoolsTracker::new(config(&[(1, 2), (3, 4)]));
+        // initialized on chain, not part of the config
+        tracker.index_new_pool(NewInitializedPool {
+            currency_in:  Address::with_last_byte(5),
+            currency_out: Address::with_last_byte(6),
+            id:           PoolId::with_last_byte(0x56)
+        });
+
+        tracker.reload_pools(&config(&[(1, 2), (7, 8)]));
+        let pool = |a, b| tracker.pool_id(Address::with_last_byte(a), Address::with_last_byte(b));
+        assert_eq!(pool(1, 2), Some(PoolId::with_last_byte(0x12)));
+        assert_eq!(pool(3, 4), None);
+        assert_eq!(tracker.get_pool_addresses(PoolId::with_last_byte(0x34)), None);
+        assert_eq!(pool(5, 6), Some(PoolId::with_last_byte(0x56)));
+        assert_eq!(pool(8, 7), Some(PoolId::with_last_byte(0x78)));
     }
 }
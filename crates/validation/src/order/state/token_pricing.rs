@@ -0,0 +1,318 @@
+//! Prices gas in the tokens of our pools. Prices are averaged over the last
+//! few blocks so that moving a pool for a single block barely changes how much
+//! gas users get charged.
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    sync::Arc
+};
+
+use alloy::{
+    network::Network,
+    primitives::{address, Address, U256},
+    providers::Provider,
+    transports::Transport
+};
+use angstrom_types::matching::{Ray, SqrtPriceX96};
+use matching_engine::cfmm::uniswap::{
+    pool::EnhancedUniswapV3Pool, pool_manager::UniswapPoolManager,
+    pool_providers::PoolManagerProvider
+};
+use thiserror::Error;
+
+pub const WETH_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+
+/// number of blocks the prices are averaged over
+const BLOCKS_TO_AVG_PRICE: u64 = 5;
+/// max number of pools to convert through when a token has no WETH pool
+pub const DEFAULT_MAX_HOPS: usize = 3;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum TokenPricingError {
+    #[error("no route from {token} to WETH through at most {max_hops} pools")]
+    NoRoute { token: Address, max_hops: usize },
+    #[error("pool of {token0} and {token1} has no price")]
+    NoPrice { token0: Address, token1: Address }
+}
+
+/// The price of a pair at a block, in `token1` per `token0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairsWithPrice {
+    pub token0:         Address,
+    pub token1:         Address,
+    pub block_num:      u64,
+    pub price_1_over_0: Ray
+}
+
+impl PairsWithPrice {
+    pub fn from_pool(pool: &EnhancedUniswapV3Pool, block_num: u64) -> Self {
+        Self {
+            token0: pool.token_a,
+            token1: pool.token_b,
+            block_num,
+            price_1_over_0: Ray::from(SqrtPriceX96::from(pool.sqrt_price))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenPriceGenerator {
+    /// the prices of each pair over the last blocks, oldest first
+    prev_prices: HashMap<(Address, Address), VecDeque<PairsWithPrice>>,
+    cur_block:   u64,
+    /// max number of pools a conversion can go through
+    max_hops:    usize
+}
+
+impl Default for TokenPriceGenerator {
+    fn default() -> Self {
+        Self { prev_prices: HashMap::new(), cur_block: 0, max_hops: DEFAULT_MAX_HOPS }
+    }
+}
+
+impl TokenPriceGenerator {
+    /// Loads the prices of every pool tracked by the pool manager over the
+    /// blocks we average over. The manager is expected to be synced to
+    /// `current_block`, older blocks are loaded from the provider.
+    pub async fn new<P, T, N, Loader>(
+        provider: Arc<P>,
+        current_block: u64,
+        uni: &UniswapPoolManager<Loader>
+    ) -> eyre::Result<Self>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+        Loader: PoolManagerProvider + Send + Sync + 'static
+    {
+        let first_block = current_block.saturating_sub(BLOCKS_TO_AVG_PRICE - 1);
+        let mut prev_prices = HashMap::new();
+
+        for address in uni.pool_addresses().collect::<Vec<_>>() {
+            let mut prices = VecDeque::with_capacity(BLOCKS_TO_AVG_PRICE as usize);
+            for block_num in first_block..current_block {
+                let mut pool = EnhancedUniswapV3Pool::new(address, 0);
+                pool.initialize(Some(block_num), provider.clone()).await?;
+                prices.push_back(PairsWithPrice::from_pool(&pool, block_num));
+            }
+
+            let Some(pool) = uni.pool(&address).await else { continue };
+            let current = PairsWithPrice::from_pool(&pool, current_block);
+            prices.push_back(current);
+            prev_prices.insert((current.token0, current.token1), prices);
+        }
+
+        Ok(Self { prev_prices, cur_block: current_block, ..Default::default() })
+    }
+
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// Moves the window of every pair to the new block. `updates` holds the
+    /// prices of the pools that changed in it, the others keep their last
+    /// price.
+    pub fn on_new_block(&mut self, block_num: u64, updates: Vec<PairsWithPrice>) {
+        self.cur_block = block_num;
+
+        let mut updates = updates
+            .into_iter()
+            .map(|update| ((update.token0, update.token1), update))
+            .collect::<HashMap<_, _>>();
+
+        for (pair, prices) in self.prev_prices.iter_mut() {
+            let next = updates.remove(pair).or_else(|| {
+                prices
+                    .back()
+                    .map(|last| PairsWithPrice { block_num, ..*last })
+            });
+            prices.extend(next);
+        }
+        // pairs that we are seeing for the first time
+        for (pair, update) in updates {
+            self.prev_prices.insert(pair, VecDeque::from([update]));
+        }
+
+        for prices in self.prev_prices.values_mut() {
+            while prices.len() > BLOCKS_TO_AVG_PRICE as usize {
+                prices.pop_front();
+            }
+        }
+    }
+
+    pub fn current_block(&self) -> u64 {
+        self.cur_block
+    }
+
+    /// The amount of `token_0` one unit of ETH is worth, averaged over the
+    /// last blocks. Multiplying a gas cost in wei by it gives the cost in
+    /// `token_0`. Tokens without a WETH pool are converted through the
+    /// fewest pools possible.
+    pub fn get_eth_conversion_price(&self, token_0: Address) -> Result<Ray, TokenPricingError> {
+        let one = Ray::from(U256::from(10).pow(U256::from(27)));
+        self.route(token_0)?.windows(2).try_fold(one, |price, hop| {
+            let rate = self.rate(hop[0], hop[1])?;
+            Ok(Ray::from(price.mul_quantity(*rate)))
+        })
+    }
+
+    /// The tokens to convert through to get from WETH to `token`, both ends
+    /// included.
+    fn route(&self, token: Address) -> Result<Vec<Address>, TokenPricingError> {
+        if token == WETH_ADDRESS {
+            return Ok(vec![token])
+        }
+
+        // sorted so that equally long routes are picked deterministically
+        let mut graph: HashMap<Address, BTreeSet<Address>> = HashMap::new();
+        for (token0, token1) in self.prev_prices.keys() {
+            graph.entry(*token0).or_default().insert(*token1);
+            graph.entry(*token1).or_default().insert(*token0);
+        }
+
+        // breadth first from WETH, remembering where we reached each token from
+        let mut reached_from = HashMap::from([(WETH_ADDRESS, WETH_ADDRESS)]);
+        let mut frontier = vec![WETH_ADDRESS];
+        for _ in 0..self.max_hops {
+            let mut next = Vec::new();
+            for from in frontier {
+                for &to in graph.get(&from).into_iter().flatten() {
+                    if reached_from.contains_key(&to) {
+                        continue
+                    }
+                    reached_from.insert(to, from);
+                    next.push(to);
+                }
+            }
+
+            if reached_from.contains_key(&token) {
+                let mut route = vec![token];
+                while *route.last().unwrap() != WETH_ADDRESS {
+                    route.push(reached_from[route.last().unwrap()]);
+                }
+                route.reverse();
+                return Ok(route)
+            }
+            frontier = next;
+        }
+
+        Err(TokenPricingError::NoRoute { token, max_hops: self.max_hops })
+    }
+
+    /// The amount of `to` one unit of `from` is worth.
+    fn rate(&self, from: Address, to: Address) -> Result<Ray, TokenPricingError> {
+        if let Some(price) = self.prev_prices.get(&(from, to)).and_then(Self::average) {
+            return Ok(price)
+        }
+
+        let no_price = TokenPricingError::NoPrice { token0: to, token1: from };
+        let price = self
+            .prev_prices
+            .get(&(to, from))
+            .and_then(Self::average)
+            .ok_or_else(|| no_price.clone())?;
+        if price.is_zero() {
+            return Err(no_price)
+        }
+        Ok(Ray::calc_price(*price, U256::from(10).pow(U256::from(27))))
+    }
+
+    fn average(prices: &VecDeque<PairsWithPrice>) -> Option<Ray> {
+        if prices.is_empty() {
+            return None
+        }
+        let sum = prices
+            .iter()
+            .fold(U256::ZERO, |sum, price| sum + *price.price_1_over_0);
+
+        Some(Ray::from(sum / U256::from(prices.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: Address = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+    const WBTC: Address = address!("2260fac5e5542a773aa44fbcbedc8b35f4e1c22b");
+    const DAI: Address = address!("6b175474e89094c44da98b954eedeac495271d0f");
+
+    fn price(token0: Address, token1: Address, block_num: u64, price: f64) -> PairsWithPrice {
+        PairsWithPrice { token0, token1, block_num, price_1_over_0: Ray::from(price) }
+    }
+
+    #[test]
+    fn keeps_a_window_per_pair() {
+        let mut generator = TokenPriceGenerator::default();
+        for block in 0..10 {
+            generator.on_new_block(block, vec![price(USDC, WETH_ADDRESS, block, block as f64)]);
+        }
+
+        let prices = &generator.prev_prices[&(USDC, WETH_ADDRESS)];
+        assert_eq!(prices.len(), BLOCKS_TO_AVG_PRICE as usize);
+        assert_eq!(prices.front().unwrap().block_num, 5);
+        assert_eq!(generator.current_block(), 9);
+    }
+
+    #[test]
+    fn carries_forward_pairs_without_updates() {
+        let mut generator = TokenPriceGenerator::default();
+        generator.on_new_block(1, vec![price(USDC, WETH_ADDRESS, 1, 0.5)]);
+        generator.on_new_block(2, vec![]);
+
+        let prices = &generator.prev_prices[&(USDC, WETH_ADDRESS)];
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices.back().unwrap().block_num, 2);
+        assert_eq!(prices.back().unwrap().price_1_over_0, Ray::from(0.5));
+    }
+
+    #[test]
+    fn converts_eth_to_token0() {
+        let mut generator = TokenPriceGenerator::default();
+        generator.on_new_block(
+            1,
+            vec![price(USDC, WETH_ADDRESS, 1, 0.5), price(WETH_ADDRESS, WBTC, 1, 2.0)]
+        );
+        generator.on_new_block(
+            2,
+            vec![price(USDC, WETH_ADDRESS, 2, 0.25), price(WETH_ADDRESS, WBTC, 2, 4.0)]
+        );
+
+        let conversion = |token| generator.get_eth_conversion_price(token).unwrap().as_f64();
+        assert_eq!(conversion(WETH_ADDRESS), 1.0);
+        // WBTC is token1 of its pool so the average is used as is
+        assert!((conversion(WBTC) - 3.0).abs() < 1e-9);
+        // WETH per USDC averages to 0.375, so an ETH is worth 2.666... USDC
+        assert!((conversion(USDC) - 1.0 / 0.375).abs() < 1e-9);
+        assert_eq!(
+            generator.get_eth_conversion_price(Address::ZERO),
+            Err(TokenPricingError::NoRoute { token: Address::ZERO, max_hops: DEFAULT_MAX_HOPS })
+        );
+    }
+
+    #[test]
+    fn converts_through_intermediate_pools() {
+        let mut generator = TokenPriceGenerator::default();
+        // 1 WETH = 2 WBTC, 1 WBTC = 10 USDC, 1 USDC = 4 DAI
+        generator.on_new_block(
+            1,
+            vec![
+                price(WETH_ADDRESS, WBTC, 1, 2.0),
+                price(USDC, WBTC, 1, 0.1),
+                price(DAI, USDC, 1, 0.25),
+            ]
+        );
+
+        let usdc = generator.get_eth_conversion_price(USDC).unwrap().as_f64();
+        assert!((usdc - 20.0).abs() < 1e-9);
+        let dai = generator.get_eth_conversion_price(DAI).unwrap().as_f64();
+        assert!((dai - 80.0).abs() < 1e-9);
+
+        let generator = generator.with_max_hops(2);
+        assert!(generator.get_eth_conversion_price(USDC).is_ok());
+        assert_eq!(
+            generator.get_eth_conversion_price(DAI),
+            Err(TokenPricingError::NoRoute { token: DAI, max_hops: 2 })
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use std::task::Poll;
 
-use alloy::primitives::{Address, B256};
+use alloy::primitives::{Address, B256, U256};
+use angstrom_types::{
+    matching::uniswap::PoolSnapshot, primitive::PoolId, sol_bindings::grouped_orders::AllOrders
+};
 use futures_util::{Future, FutureExt};
 use matching_engine::cfmm::uniswap::pool_providers::PoolManagerProvider;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
@@ -9,7 +12,16 @@ use crate::{
     common::lru_db::BlockStateProviderFactory,
     order::{
         order_validator::OrderValidator,
-        state::{db_state_utils::StateFetchUtils, pools::PoolsTracker},
+        state::{
+            amm_swap::{AmmSwap, AmmSwapError},
+            config::{
+                listing::{ListingDecision, ListingError},
+                DataFetcherConfig, TokenSlots, ValidationConfig
+            },
+            db_state_utils::{StateFetchUtils, TokenSlotError},
+            gas::{GasEstimateError, OrderGasEstimate},
+            pools::PoolsTracker
+        },
         OrderValidationRequest, OrderValidationResults
     }
 };
@@ -21,15 +33,118 @@ pub enum ValidationRequest {
         block_number: u64,
         orders:       Vec<B256>,
         addresses:    Vec<Address>
+    },
+    /// users whose account state may differ on the new canonical chain
+    ChainReorg {
+        sender:       tokio::sync::oneshot::Sender<Vec<B256>>,
+        block_number: u64,
+        addresses:    Vec<Address>
+    },
+    /// standing orders the pool evicted between blocks
+    ExpiredOrders {
+        orders: Vec<B256>
+    },
+    RegisterTokenSlots {
+        slots:  TokenSlots,
+        sender: tokio::sync::oneshot::Sender<Result<(), TokenSlotError>>
+    },
+    /// the config file was changed on disk, `validation` only has the pools
+    /// that passed the listing policy
+    ReloadConfig {
+        validation:   ValidationConfig,
+        data_fetcher: DataFetcherConfig,
+        listings:     Vec<ListingDecision>
+    },
+    ListingDecisions {
+        sender: tokio::sync::oneshot::Sender<Vec<ListingDecision>>
+    },
+    SimulateAmmSwap {
+        token_in:  Address,
+        token_out: Address,
+        amount_in: U256,
+        sender:    tokio::sync::oneshot::Sender<Result<AmmSwap, AmmSwapError>>
+    },
+    AmmSnapshot {
+        pool_id: PoolId,
+        sender:  tokio::sync::oneshot::Sender<Result<PoolSnapshot, AmmSwapError>>
+    },
+    EstimateOrderGas {
+        order:  AllOrders,
+        sender: tokio::sync::oneshot::Sender<OrderGasEstimate>
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ValidationClient(pub UnboundedSender<ValidationRequest>);
 
+impl ValidationClient {
+    /// Registers the balance and approval slots of a token with the running
+    /// validator, so that orders for it can be validated without a restart.
+    pub async fn register_token_slots(&self, slots: TokenSlots) -> Result<(), TokenSlotError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.0
+            .send(ValidationRequest::RegisterTokenSlots { slots, sender: tx })
+            .map_err(|_| TokenSlotError::ValidatorStopped)?;
+
+        rx.await.map_err(|_| TokenSlotError::ValidatorStopped)?
+    }
+
+    /// The listing policy decisions on the pools of the current config.
+    pub async fn listing_decisions(&self) -> Result<Vec<ListingDecision>, ListingError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.0
+            .send(ValidationRequest::ListingDecisions { sender: tx })
+            .map_err(|_| ListingError::ValidatorStopped)?;
+
+        rx.await.map_err(|_| ListingError::ValidatorStopped)
+    }
+
+    /// What swapping `amount_in` of `token_in` against the pool of the pair
+    /// would return, along with the current price of the pool.
+    pub async fn simulate_amm_swap(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256
+    ) -> Result<AmmSwap, AmmSwapError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.0
+            .send(ValidationRequest::SimulateAmmSwap { token_in, token_out, amount_in, sender: tx })
+            .map_err(|_| AmmSwapError::ValidatorStopped)?;
+
+        rx.await.map_err(|_| AmmSwapError::ValidatorStopped)?
+    }
+
+    /// The liquidity of the pool over the ticks it has loaded, along with its
+    /// current price.
+    pub async fn amm_snapshot(&self, pool_id: PoolId) -> Result<PoolSnapshot, AmmSwapError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.0
+            .send(ValidationRequest::AmmSnapshot { pool_id, sender: tx })
+            .map_err(|_| AmmSwapError::ValidatorStopped)?;
+
+        rx.await.map_err(|_| AmmSwapError::ValidatorStopped)?
+    }
+
+    /// The gas validation would charge the order, without validating it or
+    /// adding it to the pool.
+    pub async fn estimate_order_gas(
+        &self,
+        order: AllOrders
+    ) -> Result<OrderGasEstimate, GasEstimateError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.0
+            .send(ValidationRequest::EstimateOrderGas { order, sender: tx })
+            .map_err(|_| GasEstimateError::ValidatorStopped)?;
+
+        rx.await.map_err(|_| GasEstimateError::ValidatorStopped)
+    }
+}
+
 pub struct Validator<DB, Pools, Fetch, Provider> {
     rx:              UnboundedReceiver<ValidationRequest>,
-    order_validator: OrderValidator<DB, Pools, Fetch, Provider>
+    order_validator: OrderValidator<DB, Pools, Fetch, Provider>,
+    listings:        Vec<ListingDecision>
 }
 
 impl<DB, Pools, Fetch, Provider> Validator<DB, Pools, Fetch, Provider>
@@ -43,7 +158,13 @@ where
         rx: UnboundedReceiver<ValidationRequest>,
         order_validator: OrderValidator<DB, Pools, Fetch, Provider>
     ) -> Self {
-        Self { order_validator, rx }
+        Self { order_validator, rx, listings: vec![] }
+    }
+
+    /// The listing policy decisions on the pools the validator starts with.
+    pub fn with_listings(mut self, listings: Vec<ListingDecision>) -> Self {
+        self.listings = listings;
+        self
     }
 
     fn on_new_validation_request(&mut self, req: ValidationRequest) {
@@ -56,6 +177,32 @@ where
                     .send(OrderValidationResults::TransitionedToBlock)
                     .unwrap();
             }
+            ValidationRequest::ChainReorg { sender, block_number, addresses } => {
+                let _ = sender.send(self.order_validator.on_chain_reorg(block_number, addresses));
+            }
+            ValidationRequest::ExpiredOrders { orders } => {
+                self.order_validator.on_expired_orders(orders)
+            }
+            ValidationRequest::RegisterTokenSlots { slots, sender } => {
+                let _ = sender.send(self.order_validator.register_token_slots(slots));
+            }
+            ValidationRequest::ReloadConfig { validation, data_fetcher, listings } => {
+                tracing::info!(pools = validation.pools.len(), "reloaded validation config");
+                self.order_validator.reload_config(validation, data_fetcher);
+                self.listings = listings;
+            }
+            ValidationRequest::ListingDecisions { sender } => {
+                let _ = sender.send(self.listings.clone());
+            }
+            ValidationRequest::SimulateAmmSwap { token_in, token_out, amount_in, sender } => self
+                .order_validator
+                .simulate_amm_swap(token_in, token_out, amount_in, sender),
+            ValidationRequest::AmmSnapshot { pool_id, sender } => {
+                self.order_validator.amm_snapshot(pool_id, sender)
+            }
+            ValidationRequest::EstimateOrderGas { order, sender } => {
+                let _ = sender.send(self.order_validator.estimate_order_gas(&order));
+            }
         }
     }
 }
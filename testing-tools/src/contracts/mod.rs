@@ -13,6 +13,7 @@ use crate::anvil_state_provider::utils::AnvilWalletRpc;
 pub mod anvil;
 pub mod deploy;
 pub mod environment;
+pub mod v4_swap_diff;
 //mod reward;
 //pub use reward::RewardTestEnv;
 
@@ -0,0 +1,205 @@
+//! Runs the same swaps through [`EnhancedUniswapV3Pool::simulate_swap`] and
+//! through the uniswap v4 `PoolManager` bytecode under revm, so that drift
+//! between our swap math and the contract's shows up in tests.
+use alloy::{
+    primitives::{
+        address,
+        aliases::{I24, U24},
+        Address, Bytes, I256, U160, U256
+    },
+    sol,
+    sol_types::{SolCall, SolConstructor}
+};
+use angstrom_types::{
+    contract_bindings::{pool_gate::PoolGate, pool_manager::PoolManager},
+    sol_bindings::testnet::MockERC20
+};
+use matching_engine::cfmm::uniswap::pool::EnhancedUniswapV3Pool;
+use reth_revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{ExecutionResult, Output, TxKind},
+    Evm
+};
+use uniswap_v3_math::tick_math::{get_tick_at_sqrt_ratio, MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+
+sol! {
+    struct DiffPoolKey {
+        address currency0;
+        address currency1;
+        uint24 fee;
+        int24 tickSpacing;
+        address hooks;
+    }
+
+    function initialize(DiffPoolKey memory key, uint160 sqrtPriceX96) external returns (int24 tick);
+}
+
+const CALLER: Address = address!("00000000000000000000000000000000000d1ff0");
+const GAS_LIMIT: u64 = 30_000_000;
+/// Tick spacing of the pools `PoolGate` swaps against when none is set.
+const TICK_SPACING: i32 = 60;
+
+/// A hookless, fee free pool deployed on a `PoolManager` under revm, and the
+/// same pool as we track it.
+pub struct V4SwapDiff {
+    evm:       Evm<'static, (), CacheDB<EmptyDB>>,
+    pool_gate: Address,
+    token0:    Address,
+    token1:    Address,
+    pool:      EnhancedUniswapV3Pool
+}
+
+impl V4SwapDiff {
+    /// Deploys the pool manager, the pool gate and two tokens, and
+    /// initializes their pool at `sqrt_price_x96`.
+    pub fn new(sqrt_price_x96: U256) -> eyre::Result<Self> {
+        let mut evm = Evm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .build();
+        let pool_manager = deploy(&mut evm, PoolManager::BYTECODE.to_vec())?;
+        let pool_gate = deploy(
+            &mut evm,
+            [
+                PoolGate::BYTECODE.to_vec(),
+                PoolGate::constructorCall { uniV4: pool_manager }.abi_encode()
+            ]
+            .concat()
+        )?;
+        let token_a = deploy(&mut evm, MockERC20::BYTECODE.to_vec())?;
+        let token_b = deploy(&mut evm, MockERC20::BYTECODE.to_vec())?;
+        let (token0, token1) =
+            if token_a < token_b { (token_a, token_b) } else { (token_b, token_a) };
+
+        let key = DiffPoolKey {
+            currency0:   token0,
+            currency1:   token1,
+            fee:         U24::ZERO,
+            tickSpacing: I24::unchecked_from(TICK_SPACING),
+            hooks:       Address::ZERO
+        };
+        let sqrt_price: U160 = sqrt_price_x96.to();
+        call(
+            &mut evm,
+            pool_manager,
+            initializeCall { key, sqrtPriceX96: sqrt_price }.abi_encode()
+        )?;
+
+        let mut pool = EnhancedUniswapV3Pool::new(pool_manager, 0);
+        pool.token_a = token0;
+        pool.token_b = token1;
+        pool.fee = 0;
+        pool.tick_spacing = TICK_SPACING;
+        pool.sqrt_price = sqrt_price_x96;
+        pool.tick = get_tick_at_sqrt_ratio(sqrt_price_x96)?;
+
+        Ok(Self { evm, pool_gate, token0, token1, pool })
+    }
+
+    pub fn tokens(&self) -> (Address, Address) {
+        (self.token0, self.token1)
+    }
+
+    pub fn pool(&self) -> &EnhancedUniswapV3Pool {
+        &self.pool
+    }
+
+    /// Adds the position to both pools, the ticks have to be multiples of the
+    /// tick spacing.
+    pub fn add_liquidity(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128
+    ) -> eyre::Result<()> {
+        call(
+            &mut self.evm,
+            self.pool_gate,
+            PoolGate::addLiquidityCall {
+                asset0:    self.token0,
+                asset1:    self.token1,
+                tickLower: I24::unchecked_from(tick_lower),
+                tickUpper: I24::unchecked_from(tick_upper),
+                liquidity: U256::from(liquidity),
+                salt:      Default::default()
+            }
+            .abi_encode()
+        )?;
+        self.pool.add_liquidity(tick_lower, tick_upper, liquidity);
+
+        Ok(())
+    }
+
+    /// Swaps on both pools, returning the amounts of token0 and token1 the
+    /// pool received as `(contract, simulation)`. Positive amounts specify
+    /// the input, negative ones the output, as in
+    /// [`EnhancedUniswapV3Pool::simulate_swap`].
+    pub fn swap(
+        &mut self,
+        zero_for_one: bool,
+        amount_specified: I256
+    ) -> eyre::Result<((I256, I256), (I256, I256))> {
+        let (token_in, token_out, sqrt_price_limit) = if zero_for_one {
+            (self.token0, self.token1, MIN_SQRT_RATIO + U256::from(1))
+        } else {
+            (self.token1, self.token0, MAX_SQRT_RATIO - U256::from(1))
+        };
+
+        let output = call(
+            &mut self.evm,
+            self.pool_gate,
+            PoolGate::swapCall {
+                assetIn:           token_in,
+                assetOut:          token_out,
+                // v4 specifies the input with negative amounts
+                amountSpecified:   -amount_specified,
+                sqrtPriceLimitX96: sqrt_price_limit.to()
+            }
+            .abi_encode()
+        )?;
+        // the delta of the caller packs amount0 in the upper and amount1 in the
+        // lower 128 bits, negative for what it paid in
+        let delta = U256::from_be_slice(&output[..32]);
+        let amount0 = I256::try_from((delta >> 128).wrapping_to::<u128>() as i128)?;
+        let amount1 = I256::try_from(delta.wrapping_to::<u128>() as i128)?;
+        let contract = (-amount0, -amount1);
+
+        let simulation =
+            self.pool
+                .simulate_swap_mut(token_in, amount_specified, Some(sqrt_price_limit))?;
+
+        Ok((contract, simulation))
+    }
+}
+
+fn deploy(evm: &mut Evm<'static, (), CacheDB<EmptyDB>>, code: Vec<u8>) -> eyre::Result<Address> {
+    match transact(evm, TxKind::Create, code)? {
+        Output::Create(_, Some(address)) => Ok(address),
+        output => eyre::bail!("deployment returned no address: {output:?}")
+    }
+}
+
+fn call(
+    evm: &mut Evm<'static, (), CacheDB<EmptyDB>>,
+    to: Address,
+    data: Vec<u8>
+) -> eyre::Result<Bytes> {
+    Ok(transact(evm, TxKind::Call(to), data)?.into_data())
+}
+
+fn transact(
+    evm: &mut Evm<'static, (), CacheDB<EmptyDB>>,
+    transact_to: TxKind,
+    data: Vec<u8>
+) -> eyre::Result<Output> {
+    let tx = evm.tx_mut();
+    tx.caller = CALLER;
+    tx.transact_to = transact_to;
+    tx.data = data.into();
+    tx.gas_limit = GAS_LIMIT;
+
+    match evm.transact_commit()? {
+        ExecutionResult::Success { output, .. } => Ok(output),
+        ExecutionResult::Revert { output, .. } => eyre::bail!("reverted with {output}"),
+        ExecutionResult::Halt { reason, .. } => eyre::bail!("halted with {reason:?}")
+    }
+}
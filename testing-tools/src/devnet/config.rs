@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use alloy_primitives::{hex, Address};
+use angstrom_types::primitive::{PeerId, PoolId};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use reth_network_peers::pk2id;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+/// Ports the containers listen on inside of the docker network.
+pub const RPC_PORT: u16 = 8545;
+pub const P2P_PORT: u16 = 30303;
+
+/// Where the node data is mounted inside of the node containers.
+pub const DEVNET_MOUNT: &str = "/devnet";
+/// The working directory of the node containers. The node loads its pool
+/// config relative to it.
+pub const NODE_WORKDIR: &str = "/angstrom";
+
+#[derive(Debug, Clone)]
+pub struct DevnetPool {
+    pub token0:  Address,
+    pub token1:  Address,
+    pub pool_id: PoolId
+}
+
+#[derive(Debug, Clone)]
+pub struct DevnetConfig {
+    pub node_count:      usize,
+    pub angstrom_image:  String,
+    pub angstrom_tag:    String,
+    pub anvil_image:     String,
+    pub anvil_tag:       String,
+    pub chain_id:        u64,
+    pub block_time_secs: u64,
+    /// name of the docker network, also used to prefix the container names
+    pub network:         String,
+    pub pools:           Vec<DevnetPool>,
+    /// the angstrom contract the nodes build their bundles for
+    pub angstrom:        Address,
+    /// seed the node keys are generated from
+    pub seed:            u64,
+    pub extra_node_args: Vec<String>
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self {
+            node_count:      3,
+            angstrom_image:  "angstrom".to_string(),
+            angstrom_tag:    "latest".to_string(),
+            anvil_image:     "ghcr.io/foundry-rs/foundry".to_string(),
+            anvil_tag:       "latest".to_string(),
+            chain_id:        31337,
+            block_time_secs: 12,
+            network:         "angstrom-devnet".to_string(),
+            pools:           vec![],
+            angstrom:        Address::ZERO,
+            seed:            0,
+            extra_node_args: vec![]
+        }
+    }
+}
+
+impl DevnetConfig {
+    pub fn with_node_count(mut self, node_count: usize) -> Self {
+        self.node_count = node_count;
+        self
+    }
+
+    pub fn with_angstrom_image(mut self, image: impl Into<String>, tag: impl Into<String>) -> Self {
+        self.angstrom_image = image.into();
+        self.angstrom_tag = tag.into();
+        self
+    }
+
+    pub fn with_anvil_image(mut self, image: impl Into<String>, tag: impl Into<String>) -> Self {
+        self.anvil_image = image.into();
+        self.anvil_tag = tag.into();
+        self
+    }
+
+    pub fn with_block_time(mut self, block_time_secs: u64) -> Self {
+        self.block_time_secs = block_time_secs;
+        self
+    }
+
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = network.into();
+        self
+    }
+
+    pub fn with_pool(mut self, pool: DevnetPool) -> Self {
+        self.pools.push(pool);
+        self
+    }
+
+    pub fn with_angstrom(mut self, angstrom: Address) -> Self {
+        self.angstrom = angstrom;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_extra_node_args(mut self, args: Vec<String>) -> Self {
+        self.extra_node_args = args;
+        self
+    }
+
+    /// The keys of the nodes, the same seed always generates the same keys.
+    pub fn node_keys(&self) -> Vec<SecretKey> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        (0..self.node_count)
+            .map(|_| loop {
+                let mut bytes = [0u8; 32];
+                rng.fill_bytes(&mut bytes);
+                if let Ok(key) = SecretKey::from_slice(&bytes) {
+                    break key
+                }
+            })
+            .collect()
+    }
+
+    pub fn peer_id(key: &SecretKey) -> PeerId {
+        pk2id(&PublicKey::from_secret_key(&Secp256k1::new(), key))
+    }
+
+    pub fn anvil_name(&self) -> String {
+        format!("{}-anvil", self.network)
+    }
+
+    pub fn node_name(&self, node: usize) -> String {
+        format!("{}-node-{node}", self.network)
+    }
+
+    pub fn enode(&self, node: usize, key: &SecretKey) -> String {
+        format!("enode://{}@{}:{P2P_PORT}", Self::peer_id(key), self.node_name(node))
+    }
+
+    pub fn anvil_cmd(&self) -> String {
+        format!(
+            "anvil --host 0.0.0.0 --port {RPC_PORT} --chain-id {} --block-time {}",
+            self.chain_id, self.block_time_secs
+        )
+    }
+
+    /// The arguments of a node, which trusts every other node of the devnet.
+    pub fn node_args(&self, node: usize, keys: &[SecretKey]) -> Vec<String> {
+        let trusted_peers = keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != node)
+            .map(|(i, key)| self.enode(i, key))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut args = vec![
+            "node".to_string(),
+            "--secret-key-location".to_string(),
+            format!("{DEVNET_MOUNT}/node-{node}.key"),
+            "--angstrom-address".to_string(),
+            self.angstrom.to_string(),
+            "--validation-config".to_string(),
+            format!("{DEVNET_MOUNT}/state_config.toml"),
+            "--datadir".to_string(),
+            format!("/data/node-{node}"),
+            "--http".to_string(),
+            "--http.addr".to_string(),
+            "0.0.0.0".to_string(),
+            "--http.port".to_string(),
+            RPC_PORT.to_string(),
+            "--port".to_string(),
+            P2P_PORT.to_string(),
+            "--debug.rpc-consensus-ws".to_string(),
+            format!("ws://{}:{RPC_PORT}", self.anvil_name()),
+        ];
+        if !trusted_peers.is_empty() {
+            args.extend(["--trusted-peers".to_string(), trusted_peers]);
+        }
+        args.extend(self.extra_node_args.iter().cloned());
+
+        args
+    }
+
+    /// The pool config loaded by the nodes, in the format of
+    /// `validation::TOKEN_CONFIG_FILE`.
+    pub fn state_config(&self) -> String {
+        let mut config =
+            String::from("max_validation_per_user = 1\napprovals = []\nbalances = []\n");
+        // pools are deployed in order, so their store index is their position
+        for (store_index, pool) in self.pools.iter().enumerate() {
+            write!(
+                config,
+                "\n[[pools]]\ntoken0 = \"{}\"\ntoken1 = \"{}\"\npool_id = \"{}\"\nstore_index = \
+                 {store_index}\n",
+                pool.token0, pool.token1, pool.pool_id
+            )
+            .unwrap();
+        }
+
+        config
+    }
+
+    pub fn encode_key(key: &SecretKey) -> String {
+        hex::encode(key.secret_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use validation::order::state::config::{DataFetcherConfig, ValidationConfig};
+
+    use super::*;
+
+    #[test]
+    fn generates_reproducible_configs() {
+        let config = DevnetConfig::default().with_pool(DevnetPool {
+            token0:  Address::with_last_byte(1),
+            token1:  Address::with_last_byte(2),
+            pool_id: PoolId::with_last_byte(3)
+        });
+
+        let keys = config.node_keys();
+        assert_eq!(keys, config.node_keys());
+        assert_ne!(keys, config.clone().with_seed(1).node_keys());
+
+        let args = config.node_args(0, &keys);
+        assert!(args.contains(&[config.enode(1, &keys[1]), config.enode(2, &keys[2])].join(",")));
+
+        let state_config = config.state_config();
+        let validation: ValidationConfig = toml::from_str(&state_config).unwrap();
+        assert_eq!(validation.pools.len(), 1);
+        assert_eq!(validation.pools[0].pool_id, PoolId::with_last_byte(3));
+        toml::from_str::<DataFetcherConfig>(&state_config).unwrap();
+    }
+}
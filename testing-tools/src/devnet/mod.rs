@@ -0,0 +1,134 @@
+//! Launches a devnet of angstrom nodes on top of anvil, every one of them in
+//! its own container on a shared docker network. The nodes get generated keys
+//! and trust each other, so full network integration tests can run the same
+//! way on any machine that has docker.
+pub mod config;
+
+use std::path::Path;
+
+use angstrom_types::primitive::PeerId;
+use testcontainers::{
+    core::{IntoContainerPort, Mount, WaitFor},
+    runners::AsyncRunner,
+    ContainerAsync, GenericImage, ImageExt
+};
+
+use self::config::{DevnetConfig, DEVNET_MOUNT, NODE_WORKDIR, RPC_PORT};
+
+pub struct DevnetNode {
+    pub name:     String,
+    pub peer_id:  PeerId,
+    /// rpc port of the node on the host
+    pub rpc_port: u16,
+    container:    ContainerAsync<GenericImage>
+}
+
+impl DevnetNode {
+    pub fn rpc_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.rpc_port)
+    }
+
+    pub fn container_id(&self) -> &str {
+        self.container.id()
+    }
+}
+
+/// A running devnet. The containers are removed once it's dropped.
+pub struct Devnet {
+    anvil:          ContainerAsync<GenericImage>,
+    anvil_rpc_port: u16,
+    nodes:          Vec<DevnetNode>,
+    /// keys and configs mounted into the node containers
+    _dir:           tempfile::TempDir
+}
+
+impl Devnet {
+    pub async fn launch(config: DevnetConfig) -> eyre::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let keys = config.node_keys();
+        for (i, key) in keys.iter().enumerate() {
+            std::fs::write(
+                dir.path().join(format!("node-{i}.key")),
+                DevnetConfig::encode_key(key)
+            )?;
+        }
+        let state_config = dir.path().join("state_config.toml");
+        std::fs::write(&state_config, config.state_config())?;
+
+        let anvil = GenericImage::new(&config.anvil_image, &config.anvil_tag)
+            .with_exposed_port(RPC_PORT.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("Listening on"))
+            .with_cmd([config.anvil_cmd()])
+            .with_network(&config.network)
+            .with_container_name(config.anvil_name())
+            .start()
+            .await?;
+        let anvil_rpc_port = anvil.get_host_port_ipv4(RPC_PORT).await?;
+        tracing::info!(anvil_rpc_port, "started devnet anvil");
+
+        let mut nodes = Vec::with_capacity(keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            let name = config.node_name(i);
+            let container = GenericImage::new(&config.angstrom_image, &config.angstrom_tag)
+                .with_exposed_port(RPC_PORT.tcp())
+                .with_wait_for(WaitFor::message_on_stdout("RPC HTTP server started"))
+                .with_cmd(config.node_args(i, &keys))
+                .with_network(&config.network)
+                .with_container_name(&name)
+                .with_working_dir(NODE_WORKDIR)
+                .with_mount(Mount::bind_mount(path_str(dir.path())?, DEVNET_MOUNT))
+                .start()
+                .await?;
+            let rpc_port = container.get_host_port_ipv4(RPC_PORT).await?;
+            tracing::info!(%name, rpc_port, "started devnet node");
+
+            nodes.push(DevnetNode {
+                name,
+                peer_id: DevnetConfig::peer_id(key),
+                rpc_port,
+                container
+            });
+        }
+
+        Ok(Self { anvil, anvil_rpc_port, nodes, _dir: dir })
+    }
+
+    pub fn anvil_rpc_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.anvil_rpc_port)
+    }
+
+    pub fn anvil_container_id(&self) -> &str {
+        self.anvil.id()
+    }
+
+    pub fn nodes(&self) -> &[DevnetNode] {
+        &self.nodes
+    }
+}
+
+fn path_str(path: &Path) -> eyre::Result<String> {
+    path.to_str()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| eyre::eyre!("non utf8 path {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::providers::{Provider, ProviderBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires docker and the angstrom image"]
+    async fn launches_devnet() {
+        let devnet = Devnet::launch(DevnetConfig::default().with_node_count(2))
+            .await
+            .unwrap();
+        assert_eq!(devnet.nodes().len(), 2);
+
+        for node in devnet.nodes() {
+            let provider = ProviderBuilder::new().on_http(node.rpc_url().parse().unwrap());
+            provider.get_block_number().await.unwrap();
+        }
+    }
+}
@@ -15,6 +15,9 @@ pub mod anvil_state_provider;
 /// Tools for contract deployment and testing
 pub mod contracts;
 
+/// Dockerized devnet for full network integration tests
+#[cfg(feature = "devnet")]
+pub mod devnet;
 pub mod testnet_controllers;
 pub mod types;
 
@@ -37,6 +37,12 @@ impl MockEthEventHandle {
             .expect("state changes")
     }
 
+    pub fn chain_reorg(&self, block_number: u64, addresses: Vec<Address>) {
+        self.tx
+            .send(EthEvent::ChainReorg { block_number, addresses })
+            .expect("state changes")
+    }
+
     pub fn reorged_orders(&self, orders: Vec<B256>) {
         self.tx
             .send(EthEvent::ReorgedOrders(orders))
@@ -45,6 +45,12 @@ impl OrderValidatorHandle for MockValidator {
         Box::pin(async move { OrderValidationResults::TransitionedToBlock })
     }
 
+    fn expire_orders(&self, _: Vec<alloy_primitives::B256>) {}
+
+    fn chain_reorg(&self, _: u64, _: Vec<Address>) -> validation::order::ReorgFuture {
+        Box::pin(async move { vec![] })
+    }
+
     fn validate_order(
         &self,
         _origin: angstrom_types::orders::OrderOrigin,
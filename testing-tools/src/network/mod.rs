@@ -20,6 +20,7 @@ use angstrom_network::{
 };
 pub use eth_peer::*;
 use network_future::TestnetPeerStateFuture;
+use order_pool::ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT;
 use parking_lot::RwLock;
 use reth_chainspec::Hardforks;
 use reth_metrics::common::mpsc::{MeteredPollSender, UnboundedMeteredSender};
@@ -65,10 +66,12 @@ where
 
         let peer_id = pk2id(&pub_key);
         let state = StatusState {
-            version:   0,
-            chain:     Chain::mainnet().id(),
-            peer:      peer_id,
-            timestamp: 0
+            version:           0,
+            chain:             Chain::mainnet().id(),
+            peer:              peer_id,
+            timestamp:         0,
+            max_order_horizon: ORDER_MAX_DEADLINE_HORIZON_SECS_DEFAULT,
+            relay_only:        false
         };
         let (session_manager_tx, session_manager_rx) = tokio::sync::mpsc::channel(100);
         let sidecar = VerificationSidecar {
@@ -150,7 +150,8 @@ impl AngstromTestnetNodeInternals {
                 .provider()
                 .get_block_number()
                 .await?,
-            state_provider.provider().provider()
+            state_provider.provider().provider(),
+            std::env::temp_dir().join(format!("angstrom-testnet-{testnet_node_id}"))
         );
 
         let consensus_running = Arc::new(AtomicBool::new(true));
@@ -72,7 +72,7 @@ impl ProposalBuilder {
                     .build()
             })
             .collect::<Vec<_>>();
-        let books = MatchingManager::build_books(&preproposals);
+        let books = MatchingManager::build_books(&preproposals, Default::default());
         let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> = preproposals
             .iter()
             .flat_map(|p| p.searcher.iter())
@@ -1,4 +1,4 @@
-use alloy_primitives::{Address, FixedBytes, Uint, U256};
+use alloy_primitives::{Address, Bytes, FixedBytes, Uint, U256};
 use angstrom_types::{
     matching::Ray,
     orders::{OrderId, OrderPriorityData},
@@ -26,16 +26,17 @@ use rand_distr::{num_traits::ToPrimitive, Distribution, SkewNormal};
 #[derive(Clone, Debug, Default)]
 pub struct UserOrderBuilder {
     /// If the order is not a Standing order, it is KillOrFill
-    is_standing: bool,
+    is_standing:  bool,
     /// If the order is not an Exact order, it is Partial
-    is_exact:    bool,
-    block:       u64,
-    nonce:       u64,
-    recipient:   Address,
-    asset_in:    Address,
-    asset_out:   Address,
-    amount:      u128,
-    min_price:   Ray
+    is_exact:     bool,
+    block:        u64,
+    nonce:        u64,
+    recipient:    Address,
+    asset_in:     Address,
+    asset_out:    Address,
+    amount:       u128,
+    min_price:    Ray,
+    hook_payload: Bytes
 }
 
 impl UserOrderBuilder {
@@ -97,6 +98,10 @@ impl UserOrderBuilder {
         Self { min_price, ..self }
     }
 
+    pub fn hook_payload(self, hook_payload: Bytes) -> Self {
+        Self { hook_payload, ..self }
+    }
+
     pub fn build(self) -> GroupedVanillaOrder {
         match (self.is_standing, self.is_exact) {
             (true, true) => {
@@ -107,6 +112,7 @@ impl UserOrderBuilder {
                     minPrice: *self.min_price,
                     recipient: self.recipient,
                     nonce: self.nonce,
+                    hookPayload: self.hook_payload,
                     ..Default::default()
                 };
                 GroupedVanillaOrder::Standing(StandingVariants::Exact(order))
@@ -118,6 +124,8 @@ impl UserOrderBuilder {
                     maxAmountIn: self.amount,
                     minPrice: *self.min_price,
                     recipient: self.recipient,
+                    nonce: self.nonce,
+                    hookPayload: self.hook_payload,
                     ..Default::default()
                 };
                 GroupedVanillaOrder::Standing(StandingVariants::Partial(order))
@@ -130,6 +138,7 @@ impl UserOrderBuilder {
                     amount: self.amount,
                     minPrice: *self.min_price,
                     recipient: self.recipient,
+                    hookPayload: self.hook_payload,
                     ..Default::default()
                 };
                 GroupedVanillaOrder::KillOrFill(FlashVariants::Exact(order))
@@ -142,6 +151,7 @@ impl UserOrderBuilder {
                     maxAmountIn: self.amount,
                     minPrice: *self.min_price,
                     recipient: self.recipient,
+                    hookPayload: self.hook_payload,
                     ..Default::default()
                 };
                 GroupedVanillaOrder::KillOrFill(FlashVariants::Partial(order))
@@ -211,9 +221,11 @@ impl StoredOrderBuilder {
             .or(self.order.flash_block())
             .unwrap_or_default();
         let priority_data = OrderPriorityData {
-            price:  self.order.price().into(),
-            volume: self.order.quantity().to(),
-            gas:    0
+            price:       self.order.price().into(),
+            volume:      self.order.quantity().to(),
+            gas:         0,
+            gas_units:   0,
+            received_at: 0
         };
         let tob_reward = self.tob_reward.unwrap_or_default();
         OrderWithStorageData {
@@ -288,7 +300,8 @@ pub fn generate_top_of_block_order(
     let order =
         build_top_of_block_order(quantity_in.unwrap_or_default(), quantity_out.unwrap_or_default());
 
-    let priority_data = OrderPriorityData { price: U256::from(price), volume, gas };
+    let priority_data =
+        OrderPriorityData { price: U256::from(price), volume, gas, gas_units: 0, received_at: 0 };
     let order_id = OrderIdBuilder::new()
         .pool_id(pool_id)
         .order_hash(order.order_hash())
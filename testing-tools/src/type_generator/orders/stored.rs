@@ -58,9 +58,11 @@ impl<Order: RawPoolOrder> StoredOrderBuilder<Order> {
             .or(self.order.flash_block())
             .unwrap_or_default();
         let priority_data = OrderPriorityData {
-            price:  self.order.price().into(),
-            volume: self.order.quantity().to(),
-            gas:    0
+            price:       self.order.price().into(),
+            volume:      self.order.quantity().to(),
+            gas:         0,
+            gas_units:   0,
+            received_at: 0
         };
         let tob_reward = self.tob_reward.unwrap_or_default();
         OrderWithStorageData {
@@ -5,7 +5,7 @@ use angstrom_network::{
     pool_manager::{OrderCommand, PoolHandle},
     NetworkOrderEvent
 };
-use order_pool::PoolManagerUpdate;
+use order_pool::SequencedUpdate;
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use tokio::sync::mpsc::{Sender, UnboundedSender};
 
@@ -14,7 +14,7 @@ pub struct SendingStromHandles {
     pub eth_tx:          Sender<EthCommand>,
     pub network_tx:      UnboundedMeteredSender<NetworkOrderEvent>,
     pub orderpool_tx:    UnboundedSender<OrderCommand>,
-    pub pool_manager_tx: tokio::sync::broadcast::Sender<PoolManagerUpdate>,
+    pub pool_manager_tx: tokio::sync::broadcast::Sender<SequencedUpdate>,
     // pub consensus_tx:    Sender<ConsensusMessage>,
     pub consensus_tx_op: UnboundedMeteredSender<StromConsensusEvent>
 }
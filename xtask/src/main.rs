@@ -0,0 +1,107 @@
+//! Repo maintenance tasks, run through `cargo xtask <task>`.
+//!
+//! `regen-artifacts --contracts <dir>` rebuilds the batch request contracts
+//! listed in `crates/matching-engine/src/cfmm/uniswap/artifacts.lock` with
+//! forge, copies the artifacts next to the pool data loader and rewrites the
+//! manifest the matching-engine build script checks them against.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode}
+};
+
+const ARTIFACT_DIR: &str = "crates/matching-engine/src/cfmm/uniswap";
+const MANIFEST: &str = "crates/matching-engine/src/cfmm/uniswap/artifacts.lock";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let res = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["regen-artifacts", "--contracts", contracts] => regen_artifacts(Path::new(contracts)),
+        _ => Err("usage: cargo xtask regen-artifacts --contracts <path to the contracts repo>"
+            .to_string())
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn regen_artifacts(contracts: &Path) -> Result<(), String> {
+    let root = workspace_dir();
+    let manifest_path = root.join(MANIFEST);
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("failed to read {}: {e}", manifest_path.display()))?;
+
+    let status = Command::new("forge")
+        .arg("build")
+        .current_dir(contracts)
+        .status()
+        .map_err(|e| format!("failed to run forge, is foundry installed? {e}"))?;
+    if !status.success() {
+        return Err("forge failed to build the contracts".to_string())
+    }
+
+    let mut regenerated = String::new();
+    for line in manifest.lines() {
+        let entry = line.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            regenerated.push_str(line);
+            regenerated.push('\n');
+            continue
+        }
+        let [artifact, source, ..] = entry.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(format!("malformed artifact manifest entry: {entry}"))
+        };
+
+        let source_file = Path::new(source)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("invalid source path {source}"))?;
+        let contract = source_file.trim_end_matches(".sol");
+        let built = contracts
+            .join("out")
+            .join(source_file)
+            .join(format!("{contract}.json"));
+        let contents =
+            fs::read(&built).map_err(|e| format!("failed to read {}: {e}", built.display()))?;
+        let source_hash = source_keccak(&contents, source)
+            .ok_or_else(|| format!("{} has no metadata for {source}", built.display()))?;
+
+        let dest = root.join(ARTIFACT_DIR).join(artifact);
+        fs::write(&dest, &contents)
+            .map_err(|e| format!("failed to write {}: {e}", dest.display()))?;
+        regenerated
+            .push_str(&format!("{artifact} {source} {source_hash} {:016x}\n", fnv1a64(&contents)));
+        println!("regenerated {artifact} from {source}");
+    }
+
+    fs::write(&manifest_path, regenerated)
+        .map_err(|e| format!("failed to write {}: {e}", manifest_path.display()))
+}
+
+/// The keccak256 of `source` the compiler recorded in the artifact metadata.
+fn source_keccak(artifact: &[u8], source: &str) -> Option<String> {
+    let artifact = std::str::from_utf8(artifact).ok()?;
+    let prefix = format!("\"{source}\":{{\"keccak256\":\"");
+    let start = artifact.find(&prefix)? + prefix.len();
+    let len = artifact[start..].find('"')?;
+
+    Some(artifact[start..start + len].to_string())
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn workspace_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}